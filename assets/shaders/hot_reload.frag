@@ -0,0 +1,17 @@
+#version 450
+
+layout(location = 0) in vec3 v_Normal;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 1) uniform MyMaterial_color {
+    vec4 color;
+};
+
+void main() {
+    // try editing this file while the example is running
+    o_Target = color;
+# ifdef SHOW_NORMALS
+    o_Target = vec4(normalize(v_Normal) * 0.5 + 0.5, 1.0);
+# endif
+}
@@ -61,13 +61,18 @@ impl App {
             .run(&mut self.schedule, &mut self.world, &mut self.resources);
     }
 
-    pub fn run(mut self) {
+    /// Runs the startup schedule. This is called by [App::run] before the runner takes over.
+    pub fn startup(&mut self) {
         self.startup_schedule.initialize(&mut self.resources);
         self.startup_executor.run(
             &mut self.startup_schedule,
             &mut self.world,
             &mut self.resources,
         );
+    }
+
+    pub fn run(mut self) {
+        self.startup();
 
         let runner = std::mem::replace(&mut self.runner, Box::new(run_once));
         (runner)(self);
@@ -1,20 +1,32 @@
 use crate::{
     app::{App, AppExit},
+    app_stepper::AppStepper,
     event::Events,
-    plugin::{dynamically_load_plugin, Plugin},
+    platform::{
+        platform_begin_frame_system, platform_end_frame_system, platform_startup_system,
+        PlatformOverlay, PlatformRuntime, PlatformRuntimes, RichPresence,
+    },
+    plugin::{dynamically_load_plugin, Plugin, PluginRequirements, PluginRequirementsError},
     stage, startup_stage,
 };
-use bevy_ecs::{FromResources, IntoQuerySystem, Resources, System, World};
+use bevy_ecs::{
+    remove_despawned_component_references_system, remove_despawned_resource_references_system,
+    Component, EntityReferences, FromResources, IntoQuerySystem, IntoThreadLocalSystem, Resources,
+    System, SystemError, SystemErrorHandler, World,
+};
 
 /// Configure [App]s using the builder pattern
 pub struct AppBuilder {
     pub app: App,
+    /// Plugins waiting for the resources they require to be added
+    deferred_plugins: Vec<(Box<dyn Plugin>, PluginRequirements)>,
 }
 
 impl Default for AppBuilder {
     fn default() -> Self {
         let mut app_builder = AppBuilder {
             app: App::default(),
+            deferred_plugins: Vec::new(),
         };
 
         app_builder.add_default_stages();
@@ -27,6 +39,7 @@ impl AppBuilder {
     pub fn empty() -> AppBuilder {
         AppBuilder {
             app: App::default(),
+            deferred_plugins: Vec::new(),
         }
     }
 
@@ -38,11 +51,23 @@ impl AppBuilder {
         &mut self.app.resources
     }
 
+    /// Runs the App. Deferred plugins whose resources have been added are built first with
+    /// [AppBuilder::finish_plugins]. If plugins are still waiting for the resources they require, this panics with the
+    /// names of the plugins and the resources they are missing.
     pub fn run(&mut self) {
+        self.expect_plugins_finished();
         let app = std::mem::replace(&mut self.app, App::default());
         app.run();
     }
 
+    /// Builds the App into an [AppStepper], which is driven by the caller instead of the runner. Like
+    /// [AppBuilder::run], this finishes the deferred plugins first and panics if plugins are still waiting for the
+    /// resources they require.
+    pub fn stepper(&mut self) -> AppStepper {
+        self.expect_plugins_finished();
+        AppStepper::new(std::mem::take(&mut self.app))
+    }
+
     pub fn set_world(&mut self, world: World) -> &mut Self {
         self.app.world = world;
         self
@@ -197,6 +222,42 @@ impl AppBuilder {
             .add_system_to_stage(stage::EVENT_UPDATE, Events::<T>::update_system.system())
     }
 
+    /// Sends the errors of fallible systems to the `Events<SystemError>` resource instead of logging them, see
+    /// [SystemErrorHandler]
+    pub fn add_system_error_events(&mut self) -> &mut Self {
+        self.add_event::<SystemError>()
+            .add_resource(SystemErrorHandler::Custom(Box::new(|resources, error| {
+                resources
+                    .get_mut::<Events<SystemError>>()
+                    .expect("Events<SystemError> does not exist")
+                    .send(error);
+            })))
+    }
+
+    /// Removes references to despawned entities from the `T` resource at the end of each update in which entities were
+    /// despawned, see [EntityReferences]
+    pub fn add_entity_references_resource<T>(&mut self) -> &mut Self
+    where
+        T: EntityReferences,
+    {
+        self.add_system_to_stage(
+            stage::LAST,
+            remove_despawned_resource_references_system::<T>(),
+        )
+    }
+
+    /// Removes references to despawned entities from `T` components at the end of each update in which entities
+    /// were despawned, see [EntityReferences]
+    pub fn add_entity_references_component<T>(&mut self) -> &mut Self
+    where
+        T: EntityReferences + Component,
+    {
+        self.add_system_to_stage(
+            stage::LAST,
+            remove_despawned_component_references_system::<T>(),
+        )
+    }
+
     pub fn add_resource<T>(&mut self, resource: T) -> &mut Self
     where
         T: Send + Sync + 'static,
@@ -215,6 +276,36 @@ impl AppBuilder {
         self
     }
 
+    /// Keeps the app running when a system panics: the panic is logged and the system is disabled. See
+    /// [ParallelExecutor::set_catch_system_panics](bevy_ecs::ParallelExecutor::set_catch_system_panics).
+    pub fn catch_system_panics(&mut self) -> &mut Self {
+        self.app.executor.set_catch_system_panics(true);
+        self.app.startup_executor.set_catch_system_panics(true);
+        self
+    }
+
+    /// Adds a [PlatformRuntime], ex: for a storefront SDK. The systems that call the runtimes are added with the first
+    /// runtime.
+    pub fn add_platform_runtime(&mut self, runtime: impl PlatformRuntime) -> &mut Self {
+        if self.resources().get::<PlatformRuntimes>().is_none() {
+            self.init_resource::<PlatformRuntimes>()
+                .init_resource::<PlatformOverlay>()
+                .init_resource::<RichPresence>()
+                .add_startup_system(platform_startup_system.thread_local_system())
+                .add_system_to_stage(
+                    stage::FIRST,
+                    platform_begin_frame_system.thread_local_system(),
+                )
+                .add_system_to_stage(stage::LAST, platform_end_frame_system.thread_local_system());
+        }
+
+        self.resources()
+            .get_mut::<PlatformRuntimes>()
+            .unwrap()
+            .add(runtime);
+        self
+    }
+
     pub fn set_runner(&mut self, run_fn: impl Fn(App) + 'static) -> &mut Self {
         self.app.runner = Box::new(run_fn);
         self
@@ -224,15 +315,118 @@ impl AppBuilder {
         let (_lib, plugin) = dynamically_load_plugin(path);
         log::debug!("loaded plugin: {}", plugin.name());
         plugin.build(self);
-        self
+        self.build_deferred_plugins()
     }
 
+    /// Builds `plugin`, or defers it until the resources it requires are added, see [Plugin::requirements]
     pub fn add_plugin<T>(&mut self, plugin: T) -> &mut Self
     where
         T: Plugin,
     {
+        let mut requirements = PluginRequirements::default();
+        plugin.requirements(&mut requirements);
+        let missing = requirements.missing(&self.app.resources);
+        if !missing.is_empty() {
+            log::info!(
+                "deferred plugin: {}, until {} are added",
+                plugin.name(),
+                missing.join(", ")
+            );
+            self.deferred_plugins.push((Box::new(plugin), requirements));
+            return self;
+        }
+
         log::debug!("added plugin: {}", plugin.name());
         plugin.build(self);
+        self.build_deferred_plugins()
+    }
+
+    /// Builds the deferred plugins whose required resources have been added since they were added
+    fn build_deferred_plugins(&mut self) -> &mut Self {
+        while let Some(index) = self
+            .deferred_plugins
+            .iter()
+            .position(|(_plugin, requirements)| {
+                requirements.missing(&self.app.resources).is_empty()
+            })
+        {
+            let (plugin, _requirements) = self.deferred_plugins.remove(index);
+            log::debug!("added deferred plugin: {}", plugin.name());
+            plugin.build(self);
+        }
         self
     }
+
+    /// Builds the deferred plugins whose required resources were added with [AppBuilder::add_resource], and returns
+    /// an error naming the plugins that are still missing resources. [AppBuilder::run] calls this before running the
+    /// App.
+    pub fn finish_plugins(&mut self) -> Result<(), PluginRequirementsError> {
+        self.build_deferred_plugins();
+        if self.deferred_plugins.is_empty() {
+            return Ok(());
+        }
+        Err(PluginRequirementsError {
+            plugins: self
+                .deferred_plugins
+                .iter()
+                .map(|(plugin, requirements)| {
+                    (
+                        plugin.name().to_string(),
+                        requirements.missing(&self.app.resources),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    /// Calls [AppBuilder::finish_plugins] and panics with its error, for [AppBuilder::run] and [AppBuilder::stepper]
+    fn expect_plugins_finished(&mut self) {
+        if let Err(err) = self.finish_plugins() {
+            panic!("{}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AppBuilder, Plugin, PluginRequirements};
+
+    struct Windows;
+    struct WindowPlugin;
+    struct Ui;
+    struct UiPlugin;
+
+    impl Plugin for WindowPlugin {
+        fn build(&self, app: &mut AppBuilder) {
+            app.add_resource(Windows);
+        }
+    }
+
+    impl Plugin for UiPlugin {
+        fn build(&self, app: &mut AppBuilder) {
+            assert!(app.resources().contains::<Windows>());
+            app.add_resource(Ui);
+        }
+
+        fn requirements(&self, requirements: &mut PluginRequirements) {
+            requirements.requires::<Windows>();
+        }
+    }
+
+    #[test]
+    fn deferred_plugins() {
+        let mut app = AppBuilder::empty();
+        app.add_plugin(UiPlugin);
+        assert!(!app.resources().contains::<Ui>());
+        app.add_plugin(WindowPlugin);
+        assert!(app.resources().contains::<Ui>());
+        assert!(app.finish_plugins().is_ok());
+
+        let mut app = AppBuilder::empty();
+        app.add_plugin(UiPlugin);
+        let err = app.finish_plugins().unwrap_err();
+        assert_eq!(err.plugins.len(), 1);
+        assert!(err.plugins[0].0.ends_with("UiPlugin"));
+        assert!(err.plugins[0].1[0].ends_with("Windows"));
+    }
 }
@@ -0,0 +1,140 @@
+use crate::{
+    app::{App, AppExit},
+    event::{EventReader, Events},
+};
+use bevy_ecs::Resource;
+
+/// Whether a stepped [App] wants to keep running
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AppStatus {
+    Running,
+    /// An [AppExit] event was sent
+    Exit,
+}
+
+/// Drives an [App] from a loop owned by the caller, ex: an editor, a simulation, or a test harness, instead of a
+/// runner like winit. Events from the outside are sent with [AppStepper::send_event], then [AppStepper::step] runs the
+/// App schedule once.
+///
+/// ## Example
+/// ```
+///use bevy_app::{prelude::*, AppExit};
+///
+///let mut stepper = App::build().stepper();
+///assert_eq!(stepper.step(), AppStatus::Running);
+///stepper.send_event(AppExit);
+///assert_eq!(stepper.step(), AppStatus::Exit);
+/// ```
+pub struct AppStepper {
+    pub app: App,
+    app_exit_event_reader: EventReader<AppExit>,
+    started: bool,
+}
+
+impl AppStepper {
+    pub fn new(app: App) -> Self {
+        AppStepper {
+            app,
+            app_exit_event_reader: Default::default(),
+            started: false,
+        }
+    }
+
+    /// Sends `event` to the [Events] resource of its type. The events are seen by the systems of the next step.
+    pub fn send_event<T: Resource>(&mut self, event: T) {
+        self.app
+            .resources
+            .get_mut::<Events<T>>()
+            .expect(
+                "Events resource does not exist. Consider adding it with AppBuilder::add_event.",
+            )
+            .send(event);
+    }
+
+    /// Runs the App schedule once. The startup schedule runs before the first step.
+    pub fn step(&mut self) -> AppStatus {
+        if !self.started {
+            self.app.startup();
+            self.started = true;
+        }
+
+        self.app.update();
+
+        match self.app.resources.get::<Events<AppExit>>() {
+            Some(app_exit_events)
+                if self
+                    .app_exit_event_reader
+                    .latest(&app_exit_events)
+                    .is_some() =>
+            {
+                AppStatus::Exit
+            }
+            _ => AppStatus::Running,
+        }
+    }
+
+    pub fn into_app(self) -> App {
+        self.app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppStatus, AppStepper};
+    use crate::{
+        app::AppExit,
+        app_builder::AppBuilder,
+        event::{EventReader, Events},
+    };
+    use bevy_ecs::{IntoQuerySystem, Local, Res, ResMut};
+
+    struct Input(u32);
+
+    #[derive(Default)]
+    struct Total(u32);
+
+    fn input_system(
+        mut reader: Local<EventReader<Input>>,
+        inputs: Res<Events<Input>>,
+        mut total: ResMut<Total>,
+        mut app_exit_events: ResMut<Events<AppExit>>,
+    ) {
+        for input in reader.iter(&inputs) {
+            total.0 += input.0;
+        }
+        if total.0 >= 3 {
+            app_exit_events.send(AppExit);
+        }
+    }
+
+    #[test]
+    fn step_with_events() {
+        let mut app = AppBuilder::default();
+        app.add_event::<Input>()
+            .init_resource::<Total>()
+            .add_system(input_system.system());
+        let mut stepper = app.stepper();
+
+        stepper.send_event(Input(1));
+        assert_eq!(stepper.step(), AppStatus::Running);
+        assert_eq!(stepper.step(), AppStatus::Running);
+        stepper.send_event(Input(2));
+        assert_eq!(stepper.step(), AppStatus::Exit);
+        assert_eq!(stepper.app.resources.get::<Total>().unwrap().0, 3);
+    }
+
+    #[test]
+    fn startup_runs_once() {
+        fn startup_system(mut total: ResMut<Total>) {
+            total.0 += 1;
+        }
+
+        let mut app = AppBuilder::default();
+        app.init_resource::<Total>()
+            .add_startup_system(startup_system.system());
+        let mut stepper: AppStepper = app.stepper();
+        stepper.step();
+        stepper.step();
+        assert_eq!(stepper.into_app().resources.get::<Total>().unwrap().0, 1);
+    }
+}
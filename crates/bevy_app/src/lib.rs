@@ -5,14 +5,18 @@ pub mod startup_stage;
 
 mod app;
 mod app_builder;
+mod app_stepper;
 mod event;
+mod platform;
 mod plugin;
 mod schedule_runner;
 
 pub use app::*;
 pub use app_builder::*;
+pub use app_stepper::*;
 pub use bevy_derive::DynamicPlugin;
 pub use event::*;
+pub use platform::*;
 pub use plugin::*;
 pub use schedule_runner::*;
 
@@ -20,8 +24,10 @@ pub mod prelude {
     pub use crate::{
         app::App,
         app_builder::AppBuilder,
+        app_stepper::{AppStatus, AppStepper},
         event::{EventReader, Events},
-        plugin::Plugin,
+        platform::{PlatformOverlay, RichPresence},
+        plugin::{Plugin, PluginRequirements},
         stage, DynamicPlugin,
     };
 }
@@ -0,0 +1,246 @@
+use crate::{
+    app::AppExit,
+    event::{EventReader, Events},
+};
+use bevy_ecs::{Resources, World};
+use std::collections::HashMap;
+
+/// The runtime of a platform the game is published on, ex: a storefront or console SDK. The runtime is called at fixed
+/// points of each frame, so a plugin for a platform can run its callbacks, feed its input into the engine, and report
+/// its overlay without changes to the engine. Runtimes are added with
+/// [AppBuilder::add_platform_runtime](crate::AppBuilder::add_platform_runtime).
+pub trait PlatformRuntime: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    /// Called once in the `STARTUP` stage
+    fn startup(&mut self, _world: &mut World, _resources: &mut Resources) {}
+
+    /// Called at the start of each frame in the `FIRST` stage, before input events are processed. Platform callbacks
+    /// should run here, and platform input should be written to the engine's input resources here, so it is handled
+    /// in the same frame.
+    fn begin_frame(&mut self, _world: &mut World, _resources: &mut Resources) {}
+
+    /// Called at the end of each frame in the `LAST` stage, after the frame was rendered and presented. Work that must
+    /// not overlap with the platform overlay drawing into the window belongs here.
+    fn end_frame(&mut self, _world: &mut World, _resources: &mut Resources) {}
+
+    /// Whether the platform overlay is open. This is read after [PlatformRuntime::begin_frame] and stored in the
+    /// [PlatformOverlay] resource.
+    fn overlay_active(&self) -> bool {
+        false
+    }
+
+    /// Called after [PlatformRuntime::begin_frame] when the [RichPresence] resource changed
+    fn rich_presence_changed(&mut self, _rich_presence: &RichPresence) {}
+
+    /// Called once when an [AppExit] event is sent
+    fn shutdown(&mut self, _world: &mut World, _resources: &mut Resources) {}
+}
+
+/// Whether the overlay of a [PlatformRuntime] is open. Games usually pause while it is, and input actions are released
+/// so input meant for the overlay doesn't reach the game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformOverlay {
+    pub active: bool,
+}
+
+/// What the player is doing, shown to their friends by the platform, ex: "status" = "Exploring the caves". Keys and
+/// values are passed to every [PlatformRuntime] when they change.
+#[derive(Debug, Default, Clone)]
+pub struct RichPresence {
+    values: HashMap<String, String>,
+    changed: bool,
+}
+
+impl RichPresence {
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        if self.values.get(&key) != Some(&value) {
+            self.values.insert(key, value);
+            self.changed = true;
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|value| value.as_str())
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.values.remove(key).is_some() {
+            self.changed = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        if !self.values.is_empty() {
+            self.values.clear();
+            self.changed = true;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+/// The [PlatformRuntime]s of the app
+#[derive(Default)]
+pub struct PlatformRuntimes {
+    runtimes: Vec<Box<dyn PlatformRuntime>>,
+    app_exit_event_reader: EventReader<AppExit>,
+    shut_down: bool,
+}
+
+impl PlatformRuntimes {
+    pub fn add(&mut self, runtime: impl PlatformRuntime) {
+        self.runtimes.push(Box::new(runtime));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn PlatformRuntime> {
+        self.runtimes.iter().map(|runtime| &**runtime)
+    }
+}
+
+/// Runs `func` for each runtime. The runtimes are taken out of resources while they run, so they can access resources.
+fn run_platform_runtimes(
+    resources: &mut Resources,
+    mut func: impl FnMut(&mut PlatformRuntimes, &mut Resources),
+) {
+    let mut runtimes = std::mem::take(&mut *resources.get_mut::<PlatformRuntimes>().unwrap());
+    func(&mut runtimes, resources);
+    *resources.get_mut::<PlatformRuntimes>().unwrap() = runtimes;
+}
+
+pub(crate) fn platform_startup_system(world: &mut World, resources: &mut Resources) {
+    run_platform_runtimes(resources, |runtimes, resources| {
+        for runtime in runtimes.runtimes.iter_mut() {
+            runtime.startup(world, resources);
+        }
+    });
+}
+
+pub(crate) fn platform_begin_frame_system(world: &mut World, resources: &mut Resources) {
+    run_platform_runtimes(resources, |runtimes, resources| {
+        let mut overlay_active = false;
+        for runtime in runtimes.runtimes.iter_mut() {
+            runtime.begin_frame(world, resources);
+            overlay_active |= runtime.overlay_active();
+        }
+        resources.get_mut::<PlatformOverlay>().unwrap().active = overlay_active;
+
+        let mut rich_presence = resources.get_mut::<RichPresence>().unwrap();
+        if rich_presence.changed {
+            rich_presence.changed = false;
+            for runtime in runtimes.runtimes.iter_mut() {
+                runtime.rich_presence_changed(&rich_presence);
+            }
+        }
+    });
+}
+
+pub(crate) fn platform_end_frame_system(world: &mut World, resources: &mut Resources) {
+    run_platform_runtimes(resources, |runtimes, resources| {
+        for runtime in runtimes.runtimes.iter_mut() {
+            runtime.end_frame(world, resources);
+        }
+
+        let exiting = {
+            let app_exit_events = resources.get::<Events<AppExit>>().unwrap();
+            runtimes
+                .app_exit_event_reader
+                .latest(&app_exit_events)
+                .is_some()
+        };
+        if exiting && !runtimes.shut_down {
+            runtimes.shut_down = true;
+            for runtime in runtimes.runtimes.iter_mut() {
+                runtime.shutdown(world, resources);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlatformOverlay, PlatformRuntime, RichPresence};
+    use crate::{AppBuilder, AppExit, AppStatus};
+    use bevy_ecs::{Resources, World};
+    use std::sync::{Arc, Mutex};
+
+    struct TestRuntime(Arc<Mutex<Vec<String>>>);
+
+    impl PlatformRuntime for TestRuntime {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn startup(&mut self, _world: &mut World, _resources: &mut Resources) {
+            self.0.lock().unwrap().push("startup".to_string());
+        }
+
+        fn begin_frame(&mut self, _world: &mut World, _resources: &mut Resources) {
+            self.0.lock().unwrap().push("begin_frame".to_string());
+        }
+
+        fn end_frame(&mut self, _world: &mut World, _resources: &mut Resources) {
+            self.0.lock().unwrap().push("end_frame".to_string());
+        }
+
+        fn overlay_active(&self) -> bool {
+            true
+        }
+
+        fn rich_presence_changed(&mut self, rich_presence: &RichPresence) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("status={}", rich_presence.get("status").unwrap()));
+        }
+
+        fn shutdown(&mut self, _world: &mut World, _resources: &mut Resources) {
+            self.0.lock().unwrap().push("shutdown".to_string());
+        }
+    }
+
+    #[test]
+    fn platform_runtime() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut stepper = AppBuilder::default()
+            .add_platform_runtime(TestRuntime(calls.clone()))
+            .stepper();
+
+        stepper
+            .app
+            .resources
+            .get_mut::<RichPresence>()
+            .unwrap()
+            .set("status", "In the menu");
+        assert_eq!(stepper.step(), AppStatus::Running);
+        assert!(
+            stepper
+                .app
+                .resources
+                .get::<PlatformOverlay>()
+                .unwrap()
+                .active
+        );
+
+        stepper.send_event(AppExit);
+        assert_eq!(stepper.step(), AppStatus::Exit);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "startup",
+                "begin_frame",
+                "status=In the menu",
+                "end_frame",
+                "begin_frame",
+                "end_frame",
+                "shutdown"
+            ]
+        );
+    }
+}
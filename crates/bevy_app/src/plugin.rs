@@ -1,6 +1,7 @@
 use crate::AppBuilder;
+use bevy_ecs::{Resource, Resources};
 use libloading::{Library, Symbol};
-use std::any::Any;
+use std::{any::Any, fmt};
 
 /// A collection of Bevy App logic and configuration
 ///
@@ -10,8 +11,68 @@ pub trait Plugin: Any + Send + Sync {
     fn name(&self) -> &str {
         std::any::type_name::<Self>()
     }
+
+    /// Declares the resources the plugin needs, ex: `requirements.requires::<Windows>()`. If they haven't been added when
+    /// the plugin is added, [AppBuilder] builds the plugin once a later plugin adds them, so plugins can be added in any
+    /// order.
+    ///
+    /// A deferred plugin's systems are registered late, when it is built. Within a stage they come after the systems of
+    /// the plugins that were added in the meantime, rather than in the order the plugins were added in.
+    fn requirements(&self, _requirements: &mut PluginRequirements) {}
+}
+
+/// The type name of a required resource, and a function that checks whether [Resources] contain it
+type ResourceRequirement = (&'static str, fn(&Resources) -> bool);
+
+/// The resources a [Plugin] requires, see [Plugin::requirements]
+#[derive(Default)]
+pub struct PluginRequirements {
+    resources: Vec<ResourceRequirement>,
 }
 
+impl PluginRequirements {
+    pub fn requires<T: Resource>(&mut self) -> &mut Self {
+        self.resources
+            .push((std::any::type_name::<T>(), Resources::contains::<T>));
+        self
+    }
+
+    /// The type names of the required resources that `resources` doesn't contain
+    pub fn missing(&self, resources: &Resources) -> Vec<&'static str> {
+        self.resources
+            .iter()
+            .filter(|(_name, contains)| !contains(resources))
+            .map(|(name, _contains)| *name)
+            .collect()
+    }
+}
+
+/// Plugins that were never built, because resources they require were never added
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginRequirementsError {
+    /// The name of each plugin, and the type names of its missing resources
+    pub plugins: Vec<(String, Vec<&'static str>)>,
+}
+
+impl fmt::Display for PluginRequirementsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (plugin, missing) in self.plugins.iter() {
+            writeln!(
+                f,
+                "Plugin `{}` requires resources that were never added: {}.",
+                plugin,
+                missing.join(", ")
+            )?;
+        }
+        write!(
+            f,
+            "Add the plugins that add these resources, or add them with AppBuilder::add_resource."
+        )
+    }
+}
+
+impl std::error::Error for PluginRequirementsError {}
+
 pub type CreatePlugin = unsafe fn() -> *mut dyn Plugin;
 
 /// Dynamically links a plugin a the given path. The plugin must export the [CreatePlugin] function.
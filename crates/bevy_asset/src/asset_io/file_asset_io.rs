@@ -0,0 +1,151 @@
+use super::{AssetIo, AssetIoError};
+#[cfg(feature = "filesystem_watcher")]
+use crate::filesystem_watcher::FilesystemWatcher;
+#[cfg(feature = "filesystem_watcher")]
+use std::{collections::HashSet, sync::RwLock};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Reads assets from the filesystem, relative to a root folder. This is the default [AssetIo].
+pub struct FileAssetIo {
+    root_path: PathBuf,
+    #[cfg(feature = "filesystem_watcher")]
+    filesystem_watcher: RwLock<Option<FilesystemWatcher>>,
+}
+
+impl Default for FileAssetIo {
+    fn default() -> Self {
+        FileAssetIo::new(Self::get_root_path())
+    }
+}
+
+impl FileAssetIo {
+    pub fn new<P: AsRef<Path>>(root_path: P) -> Self {
+        FileAssetIo {
+            root_path: root_path.as_ref().to_owned(),
+            #[cfg(feature = "filesystem_watcher")]
+            filesystem_watcher: Default::default(),
+        }
+    }
+
+    /// Returns the folder assets are loaded from by default: `CARGO_MANIFEST_DIR` when running with cargo, otherwise
+    /// the folder of the executable
+    pub fn get_root_path() -> PathBuf {
+        if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+            PathBuf::from(manifest_dir)
+        } else {
+            env::current_exe()
+                .ok()
+                .and_then(|exe_path| exe_path.parent().map(|path| path.to_owned()))
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+}
+
+impl AssetIo for FileAssetIo {
+    fn load_path(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        fs::read(self.root_path.join(path)).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => AssetIoError::NotFound(path.to_owned()),
+            _ => AssetIoError::Io(err),
+        })
+    }
+
+    fn read_directory(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(self.root_path.join(path))? {
+            let child_path = entry?.path();
+            paths.push(
+                child_path
+                    .strip_prefix(&self.root_path)
+                    .map(|path| path.to_owned())
+                    .unwrap_or(child_path),
+            );
+        }
+
+        Ok(paths)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.root_path.join(path).is_dir()
+    }
+
+    #[cfg(feature = "filesystem_watcher")]
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.filesystem_watcher
+            .write()
+            .unwrap()
+            .get_or_insert_with(FilesystemWatcher::default);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "filesystem_watcher"))]
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        log::warn!("Watching assets for changes requires the filesystem_watcher feature");
+        Ok(())
+    }
+
+    #[cfg(feature = "filesystem_watcher")]
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        // TODO: watching each asset explicitly is a simpler implementation, its possible it would be more efficient to watch
+        // folders instead (when possible)
+        if let Some(watcher) = self.filesystem_watcher.write().unwrap().as_mut() {
+            watcher
+                .watch(self.root_path.join(path))
+                .map_err(|_error| AssetIoError::PathWatchError(path.to_owned()))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "filesystem_watcher")]
+    fn get_changed_paths(&self) -> Vec<PathBuf> {
+        use crossbeam_channel::TryRecvError;
+        use notify::event::{Event, EventKind, ModifyKind};
+
+        let filesystem_watcher = self.filesystem_watcher.read().unwrap();
+        let filesystem_watcher = match filesystem_watcher.as_ref() {
+            Some(filesystem_watcher) => filesystem_watcher,
+            None => return Vec::new(),
+        };
+
+        let mut changed = HashSet::new();
+        loop {
+            let event = match filesystem_watcher.receiver.try_recv() {
+                Ok(Ok(event)) => event,
+                Ok(Err(err)) => {
+                    log::warn!("Failed to watch assets for changes: {:?}", err);
+                    continue;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected"),
+            };
+            // some editors save files by writing a new file and renaming it over the old one
+            if let Event {
+                kind:
+                    EventKind::Create(_)
+                    | EventKind::Modify(ModifyKind::Any)
+                    | EventKind::Modify(ModifyKind::Data(_))
+                    | EventKind::Modify(ModifyKind::Name(_)),
+                paths,
+                ..
+            } = event
+            {
+                for path in paths {
+                    let relative_path = path
+                        .strip_prefix(&self.root_path)
+                        .map(|path| path.to_owned())
+                        .unwrap_or(path);
+                    changed.insert(relative_path);
+                }
+            }
+        }
+
+        changed.into_iter().collect()
+    }
+}
@@ -0,0 +1,140 @@
+use super::{AssetIo, AssetIoError};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Reads assets from memory, ex: assets embedded in the executable with `include_bytes!`, or assets downloaded at
+/// runtime. Clones share the same assets, so an app can keep a clone to add assets after passing one to
+/// [AddAsset::set_asset_io](crate::AddAsset::set_asset_io).
+#[derive(Clone, Default)]
+pub struct MemoryAssetIo {
+    assets: Arc<RwLock<HashMap<PathBuf, Cow<'static, [u8]>>>>,
+    // None until watch_for_changes is called
+    changed_paths: Arc<Mutex<Option<HashSet<PathBuf>>>>,
+}
+
+impl MemoryAssetIo {
+    /// Adds the asset at `path`, replacing the asset that was there. Use a `&'static [u8]` to add an embedded asset
+    /// without copying it.
+    pub fn insert<P: AsRef<Path>, B: Into<Cow<'static, [u8]>>>(&self, path: P, bytes: B) {
+        let path = path.as_ref();
+        self.assets
+            .write()
+            .unwrap()
+            .insert(path.to_owned(), bytes.into());
+        if let Some(changed_paths) = self.changed_paths.lock().unwrap().as_mut() {
+            changed_paths.insert(path.to_owned());
+        }
+    }
+
+    /// Removes the asset at `path`, returning true if there was one
+    pub fn remove<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.assets.write().unwrap().remove(path.as_ref()).is_some()
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.assets.read().unwrap().contains_key(path.as_ref())
+    }
+}
+
+impl AssetIo for MemoryAssetIo {
+    fn load_path(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.assets
+            .read()
+            .unwrap()
+            .get(path)
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+    }
+
+    fn read_directory(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        let mut children = BTreeSet::new();
+        for asset_path in self.assets.read().unwrap().keys() {
+            if let Some(child) = asset_path
+                .ancestors()
+                .find(|ancestor| ancestor.parent() == Some(path))
+            {
+                children.insert(child.to_owned());
+            }
+        }
+
+        if children.is_empty() {
+            Err(AssetIoError::NotFound(path.to_owned()))
+        } else {
+            Ok(children.into_iter().collect())
+        }
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.assets
+            .read()
+            .unwrap()
+            .keys()
+            .any(|asset_path| asset_path != path && asset_path.starts_with(path))
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.changed_paths
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashSet::new);
+        Ok(())
+    }
+
+    fn get_changed_paths(&self) -> Vec<PathBuf> {
+        match self.changed_paths.lock().unwrap().as_mut() {
+            Some(changed_paths) => changed_paths.drain().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryAssetIo;
+    use crate::AssetIo;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn memory_asset_io() {
+        let asset_io = MemoryAssetIo::default();
+        asset_io.insert("assets/textures/player.png", &[1u8, 2, 3][..]);
+        asset_io.insert("assets/sounds/jump.ogg", vec![4]);
+
+        assert_eq!(
+            asset_io
+                .load_path(Path::new("assets/textures/player.png"))
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(asset_io.load_path(Path::new("assets/missing.png")).is_err());
+        assert!(asset_io.is_directory(Path::new("assets/textures")));
+        assert!(!asset_io.is_directory(Path::new("assets/textures/player.png")));
+        assert_eq!(
+            asset_io.read_directory(Path::new("assets")).unwrap(),
+            vec![
+                PathBuf::from("assets/sounds"),
+                PathBuf::from("assets/textures")
+            ]
+        );
+
+        // changes are only tracked once watching starts
+        assert!(asset_io.get_changed_paths().is_empty());
+        asset_io.watch_for_changes().unwrap();
+        asset_io.clone().insert("assets/sounds/jump.ogg", vec![5]);
+        assert_eq!(
+            asset_io.get_changed_paths(),
+            vec![PathBuf::from("assets/sounds/jump.ogg")]
+        );
+        assert!(asset_io.get_changed_paths().is_empty());
+        assert_eq!(
+            asset_io
+                .load_path(Path::new("assets/sounds/jump.ogg"))
+                .unwrap(),
+            vec![5]
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod file_asset_io;
+mod memory_asset_io;
+
+pub use file_asset_io::*;
+pub use memory_asset_io::*;
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Errors that occur while reading asset data from an [AssetIo]
+#[derive(Error, Debug)]
+pub enum AssetIoError {
+    #[error("Path not found: {0}")]
+    NotFound(PathBuf),
+    #[error("Encountered an io error while reading asset data.")]
+    Io(#[from] io::Error),
+    #[error("Invalid asset pack: {0}")]
+    InvalidPack(String),
+    #[error("Failed to watch path: {0}")]
+    PathWatchError(PathBuf),
+}
+
+/// Where an [AssetServer](crate::AssetServer) reads asset data from, ex: the filesystem ([FileAssetIo]), memory
+/// ([MemoryAssetIo]), or [packs](crate::pack). Paths are the ones passed to the AssetServer, ex:
+/// `assets/textures/player.png`. Use [AddAsset::set_asset_io](crate::AddAsset::set_asset_io) to change the AssetIo of
+/// an app.
+pub trait AssetIo: Send + Sync + 'static {
+    /// Reads all of the data of the file at `path`
+    fn load_path(&self, path: &Path) -> Result<Vec<u8>, AssetIoError>;
+    /// Returns the paths of the files and folders directly inside the folder at `path`
+    fn read_directory(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError>;
+    fn is_directory(&self, path: &Path) -> bool;
+    /// Starts tracking changes to assets, which are then returned by [AssetIo::get_changed_paths]. AssetIos whose assets
+    /// can't change don't need to implement this.
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+    /// Tracks changes to the asset at `path`, if [AssetIo::watch_for_changes] has been called
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+    /// Returns the paths of assets that have changed since the last call
+    fn get_changed_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
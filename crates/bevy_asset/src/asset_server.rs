@@ -1,16 +1,17 @@
 use crate::{
-    filesystem_watcher::FilesystemWatcher, AssetLoadError, AssetLoadRequestHandler, AssetLoader,
-    Assets, Handle, HandleId, LoadRequest,
+    AssetIo, AssetIoError, AssetLoadError, AssetLoadRequestHandler, AssetLoader, Assets,
+    FileAssetIo, Handle, HandleId, LoadRequest,
 };
 use anyhow::Result;
 use bevy_ecs::{Res, Resource, Resources};
-use crossbeam_channel::TryRecvError;
 use std::{
-    collections::{HashMap, HashSet},
-    env, fs, io,
+    collections::HashMap,
+    io,
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
-    thread,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
 };
 use thiserror::Error;
 
@@ -32,14 +33,20 @@ pub enum AssetServerError {
     AssetLoadError(#[from] AssetLoadError),
     #[error("Encountered an io error.")]
     Io(#[from] io::Error),
-    #[error("Failed to watch asset folder.")]
-    AssetWatchError { path: PathBuf },
+    #[error("Encountered an error while reading assets.")]
+    AssetIoError(#[from] AssetIoError),
 }
 
-struct LoaderThread {
-    // NOTE: these must remain private. the LoaderThread Arc counters are used to determine thread liveness
-    // if there is one reference, the loader thread is dead. if there are two references, the loader thread is active
-    requests: Arc<RwLock<Vec<LoadRequest>>>,
+/// Configures the [AssetServer] when the [AssetPlugin](crate::AssetPlugin) is added. Add this resource before the
+/// plugin to change the defaults.
+#[derive(Clone, Debug, Default)]
+pub struct AssetServerSettings {
+    /// Reloads assets when their files change, see [AssetServer::watch_for_changes]. Watching files on disk requires
+    /// the `filesystem_watcher` feature.
+    pub watch_for_changes: bool,
+    /// Loads assets from the [packs](crate::pack) in this folder instead of from loose files, see
+    /// [PackAssetIo](crate::pack::PackAssetIo). Relative paths are relative to [FileAssetIo::get_root_path].
+    pub asset_pack_folder: Option<PathBuf>,
 }
 
 /// Info about a specific asset, such as its path and its current load state
@@ -68,11 +75,36 @@ impl LoadState {
     }
 }
 
-/// Loads assets from the filesystem on background threads
+/// How many assets of a group have finished loading, see [AssetServer::get_group_load_progress]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LoadProgress {
+    /// Returns true when every asset has either loaded or failed
+    pub fn is_done(&self) -> bool {
+        self.loaded + self.failed == self.total
+    }
+
+    /// Returns the fraction of assets that have finished loading (or failed), from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+}
+
+/// Loads assets from an [AssetIo] on background threads. By default assets are loaded from the filesystem.
 pub struct AssetServer {
+    asset_io: Arc<dyn AssetIo>,
     asset_folders: RwLock<Vec<PathBuf>>,
-    loader_threads: RwLock<Vec<LoaderThread>>,
-    max_loader_threads: usize,
+    // loading an asset mostly waits on io, so this is separate from the pool systems run on
+    io_task_pool: rayon::ThreadPool,
     asset_handlers: Arc<RwLock<Vec<Box<dyn AssetLoadRequestHandler>>>>,
     // TODO: this is a hack to enable retrieving generic AssetLoader<T>s. there must be a better way!
     loaders: Vec<Resources>,
@@ -80,29 +112,46 @@ pub struct AssetServer {
     extension_to_loader_index: HashMap<String, usize>,
     asset_info: RwLock<HashMap<HandleId, AssetInfo>>,
     asset_info_paths: RwLock<HashMap<PathBuf, HandleId>>,
-    #[cfg(feature = "filesystem_watcher")]
-    filesystem_watcher: Arc<RwLock<Option<FilesystemWatcher>>>,
+    watching_for_changes: AtomicBool,
 }
 
 impl Default for AssetServer {
     fn default() -> Self {
         AssetServer {
-            #[cfg(feature = "filesystem_watcher")]
-            filesystem_watcher: Arc::new(RwLock::new(None)),
-            max_loader_threads: 4,
+            asset_io: Arc::new(FileAssetIo::default()),
+            io_task_pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .thread_name(|index| format!("asset_io_{}", index))
+                .build()
+                .expect("Failed to create the asset io task pool"),
             asset_folders: Default::default(),
-            loader_threads: Default::default(),
             asset_handlers: Default::default(),
             loaders: Default::default(),
             extension_to_handler_index: Default::default(),
             extension_to_loader_index: Default::default(),
             asset_info_paths: Default::default(),
             asset_info: Default::default(),
+            watching_for_changes: AtomicBool::new(false),
         }
     }
 }
 
 impl AssetServer {
+    /// Replaces where assets are read from. Assets that are already loading may still be read from the previous
+    /// [AssetIo]. If the AssetServer is watching for changes, the new AssetIo is watched instead.
+    pub fn set_asset_io<T: AssetIo>(&mut self, asset_io: T) {
+        self.asset_io = Arc::new(asset_io);
+        if self.watching_for_changes.load(Ordering::Relaxed) {
+            if let Err(err) = self.watch_for_changes() {
+                log::warn!("Failed to watch assets for changes: {:?}", err);
+            }
+        }
+    }
+
+    pub fn asset_io(&self) -> &dyn AssetIo {
+        &*self.asset_io
+    }
+
     pub fn add_handler<T>(&mut self, asset_handler: T)
     where
         T: AssetLoadRequestHandler,
@@ -133,111 +182,59 @@ impl AssetServer {
         self.loaders.push(resources);
     }
 
-    pub fn load_asset_folder<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Result<Vec<HandleId>, AssetServerError> {
-        let root_path = self.get_root_path()?;
-        let asset_folder = root_path.join(path);
+    /// Loads every asset in the folder at `path` and its subfolders in the background, skipping files no loader can
+    /// load. Use [AssetServer::get_group_load_state] or [AssetServer::get_group_load_progress] with the returned
+    /// handles to wait for the whole folder.
+    pub fn load_folder<P: AsRef<Path>>(&self, path: P) -> Result<Vec<HandleId>, AssetServerError> {
+        let asset_folder = path.as_ref().to_owned();
         let handle_ids = self.load_assets_in_folder_recursive(&asset_folder)?;
         self.asset_folders.write().unwrap().push(asset_folder);
         Ok(handle_ids)
     }
 
     pub fn get_handle<T, P: AsRef<Path>>(&self, path: P) -> Option<Handle<T>> {
+        self.get_handle_id(path).map(Handle::from)
+    }
+
+    /// Gets a handle to the asset labeled `label` within the asset at `path`, ex: a mesh in a GLTF file
+    pub fn get_labeled_handle<T, P: AsRef<Path>>(&self, path: P, label: &str) -> Option<Handle<T>> {
+        self.get_handle_id(path)
+            .map(|handle_id| Handle::from(handle_id.labeled(label)))
+    }
+
+    pub fn get_handle_id<P: AsRef<Path>>(&self, path: P) -> Option<HandleId> {
         self.asset_info_paths
             .read()
             .unwrap()
             .get(path.as_ref())
-            .map(|handle_id| Handle::from(*handle_id))
-    }
-
-    #[cfg(feature = "filesystem_watcher")]
-    fn watch_path_for_changes<P: AsRef<Path>>(
-        filesystem_watcher: &mut Option<FilesystemWatcher>,
-        path: P,
-    ) -> Result<(), AssetServerError> {
-        if let Some(watcher) = filesystem_watcher {
-            watcher
-                .watch(&path)
-                .map_err(|_error| AssetServerError::AssetWatchError {
-                    path: path.as_ref().to_owned(),
-                })?;
-        }
-
-        Ok(())
+            .cloned()
     }
 
-    #[cfg(feature = "filesystem_watcher")]
+    /// Starts watching loaded assets, and assets loaded afterwards, for changes. When an asset changes, it is loaded
+    /// again with the same handle, which sends an [AssetEvent::Modified](crate::AssetEvent::Modified) event once the
+    /// new version is ready. If the new version fails to load, the previous version is kept.
     pub fn watch_for_changes(&self) -> Result<(), AssetServerError> {
-        let mut filesystem_watcher = self.filesystem_watcher.write().unwrap();
-
-        let _ = filesystem_watcher.get_or_insert_with(|| FilesystemWatcher::default());
+        self.watching_for_changes.store(true, Ordering::Relaxed);
+        self.asset_io.watch_for_changes()?;
         // watch current files
         let asset_info_paths = self.asset_info_paths.read().unwrap();
         for asset_path in asset_info_paths.keys() {
-            Self::watch_path_for_changes(&mut filesystem_watcher, asset_path)?;
+            self.asset_io.watch_path_for_changes(asset_path)?;
         }
 
         Ok(())
     }
 
-    #[cfg(feature = "filesystem_watcher")]
-    pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
-        use notify::event::{Event, EventKind, ModifyKind};
-        let mut changed = HashSet::new();
-        loop {
-            let result = if let Some(filesystem_watcher) =
-                asset_server.filesystem_watcher.read().unwrap().as_ref()
-            {
-                match filesystem_watcher.receiver.try_recv() {
-                    Ok(result) => result,
-                    Err(TryRecvError::Empty) => {
-                        break;
-                    }
-                    Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected"),
-                }
-            } else {
-                break;
-            };
-
-            let event = result.unwrap();
-            match event {
-                Event {
-                    kind: EventKind::Modify(ModifyKind::Data(_)),
-                    paths,
-                    ..
-                } => {
-                    for path in paths.iter() {
-                        if !changed.contains(path) {
-                            let root_path = asset_server.get_root_path().unwrap();
-                            let relative_path = path.strip_prefix(root_path).unwrap();
-                            match asset_server.load_untyped(relative_path) {
-                                Ok(_) => {}
-                                Err(AssetServerError::AssetLoadError(error)) => {
-                                    panic!("{:?}", error)
-                                }
-                                Err(_) => {}
-                            }
-                        }
-                    }
-                    changed.extend(paths);
-                }
-                _ => {}
+    /// Reloads assets that have changed since the last update
+    pub fn reload_changed_assets_system(asset_server: Res<AssetServer>) {
+        for path in asset_server.asset_io.get_changed_paths() {
+            // only reload assets that have been loaded before
+            if asset_server.get_handle_id(&path).is_none() {
+                continue;
             }
-        }
-    }
 
-    fn get_root_path(&self) -> Result<PathBuf, AssetServerError> {
-        if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-            Ok(PathBuf::from(manifest_dir))
-        } else {
-            match env::current_exe() {
-                Ok(exe_path) => exe_path
-                    .parent()
-                    .ok_or(AssetServerError::InvalidRootPath)
-                    .map(|exe_parent_path| exe_parent_path.to_owned()),
-                Err(err) => Err(AssetServerError::Io(err)),
+            if let Err(err) = asset_server.load_untyped(&path) {
+                log::error!("Failed to reload asset {:?}: {:?}", path, err);
             }
         }
     }
@@ -266,7 +263,10 @@ impl AssetServer {
                 let handle_id = HandleId::new();
                 let resources = &self.loaders[*index];
                 let loader = resources.get::<Box<dyn AssetLoader<T>>>().unwrap();
-                let asset = loader.load_from_file(path)?;
+                let bytes = self.asset_io.load_path(path)?;
+                let asset = loader
+                    .from_bytes(path, bytes)
+                    .map_err(AssetLoadError::from)?;
                 let handle = Handle::from(handle_id);
                 assets.set(handle, asset);
                 Ok(handle)
@@ -294,13 +294,9 @@ impl AssetServer {
                         .get(path)
                         .and_then(|handle_id| asset_info.get_mut(&handle_id))
                     {
-                        asset_info.load_state =
-                            if let LoadState::Loaded(_version) = asset_info.load_state {
-                                new_version += 1;
-                                LoadState::Loading(new_version)
-                            } else {
-                                LoadState::Loading(new_version)
-                            };
+                        // reloads get a newer version, so results from earlier loads can't replace them
+                        new_version = asset_info.load_state.get_version() + 1;
+                        asset_info.load_state = LoadState::Loading(new_version);
                         asset_info.handle_id
                     } else {
                         let handle_id = HandleId::new();
@@ -317,17 +313,14 @@ impl AssetServer {
                     }
                 };
 
-                self.send_request_to_loader_thread(LoadRequest {
+                self.spawn_load_request(LoadRequest {
                     handle_id,
                     path: path.to_owned(),
                     handler_index: *index,
                     version: new_version,
                 });
 
-                // TODO: watching each asset explicitly is a simpler implementation, its possible it would be more efficient to watch
-                // folders instead (when possible)
-                #[cfg(feature = "filesystem_watcher")]
-                Self::watch_path_for_changes(&mut self.filesystem_watcher.write().unwrap(), path)?;
+                self.asset_io.watch_path_for_changes(path)?;
                 Ok(handle_id)
             } else {
                 Err(AssetServerError::MissingAssetHandler)
@@ -349,6 +342,14 @@ impl AssetServer {
             });
     }
 
+    /// Returns false if a newer version of the asset has been requested since `version` was
+    pub fn is_latest_version(&self, handle_id: HandleId, version: AssetVersion) -> bool {
+        match self.get_load_state_untyped(handle_id) {
+            Some(load_state) => version >= load_state.get_version(),
+            None => true,
+        }
+    }
+
     pub fn get_load_state_untyped(&self, handle_id: HandleId) -> Option<LoadState> {
         self.asset_info
             .read()
@@ -361,6 +362,9 @@ impl AssetServer {
         self.get_load_state_untyped(handle.id)
     }
 
+    /// Returns the combined load state of a group of assets: `Loaded` once they have all loaded, `Failed` if any of them
+    /// failed, and `Loading` otherwise. Returns `None` if any of the handles wasn't loaded by this AssetServer. The
+    /// version of the returned state is always 0.
     pub fn get_group_load_state(&self, handle_ids: &[HandleId]) -> Option<LoadState> {
         let mut load_state = LoadState::Loaded(0);
         for handle_id in handle_ids.iter() {
@@ -377,53 +381,31 @@ impl AssetServer {
         Some(load_state)
     }
 
-    fn send_request_to_loader_thread(&self, load_request: LoadRequest) {
-        // NOTE: This lock makes the call to Arc::strong_count safe. Removing (or reordering) it could result in undefined behavior
-        let mut loader_threads = self.loader_threads.write().unwrap();
-        if loader_threads.len() < self.max_loader_threads {
-            let loader_thread = LoaderThread {
-                requests: Arc::new(RwLock::new(vec![load_request])),
-            };
-            let requests = loader_thread.requests.clone();
-            loader_threads.push(loader_thread);
-            Self::start_thread(self.asset_handlers.clone(), requests);
-        } else {
-            let most_free_thread = loader_threads
-                .iter()
-                .min_by_key(|l| l.requests.read().unwrap().len())
-                .unwrap();
-            let mut requests = most_free_thread.requests.write().unwrap();
-            requests.push(load_request);
-            // if most free thread only has one reference, the thread as spun down. if so, we need to spin it back up!
-            if Arc::strong_count(&most_free_thread.requests) == 1 {
-                Self::start_thread(
-                    self.asset_handlers.clone(),
-                    most_free_thread.requests.clone(),
-                );
+    /// Counts how many assets of a group have loaded, ex: for a loading bar. Handles that weren't loaded by this
+    /// AssetServer count as loading.
+    pub fn get_group_load_progress(&self, handle_ids: &[HandleId]) -> LoadProgress {
+        let mut progress = LoadProgress {
+            total: handle_ids.len(),
+            ..Default::default()
+        };
+        for handle_id in handle_ids.iter() {
+            match self.get_load_state_untyped(*handle_id) {
+                Some(LoadState::Loaded(_)) => progress.loaded += 1,
+                Some(LoadState::Failed(_)) => progress.failed += 1,
+                Some(LoadState::Loading(_)) | None => {}
             }
         }
-    }
 
-    fn start_thread(
-        request_handlers: Arc<RwLock<Vec<Box<dyn AssetLoadRequestHandler>>>>,
-        requests: Arc<RwLock<Vec<LoadRequest>>>,
-    ) {
-        thread::spawn(move || {
-            loop {
-                let request = {
-                    let mut current_requests = requests.write().unwrap();
-                    if current_requests.len() == 0 {
-                        // if there are no requests, spin down the thread
-                        break;
-                    }
-
-                    current_requests.pop().unwrap()
-                };
+        progress
+    }
 
-                let handlers = request_handlers.read().unwrap();
-                let request_handler = &handlers[request.handler_index];
-                request_handler.handle_request(&request);
-            }
+    fn spawn_load_request(&self, load_request: LoadRequest) {
+        let request_handlers = self.asset_handlers.clone();
+        let asset_io = self.asset_io.clone();
+        self.io_task_pool.spawn(move || {
+            let handlers = request_handlers.read().unwrap();
+            let request_handler = &handlers[load_request.handler_index];
+            request_handler.handle_request(&load_request, &*asset_io);
         });
     }
 
@@ -431,26 +413,18 @@ impl AssetServer {
         &self,
         path: &Path,
     ) -> Result<Vec<HandleId>, AssetServerError> {
-        if !path.is_dir() {
+        if !self.asset_io.is_directory(path) {
             return Err(AssetServerError::AssetFolderNotADirectory(
                 path.to_str().unwrap().to_string(),
             ));
         }
 
-        let root_path = self.get_root_path()?;
         let mut handle_ids = Vec::new();
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let child_path = entry.path();
-            if child_path.is_dir() {
+        for child_path in self.asset_io.read_directory(path)? {
+            if self.asset_io.is_directory(&child_path) {
                 handle_ids.extend(self.load_assets_in_folder_recursive(&child_path)?);
             } else {
-                let relative_child_path = child_path.strip_prefix(&root_path).unwrap();
-                let handle = match self.load_untyped(
-                    relative_child_path
-                        .to_str()
-                        .expect("Path should be a valid string"),
-                ) {
+                let handle = match self.load_untyped(&child_path) {
                     Ok(handle) => handle,
                     Err(AssetServerError::MissingAssetHandler) => continue,
                     Err(err) => Err(err)?,
@@ -463,3 +437,139 @@ impl AssetServer {
         Ok(handle_ids)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetServer, AssetServerSettings, LoadProgress, LoadState};
+    use crate::{
+        update_asset_storage_system, AssetChannel, AssetLoadFailed, AssetLoader, Assets,
+        ChannelAssetHandler, Handle, MemoryAssetIo,
+    };
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use std::{
+        path::Path,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    struct TextLoader;
+
+    impl AssetLoader<String> for TextLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> Result<String, anyhow::Error> {
+            Ok(String::from_utf8(bytes)?)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
+    #[test]
+    fn load_folder_progress() {
+        let asset_io = MemoryAssetIo::default();
+        asset_io.insert("text/a.txt", &b"a"[..]);
+        asset_io.insert("text/nested/b.txt", &b"b"[..]);
+        asset_io.insert("text/invalid.txt", vec![0xff]);
+        asset_io.insert("text/skipped.png", Vec::new());
+
+        let channel = AssetChannel::<String>::new();
+        let mut asset_server = AssetServer::default();
+        asset_server.set_asset_io(asset_io);
+        asset_server.add_handler(ChannelAssetHandler::new(TextLoader, channel.sender.clone()));
+
+        let handle_ids = asset_server.load_folder("text").unwrap();
+        assert_eq!(handle_ids.len(), 3);
+        assert_eq!(
+            asset_server.get_group_load_state(&handle_ids),
+            Some(LoadState::Loading(0))
+        );
+        assert!(!asset_server.get_group_load_progress(&handle_ids).is_done());
+
+        for _ in 0..handle_ids.len() {
+            let result = channel
+                .receiver
+                .recv_timeout(Duration::from_secs(10))
+                .unwrap();
+            let load_state = match result.result {
+                Ok(_) => LoadState::Loaded(result.version),
+                Err(_) => LoadState::Failed(result.version),
+            };
+            asset_server.set_load_state(result.handle.id, load_state);
+        }
+
+        let progress = asset_server.get_group_load_progress(&handle_ids);
+        assert_eq!(
+            progress,
+            LoadProgress {
+                loaded: 2,
+                failed: 1,
+                total: 3
+            }
+        );
+        assert!(progress.is_done());
+        assert_eq!(progress.fraction(), 1.0);
+        assert_eq!(
+            asset_server.get_group_load_state(&handle_ids),
+            Some(LoadState::Failed(0))
+        );
+    }
+
+    #[test]
+    fn reload_ignores_earlier_loads() {
+        assert!(!AssetServerSettings::default().watch_for_changes);
+
+        let asset_io = MemoryAssetIo::default();
+        asset_io.insert("a.txt", &b"old"[..]);
+
+        let channel = AssetChannel::<String>::new();
+        let mut asset_server = AssetServer::default();
+        asset_server.set_asset_io(asset_io.clone());
+        asset_server.add_handler(ChannelAssetHandler::new(TextLoader, channel.sender.clone()));
+
+        let wait_for_results = |count: usize| {
+            let start = Instant::now();
+            while channel.receiver.len() < count {
+                assert!(start.elapsed() < Duration::from_secs(10));
+                thread::sleep(Duration::from_millis(1));
+            }
+        };
+
+        let handle_id = asset_server.load_untyped("a.txt").unwrap();
+        wait_for_results(1);
+        asset_io.insert("a.txt", &b"new"[..]);
+        assert_eq!(asset_server.load_untyped("a.txt").unwrap(), handle_id);
+        wait_for_results(2);
+
+        assert_eq!(asset_server.get_handle_id("a.txt"), Some(handle_id));
+        assert_eq!(asset_server.get_handle_id("b.txt"), None);
+        assert_eq!(
+            asset_server.get_load_state_untyped(handle_id),
+            Some(LoadState::Loading(1))
+        );
+        assert!(!asset_server.is_latest_version(handle_id, 0));
+        assert!(asset_server.is_latest_version(handle_id, 1));
+
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(asset_server);
+        resources.insert(channel);
+        resources.insert(Assets::<String>::default());
+        resources.insert(Events::<AssetLoadFailed>::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", update_asset_storage_system::<String>.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        let assets = resources.get::<Assets<String>>().unwrap();
+        let handle = Handle::<String>::from(handle_id);
+        assert_eq!(assets.get(&handle).map(|text| text.as_str()), Some("new"));
+        let asset_server = resources.get::<AssetServer>().unwrap();
+        assert_eq!(
+            asset_server.get_load_state_untyped(handle_id),
+            Some(LoadState::Loaded(1))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    update_asset_storage_system, AssetChannel, AssetLoader, AssetServer, ChannelAssetHandler,
-    Handle, HandleId,
+    update_asset_storage_system, AssetChannel, AssetIo, AssetLoadRequestHandler, AssetLoader,
+    AssetServer, ChannelAssetHandler, Handle, HandleId,
 };
 use bevy_app::{prelude::Events, AppBuilder};
 use bevy_ecs::{FromResources, IntoQuerySystem, ResMut, Resource};
@@ -119,6 +119,20 @@ pub trait AddAsset {
     where
         TLoader: AssetLoader<TAsset> + FromResources,
         TAsset: Send + Sync + 'static;
+    /// Adds an [AssetChannel] for `T`, which [AssetLoadRequestHandler]s send loaded `T` assets to. Loaded assets are
+    /// added to [Assets<T>].
+    fn add_asset_channel<T>(&mut self) -> &mut Self
+    where
+        T: Send + Sync + 'static;
+    /// Adds a handler for load requests, for loaders that don't fit [AssetLoader], ex: because they load several
+    /// assets from one file
+    fn add_asset_handler<T>(&mut self, asset_handler: T) -> &mut Self
+    where
+        T: AssetLoadRequestHandler;
+    /// Changes where the [AssetServer] reads assets from, see [AssetIo]
+    fn set_asset_io<T>(&mut self, asset_io: T) -> &mut Self
+    where
+        T: AssetIo;
 }
 
 impl AddAsset for AppBuilder {
@@ -140,14 +154,8 @@ impl AddAsset for AppBuilder {
         TLoader: AssetLoader<TAsset> + FromResources,
         TAsset: Send + Sync + 'static,
     {
+        self.add_asset_channel::<TAsset>();
         {
-            if !self.resources().contains::<AssetChannel<TAsset>>() {
-                self.resources_mut().insert(AssetChannel::<TAsset>::new());
-                self.add_system_to_stage(
-                    crate::stage::LOAD_ASSETS,
-                    update_asset_storage_system::<TAsset>.system(),
-                );
-            }
             let asset_channel = self
                 .resources()
                 .get::<AssetChannel<TAsset>>()
@@ -165,4 +173,40 @@ impl AddAsset for AppBuilder {
         }
         self
     }
+
+    fn add_asset_channel<T>(&mut self) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        if !self.resources().contains::<AssetChannel<T>>() {
+            self.resources_mut().insert(AssetChannel::<T>::new());
+            self.add_system_to_stage(
+                crate::stage::LOAD_ASSETS,
+                update_asset_storage_system::<T>.system(),
+            );
+        }
+        self
+    }
+
+    fn add_asset_handler<T>(&mut self, asset_handler: T) -> &mut Self
+    where
+        T: AssetLoadRequestHandler,
+    {
+        self.resources()
+            .get_mut::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .add_handler(asset_handler);
+        self
+    }
+
+    fn set_asset_io<T>(&mut self, asset_io: T) -> &mut Self
+    where
+        T: AssetIo,
+    {
+        self.resources()
+            .get_mut::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .set_asset_io(asset_io);
+        self
+    }
 }
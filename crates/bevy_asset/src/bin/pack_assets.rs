@@ -0,0 +1,65 @@
+//! Packs asset folders into an asset pack for shipping builds, see [bevy_asset::pack]
+//!
+//! Usage: `cargo run -p bevy_asset --bin pack_assets -- [--version <version>] <output.pack> <folder>...`
+//!
+//! Folders are stored under the paths they are given with, so run this from the folder the game loads assets from
+//! (see `FileAssetIo::get_root_path`), ex: `pack_assets packs/assets.pack assets`. Then set
+//! `AssetServerSettings::asset_pack_folder` to the folder of the pack.
+
+use bevy_asset::pack::AssetPackWriter;
+use std::{env, fs, process};
+
+const USAGE: &str = "Usage: pack_assets [--version <version>] <output.pack> <folder>...";
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut version = 0;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--version" => {
+                version = match args.next().and_then(|version| version.parse().ok()) {
+                    Some(version) => version,
+                    None => exit_with_error("--version must be followed by a number"),
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.len() < 2 {
+        exit_with_error(USAGE);
+    }
+    let output = paths.remove(0);
+
+    let mut writer = AssetPackWriter::new(version);
+    for folder in paths.iter() {
+        match writer.add_folder(folder) {
+            Ok(count) => println!("{}: {} files", folder, count),
+            Err(err) => exit_with_error(&format!("Failed to read {}: {}", folder, err)),
+        }
+    }
+
+    if let Err(err) = writer.write_to_file(&output) {
+        exit_with_error(&format!("Failed to write {}: {}", output, err));
+    }
+    let size = fs::metadata(&output)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    println!(
+        "Wrote {} assets to {} (version {}, {} bytes)",
+        writer.len(),
+        output,
+        version,
+        size
+    );
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
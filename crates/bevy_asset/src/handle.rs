@@ -1,4 +1,5 @@
 use std::{
+    collections::hash_map::DefaultHasher,
     fmt::Debug,
     hash::{Hash, Hasher},
 };
@@ -20,6 +21,18 @@ impl HandleId {
     pub fn new() -> HandleId {
         HandleId(Uuid::new_v4())
     }
+
+    /// Returns the id of the asset labeled `label` within this asset, ex: a mesh in a GLTF file. The same label always
+    /// results in the same id, so labeled assets can be referred to before they are loaded.
+    pub fn labeled(&self, label: &str) -> HandleId {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        label.hash(&mut hasher);
+        let high = hasher.finish();
+        label.hash(&mut hasher);
+        let low = hasher.finish();
+        HandleId(Uuid::from_u128((high as u128) << 64 | low as u128))
+    }
 }
 
 /// A handle into a specific Asset of type `T`
@@ -51,6 +64,11 @@ impl<T> Handle<T> {
         Handle::from_id(self.id)
     }
 
+    /// Gets a handle to the asset labeled `label` within this asset, see [HandleId::labeled]
+    pub fn labeled<U>(&self, label: &str) -> Handle<U> {
+        Handle::from_id(self.id.labeled(label))
+    }
+
     pub const fn from_id(id: HandleId) -> Self {
         Handle {
             id,
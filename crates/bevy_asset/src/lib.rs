@@ -1,11 +1,14 @@
-#[cfg(feature = "filesystem_watcher")]
-mod filesystem_watcher;
+mod asset_io;
 mod asset_server;
 mod assets;
+#[cfg(feature = "filesystem_watcher")]
+mod filesystem_watcher;
 mod handle;
 mod load_request;
 mod loader;
+pub mod pack;
 
+pub use asset_io::*;
 pub use asset_server::*;
 pub use assets::*;
 pub use handle::*;
@@ -19,7 +22,7 @@ pub mod stage {
 }
 
 pub mod prelude {
-    pub use crate::{AddAsset, AssetEvent, AssetServer, Assets, Handle};
+    pub use crate::{AddAsset, AssetEvent, AssetServer, AssetServerSettings, Assets, Handle};
 }
 
 use bevy_app::{prelude::Plugin, AppBuilder};
@@ -36,12 +39,42 @@ impl Plugin for AssetPlugin {
         app.add_stage_before(bevy_app::stage::PRE_UPDATE, stage::LOAD_ASSETS)
             .add_stage_after(bevy_app::stage::POST_UPDATE, stage::ASSET_EVENTS)
             .init_resource::<AssetServer>()
-            .register_property::<HandleId>();
+            .add_event::<AssetLoadFailed>()
+            .register_property::<HandleId>()
+            .add_system_to_stage(
+                stage::LOAD_ASSETS,
+                AssetServer::reload_changed_assets_system.system(),
+            );
 
-        #[cfg(feature = "filesystem_watcher")]
-        app.add_system_to_stage(
-            stage::LOAD_ASSETS,
-            AssetServer::filesystem_watcher_system.system(),
-        );
+        let settings = app
+            .resources()
+            .get::<AssetServerSettings>()
+            .map(|settings| (*settings).clone())
+            .unwrap_or_default();
+        if let Some(asset_pack_folder) = settings.asset_pack_folder.as_ref() {
+            let asset_pack_folder = FileAssetIo::get_root_path().join(asset_pack_folder);
+            match pack::PackAssetIo::open_folder(&asset_pack_folder) {
+                Ok(pack_asset_io) => app
+                    .resources()
+                    .get_mut::<AssetServer>()
+                    .unwrap()
+                    .set_asset_io(pack_asset_io),
+                Err(err) => log::error!(
+                    "Failed to open asset packs in {:?}, loading loose files instead: {:?}",
+                    asset_pack_folder,
+                    err
+                ),
+            }
+        }
+        if settings.watch_for_changes {
+            if let Err(err) = app
+                .resources()
+                .get::<AssetServer>()
+                .unwrap()
+                .watch_for_changes()
+            {
+                log::error!("Failed to watch assets for changes: {:?}", err);
+            }
+        }
     }
 }
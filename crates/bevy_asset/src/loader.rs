@@ -1,5 +1,6 @@
-use crate::{AssetServer, AssetVersion, Assets, Handle, LoadState};
+use crate::{AssetIoError, AssetServer, AssetVersion, Assets, Handle, LoadState};
 use anyhow::Result;
+use bevy_app::Events;
 use bevy_ecs::{Res, ResMut, Resource};
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use fs::File;
@@ -15,6 +16,8 @@ use thiserror::Error;
 pub enum AssetLoadError {
     #[error("Encountered an io error while loading asset.")]
     Io(#[from] io::Error),
+    #[error("Encountered an error while reading asset data.")]
+    AssetIo(#[from] AssetIoError),
     #[error("This asset's loader encountered an error while loading.")]
     LoaderError(#[from] anyhow::Error),
 }
@@ -53,14 +56,24 @@ impl<T> AssetChannel<T> {
     }
 }
 
-/// Reads [AssetResult]s from an [AssetChannel] and updates the [Assets] collection and [LoadState] accordingly 
+/// Sent when an asset fails to load, ex: to show the error in the app
+#[derive(Debug, Clone)]
+pub struct AssetLoadFailed {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Reads [AssetResult]s from an [AssetChannel] and updates the [Assets] collection and [LoadState] accordingly
 pub fn update_asset_storage_system<T: Resource>(
     asset_channel: Res<AssetChannel<T>>,
     asset_server: Res<AssetServer>,
     mut assets: ResMut<Assets<T>>,
+    mut load_failed_events: ResMut<Events<AssetLoadFailed>>,
 ) {
     loop {
         match asset_channel.receiver.try_recv() {
+            // the asset has been reloaded since this result was requested
+            Ok(result) if !asset_server.is_latest_version(result.handle.id, result.version) => {}
             Ok(result) => match result.result {
                 Ok(asset) => {
                     assets.set(result.handle, asset);
@@ -71,6 +84,10 @@ pub fn update_asset_storage_system<T: Resource>(
                     asset_server
                         .set_load_state(result.handle.id, LoadState::Failed(result.version));
                     log::error!("Failed to load asset: {:?}", err);
+                    load_failed_events.send(AssetLoadFailed {
+                        path: result.path,
+                        error: format!("{:?}", err),
+                    });
                 }
             },
             Err(TryRecvError::Empty) => {
@@ -0,0 +1,477 @@
+//! Asset packs bundle many asset files into one file with an index, so shipping builds can load from a handful of
+//! packs instead of thousands of loose files. Packs are written with [AssetPackWriter] (or the `pack_assets` binary)
+//! and read with [PackAssetIo].
+//!
+//! A pack starts with a header (magic, format version, pack version), followed by the data of each file, the index,
+//! and the offset of the index. The index maps each path to the range of its data.
+
+use crate::{AssetIo, AssetIoError};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::TryInto,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, Read, Write},
+    ops::{Deref, Range},
+    path::{Component, Path, PathBuf},
+};
+
+/// The extension of asset pack files
+pub const PACK_EXTENSION: &str = "pack";
+
+const MAGIC: &[u8; 4] = b"BPAK";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 8;
+const FOOTER_LEN: usize = 8;
+
+/// Returns the path of an asset inside a pack, which always uses `/` separators. Returns `None` for paths that can't be
+/// in a pack, ex: absolute paths.
+fn pack_path(path: &Path) -> Option<String> {
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(components.join("/"))
+}
+
+enum PackSource {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+/// Gathers asset files and writes them to a pack
+pub struct AssetPackWriter {
+    version: u32,
+    entries: BTreeMap<String, PackSource>,
+}
+
+impl AssetPackWriter {
+    /// Creates a writer for a pack with the given version. The version is not interpreted, it lets games check that
+    /// their packs come from the same build, see [AssetPack::version].
+    pub fn new(version: u32) -> Self {
+        AssetPackWriter {
+            version,
+            entries: Default::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds `bytes` to the pack as the asset at `path`, replacing any asset already added at that path
+    pub fn add_bytes<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        bytes: Vec<u8>,
+    ) -> Result<(), AssetIoError> {
+        let path = Self::get_pack_path(path.as_ref())?;
+        self.entries.insert(path, PackSource::Bytes(bytes));
+        Ok(())
+    }
+
+    /// Adds the file at `path` to the pack. The file is read when the pack is written.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), AssetIoError> {
+        let path = path.as_ref();
+        let pack_path = Self::get_pack_path(path)?;
+        self.entries
+            .insert(pack_path, PackSource::File(path.to_owned()));
+        Ok(())
+    }
+
+    /// Adds all files in the folder at `path` and its subfolders, skipping hidden files. Returns the number of files
+    /// added.
+    pub fn add_folder<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, AssetIoError> {
+        let mut count = 0;
+        for entry in fs::read_dir(path)? {
+            let child_path = entry?.path();
+            let file_name = child_path.file_name().and_then(|name| name.to_str());
+            if let Some(true) = file_name.map(|name| name.starts_with('.')) {
+                continue;
+            }
+
+            if child_path.is_dir() {
+                count += self.add_folder(&child_path)?;
+            } else {
+                self.add_file(&child_path)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), AssetIoError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&self.version.to_le_bytes())?;
+
+        let mut offset = HEADER_LEN as u64;
+        let mut index = Vec::new();
+        for (path, source) in self.entries.iter() {
+            let len = match source {
+                PackSource::File(file_path) => {
+                    let mut file = File::open(file_path)?;
+                    io::copy(&mut file, &mut writer)?
+                }
+                PackSource::Bytes(bytes) => {
+                    writer.write_all(bytes)?;
+                    bytes.len() as u64
+                }
+            };
+            index.push((path, offset, len));
+            offset += len;
+        }
+
+        writer.write_all(&(index.len() as u32).to_le_bytes())?;
+        for (path, data_offset, len) in index {
+            writer.write_all(&(path.len() as u32).to_le_bytes())?;
+            writer.write_all(path.as_bytes())?;
+            writer.write_all(&data_offset.to_le_bytes())?;
+            writer.write_all(&len.to_le_bytes())?;
+        }
+        writer.write_all(&offset.to_le_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), AssetIoError> {
+        let file = File::create(path)?;
+        self.write(io::BufWriter::new(file))
+    }
+
+    fn get_pack_path(path: &Path) -> Result<String, AssetIoError> {
+        pack_path(path).ok_or_else(|| {
+            AssetIoError::InvalidPack(format!("{:?} must be a relative path without '..'", path))
+        })
+    }
+}
+
+enum PackData {
+    Mapped(memmap2::Mmap),
+    Bytes(Vec<u8>),
+}
+
+impl Deref for PackData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            PackData::Mapped(mmap) => mmap,
+            PackData::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// A pack written by [AssetPackWriter]
+pub struct AssetPack {
+    data: PackData,
+    version: u32,
+    entries: HashMap<String, Range<usize>>,
+}
+
+impl AssetPack {
+    /// Opens the pack at `path`. The pack is memory mapped where possible, so only the assets that are loaded are read
+    /// from disk. Otherwise the whole pack is read up front. The pack file must not change while it is open.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, AssetIoError> {
+        let file = File::open(path)?;
+        // empty files can't be mapped
+        if file.metadata()?.len() == 0 {
+            return Self::parse(PackData::Bytes(Vec::new()));
+        }
+
+        // SAFETY: the mapped bytes change if the file is modified, and reading them faults if it is truncated. Packs
+        // are written once and only read afterwards, so they must not be modified while they are open (see above).
+        let data = match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(mmap) => PackData::Mapped(mmap),
+            // ex: on platforms or file systems without memory mapping
+            Err(err) => {
+                log::debug!("Failed to map the asset pack, reading it instead: {}", err);
+                let mut bytes = Vec::new();
+                io::BufReader::new(file).read_to_end(&mut bytes)?;
+                PackData::Bytes(bytes)
+            }
+        };
+
+        Self::parse(data)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, AssetIoError> {
+        Self::parse(PackData::Bytes(bytes))
+    }
+
+    fn parse(data: PackData) -> Result<Self, AssetIoError> {
+        let invalid = |message: &str| AssetIoError::InvalidPack(message.to_string());
+        if data.len() < HEADER_LEN + FOOTER_LEN || !data.starts_with(MAGIC) {
+            return Err(invalid("not an asset pack"));
+        }
+        let format_version = read_u32(&data, MAGIC.len());
+        if format_version != FORMAT_VERSION {
+            return Err(AssetIoError::InvalidPack(format!(
+                "format version {} is not supported",
+                format_version
+            )));
+        }
+        let version = read_u32(&data, MAGIC.len() + 4);
+
+        let index_end = data.len() - FOOTER_LEN;
+        let index_start = read_u64(&data, index_end) as usize;
+        if index_start < HEADER_LEN || index_start > index_end {
+            return Err(invalid("index out of bounds"));
+        }
+
+        let mut index = &data[index_start..index_end];
+        let mut take = |len: usize| {
+            if index.len() < len {
+                return Err(invalid("truncated index"));
+            }
+            let (bytes, rest) = index.split_at(len);
+            index = rest;
+            Ok(bytes)
+        };
+        let count = read_u32(take(4)?, 0);
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            let path_len = read_u32(take(4)?, 0) as usize;
+            let path = std::str::from_utf8(take(path_len)?)
+                .map_err(|_| invalid("path is not valid UTF-8"))?
+                .to_string();
+            let offset = read_u64(take(8)?, 0) as usize;
+            let len = read_u64(take(8)?, 0) as usize;
+            match offset.checked_add(len) {
+                Some(end) if offset >= HEADER_LEN && end <= index_start => {
+                    entries.insert(path, offset..end);
+                }
+                _ => {
+                    return Err(AssetIoError::InvalidPack(format!(
+                        "data of {} out of bounds",
+                        path
+                    )))
+                }
+            }
+        }
+
+        Ok(AssetPack {
+            data,
+            version,
+            entries,
+        })
+    }
+
+    /// The version passed to [AssetPackWriter::new]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the data of the asset at `path`, if it is in this pack
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&[u8]> {
+        let path = pack_path(path.as_ref())?;
+        self.entries
+            .get(&path)
+            .map(|range| &self.data[range.clone()])
+    }
+
+    /// Returns the paths of the assets in this pack, in no particular order
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(|path| path.as_str())
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Reads assets from [AssetPack]s instead of the filesystem. Use it with
+/// [AssetServerSettings::asset_pack_folder](crate::AssetServerSettings::asset_pack_folder), or
+/// [AssetServer::set_asset_io](crate::AssetServer::set_asset_io).
+#[derive(Default)]
+pub struct PackAssetIo {
+    packs: Vec<AssetPack>,
+    directories: HashMap<String, BTreeSet<String>>,
+}
+
+impl PackAssetIo {
+    /// Opens every pack in the folder at `path`, in file name order
+    pub fn open_folder<P: AsRef<Path>>(path: P) -> Result<Self, AssetIoError> {
+        let mut pack_paths = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.extension() == Some(OsStr::new(PACK_EXTENSION)) {
+                pack_paths.push(path);
+            }
+        }
+        pack_paths.sort();
+
+        let mut pack_asset_io = PackAssetIo::default();
+        for pack_path in pack_paths {
+            let pack = AssetPack::open(&pack_path)?;
+            if let Some(first) = pack_asset_io.packs.first() {
+                if first.version() != pack.version() {
+                    log::warn!(
+                        "Asset pack {:?} has version {}, but other packs have version {}",
+                        pack_path,
+                        pack.version(),
+                        first.version()
+                    );
+                }
+            }
+            pack_asset_io.add_pack(pack);
+        }
+
+        Ok(pack_asset_io)
+    }
+
+    /// Adds a pack. Assets in packs added later replace assets with the same path in packs added earlier, so patches can
+    /// be shipped as small packs.
+    pub fn add_pack(&mut self, pack: AssetPack) {
+        for path in pack.paths() {
+            let mut child = path;
+            while let Some(separator) = child.rfind('/') {
+                let parent = &child[..separator];
+                let is_new = self
+                    .directories
+                    .entry(parent.to_string())
+                    .or_default()
+                    .insert(child.to_string());
+                if !is_new {
+                    break;
+                }
+                child = parent;
+            }
+            self.directories
+                .entry(String::new())
+                .or_default()
+                .insert(child.to_string());
+        }
+        self.packs.push(pack);
+    }
+
+    pub fn packs(&self) -> &[AssetPack] {
+        &self.packs
+    }
+}
+
+impl AssetIo for PackAssetIo {
+    fn load_path(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        self.packs
+            .iter()
+            .rev()
+            .find_map(|pack| pack.get(path))
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+    }
+
+    fn read_directory(&self, path: &Path) -> Result<Vec<PathBuf>, AssetIoError> {
+        pack_path(path)
+            .and_then(|path| self.directories.get(&path))
+            .map(|children| children.iter().map(PathBuf::from).collect())
+            .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        match pack_path(path) {
+            Some(path) => self.directories.contains_key(&path),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetPack, AssetPackWriter, PackAssetIo};
+    use crate::{AssetIo, AssetIoError};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn pack_round_trip() {
+        let mut writer = AssetPackWriter::new(3);
+        writer
+            .add_bytes("assets/textures/player.png", vec![1, 2, 3])
+            .unwrap();
+        writer
+            .add_bytes("./assets/textures/enemy.png", vec![4, 5])
+            .unwrap();
+        writer.add_bytes("assets/empty.txt", Vec::new()).unwrap();
+        assert!(writer.add_bytes("../player.png", Vec::new()).is_err());
+
+        let mut bytes = Vec::new();
+        writer.write(&mut bytes).unwrap();
+        let pack = AssetPack::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(pack.version(), 3);
+        assert_eq!(pack.len(), 3);
+        assert_eq!(pack.get("assets/textures/player.png"), Some(&[1, 2, 3][..]));
+        assert_eq!(pack.get("assets/textures/enemy.png"), Some(&[4, 5][..]));
+        assert_eq!(pack.get("assets/empty.txt"), Some(&[][..]));
+        assert_eq!(pack.get("assets/missing.png"), None);
+
+        let mut patch = AssetPackWriter::new(3);
+        patch
+            .add_bytes("assets/textures/player.png", vec![6])
+            .unwrap();
+        let mut patch_bytes = Vec::new();
+        patch.write(&mut patch_bytes).unwrap();
+
+        let mut asset_io = PackAssetIo::default();
+        asset_io.add_pack(pack);
+        asset_io.add_pack(AssetPack::from_bytes(patch_bytes).unwrap());
+        assert_eq!(
+            asset_io
+                .load_path(Path::new("assets/textures/player.png"))
+                .unwrap(),
+            vec![6]
+        );
+        assert_eq!(
+            asset_io
+                .load_path(Path::new("assets/textures/enemy.png"))
+                .unwrap(),
+            vec![4, 5]
+        );
+        assert!(matches!(
+            asset_io.load_path(Path::new("assets/missing.png")),
+            Err(AssetIoError::NotFound(_))
+        ));
+
+        assert!(asset_io.is_directory(Path::new("assets")));
+        assert!(asset_io.is_directory(Path::new("assets/textures")));
+        assert!(!asset_io.is_directory(Path::new("assets/empty.txt")));
+        assert_eq!(
+            asset_io.read_directory(Path::new("assets")).unwrap(),
+            vec![
+                PathBuf::from("assets/empty.txt"),
+                PathBuf::from("assets/textures")
+            ]
+        );
+
+        bytes.truncate(bytes.len() - 1);
+        assert!(AssetPack::from_bytes(bytes).is_err());
+
+        let pack_path =
+            std::env::temp_dir().join(format!("bevy_asset_test_{}.pack", std::process::id()));
+        writer.write_to_file(&pack_path).unwrap();
+        let pack = AssetPack::open(&pack_path).unwrap();
+        assert_eq!(pack.get("assets/textures/player.png"), Some(&[1, 2, 3][..]));
+        drop(pack);
+        std::fs::remove_file(&pack_path).unwrap();
+    }
+}
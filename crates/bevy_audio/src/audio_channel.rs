@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+/// A group of sounds whose volume can be changed together, see [AudioVolume]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AudioChannel {
+    Music,
+    #[default]
+    Effects,
+    Voice,
+    Ambient,
+}
+
+/// The volume of each [AudioChannel]. The volume of a sound is multiplied by its channel's volume and the master
+/// volume. Changes apply to sounds that are already playing.
+#[derive(Clone, Debug)]
+pub struct AudioVolume {
+    pub master: f32,
+    channels: HashMap<AudioChannel, f32>,
+}
+
+impl Default for AudioVolume {
+    fn default() -> Self {
+        AudioVolume {
+            master: 1.0,
+            channels: Default::default(),
+        }
+    }
+}
+
+impl AudioVolume {
+    /// Returns the volume of `channel`, which is 1.0 unless it has been set
+    pub fn get(&self, channel: AudioChannel) -> f32 {
+        self.channels.get(&channel).cloned().unwrap_or(1.0)
+    }
+
+    pub fn set(&mut self, channel: AudioChannel, volume: f32) {
+        self.channels.insert(channel, volume);
+    }
+
+    /// Returns the volume `channel` is played at, including the master volume
+    pub fn get_effective(&self, channel: AudioChannel) -> f32 {
+        self.master * self.get(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AudioChannel, AudioVolume};
+
+    #[test]
+    fn effective_volume() {
+        let mut audio_volume = AudioVolume::default();
+        assert_eq!(audio_volume.get_effective(AudioChannel::Music), 1.0);
+
+        audio_volume.set(AudioChannel::Music, 0.5);
+        audio_volume.master = 0.5;
+        assert_eq!(audio_volume.get(AudioChannel::Music), 0.5);
+        assert_eq!(audio_volume.get_effective(AudioChannel::Music), 0.25);
+        assert_eq!(audio_volume.get_effective(AudioChannel::Effects), 0.5);
+    }
+}
@@ -1,13 +1,14 @@
-use crate::AudioSource;
+use crate::{AudioSink, AudioSource, AudioVolume, PlaybackSettings, SpeedControl};
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::Res;
-use rodio::{Decoder, Device, Sink};
+use rodio::{Decoder, Device, Sink, Source};
 use std::{collections::VecDeque, io::Cursor, sync::RwLock};
 
 /// Used to play audio on the current "audio device"
 pub struct AudioOutput {
     device: Device,
-    queue: RwLock<VecDeque<Handle<AudioSource>>>,
+    queue: RwLock<VecDeque<(Handle<AudioSource>, PlaybackSettings, AudioSink)>>,
+    sinks: RwLock<Vec<(Sink, AudioSink)>>,
 }
 
 impl Default for AudioOutput {
@@ -15,19 +16,76 @@ impl Default for AudioOutput {
         Self {
             device: rodio::default_output_device().unwrap(),
             queue: Default::default(),
+            sinks: Default::default(),
         }
     }
 }
 
 impl AudioOutput {
-    pub fn play_source(&self, audio_source: &AudioSource) {
-        let sink = Sink::new(&self.device);
-        sink.append(Decoder::new(Cursor::new(audio_source.clone())).unwrap());
-        sink.detach();
+    /// Plays `audio_source`, starting on the next update of the [AudioOutput]
+    pub fn play_source(&self, audio_source: &AudioSource) -> AudioSink {
+        self.play_source_with_settings(audio_source, PlaybackSettings::default())
+    }
+
+    /// Plays `audio_source` with the given `settings`, starting on the next update of the [AudioOutput]
+    pub fn play_source_with_settings(
+        &self,
+        audio_source: &AudioSource,
+        settings: PlaybackSettings,
+    ) -> AudioSink {
+        let audio_sink = AudioSink::new(&settings);
+        self.start(audio_source, &settings, audio_sink.clone());
+        audio_sink
+    }
+
+    /// Plays the audio source once it has loaded. The returned [AudioSink] controls playback.
+    pub fn play(&self, audio_source: Handle<AudioSource>) -> AudioSink {
+        self.play_with_settings(audio_source, PlaybackSettings::default())
     }
 
-    pub fn play(&self, audio_source: Handle<AudioSource>) {
-        self.queue.write().unwrap().push_front(audio_source);
+    /// Plays the audio source with the given `settings` once it has loaded
+    pub fn play_with_settings(
+        &self,
+        audio_source: Handle<AudioSource>,
+        settings: PlaybackSettings,
+    ) -> AudioSink {
+        let audio_sink = AudioSink::new(&settings);
+        self.queue
+            .write()
+            .unwrap()
+            .push_front((audio_source, settings, audio_sink.clone()));
+        audio_sink
+    }
+
+    fn start(
+        &self,
+        audio_source: &AudioSource,
+        settings: &PlaybackSettings,
+        audio_sink: AudioSink,
+    ) {
+        let decoder = match Decoder::new(Cursor::new(audio_source.clone())) {
+            Ok(decoder) => decoder,
+            Err(err) => {
+                log::warn!("Failed to decode audio source: {:?}", err);
+                audio_sink.set_finished();
+                return;
+            }
+        };
+
+        // sinks start paused, so the channel volume is applied before anything is heard
+        let sink = Sink::new(&self.device);
+        sink.pause();
+        // the speed control wraps the repeat, so the position keeps counting when the sound loops
+        if settings.looping {
+            sink.append(SpeedControl::new(
+                decoder.repeat_infinite(),
+                audio_sink.clone(),
+            ));
+        } else {
+            sink.append(SpeedControl::new(decoder, audio_sink.clone()));
+        }
+
+        self.sinks.write().unwrap().push((sink, audio_sink));
     }
 
     pub fn try_play_queued(&self, audio_sources: &Assets<AudioSource>) {
@@ -35,22 +93,49 @@ impl AudioOutput {
         let len = queue.len();
         let mut i = 0;
         while i < len {
-            let audio_source_handle = queue.pop_back().unwrap();
-            if let Some(audio_source) = audio_sources.get(&audio_source_handle) {
-                self.play_source(audio_source);
+            let (audio_source_handle, settings, audio_sink) = queue.pop_back().unwrap();
+            if audio_sink.is_stopped() {
+                audio_sink.set_finished();
+            } else if let Some(audio_source) = audio_sources.get(&audio_source_handle) {
+                self.start(audio_source, &settings, audio_sink);
             } else {
                 // audio source hasn't loaded yet. add it back to the queue
-                queue.push_front(audio_source_handle);
+                queue.push_front((audio_source_handle, settings, audio_sink));
             }
             i += 1;
         }
     }
+
+    /// Applies the controls of each [AudioSink] and the [AudioVolume] to playing sounds, and removes sounds that
+    /// have finished
+    pub fn update_sinks(&self, audio_volume: &AudioVolume) {
+        self.sinks.write().unwrap().retain(|(sink, audio_sink)| {
+            if audio_sink.is_stopped() || sink.empty() {
+                // dropping the sink stops it
+                audio_sink.set_finished();
+                return false;
+            }
+
+            sink.set_volume(audio_sink.volume() * audio_volume.get_effective(audio_sink.channel()));
+            if audio_sink.is_paused() != sink.is_paused() {
+                if audio_sink.is_paused() {
+                    sink.pause();
+                } else {
+                    sink.play();
+                }
+            }
+
+            true
+        });
+    }
 }
 
-/// Plays audio currently queued in the [AudioOutput] resource
+/// Plays audio currently queued in the [AudioOutput] resource and applies playback controls to playing audio
 pub(crate) fn play_queued_audio_system(
     audio_sources: Res<Assets<AudioSource>>,
     audio_output: Res<AudioOutput>,
+    audio_volume: Res<AudioVolume>,
 ) {
     audio_output.try_play_queued(&audio_sources);
+    audio_output.update_sinks(&audio_volume);
 }
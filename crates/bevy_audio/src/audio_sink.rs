@@ -0,0 +1,289 @@
+use crate::AudioChannel;
+use rodio::{Sample, Source};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Settings for playing a sound, see [AudioOutput::play_with_settings](crate::AudioOutput::play_with_settings)
+#[derive(Clone, Debug)]
+pub struct PlaybackSettings {
+    pub volume: f32,
+    /// 1.0 is normal speed. Changing the speed also changes the pitch.
+    pub speed: f32,
+    /// Repeats the sound until it is stopped
+    pub looping: bool,
+    /// Starts the sound paused, so it can be started later with [AudioSink::play]
+    pub paused: bool,
+    pub channel: AudioChannel,
+}
+
+impl Default for PlaybackSettings {
+    fn default() -> Self {
+        PlaybackSettings {
+            volume: 1.0,
+            speed: 1.0,
+            looping: false,
+            paused: false,
+            channel: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AtomicF32(AtomicU32);
+
+impl AtomicF32 {
+    fn new(value: f32) -> Self {
+        AtomicF32(AtomicU32::new(value.to_bits()))
+    }
+
+    fn load(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn store(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct SinkControls {
+    volume: AtomicF32,
+    speed: AtomicF32,
+    paused: AtomicBool,
+    stopped: AtomicBool,
+    finished: AtomicBool,
+    /// how far into the sound the audio output is, in nanoseconds
+    position: AtomicU64,
+}
+
+/// Controls a sound played by [AudioOutput](crate::AudioOutput). Clones control the same sound. Changes other than
+/// speed are applied by the audio system, so they take effect on the next update.
+#[derive(Clone, Debug)]
+pub struct AudioSink {
+    controls: Arc<SinkControls>,
+    channel: AudioChannel,
+}
+
+impl AudioSink {
+    pub(crate) fn new(settings: &PlaybackSettings) -> Self {
+        AudioSink {
+            controls: Arc::new(SinkControls {
+                volume: AtomicF32::new(settings.volume),
+                speed: AtomicF32::new(settings.speed),
+                paused: AtomicBool::new(settings.paused),
+                stopped: AtomicBool::new(false),
+                finished: AtomicBool::new(false),
+                position: AtomicU64::new(0),
+            }),
+            channel: settings.channel,
+        }
+    }
+
+    /// Resumes the sound if it is paused
+    pub fn play(&self) {
+        self.controls.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn pause(&self) {
+        self.controls.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.controls.paused.load(Ordering::Relaxed)
+    }
+
+    /// Stops the sound for good. Use [AudioSink::pause] to stop it temporarily.
+    pub fn stop(&self) {
+        self.controls.stopped.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.controls.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Returns true once the sound has played to the end, or has been stopped
+    pub fn is_finished(&self) -> bool {
+        self.controls.finished.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_finished(&self) {
+        self.controls.finished.store(true, Ordering::Relaxed);
+    }
+
+    /// The volume of the sound, before the volume of its channel is applied
+    pub fn volume(&self) -> f32 {
+        self.controls.volume.load()
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.controls.volume.store(volume);
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.controls.speed.load()
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        self.controls.speed.store(speed);
+    }
+
+    pub fn channel(&self) -> AudioChannel {
+        self.channel
+    }
+
+    /// How far into the sound the audio output is, measured in samples handed to the audio device, so it follows the
+    /// audio clock rather than the frame clock. Looping sounds keep counting up when they start over.
+    pub fn position(&self) -> Duration {
+        Duration::from_nanos(self.controls.position.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set_position(&self, position: Duration) {
+        self.controls
+            .position
+            .store(position.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// The number of samples per channel after which [SpeedControl] reports a new frame, so speed changes take effect
+const SPEED_FRAME_LEN: usize = 1024;
+
+/// Plays a source at the speed of an [AudioSink]. Unlike rodio's `Speed`, the speed can change while the source plays.
+/// It also tracks the [AudioSink::position].
+pub(crate) struct SpeedControl<I> {
+    input: I,
+    sink: AudioSink,
+    frame_position: usize,
+    position_seconds: f64,
+}
+
+impl<I> SpeedControl<I> {
+    pub fn new(input: I, sink: AudioSink) -> Self {
+        SpeedControl {
+            input,
+            sink,
+            frame_position: 0,
+            position_seconds: 0.0,
+        }
+    }
+}
+
+impl<I> Iterator for SpeedControl<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let samples_per_second = self.input.sample_rate() as f64 * self.input.channels() as f64;
+        let sample = self.input.next()?;
+        self.frame_position += 1;
+        if samples_per_second > 0.0 {
+            self.position_seconds += 1.0 / samples_per_second;
+            self.sink
+                .set_position(Duration::from_secs_f64(self.position_seconds));
+        }
+        // restart at the frames of the input, so frames always contain whole samples for every channel
+        if self.frame_position >= self.input.channels() as usize * SPEED_FRAME_LEN
+            || self.input.current_frame_len() == Some(0)
+        {
+            self.frame_position = 0;
+        }
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> Source for SpeedControl<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        // the speed is read at the start of each frame, so frames are kept short
+        let remaining = self.input.channels() as usize * SPEED_FRAME_LEN - self.frame_position;
+        match self.input.current_frame_len() {
+            Some(len) => Some(len.min(remaining)),
+            None => Some(remaining),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        let speed = self.sink.speed().max(0.01);
+        (self.input.sample_rate() as f32 * speed) as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AudioSink, PlaybackSettings, SpeedControl, SPEED_FRAME_LEN};
+    use crate::AudioChannel;
+    use rodio::{buffer::SamplesBuffer, Source};
+
+    #[test]
+    fn sink_controls() {
+        let sink = AudioSink::new(&PlaybackSettings {
+            volume: 0.5,
+            paused: true,
+            channel: AudioChannel::Music,
+            ..Default::default()
+        });
+        assert_eq!(sink.volume(), 0.5);
+        assert_eq!(sink.speed(), 1.0);
+        assert_eq!(sink.channel(), AudioChannel::Music);
+        assert!(sink.is_paused());
+
+        // clones control the same sound
+        let clone = sink.clone();
+        clone.play();
+        clone.set_volume(0.25);
+        clone.set_speed(2.0);
+        assert!(!sink.is_paused());
+        assert_eq!(sink.volume(), 0.25);
+        assert_eq!(sink.speed(), 2.0);
+
+        assert!(!sink.is_stopped());
+        clone.stop();
+        assert!(sink.is_stopped());
+        assert!(!sink.is_finished());
+        clone.set_finished();
+        assert!(sink.is_finished());
+    }
+
+    #[test]
+    fn speed_control() {
+        // two seconds of stereo sound at 100 samples per second
+        let input = SamplesBuffer::new(2, 100, vec![0i16; 400]);
+        let sink = AudioSink::new(&PlaybackSettings::default());
+        let mut source = SpeedControl::new(input, sink.clone());
+        assert_eq!(source.sample_rate(), 100);
+        assert!(source.current_frame_len().unwrap() <= 2 * SPEED_FRAME_LEN);
+
+        sink.set_speed(2.0);
+        assert_eq!(source.sample_rate(), 200);
+        assert_eq!(source.channels(), 2);
+
+        // the position follows the samples of the input, whatever the speed
+        source.by_ref().take(200).for_each(drop);
+        assert!((sink.position().as_secs_f64() - 1.0).abs() < 1e-6);
+        assert_eq!(source.by_ref().count(), 200);
+        assert!((sink.position().as_secs_f64() - 2.0).abs() < 1e-6);
+        assert_eq!(source.total_duration(), None);
+    }
+}
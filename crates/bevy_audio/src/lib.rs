@@ -1,15 +1,24 @@
+mod audio_channel;
 mod audio_output;
+mod audio_sink;
 mod audio_source;
+mod music_clock;
 
+pub use audio_channel::*;
 pub use audio_output::*;
+pub use audio_sink::*;
 pub use audio_source::*;
+pub use music_clock::*;
 
 pub mod prelude {
-    pub use crate::{AudioOutput, AudioSource};
+    pub use crate::{
+        AudioChannel, AudioOutput, AudioSink, AudioSource, AudioVolume, Beat, MusicClock,
+        PlaybackSettings, Tempo,
+    };
 }
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, AssetServer};
 use bevy_ecs::IntoQuerySystem;
 
 /// Adds support for audio playback to an App
@@ -19,8 +28,16 @@ pub struct AudioPlugin;
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.init_resource::<AudioOutput>()
+            .init_resource::<AudioVolume>()
+            .init_resource::<MusicClock>()
+            .add_event::<Beat>()
             .add_asset::<AudioSource>()
             .add_asset_loader::<AudioSource, Mp3Loader>()
+            .add_system_to_stage(stage::PRE_UPDATE, music_clock_system.system())
             .add_system_to_stage(stage::POST_UPDATE, play_queued_audio_system.system());
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<AssetServer>();
+    }
 }
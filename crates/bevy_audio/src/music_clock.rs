@@ -0,0 +1,257 @@
+use crate::AudioSink;
+use bevy_app::Events;
+use bevy_ecs::ResMut;
+use std::time::Duration;
+
+/// The tempo of a music track
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tempo {
+    /// Beats per minute
+    pub bpm: f32,
+    /// The time of the first beat in the track, ex: to skip a silent intro
+    pub offset: Duration,
+    pub beats_per_bar: u32,
+}
+
+impl Default for Tempo {
+    fn default() -> Self {
+        Tempo {
+            bpm: 120.0,
+            offset: Duration::from_secs(0),
+            beats_per_bar: 4,
+        }
+    }
+}
+
+impl Tempo {
+    pub fn new(bpm: f32) -> Self {
+        Tempo {
+            bpm,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Duration) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_beats_per_bar(mut self, beats_per_bar: u32) -> Self {
+        self.beats_per_bar = beats_per_bar;
+        self
+    }
+
+    /// The time between beats
+    pub fn beat_duration(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / self.bpm as f64)
+    }
+
+    /// The number of beats since the first beat at `position` in the track. This is negative before the first beat.
+    pub fn beats_at(&self, position: Duration) -> f64 {
+        (position.as_secs_f64() - self.offset.as_secs_f64()) * self.bpm as f64 / 60.0
+    }
+
+    /// The position in the track of beat number `index`
+    pub fn time_of_beat(&self, index: u64) -> Duration {
+        self.offset + Duration::from_secs_f64(index as f64 * 60.0 / self.bpm as f64)
+    }
+
+    /// The position of the beat closest to `position`, ex: to judge the timing of an input in a rhythm game
+    pub fn quantize(&self, position: Duration) -> Duration {
+        let beat = self.beats_at(position).round().max(0.0);
+        self.time_of_beat(beat as u64)
+    }
+}
+
+/// Sent by the [MusicClock] when the music reaches a beat
+#[derive(Clone, Debug, PartialEq)]
+pub struct Beat {
+    /// The number of the beat, counting from 0 at the first beat
+    pub index: u64,
+    pub bar: u64,
+    /// The beat within its bar, from 0 to [Tempo::beats_per_bar]
+    pub beat_in_bar: u32,
+    /// The position of the beat in the track. This is usually a bit earlier than the playback position when the event
+    /// is read, as beats are only checked once per update.
+    pub time: Duration,
+}
+
+/// Follows the playback position of a music track and sends a [Beat] event on each of its beats. The position is read
+/// from the [AudioSink] of the track, which follows the audio output, so beats stay in sync with what is heard even if
+/// frames are slow. The clock updates before the fixed update and update stages, so their systems see the beats of the
+/// current frame.
+#[derive(Debug, Default)]
+pub struct MusicClock {
+    track: Option<(AudioSink, Tempo)>,
+    position: Duration,
+    next_beat: u64,
+}
+
+impl MusicClock {
+    /// Follows the track played by `sink`. Beats start at the current position of the track.
+    pub fn set_track(&mut self, sink: AudioSink, tempo: Tempo) {
+        self.position = sink.position();
+        self.next_beat = first_beat_after(&tempo, self.position);
+        self.track = Some((sink, tempo));
+    }
+
+    pub fn clear_track(&mut self) {
+        self.track = None;
+        self.position = Duration::from_secs(0);
+        self.next_beat = 0;
+    }
+
+    pub fn sink(&self) -> Option<&AudioSink> {
+        self.track.as_ref().map(|(sink, _tempo)| sink)
+    }
+
+    pub fn tempo(&self) -> Option<&Tempo> {
+        self.track.as_ref().map(|(_sink, tempo)| tempo)
+    }
+
+    /// Changes the tempo of the current track, ex: when the song changes tempo
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        if let Some((_sink, current)) = self.track.as_mut() {
+            self.next_beat = first_beat_after(&tempo, self.position);
+            *current = tempo;
+        }
+    }
+
+    /// The playback position of the track, as of the last update
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// The number of beats since the first beat, including the fraction of the current beat. This is negative before
+    /// the first beat.
+    pub fn beats(&self) -> f64 {
+        match self.tempo() {
+            Some(tempo) => tempo.beats_at(self.position),
+            None => 0.0,
+        }
+    }
+
+    /// How far the track is into the current beat, from 0.0 (on the beat) to 1.0, ex: to pulse an effect to the music
+    pub fn beat_fraction(&self) -> f64 {
+        let beats = self.beats();
+        if beats < 0.0 {
+            return 0.0;
+        }
+        beats.fract()
+    }
+
+    /// Updates the position to the position of the track and sends the beats that were reached since the last update
+    pub fn update(&mut self, beats: &mut Events<Beat>) {
+        let position = match self.sink() {
+            Some(sink) => sink.position(),
+            None => return,
+        };
+        self.update_position(position, beats);
+    }
+
+    fn update_position(&mut self, position: Duration, beats: &mut Events<Beat>) {
+        let tempo = match self.tempo() {
+            Some(tempo) => tempo.clone(),
+            None => return,
+        };
+
+        if position < self.position {
+            // the track restarted
+            self.next_beat = first_beat_after(&tempo, position);
+        }
+        self.position = position;
+
+        let beats_per_bar = tempo.beats_per_bar.max(1) as u64;
+        while tempo.time_of_beat(self.next_beat) <= position {
+            let index = self.next_beat;
+            beats.send(Beat {
+                index,
+                bar: index / beats_per_bar,
+                beat_in_bar: (index % beats_per_bar) as u32,
+                time: tempo.time_of_beat(index),
+            });
+            self.next_beat += 1;
+        }
+    }
+}
+
+/// The first beat that isn't before `position`
+fn first_beat_after(tempo: &Tempo, position: Duration) -> u64 {
+    tempo.beats_at(position).ceil().max(0.0) as u64
+}
+
+/// Updates the [MusicClock] and sends [Beat] events
+pub(crate) fn music_clock_system(
+    mut music_clock: ResMut<MusicClock>,
+    mut beats: ResMut<Events<Beat>>,
+) {
+    music_clock.update(&mut beats);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Beat, MusicClock, Tempo};
+    use crate::{AudioSink, PlaybackSettings};
+    use bevy_app::{EventReader, Events};
+    use std::time::Duration;
+
+    #[test]
+    fn beats() {
+        let sink = AudioSink::new(&PlaybackSettings::default());
+        let mut music_clock = MusicClock::default();
+        // a beat every half second, starting at one second
+        music_clock.set_track(
+            sink.clone(),
+            Tempo::new(120.0)
+                .with_offset(Duration::from_secs(1))
+                .with_beats_per_bar(3),
+        );
+
+        let mut events = Events::<Beat>::default();
+        let mut reader = EventReader::<Beat>::default();
+        sink.set_position(Duration::from_millis(900));
+        music_clock.update(&mut events);
+        assert_eq!(reader.iter(&events).count(), 0);
+
+        // slow frames send every beat that was reached
+        sink.set_position(Duration::from_millis(2600));
+        music_clock.update(&mut events);
+        let beats = reader.iter(&events).cloned().collect::<Vec<_>>();
+        assert_eq!(
+            beats.iter().map(|beat| beat.index).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(beats[3].bar, 1);
+        assert_eq!(beats[3].beat_in_bar, 0);
+        assert_eq!(beats[3].time, Duration::from_millis(2500));
+        assert!((music_clock.beat_fraction() - 0.2).abs() < 0.0001);
+
+        // a restarted track starts counting again
+        sink.set_position(Duration::from_millis(1000));
+        music_clock.update(&mut events);
+        assert_eq!(
+            reader
+                .iter(&events)
+                .map(|beat| beat.index)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn quantize() {
+        let tempo = Tempo::new(120.0).with_offset(Duration::from_millis(100));
+        assert_eq!(
+            tempo.quantize(Duration::from_millis(1300)),
+            Duration::from_millis(1100)
+        );
+        assert_eq!(
+            tempo.quantize(Duration::from_millis(1400)),
+            Duration::from_millis(1600)
+        );
+        assert_eq!(
+            tempo.quantize(Duration::from_millis(0)),
+            Duration::from_millis(100)
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use bevy_app::Events;
+use bevy_ecs::{Component, Resources, World};
+use bevy_property::{Properties, Property, PropertyType, PropertyTypeRegistry};
+use bevy_type_registry::{ComponentRegistry, TypeRegistry};
+use std::{
+    any::TypeId,
+    collections::{HashMap, VecDeque},
+    hash::Hasher,
+};
+
+/// The checksum of the world after a fixed update, see [WorldChecksums]. It is sent after every fixed update, ex: to
+/// send it to the other peers of a lockstep game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickChecksum {
+    pub tick: u64,
+    pub checksum: u64,
+}
+
+/// Sent when a peer's checksum for a tick differs from the local checksum, see [WorldChecksums::check]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Desync {
+    /// The first fixed update whose results differ
+    pub tick: u64,
+    pub local: u64,
+    pub remote: u64,
+}
+
+/// Hashes the selected components of every entity after each fixed update, so the peers of a lockstep multiplayer game
+/// can detect when their simulations drift apart. Components are read through their registration, so they have to be
+/// registered with `register_component`. The checksum doesn't depend on entity ids or on the order entities are
+/// stored in: each entity's components are hashed in the order of their type names, and the hashes of the entities
+/// are sorted before they are combined. It is the same on every peer running the same build of the app.
+#[derive(Debug)]
+pub struct WorldChecksums {
+    components: Vec<TypeId>,
+    /// How many local checksums are kept to check the checksums of peers against
+    pub history_len: usize,
+    tick: u64,
+    history: VecDeque<TickChecksum>,
+    remote: HashMap<u64, Vec<u64>>,
+}
+
+impl Default for WorldChecksums {
+    fn default() -> Self {
+        WorldChecksums {
+            components: Vec::new(),
+            history_len: 600,
+            tick: 0,
+            history: VecDeque::new(),
+            remote: HashMap::new(),
+        }
+    }
+}
+
+impl WorldChecksums {
+    /// Includes `T` components in the checksum
+    pub fn add<T: Component>(&mut self) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        if !self.components.contains(&type_id) {
+            self.components.push(type_id);
+        }
+        self
+    }
+
+    /// The number of fixed updates that have run
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// The local checksum of `tick`, if it is still in the history
+    pub fn get(&self, tick: u64) -> Option<u64> {
+        self.history
+            .iter()
+            .find(|checksum| checksum.tick == tick)
+            .map(|checksum| checksum.checksum)
+    }
+
+    /// Checks a peer's checksum of `tick` against the local checksum, returning the [Desync] if they differ. The
+    /// checksums of ticks that haven't run locally yet are checked after they run, and sent as [Desync] events.
+    pub fn check(&mut self, tick: u64, checksum: u64) -> Result<(), Desync> {
+        if tick > self.tick {
+            self.remote.entry(tick).or_default().push(checksum);
+            return Ok(());
+        }
+        match self.get(tick) {
+            Some(local) if local != checksum => Err(Desync {
+                tick,
+                local,
+                remote: checksum,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn record(&mut self, checksum: u64) -> (TickChecksum, Vec<Desync>) {
+        self.tick += 1;
+        let tick_checksum = TickChecksum {
+            tick: self.tick,
+            checksum,
+        };
+        self.history.push_back(tick_checksum);
+        while self.history.len() > self.history_len {
+            self.history.pop_front();
+        }
+
+        let desyncs = self
+            .remote
+            .remove(&self.tick)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|remote| *remote != checksum)
+            .map(|remote| Desync {
+                tick: self.tick,
+                local: checksum,
+                remote,
+            })
+            .collect();
+        (tick_checksum, desyncs)
+    }
+}
+
+/// The 64 bit FNV-1a hash, which is the same on every platform and Rust version
+struct Fnv64(u64);
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Fnv64(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn hash_str(hasher: &mut Fnv64, value: &str) {
+    hasher.write(value.as_bytes());
+    hasher.write(&[0xff]);
+}
+
+fn hash_property(hasher: &mut Fnv64, property: &dyn Property, registry: &PropertyTypeRegistry) {
+    match (property.property_type(), property.as_properties()) {
+        (PropertyType::Map, Some(properties)) | (PropertyType::Seq, Some(properties)) => {
+            hash_properties(hasher, properties, registry)
+        }
+        _ => {
+            hash_str(hasher, property.type_name());
+            // values are hashed in their serialized form, which doesn't depend on their memory layout or padding
+            let serializable = property.serializable(registry);
+            match bevy_ron::ser::to_string(&serializable.borrow()) {
+                Ok(value) => hash_str(hasher, &value),
+                Err(err) => log::warn!(
+                    "Failed to serialize {} for the world checksum: {:?}",
+                    property.type_name(),
+                    err
+                ),
+            }
+        }
+    }
+}
+
+fn hash_properties(
+    hasher: &mut Fnv64,
+    properties: &dyn Properties,
+    registry: &PropertyTypeRegistry,
+) {
+    hash_str(hasher, properties.type_name());
+    for (index, property) in properties.iter_props().enumerate() {
+        if let Some(name) = properties.prop_name(index) {
+            hash_str(hasher, name);
+        }
+        hash_property(hasher, property, registry);
+    }
+}
+
+/// Hashes the `components` of every entity in `world`, see [WorldChecksums]. Components that aren't registered are
+/// skipped.
+pub fn world_checksum(
+    world: &World,
+    components: &[TypeId],
+    component_registry: &ComponentRegistry,
+    property_registry: &PropertyTypeRegistry,
+) -> u64 {
+    let mut entity_hashes = Vec::new();
+    for archetype in world.archetypes() {
+        let mut registrations = archetype
+            .types()
+            .iter()
+            .filter_map(|type_info| type_info.id().type_id())
+            .filter(|type_id| components.contains(type_id))
+            .filter_map(|type_id| component_registry.get(&type_id))
+            .collect::<Vec<_>>();
+        if registrations.is_empty() {
+            continue;
+        }
+        registrations.sort_by_key(|registration| registration.long_name);
+
+        for index in 0..archetype.len() as usize {
+            let mut hasher = Fnv64::default();
+            for registration in registrations.iter() {
+                let properties = registration.get_component_properties(archetype, index);
+                hash_str(&mut hasher, registration.long_name);
+                hash_properties(&mut hasher, properties, property_registry);
+            }
+            entity_hashes.push(hasher.finish());
+        }
+    }
+
+    entity_hashes.sort();
+    let mut hasher = Fnv64::default();
+    for entity_hash in entity_hashes {
+        hasher.write(&entity_hash.to_le_bytes());
+    }
+    hasher.finish()
+}
+
+/// Records the [WorldChecksums] checksum of each fixed update and sends [TickChecksum] and [Desync] events
+pub fn world_checksum_system(world: &mut World, resources: &mut Resources) {
+    let mut checksums = resources.get_mut::<WorldChecksums>().unwrap();
+    let checksum = if checksums.components.is_empty() {
+        0
+    } else {
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+        let component_registry = type_registry.component.read().unwrap();
+        let property_registry = type_registry.property.read().unwrap();
+        world_checksum(
+            world,
+            &checksums.components,
+            &component_registry,
+            &property_registry,
+        )
+    };
+
+    let (tick_checksum, desyncs) = checksums.record(checksum);
+    resources
+        .get_mut::<Events<TickChecksum>>()
+        .unwrap()
+        .send(tick_checksum);
+    let mut desync_events = resources.get_mut::<Events<Desync>>().unwrap();
+    for desync in desyncs {
+        log::error!(
+            "Desync at tick {}: the local checksum is {:x}, and a peer's is {:x}",
+            desync.tick,
+            desync.local,
+            desync.remote
+        );
+        desync_events.send(desync);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{world_checksum, Desync, WorldChecksums};
+    use crate::Timer;
+    use bevy_ecs::World;
+    use bevy_property::PropertyTypeRegistry;
+    use bevy_type_registry::ComponentRegistry;
+    use std::any::TypeId;
+
+    #[test]
+    fn checksum() {
+        let mut component_registry = ComponentRegistry::default();
+        component_registry.register::<Timer>();
+        let property_registry = PropertyTypeRegistry::default();
+        let components = [TypeId::of::<Timer>()];
+        let checksum = |world: &World| {
+            world_checksum(world, &components, &component_registry, &property_registry)
+        };
+
+        // entities spawned in another order, with other ids and unselected components, have the same checksum, but
+        // other entities change it
+        let mut world = World::new();
+        world.spawn((Timer::from_seconds(1.0),));
+        world.spawn((Timer::from_seconds(2.0), 5u32));
+        let mut other_world = World::new();
+        other_world.spawn((Timer::from_seconds(2.0),));
+        other_world.spawn((Timer::from_seconds(1.0), 7u32));
+        assert_eq!(checksum(&world), checksum(&other_world));
+
+        other_world.spawn((Timer::from_seconds(3.0),));
+        assert_ne!(checksum(&world), checksum(&other_world));
+
+        // as does a changed value
+        let mut changed_world = World::new();
+        changed_world.spawn((Timer::from_seconds(1.0),));
+        changed_world.spawn((Timer::from_seconds(2.5),));
+        assert_ne!(checksum(&world), checksum(&changed_world));
+
+        let mut checksums = WorldChecksums::default();
+        checksums.add::<Timer>();
+        checksums.record(10);
+        assert_eq!(checksums.check(1, 10), Ok(()));
+        assert_eq!(
+            checksums.check(1, 11),
+            Err(Desync {
+                tick: 1,
+                local: 10,
+                remote: 11
+            })
+        );
+        // a peer that is ahead is checked once the tick runs
+        assert_eq!(checksums.check(2, 21), Ok(()));
+        let (_tick_checksum, desyncs) = checksums.record(20);
+        assert_eq!(
+            desyncs,
+            vec![Desync {
+                tick: 2,
+                local: 20,
+                remote: 21
+            }]
+        );
+    }
+}
@@ -1,15 +1,20 @@
 mod bytes;
+mod checksum;
 mod float_ord;
 mod label;
 mod time;
 
 pub use bytes::*;
+pub use checksum::*;
 pub use float_ord::*;
 pub use label::*;
 pub use time::*;
 
 pub mod prelude {
-    pub use crate::{EntityLabels, Labels, Time, Timer};
+    pub use crate::{
+        fixed_stage, AddFixedSystem, Desync, EntityLabels, FixedTimestep, Labels, StepWithDelta,
+        TickChecksum, Time, Timer, WorldChecksums,
+    };
 }
 
 use bevy_app::prelude::*;
@@ -24,7 +29,13 @@ pub struct CorePlugin;
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.init_resource::<Time>()
+            .init_resource::<FixedTimestep>()
+            .init_resource::<FixedUpdate>()
             .init_resource::<EntityLabels>()
+            .init_resource::<SystemToggles>()
+            .init_resource::<WorldChecksums>()
+            .add_event::<TickChecksum>()
+            .add_event::<Desync>()
             .register_component::<Timer>()
             .register_property::<Vec2>()
             .register_property::<Vec3>()
@@ -34,6 +45,15 @@ impl Plugin for CorePlugin {
             .register_property::<Option<String>>()
             .add_system_to_stage(stage::FIRST, time_system.system())
             .add_system_to_stage(stage::FIRST, timer_system.system())
-            .add_system_to_stage(stage::PRE_UPDATE, entity_labels_system.system());
+            .add_system_to_stage(stage::PRE_UPDATE, entity_labels_system.system())
+            .add_stage_after(stage::PRE_UPDATE, FIXED_UPDATE_STAGE)
+            .add_system_to_stage(
+                FIXED_UPDATE_STAGE,
+                fixed_update_system.thread_local_system(),
+            )
+            .add_fixed_system_to_stage(
+                fixed_stage::LAST,
+                world_checksum_system.thread_local_system(),
+            );
     }
 }
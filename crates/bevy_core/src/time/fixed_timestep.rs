@@ -0,0 +1,187 @@
+use crate::time::Time;
+use bevy_app::AppBuilder;
+use bevy_ecs::{ParallelExecutor, Resources, Schedule, System, World};
+use std::time::Duration;
+
+/// The stage of the App schedule that runs the fixed update schedule
+pub const FIXED_UPDATE_STAGE: &str = "fixed_update";
+
+/// The stages of the fixed update schedule, see [FixedTimestep]
+pub mod fixed_stage {
+    /// Fixed update systems are added to this stage by default
+    pub const UPDATE: &str = "update";
+    /// Runs after the other fixed update stages, ex: to record the results of each fixed update
+    pub const LAST: &str = "last";
+}
+
+/// Runs the systems added with [AddFixedSystem] at a fixed rate, independent of the frame rate. This keeps
+/// simulations, ex: physics, deterministic. Depending on the frame rate, the fixed update schedule runs zero or more
+/// times per frame.
+#[derive(Debug)]
+pub struct FixedTimestep {
+    /// The time between fixed updates
+    pub step: Duration,
+    /// At most this many fixed updates run per frame. Time beyond that is dropped, so slow frames can't cause ever
+    /// slower frames.
+    pub max_steps_per_frame: u32,
+    accumulator: Duration,
+    steps: u32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        FixedTimestep::new(Duration::from_secs_f64(1.0 / 60.0))
+    }
+}
+
+impl FixedTimestep {
+    /// Panics if `step` is zero, as fixed updates would never catch up with the frame time
+    pub fn new(step: Duration) -> Self {
+        assert!(
+            step > Duration::from_secs(0),
+            "The step of a FixedTimestep must be greater than zero."
+        );
+        FixedTimestep {
+            step,
+            max_steps_per_frame: 5,
+            accumulator: Duration::from_secs(0),
+            steps: 0,
+        }
+    }
+
+    pub fn from_seconds(seconds: f64) -> Self {
+        FixedTimestep::new(Duration::from_secs_f64(seconds))
+    }
+
+    /// The number of fixed updates that ran this frame
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// The time since the last fixed update, as a fraction of the step. This is how far rendering is between the
+    /// last two fixed updates, see `TransformInterpolation` in `bevy_transform`.
+    pub fn overstep_fraction(&self) -> f32 {
+        if self.step == Duration::from_secs(0) {
+            return 0.0;
+        }
+        (self.accumulator.as_secs_f64() / self.step.as_secs_f64()) as f32
+    }
+
+    /// Adds `delta` to the accumulated time and returns the number of fixed updates to run
+    pub fn update(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        self.steps = 0;
+        if self.step == Duration::from_secs(0) {
+            return 0;
+        }
+
+        while self.accumulator >= self.step {
+            if self.steps == self.max_steps_per_frame {
+                // drop the time that can't be caught up on, but keep the overstep
+                let step_nanos = self.step.as_nanos();
+                self.accumulator =
+                    Duration::from_nanos((self.accumulator.as_nanos() % step_nanos) as u64);
+                break;
+            }
+            self.accumulator -= self.step;
+            self.steps += 1;
+        }
+
+        self.steps
+    }
+}
+
+/// The schedule of fixed update systems, see [FixedTimestep]
+pub struct FixedUpdate {
+    pub schedule: Schedule,
+    executor: ParallelExecutor,
+}
+
+impl Default for FixedUpdate {
+    fn default() -> Self {
+        let mut schedule = Schedule::default();
+        schedule.add_stage(fixed_stage::UPDATE);
+        schedule.add_stage(fixed_stage::LAST);
+        FixedUpdate {
+            schedule,
+            // change trackers are cleared once per frame by the App schedule
+            executor: ParallelExecutor::without_tracker_clears(),
+        }
+    }
+}
+
+/// Runs the [FixedUpdate] schedule as many times as the [FixedTimestep] calls for
+pub(crate) fn fixed_update_system(world: &mut World, resources: &mut Resources) {
+    let steps = {
+        let delta = resources.get::<Time>().unwrap().delta;
+        resources.get_mut::<FixedTimestep>().unwrap().update(delta)
+    };
+    if steps == 0 {
+        return;
+    }
+
+    // the schedule is taken out of resources while it runs, so its systems can access resources
+    let mut fixed_update = std::mem::take(&mut *resources.get_mut::<FixedUpdate>().unwrap());
+    fixed_update.schedule.initialize(resources);
+    for _ in 0..steps {
+        fixed_update
+            .executor
+            .run(&mut fixed_update.schedule, world, resources);
+    }
+    *resources.get_mut::<FixedUpdate>().unwrap() = fixed_update;
+}
+
+/// Adds systems to the [FixedUpdate] schedule
+pub trait AddFixedSystem {
+    fn add_fixed_system(&mut self, system: Box<dyn System>) -> &mut Self;
+    fn add_fixed_system_to_stage(
+        &mut self,
+        stage_name: &'static str,
+        system: Box<dyn System>,
+    ) -> &mut Self;
+}
+
+impl AddFixedSystem for AppBuilder {
+    fn add_fixed_system(&mut self, system: Box<dyn System>) -> &mut Self {
+        self.add_fixed_system_to_stage(fixed_stage::UPDATE, system)
+    }
+
+    fn add_fixed_system_to_stage(
+        &mut self,
+        stage_name: &'static str,
+        system: Box<dyn System>,
+    ) -> &mut Self {
+        self.resources()
+            .get_mut::<FixedUpdate>()
+            .expect("FixedUpdate does not exist. Consider adding the CorePlugin.")
+            .schedule
+            .add_system_to_stage(stage_name, system);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedTimestep;
+    use std::time::Duration;
+
+    #[test]
+    fn fixed_timestep() {
+        let mut fixed_timestep = FixedTimestep::from_seconds(0.1);
+        assert_eq!(fixed_timestep.update(Duration::from_millis(50)), 0);
+        assert!((fixed_timestep.overstep_fraction() - 0.5).abs() < 0.001);
+        assert_eq!(fixed_timestep.update(Duration::from_millis(175)), 2);
+        assert!((fixed_timestep.overstep_fraction() - 0.25).abs() < 0.001);
+
+        // long frames run at most max_steps_per_frame fixed updates
+        assert_eq!(fixed_timestep.update(Duration::from_secs(10)), 5);
+        assert_eq!(fixed_timestep.steps(), 5);
+        assert!((fixed_timestep.overstep_fraction() - 0.25).abs() < 0.001);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be greater than zero")]
+    fn zero_step() {
+        FixedTimestep::from_seconds(0.0);
+    }
+}
@@ -1,3 +1,4 @@
+use bevy_app::{AppStatus, AppStepper};
 use bevy_ecs::ResMut;
 use std::time::{Duration, Instant};
 
@@ -9,6 +10,7 @@ pub struct Time {
     pub delta_seconds: f32,
     pub seconds_since_startup: f64,
     pub startup: Instant,
+    manual_delta: Option<Duration>,
 }
 
 impl Default for Time {
@@ -20,25 +22,55 @@ impl Default for Time {
             delta_seconds_f64: 0.0,
             seconds_since_startup: 0.0,
             delta_seconds: 0.0,
+            manual_delta: None,
         }
     }
 }
 
 impl Time {
     pub fn update(&mut self) {
+        if let Some(delta) = self.manual_delta {
+            let now = self.instant.unwrap_or(self.startup) + delta;
+            self.update_delta(now, delta);
+            return;
+        }
+
         let now = Instant::now();
         if let Some(instant) = self.instant {
-            self.delta = now - instant;
-            self.delta_seconds_f64 = self.delta.as_secs_f64();
-            self.delta_seconds = self.delta.as_secs_f32();
+            self.update_delta(now, now - instant);
+        } else {
+            self.update_delta(now, self.delta);
         }
+    }
+
+    fn update_delta(&mut self, now: Instant, delta: Duration) {
+        self.delta = delta;
+        self.delta_seconds_f64 = delta.as_secs_f64();
+        self.delta_seconds = delta.as_secs_f32();
 
         let duration_since_startup = now - self.startup;
         self.seconds_since_startup = duration_since_startup.as_secs_f64();
         self.instant = Some(now);
     }
 
+    /// Advances time by `delta` on every update instead of reading the system clock, ex: when the App is stepped by
+    /// an external loop or a test. `None` goes back to the system clock.
+    pub fn set_manual_delta(&mut self, delta: Option<Duration>) {
+        if self.manual_delta.is_some() && delta.is_none() {
+            // the manual clock may have run ahead of the system clock
+            self.instant = None;
+        }
+        self.manual_delta = delta;
+    }
+
+    pub fn manual_delta(&self) -> Option<Duration> {
+        self.manual_delta
+    }
+
     pub fn time_since_startup(&self) -> Duration {
+        if self.manual_delta.is_some() {
+            return Duration::from_secs_f64(self.seconds_since_startup);
+        }
         Instant::now() - self.startup
     }
 }
@@ -46,3 +78,45 @@ impl Time {
 pub(crate) fn time_system(mut time: ResMut<Time>) {
     time.update();
 }
+
+/// Steps an [AppStepper] with a given time delta, see [Time::set_manual_delta]
+pub trait StepWithDelta {
+    fn step_with_delta(&mut self, delta: Duration) -> AppStatus;
+}
+
+impl StepWithDelta for AppStepper {
+    fn step_with_delta(&mut self, delta: Duration) -> AppStatus {
+        self.app
+            .resources
+            .get_mut::<Time>()
+            .expect("Time does not exist. Consider adding the CorePlugin.")
+            .set_manual_delta(Some(delta));
+        self.step()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{time_system, StepWithDelta, Time};
+    use bevy_app::{stage, AppBuilder, AppStatus};
+    use bevy_ecs::IntoQuerySystem;
+    use std::time::Duration;
+
+    #[test]
+    fn step_with_delta() {
+        let mut app = AppBuilder::default();
+        app.init_resource::<Time>()
+            .add_system_to_stage(stage::FIRST, time_system.system());
+        let mut stepper = app.stepper();
+        for _ in 0..3 {
+            assert_eq!(
+                stepper.step_with_delta(Duration::from_millis(100)),
+                AppStatus::Running
+            );
+        }
+
+        let time = stepper.app.resources.get::<Time>().unwrap();
+        assert_eq!(time.delta, Duration::from_millis(100));
+        assert!((time.seconds_since_startup - 0.3).abs() < 0.0001);
+    }
+}
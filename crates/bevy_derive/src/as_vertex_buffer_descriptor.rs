@@ -41,12 +41,17 @@ pub fn derive_as_vertex_buffer_descriptor(input: TokenStream) -> TokenStream {
                         || VertexAttributes::default(),
                         |a| {
                             syn::custom_keyword!(ignore);
+                            syn::custom_keyword!(instance);
                             let mut vertex_attributes = VertexAttributes::default();
                             a.parse_args_with(|input: ParseStream| {
                                 if let Some(_) = input.parse::<Option<ignore>>()? {
                                     vertex_attributes.ignore = true;
                                     return Ok(());
                                 }
+                                if let Some(_) = input.parse::<Option<instance>>()? {
+                                    vertex_attributes.instance = true;
+                                    return Ok(());
+                                }
                                 Ok(())
                             })
                             .expect("invalid 'vertex' attribute format");
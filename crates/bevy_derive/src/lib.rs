@@ -8,6 +8,7 @@ mod render_resource;
 mod render_resources;
 mod resource;
 mod shader_defs;
+mod system_param;
 
 use proc_macro::TokenStream;
 
@@ -39,7 +40,8 @@ pub fn derive_render_resource(input: TokenStream) -> TokenStream {
 
 /// Derives the ShaderDefs trait. Each field must implement ShaderDef or this will fail.
 /// You can ignore fields using `#[shader_defs(ignore)]`.
-#[proc_macro_derive(ShaderDefs, attributes(shader_def, as_crate))]
+/// Fields marked with `#[shader_defs]` hold a list of shader def names (ex: `Vec<String>`), which are all defined.
+#[proc_macro_derive(ShaderDefs, attributes(shader_def, shader_defs, as_crate))]
 pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
     shader_defs::derive_shader_defs(input)
 }
@@ -50,6 +52,13 @@ pub fn derive_as_vertex_buffer_descriptor(input: TokenStream) -> TokenStream {
     as_vertex_buffer_descriptor::derive_as_vertex_buffer_descriptor(input)
 }
 
+/// Derives the SystemParam trait, which lets the struct be used as a query system parameter. Each field must also
+/// implement SystemParam or this will fail.
+#[proc_macro_derive(SystemParam, attributes(as_crate))]
+pub fn derive_system_param(input: TokenStream) -> TokenStream {
+    system_param::derive_system_param(input)
+}
+
 /// Generates a dynamic plugin entry point function for the given `Plugin` type.  
 #[proc_macro_derive(DynamicPlugin)]
 pub fn derive_dynamic_plugin(input: TokenStream) -> TokenStream {
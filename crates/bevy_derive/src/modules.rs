@@ -8,6 +8,7 @@ pub struct Modules {
     pub bevy_asset: String,
     pub bevy_core: String,
     pub bevy_app: String,
+    pub bevy_ecs: String,
 }
 
 impl Modules {
@@ -17,6 +18,7 @@ impl Modules {
             bevy_render: "bevy::render".to_string(),
             bevy_core: "bevy::core".to_string(),
             bevy_app: "bevy::app".to_string(),
+            bevy_ecs: "bevy::ecs".to_string(),
         }
     }
 
@@ -26,6 +28,7 @@ impl Modules {
             bevy_render: "bevy_render".to_string(),
             bevy_core: "bevy_core".to_string(),
             bevy_app: "bevy_app".to_string(),
+            bevy_ecs: "bevy_ecs".to_string(),
         }
     }
 }
@@ -48,6 +51,8 @@ pub fn get_modules(attributes: &[Attribute]) -> Modules {
             let value = attribute.tokens.to_string();
             if &value[1..value.len() - 1] == modules.bevy_render {
                 modules.bevy_render = "crate".to_string();
+            } else if &value[1..value.len() - 1] == modules.bevy_ecs {
+                modules.bevy_ecs = "crate".to_string();
             }
         }
     }
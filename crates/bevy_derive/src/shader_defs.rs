@@ -6,6 +6,7 @@ use quote::quote;
 use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Fields, Path};
 
 static SHADER_DEF_ATTRIBUTE_NAME: &'static str = "shader_def";
+static SHADER_DEFS_ATTRIBUTE_NAME: &'static str = "shader_defs";
 
 pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
@@ -20,16 +21,21 @@ pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
         _ => panic!("expected a struct with named fields"),
     };
 
+    let has_attribute = |f: &&syn::Field, name: &str| {
+        f.attrs
+            .iter()
+            .find(|a| a.path.get_ident().as_ref().unwrap().to_string() == name)
+            .is_some()
+    };
     let shader_def_idents = fields
         .iter()
-        .filter(|f| {
-            f.attrs
-                .iter()
-                .find(|a| {
-                    a.path.get_ident().as_ref().unwrap().to_string() == SHADER_DEF_ATTRIBUTE_NAME
-                })
-                .is_some()
-        })
+        .filter(|f| has_attribute(f, SHADER_DEF_ATTRIBUTE_NAME))
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect::<Vec<&Ident>>();
+    // fields with a list of shader def names, which are defined as they are
+    let shader_defs_idents = fields
+        .iter()
+        .filter(|f| has_attribute(f, SHADER_DEFS_ATTRIBUTE_NAME))
         .map(|f| f.ident.as_ref().unwrap())
         .collect::<Vec<&Ident>>();
     let struct_name = &ast.ident;
@@ -47,7 +53,7 @@ pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
     TokenStream::from(quote! {
         impl #impl_generics #bevy_render_path::shader::ShaderDefs for #struct_name#ty_generics {
             fn shader_defs_len(&self) -> usize {
-                #shader_defs_len
+                #shader_defs_len #(+ self.#shader_defs_idents.len())*
             }
 
             fn get_shader_def(&self, index: usize) -> Option<&str> {
@@ -58,7 +64,10 @@ pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
                     } else {
                         None
                     },)*
-                    _ => None,
+                    _ => std::iter::empty::<&String>()
+                        #(.chain(self.#shader_defs_idents.iter()))*
+                        .nth(index - #shader_defs_len)
+                        .map(|shader_def| shader_def.as_str()),
                 }
             }
 
@@ -0,0 +1,44 @@
+use crate::modules::{get_modules, get_path};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Fields};
+
+pub fn derive_system_param(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let fields = match &ast.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => panic!("expected a struct with named fields"),
+    };
+
+    let modules = get_modules(&ast.attrs);
+    let bevy_ecs_path = get_path(&modules.bevy_ecs);
+
+    let field_types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let fields = fields.iter().map(|field| field.ident.as_ref().unwrap());
+
+    let generics = ast.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let struct_name = &ast.ident;
+
+    TokenStream::from(quote! {
+        impl #impl_generics #bevy_ecs_path::SystemParam for #struct_name#ty_generics #where_clause {
+            fn init(system_state: &mut #bevy_ecs_path::SystemState) {
+                #(<#field_types as #bevy_ecs_path::SystemParam>::init(system_state);)*
+            }
+
+            unsafe fn get_param(
+                system_state: &#bevy_ecs_path::SystemState,
+                world: &#bevy_ecs_path::World,
+                resources: &#bevy_ecs_path::Resources,
+            ) -> Option<Self> {
+                Some(#struct_name {
+                    #(#fields: <#field_types as #bevy_ecs_path::SystemParam>::get_param(system_state, world, resources)?,)*
+                })
+            }
+        }
+    })
+}
@@ -62,8 +62,14 @@ impl Diagnostic {
         }
     }
 
+    /// The most recent measurement
     pub fn value(&self) -> Option<f64> {
-        self.history.back().map(|measurement| measurement.value)
+        self.history.front().map(|measurement| measurement.value)
+    }
+
+    /// Measurements from newest to oldest
+    pub fn values(&self) -> impl Iterator<Item = &f64> {
+        self.history.iter().map(|measurement| &measurement.value)
     }
 
     pub fn sum(&self) -> f64 {
@@ -126,6 +132,8 @@ impl Diagnostics {
             .and_then(|diagnostic| diagnostic.history.front())
     }
 
+    /// Records a measurement of the [Diagnostic] with the given id. Measurements of diagnostics that haven't been
+    /// added are ignored.
     pub fn add_measurement(&mut self, id: DiagnosticId, value: f64) {
         if let Some(diagnostic) = self.diagnostics.get_mut(&id) {
             diagnostic.add_measurement(value);
@@ -136,3 +144,27 @@ impl Diagnostics {
         self.diagnostics.values()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_history() {
+        let id = DiagnosticId::default();
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.add(Diagnostic::new(id, "test", 3));
+        for value in 1..=5 {
+            diagnostics.add_measurement(id, value as f64);
+        }
+
+        let diagnostic = diagnostics.get(id).unwrap();
+        assert_eq!(diagnostic.value(), Some(5.0));
+        assert_eq!(diagnostic.history_len(), 3);
+        assert_eq!(diagnostic.average(), Some(4.0));
+        assert_eq!(
+            diagnostic.values().cloned().collect::<Vec<_>>(),
+            vec![5.0, 4.0, 3.0]
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_app::prelude::*;
+use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem, ResMut, Resources, World};
+
+/// Adds "entity count" diagnostic to an App
+#[derive(Default)]
+pub struct EntityCountDiagnosticsPlugin;
+
+impl Plugin for EntityCountDiagnosticsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(Self::setup_system.system())
+            .add_system(Self::diagnostic_system.thread_local_system());
+    }
+}
+
+impl EntityCountDiagnosticsPlugin {
+    pub const ENTITY_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(187513512115068938494459732780662867798);
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::ENTITY_COUNT, "entity_count", 20));
+    }
+
+    pub fn diagnostic_system(world: &mut World, resources: &mut Resources) {
+        let entity_count = world.iter().count();
+        if let Some(mut diagnostics) = resources.get_mut::<Diagnostics>() {
+            diagnostics.add_measurement(Self::ENTITY_COUNT, entity_count as f64);
+        }
+    }
+}
@@ -1,11 +1,15 @@
 mod diagnostic;
+mod entity_count_diagnostics_plugin;
 mod frame_time_diagnostics_plugin;
 mod print_diagnostics_plugin;
 #[cfg(feature = "profiler")]
 mod system_profiler;
+mod telemetry_plugin;
 pub use diagnostic::*;
+pub use entity_count_diagnostics_plugin::EntityCountDiagnosticsPlugin;
 pub use frame_time_diagnostics_plugin::FrameTimeDiagnosticsPlugin;
 pub use print_diagnostics_plugin::PrintDiagnosticsPlugin;
+pub use telemetry_plugin::*;
 
 use bevy_app::prelude::*;
 
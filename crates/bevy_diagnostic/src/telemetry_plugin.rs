@@ -0,0 +1,318 @@
+use crate::Diagnostics;
+use bevy_app::{prelude::*, AppExit};
+use bevy_core::{Time, Timer};
+use bevy_ecs::{IntoQuerySystem, Local, Res, ResMut};
+use std::{
+    panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// The value of a [Diagnostic](crate::Diagnostic) at the time of a [TelemetryEvent::DiagnosticsSnapshot]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticSnapshot {
+    pub name: String,
+    pub value: f64,
+    pub average: Option<f64>,
+}
+
+/// A structured event passed to the [TelemetrySink]. Games send their own events, ex: state transitions, as
+/// `TelemetryEvent` events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelemetryEvent {
+    /// The app started, or telemetry was allowed for the first time in this run
+    SessionStart {
+        session_id: Uuid,
+    },
+    /// The app is exiting
+    SessionEnd {
+        session_id: Uuid,
+        duration: Duration,
+    },
+    /// The game moved from one state to another, ex: "main_menu" to "level_1"
+    StateTransition {
+        from: String,
+        to: String,
+    },
+    DiagnosticsSnapshot(Vec<DiagnosticSnapshot>),
+    /// A panic. Only the panic message and location are recorded.
+    Crash {
+        message: String,
+        location: Option<String>,
+    },
+    /// Any other game event, ex: "level_completed" with the level and score as properties
+    Custom {
+        name: String,
+        properties: Vec<(String, String)>,
+    },
+}
+
+/// Receives [TelemetryEvent]s, ex: to forward them to an analytics backend. Events are only passed to the sink while
+/// the player allows telemetry.
+pub trait TelemetrySink: Send + Sync + 'static {
+    fn record(&mut self, session_id: Uuid, event: &TelemetryEvent);
+
+    /// Called once per update after events were recorded, and before the app exits or a crash is reported
+    fn flush(&mut self) {}
+}
+
+/// A [TelemetrySink] that drops every event
+#[derive(Debug, Default)]
+pub struct NoopTelemetrySink;
+
+impl TelemetrySink for NoopTelemetrySink {
+    fn record(&mut self, _session_id: Uuid, _event: &TelemetryEvent) {}
+}
+
+struct TelemetryShared {
+    allowed: AtomicBool,
+    sink: Mutex<Box<dyn TelemetrySink>>,
+}
+
+/// Passes [TelemetryEvent]s to a [TelemetrySink].
+///
+/// Telemetry is off until [Telemetry::set_allowed] is called with the player's consent. Events recorded before that
+/// are dropped, not kept for later, and withdrawing consent stops recording right away. The engine's own events carry
+/// no personal data, and the session id is random for each run of the app.
+pub struct Telemetry {
+    shared: Arc<TelemetryShared>,
+    session_id: Uuid,
+    session_started: bool,
+    session_start_sent: bool,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Telemetry::new(NoopTelemetrySink)
+    }
+}
+
+impl Telemetry {
+    pub fn new(sink: impl TelemetrySink) -> Self {
+        Telemetry {
+            shared: Arc::new(TelemetryShared {
+                allowed: AtomicBool::new(false),
+                sink: Mutex::new(Box::new(sink)),
+            }),
+            session_id: Uuid::new_v4(),
+            session_started: false,
+            session_start_sent: false,
+        }
+    }
+
+    pub fn set_sink(&mut self, sink: impl TelemetrySink) {
+        *self.shared.sink.lock().unwrap() = Box::new(sink);
+    }
+
+    /// Allows or stops telemetry. This should follow the player's choice.
+    pub fn set_allowed(&mut self, allowed: bool) {
+        self.shared.allowed.store(allowed, Ordering::SeqCst);
+        self.send_session_start();
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        self.shared.allowed.load(Ordering::SeqCst)
+    }
+
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+
+    /// Passes `event` to the sink if telemetry is allowed
+    pub fn record(&self, event: &TelemetryEvent) {
+        if self.is_allowed() {
+            self.shared
+                .sink
+                .lock()
+                .unwrap()
+                .record(self.session_id, event);
+        }
+    }
+
+    pub fn flush(&self) {
+        if self.is_allowed() {
+            self.shared.sink.lock().unwrap().flush();
+        }
+    }
+
+    pub(crate) fn start_session(&mut self) {
+        self.session_started = true;
+        self.send_session_start();
+    }
+
+    fn send_session_start(&mut self) {
+        if self.session_started && !self.session_start_sent && self.is_allowed() {
+            self.session_start_sent = true;
+            self.record(&TelemetryEvent::SessionStart {
+                session_id: self.session_id,
+            });
+        }
+    }
+
+    /// Records a [TelemetryEvent::Crash] for each panic, then runs the previous panic hook
+    fn install_panic_hook(&self) {
+        let shared = self.shared.clone();
+        let session_id = self.session_id;
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if shared.allowed.load(Ordering::SeqCst) {
+                let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+                    message.to_string()
+                } else if let Some(message) = info.payload().downcast_ref::<String>() {
+                    message.clone()
+                } else {
+                    "unknown panic".to_string()
+                };
+                let event = TelemetryEvent::Crash {
+                    message,
+                    location: info.location().map(|location| location.to_string()),
+                };
+                // a panic while recording already holds the lock, so don't wait for it
+                if let Ok(mut sink) = shared.sink.try_lock() {
+                    sink.record(session_id, &event);
+                    sink.flush();
+                }
+            }
+            previous_hook(info);
+        }));
+    }
+}
+
+/// Passes session, diagnostics, and crash [TelemetryEvent]s, as well as any `TelemetryEvent` events sent by the game,
+/// to the [TelemetrySink] of the [Telemetry] resource. Telemetry is off until the player allows it. Add a [Telemetry]
+/// resource before this plugin, or set its sink with [Telemetry::set_sink], so panics reach the sink.
+pub struct TelemetryPlugin {
+    /// How often a [TelemetryEvent::DiagnosticsSnapshot] is recorded. Snapshots are off if this is `None`.
+    pub snapshot_interval: Option<Duration>,
+    pub record_panics: bool,
+}
+
+impl Default for TelemetryPlugin {
+    fn default() -> Self {
+        TelemetryPlugin {
+            snapshot_interval: Some(Duration::from_secs(60)),
+            record_panics: true,
+        }
+    }
+}
+
+/// State used by the [TelemetryPlugin]
+pub struct TelemetrySnapshotState {
+    timer: Option<Timer>,
+}
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Telemetry>()
+            .add_event::<TelemetryEvent>()
+            .add_resource(TelemetrySnapshotState {
+                timer: self.snapshot_interval.map(Timer::new),
+            })
+            .add_startup_system(telemetry_session_start_system.system())
+            .add_system_to_stage(stage::LAST, telemetry_system.system());
+
+        if self.record_panics {
+            app.resources()
+                .get::<Telemetry>()
+                .unwrap()
+                .install_panic_hook();
+        }
+    }
+}
+
+fn telemetry_session_start_system(mut telemetry: ResMut<Telemetry>) {
+    telemetry.start_session();
+}
+
+/// Records the `TelemetryEvent` events and diagnostics snapshots of this update, and ends the session when the app
+/// exits
+pub fn telemetry_system(
+    mut event_reader: Local<EventReader<TelemetryEvent>>,
+    mut app_exit_reader: Local<EventReader<AppExit>>,
+    telemetry: Res<Telemetry>,
+    events: Res<Events<TelemetryEvent>>,
+    app_exit_events: Res<Events<AppExit>>,
+    mut snapshot_state: ResMut<TelemetrySnapshotState>,
+    time: Res<Time>,
+    diagnostics: Res<Diagnostics>,
+) {
+    for event in event_reader.iter(&events) {
+        telemetry.record(event);
+    }
+
+    if let Some(timer) = snapshot_state.timer.as_mut() {
+        timer.tick(time.delta_seconds);
+        if timer.finished {
+            telemetry.record(&TelemetryEvent::DiagnosticsSnapshot(
+                diagnostics
+                    .iter()
+                    .filter_map(|diagnostic| {
+                        diagnostic.value().map(|value| DiagnosticSnapshot {
+                            name: diagnostic.name.clone(),
+                            value,
+                            average: diagnostic.average(),
+                        })
+                    })
+                    .collect(),
+            ));
+            timer.reset();
+        }
+    }
+
+    if app_exit_reader.latest(&app_exit_events).is_some() {
+        telemetry.record(&TelemetryEvent::SessionEnd {
+            session_id: telemetry.session_id(),
+            duration: time.time_since_startup(),
+        });
+    }
+
+    telemetry.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Telemetry, TelemetryEvent, TelemetrySink};
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    struct TestSink(Arc<Mutex<Vec<TelemetryEvent>>>);
+
+    impl TelemetrySink for TestSink {
+        fn record(&mut self, _session_id: Uuid, event: &TelemetryEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn consent() {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let mut telemetry = Telemetry::new(TestSink(recorded.clone()));
+        let transition = TelemetryEvent::StateTransition {
+            from: "main_menu".to_string(),
+            to: "level_1".to_string(),
+        };
+
+        // nothing is recorded or kept before consent
+        telemetry.start_session();
+        telemetry.record(&transition);
+        assert!(recorded.lock().unwrap().is_empty());
+
+        telemetry.set_allowed(true);
+        telemetry.record(&transition);
+        telemetry.set_allowed(false);
+        telemetry.record(&transition);
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            vec![
+                TelemetryEvent::SessionStart {
+                    session_id: telemetry.session_id()
+                },
+                transition
+            ]
+        );
+    }
+}
@@ -37,7 +37,7 @@ use crate::{borrow::AtomicBorrow, query::Fetch, Access, Component, Query};
 /// go through the `World`.
 pub struct Archetype {
     types: Vec<TypeInfo>,
-    state: HashMap<TypeId, TypeState>,
+    state: HashMap<ComponentId, TypeState>,
     len: u32,
     entities: Box<[u32]>,
     // UnsafeCell allows unique references into `data` to be constructed while shared references
@@ -92,17 +92,17 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn has<T: Component>(&self) -> bool {
-        self.has_dynamic(TypeId::of::<T>())
+        self.has_dynamic(ComponentId::of::<T>())
     }
 
-    pub(crate) fn has_dynamic(&self, id: TypeId) -> bool {
+    pub(crate) fn has_dynamic(&self, id: ComponentId) -> bool {
         self.state.contains_key(&id)
     }
 
     #[allow(missing_docs)]
     #[inline]
     pub fn get<T: Component>(&self) -> Option<NonNull<T>> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe {
             NonNull::new_unchecked(
                 (*self.data.get()).as_ptr().add(state.offset).cast::<T>() as *mut T
@@ -113,7 +113,7 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn get_with_added<T: Component>(&self) -> Option<(NonNull<T>, NonNull<bool>)> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe {
             (
                 NonNull::new_unchecked(
@@ -127,7 +127,7 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn get_with_mutated<T: Component>(&self) -> Option<(NonNull<T>, NonNull<bool>)> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe {
             (
                 NonNull::new_unchecked(
@@ -143,7 +143,7 @@ impl Archetype {
     pub fn get_with_added_and_mutated<T: Component>(
         &self,
     ) -> Option<(NonNull<T>, NonNull<bool>, NonNull<bool>)> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe {
             (
                 NonNull::new_unchecked(
@@ -158,19 +158,19 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn get_mutated<T: Component>(&self) -> Option<NonNull<bool>> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe { NonNull::new_unchecked(state.mutated_entities.as_ptr() as *mut bool) })
     }
 
     #[allow(missing_docs)]
     #[inline]
     pub fn get_added<T: Component>(&self) -> Option<NonNull<bool>> {
-        let state = self.state.get(&TypeId::of::<T>())?;
+        let state = self.state.get(&ComponentId::of::<T>())?;
         Some(unsafe { NonNull::new_unchecked(state.added_entities.as_ptr() as *mut bool) })
     }
 
     #[allow(missing_docs)]
-    pub fn get_type_state_mut(&mut self, ty: TypeId) -> Option<&mut TypeState> {
+    pub fn get_type_state_mut(&mut self, ty: ComponentId) -> Option<&mut TypeState> {
         self.state.get_mut(&ty)
     }
 
@@ -179,7 +179,7 @@ impl Archetype {
     pub fn borrow<T: Component>(&self) {
         if self
             .state
-            .get(&TypeId::of::<T>())
+            .get(&ComponentId::of::<T>())
             .map_or(false, |x| !x.borrow.borrow())
         {
             panic!("{} already borrowed uniquely", type_name::<T>());
@@ -191,7 +191,7 @@ impl Archetype {
     pub fn borrow_mut<T: Component>(&self) {
         if self
             .state
-            .get(&TypeId::of::<T>())
+            .get(&ComponentId::of::<T>())
             .map_or(false, |x| !x.borrow.borrow_mut())
         {
             panic!("{} already borrowed", type_name::<T>());
@@ -201,7 +201,7 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn release<T: Component>(&self) {
-        if let Some(x) = self.state.get(&TypeId::of::<T>()) {
+        if let Some(x) = self.state.get(&ComponentId::of::<T>()) {
             x.borrow.release();
         }
     }
@@ -209,11 +209,59 @@ impl Archetype {
     #[allow(missing_docs)]
     #[inline]
     pub fn release_mut<T: Component>(&self) {
-        if let Some(x) = self.state.get(&TypeId::of::<T>()) {
+        if let Some(x) = self.state.get(&ComponentId::of::<T>()) {
             x.borrow.release_mut();
         }
     }
 
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn borrow_dynamic(&self, id: ComponentId) {
+        if self.state.get(&id).map_or(false, |x| !x.borrow.borrow()) {
+            panic!("{:?} already borrowed uniquely", id);
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn borrow_mut_dynamic(&self, id: ComponentId) {
+        if self
+            .state
+            .get(&id)
+            .map_or(false, |x| !x.borrow.borrow_mut())
+        {
+            panic!("{:?} already borrowed", id);
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn release_dynamic(&self, id: ComponentId) {
+        if let Some(x) = self.state.get(&id) {
+            x.borrow.release();
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn release_mut_dynamic(&self, id: ComponentId) {
+        if let Some(x) = self.state.get(&id) {
+            x.borrow.release_mut();
+        }
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn get_mutated_dynamic(&self, id: ComponentId) -> Option<NonNull<bool>> {
+        let state = self.state.get(&id)?;
+        Some(unsafe { NonNull::new_unchecked(state.mutated_entities.as_ptr() as *mut bool) })
+    }
+
+    /// Metadata for the component type with the given `id`, if this archetype stores it
+    pub fn type_info(&self, id: ComponentId) -> Option<&TypeInfo> {
+        self.types.iter().find(|ty| ty.id == id)
+    }
+
     #[allow(missing_docs)]
     #[inline]
     pub fn len(&self) -> u32 {
@@ -242,7 +290,7 @@ impl Archetype {
     /// `index` must be in-bounds
     pub(crate) unsafe fn get_dynamic(
         &self,
-        ty: TypeId,
+        ty: ComponentId,
         size: usize,
         index: u32,
     ) -> Option<NonNull<u8>> {
@@ -372,7 +420,7 @@ impl Archetype {
     pub(crate) unsafe fn move_to(
         &mut self,
         index: u32,
-        mut f: impl FnMut(*mut u8, TypeId, usize, bool, bool),
+        mut f: impl FnMut(*mut u8, ComponentId, usize, bool, bool),
     ) -> Option<u32> {
         let last = self.len - 1;
         for ty in &self.types {
@@ -412,7 +460,7 @@ impl Archetype {
     pub unsafe fn put_dynamic(
         &mut self,
         component: *mut u8,
-        ty: TypeId,
+        ty: ComponentId,
         size: usize,
         index: u32,
         added: bool,
@@ -479,10 +527,42 @@ impl TypeState {
     }
 }
 
+/// Uniquely identifies a component type, which is either a Rust type or a type registered at runtime
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ComponentId {
+    /// A Rust type, identified by its `TypeId`
+    RustTypeId(TypeId),
+    /// A type defined outside of Rust (ex: by a scripting language), identified by a user-assigned id
+    ExternalId(u64),
+}
+
+impl ComponentId {
+    /// The id of the Rust type `T`
+    #[inline]
+    pub fn of<T: 'static>() -> Self {
+        ComponentId::RustTypeId(TypeId::of::<T>())
+    }
+
+    /// The `TypeId` of this component if it is a Rust type
+    #[inline]
+    pub fn type_id(&self) -> Option<TypeId> {
+        match self {
+            ComponentId::RustTypeId(type_id) => Some(*type_id),
+            ComponentId::ExternalId(_) => None,
+        }
+    }
+}
+
+impl From<TypeId> for ComponentId {
+    fn from(type_id: TypeId) -> Self {
+        ComponentId::RustTypeId(type_id)
+    }
+}
+
 /// Metadata required to store a component
 #[derive(Debug, Copy, Clone)]
 pub struct TypeInfo {
-    id: TypeId,
+    id: ComponentId,
     layout: Layout,
     drop: unsafe fn(*mut u8),
 }
@@ -495,15 +575,27 @@ impl TypeInfo {
         }
 
         Self {
-            id: TypeId::of::<T>(),
+            id: ComponentId::of::<T>(),
             layout: Layout::new::<T>(),
             drop: drop_ptr::<T>,
         }
     }
 
+    /// Metadata for a component type whose layout is only known at runtime
+    ///
+    /// # Safety
+    /// `drop` must be safe to call on any value that is inserted with this `TypeInfo`
+    pub unsafe fn of_external(id: u64, layout: Layout, drop: unsafe fn(*mut u8)) -> Self {
+        Self {
+            id: ComponentId::ExternalId(id),
+            layout,
+            drop,
+        }
+    }
+
     #[allow(missing_docs)]
     #[inline]
-    pub fn id(&self) -> TypeId {
+    pub fn id(&self) -> ComponentId {
         self.id
     }
 
@@ -525,7 +617,7 @@ impl PartialOrd for TypeInfo {
 }
 
 impl Ord for TypeInfo {
-    /// Order by alignment, descending. Ties broken with TypeId.
+    /// Order by alignment, descending. Ties broken with ComponentId.
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.layout
             .align()
@@ -21,7 +21,10 @@ use core::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use crate::{archetype::Archetype, Component, MissingComponent};
+use crate::{
+    archetype::{Archetype, ComponentId},
+    Component, ComponentError, MissingComponent,
+};
 
 pub struct AtomicBorrow(AtomicUsize);
 
@@ -176,6 +179,116 @@ where
     }
 }
 
+/// Shared borrow of an entity's component, viewed as raw bytes
+pub struct DynamicRef<'a> {
+    archetype: &'a Archetype,
+    id: ComponentId,
+    target: NonNull<u8>,
+    size: usize,
+}
+
+impl<'a> DynamicRef<'a> {
+    #[allow(missing_docs)]
+    pub unsafe fn new(
+        archetype: &'a Archetype,
+        id: ComponentId,
+        index: u32,
+    ) -> Result<Self, ComponentError> {
+        let size = archetype
+            .type_info(id)
+            .ok_or(ComponentError::MissingDynamicComponent(id))?
+            .layout()
+            .size();
+        let target = archetype.get_dynamic(id, size, index).unwrap();
+        archetype.borrow_dynamic(id);
+        Ok(Self {
+            archetype,
+            id,
+            target,
+            size,
+        })
+    }
+}
+
+unsafe impl Send for DynamicRef<'_> {}
+unsafe impl Sync for DynamicRef<'_> {}
+
+impl<'a> Drop for DynamicRef<'a> {
+    fn drop(&mut self) {
+        self.archetype.release_dynamic(self.id);
+    }
+}
+
+impl<'a> Deref for DynamicRef<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.target.as_ptr(), self.size) }
+    }
+}
+
+/// Unique borrow of an entity's component, viewed as raw bytes
+pub struct DynamicRefMut<'a> {
+    archetype: &'a Archetype,
+    id: ComponentId,
+    target: NonNull<u8>,
+    size: usize,
+    modified: &'a mut bool,
+}
+
+impl<'a> DynamicRefMut<'a> {
+    #[allow(missing_docs)]
+    pub unsafe fn new(
+        archetype: &'a Archetype,
+        id: ComponentId,
+        index: u32,
+    ) -> Result<Self, ComponentError> {
+        let size = archetype
+            .type_info(id)
+            .ok_or(ComponentError::MissingDynamicComponent(id))?
+            .layout()
+            .size();
+        let target = archetype.get_dynamic(id, size, index).unwrap();
+        archetype.borrow_mut_dynamic(id);
+        let modified = archetype
+            .get_mutated_dynamic(id)
+            .unwrap()
+            .as_ptr()
+            .add(index as usize);
+        Ok(Self {
+            archetype,
+            id,
+            target,
+            size,
+            modified: &mut *modified,
+        })
+    }
+}
+
+unsafe impl Send for DynamicRefMut<'_> {}
+unsafe impl Sync for DynamicRefMut<'_> {}
+
+impl<'a> Drop for DynamicRefMut<'a> {
+    fn drop(&mut self) {
+        self.archetype.release_mut_dynamic(self.id);
+    }
+}
+
+impl<'a> Deref for DynamicRefMut<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.target.as_ptr(), self.size) }
+    }
+}
+
+impl<'a> DerefMut for DynamicRefMut<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        *self.modified = true;
+        unsafe { core::slice::from_raw_parts_mut(self.target.as_ptr(), self.size) }
+    }
+}
+
 /// Handle to an entity with any component types
 #[derive(Copy, Clone)]
 pub struct EntityRef<'a> {
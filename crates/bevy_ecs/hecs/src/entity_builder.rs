@@ -21,14 +21,14 @@ use crate::alloc::{
     vec::Vec,
 };
 use core::{
-    any::TypeId,
+    fmt,
     mem::{self, MaybeUninit},
     ptr,
 };
 
 use hashbrown::HashSet;
 
-use crate::{archetype::TypeInfo, Component, DynamicBundle};
+use crate::{archetype::TypeInfo, Component, ComponentId, DynamicBundle};
 
 /// Helper for incrementally constructing a bundle of components with dynamic component types
 ///
@@ -47,8 +47,8 @@ pub struct EntityBuilder {
     storage: Box<[MaybeUninit<u8>]>,
     cursor: usize,
     info: Vec<(TypeInfo, usize)>,
-    ids: Vec<TypeId>,
-    id_set: HashSet<TypeId>,
+    ids: Vec<ComponentId>,
+    id_set: HashSet<ComponentId>,
 }
 
 impl EntityBuilder {
@@ -65,7 +65,7 @@ impl EntityBuilder {
 
     /// Add `component` to the entity
     pub fn add<T: Component>(&mut self, component: T) -> &mut Self {
-        if !self.id_set.insert(TypeId::of::<T>()) {
+        if !self.id_set.insert(ComponentId::of::<T>()) {
             return self;
         }
         let end = self.cursor + mem::size_of::<T>();
@@ -86,6 +86,42 @@ impl EntityBuilder {
         self
     }
 
+    /// Add a component whose type is only known at runtime, copying its value from `data`
+    ///
+    /// Fails without adding the component if `data` isn't exactly as large as the layout of `info`.
+    ///
+    /// # Safety
+    /// `data` must be a valid instance of the component described by `info`. Ownership of the value is
+    /// transferred to the builder, so the caller must not drop it.
+    pub unsafe fn add_dynamic(
+        &mut self,
+        info: TypeInfo,
+        data: &[u8],
+    ) -> Result<&mut Self, LayoutMismatch> {
+        if info.layout().size() != data.len() {
+            return Err(LayoutMismatch {
+                id: info.id(),
+                expected: info.layout().size(),
+                found: data.len(),
+            });
+        }
+        if !self.id_set.insert(info.id()) {
+            return Ok(self);
+        }
+        let end = self.cursor + data.len();
+        if end > self.storage.len() {
+            self.grow(end);
+        }
+        ptr::copy_nonoverlapping(
+            data.as_ptr(),
+            self.storage.as_mut_ptr().add(self.cursor).cast::<u8>(),
+            data.len(),
+        );
+        self.info.push((info, self.cursor));
+        self.cursor += data.len();
+        Ok(self)
+    }
+
     fn grow(&mut self, min_size: usize) {
         let new_len = min_size.next_power_of_two().max(64);
         let mut new_storage = vec![MaybeUninit::uninit(); new_len].into_boxed_slice();
@@ -166,7 +202,7 @@ pub struct BuiltEntity<'a> {
 }
 
 impl DynamicBundle for BuiltEntity<'_> {
-    fn with_ids<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+    fn with_ids<T>(&self, f: impl FnOnce(&[ComponentId]) -> T) -> T {
         f(&self.builder.ids)
     }
 
@@ -175,7 +211,7 @@ impl DynamicBundle for BuiltEntity<'_> {
         self.builder.info.iter().map(|x| x.0).collect()
     }
 
-    unsafe fn put(self, mut f: impl FnMut(*mut u8, TypeId, usize) -> bool) {
+    unsafe fn put(self, mut f: impl FnMut(*mut u8, ComponentId, usize) -> bool) {
         for (ty, offset) in self.builder.info.drain(..) {
             let ptr = self.builder.storage.as_mut_ptr().add(offset).cast();
             if !f(ptr, ty.id(), ty.layout().size()) {
@@ -192,3 +228,27 @@ impl Drop for BuiltEntity<'_> {
         self.builder.clear();
     }
 }
+
+/// Error indicating that the value of a dynamic component didn't have the size of its type's layout
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct LayoutMismatch {
+    /// The component type
+    pub id: ComponentId,
+    /// The size of the component type's layout
+    pub expected: usize,
+    /// The size of the value
+    pub found: usize,
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} components are {} bytes large, but the value has {} bytes",
+            self.id, self.expected, self.found
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutMismatch {}
@@ -75,11 +75,11 @@ mod query_one;
 mod serde;
 mod world;
 
-pub use archetype::Archetype;
-pub use borrow::{EntityRef, Ref, RefMut};
+pub use archetype::{Archetype, ComponentId};
+pub use borrow::{DynamicRef, DynamicRefMut, EntityRef, Ref, RefMut};
 pub use bundle::{Bundle, DynamicBundle, MissingComponent};
 pub use entities::{Entity, Location, NoSuchEntity};
-pub use entity_builder::{BuiltEntity, EntityBuilder};
+pub use entity_builder::{BuiltEntity, EntityBuilder, LayoutMismatch};
 pub use query::{
     Access, Added, BatchedIter, Changed, Mut, Mutated, Query, QueryBorrow, QueryIter, With, Without,
 };
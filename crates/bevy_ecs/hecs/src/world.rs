@@ -15,7 +15,7 @@
 // modified by Bevy contributors
 
 use crate::alloc::vec::Vec;
-use core::{any::TypeId, convert::TryFrom, fmt, mem, ptr};
+use core::{convert::TryFrom, fmt, mem, ptr};
 
 #[cfg(feature = "std")]
 use std::error::Error;
@@ -23,10 +23,10 @@ use std::error::Error;
 use hashbrown::{HashMap, HashSet};
 
 use crate::{
-    archetype::Archetype,
+    archetype::{Archetype, ComponentId},
     entities::{Entities, Location},
-    Bundle, DynamicBundle, Entity, EntityRef, MissingComponent, NoSuchEntity, Query, QueryBorrow,
-    QueryOne, Ref, RefMut,
+    Bundle, DynamicBundle, DynamicRef, DynamicRefMut, Entity, EntityRef, MissingComponent,
+    NoSuchEntity, Query, QueryBorrow, QueryOne, Ref, RefMut,
 };
 
 /// An unordered collection of entities, each having any number of distinctly typed components
@@ -38,11 +38,12 @@ use crate::{
 /// runs, allowing for extremely fast, cache-friendly iteration.
 pub struct World {
     entities: Entities,
-    index: HashMap<Vec<TypeId>, u32>,
-    removed_components: HashMap<TypeId, Vec<Entity>>,
+    index: HashMap<Vec<ComponentId>, u32>,
+    removed_components: HashMap<ComponentId, Vec<Entity>>,
     #[allow(missing_docs)]
     pub archetypes: Vec<Archetype>,
     archetype_generation: u64,
+    despawned_count: u64,
 }
 
 impl World {
@@ -58,6 +59,7 @@ impl World {
             index,
             archetypes,
             archetype_generation: 0,
+            despawned_count: 0,
             removed_components: HashMap::default(),
         }
     }
@@ -168,6 +170,7 @@ impl World {
                 .or_insert_with(|| Vec::new());
             removed_entities.push(entity);
         }
+        self.despawned_count += 1;
         Ok(())
     }
 
@@ -205,6 +208,7 @@ impl World {
                     .or_insert_with(|| Vec::new());
                 removed_entities.extend(archetype.iter_entities().map(|id| Entity::from_id(*id)));
             }
+            self.despawned_count += archetype.len() as u64;
             archetype.clear();
         }
         self.entities.clear();
@@ -215,6 +219,12 @@ impl World {
         self.entities.contains(entity)
     }
 
+    /// The number of entities despawned since the world was created. Unlike `removed`, this is not reset by
+    /// `clear_trackers`, so it can tell whether any entities were despawned between two points in time.
+    pub fn despawned_count(&self) -> u64 {
+        self.despawned_count
+    }
+
     /// Efficiently iterate over all entities that have certain components
     ///
     /// Calling `iter` on the returned value yields `(Entity, Q)` tuples, where `Q` is some query
@@ -305,6 +315,49 @@ impl World {
         Ok(unsafe { RefMut::new(&self.archetypes[loc.archetype as usize], loc.index)? })
     }
 
+    /// Borrow the component of `entity` identified by `id` as raw bytes
+    ///
+    /// Panics if the component is already uniquely borrowed from another entity with the same components.
+    pub fn get_dynamic(
+        &self,
+        entity: Entity,
+        id: ComponentId,
+    ) -> Result<DynamicRef<'_>, ComponentError> {
+        let loc = self.entities.get(entity)?;
+        unsafe { DynamicRef::new(&self.archetypes[loc.archetype as usize], id, loc.index) }
+    }
+
+    /// Uniquely borrow the component of `entity` identified by `id` as raw bytes
+    ///
+    /// Panics if the component is already borrowed from another entity with the same components.
+    pub fn get_dynamic_mut(
+        &self,
+        entity: Entity,
+        id: ComponentId,
+    ) -> Result<DynamicRefMut<'_>, ComponentError> {
+        let loc = self.entities.get(entity)?;
+        unsafe { DynamicRefMut::new(&self.archetypes[loc.archetype as usize], id, loc.index) }
+    }
+
+    /// Iterate over every entity that has the component identified by `id`, borrowing the component as raw bytes
+    ///
+    /// This is useful for component types that are only known at runtime. Prefer `World::query` for Rust types.
+    pub fn query_dynamic(
+        &self,
+        id: ComponentId,
+    ) -> impl Iterator<Item = (Entity, DynamicRef<'_>)> + '_ {
+        self.archetypes
+            .iter()
+            .filter(move |archetype| archetype.has_dynamic(id))
+            .flat_map(move |archetype| {
+                (0..archetype.len()).map(move |index| {
+                    let entity = Entity::from_id(archetype.entity_id(index));
+                    let component = unsafe { DynamicRef::new(archetype, id, index).unwrap() };
+                    (entity, component)
+                })
+            })
+    }
+
     /// Access an entity regardless of its component types
     ///
     /// Does not immediately borrow any component.
@@ -338,7 +391,7 @@ impl World {
     #[allow(missing_docs)]
     pub fn removed<C: Component>(&self) -> &[Entity] {
         self.removed_components
-            .get(&TypeId::of::<C>())
+            .get(&ComponentId::of::<C>())
             .map_or(&[], |entities| entities.as_slice())
     }
 
@@ -644,6 +697,8 @@ pub enum ComponentError {
     NoSuchEntity,
     /// The entity did not have a requested component
     MissingComponent(MissingComponent),
+    /// The entity did not have a component requested by its `ComponentId`
+    MissingDynamicComponent(ComponentId),
 }
 
 #[cfg(feature = "std")]
@@ -655,6 +710,7 @@ impl fmt::Display for ComponentError {
         match *self {
             NoSuchEntity => f.write_str("no such entity"),
             MissingComponent(ref x) => x.fmt(f),
+            MissingDynamicComponent(ref id) => write!(f, "missing {:?} component", id),
         }
     }
 }
@@ -164,6 +164,55 @@ fn dynamic_components() {
     );
 }
 
+#[test]
+fn external_components() {
+    unsafe fn drop_nothing(_: *mut u8) {}
+
+    let mut world = World::new();
+    let info = unsafe {
+        TypeInfo::of_external(
+            0,
+            std::alloc::Layout::from_size_align(4, 4).unwrap(),
+            drop_nothing,
+        )
+    };
+    let mut builder = EntityBuilder::new();
+    builder.add(123);
+    unsafe {
+        builder.add_dynamic(info, &7u32.to_ne_bytes()).unwrap();
+        assert_eq!(
+            builder.add_dynamic(info, &7u16.to_ne_bytes()).err(),
+            Some(LayoutMismatch {
+                id: ComponentId::ExternalId(0),
+                expected: 4,
+                found: 2,
+            })
+        );
+    }
+    let e = world.spawn(builder.build());
+    let f = world.spawn((456,));
+
+    let id = ComponentId::ExternalId(0);
+    assert_eq!(*world.get_dynamic(e, id).unwrap(), 7u32.to_ne_bytes());
+    assert_eq!(
+        world.get_dynamic(f, id).err(),
+        Some(ComponentError::MissingDynamicComponent(id))
+    );
+
+    world
+        .get_dynamic_mut(e, id)
+        .unwrap()
+        .copy_from_slice(&8u32.to_ne_bytes());
+    assert_eq!(
+        world
+            .query_dynamic(id)
+            .map(|(entity, bytes)| (entity, bytes.to_vec()))
+            .collect::<Vec<_>>(),
+        &[(e, 8u32.to_ne_bytes().to_vec())]
+    );
+    assert_eq!(*world.get::<i32>(e).unwrap(), 123);
+}
+
 #[test]
 #[should_panic(expected = "already borrowed")]
 fn illegal_borrow() {
@@ -0,0 +1,159 @@
+use crate::resource::Resources;
+use std::{borrow::Cow, error::Error, fmt};
+
+/// An error from anywhere in Bevy. Any [Error] converts into it, so `?` works in functions that return [BevyResult].
+pub struct BevyError(Box<dyn Error + Send + Sync + 'static>);
+
+impl BevyError {
+    /// Creates an error from a message
+    pub fn new(message: impl Into<String>) -> Self {
+        BevyError(Box::new(MessageError(message.into())))
+    }
+
+    /// Returns the underlying error if it is of type `E`
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref::<E>()
+    }
+
+    /// Returns the underlying error
+    pub fn inner(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.0
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for BevyError {
+    fn from(error: E) -> Self {
+        BevyError(Box::new(error))
+    }
+}
+
+impl fmt::Debug for BevyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for BevyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[derive(Debug)]
+struct MessageError(String);
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for MessageError {}
+
+/// The result of a fallible operation, ex: a system that returns errors instead of panicking
+pub type BevyResult<T = ()> = Result<T, BevyError>;
+
+/// An error returned by a system, or by one of the commands it queued
+#[derive(Debug)]
+pub struct SystemError {
+    pub system_name: Cow<'static, str>,
+    pub error: BevyError,
+}
+
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.system_name, self.error)
+    }
+}
+
+/// A function that handles [SystemError]s, see [SystemErrorHandler::Custom]
+pub type SystemErrorHandlerFn = Box<dyn Fn(&Resources, SystemError) + Send + Sync>;
+
+/// Handles [SystemError]s. Insert this resource to choose what happens when a system fails. Errors are handled when the
+/// stage of the failed system is flushed. Without this resource errors are logged.
+#[derive(Default)]
+pub enum SystemErrorHandler {
+    /// Logs the error
+    #[default]
+    Log,
+    /// Panics with the error, ex: in tests
+    Panic,
+    /// Calls the given function, ex: to send the error to an `Events` resource
+    Custom(SystemErrorHandlerFn),
+}
+
+impl fmt::Debug for SystemErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemErrorHandler::Log => write!(f, "Log"),
+            SystemErrorHandler::Panic => write!(f, "Panic"),
+            SystemErrorHandler::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl SystemErrorHandler {
+    pub fn handle(&self, resources: &Resources, error: SystemError) {
+        match self {
+            SystemErrorHandler::Log => log::error!("{}", error),
+            SystemErrorHandler::Panic => panic!("{}", error),
+            SystemErrorHandler::Custom(handler) => handler(resources, error),
+        }
+    }
+}
+
+/// Handles `error` with the [SystemErrorHandler] resource
+pub(crate) fn handle_system_error(resources: &Resources, error: SystemError) {
+    match resources.get::<SystemErrorHandler>() {
+        Some(handler) => handler.handle(resources, error),
+        None => SystemErrorHandler::default().handle(resources, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BevyError, BevyResult, SystemErrorHandler};
+    use crate::{
+        resource::{ResMut, Resources},
+        schedule::Schedule,
+        system::{Commands, IntoQuerySystem},
+    };
+    use bevy_hecs::{Entity, World};
+    use std::sync::{Arc, Mutex};
+
+    fn parse_system(mut value: ResMut<u32>) -> BevyResult {
+        *value = "not a number".parse::<u32>()?;
+        Ok(())
+    }
+
+    fn despawn_system(mut commands: Commands) -> BevyResult {
+        commands.despawn(Entity::new());
+        Err(BevyError::new("oh no"))
+    }
+
+    #[test]
+    fn handle_system_errors() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let handler_errors = errors.clone();
+        resources.insert(SystemErrorHandler::Custom(Box::new(
+            move |_resources, error| handler_errors.lock().unwrap().push(error.to_string()),
+        )));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", parse_system.system());
+        schedule.add_system_to_stage("update", despawn_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        let mut errors = errors.lock().unwrap().clone();
+        errors.sort();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("despawn_system failed: Cannot despawn entity"));
+        assert!(errors[1].contains("despawn_system failed: oh no"));
+        assert!(errors[2].contains("parse_system failed: invalid digit"));
+    }
+}
@@ -1,9 +1,11 @@
 pub use bevy_hecs::{Query as HecsQuery, *};
+mod error;
 mod resource;
 mod schedule;
 mod system;
 mod world;
 
+pub use error::*;
 pub use resource::*;
 pub use schedule::*;
 pub use system::{Query, *};
@@ -11,9 +13,12 @@ pub use world::*;
 
 pub mod prelude {
     pub use crate::{
+        error::{BevyError, BevyResult, SystemErrorHandler},
         resource::{FromResources, Local, Res, ResMut, Resource, Resources},
+        schedule::SystemToggles,
         system::{
-            Commands, IntoForEachSystem, IntoQuerySystem, IntoThreadLocalSystem, Query, System,
+            Commands, IntoForEachSystem, IntoQuerySystem, IntoThreadLocalSystem, Query, Single,
+            System, SystemParam,
         },
         world::WorldBuilderSource,
         Added, Bundle, Changed, Component, Entity, Mut, Mutated, Ref, RefMut, With, Without, World,
@@ -198,9 +198,13 @@ impl<'a, T: Resource + FromResources> ResourceQuery for Local<'a, T> {
     type Fetch = FetchResourceLocalMut<T>;
 
     fn initialize(resources: &mut Resources, id: Option<SystemId>) {
-        let value = T::from_resources(resources);
         let id = id.expect("Local<T> resources can only be used by systems");
-        resources.insert_local(id, value);
+        // systems are re-initialized whenever the schedule changes, so existing state (including state inserted with
+        // `insert_local_resource`) must be preserved
+        if resources.get_local::<T>(id).is_none() {
+            let value = T::from_resources(resources);
+            resources.insert_local(id, value);
+        }
     }
 }
 
@@ -1,5 +1,7 @@
 mod parallel_executor;
 mod schedule;
+mod system_toggles;
 
 pub use parallel_executor::*;
 pub use schedule::*;
+pub use system_toggles::*;
@@ -1,4 +1,4 @@
-use super::Schedule;
+use super::{Schedule, SystemToggles};
 use crate::{
     resource::Resources,
     system::{ArchetypeAccess, System, ThreadLocalExecution, TypeAccess},
@@ -8,7 +8,9 @@ use fixedbitset::FixedBitSet;
 use bevy_hecs::{ArchetypesGeneration, World};
 use rayon::ScopeFifo;
 use std::{
+    any::Any,
     ops::Range,
+    panic::AssertUnwindSafe,
     sync::{Arc, Mutex},
 };
 
@@ -25,6 +27,7 @@ pub struct ParallelExecutor {
     stages: Vec<ExecutorStage>,
     last_schedule_generation: usize,
     clear_trackers: bool,
+    catch_system_panics: bool,
 }
 
 impl Default for ParallelExecutor {
@@ -33,6 +36,7 @@ impl Default for ParallelExecutor {
             stages: Default::default(),
             last_schedule_generation: usize::MAX, // MAX forces prepare to run the first time
             clear_trackers: true,
+            catch_system_panics: false,
         }
     }
 }
@@ -45,6 +49,17 @@ impl ParallelExecutor {
         }
     }
 
+    /// When enabled, a panic in a system doesn't take down the app. The panic is logged, the system is disabled with
+    /// the [SystemToggles] resource, and the rest of the schedule keeps running. This is meant for editors and
+    /// live-coding sessions.
+    pub fn set_catch_system_panics(&mut self, catch_system_panics: bool) {
+        self.catch_system_panics = catch_system_panics;
+    }
+
+    pub fn catch_system_panics(&self) -> bool {
+        self.catch_system_panics
+    }
+
     pub fn run(&mut self, schedule: &mut Schedule, world: &mut World, resources: &mut Resources) {
         let schedule_generation = schedule.generation();
         let schedule_changed = schedule.generation() != self.last_schedule_generation;
@@ -56,7 +71,12 @@ impl ParallelExecutor {
         for (stage_name, executor_stage) in schedule.stage_order.iter().zip(self.stages.iter_mut())
         {
             if let Some(stage_systems) = schedule.stages.get_mut(stage_name) {
+                #[cfg(feature = "profiler")]
+                crate::profiler::profiler_start(resources, stage_scope(stage_name));
+                executor_stage.catch_system_panics = self.catch_system_panics;
                 executor_stage.run(world, resources, stage_systems, schedule_changed);
+                #[cfg(feature = "profiler")]
+                crate::profiler::profiler_stop(resources, stage_scope(stage_name));
             }
         }
 
@@ -68,6 +88,12 @@ impl ParallelExecutor {
     }
 }
 
+/// The profiler scope of a whole stage, which is kept apart from the scopes of its systems
+#[cfg(feature = "profiler")]
+fn stage_scope(stage_name: &str) -> std::borrow::Cow<'static, str> {
+    format!("stage::{}", stage_name).into()
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecutorStage {
     /// each system's set of dependencies
@@ -80,6 +106,11 @@ pub struct ExecutorStage {
     /// the currently finished systems
     finished_systems: FixedBitSet,
     running_systems: FixedBitSet,
+    /// systems turned off by the [SystemToggles] resource
+    disabled_systems: FixedBitSet,
+    catch_system_panics: bool,
+    /// the systems that panicked during this run, with their panic messages
+    panicked_systems: Arc<Mutex<Vec<(usize, String)>>>,
 
     sender: Sender<usize>,
     receiver: Receiver<usize>,
@@ -96,6 +127,9 @@ impl Default for ExecutorStage {
             next_thread_local_index: 0,
             finished_systems: Default::default(),
             running_systems: Default::default(),
+            disabled_systems: Default::default(),
+            catch_system_panics: false,
+            panicked_systems: Default::default(),
             sender,
             receiver,
             last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces prepare to run the first time
@@ -264,10 +298,26 @@ impl ExecutorStage {
 
                 // handle multi-threaded system
                 let sender = self.sender.clone();
+                let disabled = self.disabled_systems.contains(system_index);
+                let catch_system_panics = self.catch_system_panics;
+                let panicked_systems = self.panicked_systems.clone();
                 self.running_systems.insert(system_index);
                 scope.spawn_fifo(move |_| {
-                    let mut system = system.lock().unwrap();
-                    system.run(world, resources);
+                    if !disabled {
+                        let mut system = system.lock().unwrap();
+                        #[cfg(feature = "profiler")]
+                        crate::profiler::profiler_start(resources, system.name().clone());
+                        if let Some(message) =
+                            run_system(catch_system_panics, || system.run(world, resources))
+                        {
+                            panicked_systems
+                                .lock()
+                                .unwrap()
+                                .push((system_index, message));
+                        }
+                        #[cfg(feature = "profiler")]
+                        crate::profiler::profiler_stop(resources, system.name().clone());
+                    }
                     sender.send(system_index).unwrap();
                 });
 
@@ -306,6 +356,20 @@ impl ExecutorStage {
             }
         }
 
+        // systems are toggled before the stage runs, because systems may hold the toggles while others run
+        self.disabled_systems.clear();
+        self.disabled_systems.grow(systems.len());
+        if let Some(system_toggles) = resources.get::<SystemToggles>() {
+            if system_toggles.any_disabled() {
+                for (system_index, system) in systems.iter().enumerate() {
+                    let system = system.lock().unwrap();
+                    if !system_toggles.is_enabled(&**system) {
+                        self.disabled_systems.insert(system_index);
+                    }
+                }
+            }
+        }
+
         self.next_thread_local_index = 0;
         self.prepare_to_next_thread_local(world, systems, schedule_changed);
 
@@ -337,8 +401,21 @@ impl ExecutorStage {
                 // if a thread local system is ready to run, run it exclusively on the main thread
                 let mut system = systems[thread_local_index].lock().unwrap();
                 self.running_systems.insert(thread_local_index);
-                system.run(world, resources);
-                system.run_thread_local(world, resources);
+                if !self.disabled_systems.contains(thread_local_index) {
+                    #[cfg(feature = "profiler")]
+                    crate::profiler::profiler_start(resources, system.name().clone());
+                    if let Some(message) = run_system(self.catch_system_panics, || {
+                        system.run(world, resources);
+                        system.run_thread_local(world, resources);
+                    }) {
+                        self.panicked_systems
+                            .lock()
+                            .unwrap()
+                            .push((thread_local_index, message));
+                    }
+                    #[cfg(feature = "profiler")]
+                    crate::profiler::profiler_stop(resources, system.name().clone());
+                }
                 self.finished_systems.insert(thread_local_index);
                 self.sender.send(thread_local_index).unwrap();
 
@@ -374,21 +451,84 @@ impl ExecutorStage {
         }
 
         // "flush"
-        for system in systems.iter() {
+        for (system_index, system) in systems.iter().enumerate() {
+            if self.disabled_systems.contains(system_index) {
+                continue;
+            }
             let mut system = system.lock().unwrap();
             match system.thread_local_execution() {
-                ThreadLocalExecution::NextFlush => system.run_thread_local(world, resources),
+                ThreadLocalExecution::NextFlush => {
+                    if let Some(message) = run_system(self.catch_system_panics, || {
+                        system.run_thread_local(world, resources)
+                    }) {
+                        self.panicked_systems
+                            .lock()
+                            .unwrap()
+                            .push((system_index, message));
+                    }
+                }
                 ThreadLocalExecution::Immediate => { /* already ran */ }
             }
         }
 
+        self.disable_panicked_systems(resources, systems);
         self.last_archetypes_generation = world.archetypes_generation();
     }
+
+    /// Logs the systems that panicked during this run and disables them by id with the [SystemToggles] resource, so
+    /// other instances of generic or closure systems with the same name keep running
+    fn disable_panicked_systems(
+        &mut self,
+        resources: &mut Resources,
+        systems: &[Arc<Mutex<Box<dyn System>>>],
+    ) {
+        let panicked_systems = std::mem::take(&mut *self.panicked_systems.lock().unwrap());
+        if panicked_systems.is_empty() {
+            return;
+        }
+
+        if resources.get::<SystemToggles>().is_none() {
+            resources.insert(SystemToggles::default());
+        }
+        let mut system_toggles = resources.get_mut::<SystemToggles>().unwrap();
+        for (system_index, message) in panicked_systems {
+            let system = systems[system_index].lock().unwrap();
+            log::error!(
+                "System {} ({:?}) panicked and has been disabled: {}. Enable it again with SystemToggles::enable_system_id once it is fixed.",
+                system.name(),
+                system.id(),
+                message
+            );
+            system_toggles.disable_system_id(system.id());
+        }
+    }
+}
+
+/// Runs `run`. If `catch_panics` is set, a panic is caught and its message is returned.
+fn run_system(catch_panics: bool, run: impl FnOnce()) -> Option<String> {
+    if !catch_panics {
+        run();
+        return None;
+    }
+
+    std::panic::catch_unwind(AssertUnwindSafe(run))
+        .err()
+        .map(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ParallelExecutor;
+    use super::{ParallelExecutor, SystemToggles};
     use crate::{
         resource::{Res, ResMut, Resources},
         schedule::Schedule,
@@ -404,6 +544,51 @@ mod tests {
         count: Arc<Mutex<usize>>,
     }
 
+    #[test]
+    fn catch_system_panics() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+
+        fn panicking_system(_count: ResMut<u32>) {
+            panic!("oh no");
+        }
+
+        fn count_system(mut count: ResMut<u32>) {
+            *count += 1;
+            if *count == 2 {
+                panic!("oh no");
+            }
+        }
+
+        // both count systems have the same name, but only the second one panics
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        let panicking_system = panicking_system.system();
+        let panicking_system_id = panicking_system.id();
+        let count_system_a = count_system.system();
+        let count_system_a_id = count_system_a.id();
+        let count_system_b = count_system.system();
+        let count_system_b_id = count_system_b.id();
+        schedule.add_system_to_stage("update", panicking_system);
+        schedule.add_system_to_stage("update", count_system_a);
+        schedule.add_system_to_stage("update", count_system_b);
+        schedule.initialize(&mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.set_catch_system_panics(true);
+        executor.run(&mut schedule, &mut world, &mut resources);
+        executor.run(&mut schedule, &mut world, &mut resources);
+
+        // the resources the panicking systems borrowed were released, and they were disabled after the first run
+        assert_eq!(*resources.get::<u32>().unwrap(), 3);
+        let system_toggles = resources.get::<SystemToggles>().unwrap();
+        assert!(system_toggles.is_system_id_disabled(panicking_system_id));
+        assert!(system_toggles.is_system_id_disabled(count_system_b_id));
+        assert!(!system_toggles.is_system_id_disabled(count_system_a_id));
+        assert_eq!(system_toggles.iter_disabled().count(), 0);
+    }
+
     #[test]
     fn cross_stage_archetype_change_prepare() {
         let mut world = World::new();
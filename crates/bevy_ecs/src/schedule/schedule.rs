@@ -1,3 +1,4 @@
+use super::system_toggles::is_system_enabled;
 use crate::{
     resource::Resources,
     system::{System, SystemId, ThreadLocalExecution},
@@ -130,9 +131,12 @@ impl Schedule {
         for stage_name in self.stage_order.iter() {
             if let Some(stage_systems) = self.stages.get_mut(stage_name) {
                 for system in stage_systems.iter_mut() {
+                    let mut system = system.lock().unwrap();
+                    if !is_system_enabled(resources, &**system) {
+                        continue;
+                    }
                     #[cfg(feature = "profiler")]
                     crate::profiler::profiler_start(resources, system.name().clone());
-                    let mut system = system.lock().unwrap();
                     system.update_archetype_access(world);
                     match system.thread_local_execution() {
                         ThreadLocalExecution::NextFlush => system.run(world, resources),
@@ -0,0 +1,276 @@
+use crate::{resource::Resources, System, SystemId};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// Turns systems on and off at runtime, ex: to isolate a subsystem while debugging. Disabled systems are skipped by
+/// the [Schedule](crate::Schedule) and the [ParallelExecutor](crate::ParallelExecutor). Changes apply from the next
+/// stage on.
+///
+/// Systems are matched by label. A system has these labels:
+/// * its name, which is the path of its function for function systems, ex: `my_game::physics::gravity_system`
+/// * each module its function is in, ex: `my_game::physics`, so a whole module of systems can be turned off at once
+/// * the labels added with [SystemToggles::add_label]
+///
+/// Single systems can also be turned off by [SystemId], which doesn't affect other systems with the same name, ex:
+/// other instances of a generic or closure system.
+#[derive(Debug, Default)]
+pub struct SystemToggles {
+    labels: HashMap<Cow<'static, str>, Vec<Cow<'static, str>>>,
+    disabled: HashSet<Cow<'static, str>>,
+    disabled_system_ids: HashSet<SystemId>,
+}
+
+impl SystemToggles {
+    /// Adds `label` to the system named `system_name`, see [System::name](crate::System::name)
+    pub fn add_label(
+        &mut self,
+        system_name: impl Into<Cow<'static, str>>,
+        label: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.labels
+            .entry(system_name.into())
+            .or_default()
+            .push(label.into());
+        self
+    }
+
+    pub fn enable(&mut self, label: &str) {
+        self.disabled.remove(label);
+    }
+
+    pub fn disable(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.disabled.insert(label.into());
+    }
+
+    pub fn set_enabled(&mut self, label: impl Into<Cow<'static, str>>, enabled: bool) {
+        let label = label.into();
+        if enabled {
+            self.enable(&label);
+        } else {
+            self.disable(label);
+        }
+    }
+
+    /// Enables the label if it is disabled and disables it otherwise. Returns whether it is now enabled.
+    pub fn toggle(&mut self, label: impl Into<Cow<'static, str>>) -> bool {
+        let label = label.into();
+        let enabled = self.is_label_disabled(&label);
+        self.set_enabled(label, enabled);
+        enabled
+    }
+
+    pub fn enable_system_id(&mut self, system_id: SystemId) {
+        self.disabled_system_ids.remove(&system_id);
+    }
+
+    pub fn disable_system_id(&mut self, system_id: SystemId) {
+        self.disabled_system_ids.insert(system_id);
+    }
+
+    pub fn is_system_id_disabled(&self, system_id: SystemId) -> bool {
+        self.disabled_system_ids.contains(&system_id)
+    }
+
+    /// Enables all labels and system ids
+    pub fn enable_all(&mut self) {
+        self.disabled.clear();
+        self.disabled_system_ids.clear();
+    }
+
+    pub fn is_label_disabled(&self, label: &str) -> bool {
+        self.disabled.contains(label)
+    }
+
+    /// Iterates the disabled labels
+    pub fn iter_disabled(&self) -> impl Iterator<Item = &str> {
+        self.disabled.iter().map(|label| label.as_ref())
+    }
+
+    /// Returns false if any of the labels of the system named `system_name` are disabled
+    pub fn is_system_enabled(&self, system_name: &str) -> bool {
+        if self.disabled.is_empty() {
+            return true;
+        }
+
+        if self.disabled.contains(system_name) {
+            return false;
+        }
+
+        let mut module_path = system_name;
+        while let Some(index) = module_path.rfind("::") {
+            module_path = &module_path[..index];
+            if self.disabled.contains(module_path) {
+                return false;
+            }
+        }
+
+        match self.labels.get(system_name) {
+            Some(labels) => labels.iter().all(|label| !self.disabled.contains(label)),
+            None => true,
+        }
+    }
+
+    /// Returns false if the system is disabled by its id or by any of its labels
+    pub fn is_enabled(&self, system: &dyn System) -> bool {
+        !self.is_system_id_disabled(system.id()) && self.is_system_enabled(&system.name())
+    }
+
+    /// Returns true if any label or system id is disabled
+    pub fn any_disabled(&self) -> bool {
+        !self.disabled.is_empty() || !self.disabled_system_ids.is_empty()
+    }
+
+    /// Runs a text command, ex: from a debug console. The commands are `enable <label>`, `disable <label>`,
+    /// `toggle <label>`, `enable_all`, and `list`, which lists the disabled labels. Returns a message describing the
+    /// result.
+    pub fn run_command(&mut self, command: &str) -> Result<String, SystemToggleCommandError> {
+        let mut words = command.split_whitespace();
+        let name = words.next().ok_or(SystemToggleCommandError::Empty)?;
+        let label = words.next();
+        if words.next().is_some() {
+            return Err(SystemToggleCommandError::TooManyArguments);
+        }
+
+        match (name, label) {
+            ("enable", Some(label)) => {
+                self.enable(label);
+                Ok(format!("enabled {}", label))
+            }
+            ("disable", Some(label)) => {
+                self.disable(label.to_string());
+                Ok(format!("disabled {}", label))
+            }
+            ("toggle", Some(label)) => {
+                if self.toggle(label.to_string()) {
+                    Ok(format!("enabled {}", label))
+                } else {
+                    Ok(format!("disabled {}", label))
+                }
+            }
+            ("enable_all", None) => {
+                self.enable_all();
+                Ok("enabled all systems".to_string())
+            }
+            ("list", None) => {
+                let mut disabled = self.iter_disabled().collect::<Vec<_>>();
+                disabled.sort();
+                Ok(format!("disabled: {}", disabled.join(", ")))
+            }
+            ("enable", None) | ("disable", None) | ("toggle", None) => {
+                Err(SystemToggleCommandError::MissingLabel)
+            }
+            (_, _) => Err(SystemToggleCommandError::UnknownCommand(name.to_string())),
+        }
+    }
+}
+
+/// An error from [SystemToggles::run_command]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemToggleCommandError {
+    Empty,
+    UnknownCommand(String),
+    MissingLabel,
+    TooManyArguments,
+}
+
+impl fmt::Display for SystemToggleCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemToggleCommandError::Empty => write!(f, "empty command"),
+            SystemToggleCommandError::UnknownCommand(name) => {
+                write!(f, "unknown command: {}", name)
+            }
+            SystemToggleCommandError::MissingLabel => write!(f, "command requires a label"),
+            SystemToggleCommandError::TooManyArguments => write!(f, "too many arguments"),
+        }
+    }
+}
+
+impl std::error::Error for SystemToggleCommandError {}
+
+/// Returns false if the [SystemToggles] resource disables `system`
+pub(crate) fn is_system_enabled(resources: &Resources, system: &dyn System) -> bool {
+    match resources.get::<SystemToggles>() {
+        Some(system_toggles) => system_toggles.is_enabled(system),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SystemToggleCommandError, SystemToggles};
+    use crate::{IntoQuerySystem, ResMut, Resources, Schedule, World};
+
+    #[test]
+    fn toggle_labels() {
+        let mut toggles = SystemToggles::default();
+        toggles.add_label("game::physics::gravity_system", "simulation");
+        assert!(toggles.is_system_enabled("game::physics::gravity_system"));
+
+        toggles.disable("game::physics");
+        assert!(!toggles.is_system_enabled("game::physics::gravity_system"));
+        assert!(toggles.is_system_enabled("game::physics_debug::draw_system"));
+        toggles.enable("game::physics");
+
+        toggles.disable("simulation");
+        assert!(!toggles.is_system_enabled("game::physics::gravity_system"));
+        assert!(toggles.is_system_enabled("game::ai::think_system"));
+        assert!(toggles.toggle("simulation"));
+        assert!(toggles.is_system_enabled("game::physics::gravity_system"));
+    }
+
+    #[test]
+    fn run_command() {
+        let mut toggles = SystemToggles::default();
+        assert_eq!(
+            toggles.run_command("disable game::ai").unwrap(),
+            "disabled game::ai"
+        );
+        assert!(!toggles.is_system_enabled("game::ai::think_system"));
+        assert_eq!(toggles.run_command("list").unwrap(), "disabled: game::ai");
+        assert_eq!(
+            toggles.run_command("toggle game::ai").unwrap(),
+            "enabled game::ai"
+        );
+        assert_eq!(
+            toggles.run_command("toggle"),
+            Err(SystemToggleCommandError::MissingLabel)
+        );
+        assert_eq!(
+            toggles.run_command("explode game::ai"),
+            Err(SystemToggleCommandError::UnknownCommand(
+                "explode".to_string()
+            ))
+        );
+    }
+
+    #[derive(Default)]
+    struct Counter(usize);
+
+    fn count_system(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    #[test]
+    fn skip_disabled_systems() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Counter::default());
+        resources.insert(SystemToggles::default());
+
+        let system = count_system.system();
+        let name = system.name();
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", system);
+        schedule.initialize(&mut resources);
+
+        schedule.run(&mut world, &mut resources);
+        resources.get_mut::<SystemToggles>().unwrap().disable(name);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Counter>().unwrap().0, 1);
+    }
+}
@@ -1,7 +1,11 @@
 use super::SystemId;
-use crate::resource::{Resource, Resources};
-use bevy_hecs::{Bundle, Component, DynamicBundle, Entity, World};
+use crate::{
+    error::{handle_system_error, BevyError, BevyResult, SystemError},
+    resource::{Resource, Resources},
+};
+use bevy_hecs::{Bundle, Component, DynamicBundle, Entity, EntityBuilder, TypeInfo, World};
 use std::{
+    borrow::Cow,
     marker::PhantomData,
     sync::{Arc, Mutex},
 };
@@ -12,9 +16,9 @@ pub enum Command {
     WriteResources(Box<dyn ResourcesWriter>),
 }
 
-/// A [World] mutation
+/// A [World] mutation. Errors are handled by the [SystemErrorHandler](crate::SystemErrorHandler).
 pub trait WorldWriter: Send + Sync {
-    fn write(self: Box<Self>, world: &mut World);
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult;
 }
 
 pub(crate) struct Spawn<T>
@@ -28,8 +32,9 @@ impl<T> WorldWriter for Spawn<T>
 where
     T: DynamicBundle + Send + Sync + 'static,
 {
-    fn write(self: Box<Self>, world: &mut World) {
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
         world.spawn(self.components);
+        Ok(())
     }
 }
 
@@ -45,8 +50,9 @@ impl<T> WorldWriter for SpawnAsEntity<T>
 where
     T: DynamicBundle + Send + Sync + 'static,
 {
-    fn write(self: Box<Self>, world: &mut World) {
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
         world.spawn_as_entity(self.entity, self.components);
+        Ok(())
     }
 }
 
@@ -63,8 +69,9 @@ where
     I: IntoIterator + Send + Sync,
     I::Item: Bundle,
 {
-    fn write(self: Box<Self>, world: &mut World) {
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
         world.spawn_batch(self.components_iter);
+        Ok(())
     }
 }
 
@@ -73,8 +80,10 @@ pub(crate) struct Despawn {
 }
 
 impl WorldWriter for Despawn {
-    fn write(self: Box<Self>, world: &mut World) {
-        world.despawn(self.entity).unwrap();
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
+        world
+            .despawn(self.entity)
+            .map_err(|_| missing_entity_error("despawn", self.entity))
     }
 }
 
@@ -90,8 +99,11 @@ impl<T> WorldWriter for Insert<T>
 where
     T: DynamicBundle + Send + Sync + 'static,
 {
-    fn write(self: Box<Self>, world: &mut World) {
-        world.insert(self.entity, self.components).unwrap();
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
+        let entity = self.entity;
+        world
+            .insert(entity, self.components)
+            .map_err(|_| missing_entity_error("insert components into", entity))
     }
 }
 
@@ -107,8 +119,30 @@ impl<T> WorldWriter for InsertOne<T>
 where
     T: Component,
 {
-    fn write(self: Box<Self>, world: &mut World) {
-        world.insert(self.entity, (self.component,)).unwrap();
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
+        let entity = self.entity;
+        world
+            .insert(entity, (self.component,))
+            .map_err(|_| missing_entity_error("insert a component into", entity))
+    }
+}
+
+pub(crate) struct InsertDynamic {
+    entity: Entity,
+    info: TypeInfo,
+    data: Vec<u8>,
+}
+
+impl WorldWriter for InsertDynamic {
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
+        let mut builder = EntityBuilder::new();
+        // SAFE: the caller of Commands::insert_dynamic guarantees that data is a valid instance of info
+        unsafe {
+            builder.add_dynamic(self.info, &self.data)?;
+        }
+        world
+            .insert(self.entity, builder.build())
+            .map_err(|_| missing_entity_error("insert a component into", self.entity))
     }
 }
 
@@ -124,13 +158,22 @@ impl<T> WorldWriter for RemoveOne<T>
 where
     T: Component,
 {
-    fn write(self: Box<Self>, world: &mut World) {
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
         if world.get::<T>(self.entity).is_ok() {
-            world.remove_one::<T>(self.entity).unwrap();
+            world.remove_one::<T>(self.entity)?;
         }
+        Ok(())
     }
 }
 
+/// The error of a command that targets an entity that doesn't exist, ex: because it was despawned earlier
+pub fn missing_entity_error(action: &str, entity: Entity) -> BevyError {
+    BevyError::new(format!(
+        "Cannot {} entity {:?} because it does not exist",
+        action, entity
+    ))
+}
+
 pub trait ResourcesWriter: Send + Sync {
     fn write(self: Box<Self>, resources: &mut Resources);
 }
@@ -285,6 +328,21 @@ impl Commands {
         self.write_world(InsertOne { entity, component })
     }
 
+    /// Inserts a component whose type is only known at runtime (see [DynamicComponents](crate::DynamicComponents)).
+    /// If the command is never applied, or fails because `data` doesn't have the size of the type, the value is leaked
+    /// instead of dropped.
+    ///
+    /// # Safety
+    /// `data` must be a valid instance of the component type described by `info`
+    pub unsafe fn insert_dynamic(
+        &mut self,
+        entity: Entity,
+        info: TypeInfo,
+        data: Vec<u8>,
+    ) -> &mut Self {
+        self.write_world(InsertDynamic { entity, info, data })
+    }
+
     pub fn insert_resource<T: Resource>(&mut self, resource: T) -> &mut Self {
         self.write_resources(InsertResource { resource })
     }
@@ -316,12 +374,26 @@ impl Commands {
         self
     }
 
+    /// Applies the queued commands. Errors are handled by the [SystemErrorHandler](crate::SystemErrorHandler).
     pub fn apply(&self, world: &mut World, resources: &mut Resources) {
+        self.apply_for_system(world, resources, "Commands");
+    }
+
+    /// Applies the queued commands of the system named `system_name`. Errors are reported as errors of that system.
+    pub(crate) fn apply_for_system(
+        &self,
+        world: &mut World,
+        resources: &mut Resources,
+        system_name: &str,
+    ) {
         let mut commands = self.commands.lock().unwrap();
         for command in commands.commands.drain(..) {
             match command {
                 Command::WriteWorld(writer) => {
-                    writer.write(world);
+                    if let Err(error) = writer.write(world) {
+                        let system_name = Cow::Owned(system_name.to_string());
+                        handle_system_error(resources, SystemError { system_name, error });
+                    }
                 }
                 Command::WriteResources(writer) => writer.write(resources),
             }
@@ -1,8 +1,11 @@
 pub use super::Query;
 use super::TypeAccess;
 use crate::{
+    error::{handle_system_error, BevyError, BevyResult, SystemError},
     resource::{FetchResource, ResourceQuery, Resources, UnsafeClone},
-    system::{ArchetypeAccess, Commands, System, SystemId, ThreadLocalExecution},
+    system::{
+        ArchetypeAccess, Commands, System, SystemId, SystemParam, SystemState, ThreadLocalExecution,
+    },
 };
 use bevy_hecs::{Fetch, Query as HecsQuery, World};
 use std::borrow::Cow;
@@ -11,7 +14,7 @@ pub(crate) struct SystemFn<State, F, ThreadLocalF, Init, SetArchetypeAccess>
 where
     F: FnMut(&World, &Resources, &ArchetypeAccess, &mut State) + Send + Sync,
     ThreadLocalF: FnMut(&mut World, &mut Resources, &mut State) + Send + Sync,
-    Init: FnMut(&mut Resources) + Send + Sync,
+    Init: FnMut(&mut Resources, &mut State) + Send + Sync,
     SetArchetypeAccess: FnMut(&World, &mut ArchetypeAccess, &mut State) + Send + Sync,
     State: Send + Sync,
 {
@@ -32,7 +35,7 @@ impl<State, F, ThreadLocalF, Init, SetArchetypeAccess> System
 where
     F: FnMut(&World, &Resources, &ArchetypeAccess, &mut State) + Send + Sync,
     ThreadLocalF: FnMut(&mut World, &mut Resources, &mut State) + Send + Sync,
-    Init: FnMut(&mut Resources) + Send + Sync,
+    Init: FnMut(&mut Resources, &mut State) + Send + Sync,
     SetArchetypeAccess: FnMut(&World, &mut ArchetypeAccess, &mut State) + Send + Sync,
     State: Send + Sync,
 {
@@ -66,7 +69,7 @@ where
     }
 
     fn initialize(&mut self, resources: &mut Resources) {
-        (self.init_func)(resources);
+        (self.init_func)(resources, &mut self.state);
     }
 
     fn id(&self) -> SystemId {
@@ -74,6 +77,16 @@ where
     }
 }
 
+/// Calls its function when dropped. Systems release the resources they borrowed with it, so the resources are released
+/// even if the system panics.
+struct ReleaseOnDrop<F: FnMut()>(F);
+
+impl<F: FnMut()> Drop for ReleaseOnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)();
+    }
+}
+
 /// Converts `Self` into a For-Each system
 pub trait IntoForEachSystem<CommandBuffer, R, C> {
     fn system(self) -> Box<dyn System>;
@@ -98,25 +111,26 @@ macro_rules! impl_into_foreach_system {
             #[allow(unused_unsafe)]
             fn system(mut self) -> Box<dyn System> {
                 let id = SystemId::new();
+                let name: Cow<'static, str> = core::any::type_name::<Self>().into();
                 Box::new(SystemFn {
                     state: Commands::default(),
                     thread_local_execution: ThreadLocalExecution::NextFlush,
-                    name: core::any::type_name::<Self>().into(),
+                    name: name.clone(),
                     id,
                     func: move |world, resources, _archetype_access, state| {
                         <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::borrow(&resources);
+                        let _release = ReleaseOnDrop(|| <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::release(&resources));
                         {
                             let ($($resource,)*) = resources.query_system::<($($resource,)*)>(id);
                             for ($($component,)*) in world.query::<($($component,)*)>().iter() {
                                 fn_call!(self, ($($commands, state)*), ($($resource),*), ($($component),*))
                             }
                         }
-                        <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::release(&resources);
                     },
                     thread_local_func: move |world, resources, state| {
-                        state.apply(world, resources);
+                        state.apply_for_system(world, resources, &name);
                     },
-                    init_func: move |resources| {
+                    init_func: move |resources, _state| {
                         <($($resource,)*)>::initialize(resources, Some(id));
                     },
                     resource_access: <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::access(),
@@ -131,88 +145,137 @@ macro_rules! impl_into_foreach_system {
     };
 }
 
-struct QuerySystemState {
-    archetype_accesses: Vec<ArchetypeAccess>,
-    commands: Commands,
-}
-
-/// Converts `Self` into a Query System
-pub trait IntoQuerySystem<Commands, R, Q> {
+/// Converts `Self` into a Query System. Every parameter of the system must implement [SystemParam], ex: [Query],
+/// [Res](crate::Res), [ResMut](crate::ResMut), [Local](crate::Local), [Commands], or a struct deriving `SystemParam`.
+///
+/// Closures are supported too. State moved into a closure is owned by the system, like a [Local](crate::Local), which
+/// lets functions return systems configured by their arguments:
+///
+/// ```ignore
+/// fn despawn_after<T: Component>(seconds: f32) -> impl FnMut(Commands, Res<Time>, Query<(Entity, &T)>) {
+///     let mut elapsed = 0.0;
+///     move |mut commands, time, mut query| { /* ... */ }
+/// }
+///
+/// app.add_system(despawn_after::<Bullet>(2.0).system());
+/// ```
+pub trait IntoQuerySystem<Params> {
     fn system(self) -> Box<dyn System>;
 }
 
 macro_rules! impl_into_query_system {
-    (($($commands: ident)*), ($($resource: ident),*), ($($query: ident),*)) => {
-        impl<Func, $($resource,)* $($query,)*> IntoQuerySystem<($($commands,)*), ($($resource,)*), ($($query,)*)> for Func where
-            Func:
-                FnMut($($commands,)* $($resource,)* $(Query<$query>,)*) +
-                FnMut(
-                    $($commands,)*
-                    $(<<$resource as ResourceQuery>::Fetch as FetchResource>::Item,)*
-                    $(Query<$query>,)*) +
-                Send + Sync +'static,
-            $($query: HecsQuery,)*
-            $($resource: ResourceQuery,)*
+    ($($param: ident),*) => {
+        impl<Func, $($param: SystemParam),*> IntoQuerySystem<($($param,)*)> for Func
+        where
+            Func: FnMut($($param),*) + Send + Sync + 'static,
         {
             #[allow(non_snake_case)]
             #[allow(unused_variables)]
             #[allow(unused_unsafe)]
-            #[allow(unused_assignments)]
             #[allow(unused_mut)]
+            #[allow(irrefutable_let_patterns)]
             fn system(mut self) -> Box<dyn System> {
-                let id = SystemId::new();
-                $(let $query = ArchetypeAccess::default();)*
-                Box::new(SystemFn {
-                    state: QuerySystemState {
-                        archetype_accesses: vec![
-                            $($query,)*
-                        ],
-                        commands: Commands::default(),
-                    },
-                    thread_local_execution: ThreadLocalExecution::NextFlush,
-                    id,
-                    name: core::any::type_name::<Self>().into(),
-                    func: move |world, resources, archetype_access, state| {
-                        <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::borrow(&resources);
-                        {
-                            let ($($resource,)*) = resources.query_system::<($($resource,)*)>(id);
-                            let mut i = 0;
-                            $(
-                                let $query = Query::<$query>::new(world, &state.archetype_accesses[i]);
-                                i += 1;
-                            )*
-
-                            let commands = &state.commands;
-                            fn_call!(self, ($($commands, commands)*), ($($resource),*), ($($query),*))
+                let name: Cow<'static, str> = core::any::type_name::<Self>().into();
+                let mut state = SystemState::new(SystemId::new());
+                $(<$param as SystemParam>::init(&mut state);)*
+                query_system(name.clone(), state, move |world, resources, state| {
+                    unsafe {
+                        if let ($(Some($param),)*) = ($(<$param as SystemParam>::get_param(state, world, resources),)*) {
+                            self($($param),*);
                         }
-                        <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::release(&resources);
-                    },
-                    thread_local_func: move |world, resources, state| {
-                        state.commands.apply(world, resources);
-                    },
-                    init_func: move |resources| {
-                        <($($resource,)*)>::initialize(resources, Some(id));
-                    },
-                    resource_access: <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::access(),
-                    archetype_access: ArchetypeAccess::default(),
-                    set_archetype_access: |world, archetype_access, state| {
-                        archetype_access.clear();
-                        let mut i = 0;
-                        let mut access: &mut ArchetypeAccess;
-                        $(
-                            access = &mut state.archetype_accesses[i];
-                            access.clear();
-                            access.set_access_for_query::<$query>(world);
-                            archetype_access.union(access);
-                            i += 1;
-                         )*
-                    },
+                    }
+                    Ok(())
+                })
+            }
+        }
+
+        /// Systems can return a [BevyResult]. Errors are handled by the [SystemErrorHandler](crate::SystemErrorHandler)
+        impl<Func, $($param: SystemParam),*> IntoQuerySystem<(BevyError, ($($param,)*))> for Func
+        where
+            Func: FnMut($($param),*) -> BevyResult + Send + Sync + 'static,
+        {
+            #[allow(non_snake_case)]
+            #[allow(unused_variables)]
+            #[allow(unused_unsafe)]
+            #[allow(unused_mut)]
+            #[allow(irrefutable_let_patterns)]
+            fn system(mut self) -> Box<dyn System> {
+                let name: Cow<'static, str> = core::any::type_name::<Self>().into();
+                let mut state = SystemState::new(SystemId::new());
+                $(<$param as SystemParam>::init(&mut state);)*
+                query_system(name.clone(), state, move |world, resources, state| {
+                    unsafe {
+                        if let ($(Some($param),)*) = ($(<$param as SystemParam>::get_param(state, world, resources),)*) {
+                            return self($($param),*);
+                        }
+                    }
+                    Ok(())
                 })
             }
         }
     };
 }
 
+/// Builds a Query System from `run`, which gets the system's parameters from its [SystemState] and calls the system
+fn query_system(
+    name: Cow<'static, str>,
+    state: SystemState,
+    mut run: impl FnMut(&World, &Resources, &SystemState) -> BevyResult + Send + Sync + 'static,
+) -> Box<dyn System> {
+    let id = state.id;
+    let resource_access = state.resource_access.clone();
+    Box::new(SystemFn {
+        state,
+        thread_local_execution: ThreadLocalExecution::NextFlush,
+        id,
+        name: name.clone(),
+        func: move |world, resources, _archetype_access, state| {
+            let result = {
+                let state = &*state;
+                state.borrow_resources(resources);
+                let _release = ReleaseOnDrop(|| state.release_resources(resources));
+                state.reset_indices();
+                run(world, resources, state)
+            };
+            if let Err(error) = result {
+                state.error = Some(error);
+            }
+        },
+        thread_local_func: move |world, resources, state| {
+            state.commands.apply_for_system(world, resources, &name);
+            if let Some(error) = state.error.take() {
+                let system_name = name.clone();
+                handle_system_error(resources, SystemError { system_name, error });
+            }
+        },
+        init_func: move |resources, state| {
+            state.initialize_resources(resources);
+        },
+        resource_access,
+        archetype_access: ArchetypeAccess::default(),
+        set_archetype_access: |world, archetype_access, state| {
+            state.update_archetype_access(world, archetype_access);
+        },
+    })
+}
+
+macro_rules! impl_into_query_systems {
+    ($($param: ident),*) => {
+        #[rustfmt::skip]
+        impl_into_query_system!($($param),*);
+        impl_into_query_systems_smaller!($($param),*);
+    };
+}
+
+macro_rules! impl_into_query_systems_smaller {
+    () => {};
+    ($first: ident $(, $param: ident)*) => {
+        impl_into_query_systems!($($param),*);
+    };
+}
+
+impl_into_query_systems!(P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15);
+
 macro_rules! fn_call {
     ($self:ident, ($($commands: ident, $commands_var: ident)*), ($($resource: ident),*), ($($a: ident),*)) => {
         unsafe { $self($($commands_var.clone(),)* $($resource.unsafe_clone(),)* $($a,)*) }
@@ -222,15 +285,6 @@ macro_rules! fn_call {
     };
 }
 
-macro_rules! impl_into_query_systems {
-    (($($resource: ident,)*), ($($query: ident),*)) => {
-        #[rustfmt::skip]
-        impl_into_query_system!((), ($($resource),*), ($($query),*));
-        #[rustfmt::skip]
-        impl_into_query_system!((Commands), ($($resource),*), ($($query),*));
-    }
-}
-
 macro_rules! impl_into_foreach_systems {
     (($($resource: ident,)*), ($($component: ident),*)) => {
         #[rustfmt::skip]
@@ -258,21 +312,6 @@ macro_rules! impl_into_systems {
         impl_into_foreach_systems!(($($resource,)*), (A,B,C,D,E,F,G));
         #[rustfmt::skip]
         impl_into_foreach_systems!(($($resource,)*), (A,B,C,D,E,F,G,H));
-
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), ());
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A));
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A,B));
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A,B,C));
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A,B,C,D));
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A,B,C,D,E));
-        #[rustfmt::skip]
-        impl_into_query_systems!(($($resource,)*), (A,B,C,D,E,F));
     };
 }
 
@@ -315,7 +354,7 @@ where
                 self.run(world, resources);
             },
             func: |_, _, _, _| {},
-            init_func: |_| {},
+            init_func: |_, _| {},
             set_archetype_access: |_, _, _| {},
             thread_local_execution: ThreadLocalExecution::Immediate,
             name: core::any::type_name::<F>().into(),
@@ -344,10 +383,11 @@ where
 mod tests {
     use super::{IntoQuerySystem, Query};
     use crate::{
-        resource::{ResMut, Resources},
+        resource::{Local, ResMut, Resources},
         schedule::Schedule,
+        system::{Commands, Single, SystemParam},
     };
-    use bevy_hecs::{Entity, With, World};
+    use bevy_hecs::{Component, Entity, With, World};
 
     struct A;
     struct B;
@@ -415,4 +455,161 @@ mod tests {
 
         assert!(*resources.get::<bool>().unwrap(), "system ran");
     }
+
+    #[test]
+    fn local_state_persists_across_schedule_changes() {
+        #[derive(Default)]
+        struct Counter(u32);
+
+        fn count_system(mut counter: Local<Counter>, mut result: ResMut<u32>) {
+            counter.0 += 1;
+            *result = counter.0;
+        }
+
+        fn empty_system(_result: ResMut<u32>) {}
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", count_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 2);
+
+        // adding a system re-initializes the schedule, which should not reset local state
+        schedule.add_system_to_stage("update", empty_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 3);
+    }
+
+    #[test]
+    fn derived_system_params() {
+        #[derive(SystemParam)]
+        #[as_crate(bevy_ecs)]
+        struct Params<'a> {
+            commands: Commands,
+            a_query: Query<'a, &'static A>,
+            count: ResMut<'a, u32>,
+        }
+
+        fn param_system(mut params: Params) {
+            *params.count = params.a_query.iter().iter().count() as u32;
+            params.commands.spawn((B,));
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+        world.spawn((A,));
+        world.spawn((A, C));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", param_system.system());
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(*resources.get::<u32>().unwrap(), 2);
+        assert_eq!(world.query::<&B>().iter().count(), 1);
+    }
+
+    #[test]
+    fn closure_systems_capture_configuration() {
+        fn add_system(amount: u32) -> impl FnMut(ResMut<u32>) + Send + Sync + 'static {
+            let mut runs = 0;
+            move |mut total: ResMut<u32>| {
+                runs += 1;
+                *total += amount * runs;
+            }
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", add_system(1).system());
+        schedule.add_system_to_stage("update", add_system(10).system());
+
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 11);
+
+        // each closure keeps its own captured state
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 33);
+    }
+
+    #[test]
+    fn generic_systems_have_separate_state() {
+        fn count_system<T: Component>(
+            mut runs: Local<u32>,
+            mut query: Query<&T>,
+            mut counts: ResMut<Vec<(u32, usize)>>,
+        ) {
+            *runs += 1;
+            counts.push((*runs, query.iter().iter().count()));
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<(u32, usize)>::new());
+        world.spawn((A,));
+        world.spawn((A, B));
+
+        let count_a = count_system::<A>.system();
+        let count_b = count_system::<B>.system();
+        assert_ne!(count_a.id(), count_b.id());
+        assert_ne!(count_a.name(), count_b.name());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", count_a);
+        schedule.add_system_to_stage("update", count_b);
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<Vec<(u32, usize)>>().unwrap(),
+            vec![(1, 2), (1, 1), (2, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn single_only_runs_with_one_match() {
+        fn single_system(single: Single<With<A, &B>>, mut runs: ResMut<u32>) {
+            assert!(single.get::<B>().is_ok());
+            *runs += 1;
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", single_system.system());
+
+        // no matching entities
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 0);
+
+        let entity = world.spawn((A, B));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 1);
+
+        // more than one matching entity
+        world.spawn((A, B));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 1);
+
+        world.despawn(entity).unwrap();
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 2);
+    }
 }
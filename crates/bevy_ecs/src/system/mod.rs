@@ -1,8 +1,9 @@
 mod commands;
 mod into_system;
 #[cfg(feature = "profiler")]
-mod profiler;
+pub mod profiler;
 mod system;
+mod system_param;
 mod query;
 
 pub use commands::*;
@@ -10,4 +11,5 @@ pub use into_system::*;
 #[cfg(feature = "profiler")]
 pub use profiler::*;
 pub use system::*;
+pub use system_param::*;
 pub use query::*;
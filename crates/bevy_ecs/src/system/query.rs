@@ -258,3 +258,43 @@ impl<Q: HecsQuery> ChunkIter<Q> {
         }
     }
 }
+
+/// Provides access to the only entity matching the query `Q`, ex: `Single<With<MainCamera, &Camera>>`. This lets
+/// singleton data live on an entity instead of in a resource. Systems with a `Single` parameter only run when exactly
+/// one entity matches.
+pub struct Single<'a, Q: HecsQuery> {
+    entity: Entity,
+    query: Query<'a, (Entity, Q)>,
+}
+
+impl<'a, Q: HecsQuery> Single<'a, Q> {
+    /// Returns the matching entity if exactly one entity matches `query`
+    pub fn new(mut query: Query<'a, (Entity, Q)>) -> Option<Self> {
+        let entity = {
+            let mut borrow = query.iter();
+            let mut entities = borrow.iter().map(|(entity, _)| entity);
+            match (entities.next(), entities.next()) {
+                (Some(entity), None) => entity,
+                _ => return None,
+            }
+        };
+
+        Some(Single { entity, query })
+    }
+
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Gets a reference to the entity's component of the given type. This will fail if the entity does not have
+    /// the given component type or if the given component type does not match this query.
+    pub fn get<T: Component>(&self) -> Result<Ref<'_, T>, QueryError> {
+        self.query.get::<T>(self.entity)
+    }
+
+    /// Gets a mutable reference to the entity's component of the given type. This will fail if the entity does not
+    /// have the given component type or if the given component type does not match this query.
+    pub fn get_mut<T: Component>(&self) -> Result<RefMut<'_, T>, QueryError> {
+        self.query.get_mut::<T>(self.entity)
+    }
+}
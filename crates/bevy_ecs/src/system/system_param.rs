@@ -0,0 +1,231 @@
+use super::{ArchetypeAccess, Commands, Query, Single, SystemId, TypeAccess};
+use crate::{
+    error::BevyError,
+    resource::{
+        FetchResource, FetchResourceLocalMut, FromResources, Local, Res, ResMut, Resource,
+        ResourceIndex, ResourceQuery, Resources,
+    },
+};
+use bevy_hecs::{Entity, Query as HecsQuery, World};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub use bevy_derive::SystemParam;
+
+/// The state of a system built from [SystemParam]s: the accesses its parameters registered and the [Commands] it
+/// shares between them
+pub struct SystemState {
+    pub(crate) id: SystemId,
+    pub(crate) commands: Commands,
+    pub(crate) resource_access: TypeAccess,
+    pub(crate) archetype_accesses: Vec<ArchetypeAccess>,
+    pub(crate) query_access_setters: Vec<fn(&World, &mut ArchetypeAccess)>,
+    pub(crate) resource_initializers: Vec<fn(&mut Resources, SystemId)>,
+    pub(crate) resource_borrows: Vec<(fn(&Resources), fn(&Resources))>,
+    pub(crate) current_query_index: AtomicUsize,
+    /// the error returned by the last run of a fallible system, handled at the next flush
+    pub(crate) error: Option<BevyError>,
+}
+
+impl SystemState {
+    pub(crate) fn new(id: SystemId) -> Self {
+        SystemState {
+            id,
+            commands: Commands::default(),
+            resource_access: TypeAccess::default(),
+            archetype_accesses: Vec::new(),
+            query_access_setters: Vec::new(),
+            resource_initializers: Vec::new(),
+            resource_borrows: Vec::new(),
+            current_query_index: AtomicUsize::new(0),
+            error: None,
+        }
+    }
+
+    /// The id of the system this state belongs to
+    pub fn id(&self) -> SystemId {
+        self.id
+    }
+
+    /// Registers a query. Its [ArchetypeAccess] is updated whenever the world's archetypes change.
+    pub fn add_query<Q: HecsQuery>(&mut self) {
+        self.archetype_accesses.push(ArchetypeAccess::default());
+        self.query_access_setters
+            .push(|world, access| access.set_access_for_query::<Q>(world));
+    }
+
+    /// Registers the resource accesses of the given [ResourceQuery]
+    pub fn add_resource_query<R: ResourceQuery>(&mut self) {
+        self.resource_access
+            .union(&<R::Fetch as FetchResource>::access());
+        self.resource_initializers
+            .push(|resources, id| R::initialize(resources, Some(id)));
+        self.resource_borrows.push((
+            <R::Fetch as FetchResource>::borrow,
+            <R::Fetch as FetchResource>::release,
+        ));
+    }
+
+    pub(crate) fn initialize_resources(&self, resources: &mut Resources) {
+        for initialize in self.resource_initializers.iter() {
+            initialize(resources, self.id);
+        }
+    }
+
+    pub(crate) fn update_archetype_access(
+        &mut self,
+        world: &World,
+        archetype_access: &mut ArchetypeAccess,
+    ) {
+        archetype_access.clear();
+        for (access, set_access) in self
+            .archetype_accesses
+            .iter_mut()
+            .zip(self.query_access_setters.iter())
+        {
+            access.clear();
+            set_access(world, access);
+            archetype_access.union(access);
+        }
+    }
+
+    pub(crate) fn borrow_resources(&self, resources: &Resources) {
+        for (borrow, _release) in self.resource_borrows.iter() {
+            borrow(resources);
+        }
+    }
+
+    pub(crate) fn release_resources(&self, resources: &Resources) {
+        for (_borrow, release) in self.resource_borrows.iter() {
+            release(resources);
+        }
+    }
+
+    pub(crate) fn reset_indices(&self) {
+        self.current_query_index.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A parameter of a query system. Implement this with `#[derive(SystemParam)]` to group several parameters into a
+/// struct that can be reused across systems:
+///
+/// ```ignore
+/// #[derive(SystemParam)]
+/// struct PlayerParams<'a> {
+///     commands: Commands,
+///     players: Query<'a, (Entity, &'static Player)>,
+///     time: Res<'a, Time>,
+/// }
+///
+/// fn player_system(mut params: PlayerParams) { /* ... */ }
+/// ```
+pub trait SystemParam: Sized {
+    /// Registers the parameter's resource and archetype accesses with the system
+    fn init(system_state: &mut SystemState);
+
+    /// Fetches the parameter. Parameters are fetched in the same order they were initialized in. Systems don't run when
+    /// one of their parameters returns `None`.
+    ///
+    /// # Safety
+    /// The caller must ensure the accesses registered in `init` are respected for as long as the parameter lives
+    unsafe fn get_param(
+        system_state: &SystemState,
+        world: &World,
+        resources: &Resources,
+    ) -> Option<Self>;
+}
+
+impl<'a, Q: HecsQuery> SystemParam for Query<'a, Q> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_query::<Q>();
+    }
+
+    unsafe fn get_param(
+        system_state: &SystemState,
+        world: &World,
+        _resources: &Resources,
+    ) -> Option<Self> {
+        let query_index = system_state
+            .current_query_index
+            .fetch_add(1, Ordering::Relaxed);
+        // the system outlives the parameters it passes to its function
+        let world: &'a World = &*(world as *const World);
+        let archetype_access: &'a ArchetypeAccess =
+            &*(&system_state.archetype_accesses[query_index] as *const ArchetypeAccess);
+        Some(Query::new(world, archetype_access))
+    }
+}
+
+impl<'a, Q: HecsQuery> SystemParam for Single<'a, Q> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_query::<(Entity, Q)>();
+    }
+
+    unsafe fn get_param(
+        system_state: &SystemState,
+        world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        Single::new(Query::get_param(system_state, world, resources)?)
+    }
+}
+
+impl SystemParam for Commands {
+    fn init(_system_state: &mut SystemState) {}
+
+    unsafe fn get_param(
+        system_state: &SystemState,
+        _world: &World,
+        _resources: &Resources,
+    ) -> Option<Self> {
+        Some(system_state.commands.clone())
+    }
+}
+
+impl<'a, T: Resource> SystemParam for Res<'a, T> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_resource_query::<Res<'static, T>>();
+    }
+
+    unsafe fn get_param(
+        _system_state: &SystemState,
+        _world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        Some(Res::new(
+            resources.get_unsafe_ref::<T>(ResourceIndex::Global),
+        ))
+    }
+}
+
+impl<'a, T: Resource> SystemParam for ResMut<'a, T> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_resource_query::<ResMut<'static, T>>();
+    }
+
+    unsafe fn get_param(
+        _system_state: &SystemState,
+        _world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        Some(ResMut::new(
+            resources.get_unsafe_ref::<T>(ResourceIndex::Global),
+        ))
+    }
+}
+
+impl<'a, T: Resource + FromResources> SystemParam for Local<'a, T> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_resource_query::<Local<'static, T>>();
+    }
+
+    unsafe fn get_param(
+        system_state: &SystemState,
+        _world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        Some(<FetchResourceLocalMut<T> as FetchResource<'a>>::get(
+            &*(resources as *const Resources),
+            Some(system_state.id),
+        ))
+    }
+}
@@ -0,0 +1,106 @@
+use bevy_hecs::{ComponentId, TypeInfo};
+use std::{alloc::Layout, collections::HashMap, fmt};
+
+/// Component types that are defined at runtime (ex: by a scripting language) instead of by a Rust type
+///
+/// Values of these components are inserted as raw bytes with [Commands::insert_dynamic](crate::Commands::insert_dynamic)
+/// and can be read back with `World::get_dynamic` and `World::query_dynamic`.
+#[derive(Default)]
+pub struct DynamicComponents {
+    types: HashMap<String, TypeInfo>,
+    next_id: u64,
+}
+
+impl DynamicComponents {
+    /// Registers a component type named `name`. If a type with the same name was already registered, its
+    /// existing [TypeInfo] is returned instead, as long as it has the same layout.
+    ///
+    /// # Safety
+    /// `drop` must be safe to call on every value inserted with the returned [TypeInfo]
+    pub unsafe fn register(
+        &mut self,
+        name: &str,
+        layout: Layout,
+        drop: unsafe fn(*mut u8),
+    ) -> Result<TypeInfo, DynamicLayoutConflict> {
+        if let Some(info) = self.types.get(name) {
+            return if info.layout() == layout {
+                Ok(*info)
+            } else {
+                Err(DynamicLayoutConflict {
+                    name: name.to_string(),
+                    registered: info.layout(),
+                    requested: layout,
+                })
+            };
+        }
+
+        let info = TypeInfo::of_external(self.next_id, layout, drop);
+        self.next_id += 1;
+        self.types.insert(name.to_string(), info);
+        Ok(info)
+    }
+
+    pub fn get(&self, name: &str) -> Option<TypeInfo> {
+        self.types.get(name).cloned()
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<ComponentId> {
+        self.types.get(name).map(|info| info.id())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TypeInfo)> {
+        self.types.iter().map(|(name, info)| (name.as_str(), info))
+    }
+}
+
+/// Error returned when a dynamic component type is registered again with a different layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicLayoutConflict {
+    pub name: String,
+    pub registered: Layout,
+    pub requested: Layout,
+}
+
+impl fmt::Display for DynamicLayoutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dynamic component {} was registered with {:?} and can't be registered again with {:?}",
+            self.name, self.registered, self.requested
+        )
+    }
+}
+
+impl std::error::Error for DynamicLayoutConflict {}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamicComponents, DynamicLayoutConflict};
+    use std::alloc::Layout;
+
+    unsafe fn drop_nothing(_: *mut u8) {}
+
+    #[test]
+    fn register() {
+        let mut components = DynamicComponents::default();
+        let layout = Layout::from_size_align(4, 4).unwrap();
+        let position = unsafe { components.register("position", layout, drop_nothing) }.unwrap();
+        let health = unsafe { components.register("health", layout, drop_nothing) }.unwrap();
+        assert_ne!(position.id(), health.id());
+
+        let again = unsafe { components.register("position", layout, drop_nothing) }.unwrap();
+        assert_eq!(again.id(), position.id());
+
+        let larger = Layout::from_size_align(8, 4).unwrap();
+        assert_eq!(
+            unsafe { components.register("position", larger, drop_nothing) }.err(),
+            Some(DynamicLayoutConflict {
+                name: "position".to_string(),
+                registered: layout,
+                requested: larger,
+            })
+        );
+        assert_eq!(components.get_id("position"), Some(position.id()));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{IntoThreadLocalSystem, Resources, System};
+use bevy_hecs::{Component, Entity, Mut, World};
+
+/// Implemented by resources and components that store references to other entities, ex: caches keyed by [Entity].
+/// Systems created by [remove_despawned_resource_references_system] and
+/// [remove_despawned_component_references_system] use it to remove references to entities after they are despawned,
+/// so stale entities aren't used by mistake.
+pub trait EntityReferences: Send + Sync + 'static {
+    /// Returns true if any of the referenced entities is not alive
+    fn references_despawned(&self, is_alive: &dyn Fn(Entity) -> bool) -> bool;
+    /// Removes (or clears) the references to entities that are not alive
+    fn remove_despawned(&mut self, is_alive: &dyn Fn(Entity) -> bool);
+}
+
+/// Returns true if entities have been despawned since `last_despawned_count` was recorded, and records the current
+/// count
+fn entities_despawned(world: &World, last_despawned_count: &mut Option<u64>) -> bool {
+    let despawned_count = world.despawned_count();
+    let despawned = *last_despawned_count != Some(despawned_count);
+    *last_despawned_count = Some(despawned_count);
+    despawned
+}
+
+/// Creates a system that removes references to despawned entities from the `T` resource. The system only does work
+/// when entities have been despawned since it last ran, so despawns are caught even if they happen after it runs
+/// in a frame.
+pub fn remove_despawned_resource_references_system<T>() -> Box<dyn System>
+where
+    T: EntityReferences,
+{
+    let mut last_despawned_count = None;
+    (move |world: &mut World, resources: &mut Resources| {
+        if !entities_despawned(world, &mut last_despawned_count) {
+            return;
+        }
+
+        if let Some(mut resource) = resources.get_mut::<T>() {
+            let is_alive = |entity| world.contains(entity);
+            if resource.references_despawned(&is_alive) {
+                resource.remove_despawned(&is_alive);
+            }
+        }
+    })
+    .thread_local_system()
+}
+
+/// Creates a system that removes references to despawned entities from every `T` component. Like
+/// [remove_despawned_resource_references_system], it only does work when entities have been despawned. Only
+/// components that referenced despawned entities are marked as mutated.
+pub fn remove_despawned_component_references_system<T>() -> Box<dyn System>
+where
+    T: EntityReferences + Component,
+{
+    let mut last_despawned_count = None;
+    (move |world: &mut World, _resources: &mut Resources| {
+        if !entities_despawned(world, &mut last_despawned_count) {
+            return;
+        }
+
+        let is_alive = |entity| world.contains(entity);
+        for mut component in &mut world.query::<Mut<T>>().iter() {
+            if component.references_despawned(&is_alive) {
+                component.remove_despawned(&is_alive);
+            }
+        }
+    })
+    .thread_local_system()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        remove_despawned_component_references_system, remove_despawned_resource_references_system,
+        EntityReferences,
+    };
+    use crate::{Resources, Schedule, World};
+    use bevy_hecs::Entity;
+
+    #[derive(Default)]
+    struct Targets(Vec<Entity>);
+
+    impl EntityReferences for Targets {
+        fn references_despawned(&self, is_alive: &dyn Fn(Entity) -> bool) -> bool {
+            self.0.iter().any(|entity| !is_alive(*entity))
+        }
+
+        fn remove_despawned(&mut self, is_alive: &dyn Fn(Entity) -> bool) {
+            self.0.retain(|entity| is_alive(*entity));
+        }
+    }
+
+    #[test]
+    fn remove_despawned_references() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let a = world.spawn((1,));
+        let b = world.spawn((2,));
+        let holder = world.spawn((Targets(vec![a, b]),));
+        resources.insert(Targets(vec![a, b]));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage(
+            "update",
+            remove_despawned_resource_references_system::<Targets>(),
+        );
+        schedule.add_system_to_stage(
+            "update",
+            remove_despawned_component_references_system::<Targets>(),
+        );
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        world.despawn(a).unwrap();
+        // trackers are cleared every frame, but the despawn is still seen by the next run
+        world.clear_trackers();
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Targets>().unwrap().0, vec![b]);
+        assert_eq!(world.get::<Targets>(holder).unwrap().0, vec![b]);
+
+        // runs without despawns don't touch references, even if they are stale
+        resources.get_mut::<Targets>().unwrap().0.push(a);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(resources.get::<Targets>().unwrap().0, vec![b, a]);
+    }
+}
@@ -0,0 +1,106 @@
+use bevy_hecs::{Changed, Component, Entity, World};
+
+/// Copies components of selected types from one [World] to another. This lets a pipelined subsystem (ex: audio or
+/// physics) run its own schedule on a separate world that mirrors the parts of the app world it cares about.
+///
+/// Entities keep their ids in the target world. Only components that were added or mutated since the source world's
+/// trackers were last cleared are copied, so [WorldExtractor::extract] should run once per frame, before the end of the
+/// source schedule. For example, from a thread local system in the last stage:
+///
+/// ```ignore
+/// struct PhysicsWorld {
+///     world: World,
+///     extractor: WorldExtractor,
+/// }
+///
+/// fn extract_physics_system(world: &mut World, resources: &mut Resources) {
+///     let mut physics = resources.get_mut::<PhysicsWorld>().unwrap();
+///     let PhysicsWorld { world: target, extractor } = &mut *physics;
+///     extractor.extract(world, target);
+/// }
+/// ```
+#[derive(Default)]
+pub struct WorldExtractor {
+    extractors: Vec<fn(&World, &mut World)>,
+}
+
+impl WorldExtractor {
+    /// Copies components of type `T` on each extraction
+    pub fn add_component<T: Component + Clone>(&mut self) -> &mut Self {
+        self.extractors.push(extract_component::<T>);
+        self
+    }
+
+    /// Copies the changed components of each registered type from `source` to `target`. Components removed from
+    /// `source` are removed from `target` and entities despawned in `source` are despawned in `target`.
+    pub fn extract(&self, source: &World, target: &mut World) {
+        for extract in self.extractors.iter() {
+            extract(source, target);
+        }
+    }
+}
+
+fn extract_component<T: Component + Clone>(source: &World, target: &mut World) {
+    for entity in source.removed::<T>().iter() {
+        if !source.contains(*entity) {
+            // the entity was despawned
+            let _ = target.despawn(*entity);
+        } else if target.get::<T>(*entity).is_ok() {
+            target.remove_one::<T>(*entity).unwrap();
+        }
+    }
+
+    for (entity, component) in &mut source.query::<(Entity, Changed<T>)>() {
+        let component = (*component).clone();
+        if target.contains(entity) {
+            target.insert_one(entity, component).unwrap();
+        } else {
+            target.spawn_as_entity(entity, (component,));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorldExtractor;
+    use bevy_hecs::World;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Velocity(f32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Mass(f32);
+
+    #[test]
+    fn extract_changed_components() {
+        let mut extractor = WorldExtractor::default();
+        extractor
+            .add_component::<Velocity>()
+            .add_component::<Mass>();
+        let mut source = World::new();
+        let mut target = World::new();
+
+        let a = source.spawn((Velocity(1.0), Mass(2.0)));
+        let b = source.spawn((Velocity(3.0), "not extracted"));
+        extractor.extract(&source, &mut target);
+        source.clear_trackers();
+        assert_eq!(*target.get::<Velocity>(a).unwrap(), Velocity(1.0));
+        assert_eq!(*target.get::<Mass>(a).unwrap(), Mass(2.0));
+        assert_eq!(*target.get::<Velocity>(b).unwrap(), Velocity(3.0));
+        assert!(target.get::<&str>(b).is_err());
+
+        // unchanged components aren't copied again
+        *target.get_mut::<Mass>(a).unwrap() = Mass(5.0);
+        *source.get_mut::<Velocity>(a).unwrap() = Velocity(4.0);
+        source.remove_one::<Velocity>(b).unwrap();
+        extractor.extract(&source, &mut target);
+        source.clear_trackers();
+        assert_eq!(*target.get::<Velocity>(a).unwrap(), Velocity(4.0));
+        assert_eq!(*target.get::<Mass>(a).unwrap(), Mass(5.0));
+        assert!(target.get::<Velocity>(b).is_err());
+
+        source.despawn(a).unwrap();
+        extractor.extract(&source, &mut target);
+        assert!(!target.contains(a));
+    }
+}
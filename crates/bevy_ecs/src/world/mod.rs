@@ -1,3 +1,9 @@
+mod dynamic_components;
+mod entity_references;
+mod extract;
 mod world_builder;
 
+pub use dynamic_components::*;
+pub use entity_references::*;
+pub use extract::*;
 pub use world_builder::*;
@@ -0,0 +1,115 @@
+mod converter;
+
+use bevy_app::prelude::*;
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use bevy_input::gamepad::{GamepadAxisType, GamepadButtonType, GamepadEvent, GamepadEventType};
+use converter::{convert_axis, convert_button, convert_gamepad_id};
+use crossbeam_channel::{Receiver, Sender};
+use std::{thread, time::Duration};
+
+/// How long the gamepad thread waits for new events once there are none left
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Reads gamepads with [gilrs](https://gitlab.com/gilrs-project/gilrs) and sends their input as [GamepadEvent]s
+#[derive(Default)]
+pub struct GilrsPlugin;
+
+impl Plugin for GilrsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        // gilrs contexts can't be shared between threads, so the context lives on a thread of its own
+        thread::Builder::new()
+            .name("gilrs".to_string())
+            .spawn(move || gilrs_thread(sender))
+            .expect("failed to spawn the gamepad thread");
+
+        app.add_resource(GilrsEvents { receiver })
+            .add_system_to_stage(bevy_app::stage::FIRST, gilrs_event_system.system());
+    }
+}
+
+/// The gamepad events received from the gamepad thread
+pub struct GilrsEvents {
+    receiver: Receiver<GamepadEvent>,
+}
+
+fn gilrs_thread(sender: Sender<GamepadEvent>) {
+    let mut gilrs = match gilrs::Gilrs::new() {
+        Ok(gilrs) => gilrs,
+        Err(err) => {
+            log::warn!("gamepads are unavailable: {}", err);
+            return;
+        }
+    };
+
+    // gamepads that were connected before startup don't send connection events
+    for (gamepad_id, _) in gilrs.gamepads() {
+        let event = GamepadEvent {
+            gamepad: convert_gamepad_id(gamepad_id),
+            event_type: GamepadEventType::Connected,
+        };
+        if sender.send(event).is_err() {
+            return;
+        }
+    }
+
+    loop {
+        while let Some(gilrs_event) = gilrs.next_event() {
+            for event in convert_event(&gilrs_event) {
+                // the app was dropped
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn convert_event(gilrs_event: &gilrs::Event) -> Vec<GamepadEvent> {
+    let gamepad = convert_gamepad_id(gilrs_event.id);
+    let event_types = match gilrs_event.event {
+        gilrs::EventType::Connected => vec![GamepadEventType::Connected],
+        gilrs::EventType::Disconnected => vec![GamepadEventType::Disconnected],
+        gilrs::EventType::ButtonChanged(button, value, _) => match convert_button(button) {
+            // gilrs reports analog triggers as buttons, so their values are sent as axes too
+            Some(GamepadButtonType::LeftTrigger2) => vec![
+                GamepadEventType::ButtonChanged(GamepadButtonType::LeftTrigger2, value),
+                GamepadEventType::AxisChanged(GamepadAxisType::LeftTrigger, value),
+            ],
+            Some(GamepadButtonType::RightTrigger2) => vec![
+                GamepadEventType::ButtonChanged(GamepadButtonType::RightTrigger2, value),
+                GamepadEventType::AxisChanged(GamepadAxisType::RightTrigger, value),
+            ],
+            Some(button_type) => vec![GamepadEventType::ButtonChanged(button_type, value)],
+            None => Vec::new(),
+        },
+        gilrs::EventType::AxisChanged(axis, value, _) => convert_axis(axis)
+            .map(|axis_type| GamepadEventType::AxisChanged(axis_type, value))
+            .into_iter()
+            .collect(),
+        // presses and releases are derived from ButtonChanged with the thresholds in GamepadSettings
+        gilrs::EventType::ButtonPressed(_, _)
+        | gilrs::EventType::ButtonRepeated(_, _)
+        | gilrs::EventType::ButtonReleased(_, _)
+        | gilrs::EventType::Dropped => Vec::new(),
+    };
+
+    event_types
+        .into_iter()
+        .map(|event_type| GamepadEvent {
+            gamepad,
+            event_type,
+        })
+        .collect()
+}
+
+/// Sends the gamepad events received from the gamepad thread
+pub fn gilrs_event_system(
+    gilrs_events: Res<GilrsEvents>,
+    mut gamepad_events: ResMut<Events<GamepadEvent>>,
+) {
+    for event in gilrs_events.receiver.try_iter() {
+        gamepad_events.send(event);
+    }
+}
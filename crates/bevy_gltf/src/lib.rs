@@ -2,8 +2,11 @@ mod loader;
 pub use loader::*;
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
-use bevy_render::mesh::Mesh;
+use bevy_asset::{AddAsset, AssetServer};
+use bevy_ecs::FromResources;
+use bevy_pbr::prelude::StandardMaterial;
+use bevy_render::{mesh::Mesh, texture::Texture};
+use bevy_scene::Scene;
 
 /// Adds support for GLTF file loading to Apps
 #[derive(Default)]
@@ -11,6 +14,15 @@ pub struct GltfPlugin;
 
 impl Plugin for GltfPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_asset_loader::<Mesh, GltfLoader>();
+        app.add_asset_channel::<Mesh>()
+            .add_asset_channel::<StandardMaterial>()
+            .add_asset_channel::<Texture>()
+            .add_asset_channel::<Scene>();
+        let loader = GltfLoader::from_resources(app.resources());
+        app.add_asset_handler(loader);
+    }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<AssetServer>();
     }
 }
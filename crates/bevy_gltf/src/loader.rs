@@ -1,28 +1,195 @@
 use bevy_render::{
+    color::Color,
+    draw::Draw,
     mesh::{Mesh, VertexAttribute, VertexAttributeValues},
     pipeline::PrimitiveTopology,
+    texture::{ImageTextureLoader, Texture},
 };
 
-use anyhow::Result;
-use bevy_asset::AssetLoader;
-use gltf::{buffer::Source, iter, mesh::Mode};
-use std::{fs, io, path::Path};
+use bevy_asset::{
+    AssetChannel, AssetIo, AssetIoError, AssetLoadError, AssetLoadRequestHandler, AssetLoader,
+    AssetResult, Handle, HandleId, LoadRequest,
+};
+use bevy_ecs::{Entity, FromResources, Resources};
+use bevy_math::Mat4;
+use bevy_pbr::prelude::{PbrComponents, StandardMaterial};
+use bevy_property::Properties;
+use bevy_scene::Scene;
+use bevy_transform::prelude::{LocalTransform, Parent, Transform};
+use crossbeam_channel::Sender;
+use gltf::{buffer::Source, material::AlphaMode, mesh::Mode};
+use std::path::Path;
 use thiserror::Error;
 
-/// Loads meshes from GLTF files into Mesh assets
-///
-/// NOTE: eventually this will loading into Scenes instead of Meshes
-#[derive(Default)]
-pub struct GltfLoader;
+/// The label of the material used by primitives that don't have a material
+pub const DEFAULT_MATERIAL_LABEL: &str = "MaterialDefault";
+
+/// Returns the label of a primitive's [Mesh] within a GLTF file
+pub fn mesh_label(mesh: usize, primitive: usize) -> String {
+    format!("Mesh{}/Primitive{}", mesh, primitive)
+}
+
+/// Returns the label of a [StandardMaterial] within a GLTF file
+pub fn material_label(material: usize) -> String {
+    format!("Material{}", material)
+}
+
+/// Returns the label of a [Texture] within a GLTF file
+pub fn texture_label(texture: usize) -> String {
+    format!("Texture{}", texture)
+}
+
+/// Returns the label of a [Scene] within a GLTF file
+pub fn scene_label(scene: usize) -> String {
+    format!("Scene{}", scene)
+}
+
+/// The assets in a GLTF file. When a GLTF file is loaded by the [AssetServer](bevy_asset::AssetServer), each asset is
+/// stored with the handle labeled by its label within the file's handle, see [Handle::labeled]. The default scene is
+/// also stored with the file's handle, so `asset_server.load::<Scene, _>("model.gltf")` can be spawned directly.
+pub struct Gltf {
+    /// The primitives of each mesh, labeled with [mesh_label]
+    pub meshes: Vec<Vec<Mesh>>,
+    /// Labeled with [material_label]
+    pub materials: Vec<StandardMaterial>,
+    /// The textures that could be decoded and their index in the file, labeled with [texture_label]
+    pub textures: Vec<(usize, Texture)>,
+    /// Labeled with [scene_label], except for the default scene which is stored with the file's handle
+    pub scenes: Vec<Scene>,
+    pub default_scene: Option<usize>,
+}
+
+/// Loads GLTF files into [Mesh], [StandardMaterial], [Texture], and [Scene] assets, see [Gltf]
+pub struct GltfLoader {
+    mesh_sender: Sender<AssetResult<Mesh>>,
+    material_sender: Sender<AssetResult<StandardMaterial>>,
+    texture_sender: Sender<AssetResult<Texture>>,
+    scene_sender: Sender<AssetResult<Scene>>,
+}
+
+impl FromResources for GltfLoader {
+    fn from_resources(resources: &Resources) -> Self {
+        GltfLoader {
+            mesh_sender: resources
+                .get::<AssetChannel<Mesh>>()
+                .unwrap()
+                .sender
+                .clone(),
+            material_sender: resources
+                .get::<AssetChannel<StandardMaterial>>()
+                .unwrap()
+                .sender
+                .clone(),
+            texture_sender: resources
+                .get::<AssetChannel<Texture>>()
+                .unwrap()
+                .sender
+                .clone(),
+            scene_sender: resources
+                .get::<AssetChannel<Scene>>()
+                .unwrap()
+                .sender
+                .clone(),
+        }
+    }
+}
+
+fn send_asset<T>(
+    sender: &Sender<AssetResult<T>>,
+    load_request: &LoadRequest,
+    handle_id: HandleId,
+    result: Result<T, AssetLoadError>,
+) {
+    sender
+        .send(AssetResult {
+            result,
+            handle: Handle::from(handle_id),
+            path: load_request.path.clone(),
+            version: load_request.version,
+        })
+        .expect("loaded asset should have been sent");
+}
+
+impl GltfLoader {
+    fn send_assets(&self, load_request: &LoadRequest, gltf: Gltf) {
+        let handle_id = load_request.handle_id;
+        for (index, texture) in gltf.textures {
+            let texture_id = handle_id.labeled(&texture_label(index));
+            send_asset(&self.texture_sender, load_request, texture_id, Ok(texture));
+        }
+
+        let default_material_id = handle_id.labeled(DEFAULT_MATERIAL_LABEL);
+        send_asset(
+            &self.material_sender,
+            load_request,
+            default_material_id,
+            Ok(StandardMaterial::default()),
+        );
+        for (index, material) in gltf.materials.into_iter().enumerate() {
+            let material_id = handle_id.labeled(&material_label(index));
+            send_asset(
+                &self.material_sender,
+                load_request,
+                material_id,
+                Ok(material),
+            );
+        }
+
+        for (mesh_index, primitives) in gltf.meshes.into_iter().enumerate() {
+            for (primitive_index, mesh) in primitives.into_iter().enumerate() {
+                // the first mesh is also stored with the file's handle, so single-mesh files can be loaded as a Mesh
+                if mesh_index == 0 && primitive_index == 0 {
+                    send_asset(&self.mesh_sender, load_request, handle_id, Ok(mesh.clone()));
+                }
+                let mesh_id = handle_id.labeled(&mesh_label(mesh_index, primitive_index));
+                send_asset(&self.mesh_sender, load_request, mesh_id, Ok(mesh));
+            }
+        }
 
-impl AssetLoader<Mesh> for GltfLoader {
-    fn from_bytes(&self, asset_path: &Path, bytes: Vec<u8>) -> Result<Mesh> {
-        let mesh = load_gltf(asset_path, bytes)?;
-        Ok(mesh)
+        // the default scene is stored with the file's handle instead of its label. it's sent last, so everything it
+        // uses has been sent when it is loaded.
+        let default_scene = gltf.default_scene.unwrap_or(0);
+        let mut main_scene = None;
+        for (index, scene) in gltf.scenes.into_iter().enumerate() {
+            if index == default_scene {
+                main_scene = Some(scene);
+            } else {
+                let scene_id = handle_id.labeled(&scene_label(index));
+                send_asset(&self.scene_sender, load_request, scene_id, Ok(scene));
+            }
+        }
+        send_asset(
+            &self.scene_sender,
+            load_request,
+            handle_id,
+            Ok(main_scene.unwrap_or_default()),
+        );
+    }
+}
+
+impl AssetLoadRequestHandler for GltfLoader {
+    fn handle_request(&self, load_request: &LoadRequest, asset_io: &dyn AssetIo) {
+        let result = asset_io
+            .load_path(&load_request.path)
+            .map_err(AssetLoadError::from)
+            .and_then(|bytes| {
+                load_gltf(&load_request.path, bytes, asset_io, load_request.handle_id)
+                    .map_err(|err| AssetLoadError::LoaderError(err.into()))
+            });
+        match result {
+            Ok(gltf) => self.send_assets(load_request, gltf),
+            // failures are reported through the file's scene, which is what the file's handle tracks
+            Err(err) => send_asset(
+                &self.scene_sender,
+                load_request,
+                load_request.handle_id,
+                Err(err),
+            ),
+        }
     }
 
     fn extensions(&self) -> &[&str] {
-        static EXTENSIONS: &[&str] = &["gltf"];
+        static EXTENSIONS: &[&str] = &["gltf", "glb"];
         EXTENSIONS
     }
 }
@@ -35,13 +202,15 @@ pub enum GltfError {
     #[error("Invalid GLTF file.")]
     Gltf(#[from] gltf::Error),
     #[error("Failed to load file.")]
-    Io(#[from] io::Error),
-    #[error("Binary buffers not supported yet.")]
-    BinaryBuffersUnsupported,
+    AssetIo(#[from] AssetIoError),
+    #[error("The GLB file's binary buffer is missing.")]
+    MissingBlob,
     #[error("Failed to decode base64 mesh data.")]
     Base64Decode(#[from] base64::DecodeError),
     #[error("Unsupported buffer format.")]
     BufferFormatUnsupported,
+    #[error("Failed to decode texture: {0}")]
+    TextureDecode(String),
 }
 
 fn get_primitive_topology(mode: Mode) -> Result<PrimitiveTopology, GltfError> {
@@ -51,91 +220,343 @@ fn get_primitive_topology(mode: Mode) -> Result<PrimitiveTopology, GltfError> {
         Mode::LineStrip => Ok(PrimitiveTopology::LineStrip),
         Mode::Triangles => Ok(PrimitiveTopology::TriangleList),
         Mode::TriangleStrip => Ok(PrimitiveTopology::TriangleStrip),
-        mode @ _ => Err(GltfError::UnsupportedPrimitive { mode }),
+        mode => Err(GltfError::UnsupportedPrimitive { mode }),
     }
 }
 
-// TODO: this should return a scene
-pub fn load_gltf(asset_path: &Path, bytes: Vec<u8>) -> Result<Mesh, GltfError> {
+/// Loads the assets in a GLTF file. `handle_id` is the handle of the file, which the handles of the labeled assets
+/// used by scenes are derived from. Buffers and images outside the file are read with `asset_io`.
+pub fn load_gltf(
+    asset_path: &Path,
+    bytes: Vec<u8>,
+    asset_io: &dyn AssetIo,
+    handle_id: HandleId,
+) -> Result<Gltf, GltfError> {
     let gltf = gltf::Gltf::from_slice(&bytes)?;
-    let buffer_data = load_buffers(gltf.buffers(), asset_path)?;
-    for scene in gltf.scenes() {
-        for node in scene.nodes() {
-            return Ok(load_node(&buffer_data, &node, 1)?);
+    let buffer_data = load_buffers(&gltf, asset_path, asset_io)?;
+
+    let mut meshes = Vec::new();
+    for mesh in gltf.meshes() {
+        let mut primitives = Vec::new();
+        for primitive in mesh.primitives() {
+            primitives.push(load_primitive(&buffer_data, &primitive)?);
+        }
+        meshes.push(primitives);
+    }
+
+    let mut textures = Vec::new();
+    for texture in gltf.textures() {
+        if let Some(loaded_texture) = load_texture(&buffer_data, &texture, asset_path, asset_io)? {
+            textures.push((texture.index(), loaded_texture));
+        }
+    }
+
+    let materials = gltf
+        .materials()
+        .map(|material| load_material(&material, &textures, handle_id))
+        .collect();
+
+    let scenes = gltf
+        .scenes()
+        .map(|scene| load_scene(&scene, handle_id))
+        .collect();
+
+    Ok(Gltf {
+        meshes,
+        materials,
+        textures,
+        scenes,
+        default_scene: gltf.default_scene().map(|scene| scene.index()),
+    })
+}
+
+fn load_primitive(buffer_data: &[Vec<u8>], primitive: &gltf::Primitive) -> Result<Mesh, GltfError> {
+    let reader = primitive.reader(|buffer| Some(&buffer_data[buffer.index()]));
+    let primitive_topology = get_primitive_topology(primitive.mode())?;
+    let mut mesh = Mesh::new(primitive_topology);
+
+    if let Some(positions) = reader.read_positions() {
+        mesh.attributes.push(VertexAttribute {
+            name: VertexAttribute::POSITION.into(),
+            values: VertexAttributeValues::Float3(positions.collect()),
+        });
+    }
+
+    if let Some(normals) = reader.read_normals() {
+        mesh.attributes.push(VertexAttribute {
+            name: VertexAttribute::NORMAL.into(),
+            values: VertexAttributeValues::Float3(normals.collect()),
+        });
+    }
+
+    if let Some(uvs) = reader.read_tex_coords(0) {
+        mesh.attributes.push(VertexAttribute {
+            name: VertexAttribute::UV.into(),
+            values: VertexAttributeValues::Float2(uvs.into_f32().collect()),
+        });
+    }
+
+    if let Some(indices) = reader.read_indices() {
+        mesh.indices = Some(indices.into_u32().collect::<Vec<u32>>());
+    }
+
+    Ok(mesh)
+}
+
+fn load_material(
+    material: &gltf::Material,
+    textures: &[(usize, Texture)],
+    handle_id: HandleId,
+) -> StandardMaterial {
+    let pbr = material.pbr_metallic_roughness();
+    let [r, g, b, a] = pbr.base_color_factor();
+    let albedo_texture = pbr.base_color_texture().and_then(|info| {
+        let index = info.texture().index();
+        if textures
+            .iter()
+            .any(|(texture_index, _)| *texture_index == index)
+        {
+            Some(Handle::from(handle_id.labeled(&texture_label(index))))
+        } else {
+            None
         }
+    });
+
+    StandardMaterial {
+        albedo: Color::rgba(r, g, b, a),
+        albedo_texture,
+        ..Default::default()
+    }
+}
+
+/// Decodes a texture. Returns `None` for image formats that aren't supported yet, so the rest of the file can still be
+/// used.
+fn load_texture(
+    buffer_data: &[Vec<u8>],
+    texture: &gltf::Texture,
+    asset_path: &Path,
+    asset_io: &dyn AssetIo,
+) -> Result<Option<Texture>, GltfError> {
+    let (bytes, is_png) = match texture.source().source() {
+        gltf::image::Source::View { view, mime_type } => {
+            let buffer = &buffer_data[view.buffer().index()];
+            let bytes = buffer[view.offset()..view.offset() + view.length()].to_vec();
+            (bytes, mime_type == "image/png")
+        }
+        gltf::image::Source::Uri { uri, mime_type } => {
+            let is_png = match mime_type {
+                Some(mime_type) => mime_type == "image/png",
+                None => uri.starts_with("data:image/png") || uri.to_lowercase().ends_with(".png"),
+            };
+            (load_uri(uri, asset_path, asset_io)?, is_png)
+        }
+    };
+
+    if !is_png {
+        log::warn!(
+            "Texture {} in {} isn't a PNG, which is the only format GLTF textures can be loaded from",
+            texture.index(),
+            asset_path.display()
+        );
+        return Ok(None);
+    }
+
+    ImageTextureLoader
+        .from_bytes(Path::new("texture.png"), bytes)
+        .map(Some)
+        .map_err(|err| GltfError::TextureDecode(err.to_string()))
+}
+
+fn load_scene(scene: &gltf::Scene, handle_id: HandleId) -> Scene {
+    let mut world_scene = Scene::default();
+    for node in scene.nodes() {
+        load_node(&node, None, handle_id, &mut world_scene);
     }
 
-    // TODO: remove this when full gltf support is added
-    panic!("no mesh found!")
+    world_scene
 }
 
-fn load_node(buffer_data: &[Vec<u8>], node: &gltf::Node, depth: i32) -> Result<Mesh, GltfError> {
+/// Adds an entity for `node` and its children to `scene`. Each primitive of the node's mesh is a child entity of the
+/// node's entity, with the components of a [PbrComponents].
+fn load_node(node: &gltf::Node, parent: Option<Entity>, handle_id: HandleId, scene: &mut Scene) {
+    let entity = Entity::new();
+    let mut components = vec![
+        Transform::default().to_dynamic(),
+        LocalTransform(Mat4::from_cols_array_2d(&node.transform().matrix())).to_dynamic(),
+    ];
+    if let Some(parent) = parent {
+        components.push(Parent(parent).to_dynamic());
+    }
+    scene.entities.push(bevy_scene::Entity {
+        entity: entity.id(),
+        components,
+    });
+
     if let Some(mesh) = node.mesh() {
         for primitive in mesh.primitives() {
-            let reader = primitive.reader(|buffer| Some(&buffer_data[buffer.index()]));
-            let primitive_topology = get_primitive_topology(primitive.mode())?;
-            let mut mesh = Mesh::new(primitive_topology);
-            reader
-                .read_positions()
-                .map(|v| VertexAttribute {
-                    name: "Vertex_Position".into(),
-                    values: VertexAttributeValues::Float3(v.collect()),
-                })
-                .map(|vertex_attribute| mesh.attributes.push(vertex_attribute));
-
-            reader
-                .read_normals()
-                .map(|v| VertexAttribute {
-                    name: "Vertex_Normal".into(),
-                    values: VertexAttributeValues::Float3(v.collect()),
-                })
-                .map(|vertex_attribute| mesh.attributes.push(vertex_attribute));
-
-            reader
-                .read_tex_coords(0)
-                .map(|v| VertexAttribute {
-                    name: "Vertex_Uv".into(),
-                    values: VertexAttributeValues::Float2(v.into_f32().collect()),
-                })
-                .map(|vertex_attribute| mesh.attributes.push(vertex_attribute));
-
-            reader.read_indices().map(|indices| {
-                mesh.indices = Some(indices.into_u32().collect::<Vec<u32>>());
-            });
+            let gltf_material = primitive.material();
+            let material_label = match gltf_material.index() {
+                Some(index) => material_label(index),
+                None => DEFAULT_MATERIAL_LABEL.to_string(),
+            };
+            let mesh: Handle<Mesh> =
+                Handle::from(handle_id.labeled(&mesh_label(mesh.index(), primitive.index())));
+            let material: Handle<StandardMaterial> =
+                Handle::from(handle_id.labeled(&material_label));
+            let pbr_components = PbrComponents::default();
+            let draw = Draw {
+                is_transparent: gltf_material.alpha_mode() == AlphaMode::Blend,
+                ..Default::default()
+            };
 
-            return Ok(mesh);
+            scene.entities.push(bevy_scene::Entity {
+                entity: Entity::new().id(),
+                components: vec![
+                    mesh.to_dynamic(),
+                    material.to_dynamic(),
+                    pbr_components.main_pass.to_dynamic(),
+                    draw.to_dynamic(),
+                    pbr_components.render_pipelines.to_dynamic(),
+                    Transform::default().to_dynamic(),
+                    LocalTransform(Mat4::identity()).to_dynamic(),
+                    Parent(entity).to_dynamic(),
+                ],
+            });
         }
     }
 
     for child in node.children() {
-        return Ok(load_node(buffer_data, &child, depth + 1)?);
+        load_node(&child, Some(entity), handle_id, scene);
     }
-
-    panic!("failed to find mesh")
 }
 
-fn load_buffers(buffers: iter::Buffers, asset_path: &Path) -> Result<Vec<Vec<u8>>, GltfError> {
-    const OCTET_STREAM_URI: &str = "data:application/octet-stream;base64,";
+/// Reads the data of a uri, which is either a base64 data uri or a path relative to the GLTF file
+fn load_uri(uri: &str, asset_path: &Path, asset_io: &dyn AssetIo) -> Result<Vec<u8>, GltfError> {
+    if uri.starts_with("data:") {
+        match uri.find(";base64,") {
+            Some(index) => Ok(base64::decode(&uri[index + ";base64,".len()..])?),
+            None => Err(GltfError::BufferFormatUnsupported),
+        }
+    } else {
+        let path = asset_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(uri);
+        Ok(asset_io.load_path(&path)?)
+    }
+}
 
+fn load_buffers(
+    gltf: &gltf::Gltf,
+    asset_path: &Path,
+    asset_io: &dyn AssetIo,
+) -> Result<Vec<Vec<u8>>, GltfError> {
     let mut buffer_data = Vec::new();
-    for buffer in buffers {
+    for buffer in gltf.buffers() {
         match buffer.source() {
-            Source::Uri(uri) => {
-                if uri.starts_with("data:") {
-                    if uri.starts_with(OCTET_STREAM_URI) {
-                        buffer_data.push(base64::decode(&uri[OCTET_STREAM_URI.len()..])?);
-                    } else {
-                        return Err(GltfError::BufferFormatUnsupported);
-                    }
-                } else {
-                    let buffer_path = asset_path.parent().unwrap().join(uri);
-                    let buffer_bytes = fs::read(buffer_path)?;
-                    buffer_data.push(buffer_bytes);
-                }
-            }
-            Source::Bin => return Err(GltfError::BinaryBuffersUnsupported),
+            Source::Uri(uri) => buffer_data.push(load_uri(uri, asset_path, asset_io)?),
+            Source::Bin => buffer_data.push(gltf.blob.clone().ok_or(GltfError::MissingBlob)?),
         }
     }
 
     Ok(buffer_data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{load_gltf, mesh_label};
+    use bevy_asset::{Assets, Handle, HandleId, MemoryAssetIo};
+    use bevy_ecs::{Resources, World};
+    use bevy_pbr::prelude::StandardMaterial;
+    use bevy_render::render_graph::base::MainPass;
+    use bevy_render::{color::Color, draw::Draw, mesh::Mesh, pipeline::RenderPipelines};
+    use bevy_scene::{Scene, SceneSpawner};
+    use bevy_transform::prelude::{LocalTransform, Parent, Transform};
+    use bevy_type_registry::TypeRegistry;
+    use std::path::Path;
+
+    fn triangle_gltf() -> String {
+        let positions: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+            .iter()
+            .flat_map(|value| value.to_le_bytes().to_vec())
+            .collect();
+        format!(
+            r#"{{
+                "asset": {{ "version": "2.0" }},
+                "scene": 0,
+                "scenes": [{{ "nodes": [0] }}],
+                "nodes": [
+                    {{ "children": [1], "translation": [1.0, 2.0, 3.0] }},
+                    {{ "mesh": 0, "translation": [0.0, 0.0, 1.0] }}
+                ],
+                "materials": [{{ "pbrMetallicRoughness": {{ "baseColorFactor": [1.0, 0.0, 0.0, 1.0] }} }}],
+                "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "material": 0 }}] }}],
+                "accessors": [{{
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+                }}],
+                "bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+                "buffers": [{{
+                    "byteLength": 36,
+                    "uri": "data:application/octet-stream;base64,{}"
+                }}]
+            }}"#,
+            base64::encode(&positions)
+        )
+    }
+
+    #[test]
+    fn load_gltf_scene() {
+        let handle_id = HandleId::new();
+        let gltf = load_gltf(
+            Path::new("models/triangle.gltf"),
+            triangle_gltf().into_bytes(),
+            &MemoryAssetIo::default(),
+            handle_id,
+        )
+        .unwrap();
+        assert_eq!(gltf.meshes.len(), 1);
+        assert_eq!(gltf.meshes[0][0].attributes[0].values.len(), 3);
+        assert_eq!(gltf.materials[0].albedo, Color::rgb(1.0, 0.0, 0.0));
+        // two nodes, and an entity for the primitive
+        assert_eq!(gltf.scenes[0].entities.len(), 3);
+
+        let mut resources = Resources::default();
+        let type_registry = TypeRegistry::default();
+        {
+            let mut component_registry = type_registry.component.write().unwrap();
+            component_registry.register::<Transform>();
+            component_registry.register::<LocalTransform>();
+            component_registry.register::<Parent>();
+            component_registry.register::<Handle<Mesh>>();
+            component_registry.register::<Handle<StandardMaterial>>();
+            component_registry.register::<MainPass>();
+            component_registry.register::<Draw>();
+            component_registry.register::<RenderPipelines>();
+        }
+        resources.insert(type_registry);
+        let mut scenes = Assets::<Scene>::default();
+        let scene_handle = Handle::from(handle_id);
+        scenes.set(scene_handle, gltf.scenes.into_iter().next().unwrap());
+        resources.insert(scenes);
+
+        let mut world = World::default();
+        let mut scene_spawner = SceneSpawner::default();
+        scene_spawner
+            .spawn_sync(&mut world, &resources, scene_handle)
+            .unwrap();
+
+        // the primitive's entity is a child of the child node, whose parent is the root node
+        let mut query = world.query::<(&Handle<Mesh>, &Parent)>();
+        let (mesh, parent) = query.iter().next().unwrap();
+        assert_eq!(*mesh, Handle::from(handle_id.labeled(&mesh_label(0, 0))));
+        let node_transform = world.get::<LocalTransform>(parent.0).unwrap();
+        assert_eq!(node_transform.0.w_axis().z(), 1.0);
+        let root = world.get::<Parent>(parent.0).unwrap().0;
+        assert_eq!(
+            world.get::<LocalTransform>(root).unwrap().0.w_axis().y(),
+            2.0
+        );
+        assert!(world.get::<Parent>(root).is_err());
+    }
+}
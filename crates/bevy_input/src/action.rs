@@ -0,0 +1,419 @@
+use crate::{
+    gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads},
+    keyboard::KeyCode,
+    mouse::MouseButton,
+    Axis, Input,
+};
+use bevy_app::{AppBuilder, PlatformOverlay};
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use std::{collections::HashMap, hash::Hash};
+
+/// A logical action, ex: an enum with a `Jump` variant, that [InputBinding]s are bound to in an [InputMap]
+pub trait Action: Copy + Eq + Hash + Send + Sync + 'static {}
+
+impl<T> Action for T where T: Copy + Eq + Hash + Send + Sync + 'static {}
+
+/// A physical input that can be bound to an [Action]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+    GamepadButton(GamepadButtonType),
+    /// The positive half of a gamepad axis, ex: pushing the left stick right
+    GamepadAxisPositive(GamepadAxisType),
+    /// The negative half of a gamepad axis, ex: pushing the left stick left
+    GamepadAxisNegative(GamepadAxisType),
+    /// An input action of a platform runtime, see [PlatformInput]
+    Platform(PlatformAction),
+}
+
+impl From<PlatformAction> for InputBinding {
+    fn from(platform_action: PlatformAction) -> Self {
+        InputBinding::Platform(platform_action)
+    }
+}
+
+/// An input action defined by a platform runtime, ex: an action handle of a storefront's controller configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlatformAction(pub u64);
+
+/// The values of [PlatformAction]s, written by a
+/// [PlatformRuntime](bevy_app::PlatformRuntime) in its `begin_frame` callback. Platform actions are bound to
+/// [Action]s with [InputBinding::Platform], so platform input works with any [InputMap].
+#[derive(Debug, Default)]
+pub struct PlatformInput {
+    values: HashMap<PlatformAction, f32>,
+}
+
+impl PlatformInput {
+    /// Sets the value of an analog action, from 0.0 to 1.0
+    pub fn set(&mut self, action: PlatformAction, value: f32) {
+        if value > 0.0 {
+            self.values.insert(action, value);
+        } else {
+            self.values.remove(&action);
+        }
+    }
+
+    /// Sets the state of a digital action
+    pub fn set_pressed(&mut self, action: PlatformAction, pressed: bool) {
+        self.set(action, if pressed { 1.0 } else { 0.0 });
+    }
+
+    pub fn get(&self, action: PlatformAction) -> f32 {
+        self.values.get(&action).copied().unwrap_or(0.0)
+    }
+
+    /// Releases every action, ex: when the platform's controller disconnects
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl From<KeyCode> for InputBinding {
+    fn from(key_code: KeyCode) -> Self {
+        InputBinding::Key(key_code)
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(mouse_button: MouseButton) -> Self {
+        InputBinding::MouseButton(mouse_button)
+    }
+}
+
+impl From<GamepadButtonType> for InputBinding {
+    fn from(gamepad_button: GamepadButtonType) -> Self {
+        InputBinding::GamepadButton(gamepad_button)
+    }
+}
+
+/// Binds inputs to the actions of type `A`. Bindings can be changed at any time, ex: from a controls menu. The state of
+/// the actions is available in the [ActionState] resource, which is updated in the `PRE_UPDATE` stage. Actions are
+/// released while the [PlatformOverlay] is open.
+#[derive(Debug, Clone)]
+pub struct InputMap<A: Action> {
+    bindings: HashMap<A, Vec<InputBinding>>,
+    /// The gamepad whose buttons and axes trigger the actions. `None` uses every connected gamepad, while local
+    /// multiplayer games can give each player an action type, or an input map resource of their own.
+    pub gamepad: Option<Gamepad>,
+    /// Actions bound to gamepad axes or analog platform actions are pressed once they pass this value
+    pub axis_press_threshold: f32,
+}
+
+impl<A: Action> Default for InputMap<A> {
+    fn default() -> Self {
+        InputMap {
+            bindings: HashMap::default(),
+            gamepad: None,
+            axis_press_threshold: 0.5,
+        }
+    }
+}
+
+impl<A: Action> InputMap<A> {
+    /// Binds `binding` to `action`, in addition to its existing bindings
+    pub fn bind(&mut self, action: A, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Replaces the bindings of `action` with `binding`
+    pub fn rebind(&mut self, action: A, binding: impl Into<InputBinding>) -> &mut Self {
+        self.clear_action(action);
+        self.bind(action, binding)
+    }
+
+    pub fn unbind(&mut self, action: A, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            bindings.retain(|existing| *existing != binding);
+        }
+        self
+    }
+
+    /// Removes all bindings of `action`
+    pub fn clear_action(&mut self, action: A) -> &mut Self {
+        self.bindings.remove(&action);
+        self
+    }
+
+    pub fn get_bindings(&self, action: A) -> &[InputBinding] {
+        self.bindings
+            .get(&action)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    /// Iterates the actions `binding` is bound to, ex: to warn about conflicts while rebinding
+    pub fn iter_actions_bound_to(&self, binding: InputBinding) -> impl Iterator<Item = A> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bindings)| bindings.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+}
+
+/// The state of the actions of type `A`, computed from their bindings in the [InputMap]
+pub struct ActionState<A: Action> {
+    input: Input<A>,
+    values: HashMap<A, f32>,
+}
+
+impl<A: Action> Default for ActionState<A> {
+    fn default() -> Self {
+        ActionState {
+            input: Input::default(),
+            values: HashMap::default(),
+        }
+    }
+}
+
+impl<A: Action> ActionState<A> {
+    pub fn pressed(&self, action: A) -> bool {
+        self.input.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.input.just_pressed(action)
+    }
+
+    pub fn just_released(&self, action: A) -> bool {
+        self.input.just_released(action)
+    }
+
+    /// Returns the strength of the action from 0.0 to 1.0, which is the strongest value of its bindings. Keys and
+    /// buttons are either 0.0 or 1.0, while analog triggers and axes are in between.
+    pub fn value(&self, action: A) -> f32 {
+        self.values.get(&action).copied().unwrap_or(0.0)
+    }
+
+    /// Combines two opposite actions into an axis from -1.0 to 1.0, ex: `axis(Action::Left, Action::Right)`
+    pub fn axis(&self, negative: A, positive: A) -> f32 {
+        self.value(positive) - self.value(negative)
+    }
+
+    fn set(&mut self, action: A, value: f32, pressed: bool) {
+        if pressed {
+            self.input.press(action);
+        } else if self.input.pressed(action) {
+            self.input.release(action);
+        }
+
+        if value > 0.0 {
+            self.values.insert(action, value);
+        } else {
+            self.values.remove(&action);
+        }
+    }
+}
+
+/// Updates the ActionState<A> resource from the bindings in the InputMap<A> resource
+pub fn input_action_system<A: Action>(
+    input_map: Res<InputMap<A>>,
+    key_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_button_input: Res<Input<GamepadButton>>,
+    gamepad_button_axes: Res<Axis<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    platform_input: Res<PlatformInput>,
+    platform_overlay: Res<PlatformOverlay>,
+    mut action_state: ResMut<ActionState<A>>,
+) {
+    action_state.input.update();
+    let gamepads = match input_map.gamepad {
+        Some(gamepad) => vec![gamepad],
+        None => gamepads.iter().copied().collect(),
+    };
+
+    // actions that lost all of their bindings are released too
+    let mut actions = input_map.bindings.keys().copied().collect::<Vec<_>>();
+    actions.extend(action_state.values.keys().copied());
+    actions.extend(action_state.input.get_pressed().copied());
+
+    for action in actions {
+        let mut value = 0.0f32;
+        let mut pressed = false;
+        for binding in input_map.get_bindings(action) {
+            match *binding {
+                InputBinding::Key(key_code) => {
+                    if key_input.pressed(key_code) {
+                        value = 1.0;
+                        pressed = true;
+                    }
+                }
+                InputBinding::MouseButton(mouse_button) => {
+                    if mouse_button_input.pressed(mouse_button) {
+                        value = 1.0;
+                        pressed = true;
+                    }
+                }
+                InputBinding::GamepadButton(button_type) => {
+                    for gamepad in gamepads.iter() {
+                        let button = GamepadButton(*gamepad, button_type);
+                        let button_pressed = gamepad_button_input.pressed(button);
+                        let button_value = gamepad_button_axes.get(button).unwrap_or(0.0);
+                        value = value.max(if button_pressed { 1.0 } else { button_value });
+                        pressed |= button_pressed;
+                    }
+                }
+                InputBinding::GamepadAxisPositive(axis_type)
+                | InputBinding::GamepadAxisNegative(axis_type) => {
+                    let sign = match binding {
+                        InputBinding::GamepadAxisPositive(_) => 1.0,
+                        _ => -1.0,
+                    };
+                    for gamepad in gamepads.iter() {
+                        let axis_value = gamepad_axes
+                            .get(GamepadAxis(*gamepad, axis_type))
+                            .unwrap_or(0.0)
+                            * sign;
+                        value = value.max(axis_value);
+                        pressed |= axis_value >= input_map.axis_press_threshold;
+                    }
+                }
+                InputBinding::Platform(platform_action) => {
+                    let action_value = platform_input.get(platform_action);
+                    value = value.max(action_value);
+                    pressed |= action_value >= input_map.axis_press_threshold;
+                }
+            }
+        }
+
+        if platform_overlay.active {
+            // input meant for the overlay doesn't reach the game
+            action_state.set(action, 0.0, false);
+        } else {
+            action_state.set(action, value, pressed);
+        }
+    }
+}
+
+/// Adds an [InputMap] and [ActionState] for the actions of type `A` to an App
+pub trait AddInputMap {
+    fn add_input_map<A: Action>(&mut self) -> &mut Self;
+}
+
+impl AddInputMap for AppBuilder {
+    fn add_input_map<A: Action>(&mut self) -> &mut Self {
+        self.init_resource::<InputMap<A>>()
+            .init_resource::<ActionState<A>>()
+            .init_resource::<PlatformInput>()
+            .init_resource::<PlatformOverlay>()
+            .add_system_to_stage(
+                bevy_app::stage::PRE_UPDATE,
+                input_action_system::<A>.system(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        input_action_system, ActionState, InputBinding, InputMap, PlatformAction, PlatformInput,
+    };
+    use crate::{
+        gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, Gamepads},
+        keyboard::KeyCode,
+        mouse::MouseButton,
+        Axis, Input,
+    };
+    use bevy_app::PlatformOverlay;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Action {
+        Jump,
+        Left,
+        Right,
+    }
+
+    #[test]
+    fn input_actions() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut input_map = InputMap::<Action>::default();
+        input_map
+            .bind(Action::Jump, KeyCode::Space)
+            .bind(Action::Left, KeyCode::A)
+            .bind(
+                Action::Left,
+                InputBinding::GamepadAxisNegative(GamepadAxisType::LeftStickX),
+            )
+            .bind(
+                Action::Right,
+                InputBinding::GamepadAxisPositive(GamepadAxisType::LeftStickX),
+            );
+        // the test gamepad isn't connected, so it is selected explicitly
+        input_map.gamepad = Some(Gamepad(0));
+        resources.insert(input_map);
+        resources.insert(Input::<KeyCode>::default());
+        resources.insert(Input::<MouseButton>::default());
+        resources.insert(Gamepads::default());
+        resources.insert(Input::<GamepadButton>::default());
+        resources.insert(Axis::<GamepadButton>::default());
+        resources.insert(Axis::<GamepadAxis>::default());
+        resources.insert(PlatformInput::default());
+        resources.insert(PlatformOverlay::default());
+        resources.insert(ActionState::<Action>::default());
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", input_action_system::<Action>.system());
+        schedule.initialize(&mut resources);
+
+        resources
+            .get_mut::<Input<KeyCode>>()
+            .unwrap()
+            .press(KeyCode::Space);
+        resources
+            .get_mut::<Axis<GamepadAxis>>()
+            .unwrap()
+            .set(GamepadAxis(Gamepad(0), GamepadAxisType::LeftStickX), 0.3);
+        schedule.run(&mut world, &mut resources);
+        {
+            let state = resources.get::<ActionState<Action>>().unwrap();
+            assert!(state.just_pressed(Action::Jump));
+            assert_eq!(state.value(Action::Right), 0.3);
+            // 0.3 is below the press threshold
+            assert!(!state.pressed(Action::Right));
+            assert_eq!(state.axis(Action::Left, Action::Right), 0.3);
+        }
+
+        // rebinding jump releases it
+        resources
+            .get_mut::<InputMap<Action>>()
+            .unwrap()
+            .rebind(Action::Jump, KeyCode::W);
+        schedule.run(&mut world, &mut resources);
+        {
+            let state = resources.get::<ActionState<Action>>().unwrap();
+            assert!(state.just_released(Action::Jump));
+            assert!(!state.pressed(Action::Jump));
+        }
+
+        // platform actions press bound actions, unless the platform overlay is open
+        resources
+            .get_mut::<InputMap<Action>>()
+            .unwrap()
+            .bind(Action::Jump, PlatformAction(7));
+        resources
+            .get_mut::<PlatformInput>()
+            .unwrap()
+            .set_pressed(PlatformAction(7), true);
+        schedule.run(&mut world, &mut resources);
+        assert!(resources
+            .get::<ActionState<Action>>()
+            .unwrap()
+            .just_pressed(Action::Jump));
+        resources.get_mut::<PlatformOverlay>().unwrap().active = true;
+        schedule.run(&mut world, &mut resources);
+        assert!(resources
+            .get::<ActionState<Action>>()
+            .unwrap()
+            .just_released(Action::Jump));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{Axis, Input};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Identifies a connected gamepad. Ids stay the same while the gamepad is connected, so they can be assigned to players
+/// in local multiplayer.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Gamepad(pub usize);
+
+/// The gamepads that are currently connected
+#[derive(Debug, Default)]
+pub struct Gamepads {
+    gamepads: HashSet<Gamepad>,
+}
+
+impl Gamepads {
+    pub fn contains(&self, gamepad: Gamepad) -> bool {
+        self.gamepads.contains(&gamepad)
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Gamepad> {
+        self.gamepads.iter()
+    }
+}
+
+/// A raw gamepad event reported by a gamepad backend
+#[derive(Debug, Clone)]
+pub struct GamepadEvent {
+    pub gamepad: Gamepad,
+    pub event_type: GamepadEventType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEventType {
+    Connected,
+    Disconnected,
+    /// The unfiltered value of an axis. Sticks range from -1.0 to 1.0 and triggers range from 0.0 to 1.0.
+    AxisChanged(GamepadAxisType, f32),
+    /// The unfiltered value of a button, from 0.0 to 1.0. Digital buttons are either 0.0 or 1.0.
+    ButtonChanged(GamepadButtonType, f32),
+}
+
+/// A button on a gamepad, named after its position on the gamepad
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum GamepadButtonType {
+    /// The bottom face button (A on Xbox, Cross on PlayStation)
+    South,
+    /// The right face button (B on Xbox, Circle on PlayStation)
+    East,
+    /// The top face button (Y on Xbox, Triangle on PlayStation)
+    North,
+    /// The left face button (X on Xbox, Square on PlayStation)
+    West,
+    C,
+    Z,
+    /// The left bumper
+    LeftTrigger,
+    /// The left trigger. Its analog value is also available as [GamepadAxisType::LeftTrigger].
+    LeftTrigger2,
+    /// The right bumper
+    RightTrigger,
+    /// The right trigger. Its analog value is also available as [GamepadAxisType::RightTrigger].
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct GamepadButton(pub Gamepad, pub GamepadButtonType);
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum GamepadAxisType {
+    LeftStickX,
+    LeftStickY,
+    /// The analog value of the left trigger
+    LeftTrigger,
+    RightStickX,
+    RightStickY,
+    /// The analog value of the right trigger
+    RightTrigger,
+}
+
+impl GamepadAxisType {
+    pub fn is_trigger(&self) -> bool {
+        match self {
+            GamepadAxisType::LeftTrigger | GamepadAxisType::RightTrigger => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct GamepadAxis(pub Gamepad, pub GamepadAxisType);
+
+/// Converts raw axis values into the values stored in `Axis<GamepadAxis>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxisSettings {
+    /// Raw values with a magnitude below this are treated as 0.0
+    pub deadzone: f32,
+    /// Raw values with a magnitude above this are treated as 1.0
+    pub livezone: f32,
+    /// The remapped magnitude is raised to this power. Values above 1.0 give finer control near the center.
+    pub curve_exponent: f32,
+}
+
+impl Default for AxisSettings {
+    fn default() -> Self {
+        AxisSettings {
+            deadzone: 0.1,
+            livezone: 0.95,
+            curve_exponent: 1.0,
+        }
+    }
+}
+
+impl AxisSettings {
+    /// Applies the deadzone, livezone, and response curve to a raw axis value, preserving its sign
+    pub fn filter(&self, value: f32) -> f32 {
+        let magnitude = value.abs();
+        if magnitude <= self.deadzone {
+            return 0.0;
+        }
+
+        let range = (self.livezone - self.deadzone).max(std::f32::EPSILON);
+        let normalized = ((magnitude - self.deadzone) / range).min(1.0);
+        normalized.powf(self.curve_exponent) * value.signum()
+    }
+}
+
+/// Converts raw button values into presses and releases. The thresholds differ, so analog buttons resting near one of
+/// them don't flicker between pressed and released.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ButtonSettings {
+    /// Released buttons are pressed when their value reaches this
+    pub press: f32,
+    /// Pressed buttons are released when their value drops to this
+    pub release: f32,
+}
+
+impl Default for ButtonSettings {
+    fn default() -> Self {
+        ButtonSettings {
+            press: 0.75,
+            release: 0.65,
+        }
+    }
+}
+
+impl ButtonSettings {
+    pub fn is_pressed(&self, value: f32) -> bool {
+        value >= self.press
+    }
+
+    pub fn is_released(&self, value: f32) -> bool {
+        value <= self.release
+    }
+}
+
+/// Per-device axis and button settings. This can be serialized (ex: to a RON file) to persist user preferences.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GamepadSettings {
+    pub default_stick_settings: AxisSettings,
+    pub default_trigger_settings: AxisSettings,
+    pub axis_settings: HashMap<GamepadAxis, AxisSettings>,
+    #[serde(default)]
+    pub default_button_settings: ButtonSettings,
+    #[serde(default)]
+    pub button_settings: HashMap<GamepadButton, ButtonSettings>,
+}
+
+impl GamepadSettings {
+    pub fn get_axis_settings(&self, axis: GamepadAxis) -> &AxisSettings {
+        self.axis_settings.get(&axis).unwrap_or_else(|| {
+            if axis.1.is_trigger() {
+                &self.default_trigger_settings
+            } else {
+                &self.default_stick_settings
+            }
+        })
+    }
+
+    pub fn get_button_settings(&self, button: GamepadButton) -> &ButtonSettings {
+        self.button_settings
+            .get(&button)
+            .unwrap_or(&self.default_button_settings)
+    }
+}
+
+/// Sent when the `GamepadSettings` resource changes
+#[derive(Debug, Clone)]
+pub struct GamepadSettingsChanged;
+
+/// State used by the gamepad axis system
+#[derive(Default)]
+pub struct GamepadAxisState {
+    gamepad_event_reader: EventReader<GamepadEvent>,
+    raw_axes: HashMap<GamepadAxis, f32>,
+    last_settings: Option<GamepadSettings>,
+}
+
+/// Updates the Axis<GamepadAxis> resource with the latest GamepadEvents, filtered by the current GamepadSettings
+pub fn gamepad_axis_system(
+    mut state: Local<GamepadAxisState>,
+    settings: Res<GamepadSettings>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    mut settings_changed_events: ResMut<Events<GamepadSettingsChanged>>,
+    mut axes: ResMut<Axis<GamepadAxis>>,
+) {
+    let state = &mut *state;
+    // when the settings change, all axes need to be filtered again
+    if state.last_settings.as_ref() != Some(&*settings) {
+        if state.last_settings.is_some() {
+            settings_changed_events.send(GamepadSettingsChanged);
+        }
+        state.last_settings = Some(settings.clone());
+        for (axis, value) in state.raw_axes.iter() {
+            axes.set(*axis, settings.get_axis_settings(*axis).filter(*value));
+        }
+    }
+
+    for event in state.gamepad_event_reader.iter(&gamepad_events) {
+        match event.event_type {
+            GamepadEventType::AxisChanged(axis_type, value) => {
+                let axis = GamepadAxis(event.gamepad, axis_type);
+                state.raw_axes.insert(axis, value);
+                axes.set(axis, settings.get_axis_settings(axis).filter(value));
+            }
+            GamepadEventType::Disconnected => {
+                let gamepad = event.gamepad;
+                state.raw_axes.retain(|axis, _| axis.0 != gamepad);
+                for axis_type in [
+                    GamepadAxisType::LeftStickX,
+                    GamepadAxisType::LeftStickY,
+                    GamepadAxisType::LeftTrigger,
+                    GamepadAxisType::RightStickX,
+                    GamepadAxisType::RightStickY,
+                    GamepadAxisType::RightTrigger,
+                ]
+                .iter()
+                {
+                    axes.remove(GamepadAxis(gamepad, *axis_type));
+                }
+            }
+            GamepadEventType::Connected | GamepadEventType::ButtonChanged(_, _) => {}
+        }
+    }
+}
+
+/// State used by the gamepad button system
+#[derive(Default)]
+pub struct GamepadButtonState {
+    gamepad_event_reader: EventReader<GamepadEvent>,
+}
+
+/// Updates the Input<GamepadButton> resource with the latest GamepadEvents, and the Axis<GamepadButton> resource with
+/// the raw button values
+pub fn gamepad_button_system(
+    mut state: Local<GamepadButtonState>,
+    settings: Res<GamepadSettings>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    mut button_input: ResMut<Input<GamepadButton>>,
+    mut button_axes: ResMut<Axis<GamepadButton>>,
+) {
+    button_input.update();
+    for event in state.gamepad_event_reader.iter(&gamepad_events) {
+        match event.event_type {
+            GamepadEventType::ButtonChanged(button_type, value) => {
+                let button = GamepadButton(event.gamepad, button_type);
+                let button_settings = settings.get_button_settings(button);
+                if button_input.pressed(button) {
+                    if button_settings.is_released(value) {
+                        button_input.release(button);
+                    }
+                } else if button_settings.is_pressed(value) {
+                    button_input.press(button);
+                }
+                button_axes.set(button, value);
+            }
+            GamepadEventType::Disconnected => {
+                let gamepad = event.gamepad;
+                let pressed = button_input
+                    .get_pressed()
+                    .filter(|button| button.0 == gamepad)
+                    .copied()
+                    .collect::<Vec<_>>();
+                for button in pressed {
+                    button_input.release(button);
+                }
+                button_axes.retain(|button, _| button.0 != gamepad);
+            }
+            GamepadEventType::Connected | GamepadEventType::AxisChanged(_, _) => {}
+        }
+    }
+}
+
+/// State used by the gamepad connection system
+#[derive(Default)]
+pub struct GamepadConnectionState {
+    gamepad_event_reader: EventReader<GamepadEvent>,
+}
+
+/// Updates the Gamepads resource with the latest connection and disconnection GamepadEvents
+pub fn gamepad_connection_system(
+    mut state: Local<GamepadConnectionState>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    mut gamepads: ResMut<Gamepads>,
+) {
+    for event in state.gamepad_event_reader.iter(&gamepad_events) {
+        match event.event_type {
+            GamepadEventType::Connected => {
+                gamepads.gamepads.insert(event.gamepad);
+            }
+            GamepadEventType::Disconnected => {
+                gamepads.gamepads.remove(&event.gamepad);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        gamepad_button_system, AxisSettings, Gamepad, GamepadButton, GamepadButtonType,
+        GamepadEvent, GamepadEventType, GamepadSettings,
+    };
+    use crate::{Axis, Input};
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    #[test]
+    fn axis_filter() {
+        let settings = AxisSettings {
+            deadzone: 0.1,
+            livezone: 0.9,
+            curve_exponent: 1.0,
+        };
+        assert_eq!(settings.filter(0.05), 0.0);
+        assert_eq!(settings.filter(-0.05), 0.0);
+        assert_eq!(settings.filter(0.95), 1.0);
+        assert_eq!(settings.filter(-0.95), -1.0);
+        assert!((settings.filter(0.5) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn button_thresholds() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(GamepadSettings::default());
+        resources.insert(Events::<GamepadEvent>::default());
+        resources.insert(Input::<GamepadButton>::default());
+        resources.insert(Axis::<GamepadButton>::default());
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", gamepad_button_system.system());
+        schedule.initialize(&mut resources);
+
+        let gamepad = Gamepad(1);
+        let trigger = GamepadButton(gamepad, GamepadButtonType::RightTrigger2);
+        let mut send_and_update = |event_type| {
+            resources
+                .get_mut::<Events<GamepadEvent>>()
+                .unwrap()
+                .send(GamepadEvent {
+                    gamepad,
+                    event_type,
+                });
+            schedule.run(&mut world, &mut resources);
+            let input = resources.get::<Input<GamepadButton>>().unwrap();
+            (input.pressed(trigger), input.just_pressed(trigger))
+        };
+
+        let changed =
+            |value| GamepadEventType::ButtonChanged(GamepadButtonType::RightTrigger2, value);
+        assert_eq!(send_and_update(changed(0.5)), (false, false));
+        assert_eq!(send_and_update(changed(0.8)), (true, true));
+        // values between the release and press thresholds keep the button pressed
+        assert_eq!(send_and_update(changed(0.7)), (true, false));
+        assert_eq!(send_and_update(changed(0.6)), (false, false));
+        assert_eq!(send_and_update(changed(1.0)), (true, true));
+        assert_eq!(
+            send_and_update(GamepadEventType::Disconnected),
+            (false, false)
+        );
+    }
+}
@@ -46,6 +46,11 @@ where
         self.just_released.contains(&input)
     }
 
+    /// Returns the inputs that are currently pressed
+    pub fn get_pressed(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.pressed.iter()
+    }
+
     pub fn update(&mut self) {
         self.just_pressed.clear();
         self.just_released.clear();
@@ -1,15 +1,25 @@
 use crate::Input;
 use bevy_app::prelude::*;
-use bevy_ecs::{Res, ResMut, Local};
+use bevy_ecs::{Local, Res, ResMut};
 
 /// A key input event from a keyboard device
 #[derive(Debug, Clone)]
 pub struct KeyboardInput {
+    /// The physical key, independent of the keyboard layout. See [ScanCode].
     pub scan_code: u32,
+    /// The logical key, which depends on the keyboard layout (ex: the same physical key is `Q` on QWERTY and `A` on AZERTY)
     pub key_code: Option<KeyCode>,
     pub state: ElementState,
 }
 
+/// A platform-specific code identifying a physical key on the keyboard.
+///
+/// Unlike [KeyCode], scan codes don't change with the keyboard layout, which makes them a good fit for bindings that
+/// depend on the position of keys (ex: WASD movement should stay in the same place on AZERTY and Dvorak layouts).
+/// Use [KeyCode] for bindings that should follow the layout, like text shortcuts.
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+pub struct ScanCode(pub u32);
+
 /// The current "press" state of an element
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ElementState {
@@ -32,17 +42,25 @@ pub struct KeyboardInputState {
     keyboard_input_event_reader: EventReader<KeyboardInput>,
 }
 
-/// Updates the Input<KeyCode> resource with the latest KeyboardInput events
+/// Updates the Input<KeyCode> and Input<ScanCode> resources with the latest KeyboardInput events
 pub fn keyboard_input_system(
     mut state: Local<KeyboardInputState>,
     mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut scan_code_input: ResMut<Input<ScanCode>>,
     keyboard_input_events: Res<Events<KeyboardInput>>,
 ) {
     keyboard_input.update();
+    scan_code_input.update();
     for event in state
         .keyboard_input_event_reader
         .iter(&keyboard_input_events)
     {
+        let scan_code = ScanCode(event.scan_code);
+        match event.state {
+            ElementState::Pressed => scan_code_input.press(scan_code),
+            ElementState::Released => scan_code_input.release(scan_code),
+        }
+
         if let KeyboardInput {
             key_code: Some(key_code),
             state,
@@ -253,3 +271,69 @@ pub enum KeyCode {
     Paste,
     Cut,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{keyboard_input_system, ElementState, KeyCode, KeyboardInput, ScanCode};
+    use crate::Input;
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+
+    #[test]
+    fn scan_codes() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Events::<KeyboardInput>::default());
+        resources.insert(Input::<KeyCode>::default());
+        resources.insert(Input::<ScanCode>::default());
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", keyboard_input_system.system());
+        schedule.initialize(&mut resources);
+
+        let mut update =
+            |resources: &mut Resources, events: Vec<(u32, Option<KeyCode>, ElementState)>| {
+                let mut keyboard_input_events =
+                    resources.get_mut::<Events<KeyboardInput>>().unwrap();
+                for (scan_code, key_code, state) in events {
+                    keyboard_input_events.send(KeyboardInput {
+                        scan_code,
+                        key_code,
+                        state,
+                    });
+                }
+                drop(keyboard_input_events);
+                schedule.run(&mut world, resources);
+            };
+
+        // keys without a key code are still tracked by their scan code
+        update(
+            &mut resources,
+            vec![
+                (16, Some(KeyCode::A), ElementState::Pressed),
+                (100, None, ElementState::Pressed),
+            ],
+        );
+        let scan_code_input = resources.get::<Input<ScanCode>>().unwrap();
+        assert!(scan_code_input.just_pressed(ScanCode(16)));
+        assert!(scan_code_input.just_pressed(ScanCode(100)));
+        assert!(resources
+            .get::<Input<KeyCode>>()
+            .unwrap()
+            .pressed(KeyCode::A));
+        drop(scan_code_input);
+
+        update(
+            &mut resources,
+            vec![(16, Some(KeyCode::A), ElementState::Released)],
+        );
+        let scan_code_input = resources.get::<Input<ScanCode>>().unwrap();
+        assert!(scan_code_input.just_released(ScanCode(16)));
+        assert!(scan_code_input.pressed(ScanCode(100)));
+        assert!(!scan_code_input.just_pressed(ScanCode(100)));
+        assert!(!resources
+            .get::<Input<KeyCode>>()
+            .unwrap()
+            .pressed(KeyCode::A));
+    }
+}
@@ -1,23 +1,42 @@
+pub mod action;
+mod axis;
+pub mod gamepad;
 mod input;
 pub mod keyboard;
 pub mod mouse;
 pub mod system;
+pub mod touch;
 
+pub use axis::*;
 pub use input::*;
 
 pub mod prelude {
-    pub use crate::{keyboard::KeyCode, mouse::MouseButton, Input};
+    pub use crate::{
+        action::{ActionState, AddInputMap, InputBinding, InputMap, PlatformAction, PlatformInput},
+        gamepad::{
+            Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, GamepadEvent,
+            GamepadEventType, GamepadSettings, Gamepads,
+        },
+        keyboard::{KeyCode, ScanCode},
+        mouse::MouseButton,
+        touch::{Touch, TouchPhase, Touches},
+        Axis, Input,
+    };
 }
 
+use action::PlatformInput;
 use bevy_app::prelude::*;
-use keyboard::{keyboard_input_system, KeyCode, KeyboardInput};
-use mouse::{
-    mouse_button_input_system, MouseButton, MouseButtonInput, MouseMotion,
-};
+use keyboard::{keyboard_input_system, KeyCode, KeyboardInput, ScanCode};
+use mouse::{mouse_button_input_system, MouseButton, MouseButtonInput, MouseMotion, MouseWheel};
 
 use bevy_ecs::IntoQuerySystem;
+use gamepad::{
+    gamepad_axis_system, gamepad_button_system, gamepad_connection_system, GamepadAxis,
+    GamepadButton, GamepadEvent, GamepadSettings, GamepadSettingsChanged, Gamepads,
+};
+use touch::{touch_gesture_system, touch_screen_input_system, TouchGesture, TouchInput, Touches};
 
-/// Adds keyboard and mouse input to an App
+/// Adds keyboard, mouse, touch, and gamepad input to an App
 #[derive(Default)]
 pub struct InputPlugin;
 
@@ -26,7 +45,19 @@ impl Plugin for InputPlugin {
         app.add_event::<KeyboardInput>()
             .add_event::<MouseButtonInput>()
             .add_event::<MouseMotion>()
+            .add_event::<MouseWheel>()
+            .add_event::<TouchInput>()
+            .add_event::<TouchGesture>()
+            .add_event::<GamepadEvent>()
+            .add_event::<GamepadSettingsChanged>()
+            .init_resource::<GamepadSettings>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadButton>>()
             .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<ScanCode>>()
+            .init_resource::<PlatformInput>()
             .add_system_to_stage(
                 bevy_app::stage::EVENT_UPDATE,
                 keyboard_input_system.system(),
@@ -35,6 +66,21 @@ impl Plugin for InputPlugin {
             .add_system_to_stage(
                 bevy_app::stage::EVENT_UPDATE,
                 mouse_button_input_system.system(),
+            )
+            .init_resource::<Touches>()
+            .add_system_to_stage(
+                bevy_app::stage::EVENT_UPDATE,
+                touch_screen_input_system.system(),
+            )
+            .add_system_to_stage(bevy_app::stage::EVENT_UPDATE, touch_gesture_system.system())
+            .add_system_to_stage(bevy_app::stage::EVENT_UPDATE, gamepad_axis_system.system())
+            .add_system_to_stage(
+                bevy_app::stage::EVENT_UPDATE,
+                gamepad_button_system.system(),
+            )
+            .add_system_to_stage(
+                bevy_app::stage::EVENT_UPDATE,
+                gamepad_connection_system.system(),
             );
     }
 }
@@ -26,6 +26,23 @@ pub struct MouseMotion {
     pub delta: Vec2,
 }
 
+/// The unit of a [MouseWheel] delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseScrollUnit {
+    /// The delta is a number of lines (or rows) to scroll
+    Line,
+    /// The delta is a number of pixels to scroll, ex: from a touchpad
+    Pixel,
+}
+
+/// A mouse wheel event. Positive `y` values scroll up and positive `x` values scroll right.
+#[derive(Debug, Clone)]
+pub struct MouseWheel {
+    pub unit: MouseScrollUnit,
+    pub x: f32,
+    pub y: f32,
+}
+
 /// State used by the mouse button input system
 #[derive(Default)]
 pub struct MouseButtonInputState {
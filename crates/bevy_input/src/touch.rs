@@ -0,0 +1,357 @@
+use bevy_app::prelude::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use bevy_math::Vec2;
+use std::collections::HashMap;
+
+/// A touch input event
+#[derive(Debug, Clone)]
+pub struct TouchInput {
+    pub phase: TouchPhase,
+    /// The position of the touch in pixels, with the origin at the bottom left of the window
+    pub position: Vec2,
+    /// Identifies the finger for the duration of the touch. Ids may be reused once a touch has ended.
+    pub id: u64,
+}
+
+/// The phase of a touch
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+/// The state of a finger on the touch screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    pub id: u64,
+    /// The position where the touch started
+    pub start_position: Vec2,
+    /// The position of the touch at the end of the previous frame
+    pub previous_position: Vec2,
+    pub position: Vec2,
+    /// The phase of the latest event of the touch
+    pub phase: TouchPhase,
+}
+
+impl Touch {
+    /// Returns how far the touch moved this frame
+    pub fn delta(&self) -> Vec2 {
+        self.position - self.previous_position
+    }
+
+    /// Returns how far the touch moved since it started
+    pub fn distance(&self) -> Vec2 {
+        self.position - self.start_position
+    }
+}
+
+impl From<&TouchInput> for Touch {
+    fn from(input: &TouchInput) -> Self {
+        Touch {
+            id: input.id,
+            start_position: input.position,
+            previous_position: input.position,
+            position: input.position,
+            phase: input.phase,
+        }
+    }
+}
+
+/// The fingers on the touch screen, updated from the latest TouchInput events
+#[derive(Debug, Default)]
+pub struct Touches {
+    pressed: HashMap<u64, Touch>,
+    just_pressed: HashMap<u64, Touch>,
+    just_released: HashMap<u64, Touch>,
+    just_cancelled: HashMap<u64, Touch>,
+    primary: Option<u64>,
+}
+
+impl Touches {
+    /// Iterates the fingers that are on the touch screen
+    pub fn iter(&self) -> impl Iterator<Item = &Touch> + '_ {
+        self.pressed.values()
+    }
+
+    pub fn get_pressed(&self, id: u64) -> Option<&Touch> {
+        self.pressed.get(&id)
+    }
+
+    pub fn just_pressed(&self, id: u64) -> bool {
+        self.just_pressed.contains_key(&id)
+    }
+
+    pub fn just_released(&self, id: u64) -> bool {
+        self.just_released.contains_key(&id)
+    }
+
+    pub fn just_cancelled(&self, id: u64) -> bool {
+        self.just_cancelled.contains_key(&id)
+    }
+
+    pub fn iter_just_pressed(&self) -> impl Iterator<Item = &Touch> + '_ {
+        self.just_pressed.values()
+    }
+
+    pub fn iter_just_released(&self) -> impl Iterator<Item = &Touch> + '_ {
+        self.just_released.values()
+    }
+
+    pub fn iter_just_cancelled(&self) -> impl Iterator<Item = &Touch> + '_ {
+        self.just_cancelled.values()
+    }
+
+    /// Returns the id of the primary touch, which is the finger that touched the screen while no other fingers were
+    /// on it. It stays the primary touch until it is lifted, even if other fingers touch the screen in the meantime.
+    /// The primary touch of a frame in which it ended is still returned, so its release can be handled.
+    pub fn primary(&self) -> Option<u64> {
+        self.primary
+    }
+
+    /// Returns the latest state of the primary touch, see [Touches::primary]
+    pub fn primary_touch(&self) -> Option<&Touch> {
+        let id = self.primary?;
+        self.pressed
+            .get(&id)
+            .or_else(|| self.just_released.get(&id))
+            .or_else(|| self.just_cancelled.get(&id))
+    }
+
+    fn update(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.just_cancelled.clear();
+        if let Some(primary) = self.primary {
+            if !self.pressed.contains_key(&primary) {
+                self.primary = None;
+            }
+        }
+        for touch in self.pressed.values_mut() {
+            touch.previous_position = touch.position;
+        }
+    }
+
+    fn process_event(&mut self, event: &TouchInput) {
+        match event.phase {
+            TouchPhase::Started => {
+                let touch = Touch::from(event);
+                self.pressed.insert(event.id, touch);
+                self.just_pressed.insert(event.id, touch);
+                if self.primary.is_none() {
+                    self.primary = Some(event.id);
+                }
+            }
+            TouchPhase::Moved => {
+                if let Some(touch) = self.pressed.get_mut(&event.id) {
+                    touch.position = event.position;
+                    touch.phase = event.phase;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let mut touch = self
+                    .pressed
+                    .remove(&event.id)
+                    .unwrap_or_else(|| Touch::from(event));
+                touch.position = event.position;
+                touch.phase = event.phase;
+                if event.phase == TouchPhase::Ended {
+                    self.just_released.insert(event.id, touch);
+                } else {
+                    self.just_cancelled.insert(event.id, touch);
+                }
+            }
+        }
+    }
+}
+
+/// State used by the touch screen input system
+#[derive(Default)]
+pub struct TouchScreenInputState {
+    touch_input_event_reader: EventReader<TouchInput>,
+}
+
+/// Updates the Touches resource with the latest TouchInput events
+pub fn touch_screen_input_system(
+    mut state: Local<TouchScreenInputState>,
+    mut touches: ResMut<Touches>,
+    touch_input_events: Res<Events<TouchInput>>,
+) {
+    touches.update();
+    for event in state.touch_input_event_reader.iter(&touch_input_events) {
+        touches.process_event(event);
+    }
+}
+
+/// A gesture recognized from two simultaneous touches. A single finger movement can produce several gestures at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TouchGesture {
+    /// The midpoint between the touches moved by `delta` pixels
+    Pan { delta: Vec2 },
+    /// The distance between the touches was multiplied by `scale`
+    Pinch { scale: f32 },
+    /// The touches rotated counterclockwise around their midpoint by `angle` radians
+    Rotate { angle: f32 },
+}
+
+/// State used by the touch gesture system
+#[derive(Default)]
+pub struct TouchGestureState {
+    touch_input_event_reader: EventReader<TouchInput>,
+    touches: HashMap<u64, Vec2>,
+}
+
+/// Recognizes two finger pan, pinch, and rotate gestures from the latest TouchInput events
+pub fn touch_gesture_system(
+    mut state: Local<TouchGestureState>,
+    touch_input_events: Res<Events<TouchInput>>,
+    mut touch_gesture_events: ResMut<Events<TouchGesture>>,
+) {
+    let state = &mut *state;
+    for event in state.touch_input_event_reader.iter(&touch_input_events) {
+        match event.phase {
+            TouchPhase::Started => {
+                state.touches.insert(event.id, event.position);
+            }
+            TouchPhase::Moved => {
+                let old_position = match state.touches.insert(event.id, event.position) {
+                    Some(old_position) => old_position,
+                    None => continue,
+                };
+
+                // gestures are only recognized while exactly two fingers are down
+                if state.touches.len() != 2 {
+                    continue;
+                }
+
+                let other_position = match state
+                    .touches
+                    .iter()
+                    .find(|(id, _)| **id != event.id)
+                    .map(|(_, position)| *position)
+                {
+                    Some(other_position) => other_position,
+                    None => continue,
+                };
+
+                for gesture in recognize_gestures(old_position, event.position, other_position) {
+                    touch_gesture_events.send(gesture);
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                state.touches.remove(&event.id);
+            }
+        }
+    }
+}
+
+/// Returns the gestures produced by moving one of two touches from `old_position` to `new_position`
+fn recognize_gestures(
+    old_position: Vec2,
+    new_position: Vec2,
+    other_position: Vec2,
+) -> Vec<TouchGesture> {
+    let mut gestures = Vec::new();
+
+    let delta = (new_position - old_position) / 2.0;
+    if delta != Vec2::zero() {
+        gestures.push(TouchGesture::Pan { delta });
+    }
+
+    let old_offset = old_position - other_position;
+    let new_offset = new_position - other_position;
+    let old_length = old_offset.length();
+    let new_length = new_offset.length();
+    if old_length > 0.0 && new_length > 0.0 {
+        if new_length != old_length {
+            gestures.push(TouchGesture::Pinch {
+                scale: new_length / old_length,
+            });
+        }
+
+        let cross = old_offset.x() * new_offset.y() - old_offset.y() * new_offset.x();
+        let angle = cross.atan2(old_offset.dot(new_offset));
+        if angle != 0.0 {
+            gestures.push(TouchGesture::Rotate { angle });
+        }
+    }
+
+    gestures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recognize_gestures, TouchGesture, TouchInput, TouchPhase, Touches};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn touches() {
+        let event = |id, phase, x| TouchInput {
+            phase,
+            position: Vec2::new(x, 0.0),
+            id,
+        };
+        let mut touches = Touches::default();
+        touches.process_event(&event(1, TouchPhase::Started, 0.0));
+        touches.process_event(&event(2, TouchPhase::Started, 50.0));
+        assert!(touches.just_pressed(1) && touches.just_pressed(2));
+        assert_eq!(touches.primary(), Some(1));
+
+        touches.update();
+        touches.process_event(&event(1, TouchPhase::Moved, 10.0));
+        let touch = touches.get_pressed(1).unwrap();
+        assert_eq!(touch.delta(), Vec2::new(10.0, 0.0));
+        assert!(!touches.just_pressed(1));
+
+        touches.update();
+        touches.process_event(&event(1, TouchPhase::Moved, 15.0));
+        touches.process_event(&event(1, TouchPhase::Ended, 15.0));
+        assert!(touches.just_released(1));
+        assert_eq!(touches.iter().count(), 1);
+        // the primary touch is still available in the frame it ended
+        assert_eq!(
+            touches.primary_touch().unwrap().distance(),
+            Vec2::new(15.0, 0.0)
+        );
+
+        // other touches don't become primary once the primary touch ends
+        touches.update();
+        assert_eq!(touches.primary(), None);
+        touches.process_event(&event(2, TouchPhase::Cancelled, 50.0));
+        assert!(touches.just_cancelled(2));
+    }
+
+    #[test]
+    fn pinch() {
+        let gestures = recognize_gestures(
+            Vec2::new(10.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(0.0, 0.0),
+        );
+        assert_eq!(
+            gestures,
+            vec![
+                TouchGesture::Pan {
+                    delta: Vec2::new(5.0, 0.0)
+                },
+                TouchGesture::Pinch { scale: 2.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn rotate() {
+        let gestures = recognize_gestures(
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(0.0, 0.0),
+        );
+        match gestures.last() {
+            Some(TouchGesture::Rotate { angle }) => {
+                assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6)
+            }
+            _ => panic!("expected a rotate gesture"),
+        }
+    }
+}
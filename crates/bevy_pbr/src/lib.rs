@@ -3,13 +3,20 @@ pub mod render_graph;
 mod entity;
 mod light;
 mod material;
+mod static_mesh;
 
 pub use entity::*;
 pub use light::*;
 pub use material::*;
+pub use static_mesh::*;
 
 pub mod prelude {
-    pub use crate::{entity::*, light::Light, material::StandardMaterial};
+    pub use crate::{
+        entity::*,
+        light::{Light, LightKind},
+        material::StandardMaterial,
+        static_mesh::Static,
+    };
 }
 
 use bevy_app::prelude::*;
@@ -20,6 +27,7 @@ use bevy_type_registry::RegisterType;
 use light::Light;
 use material::StandardMaterial;
 use render_graph::add_pbr_graph;
+use static_mesh::{static_mesh_baking_system, Static};
 
 /// NOTE: this isn't PBR yet. consider this name "aspirational" :)
 #[derive(Default)]
@@ -29,12 +37,23 @@ impl Plugin for PbrPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<StandardMaterial>()
             .register_component::<Light>()
+            .register_component::<Static>()
             .add_system_to_stage(
                 stage::POST_UPDATE,
                 shader::asset_shader_defs_system::<StandardMaterial>.system(),
+            )
+            // static meshes are baked after transforms have been updated
+            .add_system_to_stage(stage::LAST, static_mesh_baking_system.system())
+            .add_system_to_stage(
+                bevy_render::stage::DRAW,
+                render_graph::shadow_pipelines_system.system(),
             );
         let resources = app.resources();
         let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
         add_pbr_graph(&mut render_graph, resources);
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<RenderGraph>();
+    }
 }
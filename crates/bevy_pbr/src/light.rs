@@ -1,5 +1,5 @@
 use bevy_core::Byteable;
-use bevy_math::Mat4;
+use bevy_math::{Mat4, Vec3};
 use bevy_property::Properties;
 use bevy_render::{
     camera::{CameraProjection, PerspectiveProjection},
@@ -8,12 +8,44 @@ use bevy_render::{
 use bevy_transform::components::Translation;
 use std::ops::Range;
 
-/// A point light
+/// The number of shadow map tiles in the shadow atlas. Directional lights use one tile and point lights use six (one per
+/// cube face). Lights that don't fit into the atlas don't cast shadows.
+pub const MAX_SHADOW_TILES: usize = 16;
+/// The width and height of a shadow map tile in texels
+pub const SHADOW_TILE_SIZE: u32 = 512;
+pub(crate) const SHADOW_TILES_PER_ROW: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    /// Shines in all directions from the light's translation
+    Point,
+    /// Shines in the direction the light faces (its -Z axis) like the sun, with the same intensity everywhere.
+    /// Shadows are cast within a box around the light that extends `shadow_extent` to each side and `depth` forward.
+    Directional { shadow_extent: f32 },
+}
+
+impl Default for LightKind {
+    fn default() -> Self {
+        LightKind::Point
+    }
+}
+
+/// A point or directional light
 #[derive(Properties)]
 pub struct Light {
     pub color: Color,
     pub fov: f32,
+    /// The near and far planes of the light's shadow maps
     pub depth: Range<f32>,
+    #[property(ignore)]
+    pub kind: LightKind,
+    pub shadows_enabled: bool,
+    /// Subtracted from the depth of surfaces before they are compared with the shadow map, which keeps lit surfaces
+    /// from shadowing themselves ("shadow acne")
+    pub shadow_depth_bias: f32,
+    /// Moves surfaces along their normal (in world units) before they are looked up in the shadow map. Like the depth
+    /// bias this prevents shadow acne, especially on surfaces at grazing angles to the light.
+    pub shadow_normal_bias: f32,
 }
 
 impl Default for Light {
@@ -22,6 +54,63 @@ impl Default for Light {
             color: Color::rgb(1.0, 1.0, 1.0),
             depth: 0.1..50.0,
             fov: f32::to_radians(60.0),
+            kind: LightKind::Point,
+            shadows_enabled: false,
+            shadow_depth_bias: 0.0005,
+            shadow_normal_bias: 0.05,
+        }
+    }
+}
+
+impl Light {
+    /// Returns the number of shadow map tiles the light needs
+    pub fn shadow_tile_count(&self) -> usize {
+        if !self.shadows_enabled {
+            return 0;
+        }
+        match self.kind {
+            LightKind::Point => 6,
+            LightKind::Directional { .. } => 1,
+        }
+    }
+
+    /// Returns the view projection of each of the light's shadow map tiles. Point lights have one per cube face, in
+    /// the order +X, -X, +Y, -Y, +Z, -Z.
+    pub fn shadow_view_projections(&self, transform: &Mat4) -> Vec<Mat4> {
+        match self.kind {
+            LightKind::Point => {
+                let projection = Mat4::perspective_rh(
+                    std::f32::consts::FRAC_PI_2,
+                    1.0,
+                    self.depth.start,
+                    self.depth.end,
+                );
+                let position = Vec3::from(transform.w_axis().truncate());
+                [
+                    (Vec3::unit_x(), Vec3::unit_y()),
+                    (-Vec3::unit_x(), Vec3::unit_y()),
+                    (Vec3::unit_y(), Vec3::unit_z()),
+                    (-Vec3::unit_y(), Vec3::unit_z()),
+                    (Vec3::unit_z(), Vec3::unit_y()),
+                    (-Vec3::unit_z(), Vec3::unit_y()),
+                ]
+                .iter()
+                .map(|(direction, up)| {
+                    projection * Mat4::look_at_rh(position, position + *direction, *up)
+                })
+                .collect()
+            }
+            LightKind::Directional { shadow_extent } => {
+                let projection = Mat4::orthographic_rh(
+                    -shadow_extent,
+                    shadow_extent,
+                    -shadow_extent,
+                    shadow_extent,
+                    self.depth.start,
+                    self.depth.end,
+                );
+                vec![projection * transform.inverse()]
+            }
         }
     }
 }
@@ -30,14 +119,24 @@ impl Default for Light {
 #[derive(Clone, Copy)]
 pub(crate) struct LightRaw {
     pub proj: [[f32; 4]; 4],
+    /// The position of point lights (w = 1.0), or the direction towards directional lights (w = 0.0)
     pub pos: [f32; 4],
     pub color: [f32; 4],
+    /// The first shadow map tile (or -1.0 without shadows), depth bias, normal bias, and 1.0 if the light's shadows are
+    /// baked
+    pub shadow: [f32; 4],
 }
 
 unsafe impl Byteable for LightRaw {}
 
 impl LightRaw {
-    pub fn from(light: &Light, transform: &Mat4, translation: &Translation) -> LightRaw {
+    pub fn from(
+        light: &Light,
+        transform: &Mat4,
+        translation: &Translation,
+        first_shadow_tile: Option<usize>,
+        baked: bool,
+    ) -> LightRaw {
         let perspective = PerspectiveProjection {
             fov: light.fov,
             aspect_ratio: 1.0,
@@ -46,11 +145,119 @@ impl LightRaw {
         };
 
         let proj = perspective.get_projection_matrix() * *transform;
-        let (x, y, z) = translation.0.into();
+        let pos = match light.kind {
+            LightKind::Point => {
+                let (x, y, z) = translation.0.into();
+                [x, y, z, 1.0]
+            }
+            LightKind::Directional { .. } => {
+                let (x, y, z) = transform.z_axis().truncate().normalize().into();
+                [x, y, z, 0.0]
+            }
+        };
         LightRaw {
             proj: proj.to_cols_array_2d(),
-            pos: [x, y, z, 1.0],
+            pos,
             color: light.color.into(),
+            shadow: [
+                first_shadow_tile.map_or(-1.0, |tile| tile as f32),
+                light.shadow_depth_bias,
+                light.shadow_normal_bias,
+                if baked { 1.0 } else { 0.0 },
+            ],
         }
     }
 }
+
+/// Assigns shadow map tiles to lights in order, until the atlas is full. Returns the first tile of each light (if it
+/// got any) and the view projections of all assigned tiles.
+pub(crate) fn assign_shadow_tiles<'a>(
+    lights: impl Iterator<Item = (&'a Light, &'a Mat4)>,
+) -> (Vec<Option<usize>>, Vec<Mat4>) {
+    let mut first_tiles = Vec::new();
+    let mut view_projections = Vec::new();
+    for (light, transform) in lights {
+        let tile_count = light.shadow_tile_count();
+        if tile_count > 0 && view_projections.len() + tile_count <= MAX_SHADOW_TILES {
+            first_tiles.push(Some(view_projections.len()));
+            view_projections.extend(light.shadow_view_projections(transform));
+        } else {
+            first_tiles.push(None);
+        }
+    }
+
+    (first_tiles, view_projections)
+}
+
+/// Returns the tiles assigned by [assign_shadow_tiles] to lights whose shadows are baked, and to the other lights
+pub(crate) fn split_baked_shadow_tiles<'a>(
+    lights: impl Iterator<Item = (&'a Light, bool)>,
+    first_tiles: &[Option<usize>],
+) -> (Vec<u32>, Vec<u32>) {
+    let mut baked_tiles = Vec::new();
+    let mut dynamic_tiles = Vec::new();
+    for ((light, baked), first_tile) in lights.zip(first_tiles.iter()) {
+        if let Some(first_tile) = first_tile {
+            let tiles =
+                (*first_tile..first_tile + light.shadow_tile_count()).map(|tile| tile as u32);
+            if baked {
+                baked_tiles.extend(tiles);
+            } else {
+                dynamic_tiles.extend(tiles);
+            }
+        }
+    }
+
+    (baked_tiles, dynamic_tiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assign_shadow_tiles, split_baked_shadow_tiles, Light, LightKind};
+    use bevy_math::{Mat4, Vec3, Vec4};
+
+    #[test]
+    fn shadow_tiles() {
+        let point = Light {
+            shadows_enabled: true,
+            ..Default::default()
+        };
+        let directional = Light {
+            kind: LightKind::Directional {
+                shadow_extent: 10.0,
+            },
+            shadows_enabled: true,
+            ..Default::default()
+        };
+        let unshadowed = Light::default();
+        let transform = Mat4::from_translation(Vec3::new(0.0, 5.0, 0.0));
+        let lights = [&point, &unshadowed, &directional, &point, &point];
+        let (first_tiles, view_projections) =
+            assign_shadow_tiles(lights.iter().map(|light| (*light, &transform)));
+        // the third point light doesn't fit into the atlas
+        assert_eq!(first_tiles, vec![Some(0), None, Some(6), Some(7), None]);
+        assert_eq!(view_projections.len(), 13);
+
+        // a point below the light ends up in the center of the -Y tile, within the depth range
+        let clip = view_projections[3] * Vec4::new(0.0, 0.0, 0.0, 1.0);
+        let ndc = clip.truncate() / clip.w();
+        assert!(ndc.x().abs() < 1e-5 && ndc.y().abs() < 1e-5);
+        assert!(ndc.z() > 0.0 && ndc.z() < 1.0);
+
+        // directional lights face their -Z axis
+        let clip = view_projections[6] * Vec4::new(0.0, 5.0, -20.0, 1.0);
+        assert!(clip.z() > 0.0 && clip.z() < 1.0);
+
+        // the second point light and the directional light are baked
+        let baked = [false, false, true, true, false];
+        let (baked_tiles, dynamic_tiles) = split_baked_shadow_tiles(
+            lights
+                .iter()
+                .zip(baked.iter())
+                .map(|(light, baked)| (*light, *baked)),
+            &first_tiles,
+        );
+        assert_eq!(baked_tiles, (6..13).collect::<Vec<_>>());
+        assert_eq!(dynamic_tiles, (0..6).collect::<Vec<_>>());
+    }
+}
@@ -1,11 +1,15 @@
 #version 450
 
 const int MAX_LIGHTS = 10;
+const int MAX_SHADOW_TILES = 16;
 
 struct Light {
     mat4 proj;
+    // w is 0.0 for directional lights, whose xyz is the direction towards the light
     vec4 pos;
     vec4 color;
+    // first shadow tile (negative without shadows), depth bias, normal bias, 1.0 if the shadows are baked
+    vec4 shadow;
 };
 
 layout(location = 0) in vec3 v_Position;
@@ -21,7 +25,11 @@ layout(set = 0, binding = 0) uniform Camera {
 layout(set = 1, binding = 0) uniform Lights {
     uvec4 NumLights;
     Light SceneLights[MAX_LIGHTS];
+    mat4 ShadowViewProj[MAX_SHADOW_TILES];
 };
+layout(set = 1, binding = 1) uniform texture2D ShadowAtlas;
+layout(set = 1, binding = 2) uniform sampler ShadowAtlas_sampler;
+layout(set = 1, binding = 3) uniform texture2D BakedShadowAtlas;
 
 layout(set = 3, binding = 0) uniform StandardMaterial_albedo {
     vec4 Albedo;
@@ -32,6 +40,51 @@ layout(set = 3, binding = 1) uniform texture2D StandardMaterial_albedo_texture;
 layout(set = 3, binding = 2) uniform sampler StandardMaterial_albedo_texture_sampler;
 # endif
 
+// returns how much of the light reaches the fragment, between 0.0 (shadowed) and 1.0 (lit)
+float shadow(Light light, vec3 normal) {
+    int tile = int(light.shadow.x);
+    if (tile < 0) {
+        return 1.0;
+    }
+    if (light.pos.w != 0.0) {
+        // point lights have one tile per cube face: +X, -X, +Y, -Y, +Z, -Z
+        vec3 direction = v_Position - light.pos.xyz;
+        vec3 magnitude = abs(direction);
+        if (magnitude.x >= magnitude.y && magnitude.x >= magnitude.z) {
+            tile += direction.x > 0.0 ? 0 : 1;
+        } else if (magnitude.y >= magnitude.z) {
+            tile += direction.y > 0.0 ? 2 : 3;
+        } else {
+            tile += direction.z > 0.0 ? 4 : 5;
+        }
+    }
+
+    vec4 clip = ShadowViewProj[tile] * vec4(v_Position + normal * light.shadow.z, 1.0);
+    vec3 ndc = clip.xyz / clip.w;
+    if (clip.w <= 0.0 || any(greaterThan(abs(ndc.xy), vec2(1.0))) || ndc.z > 1.0) {
+        return 1.0;
+    }
+
+    // NumLights.y is the number of tiles per atlas row and NumLights.z the size of a tile
+    int tile_size = int(NumLights.z);
+    ivec2 tile_origin = ivec2(tile % int(NumLights.y), tile / int(NumLights.y)) * tile_size;
+    ivec2 texel = ivec2((ndc.xy * vec2(0.5, -0.5) + 0.5) * float(tile_size));
+    float depth = ndc.z - light.shadow.y;
+
+    // 3x3 percentage closer filtering, without leaving the tile
+    float lit = 0.0;
+    for (int x = -1; x <= 1; ++x) {
+        for (int y = -1; y <= 1; ++y) {
+            ivec2 offset = clamp(texel + ivec2(x, y), ivec2(0), ivec2(tile_size - 1));
+            float occluder = light.shadow.w > 0.0
+                ? texelFetch(sampler2D(BakedShadowAtlas, ShadowAtlas_sampler), tile_origin + offset, 0).r
+                : texelFetch(sampler2D(ShadowAtlas, ShadowAtlas_sampler), tile_origin + offset, 0).r;
+            lit += depth <= occluder ? 1.0 : 0.0;
+        }
+    }
+    return lit / 9.0;
+}
+
 void main() {
     vec4 output_color = Albedo;
 # ifdef STANDARDMATERIAL_ALBEDO_TEXTURE
@@ -48,10 +101,12 @@ void main() {
     for (int i=0; i<int(NumLights.x) && i<MAX_LIGHTS; ++i) {
         Light light = SceneLights[i];
         // compute Lambertian diffuse term
-        vec3 light_dir = normalize(light.pos.xyz - v_Position);
+        vec3 light_dir = light.pos.w == 0.0
+            ? normalize(light.pos.xyz)
+            : normalize(light.pos.xyz - v_Position);
         float diffuse = max(0.0, dot(normal, light_dir));
         // add light contribution
-        color += diffuse * light.color.xyz;
+        color += diffuse * shadow(light, normal) * light.color.xyz;
     }
     output_color.xyz *= color;
 # endif
@@ -12,11 +12,21 @@ layout(set = 0, binding = 0) uniform Camera {
     mat4 ViewProj;
 };
 
+# ifdef INDIRECT_INSTANCES
+// drawn by IndirectInstances, with one draw per instance
+layout(set = 2, binding = 0) readonly buffer IndirectInstances {
+    mat4 Instances[];
+};
+# else
 layout(set = 2, binding = 0) uniform Transform {
     mat4 Model;
 };
+# endif
 
 void main() {
+# ifdef INDIRECT_INSTANCES
+    mat4 Model = Instances[gl_InstanceIndex];
+# endif
     v_Normal = (Model * vec4(Vertex_Normal, 1.0)).xyz;
     v_Normal = mat3(Model) * Vertex_Normal;
     v_Position = (Model * vec4(Vertex_Position, 1.0)).xyz;
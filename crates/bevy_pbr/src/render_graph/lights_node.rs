@@ -1,19 +1,29 @@
 use crate::{
-    light::{Light, LightRaw},
+    light::{
+        assign_shadow_tiles, Light, LightRaw, MAX_SHADOW_TILES, SHADOW_TILES_PER_ROW,
+        SHADOW_TILE_SIZE,
+    },
     render_graph::uniform,
+    static_mesh::Static,
 };
 use bevy_core::{AsBytes, Byteable};
 use bevy_ecs::{Commands, IntoQuerySystem, Local, Query, Res, ResMut, Resources, System, World};
+use bevy_math::Mat4;
 use bevy_render::{
     render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
     renderer::{
         BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding,
         RenderResourceBindings, RenderResourceContext,
     },
+    texture::{
+        Extent3d, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat,
+        TextureUsage,
+    },
 };
 use bevy_transform::prelude::*;
 
-/// A Render Graph [Node] that write light data from the ECS to GPU buffers
+/// A Render Graph [Node] that write light data from the ECS to GPU buffers. It also assigns shadow atlas tiles to
+/// lights and creates the shadow atlas, which [ShadowPassNode](super::ShadowPassNode) renders into.
 #[derive(Default)]
 pub struct LightsNode {
     command_queue: CommandQueue,
@@ -45,6 +55,7 @@ impl Node for LightsNode {
 #[repr(C)]
 #[derive(Clone, Copy)]
 struct LightCount {
+    /// light count, shadow tiles per atlas row, shadow tile size
     pub num_lights: [u32; 4],
 }
 
@@ -80,7 +91,7 @@ pub fn lights_node_system(
     render_resource_context: Res<Box<dyn RenderResourceContext>>,
     // TODO: this write on RenderResourceBindings will prevent this system from running in parallel with other systems that do the same
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
-    mut query: Query<(&Light, &Transform, &Translation)>,
+    mut query: Query<(&Light, &Transform, &Translation, Option<&Static>)>,
 ) {
     let state = &mut state;
     let render_resource_context = &**render_resource_context;
@@ -91,7 +102,10 @@ pub fn lights_node_system(
     let light_array_size = size * light_count;
     let light_array_max_size = size * state.max_lights;
     let current_light_uniform_size = light_count_size + light_array_size;
-    let max_light_uniform_size = light_count_size + light_array_max_size;
+    // the view projections of the shadow tiles follow the light array
+    let shadow_view_projections_offset = light_count_size + light_array_max_size;
+    let shadow_view_projections_size = std::mem::size_of::<Mat4>() * MAX_SHADOW_TILES;
+    let max_light_uniform_size = shadow_view_projections_offset + shadow_view_projections_size;
 
     if let Some(staging_buffer) = state.staging_buffer {
         if light_count == 0 {
@@ -121,26 +135,90 @@ pub fn lights_node_system(
             mapped_at_creation: true,
         });
         state.staging_buffer = Some(staging_buffer);
+
+        let shadow_atlas_size = SHADOW_TILE_SIZE * SHADOW_TILES_PER_ROW;
+        let shadow_atlas_descriptor = TextureDescriptor {
+            size: Extent3d {
+                width: shadow_atlas_size,
+                height: shadow_atlas_size,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Depth32Float,
+            usage: TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT,
+        };
+        let shadow_atlas = render_resource_context.create_texture(shadow_atlas_descriptor);
+        render_resource_bindings.set(
+            uniform::SHADOW_ATLAS,
+            RenderResourceBinding::Texture(shadow_atlas),
+        );
+        // the tiles of static lights are rendered into their own atlas, which persists between frames
+        let baked_shadow_atlas = render_resource_context.create_texture(shadow_atlas_descriptor);
+        render_resource_bindings.set(
+            uniform::BAKED_SHADOW_ATLAS,
+            RenderResourceBinding::Texture(baked_shadow_atlas),
+        );
+        // shadow maps are filtered in the shader, as shadow samplers can't be reflected yet
+        let shadow_atlas_sampler =
+            render_resource_context.create_sampler(&SamplerDescriptor::default());
+        render_resource_bindings.set(
+            uniform::SHADOW_ATLAS_SAMPLER,
+            RenderResourceBinding::Sampler(shadow_atlas_sampler),
+        );
     }
 
+    let (first_shadow_tiles, shadow_view_projections) = {
+        let mut lights = query.iter();
+        let lights = lights
+            .iter()
+            .map(|(light, transform, _, _)| (light, &transform.value));
+        assign_shadow_tiles(lights)
+    };
+
     let staging_buffer = state.staging_buffer.unwrap();
     render_resource_context.write_mapped_buffer(
         staging_buffer,
-        0..current_light_uniform_size as u64,
+        0..max_light_uniform_size as u64,
         &mut |data, _renderer| {
             // light count
-            data[0..light_count_size].copy_from_slice([light_count as u32, 0, 0, 0].as_bytes());
+            data[0..light_count_size].copy_from_slice(
+                [
+                    light_count as u32,
+                    SHADOW_TILES_PER_ROW,
+                    SHADOW_TILE_SIZE,
+                    0,
+                ]
+                .as_bytes(),
+            );
 
             // light array
-            for ((light, transform, translation), slot) in query
+            for (((light, transform, translation, baked), first_shadow_tile), slot) in query
                 .iter()
                 .iter()
+                .zip(first_shadow_tiles.iter())
                 .zip(data[light_count_size..current_light_uniform_size].chunks_exact_mut(size))
             {
                 slot.copy_from_slice(
-                    LightRaw::from(&light, &transform.value, &translation).as_bytes(),
+                    LightRaw::from(
+                        &light,
+                        &transform.value,
+                        &translation,
+                        *first_shadow_tile,
+                        baked.is_some(),
+                    )
+                    .as_bytes(),
                 );
             }
+
+            // shadow tiles
+            for (view_projection, slot) in shadow_view_projections.iter().zip(
+                data[shadow_view_projections_offset..max_light_uniform_size]
+                    .chunks_exact_mut(std::mem::size_of::<Mat4>()),
+            ) {
+                slot.copy_from_slice(view_projection.to_cols_array().as_bytes());
+            }
         },
     );
     render_resource_context.unmap_buffer(staging_buffer);
@@ -1,19 +1,27 @@
 mod forward_pipeline;
 mod lights_node;
+mod shadow_pass_node;
+mod shadow_pipeline;
 
 pub use forward_pipeline::*;
 pub use lights_node::*;
+pub use shadow_pass_node::*;
+pub use shadow_pipeline::*;
 
 /// the names of pbr graph nodes
 pub mod node {
     pub const TRANSFORM: &str = "transform";
     pub const STANDARD_MATERIAL: &str = "standard_material";
     pub const LIGHTS: &str = "lights";
+    pub const SHADOW_PASS: &str = "shadow_pass";
 }
 
 /// the names of pbr uniforms
 pub mod uniform {
     pub const LIGHTS: &str = "Lights";
+    pub const SHADOW_ATLAS: &str = "ShadowAtlas";
+    pub const SHADOW_ATLAS_SAMPLER: &str = "ShadowAtlas_sampler";
+    pub const BAKED_SHADOW_ATLAS: &str = "BakedShadowAtlas";
 }
 
 use bevy_asset::Assets;
@@ -33,12 +41,14 @@ pub(crate) fn add_pbr_graph(graph: &mut RenderGraph, resources: &Resources) {
         AssetRenderResourcesNode::<StandardMaterial>::new(true),
     );
     graph.add_system_node(node::LIGHTS, LightsNode::new(10));
+    graph.add_node(node::SHADOW_PASS, ShadowPassNode::default());
     let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
     let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
     pipelines.set(
         FORWARD_PIPELINE_HANDLE,
         build_forward_pipeline(&mut shaders),
     );
+    pipelines.set(SHADOW_PIPELINE_HANDLE, build_shadow_pipeline(&mut shaders));
 
     // TODO: replace these with "autowire" groups
     graph.add_node_edge(node::STANDARD_MATERIAL, base::node::MAIN_PASS)
@@ -47,4 +57,10 @@ pub(crate) fn add_pbr_graph(graph: &mut RenderGraph, resources: &Resources) {
         .unwrap();
     graph.add_node_edge(node::LIGHTS, base::node::MAIN_PASS)
         .unwrap();
+    graph.add_node_edge(node::TRANSFORM, node::SHADOW_PASS)
+        .unwrap();
+    graph.add_node_edge(node::LIGHTS, node::SHADOW_PASS)
+        .unwrap();
+    graph.add_node_edge(node::SHADOW_PASS, base::node::MAIN_PASS)
+        .unwrap();
 }
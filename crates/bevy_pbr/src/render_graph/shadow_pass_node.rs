@@ -0,0 +1,407 @@
+use crate::{
+    light::{
+        assign_shadow_tiles, split_baked_shadow_tiles, Light, SHADOW_TILES_PER_ROW,
+        SHADOW_TILE_SIZE,
+    },
+    render_graph::{uniform, FORWARD_PIPELINE_HANDLE, SHADOW_PIPELINE_HANDLE},
+    static_mesh::Static,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core::AsBytes;
+use bevy_ecs::{Entity, Query, Res, ResMut, Resources, World};
+use bevy_math::Mat4;
+use bevy_render::{
+    draw::Draw,
+    indirect::IndirectInstances,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachmentDescriptor,
+        TextureAttachment,
+    },
+    pipeline::{
+        BindGroupDescriptorId, DynamicBinding, PipelineCompiler, PipelineDescriptor,
+        PipelineSpecialization, PrimitiveTopology, RenderPipelines, VertexBufferDescriptors,
+    },
+    render_graph::{Node, ResourceSlots},
+    renderer::{
+        BindGroup, BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding,
+        RenderResourceBindings, RenderResourceContext, TextureId,
+    },
+    shader::Shader,
+};
+use bevy_transform::prelude::{Transform, Translation};
+
+/// Shadow tiles are selected with dynamic offsets into one uniform buffer, which have to be aligned to 256 bytes
+const SHADOW_TILE_UNIFORM_ALIGNMENT: usize = 256;
+
+struct ShadowCaster {
+    pipeline: Handle<PipelineDescriptor>,
+    bind_group: BindGroup,
+    vertex_buffer: BufferId,
+    index_buffer: BufferId,
+    index_count: u32,
+    is_static: bool,
+}
+
+struct ShadowBindGroups {
+    tile_descriptor: BindGroupDescriptorId,
+    tile: BindGroup,
+    transform_descriptor: BindGroupDescriptorId,
+}
+
+/// The tiles and [Static] casters the baked shadow atlas was rendered with
+#[derive(PartialEq)]
+struct BakedShadows {
+    tiles: Vec<(u32, Mat4)>,
+    casters: Vec<(Entity, BufferId, BufferId, Mat4)>,
+}
+
+/// A Render Graph [Node] that renders the depth of every mesh drawn with the forward pipeline into the shadow atlas,
+/// once for each shadow tile assigned by [LightsNode](super::LightsNode).
+///
+/// The shadows of lights with the [Static] component are baked: their tiles are rendered into a separate atlas with
+/// only [Static] meshes, and are only rendered again when a static light or mesh actually changes. A scene whose lights
+/// are all static doesn't render shadows at all after they have been baked.
+///
+/// The node records its passes in parallel with other nodes, so it doesn't compile pipelines itself. The shadow
+/// pipelines are compiled by [shadow_pipelines_system] beforehand.
+#[derive(Default)]
+pub struct ShadowPassNode {
+    tile_buffer: Option<(BufferId, Vec<Mat4>)>,
+    baked: Option<BakedShadows>,
+}
+
+impl ShadowPassNode {
+    /// Returns the buffer holding the view projection of each tile, recreating it if they changed
+    fn tile_buffer(
+        &mut self,
+        render_context: &mut dyn RenderContext,
+        view_projections: &[Mat4],
+    ) -> BufferId {
+        let render_resource_context = render_context.resources_mut();
+        if let Some((buffer, ref current_view_projections)) = self.tile_buffer {
+            if current_view_projections.as_slice() == view_projections {
+                return buffer;
+            }
+            render_resource_context.remove_buffer(buffer);
+        }
+
+        let mut data = vec![0; view_projections.len() * SHADOW_TILE_UNIFORM_ALIGNMENT];
+        for (view_projection, slot) in view_projections
+            .iter()
+            .zip(data.chunks_exact_mut(SHADOW_TILE_UNIFORM_ALIGNMENT))
+        {
+            slot[..std::mem::size_of::<Mat4>()]
+                .copy_from_slice(view_projection.to_cols_array().as_bytes());
+        }
+        let buffer = render_resource_context.create_buffer_with_data(
+            BufferInfo {
+                size: data.len(),
+                buffer_usage: BufferUsage::UNIFORM,
+                ..Default::default()
+            },
+            &data,
+        );
+        self.tile_buffer = Some((buffer, view_projections.to_vec()));
+        buffer
+    }
+}
+
+impl Node for ShadowPassNode {
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
+        let (shadow_atlas, baked_shadow_atlas) = match (
+            render_resource_bindings
+                .get(uniform::SHADOW_ATLAS)
+                .and_then(|binding| binding.get_texture()),
+            render_resource_bindings
+                .get(uniform::BAKED_SHADOW_ATLAS)
+                .and_then(|binding| binding.get_texture()),
+        ) {
+            (Some(shadow_atlas), Some(baked_shadow_atlas)) => (shadow_atlas, baked_shadow_atlas),
+            _ => return,
+        };
+
+        // lights are queried like the lights node does, so they get the same tiles
+        let mut light_query = world.query::<(&Light, &Transform, &Translation, Option<&Static>)>();
+        let lights = light_query
+            .iter()
+            .map(|(light, transform, _translation, baked)| {
+                (light, &transform.value, baked.is_some())
+            })
+            .collect::<Vec<_>>();
+        let (first_tiles, view_projections) = assign_shadow_tiles(
+            lights
+                .iter()
+                .map(|(light, transform, _baked)| (*light, *transform)),
+        );
+        if view_projections.is_empty() {
+            return;
+        }
+        let (baked_tiles, dynamic_tiles) = split_baked_shadow_tiles(
+            lights
+                .iter()
+                .map(|(light, _transform, baked)| (*light, *baked)),
+            &first_tiles,
+        );
+
+        let pipelines = resources.get::<Assets<PipelineDescriptor>>().unwrap();
+        let pipeline_compiler = resources.get::<PipelineCompiler>().unwrap();
+
+        let mut casters = Vec::new();
+        let mut static_casters = Vec::new();
+        for (entity, draw, render_pipelines) in
+            &mut world.query::<(Entity, &Draw, &RenderPipelines)>()
+        {
+            let forward_pipeline = match render_pipelines
+                .pipelines
+                .iter()
+                .find(|render_pipeline| render_pipeline.pipeline == FORWARD_PIPELINE_HANDLE)
+            {
+                Some(forward_pipeline) if draw.is_visible => forward_pipeline,
+                _ => continue,
+            };
+            // the shadow pipeline draws one transform per entity, so indirect instances don't cast shadows
+            if world.get::<IndirectInstances>(entity).is_ok() {
+                continue;
+            }
+            let transform_binding = render_pipelines.bindings.get("Transform");
+            let vertex_buffers = render_pipelines.bindings.get_vertex_buffer("Vertex");
+            let (transform_binding, vertex_buffer, index_buffer) =
+                match (transform_binding, vertex_buffers) {
+                    (Some(transform_binding), Some((vertex_buffer, Some(index_buffer)))) => {
+                        (transform_binding, vertex_buffer, index_buffer)
+                    }
+                    _ => continue,
+                };
+            let index_count = match render_context.resources().get_buffer_info(index_buffer) {
+                Some(buffer_info) => (buffer_info.size / 2) as u32,
+                None => continue,
+            };
+
+            let specialization =
+                shadow_specialization(forward_pipeline.specialization.primitive_topology);
+            // meshes whose pipeline hasn't been compiled yet cast shadows from the next frame on
+            let pipeline = match pipeline_compiler
+                .get_specialized_pipeline(SHADOW_PIPELINE_HANDLE, &specialization)
+            {
+                Some(pipeline) => pipeline,
+                None => continue,
+            };
+
+            let is_static = world.get::<Static>(entity).is_ok();
+            if is_static {
+                let transform = world
+                    .get::<Transform>(entity)
+                    .map_or(Mat4::identity(), |transform| transform.value);
+                static_casters.push((entity, vertex_buffer, index_buffer, transform));
+            }
+            casters.push(ShadowCaster {
+                pipeline,
+                bind_group: BindGroup::build()
+                    .add_binding(0, transform_binding.clone())
+                    .finish(),
+                vertex_buffer,
+                index_buffer,
+                index_count,
+                is_static,
+            });
+        }
+
+        let baked = BakedShadows {
+            tiles: baked_tiles
+                .iter()
+                .map(|tile| (*tile, view_projections[*tile as usize]))
+                .collect(),
+            casters: static_casters,
+        };
+        let rebake = !baked_tiles.is_empty() && self.baked.as_ref() != Some(&baked);
+        if dynamic_tiles.is_empty() && !rebake {
+            return;
+        }
+
+        // every specialization of the shadow pipeline has the same layout
+        let layout = match casters.first() {
+            Some(caster) => pipelines
+                .get(&caster.pipeline)
+                .unwrap()
+                .get_layout()
+                .unwrap(),
+            None => return,
+        };
+        let tile_bind_group_descriptor = layout.get_bind_group(0).unwrap().id;
+        let transform_bind_group_descriptor = layout.get_bind_group(1).unwrap().id;
+        let tile_buffer = self.tile_buffer(render_context, &view_projections);
+        let tile_bind_group = BindGroup::build()
+            .add_binding(
+                0,
+                RenderResourceBinding::Buffer {
+                    buffer: tile_buffer,
+                    range: 0..std::mem::size_of::<Mat4>() as u64,
+                    dynamic_index: None,
+                },
+            )
+            .finish();
+        let render_resource_context = render_context.resources();
+        render_resource_context.create_bind_group(tile_bind_group_descriptor, &tile_bind_group);
+        for caster in casters.iter() {
+            render_resource_context
+                .create_bind_group(transform_bind_group_descriptor, &caster.bind_group);
+        }
+
+        let bind_groups = ShadowBindGroups {
+            tile_descriptor: tile_bind_group_descriptor,
+            tile: tile_bind_group,
+            transform_descriptor: transform_bind_group_descriptor,
+        };
+        if !dynamic_tiles.is_empty() {
+            render_tiles(
+                render_context,
+                &render_resource_bindings,
+                shadow_atlas,
+                &dynamic_tiles,
+                &casters.iter().collect::<Vec<_>>(),
+                &bind_groups,
+            );
+        }
+        if rebake {
+            render_tiles(
+                render_context,
+                &render_resource_bindings,
+                baked_shadow_atlas,
+                &baked_tiles,
+                &casters
+                    .iter()
+                    .filter(|caster| caster.is_static)
+                    .collect::<Vec<_>>(),
+                &bind_groups,
+            );
+            self.baked = Some(baked);
+        }
+    }
+
+    fn encodes_in_parallel(&self) -> bool {
+        true
+    }
+}
+
+/// The shadow pipeline specialization for meshes drawn with `primitive_topology`
+fn shadow_specialization(primitive_topology: PrimitiveTopology) -> PipelineSpecialization {
+    PipelineSpecialization {
+        primitive_topology,
+        dynamic_bindings: vec![
+            // ShadowTile
+            DynamicBinding {
+                bind_group: 0,
+                binding: 0,
+            },
+            // Transform
+            DynamicBinding {
+                bind_group: 1,
+                binding: 0,
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+/// Compiles the shadow pipeline for the primitive topology of each mesh drawn with the forward pipeline, so
+/// [ShadowPassNode] can record its passes without borrowing the pipelines mutably
+pub fn shadow_pipelines_system(
+    mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+    mut shaders: ResMut<Assets<Shader>>,
+    mut pipeline_compiler: ResMut<PipelineCompiler>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    vertex_buffer_descriptors: Res<VertexBufferDescriptors>,
+    mut query: Query<&RenderPipelines>,
+) {
+    for render_pipelines in &mut query.iter() {
+        for render_pipeline in render_pipelines.pipelines.iter() {
+            if render_pipeline.pipeline != FORWARD_PIPELINE_HANDLE {
+                continue;
+            }
+            let specialization =
+                shadow_specialization(render_pipeline.specialization.primitive_topology);
+            if pipeline_compiler
+                .get_specialized_pipeline(SHADOW_PIPELINE_HANDLE, &specialization)
+                .is_none()
+            {
+                pipeline_compiler.compile_pipeline(
+                    &**render_resource_context,
+                    &mut pipelines,
+                    &mut shaders,
+                    SHADOW_PIPELINE_HANDLE,
+                    &vertex_buffer_descriptors,
+                    &specialization,
+                );
+            }
+        }
+    }
+}
+
+/// Clears `atlas` and renders the depth of `casters` into each of its `tiles`
+fn render_tiles(
+    render_context: &mut dyn RenderContext,
+    render_resource_bindings: &RenderResourceBindings,
+    atlas: TextureId,
+    tiles: &[u32],
+    casters: &[&ShadowCaster],
+    bind_groups: &ShadowBindGroups,
+) {
+    let pass_descriptor = PassDescriptor {
+        color_attachments: Vec::new(),
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+            attachment: TextureAttachment::Id(atlas),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    };
+    render_context.begin_pass(
+        &pass_descriptor,
+        render_resource_bindings,
+        &mut |render_pass| {
+            for tile in tiles.iter() {
+                render_pass.set_viewport(
+                    (tile % SHADOW_TILES_PER_ROW * SHADOW_TILE_SIZE) as f32,
+                    (tile / SHADOW_TILES_PER_ROW * SHADOW_TILE_SIZE) as f32,
+                    SHADOW_TILE_SIZE as f32,
+                    SHADOW_TILE_SIZE as f32,
+                    0.0,
+                    1.0,
+                );
+                for caster in casters.iter() {
+                    render_pass.set_pipeline(caster.pipeline);
+                    render_pass.set_bind_group(
+                        0,
+                        bind_groups.tile_descriptor,
+                        bind_groups.tile.id,
+                        Some(&[tile * SHADOW_TILE_UNIFORM_ALIGNMENT as u32]),
+                    );
+                    render_pass.set_bind_group(
+                        1,
+                        bind_groups.transform_descriptor,
+                        caster.bind_group.id,
+                        caster
+                            .bind_group
+                            .dynamic_uniform_indices
+                            .as_ref()
+                            .map(|indices| indices.as_slice()),
+                    );
+                    render_pass.set_vertex_buffer(0, caster.vertex_buffer, 0);
+                    render_pass.set_index_buffer(caster.index_buffer, 0);
+                    render_pass.draw_indexed(0..caster.index_count, 0, 0..1);
+                }
+            }
+        },
+    );
+}
@@ -0,0 +1,61 @@
+use bevy_asset::{Assets, Handle};
+use bevy_render::{
+    pipeline::{
+        CompareFunction, CullMode, DepthStencilStateDescriptor, FrontFace, PipelineDescriptor,
+        RasterizationStateDescriptor, StencilStateFaceDescriptor,
+    },
+    shader::{Shader, ShaderStage, ShaderStages},
+    texture::TextureFormat,
+};
+
+pub const SHADOW_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(250739117454912620468376440106532613851);
+
+pub(crate) fn build_shadow_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        rasterization_state: Some(RasterizationStateDescriptor {
+            front_face: FrontFace::Ccw,
+            cull_mode: CullMode::Back,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        depth_stencil_state: Some(DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+            stencil_front: StencilStateFaceDescriptor::IGNORE,
+            stencil_back: StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }),
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("shadow.vert"),
+            )),
+            fragment: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_render::shader::{Shader, ShaderStage};
+
+    #[test]
+    fn shadow_shaders_compile() {
+        // compilation panics on glsl errors
+        let vertex =
+            Shader::from_glsl(ShaderStage::Vertex, include_str!("shadow.vert")).get_spirv(None);
+        assert!(!vertex.is_empty());
+
+        let shader_defs = vec!["STANDARDMATERIAL_SHADED".to_string()];
+        let fragment = Shader::from_glsl(
+            ShaderStage::Fragment,
+            include_str!("../forward_pipeline/forward.frag"),
+        )
+        .get_spirv(Some(&shader_defs));
+        assert!(!fragment.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{entity::PbrComponents, material::StandardMaterial};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::{Added, Commands, Entity, Local, Query, Res, ResMut, With, Without};
+use bevy_math::Mat4;
+use bevy_property::Properties;
+use bevy_render::{
+    draw::Draw,
+    mesh::{Mesh, MeshMergeError},
+};
+use bevy_transform::prelude::Transform;
+use std::collections::HashMap;
+
+/// Marks a mesh entity that will never move or change. Static entities that share a material are baked into a single
+/// combined mesh once their meshes have loaded, which cuts down on draw calls for environment geometry. The shadows of
+/// static [Light](crate::Light)s are baked, and only static meshes cast them.
+#[derive(Debug, Default, Clone, Properties)]
+pub struct Static;
+
+/// Added to [Static] entities whose mesh can't be merged with the other meshes of their material (ex: because it uses
+/// a strip topology or different vertex attributes). They are drawn on their own and aren't baked again.
+#[derive(Debug, Default, Clone, Properties)]
+pub struct StaticBakeFailed;
+
+/// Merges `meshes` into one mesh. Returns the merged mesh (if any mesh could be merged) and whether each mesh was
+/// merged into it.
+pub fn bake_meshes<'a>(
+    meshes: impl Iterator<Item = (&'a Mesh, &'a Mat4)>,
+) -> (Option<Mesh>, Vec<Result<(), MeshMergeError>>) {
+    let mut baked_mesh: Option<Mesh> = None;
+    let mut results = Vec::new();
+    for (mesh, transform) in meshes {
+        let result = match baked_mesh {
+            Some(ref mut baked_mesh) => baked_mesh.merge(mesh, transform),
+            None => {
+                let mut first_mesh = Mesh::new(mesh.primitive_topology);
+                first_mesh.attributes = mesh
+                    .attributes
+                    .iter()
+                    .map(|attribute| attribute.with_no_values())
+                    .collect();
+                first_mesh.indices = mesh.indices.as_ref().map(|_| Vec::new());
+                let result = first_mesh.merge(mesh, transform);
+                if result.is_ok() {
+                    baked_mesh = Some(first_mesh);
+                }
+                result
+            }
+        };
+        results.push(result);
+    }
+
+    (baked_mesh, results)
+}
+
+/// Merges the meshes of [Static] entities that share a [StandardMaterial] into one mesh per material. Baking happens
+/// when static entities are added or their meshes finish loading, never every frame. Baked entities are hidden (but
+/// not despawned, so other systems can still reference them) and lose their [Static] marker. Entities that can't be
+/// baked get a [StaticBakeFailed] marker instead.
+pub fn static_mesh_baking_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mesh_events: Res<Events<AssetEvent<Mesh>>>,
+    mut mesh_event_reader: Local<EventReader<AssetEvent<Mesh>>>,
+    mut added_query: Query<Added<Static>>,
+    mut query: Query<
+        Without<
+            StaticBakeFailed,
+            With<
+                Static,
+                (
+                    Entity,
+                    &Handle<Mesh>,
+                    &Handle<StandardMaterial>,
+                    &Transform,
+                    &mut Draw,
+                ),
+            >,
+        >,
+    >,
+) {
+    let meshes_loaded = mesh_event_reader
+        .iter(&mesh_events)
+        .any(|event| matches!(event, AssetEvent::Created { .. }));
+    let static_added = added_query.iter().iter().next().is_some();
+    if !meshes_loaded && !static_added {
+        return;
+    }
+
+    let mut batches = HashMap::<Handle<StandardMaterial>, Vec<(Entity, Handle<Mesh>, Mat4)>>::new();
+    for (entity, mesh, material, transform, _draw) in &mut query.iter() {
+        batches
+            .entry(*material)
+            .or_insert_with(Vec::new)
+            .push((entity, *mesh, transform.value));
+    }
+
+    for (material, batch) in batches {
+        // a single mesh gains nothing from baking. wait until all meshes in the batch are loaded
+        if batch.len() < 2 || batch.iter().any(|(_, mesh, _)| meshes.get(mesh).is_none()) {
+            continue;
+        }
+
+        let (baked_mesh, results) = bake_meshes(
+            batch
+                .iter()
+                .map(|(_, mesh, transform)| (meshes.get(mesh).unwrap(), transform)),
+        );
+        for ((entity, _, _), result) in batch.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    query.get_mut::<Draw>(*entity).unwrap().is_visible = false;
+                    commands.remove_one::<Static>(*entity);
+                }
+                Err(err) => {
+                    log::warn!("Static entity {:?} could not be baked: {}", entity, err);
+                    commands.insert_one(*entity, StaticBakeFailed);
+                }
+            }
+        }
+
+        if let Some(baked_mesh) = baked_mesh {
+            commands
+                .spawn(PbrComponents {
+                    mesh: meshes.add(baked_mesh),
+                    material,
+                    ..Default::default()
+                })
+                // the combined mesh is static too, so it casts baked shadows. it is never baked again on its own
+                .with(Static);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bake_meshes;
+    use bevy_math::{Mat4, Vec3};
+    use bevy_render::{
+        mesh::{shape, Mesh, MeshMergeError},
+        pipeline::PrimitiveTopology,
+    };
+
+    #[test]
+    fn bake() {
+        let cube = Mesh::from(shape::Cube { size: 1.0 });
+        let mut strip = cube.clone();
+        strip.primitive_topology = PrimitiveTopology::TriangleStrip;
+        let transforms = [
+            Mat4::identity(),
+            Mat4::from_translation(Vec3::new(3.0, 0.0, 0.0)),
+            Mat4::from_translation(Vec3::new(6.0, 0.0, 0.0)),
+        ];
+
+        // the first mesh can't be merged, so the second one starts the baked mesh
+        let (baked_mesh, results) = bake_meshes(
+            [&strip, &cube, &cube]
+                .iter()
+                .zip(transforms.iter())
+                .map(|(mesh, transform)| (*mesh, transform)),
+        );
+        assert!(matches!(
+            results[0],
+            Err(MeshMergeError::StripPrimitiveTopology)
+        ));
+        assert!(results[1].is_ok() && results[2].is_ok());
+        let baked_mesh = baked_mesh.unwrap();
+        assert_eq!(
+            baked_mesh.indices.unwrap().len(),
+            cube.indices.as_ref().unwrap().len() * 2
+        );
+    }
+}
@@ -357,7 +357,7 @@ pub fn impl_property(input: TokenStream) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = property_def.generics.split_for_impl();
     let ty = &property_def.type_name;
     let serialize_fn = if let Some(serialize_fn) = property_def.serialize_fn {
-        quote! { #serialize_fn(self) }
+        quote! { #serialize_fn(self, registry) }
     } else {
         quote! {
             #bevy_property_path::property_serde::Serializable::Owned(Box::new(#bevy_property_path::property_serde::PropertyValueSerializer::new(self, registry)))
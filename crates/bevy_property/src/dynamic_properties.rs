@@ -61,6 +61,30 @@ impl DynamicProperties {
             self.push(prop, Some(name));
         }
     }
+
+    /// Removes the property called `name` and returns it
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Property>> {
+        let index = self.prop_indices.remove(name)?;
+        self.prop_names.remove(index);
+        for prop_index in self.prop_indices.values_mut() {
+            if *prop_index > index {
+                *prop_index -= 1;
+            }
+        }
+        Some(self.props.remove(index))
+    }
+
+    /// Renames the property called `name` to `new_name`, replacing any property that already has that name. Returns
+    /// false if there is no property called `name`.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> bool {
+        match self.remove(name) {
+            Some(prop) => {
+                self.set_box(new_name, prop);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl Properties for DynamicProperties {
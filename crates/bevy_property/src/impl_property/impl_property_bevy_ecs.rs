@@ -1,24 +1,50 @@
-use crate::{impl_property, property_serde::Serializable, Property, PropertyTypeRegistry};
+use crate::{
+    impl_property,
+    property_serde::{Serializable, TYPE_FIELD, VALUE_FIELD},
+    Property, PropertyTypeRegistry,
+};
 use bevy_ecs::Entity;
 use erased_serde::Deserializer;
-use serde::Deserialize;
+use serde::{ser::SerializeMap, Deserialize, Serialize};
 
 impl_property!(Entity, serialize_entity, deserialize_entity);
 
-mod private {
-    use serde::{Deserialize, Serialize};
-    #[derive(Serialize, Deserialize)]
-    pub(super) struct Entity(pub(super) u32);
+/// Serializes an entity's id along with its type name like other values, so it can be deserialized in maps and
+/// sequences
+struct EntitySerializer<'a> {
+    entity: Entity,
+    registry: &'a PropertyTypeRegistry,
 }
 
-fn serialize_entity(entity: &Entity) -> Serializable {
-    Serializable::Owned(Box::new(private::Entity(entity.id())))
+impl<'a> Serialize for EntitySerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let type_name = std::any::type_name::<Entity>();
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry(
+            TYPE_FIELD,
+            self.registry
+                .format_type_name(type_name)
+                .unwrap_or(type_name),
+        )?;
+        state.serialize_entry(VALUE_FIELD, &self.entity.id())?;
+        state.end()
+    }
+}
+
+fn serialize_entity<'a>(entity: &Entity, registry: &'a PropertyTypeRegistry) -> Serializable<'a> {
+    Serializable::Owned(Box::new(EntitySerializer {
+        entity: *entity,
+        registry,
+    }))
 }
 
 fn deserialize_entity(
     deserializer: &mut dyn Deserializer,
     _registry: &PropertyTypeRegistry,
 ) -> Result<Box<dyn Property>, erased_serde::Error> {
-    let entity = private::Entity::deserialize(deserializer)?;
-    Ok(Box::new(Entity::from_id(entity.0)))
+    let id = u32::deserialize(deserializer)?;
+    Ok(Box::new(Entity::from_id(id)))
 }
@@ -1,12 +1,48 @@
-use crate::{property_serde::Serializable, Property, PropertyType, PropertyTypeRegistry};
-use serde::Serialize;
+use crate::{
+    property_serde::{SeqSerializer, Serializable},
+    Properties, Property, PropertyIter, PropertyType, PropertyTypeRegistry,
+};
 use smallvec::{Array, SmallVec};
 use std::any::Any;
 
+impl<T, I> Properties for SmallVec<T>
+where
+    T: Clone + Send + Sync + 'static + Array<Item = I>,
+    I: Property + Clone,
+{
+    fn prop(&self, _name: &str) -> Option<&dyn Property> {
+        None
+    }
+
+    fn prop_mut(&mut self, _name: &str) -> Option<&mut dyn Property> {
+        None
+    }
+
+    fn prop_with_index(&self, index: usize) -> Option<&dyn Property> {
+        Some(&self[index])
+    }
+
+    fn prop_with_index_mut(&mut self, index: usize) -> Option<&mut dyn Property> {
+        Some(&mut self[index])
+    }
+
+    fn prop_name(&self, _index: usize) -> Option<&str> {
+        None
+    }
+
+    fn prop_len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter_props(&self) -> PropertyIter {
+        PropertyIter::new(self)
+    }
+}
+
 impl<T, I> Property for SmallVec<T>
 where
-    T: Clone + Send + Sync + Serialize + 'static + Array<Item = I>,
-    I: Send + Sync + Clone + Serialize + 'static,
+    T: Clone + Send + Sync + 'static + Array<Item = I>,
+    I: Property + Clone,
 {
     #[inline]
     fn type_name(&self) -> &str {
@@ -34,17 +70,42 @@ where
     }
 
     fn set(&mut self, value: &dyn Property) {
-        let value = value.any();
-        if let Some(prop) = value.downcast_ref::<Self>() {
+        if let Some(prop) = value.any().downcast_ref::<Self>() {
             *self = prop.clone();
+        } else if let Some(properties) = value.as_properties() {
+            if properties.property_type() != self.property_type() {
+                panic!(
+                    "Properties type mismatch. This type is {:?} but the applied type is {:?}",
+                    self.property_type(),
+                    properties.property_type()
+                );
+            }
+
+            // items that don't exist yet are cloned from the applied values, as there is no default item to apply them to
+            self.truncate(properties.prop_len());
+            for (i, prop) in properties.iter_props().enumerate() {
+                if let Some(item) = self.get_mut(i) {
+                    item.apply(prop);
+                } else if let Some(item) = prop.any().downcast_ref::<I>() {
+                    self.push(item.clone());
+                } else {
+                    panic!("prop value is not {}", std::any::type_name::<I>());
+                }
+            }
+        } else {
+            panic!("attempted to apply non-Properties type to Properties type");
         }
     }
 
-    fn serializable<'a>(&'a self, _registry: &'a PropertyTypeRegistry) -> Serializable<'a> {
-        Serializable::Borrowed(self)
+    fn as_properties(&self) -> Option<&dyn Properties> {
+        Some(self)
+    }
+
+    fn serializable<'a>(&'a self, registry: &'a PropertyTypeRegistry) -> Serializable<'a> {
+        Serializable::Owned(Box::new(SeqSerializer::new(self, registry)))
     }
 
     fn property_type(&self) -> PropertyType {
-        PropertyType::Value
+        PropertyType::Seq
     }
 }
@@ -1,9 +1,13 @@
 use super::CameraProjection;
+use crate::texture::Texture;
 use bevy_app::prelude::{EventReader, Events};
-use bevy_ecs::{Component, Local, Query, Res};
-use bevy_math::Mat4;
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Changed, Component, Entity, Local, Query, Res};
+use bevy_math::{Mat4, Vec2, Vec3};
 use bevy_property::Properties;
+use bevy_transform::prelude::Transform;
 use bevy_window::{WindowCreated, WindowId, WindowResized, Windows};
+use std::collections::HashMap;
 
 #[derive(Default, Debug, Properties)]
 pub struct Camera {
@@ -13,6 +17,119 @@ pub struct Camera {
     pub window: WindowId,
     #[property(ignore)]
     pub depth_calculation: DepthCalculation,
+    /// The region of the window the camera renders to. `None` renders to the whole window.
+    #[property(ignore)]
+    pub viewport: Option<Viewport>,
+    /// Renders into this texture instead of `window`. The camera needs a unique name, and the texture should be
+    /// created with [Texture::new_render_target]. Materials and UI images can use the texture like any other, but the
+    /// camera must not see the entities that show its own texture, see [RenderLayers].
+    #[property(ignore)]
+    pub render_target: Option<Handle<Texture>>,
+}
+
+impl Camera {
+    /// Returns the region of the window the camera renders to, or `None` if the window doesn't exist
+    pub fn window_viewport(&self, windows: &Windows) -> Option<Viewport> {
+        let window = windows.get(self.window)?;
+        Some(self.viewport.unwrap_or_else(|| {
+            Viewport::new(
+                Vec2::zero(),
+                Vec2::new(window.width as f32, window.height as f32),
+            )
+        }))
+    }
+
+    /// Projects a world position into the camera's window, in physical pixels from the bottom left corner of the
+    /// window. Positions outside of the camera's view are projected too, but positions behind the camera return
+    /// `None`.
+    pub fn world_to_screen(
+        &self,
+        windows: &Windows,
+        camera_transform: &Transform,
+        world_position: Vec3,
+    ) -> Option<Vec2> {
+        let viewport = self.window_viewport(windows)?;
+        let clip =
+            self.projection_matrix * camera_transform.value.inverse() * world_position.extend(1.0);
+        if clip.w() <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate().truncate() / clip.w();
+        Some(viewport.position + (ndc + Vec2::one()) / 2.0 * viewport.size)
+    }
+
+    /// Returns the ray from the camera through a position in its window, in physical pixels from the bottom left corner
+    /// of the window like [CursorMoved](bevy_window::CursorMoved) positions. The ray starts on the camera's near plane.
+    pub fn screen_to_world_ray(
+        &self,
+        windows: &Windows,
+        camera_transform: &Transform,
+        screen_position: Vec2,
+    ) -> Option<Ray> {
+        let viewport = self.window_viewport(windows)?;
+        let ndc = (screen_position - viewport.position) / viewport.size * 2.0 - Vec2::one();
+        let ndc_to_world = camera_transform.value * self.projection_matrix.inverse();
+        let unproject = |depth: f32| {
+            let position = ndc_to_world * ndc.extend(depth).extend(1.0);
+            Vec3::from(position.truncate()) / position.w()
+        };
+        let near = unproject(0.0);
+        let far = unproject(1.0);
+        let direction = far - near;
+        if !direction.length_squared().is_normal() {
+            return None;
+        }
+
+        Some(Ray {
+            origin: near,
+            direction: direction.normalize(),
+        })
+    }
+}
+
+/// A half-line in world space, ex: the ray under the cursor returned by [Camera::screen_to_world_ray]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// A unit vector
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Returns the point `distance` along the ray
+    pub fn point_at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Returns the distance along the ray to the plane through `plane_origin` with `plane_normal`, or `None` if the ray
+    /// is parallel to the plane or points away from it
+    pub fn intersect_plane(&self, plane_origin: Vec3, plane_normal: Vec3) -> Option<f32> {
+        let denominator = self.direction.dot(plane_normal);
+        if denominator.abs() <= std::f32::EPSILON {
+            return None;
+        }
+
+        let distance = (plane_origin - self.origin).dot(plane_normal) / denominator;
+        if distance >= 0.0 {
+            Some(distance)
+        } else {
+            None
+        }
+    }
+}
+
+/// A region of a window, in physical pixels from the bottom left corner of the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Viewport {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Viewport { position, size }
+    }
 }
 
 #[derive(Debug)]
@@ -31,6 +148,8 @@ impl Default for DepthCalculation {
 pub struct CameraSystemState {
     window_resized_event_reader: EventReader<WindowResized>,
     window_created_event_reader: EventReader<WindowCreated>,
+    viewports: HashMap<Entity, Option<Viewport>>,
+    render_target_sizes: HashMap<Entity, Vec2>,
 }
 
 pub fn camera_system<T: CameraProjection + Component>(
@@ -38,7 +157,9 @@ pub fn camera_system<T: CameraProjection + Component>(
     window_resized_events: Res<Events<WindowResized>>,
     window_created_events: Res<Events<WindowCreated>>,
     windows: Res<Windows>,
-    mut query: Query<(&mut Camera, &mut T)>,
+    textures: Res<Assets<Texture>>,
+    mut changed_query: Query<(Entity, Changed<T>)>,
+    mut query: Query<(Entity, &mut Camera, &mut T)>,
 ) {
     let mut changed_window_ids = Vec::new();
     // handle resize events. latest events are handled first because we only want to resize each window once
@@ -67,13 +188,96 @@ pub fn camera_system<T: CameraProjection + Component>(
         changed_window_ids.push(event.id);
     }
 
-    for (mut camera, mut camera_projection) in &mut query.iter() {
-        if let Some(window) = windows.get(camera.window) {
-            if changed_window_ids.contains(&window.id) {
-                camera_projection.update(window.width as usize, window.height as usize);
-                camera.projection_matrix = camera_projection.get_projection_matrix();
-                camera.depth_calculation = camera_projection.depth_calculation();
+    // projections that were added or changed (for example zoomed) need to be recomputed too
+    let changed_projections = changed_query
+        .iter()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    for (entity, mut camera, mut camera_projection) in &mut query.iter() {
+        let projection_changed = changed_projections.contains(&entity);
+        let (width, height) = if let Some(render_target) = camera.render_target {
+            let size = match textures.get(&render_target) {
+                Some(texture) => texture.size,
+                None => continue,
+            };
+            if state.render_target_sizes.get(&entity) == Some(&size) && !projection_changed {
+                continue;
             }
-        }
+            state.render_target_sizes.insert(entity, size);
+            (size.x() as usize, size.y() as usize)
+        } else {
+            let viewport_changed = state.viewports.get(&entity) != Some(&camera.viewport);
+            let window = match windows.get(camera.window) {
+                Some(window) => window,
+                None => continue,
+            };
+            if !changed_window_ids.contains(&window.id) && !viewport_changed && !projection_changed
+            {
+                continue;
+            }
+            state.viewports.insert(entity, camera.viewport);
+            match camera.viewport {
+                Some(viewport) => (viewport.size.x() as usize, viewport.size.y() as usize),
+                None => (window.width as usize, window.height as usize),
+            }
+        };
+
+        camera_projection.update(width, height);
+        camera.projection_matrix = camera_projection.get_projection_matrix();
+        camera.depth_calculation = camera_projection.depth_calculation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+    use bevy_math::{Mat4, Vec2, Vec3};
+    use bevy_transform::prelude::Transform;
+    use bevy_window::{Window, WindowDescriptor, WindowId, Windows};
+
+    #[test]
+    fn world_to_screen() {
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: 800,
+                height: 600,
+                ..Default::default()
+            },
+        ));
+        let camera = Camera {
+            projection_matrix: Mat4::perspective_rh(
+                std::f32::consts::FRAC_PI_2,
+                800.0 / 600.0,
+                1.0,
+                100.0,
+            ),
+            ..Default::default()
+        };
+        let transform = Transform::new(Mat4::from_translation(Vec3::new(0.0, 0.0, 10.0)));
+
+        let center = camera.world_to_screen(&windows, &transform, Vec3::zero());
+        assert_eq!(center, Some(Vec2::new(400.0, 300.0)));
+        let right = camera
+            .world_to_screen(&windows, &transform, Vec3::new(10.0, 0.0, 0.0))
+            .unwrap();
+        assert!((right - Vec2::new(700.0, 300.0)).length() < 1e-3);
+        assert_eq!(
+            camera.world_to_screen(&windows, &transform, Vec3::new(0.0, 0.0, 20.0)),
+            None
+        );
+
+        let ray = camera
+            .screen_to_world_ray(&windows, &transform, right)
+            .unwrap();
+        let distance = ray.intersect_plane(Vec3::zero(), Vec3::unit_z()).unwrap();
+        assert!((ray.point_at(distance) - Vec3::new(10.0, 0.0, 0.0)).length() < 1e-3);
+        assert_eq!(
+            ray.intersect_plane(Vec3::new(0.0, 0.0, 20.0), Vec3::unit_z()),
+            None
+        );
     }
 }
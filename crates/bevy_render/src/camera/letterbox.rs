@@ -0,0 +1,125 @@
+use super::{Camera, Viewport};
+use bevy_ecs::{Query, Res};
+use bevy_math::Vec2;
+use bevy_window::Windows;
+
+/// Keeps the view of a camera at a fixed aspect ratio, so the composition of the game is the same on every monitor.
+/// [letterbox_system] gives the camera the largest viewport with that aspect ratio, centered in its window. Windows
+/// that are wider than the aspect ratio get bars on the left and right (pillarboxing), and taller windows get bars on
+/// the top and bottom (letterboxing). The bars are cleared to the [ClearColor](crate::pass::ClearColor).
+///
+/// The viewport follows window resizes. A camera shouldn't have both a `Letterbox` and a
+/// [SplitScreenCamera](super::SplitScreenCamera).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Letterbox {
+    /// Width divided by height, ex: 16.0 / 9.0
+    pub aspect_ratio: f32,
+    /// Scales the view by whole numbers only, ex: for pixel art drawn at this resolution. Windows smaller than this
+    /// size fall back to the largest viewport with the aspect ratio.
+    pub pixel_perfect_size: Option<Vec2>,
+}
+
+impl Letterbox {
+    pub fn new(aspect_ratio: f32) -> Self {
+        Letterbox {
+            aspect_ratio,
+            pixel_perfect_size: None,
+        }
+    }
+
+    /// Keeps the aspect ratio of `width` x `height` and only scales the view by whole numbers
+    pub fn pixel_perfect(width: f32, height: f32) -> Self {
+        Letterbox {
+            aspect_ratio: width / height,
+            pixel_perfect_size: Some(Vec2::new(width, height)),
+        }
+    }
+
+    /// Returns the viewport in a window of the given size, in physical pixels
+    pub fn viewport(&self, window_size: Vec2) -> Viewport {
+        if window_size.x() <= 0.0 || window_size.y() <= 0.0 || self.aspect_ratio <= 0.0 {
+            return Viewport::new(Vec2::zero(), window_size);
+        }
+
+        let scale = self
+            .pixel_perfect_size
+            .map(|size| {
+                (window_size.x() / size.x())
+                    .min(window_size.y() / size.y())
+                    .floor()
+            })
+            .unwrap_or(0.0);
+        let size = if scale >= 1.0 {
+            self.pixel_perfect_size.unwrap() * scale
+        } else if window_size.x() / window_size.y() > self.aspect_ratio {
+            Vec2::new(
+                (window_size.y() * self.aspect_ratio).round(),
+                window_size.y(),
+            )
+        } else {
+            Vec2::new(
+                window_size.x(),
+                (window_size.x() / self.aspect_ratio).round(),
+            )
+        };
+        let position = ((window_size - size) / 2.0).floor();
+        Viewport::new(position, size)
+    }
+}
+
+/// Assigns viewports to cameras with a [Letterbox]
+pub fn letterbox_system(windows: Res<Windows>, mut query: Query<(&Letterbox, &mut Camera)>) {
+    for (letterbox, mut camera) in &mut query.iter() {
+        let window = match windows.get(camera.window) {
+            Some(window) => window,
+            None => continue,
+        };
+        let viewport =
+            Some(letterbox.viewport(Vec2::new(window.width as f32, window.height as f32)));
+        // avoid mutating cameras whose viewport didn't change
+        if camera.viewport != viewport {
+            camera.viewport = viewport;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Letterbox, Viewport};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn letterbox_viewport() {
+        let letterbox = Letterbox::new(16.0 / 9.0);
+        // a 16:9 window is filled
+        assert_eq!(
+            letterbox.viewport(Vec2::new(1920.0, 1080.0)),
+            Viewport::new(Vec2::zero(), Vec2::new(1920.0, 1080.0))
+        );
+        // letterbox in a 4:3 window
+        assert_eq!(
+            letterbox.viewport(Vec2::new(1600.0, 1200.0)),
+            Viewport::new(Vec2::new(0.0, 150.0), Vec2::new(1600.0, 900.0))
+        );
+        // pillarbox in an ultrawide window
+        assert_eq!(
+            letterbox.viewport(Vec2::new(2560.0, 1080.0)),
+            Viewport::new(Vec2::new(320.0, 0.0), Vec2::new(1920.0, 1080.0))
+        );
+
+        let pixel_perfect = Letterbox::pixel_perfect(320.0, 180.0);
+        assert_eq!(
+            pixel_perfect.viewport(Vec2::new(1600.0, 1200.0)),
+            Viewport::new(Vec2::new(0.0, 150.0), Vec2::new(1600.0, 900.0))
+        );
+        assert_eq!(
+            pixel_perfect.viewport(Vec2::new(1366.0, 768.0)),
+            Viewport::new(Vec2::new(43.0, 24.0), Vec2::new(1280.0, 720.0))
+        );
+        // too small for a whole number scale
+        assert_eq!(
+            pixel_perfect.viewport(Vec2::new(160.0, 160.0)),
+            Viewport::new(Vec2::new(0.0, 35.0), Vec2::new(160.0, 90.0))
+        );
+    }
+}
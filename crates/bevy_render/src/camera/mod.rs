@@ -1,9 +1,19 @@
 mod active_cameras;
 mod camera;
+mod letterbox;
 mod projection;
+mod render_layers;
+mod render_target;
+mod split_screen;
 mod visible_entities;
+mod window_graph;
 
 pub use active_cameras::*;
 pub use camera::*;
+pub use letterbox::*;
 pub use projection::*;
+pub use render_layers::*;
+pub use render_target::*;
+pub use split_screen::*;
 pub use visible_entities::*;
+pub use window_graph::*;
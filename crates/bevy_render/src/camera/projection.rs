@@ -49,6 +49,17 @@ pub enum WindowOrigin {
     BottomLeft,
 }
 
+/// Decides how much of the world an [OrthographicProjection] shows for a given window size
+#[derive(Debug, Clone, Property, Serialize, Deserialize)]
+pub enum ScalingMode {
+    /// One world unit is one pixel, so resizing the window changes how much of the world is visible
+    WindowSize,
+    /// Always shows this many world units vertically. The visible width follows the aspect ratio of the window.
+    FixedVertical(f32),
+    /// Always shows this many world units horizontally. The visible height follows the aspect ratio of the window.
+    FixedHorizontal(f32),
+}
+
 #[derive(Debug, Clone, Properties)]
 pub struct OrthographicProjection {
     pub left: f32,
@@ -58,6 +69,9 @@ pub struct OrthographicProjection {
     pub near: f32,
     pub far: f32,
     pub window_origin: WindowOrigin,
+    pub scaling_mode: ScalingMode,
+    /// Multiplies the size of the visible area. Values below 1.0 zoom in, values above 1.0 zoom out.
+    pub scale: f32,
 }
 
 impl CameraProjection for OrthographicProjection {
@@ -73,10 +87,22 @@ impl CameraProjection for OrthographicProjection {
     }
 
     fn update(&mut self, width: usize, height: usize) {
+        // minimized windows have a size of zero
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+        let (width, height) = match self.scaling_mode {
+            ScalingMode::WindowSize => (width, height),
+            ScalingMode::FixedVertical(visible_height) => {
+                (width / height * visible_height, visible_height)
+            }
+            ScalingMode::FixedHorizontal(visible_width) => {
+                (visible_width, height / width * visible_width)
+            }
+        };
+        let (width, height) = (width * self.scale, height * self.scale);
         match self.window_origin {
             WindowOrigin::Center => {
-                let half_width = width as f32 / 2.0;
-                let half_height = height as f32 / 2.0;
+                let half_width = width / 2.0;
+                let half_height = height / 2.0;
                 self.left = -half_width;
                 self.right = half_width;
                 self.top = half_height;
@@ -84,8 +110,8 @@ impl CameraProjection for OrthographicProjection {
             }
             WindowOrigin::BottomLeft => {
                 self.left = 0.0;
-                self.right = width as f32;
-                self.top = height as f32;
+                self.right = width;
+                self.top = height;
                 self.bottom = 0.0;
             }
         }
@@ -106,6 +132,58 @@ impl Default for OrthographicProjection {
             near: 0.0,
             far: 1000.0,
             window_origin: WindowOrigin::Center,
+            scaling_mode: ScalingMode::WindowSize,
+            scale: 1.0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CameraProjection, OrthographicProjection, ScalingMode, WindowOrigin};
+
+    #[test]
+    fn orthographic_scaling_modes() {
+        let mut projection = OrthographicProjection::default();
+        projection.update(800, 600);
+        assert_eq!(
+            (
+                projection.left,
+                projection.right,
+                projection.bottom,
+                projection.top
+            ),
+            (-400.0, 400.0, -300.0, 300.0)
+        );
+
+        projection.scale = 0.5;
+        projection.update(800, 600);
+        assert_eq!((projection.right, projection.top), (200.0, 150.0));
+
+        let mut projection = OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical(10.0),
+            ..Default::default()
+        };
+        projection.update(800, 400);
+        assert_eq!((projection.right, projection.top), (10.0, 5.0));
+        // resizing the window keeps the visible height
+        projection.update(400, 400);
+        assert_eq!((projection.right, projection.top), (5.0, 5.0));
+
+        let mut projection = OrthographicProjection {
+            scaling_mode: ScalingMode::FixedHorizontal(20.0),
+            window_origin: WindowOrigin::BottomLeft,
+            ..Default::default()
+        };
+        projection.update(800, 400);
+        assert_eq!(
+            (
+                projection.left,
+                projection.right,
+                projection.bottom,
+                projection.top
+            ),
+            (0.0, 20.0, 0.0, 10.0)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use bevy_property::Properties;
+
+/// The layers an entity belongs to, as a bitmask of up to 32 layers. Cameras only draw entities that share at least
+/// one layer with them. Cameras and entities without this component are on layer 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Properties)]
+pub struct RenderLayers {
+    pub mask: u32,
+}
+
+impl RenderLayers {
+    pub const TOTAL_LAYERS: u8 = 32;
+
+    /// Returns layers containing only the given layer
+    pub fn layer(layer: u8) -> Self {
+        RenderLayers { mask: 0 }.with(layer)
+    }
+
+    /// Returns layers containing all layers
+    pub fn all() -> Self {
+        RenderLayers { mask: u32::MAX }
+    }
+
+    /// Returns layers containing no layers. Entities on no layers are never drawn.
+    pub fn none() -> Self {
+        RenderLayers { mask: 0 }
+    }
+
+    /// Adds the given layer
+    pub fn with(mut self, layer: u8) -> Self {
+        assert!(
+            layer < Self::TOTAL_LAYERS,
+            "layer {} is out of range, there are {} layers",
+            layer,
+            Self::TOTAL_LAYERS
+        );
+        self.mask |= 1 << layer;
+        self
+    }
+
+    /// Removes the given layer
+    pub fn without(mut self, layer: u8) -> Self {
+        if layer < Self::TOTAL_LAYERS {
+            self.mask &= !(1 << layer);
+        }
+        self
+    }
+
+    /// Returns true if the layers share at least one layer
+    pub fn intersects(&self, other: &RenderLayers) -> bool {
+        self.mask & other.mask != 0
+    }
+}
+
+impl Default for RenderLayers {
+    fn default() -> Self {
+        RenderLayers::layer(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderLayers;
+
+    #[test]
+    fn render_layers() {
+        assert_eq!(RenderLayers::default().mask, 1);
+        assert_eq!(RenderLayers::layer(1).with(3).mask, 0b1010);
+        assert_eq!(RenderLayers::layer(1).with(3).without(1).mask, 0b1000);
+
+        let ui = RenderLayers::layer(1);
+        assert!(!ui.intersects(&RenderLayers::default()));
+        assert!(ui.intersects(&RenderLayers::default().with(1)));
+        assert!(ui.intersects(&RenderLayers::all()));
+        assert!(!RenderLayers::none().intersects(&RenderLayers::all()));
+    }
+}
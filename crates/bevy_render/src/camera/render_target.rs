@@ -0,0 +1,114 @@
+use super::{ActiveCameras, Camera};
+use crate::{
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+    },
+    render_graph::{
+        base::{self, MainPass},
+        CameraNode, PassNode, RenderGraph, TextureTargetNode,
+    },
+    texture::Texture,
+    Color,
+};
+use bevy_asset::Handle;
+use bevy_ecs::{Entity, Resources, World};
+use std::collections::HashSet;
+
+/// Adds the render graph nodes that draw a camera into a [Texture] asset
+pub trait RenderTargetGraphBuilder {
+    fn add_render_target_graph(&mut self, camera_name: &str, texture: Handle<Texture>)
+        -> &mut Self;
+}
+
+impl RenderTargetGraphBuilder for RenderGraph {
+    fn add_render_target_graph(
+        &mut self,
+        camera_name: &str,
+        texture: Handle<Texture>,
+    ) -> &mut Self {
+        let camera = format!("{}_camera", camera_name);
+        let target = format!("{}_render_target", camera_name);
+        let pass = format!("{}_pass", camera_name);
+
+        let mut pass_node = PassNode::<&MainPass>::new(PassDescriptor {
+            color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                attachment: TextureAttachment::Input("color_attachment".to_string()),
+                resolve_target: Some(TextureAttachment::Input("color_resolve_target".to_string())),
+                ops: Operations {
+                    load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                attachment: TextureAttachment::Input("depth".to_string()),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: 1,
+        });
+        pass_node.use_default_clear_color(0);
+        pass_node.use_msaa();
+        pass_node.add_camera(camera_name);
+
+        // the pass has the same dependencies as the main pass (buffers, lights, ...). it runs before the main pass, so
+        // the main pass can show its texture in the same frame
+        let main_pass_inputs = self
+            .iter_node_inputs(base::node::MAIN_PASS)
+            .map(|inputs| inputs.map(|(_edge, node)| node.id).collect::<HashSet<_>>())
+            .unwrap_or_default();
+
+        self.add_system_node(camera.clone(), CameraNode::new(camera_name.to_string()));
+        self.add_node(target.clone(), TextureTargetNode::new(texture));
+        self.add_node(pass.clone(), pass_node);
+        for (output, input) in [
+            (TextureTargetNode::OUT_COLOR_ATTACHMENT, "color_attachment"),
+            (
+                TextureTargetNode::OUT_COLOR_RESOLVE_TARGET,
+                "color_resolve_target",
+            ),
+            (TextureTargetNode::OUT_DEPTH, "depth"),
+        ]
+        .iter()
+        {
+            self.add_slot_edge(target.clone(), *output, pass.clone(), *input)
+                .unwrap();
+        }
+        self.add_node_edge(camera, pass.clone()).unwrap();
+        for node in main_pass_inputs {
+            self.add_node_edge(node, target.clone()).unwrap();
+            self.add_node_edge(node, pass.clone()).unwrap();
+        }
+        if self.get_node_id(base::node::MAIN_PASS).is_ok() {
+            self.add_node_edge(pass, base::node::MAIN_PASS).unwrap();
+        }
+
+        self
+    }
+}
+
+/// Gives each camera with a [Camera::render_target] its own pass, the first time it has one. Cameras without a name
+/// are named `RenderTarget{entity}`.
+pub fn render_target_graph_system(world: &mut World, resources: &mut Resources) {
+    let mut active_cameras = resources.get_mut::<ActiveCameras>().unwrap();
+    let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
+    for (entity, mut camera) in &mut world.query::<(Entity, &mut Camera)>() {
+        let texture = match camera.render_target {
+            Some(texture) => texture,
+            None => continue,
+        };
+        let camera_name = camera
+            .name
+            .get_or_insert_with(|| format!("RenderTarget{}", entity.id()))
+            .clone();
+        if active_cameras.cameras.contains_key(&camera_name) {
+            continue;
+        }
+
+        active_cameras.add(&camera_name);
+        render_graph.add_render_target_graph(&camera_name, texture);
+    }
+}
@@ -0,0 +1,223 @@
+use super::{ActiveCameras, Camera, Viewport};
+use crate::render_graph::{
+    base::{self, MainPass},
+    CameraNode, PassNode, RenderGraph,
+};
+use bevy_ecs::{Query, Res, Resources, World};
+use bevy_math::Vec2;
+use bevy_window::{WindowId, Windows};
+use std::collections::HashMap;
+
+/// Marks a camera as the view of a local player. [split_screen_system] gives the cameras of each window a viewport
+/// according to the [SplitScreen] resource, in order of `player`.
+///
+/// Each player camera is drawn by the main pass, so it needs a unique name. Cameras without a name are named
+/// `SplitScreenPlayer{player}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitScreenCamera {
+    pub player: usize,
+}
+
+impl SplitScreenCamera {
+    pub fn new(player: usize) -> Self {
+        SplitScreenCamera { player }
+    }
+}
+
+/// How the viewports of split screen players are arranged. Players are laid out in reading order, starting at the top
+/// left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitScreenLayout {
+    /// Two players are side by side in landscape windows and stacked in portrait windows. More players are laid out
+    /// in a grid with as many columns as rows (or one more).
+    Auto,
+    /// Players are stacked on top of each other
+    Horizontal,
+    /// Players are side by side
+    Vertical,
+    Grid {
+        columns: usize,
+    },
+}
+
+impl Default for SplitScreenLayout {
+    fn default() -> Self {
+        SplitScreenLayout::Auto
+    }
+}
+
+impl SplitScreenLayout {
+    /// Returns the viewports of `count` players in a window of the given size. `gap` is the space between viewports.
+    /// All sizes are in physical pixels.
+    pub fn viewports(&self, count: usize, window_size: Vec2, gap: f32) -> Vec<Viewport> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let columns = match *self {
+            SplitScreenLayout::Auto if count == 2 => {
+                if window_size.x() >= window_size.y() {
+                    2
+                } else {
+                    1
+                }
+            }
+            SplitScreenLayout::Auto => (count as f32).sqrt().ceil() as usize,
+            SplitScreenLayout::Horizontal => 1,
+            SplitScreenLayout::Vertical => count,
+            SplitScreenLayout::Grid { columns } => columns.max(1).min(count),
+        };
+        let rows = (count + columns - 1) / columns;
+        let size = Vec2::new(
+            ((window_size.x() - gap * (columns - 1) as f32) / columns as f32).max(0.0),
+            ((window_size.y() - gap * (rows - 1) as f32) / rows as f32).max(0.0),
+        );
+
+        (0..count)
+            .map(|index| {
+                let column = index % columns;
+                let row = index / columns;
+                Viewport::new(
+                    Vec2::new(
+                        column as f32 * (size.x() + gap),
+                        window_size.y() - (row + 1) as f32 * size.y() - row as f32 * gap,
+                    ),
+                    size,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Configures the viewports of [SplitScreenCamera]s
+#[derive(Debug, Clone, Default)]
+pub struct SplitScreen {
+    pub layout: SplitScreenLayout,
+    /// The space between viewports in physical pixels
+    pub gap: f32,
+}
+
+impl SplitScreen {
+    /// Returns the viewport of each player camera, grouped by window. Windows that don't exist are skipped.
+    pub fn viewports(
+        &self,
+        windows: &Windows,
+        cameras: impl Iterator<Item = (SplitScreenCamera, WindowId)>,
+    ) -> HashMap<(WindowId, usize), Viewport> {
+        let mut players = HashMap::<WindowId, Vec<usize>>::new();
+        for (split_screen_camera, window_id) in cameras {
+            players
+                .entry(window_id)
+                .or_default()
+                .push(split_screen_camera.player);
+        }
+
+        let mut viewports = HashMap::new();
+        for (window_id, mut players) in players {
+            let window = match windows.get(window_id) {
+                Some(window) => window,
+                None => continue,
+            };
+            players.sort_unstable();
+            players.dedup();
+            let window_size = Vec2::new(window.width as f32, window.height as f32);
+            for (player, viewport) in players.iter().zip(
+                self.layout
+                    .viewports(players.len(), window_size, self.gap)
+                    .into_iter(),
+            ) {
+                viewports.insert((window_id, *player), viewport);
+            }
+        }
+        viewports
+    }
+}
+
+/// Assigns viewports to [SplitScreenCamera]s. Runs every frame, so viewports follow window resizes and players joining
+/// or leaving.
+pub fn split_screen_system(
+    split_screen: Res<SplitScreen>,
+    windows: Res<Windows>,
+    mut query: Query<(&SplitScreenCamera, &mut Camera)>,
+) {
+    let cameras = (&mut query.iter())
+        .into_iter()
+        .map(|(split_screen_camera, camera)| (*split_screen_camera, camera.window))
+        .collect::<Vec<_>>();
+    let viewports = split_screen.viewports(&windows, cameras.into_iter());
+
+    for (split_screen_camera, mut camera) in &mut query.iter() {
+        let viewport = viewports
+            .get(&(camera.window, split_screen_camera.player))
+            .cloned();
+        // avoid mutating cameras whose viewport didn't change
+        if camera.viewport != viewport {
+            camera.viewport = viewport;
+        }
+    }
+}
+
+/// Adds [SplitScreenCamera]s to the main pass the first time they are seen
+pub fn split_screen_graph_system(world: &mut World, resources: &mut Resources) {
+    let mut active_cameras = resources.get_mut::<ActiveCameras>().unwrap();
+    let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
+    for (split_screen_camera, mut camera) in &mut world.query::<(&SplitScreenCamera, &mut Camera)>()
+    {
+        let camera_name = camera
+            .name
+            .get_or_insert_with(|| format!("SplitScreenPlayer{}", split_screen_camera.player))
+            .clone();
+        if active_cameras.cameras.contains_key(&camera_name) {
+            continue;
+        }
+
+        active_cameras.add(&camera_name);
+        let camera_node = format!("{}_camera", camera_name);
+        render_graph.add_system_node(camera_node.clone(), CameraNode::new(camera_name.clone()));
+        if let Ok(main_pass) =
+            render_graph.get_node_mut::<PassNode<&MainPass>>(base::node::MAIN_PASS)
+        {
+            main_pass.add_camera(&camera_name);
+            render_graph
+                .add_node_edge(camera_node, base::node::MAIN_PASS)
+                .unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SplitScreenLayout, Viewport};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn split_screen_viewports() {
+        let window_size = Vec2::new(1280.0, 720.0);
+        assert_eq!(
+            SplitScreenLayout::Auto.viewports(1, window_size, 0.0),
+            vec![Viewport::new(Vec2::zero(), window_size)]
+        );
+        assert_eq!(
+            SplitScreenLayout::Auto.viewports(2, window_size, 0.0),
+            vec![
+                Viewport::new(Vec2::new(0.0, 0.0), Vec2::new(640.0, 720.0)),
+                Viewport::new(Vec2::new(640.0, 0.0), Vec2::new(640.0, 720.0)),
+            ]
+        );
+        assert_eq!(
+            SplitScreenLayout::Horizontal.viewports(2, window_size, 20.0),
+            vec![
+                Viewport::new(Vec2::new(0.0, 370.0), Vec2::new(1280.0, 350.0)),
+                Viewport::new(Vec2::new(0.0, 0.0), Vec2::new(1280.0, 350.0)),
+            ]
+        );
+
+        // three players use a 2x2 grid, starting at the top left
+        let viewports = SplitScreenLayout::Auto.viewports(3, window_size, 0.0);
+        assert_eq!(viewports.len(), 3);
+        assert_eq!(viewports[0].position, Vec2::new(0.0, 360.0));
+        assert_eq!(viewports[1].position, Vec2::new(640.0, 360.0));
+        assert_eq!(viewports[2].position, Vec2::new(0.0, 0.0));
+        assert_eq!(viewports[2].size, Vec2::new(640.0, 360.0));
+    }
+}
@@ -1,9 +1,15 @@
-use super::{Camera, DepthCalculation};
+use super::{Camera, DepthCalculation, RenderLayers};
 use crate::Draw;
 use bevy_core::FloatOrd;
 use bevy_ecs::{Entity, Query};
-use bevy_transform::prelude::Transform;
 use bevy_property::Properties;
+use bevy_transform::prelude::Transform;
+use bevy_window::WindowId;
+
+/// Restricts drawing an entity to the cameras of one window, ex: ui nodes that are laid out in that window. Entities
+/// without this component are drawn by the cameras of every window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetWindow(pub WindowId);
 
 #[derive(Debug)]
 pub struct VisibleEntity {
@@ -24,12 +30,19 @@ impl VisibleEntities {
 }
 
 pub fn visible_entities_system(
-    mut camera_query: Query<(&Camera, &Transform, &mut VisibleEntities)>,
+    mut camera_query: Query<(Entity, &Camera, &Transform, &mut VisibleEntities)>,
+    camera_layers_query: Query<(&Camera, &RenderLayers)>,
     mut draw_query: Query<(Entity, &Draw)>,
     draw_transform_query: Query<(&Draw, &Transform)>,
+    draw_layers_query: Query<(&Draw, &RenderLayers)>,
+    draw_window_query: Query<(&Draw, &TargetWindow)>,
 ) {
-    for (camera, camera_transform, mut visible_entities) in &mut camera_query.iter() {
+    for (camera_entity, camera, camera_transform, mut visible_entities) in &mut camera_query.iter()
+    {
         visible_entities.value.clear();
+        let camera_layers = camera_layers_query
+            .get::<RenderLayers>(camera_entity)
+            .map_or_else(|_| RenderLayers::default(), |layers| *layers);
         let camera_position = camera_transform.value.w_axis().truncate();
 
         let mut no_transform_order = 0.0;
@@ -39,6 +52,19 @@ pub fn visible_entities_system(
                 continue;
             }
 
+            let layers = draw_layers_query
+                .get::<RenderLayers>(entity)
+                .map_or_else(|_| RenderLayers::default(), |layers| *layers);
+            if !camera_layers.intersects(&layers) {
+                continue;
+            }
+
+            if let Ok(target_window) = draw_window_query.get::<TargetWindow>(entity) {
+                if target_window.0 != camera.window {
+                    continue;
+                }
+            }
+
             let order = if let Ok(transform) = draw_transform_query.get::<Transform>(entity) {
                 let position = transform.value.w_axis().truncate();
                 // smaller distances are sorted to lower indices by using the distance from the camera
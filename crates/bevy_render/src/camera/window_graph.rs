@@ -0,0 +1,213 @@
+use crate::{
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+    },
+    render_graph::{
+        base::{self, MainPass},
+        CameraNode, PassNode, RenderGraph, WindowSwapChainNode, WindowTextureNode,
+    },
+    texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
+    Color,
+};
+use bevy_window::WindowId;
+use std::collections::HashSet;
+
+/// The names of the nodes [WindowGraphBuilder::add_window_graph] adds for a window, see [window_node_name]
+pub mod window_node {
+    pub const SWAP_CHAIN: &str = "swap_chain";
+    pub const DEPTH_TEXTURE: &str = "depth_texture";
+    pub const SAMPLED_COLOR_ATTACHMENT: &str = "sampled_color_attachment";
+    pub const MAIN_PASS: &str = "main_pass";
+}
+
+/// Returns the name of one of the [window_node]s of a window
+pub fn window_node_name(window_id: WindowId, node: &str) -> String {
+    format!("window_{}_{}", window_id.to_string(), node)
+}
+
+/// Adds the render graph nodes that draw cameras into windows other than the primary window, which is set up by the
+/// base graph. Cameras drawn this way need their [Camera::window](super::Camera::window) set, and have to be added to
+/// [ActiveCameras](super::ActiveCameras).
+pub trait WindowGraphBuilder {
+    /// Adds the swap chain, depth texture, and main pass of a window. Does nothing if the window already has them.
+    fn add_window_graph(&mut self, window_id: WindowId) -> &mut Self;
+    /// Draws a camera in the main pass of a window, adding the window's nodes if needed. Cameras in the same window share
+    /// its main pass.
+    fn add_window_camera_graph(&mut self, window_id: WindowId, camera_name: &str) -> &mut Self;
+}
+
+impl WindowGraphBuilder for RenderGraph {
+    fn add_window_graph(&mut self, window_id: WindowId) -> &mut Self {
+        let swap_chain = window_node_name(window_id, window_node::SWAP_CHAIN);
+        let depth_texture = window_node_name(window_id, window_node::DEPTH_TEXTURE);
+        let sampled_color_attachment =
+            window_node_name(window_id, window_node::SAMPLED_COLOR_ATTACHMENT);
+        let pass = window_node_name(window_id, window_node::MAIN_PASS);
+        if self.get_node_id(pass.clone()).is_ok() {
+            return self;
+        }
+
+        let mut depth_texture_node = WindowTextureNode::new(
+            window_id,
+            TextureDescriptor {
+                size: Extent3d {
+                    depth: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+        );
+        depth_texture_node.use_msaa();
+        let mut sampled_color_attachment_node = WindowTextureNode::new(
+            window_id,
+            TextureDescriptor {
+                size: Extent3d {
+                    depth: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+        );
+        sampled_color_attachment_node.only_with_msaa();
+
+        let mut pass_node = PassNode::<&MainPass>::new(PassDescriptor {
+            color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                attachment: TextureAttachment::Input("color_attachment".to_string()),
+                resolve_target: Some(TextureAttachment::Input("color_resolve_target".to_string())),
+                ops: Operations {
+                    load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                attachment: TextureAttachment::Input("depth".to_string()),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: 1,
+        });
+        pass_node.use_default_clear_color(0);
+        pass_node.use_msaa();
+
+        // the pass has the same dependencies as the primary window's main pass (buffers, lights, ...)
+        let main_pass_inputs = self
+            .iter_node_inputs(base::node::MAIN_PASS)
+            .map(|inputs| inputs.map(|(_edge, node)| node.id).collect::<HashSet<_>>())
+            .unwrap_or_default();
+
+        self.add_node(swap_chain.clone(), WindowSwapChainNode::new(window_id));
+        self.add_node(depth_texture.clone(), depth_texture_node);
+        self.add_node(
+            sampled_color_attachment.clone(),
+            sampled_color_attachment_node,
+        );
+        self.add_node(pass.clone(), pass_node);
+        self.add_slot_edge(
+            swap_chain,
+            WindowSwapChainNode::OUT_TEXTURE,
+            pass.clone(),
+            "color_resolve_target",
+        )
+        .unwrap();
+        self.add_slot_edge(
+            sampled_color_attachment,
+            WindowTextureNode::OUT_TEXTURE,
+            pass.clone(),
+            "color_attachment",
+        )
+        .unwrap();
+        self.add_slot_edge(
+            depth_texture,
+            WindowTextureNode::OUT_TEXTURE,
+            pass.clone(),
+            "depth",
+        )
+        .unwrap();
+        for node in main_pass_inputs {
+            self.add_node_edge(node, pass.clone()).unwrap();
+        }
+
+        self
+    }
+
+    fn add_window_camera_graph(&mut self, window_id: WindowId, camera_name: &str) -> &mut Self {
+        self.add_window_graph(window_id);
+        let camera = format!("{}_camera", camera_name);
+        let pass = window_node_name(window_id, window_node::MAIN_PASS);
+        self.get_node_mut::<PassNode<&MainPass>>(pass.clone())
+            .unwrap()
+            .add_camera(camera_name);
+        self.add_system_node(camera.clone(), CameraNode::new(camera_name.to_string()));
+        self.add_node_edge(camera, pass).unwrap();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{window_node, window_node_name, WindowGraphBuilder};
+    use crate::render_graph::{
+        base::{self, MainPass},
+        PassNode, RenderGraph, WindowSwapChainNode,
+    };
+    use bevy_window::WindowId;
+
+    #[test]
+    fn window_camera_graph() {
+        let mut graph = RenderGraph::default();
+        // stand-ins for the primary window's main pass and one of its dependencies
+        let dependency =
+            graph.add_node("dependency", WindowSwapChainNode::new(WindowId::primary()));
+        graph.add_node(
+            base::node::MAIN_PASS,
+            WindowSwapChainNode::new(WindowId::primary()),
+        );
+        graph
+            .add_node_edge("dependency", base::node::MAIN_PASS)
+            .unwrap();
+
+        let window_id = WindowId::new();
+        graph
+            .add_window_camera_graph(window_id, "first")
+            .add_window_camera_graph(window_id, "second");
+        let pass = window_node_name(window_id, window_node::MAIN_PASS);
+        let pass_inputs = graph
+            .iter_node_inputs(pass.clone())
+            .unwrap()
+            .map(|(_edge, node)| node.id)
+            .collect::<Vec<_>>();
+        assert!(pass_inputs.contains(&dependency));
+        let window_nodes = [
+            window_node::SWAP_CHAIN,
+            window_node::DEPTH_TEXTURE,
+            window_node::SAMPLED_COLOR_ATTACHMENT,
+        ]
+        .iter()
+        .map(|node| window_node_name(window_id, node));
+        let camera_nodes = ["first_camera", "second_camera"]
+            .iter()
+            .map(|node| node.to_string());
+        for node in window_nodes.chain(camera_nodes) {
+            assert!(pass_inputs.contains(&graph.get_node_id(node).unwrap()));
+        }
+
+        // both cameras share the window's main pass
+        let pass_node = graph.get_node_mut::<PassNode<&MainPass>>(pass).unwrap();
+        assert!(pass_node.remove_camera("first"));
+        assert!(pass_node.remove_camera("second"));
+    }
+}
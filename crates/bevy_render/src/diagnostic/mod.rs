@@ -0,0 +1,2 @@
+mod render_stats_diagnostics_plugin;
+pub use render_stats_diagnostics_plugin::RenderStatsDiagnosticsPlugin;
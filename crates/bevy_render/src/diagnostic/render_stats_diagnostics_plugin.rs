@@ -0,0 +1,88 @@
+use crate::{
+    render_stats::{BatchBreak, RenderStats},
+    stage,
+};
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+
+/// Adds [RenderStats] diagnostics to an App: draw calls, batches, and how many batches were broken for each
+/// [BatchBreak] reason
+#[derive(Default)]
+pub struct RenderStatsDiagnosticsPlugin;
+
+impl Plugin for RenderStatsDiagnosticsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(Self::setup_system.system())
+            .add_system_to_stage(stage::POST_RENDER, Self::diagnostic_system.system());
+    }
+}
+
+impl RenderStatsDiagnosticsPlugin {
+    pub const DRAW_CALLS: DiagnosticId =
+        DiagnosticId::from_u128(150262591420372958411183838620489826330);
+    pub const BATCHES: DiagnosticId =
+        DiagnosticId::from_u128(276113934211925402196370117640383532167);
+    pub const TEXTURE_BATCH_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(52936216432542818539573208327384911427);
+    pub const MESH_BATCH_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(98307713860398497497713394564934590542);
+    pub const LAYERS_BATCH_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(320148066125858337624419530391245787012);
+    pub const Z_ORDER_BATCH_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(194618472207549116391853316426457419985);
+    pub const CLIP_BATCH_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(233092519418389770785290212914003958839);
+
+    /// The diagnostic that counts batches broken for the given reason
+    pub fn batch_break_diagnostic(batch_break: BatchBreak) -> DiagnosticId {
+        match batch_break {
+            BatchBreak::Texture => Self::TEXTURE_BATCH_BREAKS,
+            BatchBreak::Mesh => Self::MESH_BATCH_BREAKS,
+            BatchBreak::Layers => Self::LAYERS_BATCH_BREAKS,
+            BatchBreak::ZOrder => Self::Z_ORDER_BATCH_BREAKS,
+            BatchBreak::Clip => Self::CLIP_BATCH_BREAKS,
+        }
+    }
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::DRAW_CALLS, "draw_calls", 20));
+        diagnostics.add(Diagnostic::new(Self::BATCHES, "batches", 20));
+        diagnostics.add(Diagnostic::new(
+            Self::TEXTURE_BATCH_BREAKS,
+            "batch_breaks_texture",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::MESH_BATCH_BREAKS,
+            "batch_breaks_mesh",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::LAYERS_BATCH_BREAKS,
+            "batch_breaks_layers",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::Z_ORDER_BATCH_BREAKS,
+            "batch_breaks_z_order",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::CLIP_BATCH_BREAKS,
+            "batch_breaks_clip",
+            20,
+        ));
+    }
+
+    pub fn diagnostic_system(mut diagnostics: ResMut<Diagnostics>, render_stats: Res<RenderStats>) {
+        diagnostics.add_measurement(Self::DRAW_CALLS, render_stats.draw_calls() as f64);
+        diagnostics.add_measurement(Self::BATCHES, render_stats.batches as f64);
+        for batch_break in BatchBreak::ALL.iter() {
+            diagnostics.add_measurement(
+                Self::batch_break_diagnostic(*batch_break),
+                render_stats.batch_breaks(*batch_break) as f64,
+            );
+        }
+    }
+}
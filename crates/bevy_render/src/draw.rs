@@ -12,7 +12,7 @@ use crate::{
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::{
     FetchResource, Query, Res, ResMut, ResourceIndex, ResourceQuery, Resources, SystemId,
-    TypeAccess, UnsafeClone,
+    SystemParam, SystemState, TypeAccess, UnsafeClone, World,
 };
 use bevy_property::Properties;
 use std::{any::TypeId, ops::Range, sync::Arc};
@@ -43,6 +43,11 @@ pub enum RenderCommand {
         base_vertex: i32,
         instances: Range<u32>,
     },
+    DrawIndexedIndirect {
+        buffer: BufferId,
+        offset: u64,
+        count: u32,
+    },
 }
 
 /// A component that indicates how to draw an entity.
@@ -101,6 +106,16 @@ impl Draw {
         });
     }
 
+    /// Draws `count` sets of [DrawIndexedIndirectArgs](crate::pass::DrawIndexedIndirectArgs) stored in `buffer`.
+    /// This allows draw arguments to be generated on the GPU (ex: by a culling pass) instead of recorded here.
+    pub fn draw_indexed_indirect(&mut self, buffer: BufferId, offset: u64, count: u32) {
+        self.render_command(RenderCommand::DrawIndexedIndirect {
+            buffer,
+            offset,
+            count,
+        });
+    }
+
     #[inline]
     pub fn render_command(&mut self, render_command: RenderCommand) {
         self.render_commands.push(render_command);
@@ -147,6 +162,23 @@ impl<'a> ResourceQuery for DrawContext<'a> {
     type Fetch = FetchDrawContext;
 }
 
+impl<'a> SystemParam for DrawContext<'a> {
+    fn init(system_state: &mut SystemState) {
+        system_state.add_resource_query::<DrawContext<'static>>();
+    }
+
+    unsafe fn get_param(
+        system_state: &SystemState,
+        _world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        Some(FetchDrawContext::get(
+            &*(resources as *const Resources),
+            Some(system_state.id()),
+        ))
+    }
+}
+
 pub struct FetchDrawContext;
 
 // TODO: derive this impl
@@ -256,6 +288,20 @@ impl<'a> DrawContext<'a> {
         Ok(())
     }
 
+    /// Returns true if the pipeline and its shaders have been loaded. Shaders that are loaded from files might not be
+    /// available yet.
+    pub fn is_pipeline_loaded(&self, pipeline_handle: Handle<PipelineDescriptor>) -> bool {
+        self.pipelines
+            .get(&pipeline_handle)
+            .map_or(false, |pipeline| {
+                let shader_stages = &pipeline.shader_stages;
+                self.shaders.get(&shader_stages.vertex).is_some()
+                    && shader_stages
+                        .fragment
+                        .map_or(true, |fragment| self.shaders.get(&fragment).is_some())
+            })
+    }
+
     pub fn get_pipeline_descriptor(&self) -> Result<&PipelineDescriptor, DrawError> {
         self.current_pipeline
             .and_then(|handle| self.pipelines.get(&handle))
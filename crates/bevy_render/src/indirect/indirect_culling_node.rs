@@ -0,0 +1,159 @@
+use super::{
+    cull_instances, Frustum, IndirectCullingUniform, IndirectInstances,
+    INDIRECT_CULLING_PIPELINE_HANDLE,
+};
+use crate::{
+    camera::{ActiveCameras, Camera},
+    pass::DrawIndexedIndirectArgs,
+    pipeline::ComputePipelineDescriptor,
+    render_graph::{Node, ResourceSlots},
+    renderer::{BindGroup, RenderContext, RenderResourceBinding},
+    shader::Shader,
+};
+use bevy_asset::Assets;
+use bevy_core::AsBytes;
+use bevy_ecs::{Resources, World};
+use bevy_transform::prelude::Transform;
+use std::borrow::Cow;
+
+/// The number of instances culled by each work group of `indirect_culling.comp`
+const WORK_GROUP_SIZE: u32 = 64;
+
+/// A Render Graph [Node] that culls the [IndirectInstances] of every entity against a camera's view and writes their
+/// indirect draw arguments. Culling runs in a compute pass where compute is supported, and on the cpu otherwise.
+pub struct IndirectCullingNode {
+    camera_name: Cow<'static, str>,
+}
+
+impl IndirectCullingNode {
+    pub fn new<T>(camera_name: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        IndirectCullingNode {
+            camera_name: camera_name.into(),
+        }
+    }
+}
+
+impl Node for IndirectCullingNode {
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        let active_cameras = resources.get::<ActiveCameras>().unwrap();
+        let camera_entity = match active_cameras.get(&self.camera_name) {
+            Some(camera_entity) => camera_entity,
+            None => return,
+        };
+        let view_projection = match (
+            world.get::<Camera>(camera_entity),
+            world.get::<Transform>(camera_entity),
+        ) {
+            (Ok(camera), Ok(transform)) => camera.projection_matrix * transform.value.inverse(),
+            _ => return,
+        };
+        let frustum = Frustum::from_view_projection(&view_projection);
+
+        let bind_group_descriptor = if render_context.resources().supports_compute() {
+            let pipelines = resources
+                .get::<Assets<ComputePipelineDescriptor>>()
+                .unwrap();
+            let shaders = resources.get::<Assets<Shader>>().unwrap();
+            let pipeline = pipelines.get(&INDIRECT_CULLING_PIPELINE_HANDLE).unwrap();
+            render_context.resources().create_compute_pipeline(
+                INDIRECT_CULLING_PIPELINE_HANDLE,
+                pipeline,
+                &shaders,
+            );
+            Some(pipeline.layout.bind_groups[0].id)
+        } else {
+            None
+        };
+
+        for indirect_instances in &mut world.query::<&IndirectInstances>() {
+            let buffers = match indirect_instances.buffers {
+                Some(buffers) => buffers,
+                None => continue,
+            };
+
+            // the compute path only uploads the frustum, the cpu path uploads the culled draw arguments
+            let (data, destination) = if bind_group_descriptor.is_some() {
+                let mut planes = [[0.0; 4]; 6];
+                for (plane, frustum_plane) in planes.iter_mut().zip(frustum.planes.iter()) {
+                    *plane = (*frustum_plane).into();
+                }
+                let uniform = IndirectCullingUniform {
+                    planes,
+                    counts: [buffers.instance_count, buffers.index_count, 0, 0],
+                    bounds: [buffers.radius, 0.0, 0.0, 0.0],
+                };
+                (uniform.as_bytes().to_vec(), buffers.culling)
+            } else {
+                let draw_args = cull_instances(
+                    &frustum,
+                    indirect_instances.instances(),
+                    buffers.radius,
+                    buffers.index_count,
+                );
+                (draw_args.as_bytes().to_vec(), buffers.draw_args)
+            };
+            let render_resource_context = render_context.resources();
+            render_resource_context.map_buffer(buffers.staging);
+            render_resource_context.write_mapped_buffer(
+                buffers.staging,
+                0..data.len() as u64,
+                &mut |mapped, _renderer| {
+                    mapped[0..data.len()].copy_from_slice(&data);
+                },
+            );
+            render_resource_context.unmap_buffer(buffers.staging);
+            render_context.copy_buffer_to_buffer(
+                buffers.staging,
+                0,
+                destination,
+                0,
+                data.len() as u64,
+            );
+
+            if let Some(bind_group_descriptor) = bind_group_descriptor {
+                let buffer_binding = |buffer, size: usize| RenderResourceBinding::Buffer {
+                    buffer,
+                    range: 0..size as u64,
+                    dynamic_index: None,
+                };
+                let instance_count = buffers.instance_count as usize;
+                let bind_group = BindGroup::build()
+                    .add_binding(0, buffer_binding(buffers.culling, data.len()))
+                    .add_binding(
+                        1,
+                        buffer_binding(
+                            buffers.instances,
+                            instance_count * std::mem::size_of::<[f32; 16]>(),
+                        ),
+                    )
+                    .add_binding(
+                        2,
+                        buffer_binding(
+                            buffers.draw_args,
+                            instance_count * DrawIndexedIndirectArgs::SIZE as usize,
+                        ),
+                    )
+                    .finish();
+                render_context
+                    .resources()
+                    .create_bind_group(bind_group_descriptor, &bind_group);
+                let work_groups = (buffers.instance_count + WORK_GROUP_SIZE - 1) / WORK_GROUP_SIZE;
+                render_context.begin_compute_pass(&mut |compute_pass| {
+                    compute_pass.set_pipeline(INDIRECT_CULLING_PIPELINE_HANDLE);
+                    compute_pass.set_bind_group(0, bind_group_descriptor, bind_group.id, None);
+                    compute_pass.dispatch(work_groups, 1, 1);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+mod indirect_culling_node;
+
+pub use indirect_culling_node::*;
+
+use crate::{
+    mesh::{Mesh, VertexAttribute, VertexAttributeValues},
+    pass::DrawIndexedIndirectArgs,
+    pipeline::{
+        BindGroupDescriptor, BindType, BindingDescriptor, BindingShaderStage,
+        ComputePipelineDescriptor, PipelineLayout, RenderPipelines, UniformProperty,
+    },
+    renderer::{BufferId, BufferInfo, BufferUsage, RenderResourceBinding, RenderResourceContext},
+    shader::{Shader, ShaderStage},
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core::{AsBytes, Byteable};
+use bevy_ecs::{Query, Res};
+use bevy_math::{Mat4, Vec3, Vec4};
+
+pub const INDIRECT_CULLING_PIPELINE_HANDLE: Handle<ComputePipelineDescriptor> =
+    Handle::from_u128(229604738209617408446012633496381207813);
+
+/// The shader def set on the [RenderPipelines] of entities with [IndirectInstances]
+pub const INDIRECT_INSTANCES_SHADER_DEF: &str = "INDIRECT_INSTANCES";
+/// The name of the storage buffer binding that holds the model matrix of each instance
+pub const INDIRECT_INSTANCES_BINDING: &str = "IndirectInstances";
+
+/// Draws many copies of an entity's mesh in one indirect draw. Each frame the instances are culled against the 3d
+/// camera's view by a compute shader, which writes the draw arguments straight into the indirect buffer. Where compute
+/// isn't supported, the instances are culled on the cpu instead.
+///
+/// The entity's [RenderPipelines] are specialized with the `INDIRECT_INSTANCES` shader def: their vertex shader should
+/// read the model matrix of the drawn instance from the `IndirectInstances` storage buffer at `gl_InstanceIndex`.
+#[derive(Debug, Default)]
+pub struct IndirectInstances {
+    instances: Vec<Mat4>,
+    changed: bool,
+    pub(crate) buffers: Option<IndirectBuffers>,
+}
+
+impl IndirectInstances {
+    pub fn new(instances: Vec<Mat4>) -> Self {
+        IndirectInstances {
+            instances,
+            changed: true,
+            buffers: None,
+        }
+    }
+
+    /// The model matrix of each instance
+    pub fn instances(&self) -> &[Mat4] {
+        &self.instances
+    }
+
+    /// Replaces the instances. They are uploaded again before the next frame is drawn.
+    pub fn set_instances(&mut self, instances: Vec<Mat4>) {
+        self.instances = instances;
+        self.changed = true;
+    }
+
+    /// The indirect buffer and number of draws to draw the instances with, once they have been uploaded
+    pub fn draw_args(&self) -> Option<(BufferId, u32)> {
+        self.buffers
+            .as_ref()
+            .map(|buffers| (buffers.draw_args, buffers.instance_count))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IndirectBuffers {
+    /// The model matrix of each instance (storage)
+    pub instances: BufferId,
+    /// One set of [DrawIndexedIndirectArgs] per instance (storage and indirect)
+    pub draw_args: BufferId,
+    /// The [IndirectCullingUniform] of the compute path
+    pub culling: BufferId,
+    /// Stages writes to `culling` or, on the cpu path, `draw_args`
+    pub staging: BufferId,
+    pub instance_count: u32,
+    pub index_count: u32,
+    /// The radius of the mesh's bounding sphere around its origin
+    pub radius: f32,
+}
+
+impl IndirectBuffers {
+    fn remove(&self, render_resource_context: &dyn RenderResourceContext) {
+        render_resource_context.remove_buffer(self.instances);
+        render_resource_context.remove_buffer(self.draw_args);
+        render_resource_context.remove_buffer(self.culling);
+        render_resource_context.remove_buffer(self.staging);
+    }
+}
+
+/// The uniform read by `indirect_culling.comp`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IndirectCullingUniform {
+    pub planes: [[f32; 4]; 6],
+    /// The number of instances and the number of indices of the mesh
+    pub counts: [u32; 4],
+    /// The radius of the mesh's bounding sphere
+    pub bounds: [f32; 4],
+}
+
+// SAFE: IndirectCullingUniform is repr(C) containing primitives
+unsafe impl Byteable for IndirectCullingUniform {}
+
+/// The six planes that bound what a view projection can see. The normal (xyz) of each plane points inwards, and w is
+/// the plane's offset from the origin along it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the left, right, bottom, top, near and far planes of a view projection with a 0 to 1 depth range
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        // the columns of the transpose are the rows of the view projection
+        let rows = view_projection.transpose();
+        let (x, y, z, w) = (rows.x_axis(), rows.y_axis(), rows.z_axis(), rows.w_axis());
+        let mut planes = [w + x, w - x, w + y, w - y, z, w - z];
+        for plane in planes.iter_mut() {
+            *plane /= plane.truncate().length();
+        }
+
+        Frustum { planes }
+    }
+
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| Vec3::from(plane.truncate()).dot(center) + plane.w() >= -radius)
+    }
+}
+
+/// Returns the radius of the smallest sphere around the mesh's origin that contains all of its vertices
+pub fn bounding_radius(mesh: &Mesh) -> f32 {
+    mesh.attributes
+        .iter()
+        .find(|attribute| attribute.name == VertexAttribute::POSITION)
+        .map_or(0.0, |attribute| match &attribute.values {
+            VertexAttributeValues::Float3(positions) => positions
+                .iter()
+                .map(|position| Vec3::from(*position).length())
+                .fold(0.0, f32::max),
+            _ => 0.0,
+        })
+}
+
+/// Culls `instances` of a mesh with `index_count` indices and a bounding sphere of `radius` on the cpu. Returns the
+/// draw arguments the compute shader would have written: one set per instance, with an instance count of 0 for
+/// instances outside of the frustum.
+pub fn cull_instances(
+    frustum: &Frustum,
+    instances: &[Mat4],
+    radius: f32,
+    index_count: u32,
+) -> Vec<DrawIndexedIndirectArgs> {
+    instances
+        .iter()
+        .enumerate()
+        .map(|(index, transform)| {
+            let center = Vec3::from(transform.w_axis().truncate());
+            let scale = transform
+                .x_axis()
+                .truncate()
+                .length()
+                .max(transform.y_axis().truncate().length())
+                .max(transform.z_axis().truncate().length());
+            DrawIndexedIndirectArgs {
+                index_count,
+                instance_count: frustum.intersects_sphere(center, radius * scale) as u32,
+                base_index: 0,
+                vertex_offset: 0,
+                base_instance: index as u32,
+            }
+        })
+        .collect()
+}
+
+pub fn build_indirect_culling_pipeline(shaders: &mut Assets<Shader>) -> ComputePipelineDescriptor {
+    let binding = |index: u32, name: &str, bind_type: BindType| BindingDescriptor {
+        name: name.to_string(),
+        index,
+        bind_type,
+        shader_stage: BindingShaderStage::COMPUTE,
+    };
+    ComputePipelineDescriptor::new(
+        shaders.add(Shader::from_glsl(
+            ShaderStage::Compute,
+            include_str!("indirect_culling.comp"),
+        )),
+        PipelineLayout {
+            bind_groups: vec![BindGroupDescriptor::new(
+                0,
+                vec![
+                    binding(
+                        0,
+                        "IndirectCulling",
+                        BindType::Uniform {
+                            dynamic: false,
+                            properties: vec![
+                                UniformProperty::Array(Box::new(UniformProperty::Vec4), 6),
+                                UniformProperty::UVec4,
+                                UniformProperty::Vec4,
+                            ],
+                        },
+                    ),
+                    binding(
+                        1,
+                        INDIRECT_INSTANCES_BINDING,
+                        BindType::StorageBuffer {
+                            dynamic: false,
+                            readonly: true,
+                        },
+                    ),
+                    binding(
+                        2,
+                        "IndirectDrawArgs",
+                        BindType::StorageBuffer {
+                            dynamic: false,
+                            readonly: false,
+                        },
+                    ),
+                ],
+            )],
+            vertex_buffer_descriptors: Vec::new(),
+        },
+    )
+}
+
+/// Uploads the instances of [IndirectInstances] once their mesh is loaded, binds them to the entity's
+/// [RenderPipelines] and specializes its pipelines with the `INDIRECT_INSTANCES` shader def
+pub fn indirect_instances_system(
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    meshes: Res<Assets<Mesh>>,
+    mut query: Query<(&mut IndirectInstances, &Handle<Mesh>, &mut RenderPipelines)>,
+) {
+    let render_resource_context = &**render_resource_context;
+    for (mut indirect_instances, mesh_handle, mut render_pipelines) in &mut query.iter() {
+        for render_pipeline in render_pipelines.pipelines.iter_mut() {
+            render_pipeline
+                .specialization
+                .shader_specialization
+                .shader_defs
+                .insert(INDIRECT_INSTANCES_SHADER_DEF.to_string());
+        }
+
+        if !indirect_instances.changed {
+            continue;
+        }
+        let mesh = match meshes.get(mesh_handle) {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        indirect_instances.changed = false;
+        if let Some(buffers) = indirect_instances.buffers.take() {
+            buffers.remove(render_resource_context);
+        }
+        // without instances there is nothing to draw, see draw_render_pipelines_system
+        if indirect_instances.instances.is_empty() {
+            continue;
+        }
+
+        let instance_data = indirect_instances
+            .instances
+            .iter()
+            .map(|transform| transform.to_cols_array())
+            .collect::<Vec<_>>();
+        let instances = render_resource_context.create_buffer_with_data(
+            BufferInfo {
+                buffer_usage: BufferUsage::STORAGE,
+                ..Default::default()
+            },
+            instance_data.as_bytes(),
+        );
+        render_pipelines.bindings.set(
+            INDIRECT_INSTANCES_BINDING,
+            RenderResourceBinding::Buffer {
+                buffer: instances,
+                range: 0..instance_data.as_bytes().len() as u64,
+                dynamic_index: None,
+            },
+        );
+
+        // every instance is drawn until the first culling pass
+        let index_count = mesh.indices.as_ref().map_or(0, |indices| indices.len()) as u32;
+        let radius = bounding_radius(mesh);
+        let draw_args = (0..indirect_instances.instances.len() as u32)
+            .map(|base_instance| DrawIndexedIndirectArgs {
+                index_count,
+                instance_count: 1,
+                base_index: 0,
+                vertex_offset: 0,
+                base_instance,
+            })
+            .collect::<Vec<_>>();
+        let draw_args_size = draw_args.as_bytes().len();
+        let culling_size = std::mem::size_of::<IndirectCullingUniform>();
+        indirect_instances.buffers = Some(IndirectBuffers {
+            instances,
+            draw_args: render_resource_context.create_buffer_with_data(
+                BufferInfo {
+                    buffer_usage: BufferUsage::STORAGE
+                        | BufferUsage::INDIRECT
+                        | BufferUsage::COPY_DST,
+                    ..Default::default()
+                },
+                draw_args.as_bytes(),
+            ),
+            culling: render_resource_context.create_buffer(BufferInfo {
+                size: culling_size,
+                buffer_usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+                ..Default::default()
+            }),
+            staging: render_resource_context.create_buffer(BufferInfo {
+                size: draw_args_size.max(culling_size),
+                buffer_usage: BufferUsage::COPY_SRC | BufferUsage::MAP_WRITE,
+                ..Default::default()
+            }),
+            instance_count: draw_args.len() as u32,
+            index_count,
+            radius,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cull_instances, Frustum};
+    use crate::pass::DrawIndexedIndirectArgs;
+    use bevy_math::{Mat4, Vec3};
+
+    #[test]
+    fn cpu_culling() {
+        // a camera at the origin looking down -Z
+        let view_projection = Mat4::perspective_rh(std::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        let frustum = Frustum::from_view_projection(&view_projection);
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 0.0, -10.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 0.0, 10.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 0.0, -200.0), 1.0));
+
+        let instances = [
+            Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0)),
+            // behind the camera
+            Mat4::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+            // the center is outside of the frustum, but the sphere reaches into it
+            Mat4::from_translation(Vec3::new(11.0, 0.0, -10.0)),
+            // scaling grows the bounding sphere too
+            Mat4::from_scale_rotation_translation(
+                Vec3::new(3.0, 3.0, 3.0),
+                Default::default(),
+                Vec3::new(12.5, 0.0, -10.0),
+            ),
+            Mat4::from_translation(Vec3::new(12.5, 0.0, -10.0)),
+        ];
+        let draw_args = cull_instances(&frustum, &instances, 1.0, 36);
+        assert_eq!(
+            draw_args
+                .iter()
+                .map(|args| args.instance_count)
+                .collect::<Vec<_>>(),
+            vec![1, 0, 1, 1, 0]
+        );
+        assert_eq!(
+            draw_args[4],
+            DrawIndexedIndirectArgs {
+                index_count: 36,
+                instance_count: 0,
+                base_index: 0,
+                vertex_offset: 0,
+                base_instance: 4,
+            }
+        );
+    }
+}
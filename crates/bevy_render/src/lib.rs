@@ -1,11 +1,15 @@
 pub mod batch;
 pub mod camera;
 pub mod color;
+pub mod diagnostic;
 pub mod draw;
+pub mod indirect;
 pub mod mesh;
 pub mod pass;
 pub mod pipeline;
+pub mod post_process;
 pub mod render_graph;
+pub mod render_stats;
 pub mod renderer;
 pub mod shader;
 pub mod texture;
@@ -16,11 +20,17 @@ pub use once_cell;
 pub mod prelude {
     pub use crate::{
         base::Msaa,
+        camera::{Letterbox, RenderLayers},
         color::Color,
         draw::Draw,
         entity::*,
+        indirect::IndirectInstances,
         mesh::{shape, Mesh},
         pipeline::RenderPipelines,
+        post_process::{
+            AutoExposure, ColorGrading, Exposure, ExposureMode, NoMotionBlur, PostProcessEffect,
+            PostProcessStack,
+        },
         shader::Shader,
         texture::Texture,
     };
@@ -29,21 +39,30 @@ pub mod prelude {
 use crate::prelude::*;
 use base::{MainPass, Msaa};
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, AssetServer, Assets};
 use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem};
 use bevy_type_registry::RegisterType;
+use bevy_window::Windows;
 use camera::{
-    ActiveCameras, Camera, OrthographicProjection, PerspectiveProjection, VisibleEntities,
+    ActiveCameras, Camera, OrthographicProjection, PerspectiveProjection, RenderLayers,
+    SplitScreen, VisibleEntities,
 };
+use indirect::INDIRECT_CULLING_PIPELINE_HANDLE;
 use pipeline::{
-    DynamicBinding, PipelineCompiler, PipelineDescriptor, PipelineSpecialization,
-    PrimitiveTopology, ShaderSpecialization, VertexBufferDescriptors,
+    ComputePipelineDescriptor, DynamicBinding, PipelineCompiler, PipelineDescriptor,
+    PipelineSpecialization, PrimitiveTopology, ShaderSpecialization, VertexBufferDescriptors,
+};
+use post_process::{
+    PostProcessGraphState, AUTO_EXPOSURE_HISTOGRAM_PIPELINE_HANDLE, AUTO_EXPOSURE_PIPELINE_HANDLE,
+    POST_PROCESS_PIPELINE_HANDLE, PREPASS_PIPELINE_HANDLE,
 };
 use render_graph::{
     base::{self, BaseRenderGraphBuilder, BaseRenderGraphConfig},
-    RenderGraph,
+    RenderGraph, TransientTexturePool,
 };
+use render_stats::RenderStats;
 use renderer::{AssetRenderResourceBindings, RenderResourceBindings};
+use shader::ShaderLoader;
 use std::ops::Range;
 #[cfg(feature = "hdr")]
 use texture::HdrTextureLoader;
@@ -96,7 +115,9 @@ impl Plugin for RenderPlugin {
             .add_asset::<Mesh>()
             .add_asset::<Texture>()
             .add_asset::<Shader>()
+            .add_asset_loader::<Shader, ShaderLoader>()
             .add_asset::<PipelineDescriptor>()
+            .add_asset::<ComputePipelineDescriptor>()
             .register_component::<Camera>()
             .register_component::<Draw>()
             .register_component::<RenderPipelines>()
@@ -104,6 +125,7 @@ impl Plugin for RenderPlugin {
             .register_component::<PerspectiveProjection>()
             .register_component::<MainPass>()
             .register_component::<VisibleEntities>()
+            .register_component::<RenderLayers>()
             .register_property::<Color>()
             .register_property::<Range<f32>>()
             .register_property::<ShaderSpecialization>()
@@ -111,16 +133,28 @@ impl Plugin for RenderPlugin {
             .register_property::<PrimitiveTopology>()
             .register_properties::<PipelineSpecialization>()
             .init_resource::<RenderGraph>()
+            .init_resource::<TransientTexturePool>()
             .init_resource::<PipelineCompiler>()
             .init_resource::<RenderResourceBindings>()
             .init_resource::<VertexBufferDescriptors>()
             .init_resource::<TextureResourceSystemState>()
             .init_resource::<AssetRenderResourceBindings>()
             .init_resource::<ActiveCameras>()
+            .init_resource::<SplitScreen>()
+            .init_resource::<PostProcessGraphState>()
+            .init_resource::<RenderStats>()
             .add_system_to_stage(
                 bevy_app::stage::PRE_UPDATE,
                 draw::clear_draw_system.system(),
             )
+            .add_system_to_stage(
+                bevy_app::stage::PRE_UPDATE,
+                camera::split_screen_system.system(),
+            )
+            .add_system_to_stage(
+                bevy_app::stage::PRE_UPDATE,
+                camera::letterbox_system.system(),
+            )
             .add_system_to_stage(
                 bevy_app::stage::POST_UPDATE,
                 camera::active_cameras_system.system(),
@@ -138,7 +172,15 @@ impl Plugin for RenderPlugin {
                 bevy_app::stage::POST_UPDATE,
                 camera::visible_entities_system.system(),
             )
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                render_stats::clear_render_stats_system.system(),
+            )
             // TODO: turn these "resource systems" into graph nodes and remove the RENDER_RESOURCE stage
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                pipeline::shader_update_system.system(),
+            )
             .add_system_to_stage(
                 stage::RENDER_RESOURCE,
                 mesh::mesh_resource_provider_system.system(),
@@ -147,6 +189,22 @@ impl Plugin for RenderPlugin {
                 stage::RENDER_RESOURCE,
                 Texture::texture_resource_system.system(),
             )
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                indirect::indirect_instances_system.system(),
+            )
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                camera::split_screen_graph_system.thread_local_system(),
+            )
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                camera::render_target_graph_system.thread_local_system(),
+            )
+            .add_system_to_stage(
+                stage::RENDER_RESOURCE,
+                post_process::post_process_graph_system.thread_local_system(),
+            )
             .add_system_to_stage(
                 stage::RENDER_GRAPH_SYSTEMS,
                 render_graph::render_graph_schedule_executor_system.thread_local_system(),
@@ -157,6 +215,35 @@ impl Plugin for RenderPlugin {
                 shader::clear_shader_defs_system.system(),
             );
 
+        {
+            let resources = app.resources();
+            let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+            let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+            pipelines.set(
+                POST_PROCESS_PIPELINE_HANDLE,
+                post_process::build_post_process_pipeline(&mut shaders),
+            );
+            pipelines.set(
+                AUTO_EXPOSURE_HISTOGRAM_PIPELINE_HANDLE,
+                post_process::build_auto_exposure_histogram_pipeline(&mut shaders),
+            );
+            pipelines.set(
+                AUTO_EXPOSURE_PIPELINE_HANDLE,
+                post_process::build_auto_exposure_pipeline(&mut shaders),
+            );
+            pipelines.set(
+                PREPASS_PIPELINE_HANDLE,
+                post_process::build_prepass_pipeline(&mut shaders),
+            );
+            let mut compute_pipelines = resources
+                .get_mut::<Assets<ComputePipelineDescriptor>>()
+                .unwrap();
+            compute_pipelines.set(
+                INDIRECT_CULLING_PIPELINE_HANDLE,
+                indirect::build_indirect_culling_pipeline(&mut shaders),
+            );
+        }
+
         if app.resources().get::<Msaa>().is_none() {
             app.init_resource::<Msaa>();
         }
@@ -164,8 +251,7 @@ impl Plugin for RenderPlugin {
         if let Some(ref config) = self.base_render_graph_config {
             let resources = app.resources();
             let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
-            let msaa = resources.get::<Msaa>().unwrap();
-            render_graph.add_base_graph(config, &msaa);
+            render_graph.add_base_graph(config);
             let mut active_cameras = resources.get_mut::<ActiveCameras>().unwrap();
             if config.add_3d_camera {
                 active_cameras.add(base::camera::CAMERA3D);
@@ -176,4 +262,8 @@ impl Plugin for RenderPlugin {
             }
         }
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<AssetServer>().requires::<Windows>();
+    }
 }
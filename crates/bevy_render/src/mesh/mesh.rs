@@ -35,6 +35,16 @@ impl VertexAttributeValues {
     }
 
     // TODO: add vertex format as parameter here and perform type conversions
+    /// Returns an empty list of values with the same format as `self`
+    pub fn with_no_values(&self) -> Self {
+        match *self {
+            VertexAttributeValues::Float(_) => VertexAttributeValues::Float(Vec::new()),
+            VertexAttributeValues::Float2(_) => VertexAttributeValues::Float2(Vec::new()),
+            VertexAttributeValues::Float3(_) => VertexAttributeValues::Float3(Vec::new()),
+            VertexAttributeValues::Float4(_) => VertexAttributeValues::Float4(Vec::new()),
+        }
+    }
+
     pub fn get_bytes(&self) -> &[u8] {
         match self {
             VertexAttributeValues::Float(values) => values.as_slice().as_bytes(),
@@ -56,7 +66,7 @@ impl From<&VertexAttributeValues> for VertexFormat {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct VertexAttribute {
     pub name: Cow<'static, str>,
     pub values: VertexAttributeValues,
@@ -87,6 +97,14 @@ impl VertexAttribute {
             values: VertexAttributeValues::Float2(uvs),
         }
     }
+
+    /// Returns an attribute with the same name and format as `self`, but no values
+    pub fn with_no_values(&self) -> Self {
+        VertexAttribute {
+            name: self.name.clone(),
+            values: self.values.with_no_values(),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -101,7 +119,19 @@ pub enum MeshToVertexBufferError {
     },
 }
 
-#[derive(Debug)]
+#[derive(Error, Debug)]
+pub enum MeshMergeError {
+    #[error("Meshes with different primitive topologies cannot be merged.")]
+    IncompatiblePrimitiveTopology,
+    #[error("Meshes with strip primitive topologies cannot be merged, because the strips would be joined.")]
+    StripPrimitiveTopology,
+    #[error("Meshes must have the same vertex attributes to be merged.")]
+    MismatchedVertexAttribute { attribute_name: Cow<'static, str> },
+    #[error("Indexed and non-indexed meshes cannot be merged.")]
+    MismatchedIndices,
+}
+
+#[derive(Clone, Debug)]
 pub struct Mesh {
     pub primitive_topology: PrimitiveTopology,
     pub attributes: Vec<VertexAttribute>,
@@ -163,6 +193,95 @@ impl Mesh {
             IndexFormat::Uint32 => indices.as_slice().as_bytes().to_vec(),
         })
     }
+
+    /// Appends the vertices and indices of `other` to this mesh. `other`'s positions and normals are transformed by
+    /// `transform` first. All other attributes (ex: lightmap uvs) are copied as-is. Only list topologies can be
+    /// merged.
+    pub fn merge(&mut self, other: &Mesh, transform: &Mat4) -> Result<(), MeshMergeError> {
+        let is_strip = |topology| {
+            topology == PrimitiveTopology::LineStrip || topology == PrimitiveTopology::TriangleStrip
+        };
+        if is_strip(self.primitive_topology) || is_strip(other.primitive_topology) {
+            return Err(MeshMergeError::StripPrimitiveTopology);
+        }
+
+        if self.primitive_topology != other.primitive_topology {
+            return Err(MeshMergeError::IncompatiblePrimitiveTopology);
+        }
+
+        if self.indices.is_some() != other.indices.is_some() {
+            return Err(MeshMergeError::MismatchedIndices);
+        }
+
+        // validate before modifying anything so a failed merge leaves this mesh untouched
+        for attribute in self.attributes.iter() {
+            let compatible = other.attributes.iter().any(|other_attribute| {
+                other_attribute.name == attribute.name
+                    && VertexFormat::from(&other_attribute.values)
+                        == VertexFormat::from(&attribute.values)
+            });
+            if !compatible {
+                return Err(MeshMergeError::MismatchedVertexAttribute {
+                    attribute_name: attribute.name.clone(),
+                });
+            }
+        }
+
+        if let Some(attribute) = other
+            .attributes
+            .iter()
+            .find(|a| self.attributes.iter().all(|b| a.name != b.name))
+        {
+            return Err(MeshMergeError::MismatchedVertexAttribute {
+                attribute_name: attribute.name.clone(),
+            });
+        }
+
+        let vertex_count = self.attributes.first().map(|a| a.values.len()).unwrap_or(0) as u32;
+        let normal_matrix = transform.inverse().transpose();
+        for attribute in self.attributes.iter_mut() {
+            let other_attribute = other
+                .attributes
+                .iter()
+                .find(|a| a.name == attribute.name)
+                .unwrap();
+            let name = &attribute.name;
+            match (&mut attribute.values, &other_attribute.values) {
+                (VertexAttributeValues::Float(values), VertexAttributeValues::Float(other)) => {
+                    values.extend_from_slice(other)
+                }
+                (VertexAttributeValues::Float2(values), VertexAttributeValues::Float2(other)) => {
+                    values.extend_from_slice(other)
+                }
+                (VertexAttributeValues::Float3(values), VertexAttributeValues::Float3(other)) => {
+                    if name == VertexAttribute::POSITION {
+                        values.extend(other.iter().map(|position| -> [f32; 3] {
+                            transform.transform_point3(Vec3::from(*position)).into()
+                        }));
+                    } else if name == VertexAttribute::NORMAL {
+                        values.extend(other.iter().map(|normal| -> [f32; 3] {
+                            normal_matrix
+                                .transform_vector3(Vec3::from(*normal))
+                                .normalize()
+                                .into()
+                        }));
+                    } else {
+                        values.extend_from_slice(other)
+                    }
+                }
+                (VertexAttributeValues::Float4(values), VertexAttributeValues::Float4(other)) => {
+                    values.extend_from_slice(other)
+                }
+                _ => unreachable!("attribute formats were validated above"),
+            }
+        }
+
+        if let (Some(indices), Some(other_indices)) = (&mut self.indices, &other.indices) {
+            indices.extend(other_indices.iter().map(|i| i + vertex_count));
+        }
+
+        Ok(())
+    }
 }
 
 /// Generation for some primitive shape meshes.
@@ -581,9 +700,101 @@ pub fn mesh_resource_provider_system(
 
 #[cfg(test)]
 mod tests {
-    use super::{AsVertexBufferDescriptor, Mesh, VertexAttribute};
-    use crate::{mesh::Vertex, pipeline::PrimitiveTopology};
+    use super::{AsVertexBufferDescriptor, Mesh, MeshMergeError, VertexAttribute};
+    use crate::{
+        mesh::{Vertex, VertexAttributeValues},
+        pipeline::PrimitiveTopology,
+    };
     use bevy_core::AsBytes;
+    use bevy_math::{Mat4, Quat, Vec3};
+
+    fn triangle(topology: PrimitiveTopology) -> Mesh {
+        Mesh {
+            primitive_topology: topology,
+            attributes: vec![
+                VertexAttribute::position(vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]),
+                VertexAttribute::normal(vec![[0., 0., 1.]; 3]),
+                VertexAttribute::uv(vec![[0., 0.], [1., 0.], [0., 1.]]),
+            ],
+            indices: Some(vec![0, 1, 2]),
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let mut mesh = triangle(PrimitiveTopology::TriangleList);
+        let transform = Mat4::from_rotation_translation(
+            Quat::from_rotation_y(std::f32::consts::PI),
+            Vec3::new(5., 0., 0.),
+        );
+        mesh.merge(&triangle(PrimitiveTopology::TriangleList), &transform)
+            .unwrap();
+
+        assert_eq!(mesh.indices, Some(vec![0, 1, 2, 3, 4, 5]));
+        let values = |name: &str| {
+            mesh.attributes
+                .iter()
+                .find(|attribute| attribute.name == name)
+                .unwrap()
+                .values
+                .clone()
+        };
+        let close =
+            |a: [f32; 3], b: [f32; 3]| a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-5);
+        match values(VertexAttribute::POSITION) {
+            VertexAttributeValues::Float3(positions) => {
+                assert_eq!(positions.len(), 6);
+                assert!(close(positions[4], [4., 0., 0.]));
+            }
+            _ => panic!("positions are Float3"),
+        }
+        match values(VertexAttribute::NORMAL) {
+            VertexAttributeValues::Float3(normals) => assert!(close(normals[3], [0., 0., -1.])),
+            _ => panic!("normals are Float3"),
+        }
+        // other attributes are copied as-is
+        match values(VertexAttribute::UV) {
+            VertexAttributeValues::Float2(uvs) => assert_eq!(uvs[4], [1., 0.]),
+            _ => panic!("uvs are Float2"),
+        }
+    }
+
+    #[test]
+    fn merge_errors() {
+        let mut mesh = triangle(PrimitiveTopology::TriangleList);
+        let identity = Mat4::identity();
+        assert!(matches!(
+            mesh.merge(&triangle(PrimitiveTopology::TriangleStrip), &identity),
+            Err(MeshMergeError::StripPrimitiveTopology)
+        ));
+        assert!(matches!(
+            triangle(PrimitiveTopology::LineStrip)
+                .merge(&triangle(PrimitiveTopology::LineStrip), &identity),
+            Err(MeshMergeError::StripPrimitiveTopology)
+        ));
+        assert!(matches!(
+            mesh.merge(&triangle(PrimitiveTopology::LineList), &identity),
+            Err(MeshMergeError::IncompatiblePrimitiveTopology)
+        ));
+
+        let mut unindexed = triangle(PrimitiveTopology::TriangleList);
+        unindexed.indices = None;
+        assert!(matches!(
+            mesh.merge(&unindexed, &identity),
+            Err(MeshMergeError::MismatchedIndices)
+        ));
+
+        let mut without_uvs = triangle(PrimitiveTopology::TriangleList);
+        without_uvs.attributes.pop();
+        assert!(matches!(
+            mesh.merge(&without_uvs, &identity),
+            Err(MeshMergeError::MismatchedVertexAttribute { .. })
+        ));
+
+        // failed merges leave the mesh untouched
+        assert_eq!(mesh.indices, Some(vec![0, 1, 2]));
+        assert_eq!(mesh.attributes[0].values.len(), 3);
+    }
 
     #[test]
     fn test_get_vertex_bytes() {
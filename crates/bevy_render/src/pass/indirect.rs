@@ -0,0 +1,21 @@
+use bevy_core::Byteable;
+
+/// The arguments of a single indexed draw call, laid out the way the GPU expects them in an indirect buffer.
+/// Buffers containing these are consumed by [RenderPass::draw_indexed_indirect](super::RenderPass::draw_indexed_indirect).
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct DrawIndexedIndirectArgs {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub base_index: u32,
+    pub vertex_offset: i32,
+    pub base_instance: u32,
+}
+
+impl DrawIndexedIndirectArgs {
+    /// The size in bytes of one tightly packed set of arguments
+    pub const SIZE: u64 = std::mem::size_of::<Self>() as u64;
+}
+
+// SAFE: DrawIndexedIndirectArgs is repr(C) containing primitives
+unsafe impl Byteable for DrawIndexedIndirectArgs {}
@@ -1,7 +1,11 @@
+mod compute_pass;
+mod indirect;
 mod ops;
 mod pass;
 mod render_pass;
 
+pub use compute_pass::*;
+pub use indirect::*;
 pub use ops::*;
 pub use pass::*;
 pub use render_pass::*;
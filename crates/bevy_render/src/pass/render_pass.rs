@@ -14,6 +14,14 @@ pub trait RenderPass {
     fn set_stencil_reference(&mut self, reference: u32);
     fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>);
     fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>);
+    /// Issues `count` indexed draws whose arguments are read from tightly packed [DrawIndexedIndirectArgs](super::DrawIndexedIndirectArgs)
+    /// in `indirect_buffer`, starting at `indirect_offset`. Backends without multi-draw support issue the draws one at a time.
+    fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: BufferId,
+        indirect_offset: u64,
+        count: u32,
+    );
     fn set_bind_group(
         &mut self,
         index: u32,
@@ -0,0 +1,22 @@
+use super::PipelineLayout;
+use crate::shader::Shader;
+use bevy_asset::Handle;
+
+/// A pipeline that runs a compute shader. Unlike [PipelineDescriptor](super::PipelineDescriptor)s, compute pipelines
+/// aren't reflected or specialized, so their `layout` has to be provided.
+#[derive(Clone, Debug)]
+pub struct ComputePipelineDescriptor {
+    pub name: Option<String>,
+    pub layout: PipelineLayout,
+    pub shader: Handle<Shader>,
+}
+
+impl ComputePipelineDescriptor {
+    pub fn new(shader: Handle<Shader>, layout: PipelineLayout) -> Self {
+        ComputePipelineDescriptor {
+            name: None,
+            layout,
+            shader,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod bind_group;
 mod binding;
+mod compute_pipeline;
 mod pipeline;
 mod pipeline_compiler;
 mod pipeline_layout;
@@ -10,6 +11,7 @@ mod vertex_format;
 
 pub use bind_group::*;
 pub use binding::*;
+pub use compute_pipeline::*;
 pub use pipeline::*;
 pub use pipeline_compiler::*;
 pub use pipeline_layout::*;
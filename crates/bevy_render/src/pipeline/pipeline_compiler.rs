@@ -1,9 +1,11 @@
 use super::{state_descriptors::PrimitiveTopology, PipelineDescriptor, VertexBufferDescriptors};
 use crate::{
     renderer::RenderResourceContext,
-    shader::{Shader, ShaderSource},
+    shader::{Shader, ShaderError, ShaderSource},
 };
-use bevy_asset::{Assets, Handle};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::{Local, Res, ResMut};
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
 use bevy_property::{Properties, Property};
@@ -181,6 +183,63 @@ impl PipelineCompiler {
         specialized_pipeline_handle
     }
 
+    /// Recompiles the specializations of `shader_handle` after its source changed, and removes the compiled pipelines
+    /// that use it, so they are compiled again the next time they are used. If the new source doesn't compile, the
+    /// current specializations and pipelines are kept and the error is returned.
+    pub fn update_shader(
+        &mut self,
+        shader_handle: &Handle<Shader>,
+        pipelines: &mut Assets<PipelineDescriptor>,
+        shaders: &mut Assets<Shader>,
+    ) -> Result<(), ShaderError> {
+        if let Some(specialized_shaders) = self.specialized_shaders.get_mut(shader_handle) {
+            let shader = match shaders.get(shader_handle) {
+                Some(shader) => shader,
+                None => return Ok(()),
+            };
+            let compiled_shaders = specialized_shaders
+                .iter()
+                .map(|specialized_shader| {
+                    let shader_def_vec = specialized_shader
+                        .specialization
+                        .shader_defs
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>();
+                    shader
+                        .try_get_spirv(Some(&shader_def_vec))
+                        .map(|spirv| Shader::new(shader.stage, ShaderSource::Spirv(spirv)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for (specialized_shader, compiled_shader) in
+                specialized_shaders.iter_mut().zip(compiled_shaders)
+            {
+                shaders.remove(&specialized_shader.shader);
+                specialized_shader.shader = shaders.add(compiled_shader);
+            }
+        }
+
+        let uses_shader = |descriptor: &PipelineDescriptor| {
+            descriptor.shader_stages.vertex == *shader_handle
+                || descriptor.shader_stages.fragment == Some(*shader_handle)
+        };
+        let outdated_pipelines = self
+            .specialized_pipelines
+            .keys()
+            .filter(|source_pipeline| pipelines.get(source_pipeline).map_or(false, uses_shader))
+            .cloned()
+            .collect::<Vec<_>>();
+        for source_pipeline in outdated_pipelines {
+            let specialized_pipelines =
+                self.specialized_pipelines.remove(&source_pipeline).unwrap();
+            for specialized_pipeline in specialized_pipelines {
+                pipelines.remove(&specialized_pipeline.pipeline);
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn iter_compiled_pipelines(
         &self,
         pipeline_handle: Handle<PipelineDescriptor>,
@@ -207,3 +266,21 @@ impl PipelineCompiler {
             .flatten()
     }
 }
+
+/// Recompiles the pipelines that use a shader whose source changed
+pub fn shader_update_system(
+    mut shader_event_reader: Local<EventReader<AssetEvent<Shader>>>,
+    shader_events: Res<Events<AssetEvent<Shader>>>,
+    mut pipeline_compiler: ResMut<PipelineCompiler>,
+    mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+    mut shaders: ResMut<Assets<Shader>>,
+) {
+    for event in shader_event_reader.iter(&shader_events) {
+        if let AssetEvent::Modified { handle } = event {
+            let result = pipeline_compiler.update_shader(handle, &mut pipelines, &mut shaders);
+            if let Err(err) = result {
+                log::error!("Failed to recompile a modified shader. {}", err);
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::{PipelineDescriptor, PipelineSpecialization};
 use crate::{
     draw::{Draw, DrawContext},
+    indirect::IndirectInstances,
     prelude::Msaa,
     renderer::RenderResourceBindings,
 };
@@ -74,15 +75,24 @@ pub fn draw_render_pipelines_system(
     mut draw_context: DrawContext,
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     msaa: Res<Msaa>,
-    mut query: Query<(&mut Draw, &mut RenderPipelines)>,
+    mut query: Query<(&mut Draw, &mut RenderPipelines, Option<&IndirectInstances>)>,
 ) {
-    for (mut draw, mut render_pipelines) in &mut query.iter() {
+    for (mut draw, mut render_pipelines, indirect_instances) in &mut query.iter() {
+        // indirect instances are drawn once they have been uploaded
+        let indirect_draw_args = indirect_instances.map(|instances| instances.draw_args());
+        if let Some(None) = indirect_draw_args {
+            continue;
+        }
+
         let render_pipelines = &mut *render_pipelines;
         for pipeline in render_pipelines.pipelines.iter_mut() {
             pipeline.specialization.sample_count = msaa.samples;
         }
 
         for render_pipeline in render_pipelines.pipelines.iter() {
+            if !draw_context.is_pipeline_loaded(render_pipeline.pipeline) {
+                continue;
+            }
             draw_context
                 .set_pipeline(
                     &mut draw,
@@ -103,7 +113,10 @@ pub fn draw_render_pipelines_system(
                 .set_vertex_buffers_from_bindings(&mut draw, &[&render_pipelines.bindings])
                 .unwrap();
             if let Some(indices) = indices {
-                draw.draw_indexed(indices, 0, 0..1);
+                match indirect_draw_args {
+                    Some(Some((buffer, count))) => draw.draw_indexed_indirect(buffer, 0, count),
+                    _ => draw.draw_indexed(indices, 0, 0..1),
+                }
             }
         }
     }
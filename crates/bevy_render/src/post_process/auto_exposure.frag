@@ -0,0 +1,40 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) uniform texture2D AutoExposure_histogram_texture;
+layout(set = 0, binding = 1) uniform texture2D AutoExposure_previous;
+layout(set = 0, binding = 2) uniform sampler AutoExposure_sampler;
+layout(set = 0, binding = 3) uniform AutoExposure_params {
+    // min log2 luminance, log2 luminance range, sample count, bin count
+    vec4 Histogram;
+    // min ev100, max ev100, adaptation (1.0 jumps to the metered exposure)
+    vec4 Exposure;
+    // low percentile, high percentile
+    vec4 Percentiles;
+};
+
+void main() {
+    // average the log luminance of the samples between the low and high percentile
+    float low = Percentiles.x * Histogram.z;
+    float high = Percentiles.y * Histogram.z;
+    float accumulated = 0.0;
+    float sum = 0.0;
+    float weight = 0.0;
+    for (int i = 0; i < int(Histogram.w); i++) {
+        float count = texelFetch(sampler2D(AutoExposure_histogram_texture, AutoExposure_sampler), ivec2(i, 0), 0).r;
+        float included = max(min(accumulated + count, high) - max(accumulated, low), 0.0);
+        float log_luminance = Histogram.x + (float(i) + 0.5) / Histogram.w * Histogram.y;
+        sum += log_luminance * included;
+        weight += included;
+        accumulated += count;
+    }
+    float average = weight > 0.0 ? sum / weight : Histogram.x + Histogram.y * 0.5;
+
+    // expose like a reflected light meter with a calibration constant of 12.5
+    float metered = clamp(average + log2(100.0 / 12.5), Exposure.x, Exposure.y);
+    float previous = texelFetch(sampler2D(AutoExposure_previous, AutoExposure_sampler), ivec2(0, 0), 0).r;
+    o_Target = vec4(mix(previous, metered, Exposure.z), 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,10 @@
+#version 450
+
+layout(location = 0) in float v_Count;
+
+layout(location = 0) out vec4 o_Target;
+
+void main() {
+    // points are blended additively, so each bin ends up with the number of samples that fell into it
+    o_Target = vec4(v_Count, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,25 @@
+#version 450
+
+layout(location = 0) out float v_Count;
+
+layout(set = 0, binding = 0) uniform texture2D AutoExposure_input;
+layout(set = 0, binding = 1) uniform sampler AutoExposure_input_sampler;
+layout(set = 0, binding = 2) uniform AutoExposure_histogram {
+    // min log2 luminance, log2 luminance range, samples per row, bin count
+    vec4 Histogram;
+};
+
+void main() {
+    // each vertex samples one cell of a grid laid over the image and is drawn as a point on the texel of its bin
+    ivec2 size = textureSize(sampler2D(AutoExposure_input, AutoExposure_input_sampler), 0);
+    int columns = int(Histogram.z);
+    ivec2 cell = ivec2(gl_VertexIndex % columns, gl_VertexIndex / columns);
+    ivec2 texel = (cell * 2 + 1) * size / (columns * 2);
+    vec3 color = texelFetch(sampler2D(AutoExposure_input, AutoExposure_input_sampler), texel, 0).rgb;
+    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+    float position = clamp((log2(max(luminance, 1e-5)) - Histogram.x) / Histogram.y, 0.0, 1.0);
+    float bin = min(floor(position * Histogram.w), Histogram.w - 1.0);
+    gl_Position = vec4((bin + 0.5) / Histogram.w * 2.0 - 1.0, 0.0, 0.0, 1.0);
+    gl_PointSize = 1.0;
+    v_Count = 1.0;
+}
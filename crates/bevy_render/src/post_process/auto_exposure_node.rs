@@ -0,0 +1,313 @@
+use super::{
+    uniform_buffer, AutoExposure, Exposure, ExposureMode, AUTO_EXPOSURE_HISTOGRAM_PIPELINE_HANDLE,
+    AUTO_EXPOSURE_PIPELINE_HANDLE,
+};
+use crate::{
+    camera::ActiveCameras,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor, TextureAttachment,
+    },
+    pipeline::{
+        PipelineCompiler, PipelineDescriptor, PipelineSpecialization, VertexBufferDescriptors,
+    },
+    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    renderer::{
+        BindGroup, BufferId, RenderContext, RenderResourceBindings, RenderResourceId,
+        RenderResourceType, SamplerId, TextureId,
+    },
+    shader::Shader,
+    texture::{
+        Extent3d, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat,
+        TextureUsage,
+    },
+    Color,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core::Time;
+use bevy_ecs::{Resources, World};
+use std::borrow::Cow;
+
+/// The number of histogram bins, spread evenly over the log2 luminance range
+const BIN_COUNT: u32 = 64;
+/// The image is sampled on a grid of this many rows and columns. Bins are counted in 16 bit floats, which are exact up
+/// to 2048, so every sample landing in one bin is still counted correctly.
+const SAMPLES_PER_ROW: u32 = 32;
+const MIN_LOG_LUMINANCE: f32 = -10.0;
+const LOG_LUMINANCE_RANGE: f32 = 10.0;
+
+/// Meters the image of a camera with [ExposureMode::Auto]. The luminance of the image is counted into a histogram by
+/// drawing one point per sample into a texture with additive blending, which is then averaged and blended with the
+/// exposure of the previous frame into a 1x1 texture holding the current EV100.
+///
+/// The output is always set, but only updated while the camera uses auto exposure.
+pub struct AutoExposureNode {
+    camera_name: String,
+    sampler: Option<SamplerId>,
+    histogram_texture: Option<TextureId>,
+    exposure_textures: Vec<TextureId>,
+    current_exposure_texture: usize,
+    metered: bool,
+    histogram_buffer: Option<(BufferId, [f32; 4])>,
+    params_buffer: Option<(BufferId, [[f32; 4]; 3])>,
+}
+
+impl AutoExposureNode {
+    pub const IN_COLOR: &'static str = "color";
+    pub const OUT_EXPOSURE: &'static str = "exposure";
+
+    pub fn new(camera_name: &str) -> Self {
+        AutoExposureNode {
+            camera_name: camera_name.to_string(),
+            sampler: None,
+            histogram_texture: None,
+            exposure_textures: Vec::new(),
+            current_exposure_texture: 0,
+            metered: false,
+            histogram_buffer: None,
+            params_buffer: None,
+        }
+    }
+
+    fn create_textures(&mut self, render_context: &mut dyn RenderContext) {
+        let render_resource_context = render_context.resources_mut();
+        let texture = |width| TextureDescriptor {
+            size: Extent3d {
+                width,
+                height: 1,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba16Float,
+            usage: TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT,
+        };
+        if self.histogram_texture.is_none() {
+            self.histogram_texture =
+                Some(render_resource_context.create_texture(texture(BIN_COUNT)));
+        }
+        // the exposure ping-pongs between two textures, so each frame can read the previous one
+        while self.exposure_textures.len() < 2 {
+            self.exposure_textures
+                .push(render_resource_context.create_texture(texture(1)));
+        }
+    }
+}
+
+impl Node for AutoExposureNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        static INPUT: &[ResourceSlotInfo] = &[ResourceSlotInfo {
+            name: Cow::Borrowed(AutoExposureNode::IN_COLOR),
+            resource_type: RenderResourceType::Texture,
+        }];
+        INPUT
+    }
+
+    fn output(&self) -> &[ResourceSlotInfo] {
+        static OUTPUT: &[ResourceSlotInfo] = &[ResourceSlotInfo {
+            name: Cow::Borrowed(AutoExposureNode::OUT_EXPOSURE),
+            resource_type: RenderResourceType::Texture,
+        }];
+        OUTPUT
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        const IN_COLOR: usize = 0;
+        const OUT_EXPOSURE: usize = 0;
+        self.create_textures(render_context);
+        output.set(
+            OUT_EXPOSURE,
+            RenderResourceId::Texture(self.exposure_textures[self.current_exposure_texture]),
+        );
+
+        let active_cameras = resources.get::<ActiveCameras>().unwrap();
+        let auto_exposure = active_cameras
+            .get(&self.camera_name)
+            .and_then(|camera| world.get::<Exposure>(camera).ok())
+            .and_then(|exposure| match exposure.mode {
+                ExposureMode::Auto(ref auto_exposure) => Some(auto_exposure.clone()),
+                ExposureMode::Manual { .. } => None,
+            });
+        let auto_exposure = if let Some(auto_exposure) = auto_exposure {
+            auto_exposure
+        } else {
+            // start over from the metered exposure when auto exposure is turned back on
+            self.metered = false;
+            return;
+        };
+
+        let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
+        let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+        let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+        let mut pipeline_compiler = resources.get_mut::<PipelineCompiler>().unwrap();
+        let vertex_buffer_descriptors = resources.get::<VertexBufferDescriptors>().unwrap();
+        let delta_seconds = resources
+            .get::<Time>()
+            .map_or(0.0, |time| time.delta_seconds);
+
+        let color_texture = input.get(IN_COLOR).unwrap().get_texture().unwrap();
+        let histogram_texture = self.histogram_texture.unwrap();
+        let previous_exposure_texture = self.exposure_textures[self.current_exposure_texture];
+        self.current_exposure_texture = 1 - self.current_exposure_texture;
+        let exposure_texture = self.exposure_textures[self.current_exposure_texture];
+        let sampler = *self.sampler.get_or_insert_with(|| {
+            render_context
+                .resources()
+                .create_sampler(&SamplerDescriptor::default())
+        });
+
+        let sample_count = SAMPLES_PER_ROW * SAMPLES_PER_ROW;
+        let histogram = [
+            MIN_LOG_LUMINANCE,
+            LOG_LUMINANCE_RANGE,
+            SAMPLES_PER_ROW as f32,
+            BIN_COUNT as f32,
+        ];
+        let histogram_buffer =
+            uniform_buffer(render_context, &mut self.histogram_buffer, histogram);
+        let params = auto_exposure_params(
+            &auto_exposure,
+            histogram,
+            sample_count as f32,
+            if self.metered {
+                1.0 - (-delta_seconds * auto_exposure.adaptation_speed.max(0.0)).exp()
+            } else {
+                1.0
+            },
+        );
+        let params_buffer = uniform_buffer(render_context, &mut self.params_buffer, params);
+        self.metered = true;
+
+        let passes = [
+            (
+                AUTO_EXPOSURE_HISTOGRAM_PIPELINE_HANDLE,
+                histogram_texture,
+                0..sample_count,
+            ),
+            (AUTO_EXPOSURE_PIPELINE_HANDLE, exposure_texture, 0..3),
+        ];
+        for (pipeline_handle, target, vertices) in passes.iter().cloned() {
+            let pipeline = compile_pipeline(
+                render_context,
+                &mut pipeline_compiler,
+                &mut pipelines,
+                &mut shaders,
+                &vertex_buffer_descriptors,
+                pipeline_handle,
+            );
+            let layout = pipelines.get(&pipeline).unwrap().get_layout().unwrap();
+            let bind_group_descriptor = layout.get_bind_group(0).unwrap();
+            let mut bind_group = BindGroup::build();
+            for binding in bind_group_descriptor.bindings.iter() {
+                bind_group = match binding.name.as_str() {
+                    "AutoExposure_input" => bind_group.add_texture(binding.index, color_texture),
+                    "AutoExposure_histogram_texture" => {
+                        bind_group.add_texture(binding.index, histogram_texture)
+                    }
+                    "AutoExposure_previous" => {
+                        bind_group.add_texture(binding.index, previous_exposure_texture)
+                    }
+                    "AutoExposure_input_sampler" | "AutoExposure_sampler" => {
+                        bind_group.add_sampler(binding.index, sampler)
+                    }
+                    "AutoExposure_histogram" => bind_group.add_buffer(
+                        binding.index,
+                        histogram_buffer,
+                        0..std::mem::size_of::<[f32; 4]>() as u64,
+                    ),
+                    "AutoExposure_params" => bind_group.add_buffer(
+                        binding.index,
+                        params_buffer,
+                        0..std::mem::size_of::<[[f32; 4]; 3]>() as u64,
+                    ),
+                    _ => panic!("unexpected auto exposure binding {}", binding.name),
+                };
+            }
+            let bind_group = bind_group.finish();
+            let bind_group_descriptor_id = bind_group_descriptor.id;
+            render_context
+                .resources()
+                .create_bind_group(bind_group_descriptor_id, &bind_group);
+
+            let pass_descriptor = PassDescriptor {
+                color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                    attachment: TextureAttachment::Id(target),
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+                sample_count: 1,
+            };
+            render_context.begin_pass(
+                &pass_descriptor,
+                &render_resource_bindings,
+                &mut |render_pass| {
+                    render_pass.set_pipeline(pipeline);
+                    render_pass.set_bind_group(0, bind_group_descriptor_id, bind_group.id, None);
+                    render_pass.draw(vertices.clone(), 0..1);
+                },
+            );
+        }
+
+        output.set(OUT_EXPOSURE, RenderResourceId::Texture(exposure_texture));
+    }
+}
+
+fn compile_pipeline(
+    render_context: &dyn RenderContext,
+    pipeline_compiler: &mut PipelineCompiler,
+    pipelines: &mut Assets<PipelineDescriptor>,
+    shaders: &mut Assets<Shader>,
+    vertex_buffer_descriptors: &VertexBufferDescriptors,
+    pipeline_handle: Handle<PipelineDescriptor>,
+) -> Handle<PipelineDescriptor> {
+    // specializations override the primitive topology, so keep the one of the source pipeline
+    let specialization = PipelineSpecialization {
+        primitive_topology: pipelines.get(&pipeline_handle).unwrap().primitive_topology,
+        ..Default::default()
+    };
+    match pipeline_compiler.get_specialized_pipeline(pipeline_handle, &specialization) {
+        Some(pipeline) => pipeline,
+        None => pipeline_compiler.compile_pipeline(
+            render_context.resources(),
+            pipelines,
+            shaders,
+            pipeline_handle,
+            vertex_buffer_descriptors,
+            &specialization,
+        ),
+    }
+}
+
+fn auto_exposure_params(
+    auto_exposure: &AutoExposure,
+    histogram: [f32; 4],
+    sample_count: f32,
+    adaptation: f32,
+) -> [[f32; 4]; 3] {
+    [
+        [histogram[0], histogram[1], sample_count, histogram[3]],
+        [
+            auto_exposure.min_ev100,
+            auto_exposure.max_ev100,
+            adaptation,
+            0.0,
+        ],
+        [
+            auto_exposure.low_percentile,
+            auto_exposure.high_percentile,
+            0.0,
+            0.0,
+        ],
+    ]
+}
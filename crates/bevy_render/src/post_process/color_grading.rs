@@ -0,0 +1,110 @@
+use crate::texture::{Texture, TextureFormat};
+use bevy_asset::Handle;
+use bevy_math::Vec2;
+
+/// Grades the colors of the camera it is attached to in the final post-process pass, after the camera's
+/// [PostProcessStack](super::PostProcessStack) (if any). Exposure, contrast, and saturation are applied first, then the
+/// lookup table.
+///
+/// A lut of size N is an N*N pixels wide and N pixels high sRGB image made of one NxN tile per blue value, where red
+/// increases to the right and green increases downwards. To make one, export an identity lut with
+/// [ColorGrading::identity_lut], grade it together with a screenshot in an external tool, and load the result as a
+/// texture.
+#[derive(Debug, Clone)]
+pub struct ColorGrading {
+    /// Exposure in stops. 0.0 leaves the image unchanged.
+    pub exposure: f32,
+    /// Scales the distance of colors from middle gray. 1.0 leaves the image unchanged.
+    pub contrast: f32,
+    /// Scales the distance of colors from their luminance. 0.0 is grayscale and 1.0 leaves the image unchanged.
+    pub saturation: f32,
+    pub lut: Option<Handle<Texture>>,
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        ColorGrading {
+            exposure: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            lut: None,
+        }
+    }
+}
+
+impl ColorGrading {
+    pub fn from_lut(lut: Handle<Texture>) -> Self {
+        ColorGrading {
+            lut: Some(lut),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a lut of the given size that maps every color to itself. Panics if `size` is less than 2, which can't
+    /// hold both the darkest and the brightest value of a channel.
+    pub fn identity_lut(size: u32) -> Texture {
+        assert!(
+            size >= 2,
+            "identity luts need a size of at least 2, got {}",
+            size
+        );
+        let max = (size - 1) as f32;
+        let encode = |value: u32| (value as f32 / max * 255.0).round() as u8;
+        let mut data = Vec::with_capacity((size * size * size * 4) as usize);
+        for green in 0..size {
+            for blue in 0..size {
+                for red in 0..size {
+                    data.extend_from_slice(&[encode(red), encode(green), encode(blue), 255]);
+                }
+            }
+        }
+
+        Texture::new(
+            Vec2::new((size * size) as f32, size as f32),
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    /// Writes an identity lut of the given size to a png file
+    #[cfg(feature = "png")]
+    pub fn save_identity_lut(
+        path: impl AsRef<std::path::Path>,
+        size: u32,
+    ) -> Result<(), image::ImageError> {
+        let lut = ColorGrading::identity_lut(size);
+        image::save_buffer(path, &lut.data, size * size, size, image::ColorType::Rgba8)
+    }
+
+    pub(crate) fn params(&self, lut_size: f32) -> [f32; 4] {
+        [
+            2.0f32.powf(self.exposure),
+            self.contrast,
+            self.saturation,
+            lut_size,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorGrading;
+
+    #[test]
+    fn identity_lut() {
+        let lut = ColorGrading::identity_lut(4);
+        assert_eq!(lut.size.x(), 16.0);
+        assert_eq!(lut.size.y(), 4.0);
+        let pixel = |x: usize, y: usize| &lut.data[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+        assert_eq!(pixel(0, 0), &[0, 0, 0, 255]);
+        // tile 2 (blue), column 1 (red), row 3 (green)
+        assert_eq!(pixel(2 * 4 + 1, 3), &[85, 255, 170, 255]);
+        assert_eq!(pixel(15, 3), &[255, 255, 255, 255]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2")]
+    fn identity_lut_too_small() {
+        ColorGrading::identity_lut(0);
+    }
+}
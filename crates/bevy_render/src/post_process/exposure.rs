@@ -0,0 +1,126 @@
+/// The exposure of the camera it is attached to, applied to the camera's image before its
+/// [PostProcessStack](super::PostProcessStack) (and so before tonemapping).
+///
+/// Exposure is given as an exposure value at ISO 100 (EV100), where each step halves the light that reaches the image.
+/// Colors are scaled by `1 / (1.2 * 2^ev100)`, which is how a camera with the matching aperture, shutter speed, and
+/// sensitivity would expose a scene lit in physical units. Light intensities in bevy aren't physical, so the default
+/// exposure is [Exposure::NEUTRAL_EV100], which leaves the image unchanged.
+#[derive(Debug, Clone)]
+pub struct Exposure {
+    pub mode: ExposureMode,
+    /// Added to the exposure, in stops. Positive values brighten the image.
+    pub compensation: f32,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExposureMode {
+    Manual {
+        ev100: f32,
+    },
+    /// Meters the camera's image every frame and adapts the exposure to it
+    Auto(AutoExposure),
+}
+
+/// Settings of [ExposureMode::Auto]. The luminance of the camera's image is sorted into a histogram, and the average
+/// of the bins between `low_percentile` and `high_percentile` is exposed like a reflected light meter would: mid gray
+/// ends up at about 10% brightness, before [Exposure::compensation] is applied.
+#[derive(Debug, Clone)]
+pub struct AutoExposure {
+    pub min_ev100: f32,
+    pub max_ev100: f32,
+    /// How fast the exposure adapts to the metered exposure. Higher values adapt faster, and 0.0 doesn't adapt at
+    /// all after the first frame.
+    pub adaptation_speed: f32,
+    /// The fraction of darkest pixels that is ignored, between 0.0 and 1.0
+    pub low_percentile: f32,
+    /// The fraction of pixels (starting from the darkest) above which brighter pixels are ignored, between 0.0 and 1.0
+    pub high_percentile: f32,
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        AutoExposure {
+            min_ev100: -8.0,
+            max_ev100: 16.0,
+            adaptation_speed: 2.0,
+            low_percentile: 0.1,
+            high_percentile: 0.9,
+        }
+    }
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Exposure::manual(Exposure::NEUTRAL_EV100)
+    }
+}
+
+impl Exposure {
+    /// The EV100 that scales colors by 1.0
+    pub const NEUTRAL_EV100: f32 = -0.263_034_4;
+
+    pub fn manual(ev100: f32) -> Self {
+        Exposure {
+            mode: ExposureMode::Manual { ev100 },
+            compensation: 0.0,
+        }
+    }
+
+    /// Returns the exposure of a camera with the given aperture (f-number), shutter speed (in seconds), and sensitivity
+    /// (ISO)
+    pub fn physical(aperture: f32, shutter_speed: f32, iso: f32) -> Self {
+        Exposure::manual((aperture * aperture / shutter_speed * 100.0 / iso).log2())
+    }
+
+    pub fn auto(auto_exposure: AutoExposure) -> Self {
+        Exposure {
+            mode: ExposureMode::Auto(auto_exposure),
+            compensation: 0.0,
+        }
+    }
+
+    pub fn with_compensation(mut self, compensation: f32) -> Self {
+        self.compensation = compensation;
+        self
+    }
+
+    /// Returns the factor colors are scaled by at the given EV100
+    pub fn ev100_to_scale(ev100: f32) -> f32 {
+        1.0 / (1.2 * 2.0f32.powf(ev100))
+    }
+
+    /// Returns the scale applied to colors. In auto mode this is only the compensation, as the metered exposure is
+    /// applied on the gpu.
+    pub(crate) fn params(&self) -> [f32; 4] {
+        let compensation = 2.0f32.powf(self.compensation);
+        match self.mode {
+            ExposureMode::Manual { ev100 } => [
+                Exposure::ev100_to_scale(ev100) * compensation,
+                0.0,
+                0.0,
+                0.0,
+            ],
+            ExposureMode::Auto(_) => [compensation, 0.0, 0.0, 0.0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Exposure;
+
+    #[test]
+    fn ev100() {
+        let ev100 = |exposure: Exposure| match exposure.mode {
+            super::ExposureMode::Manual { ev100 } => ev100,
+            super::ExposureMode::Auto(_) => unreachable!(),
+        };
+        assert!(ev100(Exposure::physical(1.0, 1.0, 100.0)).abs() < 1e-5);
+        // "sunny 16": f/16, 1/100 s, ISO 100
+        assert!((ev100(Exposure::physical(16.0, 0.01, 100.0)) - 14.643_856).abs() < 1e-4);
+        // doubling the sensitivity lowers the exposure value by one stop
+        assert!((ev100(Exposure::physical(16.0, 0.01, 200.0)) - 13.643_856).abs() < 1e-4);
+        assert!((Exposure::default().params()[0] - 1.0).abs() < 1e-5);
+        assert!((Exposure::default().with_compensation(1.0).params()[0] - 2.0).abs() < 1e-5);
+    }
+}
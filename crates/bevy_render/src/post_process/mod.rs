@@ -0,0 +1,566 @@
+mod auto_exposure_node;
+mod color_grading;
+mod exposure;
+mod post_process_node;
+mod prepass_node;
+
+pub use auto_exposure_node::*;
+pub use color_grading::*;
+pub use exposure::*;
+pub use post_process_node::*;
+pub use prepass_node::*;
+
+use crate::{
+    camera::Camera,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+    },
+    pipeline::{
+        BlendDescriptor, BlendFactor, BlendOperation, ColorStateDescriptor, ColorWrite,
+        CompareFunction, CullMode, DepthStencilStateDescriptor, FrontFace, PipelineDescriptor,
+        PrimitiveTopology, RasterizationStateDescriptor, StencilStateFaceDescriptor,
+    },
+    render_graph::{
+        base::{self, MainPass},
+        PassNode, RenderGraph, WindowSwapChainNode, WindowTextureNode,
+    },
+    renderer::{BufferId, BufferInfo, BufferUsage, RenderContext},
+    shader::{Shader, ShaderStage, ShaderStages},
+    texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
+    Color,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core::AsBytes;
+use bevy_ecs::{Resources, World};
+use bevy_window::WindowId;
+use std::collections::HashSet;
+
+pub const POST_PROCESS_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(171893658223649273106582301952717347061);
+pub const AUTO_EXPOSURE_HISTOGRAM_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(80371651462903425174627911059432180926);
+pub const AUTO_EXPOSURE_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(263096514395815937712290174818376549218);
+pub const PREPASS_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
+    Handle::from_u128(119534372951340128755960683209151273064);
+
+/// A full screen effect applied to the image of a camera
+#[derive(Debug, Clone)]
+pub enum PostProcessEffect {
+    /// Adds a blurred copy of the parts of the image that are brighter than `threshold`. `radius` is in physical pixels.
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        radius: f32,
+    },
+    /// Scales colors by `exposure` and compresses them with the Reinhard operator. Cameras render to 8 bit textures, so
+    /// this mostly softens highlights.
+    Tonemap { exposure: f32 },
+    /// Darkens the image towards its corners, starting `radius` from the center (in uv units)
+    Vignette {
+        intensity: f32,
+        radius: f32,
+        smoothness: f32,
+    },
+    /// Blurs moving objects along their motion since the previous frame, scaled by `intensity` (1.0 blurs over the
+    /// whole motion). Each pixel takes `samples` samples. Objects with [NoMotionBlur] stay sharp.
+    MotionBlur { intensity: f32, samples: u32 },
+    /// Blurs what is nearer or farther than `focal_distance` (in world units from the camera), gathering samples from
+    /// a disc around each pixel. The blur radius grows with the distance from the focal plane: it is `aperture`
+    /// physical pixels far behind the focal plane, and capped at `max_radius` physical pixels in front of it.
+    DepthOfField {
+        focal_distance: f32,
+        aperture: f32,
+        max_radius: f32,
+    },
+}
+
+impl PostProcessEffect {
+    pub(crate) fn shader_def(&self) -> &'static str {
+        match self {
+            PostProcessEffect::Bloom { .. } => "POST_PROCESS_BLOOM",
+            PostProcessEffect::Tonemap { .. } => "POST_PROCESS_TONEMAP",
+            PostProcessEffect::Vignette { .. } => "POST_PROCESS_VIGNETTE",
+            PostProcessEffect::MotionBlur { .. } => "POST_PROCESS_MOTION_BLUR",
+            PostProcessEffect::DepthOfField { .. } => "POST_PROCESS_DEPTH_OF_FIELD",
+        }
+    }
+
+    pub(crate) fn params(&self) -> [f32; 4] {
+        match self {
+            PostProcessEffect::Bloom {
+                threshold,
+                intensity,
+                radius,
+            } => [*threshold, *intensity, *radius, 0.0],
+            PostProcessEffect::Tonemap { exposure } => [*exposure, 0.0, 0.0, 0.0],
+            PostProcessEffect::Vignette {
+                intensity,
+                radius,
+                smoothness,
+            } => [*intensity, *radius, *smoothness, 0.0],
+            PostProcessEffect::MotionBlur { intensity, samples } => {
+                [*intensity, *samples as f32, 0.0, 0.0]
+            }
+            PostProcessEffect::DepthOfField {
+                focal_distance,
+                aperture,
+                max_radius,
+            } => [*focal_distance, *aperture, *max_radius, 0.0],
+        }
+    }
+}
+
+/// An ordered list of [PostProcessEffect]s applied to the image of the camera it is attached to. The camera must have a
+/// name. The first time it gets a stack, the camera is moved out of the main pass into a pass of its own, which renders
+/// into an offscreen texture. Each effect then reads the output of the previous one, and the last effect replaces the
+/// contents of the camera's window before later passes (ex: the ui pass) draw on top of it.
+///
+/// Effects can be added, removed, and reordered at any time. A camera whose stack is empty (or removed) is copied to
+/// the window unchanged. An [Exposure] component on the camera is applied in the first pass, and a [ColorGrading]
+/// component in the last pass.
+#[derive(Debug, Clone, Default)]
+pub struct PostProcessStack {
+    pub effects: Vec<PostProcessEffect>,
+}
+
+impl PostProcessStack {
+    pub fn new(effects: Vec<PostProcessEffect>) -> Self {
+        PostProcessStack { effects }
+    }
+}
+
+/// Keeps an entity sharp when the camera it is drawn by has a [PostProcessEffect::MotionBlur]. Its motion vectors are
+/// still rendered.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMotionBlur;
+
+/// The cameras that have been given their own post-process graph
+#[derive(Default)]
+pub struct PostProcessGraphState {
+    cameras: HashSet<String>,
+}
+
+pub fn build_post_process_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Bgra8UnormSrgb,
+            color_blend: BlendDescriptor::REPLACE,
+            alpha_blend: BlendDescriptor::REPLACE,
+            write_mask: ColorWrite::ALL,
+        }],
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("post_process.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("post_process.frag"),
+            ))),
+        })
+    }
+}
+
+pub fn build_auto_exposure_histogram_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Rgba16Float,
+            color_blend: BlendDescriptor {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendDescriptor::REPLACE,
+            write_mask: ColorWrite::ALL,
+        }],
+        primitive_topology: PrimitiveTopology::PointList,
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("auto_exposure_histogram.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("auto_exposure_histogram.frag"),
+            ))),
+        })
+    }
+}
+
+pub fn build_auto_exposure_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Rgba16Float,
+            color_blend: BlendDescriptor::REPLACE,
+            alpha_blend: BlendDescriptor::REPLACE,
+            write_mask: ColorWrite::ALL,
+        }],
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("post_process.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("auto_exposure.frag"),
+            ))),
+        })
+    }
+}
+
+pub fn build_prepass_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    PipelineDescriptor {
+        rasterization_state: Some(RasterizationStateDescriptor {
+            front_face: FrontFace::Ccw,
+            cull_mode: CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        depth_stencil_state: Some(DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+            stencil_front: StencilStateFaceDescriptor::IGNORE,
+            stencil_back: StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }),
+        color_states: vec![ColorStateDescriptor {
+            format: TextureFormat::Rgba16Float,
+            color_blend: BlendDescriptor::REPLACE,
+            alpha_blend: BlendDescriptor::REPLACE,
+            write_mask: ColorWrite::ALL,
+        }],
+        ..PipelineDescriptor::new(ShaderStages {
+            vertex: shaders.add(Shader::from_glsl(
+                ShaderStage::Vertex,
+                include_str!("prepass.vert"),
+            )),
+            fragment: Some(shaders.add(Shader::from_glsl(
+                ShaderStage::Fragment,
+                include_str!("prepass.frag"),
+            ))),
+        })
+    }
+}
+
+/// Adds the render graph nodes that apply a camera's [PostProcessStack]
+pub trait PostProcessGraphBuilder {
+    fn add_post_process_graph(&mut self, camera_name: &str) -> &mut Self;
+}
+
+impl PostProcessGraphBuilder for RenderGraph {
+    fn add_post_process_graph(&mut self, camera_name: &str) -> &mut Self {
+        let color_texture = format!("{}_post_process_color", camera_name);
+        let depth_texture = format!("{}_post_process_depth", camera_name);
+        let sampled_color_attachment = format!("{}_post_process_sampled_color", camera_name);
+        let pass = format!("{}_pass", camera_name);
+        let post_process = format!("{}_post_process", camera_name);
+        let auto_exposure = format!("{}_auto_exposure", camera_name);
+        let prepass = format!("{}_prepass", camera_name);
+        // textures that follow the msaa sample count are multisampled attachments of the camera's pass
+        let window_texture = |format, use_msaa, usage| {
+            let mut node = WindowTextureNode::new(
+                WindowId::primary(),
+                TextureDescriptor {
+                    size: Extent3d {
+                        depth: 1,
+                        width: 1,
+                        height: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage,
+                },
+            );
+            if use_msaa {
+                node.use_msaa();
+            }
+            node
+        };
+
+        self.add_node(
+            color_texture.clone(),
+            window_texture(
+                TextureFormat::Bgra8UnormSrgb,
+                false,
+                TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT,
+            ),
+        );
+        let mut sampled_color_attachment_node = window_texture(
+            TextureFormat::Bgra8UnormSrgb,
+            true,
+            TextureUsage::OUTPUT_ATTACHMENT,
+        );
+        sampled_color_attachment_node.only_with_msaa();
+        self.add_node(
+            sampled_color_attachment.clone(),
+            sampled_color_attachment_node,
+        );
+        self.add_node(
+            depth_texture.clone(),
+            window_texture(
+                TextureFormat::Depth32Float,
+                true,
+                TextureUsage::OUTPUT_ATTACHMENT,
+            ),
+        );
+
+        let mut pass_node = PassNode::<&MainPass>::new(PassDescriptor {
+            color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                attachment: TextureAttachment::Input("color_attachment".to_string()),
+                resolve_target: Some(TextureAttachment::Input("color_resolve_target".to_string())),
+                ops: Operations {
+                    load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                attachment: TextureAttachment::Input("depth".to_string()),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: 1,
+        });
+        pass_node.use_default_clear_color(0);
+        pass_node.use_msaa();
+        pass_node.add_camera(camera_name);
+        self.add_node(pass.clone(), pass_node);
+        self.add_node(
+            post_process.clone(),
+            PostProcessNode::new(camera_name, WindowId::primary()),
+        );
+        self.add_node(auto_exposure.clone(), AutoExposureNode::new(camera_name));
+        self.add_node(
+            prepass.clone(),
+            PrepassNode::new(camera_name, WindowId::primary()),
+        );
+
+        self.add_slot_edge(
+            sampled_color_attachment,
+            WindowTextureNode::OUT_TEXTURE,
+            pass.clone(),
+            "color_attachment",
+        )
+        .unwrap();
+        self.add_slot_edge(
+            color_texture.clone(),
+            WindowTextureNode::OUT_TEXTURE,
+            pass.clone(),
+            "color_resolve_target",
+        )
+        .unwrap();
+
+        // with msaa, the last effect writes to the main pass's multisampled attachment, so passes that resolve it into
+        // the swap chain after this (ex: the ui pass) keep the effect's output
+        self.add_slot_edge(
+            base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+            WindowTextureNode::OUT_TEXTURE,
+            post_process.clone(),
+            PostProcessNode::OUT_COLOR_ATTACHMENT,
+        )
+        .unwrap();
+        self.add_slot_edge(
+            base::node::PRIMARY_SWAP_CHAIN,
+            WindowSwapChainNode::OUT_TEXTURE,
+            post_process.clone(),
+            PostProcessNode::OUT_COLOR_RESOLVE_TARGET,
+        )
+        .unwrap();
+
+        self.add_slot_edge(
+            depth_texture,
+            WindowTextureNode::OUT_TEXTURE,
+            pass.clone(),
+            "depth",
+        )
+        .unwrap();
+        self.add_slot_edge(
+            color_texture.clone(),
+            WindowTextureNode::OUT_TEXTURE,
+            post_process.clone(),
+            PostProcessNode::IN_COLOR,
+        )
+        .unwrap();
+        self.add_slot_edge(
+            color_texture,
+            WindowTextureNode::OUT_TEXTURE,
+            auto_exposure.clone(),
+            AutoExposureNode::IN_COLOR,
+        )
+        .unwrap();
+        self.add_slot_edge(
+            auto_exposure.clone(),
+            AutoExposureNode::OUT_EXPOSURE,
+            post_process.clone(),
+            PostProcessNode::IN_EXPOSURE,
+        )
+        .unwrap();
+        self.add_slot_edge(
+            prepass.clone(),
+            PrepassNode::OUT_MOTION_VECTORS,
+            post_process.clone(),
+            PostProcessNode::IN_MOTION_VECTORS,
+        )
+        .unwrap();
+        self.add_slot_edge(
+            prepass.clone(),
+            PrepassNode::OUT_DEPTH,
+            post_process.clone(),
+            PostProcessNode::IN_DEPTH,
+        )
+        .unwrap();
+
+        // the camera's pass shares the main pass's dependencies (camera and buffer nodes). the effects replace the main
+        // pass's output, so they run after it and before everything that draws on top of it
+        let main_pass_outputs = self
+            .iter_node_outputs(base::node::MAIN_PASS)
+            .unwrap()
+            .map(|(_edge, node)| node.id)
+            .collect::<HashSet<_>>();
+        self.add_node_edge(base::node::MAIN_PASS, pass.clone())
+            .unwrap();
+        self.add_node_edge(base::node::MAIN_PASS, prepass).unwrap();
+        self.add_node_edge(pass.clone(), post_process.clone())
+            .unwrap();
+        self.add_node_edge(pass, auto_exposure).unwrap();
+        self.add_node_edge(base::node::MAIN_PASS, post_process.clone())
+            .unwrap();
+        for node in main_pass_outputs {
+            self.add_node_edge(post_process.clone(), node).unwrap();
+        }
+
+        self
+    }
+}
+
+/// Gives each named camera with a [PostProcessStack], [ColorGrading], or [Exposure] its own pass and post-process nodes,
+/// the first time it has one
+pub fn post_process_graph_system(world: &mut World, resources: &mut Resources) {
+    let mut state = resources.get_mut::<PostProcessGraphState>().unwrap();
+    let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
+    for (camera, stack, color_grading, exposure) in &mut world.query::<(
+        &Camera,
+        Option<&PostProcessStack>,
+        Option<&ColorGrading>,
+        Option<&Exposure>,
+    )>() {
+        let camera_name = match camera.name {
+            Some(ref name) => name,
+            None => continue,
+        };
+        if (stack.is_none() && color_grading.is_none() && exposure.is_none())
+            || state.cameras.contains(camera_name)
+        {
+            continue;
+        }
+
+        if camera.window != WindowId::primary() {
+            log::warn!(
+                "Post-processing is only supported for cameras of the primary window. Ignoring the effects of {}.",
+                camera_name
+            );
+        } else if let Ok(main_pass) =
+            render_graph.get_node_mut::<PassNode<&MainPass>>(base::node::MAIN_PASS)
+        {
+            if main_pass.remove_camera(camera_name) {
+                render_graph.add_post_process_graph(camera_name);
+            } else {
+                log::warn!(
+                    "Post-processing is only supported for cameras of the main pass. Ignoring the effects of {}.",
+                    camera_name
+                );
+            }
+        }
+        state.cameras.insert(camera_name.clone());
+    }
+}
+
+/// Returns the uniform buffer in `buffer`, recreating it if its contents changed
+pub(crate) fn uniform_buffer<T: AsBytes + Copy + PartialEq>(
+    render_context: &mut dyn RenderContext,
+    buffer: &mut Option<(BufferId, T)>,
+    value: T,
+) -> BufferId {
+    let render_resource_context = render_context.resources_mut();
+    if let Some((buffer_id, current_value)) = buffer {
+        if *current_value == value {
+            return *buffer_id;
+        }
+        render_resource_context.remove_buffer(*buffer_id);
+    }
+
+    let buffer_id = render_resource_context.create_buffer_with_data(
+        BufferInfo {
+            size: std::mem::size_of::<T>(),
+            buffer_usage: BufferUsage::UNIFORM,
+            ..Default::default()
+        },
+        value.as_bytes(),
+    );
+    *buffer = Some((buffer_id, value));
+    buffer_id
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shader::{Shader, ShaderStage};
+
+    #[test]
+    fn post_process_shaders_compile() {
+        // compilation panics on glsl errors
+        let vertex = Shader::from_glsl(ShaderStage::Vertex, include_str!("post_process.vert"))
+            .get_spirv(None);
+        assert!(!vertex.is_empty());
+
+        for shader_def in [
+            "POST_PROCESS_COPY",
+            "POST_PROCESS_BLOOM",
+            "POST_PROCESS_TONEMAP",
+            "POST_PROCESS_VIGNETTE",
+            "POST_PROCESS_COLOR_GRADING",
+            "POST_PROCESS_COLOR_GRADING POST_PROCESS_COLOR_GRADING_LUT",
+            "POST_PROCESS_EXPOSURE",
+            "POST_PROCESS_EXPOSURE POST_PROCESS_AUTO_EXPOSURE",
+            "POST_PROCESS_MOTION_BLUR",
+            "POST_PROCESS_DEPTH_OF_FIELD",
+        ]
+        .iter()
+        {
+            let shader_defs = shader_def
+                .split(' ')
+                .map(|shader_def| shader_def.to_string())
+                .collect::<Vec<_>>();
+            let fragment =
+                Shader::from_glsl(ShaderStage::Fragment, include_str!("post_process.frag"))
+                    .get_spirv(Some(&shader_defs));
+            assert!(!fragment.is_empty());
+        }
+
+        for (stage, source) in [
+            (
+                ShaderStage::Vertex,
+                include_str!("auto_exposure_histogram.vert"),
+            ),
+            (
+                ShaderStage::Fragment,
+                include_str!("auto_exposure_histogram.frag"),
+            ),
+            (ShaderStage::Fragment, include_str!("auto_exposure.frag")),
+            (ShaderStage::Vertex, include_str!("prepass.vert")),
+            (ShaderStage::Fragment, include_str!("prepass.frag")),
+        ]
+        .iter()
+        {
+            assert!(!Shader::from_glsl(*stage, source).get_spirv(None).is_empty());
+        }
+    }
+}
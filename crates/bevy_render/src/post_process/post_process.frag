@@ -0,0 +1,161 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) uniform texture2D PostProcess_input;
+layout(set = 0, binding = 1) uniform sampler PostProcess_input_sampler;
+layout(set = 0, binding = 2) uniform PostProcess_params {
+    vec4 Params;
+};
+# ifdef POST_PROCESS_COLOR_GRADING
+layout(set = 0, binding = 3) uniform PostProcess_grading {
+    vec4 Grading;
+};
+# ifdef POST_PROCESS_COLOR_GRADING_LUT
+layout(set = 0, binding = 4) uniform texture2D PostProcess_lut;
+
+vec3 linear_to_srgb(vec3 color) {
+    vec3 low = color * 12.92;
+    vec3 high = 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055;
+    return mix(high, low, vec3(lessThanEqual(color, vec3(0.0031308))));
+}
+# endif
+# endif
+# ifdef POST_PROCESS_EXPOSURE
+layout(set = 0, binding = 5) uniform PostProcess_exposure {
+    vec4 Exposure;
+};
+# ifdef POST_PROCESS_AUTO_EXPOSURE
+layout(set = 0, binding = 6) uniform texture2D PostProcess_auto_exposure;
+# endif
+# endif
+# ifdef POST_PROCESS_MOTION_BLUR
+layout(set = 0, binding = 7) uniform texture2D PostProcess_motion_vectors;
+# endif
+# ifdef POST_PROCESS_DEPTH_OF_FIELD
+layout(set = 0, binding = 8) uniform texture2D PostProcess_depth;
+layout(set = 0, binding = 9) uniform PostProcess_depth_params {
+    // the third and fourth row of the camera's projection in its third and fourth column
+    vec4 DepthParams;
+};
+
+// returns the distance from the camera of the surface at the given texel
+float view_distance(ivec2 texel) {
+    texel = clamp(texel, ivec2(0), textureSize(sampler2D(PostProcess_depth, PostProcess_input_sampler), 0) - 1);
+    float depth = texelFetch(sampler2D(PostProcess_depth, PostProcess_input_sampler), texel, 0).r;
+    return (depth * DepthParams.w - DepthParams.y) / (depth * DepthParams.z - DepthParams.x);
+}
+
+// returns the blur radius in pixels of a surface at the given distance
+float circle_of_confusion(float distance) {
+    return min(Params.y * abs(1.0 - Params.x / max(distance, 0.0001)), Params.z);
+}
+# endif
+
+void main() {
+    vec4 color = texture(sampler2D(PostProcess_input, PostProcess_input_sampler), v_Uv);
+# ifdef POST_PROCESS_BLOOM
+    // Params: threshold, intensity, radius in pixels
+    vec2 texel_size = 1.0 / vec2(textureSize(sampler2D(PostProcess_input, PostProcess_input_sampler), 0));
+    vec3 bloom = vec3(0.0);
+    float total_weight = 0.0;
+    for (int x = -4; x <= 4; x++) {
+        for (int y = -4; y <= 4; y++) {
+            vec2 offset = vec2(x, y) * Params.z / 4.0 * texel_size;
+            float weight = exp(-float(x * x + y * y) / 8.0);
+            vec3 sample_color = texture(sampler2D(PostProcess_input, PostProcess_input_sampler), v_Uv + offset).rgb;
+            bloom += max(sample_color - vec3(Params.x), vec3(0.0)) * weight;
+            total_weight += weight;
+        }
+    }
+    color.rgb += bloom / total_weight * Params.y;
+# endif
+# ifdef POST_PROCESS_MOTION_BLUR
+    // Params: intensity, samples. motion vectors hold the motion since the previous frame in uv units (xy) and whether
+    // the surface opted out of motion blur (z)
+    vec3 motion = texture(sampler2D(PostProcess_motion_vectors, PostProcess_input_sampler), v_Uv).xyz;
+    if (motion.z < 0.5) {
+        int samples = max(int(Params.y), 2);
+        vec2 velocity = motion.xy * Params.x;
+        vec3 blurred = vec3(0.0);
+        float total_weight = 0.0;
+        for (int i = 0; i < samples; i++) {
+            vec2 uv = v_Uv - velocity * (float(i) / float(samples - 1) - 0.5);
+            // surfaces that opted out aren't smeared over their surroundings
+            float weight = 1.0 - step(0.5, texture(sampler2D(PostProcess_motion_vectors, PostProcess_input_sampler), uv).z);
+            blurred += texture(sampler2D(PostProcess_input, PostProcess_input_sampler), uv).rgb * weight;
+            total_weight += weight;
+        }
+        if (total_weight > 0.0) {
+            color.rgb = blurred / total_weight;
+        }
+    }
+# endif
+# ifdef POST_PROCESS_DEPTH_OF_FIELD
+    // Params: focal distance, aperture, max radius in pixels
+    vec2 size = vec2(textureSize(sampler2D(PostProcess_input, PostProcess_input_sampler), 0));
+    ivec2 center_texel = ivec2(v_Uv * size);
+    float center_distance = view_distance(center_texel);
+    float center_radius = circle_of_confusion(center_distance);
+    vec3 gathered = color.rgb;
+    float total_weight = 1.0;
+    const int DEPTH_OF_FIELD_SAMPLES = 48;
+    for (int i = 0; i < DEPTH_OF_FIELD_SAMPLES; i++) {
+        // samples spiral out by the golden angle, which covers the disc evenly
+        float radius = sqrt((float(i) + 0.5) / float(DEPTH_OF_FIELD_SAMPLES)) * Params.z;
+        float angle = float(i) * 2.39996;
+        vec2 offset = vec2(cos(angle), sin(angle)) * radius;
+        float sample_distance = view_distance(center_texel + ivec2(offset));
+        // a sample is blurred over this pixel if its circle of confusion reaches it. surfaces behind this pixel can't
+        // be blurred over it further than it is blurred itself, which keeps sharp foregrounds from bleeding
+        float sample_radius = circle_of_confusion(sample_distance);
+        if (sample_distance > center_distance) {
+            sample_radius = min(sample_radius, center_radius);
+        }
+        float weight = clamp(sample_radius - radius + 1.0, 0.0, 1.0);
+        gathered += texture(sampler2D(PostProcess_input, PostProcess_input_sampler), v_Uv + offset / size).rgb * weight;
+        total_weight += weight;
+    }
+    color.rgb = gathered / total_weight;
+# endif
+# ifdef POST_PROCESS_EXPOSURE
+    // Exposure: color scale. auto exposure adds the metered ev100
+    float exposure = Exposure.x;
+# ifdef POST_PROCESS_AUTO_EXPOSURE
+    float ev100 = texelFetch(sampler2D(PostProcess_auto_exposure, PostProcess_input_sampler), ivec2(0, 0), 0).r;
+    exposure /= 1.2 * exp2(ev100);
+# endif
+    color.rgb *= exposure;
+# endif
+# ifdef POST_PROCESS_TONEMAP
+    // Params: exposure
+    vec3 exposed = color.rgb * Params.x;
+    color.rgb = exposed / (exposed + vec3(1.0));
+# endif
+# ifdef POST_PROCESS_VIGNETTE
+    // Params: intensity, radius, smoothness
+    float distance_from_center = distance(v_Uv, vec2(0.5));
+    color.rgb *= 1.0 - Params.x * smoothstep(Params.y, Params.y + Params.z, distance_from_center);
+# endif
+# ifdef POST_PROCESS_COLOR_GRADING
+    // Grading: exposure scale, contrast, saturation, lut size
+    color.rgb *= Grading.x;
+    color.rgb = max((color.rgb - vec3(0.18)) * Grading.y + vec3(0.18), vec3(0.0));
+    float luminance = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+    color.rgb = max(mix(vec3(luminance), color.rgb, Grading.z), vec3(0.0));
+# ifdef POST_PROCESS_COLOR_GRADING_LUT
+    // luts are graded in srgb, so they are indexed with srgb colors
+    float size = Grading.w;
+    vec3 cell = linear_to_srgb(clamp(color.rgb, 0.0, 1.0)) * (size - 1.0);
+    float tile = floor(cell.b);
+    float next_tile = min(tile + 1.0, size - 1.0);
+    float v = (cell.g + 0.5) / size;
+    vec3 graded = texture(sampler2D(PostProcess_lut, PostProcess_input_sampler), vec2((tile * size + cell.r + 0.5) / (size * size), v)).rgb;
+    vec3 next_graded = texture(sampler2D(PostProcess_lut, PostProcess_input_sampler), vec2((next_tile * size + cell.r + 0.5) / (size * size), v)).rgb;
+    color.rgb = mix(graded, next_graded, cell.b - tile);
+# endif
+# endif
+    o_Target = color;
+}
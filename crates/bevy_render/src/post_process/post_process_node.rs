@@ -0,0 +1,360 @@
+use super::{
+    uniform_buffer, ColorGrading, Exposure, ExposureMode, PostProcessEffect, PostProcessStack,
+    POST_PROCESS_PIPELINE_HANDLE,
+};
+use crate::{
+    camera::{ActiveCameras, Camera},
+    pass::{LoadOp, Operations, PassDescriptor, TextureAttachment},
+    pipeline::{
+        PipelineCompiler, PipelineDescriptor, PipelineSpecialization, ShaderSpecialization,
+        VertexBufferDescriptors,
+    },
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots, TransientTexturePool},
+    renderer::{
+        BindGroup, BufferId, RenderContext, RenderResourceBindings, RenderResourceId,
+        RenderResourceType, SamplerId, TextureId,
+    },
+    shader::Shader,
+    texture::{
+        Extent3d, FilterMode, SamplerDescriptor, Texture, TextureDescriptor, TextureDimension,
+        TextureFormat, TextureUsage, TEXTURE_ASSET_INDEX,
+    },
+    Color,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Resources, World};
+use bevy_window::{WindowId, Windows};
+
+/// Runs the effects of a camera's [PostProcessStack] on the camera's image, one full screen pass per effect. The last
+/// pass writes to the node's color attachment and resolves it into the resolve target when msaa is enabled, or writes
+/// straight into the resolve target otherwise.
+pub struct PostProcessNode {
+    camera_name: String,
+    window_id: WindowId,
+    inputs: Vec<ResourceSlotInfo>,
+    sampler: Option<SamplerId>,
+    params_buffers: Vec<Option<(BufferId, [f32; 4])>>,
+    grading_buffer: Option<(BufferId, [f32; 4])>,
+    exposure_buffer: Option<(BufferId, [f32; 4])>,
+    depth_params_buffer: Option<(BufferId, [f32; 4])>,
+}
+
+impl PostProcessNode {
+    pub const IN_COLOR: &'static str = "color";
+    pub const OUT_COLOR_ATTACHMENT: &'static str = "color_attachment";
+    pub const OUT_COLOR_RESOLVE_TARGET: &'static str = "color_resolve_target";
+    pub const IN_EXPOSURE: &'static str = "exposure";
+    pub const IN_MOTION_VECTORS: &'static str = "motion_vectors";
+    pub const IN_DEPTH: &'static str = "depth";
+
+    pub fn new(camera_name: &str, window_id: WindowId) -> Self {
+        PostProcessNode {
+            camera_name: camera_name.to_string(),
+            window_id,
+            inputs: vec![
+                ResourceSlotInfo::new(PostProcessNode::IN_COLOR, RenderResourceType::Texture),
+                ResourceSlotInfo::new(
+                    PostProcessNode::OUT_COLOR_ATTACHMENT,
+                    RenderResourceType::Texture,
+                ),
+                ResourceSlotInfo::new(
+                    PostProcessNode::OUT_COLOR_RESOLVE_TARGET,
+                    RenderResourceType::Texture,
+                ),
+                ResourceSlotInfo::new(PostProcessNode::IN_EXPOSURE, RenderResourceType::Texture),
+                ResourceSlotInfo::new(
+                    PostProcessNode::IN_MOTION_VECTORS,
+                    RenderResourceType::Texture,
+                ),
+                ResourceSlotInfo::new(PostProcessNode::IN_DEPTH, RenderResourceType::Texture),
+            ],
+            sampler: None,
+            params_buffers: Vec::new(),
+            grading_buffer: None,
+            exposure_buffer: None,
+            depth_params_buffer: None,
+        }
+    }
+}
+
+impl Node for PostProcessNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        &self.inputs
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        const IN_COLOR: usize = 0;
+        const OUT_COLOR_ATTACHMENT: usize = 1;
+        const OUT_COLOR_RESOLVE_TARGET: usize = 2;
+        const IN_EXPOSURE: usize = 3;
+        const IN_MOTION_VECTORS: usize = 4;
+        const IN_DEPTH: usize = 5;
+        let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
+        let active_cameras = resources.get::<ActiveCameras>().unwrap();
+        let windows = resources.get::<Windows>().unwrap();
+        let textures = resources.get::<Assets<Texture>>().unwrap();
+        let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+        let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+        let mut pipeline_compiler = resources.get_mut::<PipelineCompiler>().unwrap();
+        let vertex_buffer_descriptors = resources.get::<VertexBufferDescriptors>().unwrap();
+        let msaa_samples = resources.get::<Msaa>().map_or(1, |msaa| msaa.samples);
+        let mut transient_texture_pool = resources.get_mut::<TransientTexturePool>().unwrap();
+
+        let window = if let Some(window) = windows.get(self.window_id) {
+            window
+        } else {
+            return;
+        };
+        let camera = active_cameras.get(&self.camera_name);
+        let effects = camera
+            .and_then(|camera| world.get::<PostProcessStack>(camera).ok())
+            .map(|stack| stack.effects.clone())
+            .unwrap_or_default();
+        let color_grading = camera
+            .and_then(|camera| world.get::<ColorGrading>(camera).ok())
+            .map(|color_grading| color_grading.clone());
+        let exposure = camera
+            .and_then(|camera| world.get::<Exposure>(camera).ok())
+            .map(|exposure| exposure.clone());
+        let auto_exposure_texture = input.get(IN_EXPOSURE).unwrap().get_texture().unwrap();
+        let motion_vectors_texture = input.get(IN_MOTION_VECTORS).unwrap().get_texture().unwrap();
+        let depth_texture = input.get(IN_DEPTH).unwrap().get_texture().unwrap();
+        // the part of the projection that maps view depth to depth, which effects use to get view depth back
+        let depth_params = camera
+            .and_then(|camera| world.get::<Camera>(camera).ok())
+            .map_or([0.0, 0.0, -1.0, 0.0], |camera| {
+                let projection = camera.projection_matrix;
+                [
+                    projection.z_axis().z(),
+                    projection.w_axis().z(),
+                    projection.z_axis().w(),
+                    projection.w_axis().w(),
+                ]
+            });
+        // a lut that hasn't been loaded yet is skipped
+        let lut = color_grading
+            .as_ref()
+            .and_then(|color_grading| color_grading.lut)
+            .and_then(|lut| {
+                let texture = lut_texture(render_context, lut)?;
+                Some((texture, textures.get(&lut)?.size.y()))
+            });
+
+        let color_texture = input.get(IN_COLOR).unwrap().get_texture().unwrap();
+        let color_resolve_target = input
+            .get(OUT_COLOR_RESOLVE_TARGET)
+            .unwrap()
+            .get_texture()
+            .unwrap();
+        let (color_attachment, color_resolve_target) = if msaa_samples > 1 {
+            let color_attachment = input
+                .get(OUT_COLOR_ATTACHMENT)
+                .unwrap()
+                .get_texture()
+                .unwrap();
+            (color_attachment, Some(color_resolve_target))
+        } else {
+            (color_resolve_target, None)
+        };
+
+        let sampler = *self.sampler.get_or_insert_with(|| {
+            render_context
+                .resources()
+                .create_sampler(&SamplerDescriptor {
+                    mag_filter: FilterMode::Linear,
+                    ..Default::default()
+                })
+        });
+
+        // effects ping-pong between two intermediate textures. an empty stack copies the camera's image
+        let pass_count = effects.len().max(1);
+        let intermediate_textures = (0..(pass_count - 1).min(2))
+            .map(|_| {
+                transient_texture_pool.acquire(
+                    render_context.resources(),
+                    TextureDescriptor {
+                        size: Extent3d {
+                            width: window.width,
+                            height: window.height,
+                            depth: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: TextureDimension::D2,
+                        format: TextureFormat::Bgra8UnormSrgb,
+                        usage: TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        if self.params_buffers.len() < pass_count {
+            self.params_buffers.resize(pass_count, None);
+        }
+        for index in 0..pass_count {
+            let effect = effects.get(index);
+            let is_last = index == pass_count - 1;
+            let source = if index == 0 {
+                color_texture
+            } else {
+                intermediate_textures[(index - 1) % 2]
+            };
+
+            let mut specialization = PipelineSpecialization {
+                shader_specialization: ShaderSpecialization::default(),
+                sample_count: if is_last { msaa_samples } else { 1 },
+                ..Default::default()
+            };
+            let shader_defs = &mut specialization.shader_specialization.shader_defs;
+            if let Some(effect) = effect {
+                shader_defs.insert(effect.shader_def().to_string());
+            }
+            let mut exposure_buffer = None;
+            if let (0, Some(exposure)) = (index, exposure.as_ref()) {
+                shader_defs.insert("POST_PROCESS_EXPOSURE".to_string());
+                if let ExposureMode::Auto(_) = exposure.mode {
+                    shader_defs.insert("POST_PROCESS_AUTO_EXPOSURE".to_string());
+                }
+                exposure_buffer = Some(uniform_buffer(
+                    render_context,
+                    &mut self.exposure_buffer,
+                    exposure.params(),
+                ));
+            }
+            let mut depth_params_buffer = None;
+            if let Some(PostProcessEffect::DepthOfField { .. }) = effect {
+                depth_params_buffer = Some(uniform_buffer(
+                    render_context,
+                    &mut self.depth_params_buffer,
+                    depth_params,
+                ));
+            }
+            let mut grading_buffer = None;
+            if let (true, Some(color_grading)) = (is_last, color_grading.as_ref()) {
+                shader_defs.insert("POST_PROCESS_COLOR_GRADING".to_string());
+                if lut.is_some() {
+                    shader_defs.insert("POST_PROCESS_COLOR_GRADING_LUT".to_string());
+                }
+                let params = color_grading.params(lut.map_or(0.0, |(_, size)| size));
+                grading_buffer = Some(uniform_buffer(
+                    render_context,
+                    &mut self.grading_buffer,
+                    params,
+                ));
+            }
+            let pipeline = match pipeline_compiler
+                .get_specialized_pipeline(POST_PROCESS_PIPELINE_HANDLE, &specialization)
+            {
+                Some(pipeline) => pipeline,
+                None => pipeline_compiler.compile_pipeline(
+                    render_context.resources(),
+                    &mut pipelines,
+                    &mut shaders,
+                    POST_PROCESS_PIPELINE_HANDLE,
+                    &vertex_buffer_descriptors,
+                    &specialization,
+                ),
+            };
+
+            let params = effect.map_or([0.0; 4], |effect| effect.params());
+            let params_buffer =
+                uniform_buffer(render_context, &mut self.params_buffers[index], params);
+
+            let layout = pipelines.get(&pipeline).unwrap().get_layout().unwrap();
+            let bind_group_descriptor = layout.get_bind_group(0).unwrap();
+            let mut bind_group = BindGroup::build();
+            for binding in bind_group_descriptor.bindings.iter() {
+                let uniform_range = 0..std::mem::size_of::<[f32; 4]>() as u64;
+                bind_group = match (
+                    binding.name.as_str(),
+                    grading_buffer,
+                    lut,
+                    exposure_buffer,
+                    depth_params_buffer,
+                ) {
+                    ("PostProcess_input", ..) => bind_group.add_texture(binding.index, source),
+                    ("PostProcess_input_sampler", ..) => {
+                        bind_group.add_sampler(binding.index, sampler)
+                    }
+                    ("PostProcess_params", ..) => {
+                        bind_group.add_buffer(binding.index, params_buffer, uniform_range)
+                    }
+                    ("PostProcess_grading", Some(grading_buffer), _, _, _) => {
+                        bind_group.add_buffer(binding.index, grading_buffer, uniform_range)
+                    }
+                    ("PostProcess_lut", _, Some((lut_texture, _)), _, _) => {
+                        bind_group.add_texture(binding.index, lut_texture)
+                    }
+                    ("PostProcess_exposure", _, _, Some(exposure_buffer), _) => {
+                        bind_group.add_buffer(binding.index, exposure_buffer, uniform_range)
+                    }
+                    ("PostProcess_auto_exposure", ..) => {
+                        bind_group.add_texture(binding.index, auto_exposure_texture)
+                    }
+                    ("PostProcess_motion_vectors", ..) => {
+                        bind_group.add_texture(binding.index, motion_vectors_texture)
+                    }
+                    ("PostProcess_depth", ..) => {
+                        bind_group.add_texture(binding.index, depth_texture)
+                    }
+                    ("PostProcess_depth_params", _, _, _, Some(depth_params_buffer)) => {
+                        bind_group.add_buffer(binding.index, depth_params_buffer, uniform_range)
+                    }
+                    _ => panic!("unexpected post process binding {}", binding.name),
+                };
+            }
+            let bind_group = bind_group.finish();
+            let bind_group_descriptor_id = bind_group_descriptor.id;
+            render_context
+                .resources()
+                .create_bind_group(bind_group_descriptor_id, &bind_group);
+
+            let (attachment, resolve_target) = if is_last {
+                (color_attachment, color_resolve_target)
+            } else {
+                (intermediate_textures[index % 2], None)
+            };
+            let pass_descriptor = PassDescriptor {
+                color_attachments: vec![crate::pass::RenderPassColorAttachmentDescriptor {
+                    attachment: TextureAttachment::Id(attachment),
+                    resolve_target: resolve_target.map(TextureAttachment::Id),
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+                sample_count: if is_last { msaa_samples } else { 1 },
+            };
+            render_context.begin_pass(
+                &pass_descriptor,
+                &render_resource_bindings,
+                &mut |render_pass| {
+                    render_pass.set_pipeline(pipeline);
+                    render_pass.set_bind_group(0, bind_group_descriptor_id, bind_group.id, None);
+                    render_pass.draw(0..3, 0..1);
+                },
+            );
+        }
+
+        for texture in intermediate_textures {
+            transient_texture_pool.release(texture);
+        }
+    }
+}
+
+fn lut_texture(render_context: &dyn RenderContext, lut: Handle<Texture>) -> Option<TextureId> {
+    match render_context
+        .resources()
+        .get_asset_resource(lut, TEXTURE_ASSET_INDEX)
+    {
+        Some(RenderResourceId::Texture(texture)) => Some(texture),
+        _ => None,
+    }
+}
@@ -0,0 +1,13 @@
+#version 450
+
+layout(location = 0) in vec4 v_Clip;
+layout(location = 1) in vec4 v_PreviousClip;
+layout(location = 2) in float v_NoMotionBlur;
+
+layout(location = 0) out vec4 o_Target;
+
+void main() {
+    // ndc y points up, uv y points down
+    vec2 motion = (v_Clip.xy / v_Clip.w - v_PreviousClip.xy / v_PreviousClip.w) * vec2(0.5, -0.5);
+    o_Target = vec4(motion, v_NoMotionBlur, 1.0);
+}
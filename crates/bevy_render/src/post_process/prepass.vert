@@ -0,0 +1,26 @@
+#version 450
+
+layout(location = 0) in vec3 Vertex_Position;
+
+layout(location = 0) out vec4 v_Clip;
+layout(location = 1) out vec4 v_PreviousClip;
+layout(location = 2) out float v_NoMotionBlur;
+
+layout(set = 0, binding = 0) uniform Prepass_camera {
+    mat4 ViewProj;
+    mat4 PreviousViewProj;
+};
+
+layout(set = 1, binding = 0) uniform Prepass_object {
+    mat4 Model;
+    mat4 PreviousModel;
+    // x: 1.0 if the object opted out of motion blur
+    vec4 Flags;
+};
+
+void main() {
+    v_Clip = ViewProj * Model * vec4(Vertex_Position, 1.0);
+    v_PreviousClip = PreviousViewProj * PreviousModel * vec4(Vertex_Position, 1.0);
+    v_NoMotionBlur = Flags.x;
+    gl_Position = v_Clip;
+}
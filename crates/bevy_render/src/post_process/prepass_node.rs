@@ -0,0 +1,450 @@
+use super::{
+    uniform_buffer, NoMotionBlur, PostProcessEffect, PostProcessStack, PREPASS_PIPELINE_HANDLE,
+};
+use crate::{
+    camera::{ActiveCameras, Camera},
+    draw::Draw,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
+    },
+    pipeline::{
+        DynamicBinding, PipelineCompiler, PipelineDescriptor, PipelineSpecialization,
+        RenderPipelines, VertexBufferDescriptors,
+    },
+    render_graph::{base::MainPass, Node, ResourceSlotInfo, ResourceSlots, TransientTexturePool},
+    renderer::{
+        BindGroup, BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding,
+        RenderResourceBindings, RenderResourceId, RenderResourceType, TextureId,
+    },
+    shader::Shader,
+    texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
+    Color,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core::AsBytes;
+use bevy_ecs::{Entity, Resources, World};
+use bevy_math::Mat4;
+use bevy_transform::prelude::Transform;
+use bevy_window::{WindowId, Windows};
+use std::{borrow::Cow, collections::HashMap};
+
+/// Objects are selected with dynamic offsets into one uniform buffer, which have to be aligned to 256 bytes
+const OBJECT_UNIFORM_ALIGNMENT: usize = 256;
+
+struct PrepassObject {
+    pipeline: Handle<PipelineDescriptor>,
+    vertex_buffer: BufferId,
+    index_buffer: BufferId,
+    index_count: u32,
+}
+
+/// Renders the depth and the motion of everything in a camera's main pass into single sampled textures, for effects
+/// that need them. Each pixel of the motion vectors holds how far its surface moved in uv units since the previous
+/// frame (xy) and whether the surface opted out of motion blur with [NoMotionBlur] (z). Pixels without a surface
+/// don't move and are at the far plane.
+///
+/// The model matrices and the camera's view projection are kept from one frame to the next, so objects that just
+/// appeared don't move in their first frame. The prepass only runs while the camera's [PostProcessStack] has an
+/// effect that needs it ([PostProcessEffect::MotionBlur] or [PostProcessEffect::DepthOfField]), but the outputs are
+/// always set, so they can be read by other nodes (ex: temporal anti-aliasing). The textures come from the
+/// [TransientTexturePool], so they only live until the last node that reads them ran.
+pub struct PrepassNode {
+    camera_name: String,
+    window_id: WindowId,
+    previous_view_projection: Option<Mat4>,
+    previous_models: HashMap<Entity, Mat4>,
+    camera_buffer: Option<(BufferId, [[f32; 16]; 2])>,
+    object_buffer: Option<BufferId>,
+}
+
+impl PrepassNode {
+    pub const OUT_MOTION_VECTORS: &'static str = "motion_vectors";
+    pub const OUT_DEPTH: &'static str = "depth";
+
+    pub fn new(camera_name: &str, window_id: WindowId) -> Self {
+        PrepassNode {
+            camera_name: camera_name.to_string(),
+            window_id,
+            previous_view_projection: None,
+            previous_models: HashMap::new(),
+            camera_buffer: None,
+            object_buffer: None,
+        }
+    }
+
+    /// Acquires the motion vector and depth textures for this frame. They are released after the last node that reads
+    /// them ran.
+    fn textures(
+        render_context: &dyn RenderContext,
+        transient_texture_pool: &mut TransientTexturePool,
+        size: (u32, u32),
+    ) -> (TextureId, TextureId) {
+        let texture = |format| TextureDescriptor {
+            size: Extent3d {
+                width: size.0,
+                height: size.1,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT,
+        };
+        let render_resource_context = render_context.resources();
+        (
+            transient_texture_pool
+                .acquire(render_resource_context, texture(TextureFormat::Rgba16Float)),
+            transient_texture_pool.acquire(
+                render_resource_context,
+                texture(TextureFormat::Depth32Float),
+            ),
+        )
+    }
+}
+
+impl Node for PrepassNode {
+    fn output(&self) -> &[ResourceSlotInfo] {
+        static OUTPUT: &[ResourceSlotInfo] = &[
+            ResourceSlotInfo {
+                name: Cow::Borrowed(PrepassNode::OUT_MOTION_VECTORS),
+                resource_type: RenderResourceType::Texture,
+            },
+            ResourceSlotInfo {
+                name: Cow::Borrowed(PrepassNode::OUT_DEPTH),
+                resource_type: RenderResourceType::Texture,
+            },
+        ];
+        OUTPUT
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        const OUT_MOTION_VECTORS: usize = 0;
+        const OUT_DEPTH: usize = 1;
+        let windows = resources.get::<Windows>().unwrap();
+        let window = if let Some(window) = windows.get(self.window_id) {
+            window
+        } else {
+            return;
+        };
+        let (motion_texture, depth_texture) = PrepassNode::textures(
+            render_context,
+            &mut resources.get_mut::<TransientTexturePool>().unwrap(),
+            (window.width, window.height),
+        );
+        output.set(
+            OUT_MOTION_VECTORS,
+            RenderResourceId::Texture(motion_texture),
+        );
+        output.set(OUT_DEPTH, RenderResourceId::Texture(depth_texture));
+
+        let active_cameras = resources.get::<ActiveCameras>().unwrap();
+        let camera_entity = match active_cameras.get(&self.camera_name) {
+            Some(camera_entity) => camera_entity,
+            None => return,
+        };
+        let needs_prepass = world
+            .get::<PostProcessStack>(camera_entity)
+            .map_or(false, |stack| {
+                stack.effects.iter().any(|effect| match effect {
+                    PostProcessEffect::MotionBlur { .. }
+                    | PostProcessEffect::DepthOfField { .. } => true,
+                    _ => false,
+                })
+            });
+        let view_projection = match (
+            world.get::<Camera>(camera_entity),
+            world.get::<Transform>(camera_entity),
+        ) {
+            (Ok(camera), Ok(transform)) if needs_prepass => {
+                camera.projection_matrix * transform.value.inverse()
+            }
+            _ => {
+                // start over when the prepass is needed again
+                self.previous_view_projection = None;
+                self.previous_models.clear();
+                return;
+            }
+        };
+        let previous_view_projection = self
+            .previous_view_projection
+            .replace(view_projection)
+            .unwrap_or(view_projection);
+
+        let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
+        let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+        let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+        let mut pipeline_compiler = resources.get_mut::<PipelineCompiler>().unwrap();
+        let vertex_buffer_descriptors = resources.get::<VertexBufferDescriptors>().unwrap();
+
+        let mut objects = Vec::new();
+        let mut object_data = Vec::new();
+        let mut models = HashMap::new();
+        for (entity, draw, render_pipelines, _main_pass, transform, no_motion_blur) in &mut world
+            .query::<(
+                Entity,
+                &Draw,
+                &RenderPipelines,
+                &MainPass,
+                &Transform,
+                Option<&NoMotionBlur>,
+            )>()
+        {
+            let render_pipeline = match render_pipelines.pipelines.first() {
+                Some(render_pipeline) if draw.is_visible => render_pipeline,
+                _ => continue,
+            };
+            let (vertex_buffer, index_buffer) =
+                match render_pipelines.bindings.get_vertex_buffer("Vertex") {
+                    Some((vertex_buffer, Some(index_buffer))) => (vertex_buffer, index_buffer),
+                    _ => continue,
+                };
+            let index_count = match render_context.resources().get_buffer_info(index_buffer) {
+                Some(buffer_info) => (buffer_info.size / 2) as u32,
+                None => continue,
+            };
+
+            let specialization = PipelineSpecialization {
+                primitive_topology: render_pipeline.specialization.primitive_topology,
+                dynamic_bindings: vec![
+                    // Prepass_object
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 0,
+                    },
+                ],
+                ..Default::default()
+            };
+            let pipeline = match pipeline_compiler
+                .get_specialized_pipeline(PREPASS_PIPELINE_HANDLE, &specialization)
+            {
+                Some(pipeline) => pipeline,
+                None => pipeline_compiler.compile_pipeline(
+                    render_context.resources(),
+                    &mut pipelines,
+                    &mut shaders,
+                    PREPASS_PIPELINE_HANDLE,
+                    &vertex_buffer_descriptors,
+                    &specialization,
+                ),
+            };
+
+            let model = transform.value;
+            let previous_model = self.previous_models.get(&entity).cloned().unwrap_or(model);
+            models.insert(entity, model);
+            let mut slot = [0; OBJECT_UNIFORM_ALIGNMENT];
+            slot[0..64].copy_from_slice(model.to_cols_array().as_bytes());
+            slot[64..128].copy_from_slice(previous_model.to_cols_array().as_bytes());
+            let no_motion_blur = if no_motion_blur.is_some() { 1.0 } else { 0.0 };
+            slot[128..144].copy_from_slice([no_motion_blur, 0.0, 0.0, 0.0].as_bytes());
+            object_data.extend_from_slice(&slot);
+
+            objects.push(PrepassObject {
+                pipeline,
+                vertex_buffer,
+                index_buffer,
+                index_count,
+            });
+        }
+        self.previous_models = models;
+
+        let camera_buffer = uniform_buffer(
+            render_context,
+            &mut self.camera_buffer,
+            [
+                view_projection.to_cols_array(),
+                previous_view_projection.to_cols_array(),
+            ],
+        );
+        // objects move almost every frame, so their buffer is recreated every frame
+        let render_resource_context = render_context.resources_mut();
+        if let Some(object_buffer) = self.object_buffer.take() {
+            render_resource_context.remove_buffer(object_buffer);
+        }
+        let bind_groups = if let Some(object) = objects.first() {
+            let object_buffer = render_resource_context.create_buffer_with_data(
+                BufferInfo {
+                    size: object_data.len(),
+                    buffer_usage: BufferUsage::UNIFORM,
+                    ..Default::default()
+                },
+                &object_data,
+            );
+            self.object_buffer = Some(object_buffer);
+
+            // every specialization of the prepass pipeline has the same layout
+            let layout = pipelines
+                .get(&object.pipeline)
+                .unwrap()
+                .get_layout()
+                .unwrap();
+            let camera_bind_group_descriptor = layout.get_bind_group(0).unwrap().id;
+            let object_bind_group_descriptor = layout.get_bind_group(1).unwrap().id;
+            let camera_bind_group = BindGroup::build()
+                .add_buffer(
+                    0,
+                    camera_buffer,
+                    0..std::mem::size_of::<[[f32; 16]; 2]>() as u64,
+                )
+                .finish();
+            let object_bind_group = BindGroup::build()
+                .add_binding(
+                    0,
+                    RenderResourceBinding::Buffer {
+                        buffer: object_buffer,
+                        range: 0..144,
+                        dynamic_index: None,
+                    },
+                )
+                .finish();
+            render_resource_context
+                .create_bind_group(camera_bind_group_descriptor, &camera_bind_group);
+            render_resource_context
+                .create_bind_group(object_bind_group_descriptor, &object_bind_group);
+            Some((
+                (camera_bind_group_descriptor, camera_bind_group),
+                (object_bind_group_descriptor, object_bind_group),
+            ))
+        } else {
+            None
+        };
+
+        // the pass also runs without objects, which clears the textures
+        let pass_descriptor = PassDescriptor {
+            color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                attachment: TextureAttachment::Id(motion_texture),
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                attachment: TextureAttachment::Id(depth_texture),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: 1,
+        };
+        render_context.begin_pass(
+            &pass_descriptor,
+            &render_resource_bindings,
+            &mut |render_pass| {
+                let (
+                    (camera_bind_group_descriptor, camera_bind_group),
+                    (object_bind_group_descriptor, object_bind_group),
+                ) = match bind_groups {
+                    Some(ref bind_groups) => bind_groups,
+                    None => return,
+                };
+                for (index, object) in objects.iter().enumerate() {
+                    render_pass.set_pipeline(object.pipeline);
+                    render_pass.set_bind_group(
+                        0,
+                        *camera_bind_group_descriptor,
+                        camera_bind_group.id,
+                        None,
+                    );
+                    render_pass.set_bind_group(
+                        1,
+                        *object_bind_group_descriptor,
+                        object_bind_group.id,
+                        Some(&[(index * OBJECT_UNIFORM_ALIGNMENT) as u32]),
+                    );
+                    render_pass.set_vertex_buffer(0, object.vertex_buffer, 0);
+                    render_pass.set_index_buffer(object.index_buffer, 0);
+                    render_pass.draw_indexed(0..object.index_count, 0, 0..1);
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrepassNode;
+    use crate::{
+        camera::{ActiveCameras, Camera},
+        pipeline::{PipelineCompiler, PipelineDescriptor, VertexBufferDescriptors},
+        post_process::{PostProcessEffect, PostProcessStack},
+        render_graph::{Node, ResourceSlots, TransientTexturePool},
+        renderer::{RenderResourceBindings, TestRenderContext},
+        shader::Shader,
+    };
+    use bevy_asset::Assets;
+    use bevy_ecs::{Resources, World};
+    use bevy_math::{Mat4, Vec3};
+    use bevy_transform::prelude::Transform;
+    use bevy_window::{Window, WindowDescriptor, WindowId, Windows};
+
+    #[test]
+    fn prepass_effects() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+        ));
+        resources.insert(windows);
+        resources.insert(TransientTexturePool::default());
+        resources.insert(RenderResourceBindings::default());
+        resources.insert(Assets::<PipelineDescriptor>::default());
+        resources.insert(Assets::<Shader>::default());
+        resources.insert(PipelineCompiler::default());
+        resources.insert(VertexBufferDescriptors::default());
+        let camera = world.spawn((
+            Camera::default(),
+            Transform::new(Mat4::from_translation(Vec3::new(0.0, 0.0, 5.0))),
+            PostProcessStack::new(vec![PostProcessEffect::Tonemap { exposure: 1.0 }]),
+        ));
+        let mut active_cameras = ActiveCameras::default();
+        active_cameras.add("camera");
+        active_cameras.set("camera", camera);
+        resources.insert(active_cameras);
+
+        let mut node = PrepassNode::new("camera", WindowId::primary());
+        let mut render_context = TestRenderContext::default();
+        let input = ResourceSlots::default();
+        let mut output = ResourceSlots::from(node.output());
+        let mut update = |world: &World, node: &mut PrepassNode| {
+            node.update(world, &resources, &mut render_context, &input, &mut output);
+            assert!(output.get(PrepassNode::OUT_MOTION_VECTORS).is_some());
+            assert!(output.get(PrepassNode::OUT_DEPTH).is_some());
+            node.previous_view_projection.is_some()
+        };
+
+        // the outputs are always set, but the prepass only runs for effects that need it
+        assert!(!update(&world, &mut node));
+        for effect in vec![
+            PostProcessEffect::MotionBlur {
+                intensity: 1.0,
+                samples: 8,
+            },
+            PostProcessEffect::DepthOfField {
+                focal_distance: 5.0,
+                aperture: 8.0,
+                max_radius: 4.0,
+            },
+        ] {
+            world.get_mut::<PostProcessStack>(camera).unwrap().effects = vec![effect];
+            assert!(update(&world, &mut node));
+            world.remove_one::<PostProcessStack>(camera).unwrap();
+            assert!(!update(&world, &mut node));
+            world
+                .insert_one(camera, PostProcessStack::default())
+                .unwrap();
+        }
+    }
+}
@@ -3,6 +3,7 @@ use super::{
     WindowTextureNode,
 };
 use crate::{
+    indirect::IndirectCullingNode,
     pass::{
         LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
         RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
@@ -17,6 +18,8 @@ use bevy_window::WindowId;
 #[derive(Default, Properties)]
 pub struct MainPass;
 
+/// Configures multisample anti-aliasing. The passes of the base and ui graphs draw into multisampled attachments that
+/// are resolved into the swap chain. `samples` can be changed at runtime, and 1 disables msaa.
 pub struct Msaa {
     pub samples: u32,
 }
@@ -28,6 +31,8 @@ impl Default for Msaa {
 }
 
 impl Msaa {
+    /// Returns a color attachment for the current sample count. Passes that use this don't follow runtime changes of
+    /// the sample count, see [PassNode::use_msaa] for that.
     pub fn color_attachment_descriptor(
         &self,
         attachment: TextureAttachment,
@@ -63,6 +68,7 @@ pub mod node {
     pub const PRIMARY_SWAP_CHAIN: &str = "swapchain";
     pub const CAMERA3D: &str = "camera3d";
     pub const CAMERA2D: &str = "camera2d";
+    pub const INDIRECT_CULLING: &str = "indirect_culling";
     pub const TEXTURE_COPY: &str = "texture_copy";
     pub const MAIN_DEPTH_TEXTURE: &str = "main_pass_depth_texture";
     pub const MAIN_SAMPLED_COLOR_ATTACHMENT: &str = "main_pass_sampled_color_attachment";
@@ -91,11 +97,11 @@ impl Default for BaseRenderGraphConfig {
 /// By itself this graph doesn't do much, but it allows Render plugins to interop with each other by having a common
 /// set of nodes. It can be customized using `BaseRenderGraphConfig`.
 pub trait BaseRenderGraphBuilder {
-    fn add_base_graph(&mut self, config: &BaseRenderGraphConfig, msaa: &Msaa) -> &mut Self;
+    fn add_base_graph(&mut self, config: &BaseRenderGraphConfig) -> &mut Self;
 }
 
 impl BaseRenderGraphBuilder for RenderGraph {
-    fn add_base_graph(&mut self, config: &BaseRenderGraphConfig, msaa: &Msaa) -> &mut Self {
+    fn add_base_graph(&mut self, config: &BaseRenderGraphConfig) -> &mut Self {
         self.add_node(node::TEXTURE_COPY, TextureCopyNode::default());
         if config.add_3d_camera {
             self.add_system_node(node::CAMERA3D, CameraNode::new(camera::CAMERA3D));
@@ -107,36 +113,37 @@ impl BaseRenderGraphBuilder for RenderGraph {
 
         self.add_node(node::SHARED_BUFFERS, SharedBuffersNode::default());
         if config.add_main_depth_texture {
-            self.add_node(
-                node::MAIN_DEPTH_TEXTURE,
-                WindowTextureNode::new(
-                    WindowId::primary(),
-                    TextureDescriptor {
-                        size: Extent3d {
-                            depth: 1,
-                            width: 1,
-                            height: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: msaa.samples,
-                        dimension: TextureDimension::D2,
-                        format: TextureFormat::Depth32Float, // PERF: vulkan docs recommend using 24 bit depth for better performance
-                        usage: TextureUsage::OUTPUT_ATTACHMENT,
+            let mut main_depth_texture_node = WindowTextureNode::new(
+                WindowId::primary(),
+                TextureDescriptor {
+                    size: Extent3d {
+                        depth: 1,
+                        width: 1,
+                        height: 1,
                     },
-                ),
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format: TextureFormat::Depth32Float, // PERF: vulkan docs recommend using 24 bit depth for better performance
+                    usage: TextureUsage::OUTPUT_ATTACHMENT,
+                },
             );
+            main_depth_texture_node.use_msaa();
+            self.add_node(node::MAIN_DEPTH_TEXTURE, main_depth_texture_node);
         }
 
         if config.add_main_pass {
             let mut main_pass_node = PassNode::<&MainPass>::new(PassDescriptor {
-                color_attachments: vec![msaa.color_attachment_descriptor(
-                    TextureAttachment::Input("color_attachment".to_string()),
-                    TextureAttachment::Input("color_resolve_target".to_string()),
-                    Operations {
+                color_attachments: vec![RenderPassColorAttachmentDescriptor {
+                    attachment: TextureAttachment::Input("color_attachment".to_string()),
+                    resolve_target: Some(TextureAttachment::Input(
+                        "color_resolve_target".to_string(),
+                    )),
+                    ops: Operations {
                         load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
                         store: true,
                     },
-                )],
+                }],
                 depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
                     attachment: TextureAttachment::Input("depth".to_string()),
                     depth_ops: Some(Operations {
@@ -145,10 +152,11 @@ impl BaseRenderGraphBuilder for RenderGraph {
                     }),
                     stencil_ops: None,
                 }),
-                sample_count: msaa.samples,
+                sample_count: 1,
             });
 
             main_pass_node.use_default_clear_color(0);
+            main_pass_node.use_msaa();
 
             if config.add_3d_camera {
                 main_pass_node.add_camera(camera::CAMERA3D);
@@ -167,6 +175,12 @@ impl BaseRenderGraphBuilder for RenderGraph {
 
             if config.add_3d_camera {
                 self.add_node_edge(node::CAMERA3D, node::MAIN_PASS).unwrap();
+                self.add_node(
+                    node::INDIRECT_CULLING,
+                    IndirectCullingNode::new(camera::CAMERA3D),
+                );
+                self.add_node_edge(node::INDIRECT_CULLING, node::MAIN_PASS)
+                    .unwrap();
             }
 
             if config.add_2d_camera {
@@ -179,43 +193,40 @@ impl BaseRenderGraphBuilder for RenderGraph {
             WindowSwapChainNode::new(WindowId::primary()),
         );
 
+        // the sampled color attachment node is always there, so the sample count can change without changing the graph.
+        // when msaa is disabled it has no texture and passes draw straight into the swap chain
+        let mut sampled_color_attachment_node = WindowTextureNode::new(
+            WindowId::primary(),
+            TextureDescriptor {
+                size: Extent3d {
+                    depth: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+        );
+        sampled_color_attachment_node.only_with_msaa();
+        self.add_node(
+            node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+            sampled_color_attachment_node,
+        );
+
         if config.connect_main_pass_to_swapchain {
             self.add_slot_edge(
                 node::PRIMARY_SWAP_CHAIN,
                 WindowSwapChainNode::OUT_TEXTURE,
                 node::MAIN_PASS,
-                if msaa.samples > 1 {
-                    "color_resolve_target"
-                } else {
-                    "color_attachment"
-                },
+                "color_resolve_target",
             )
             .unwrap();
-        }
-
-        if msaa.samples > 1 {
-            self.add_node(
-                node::MAIN_SAMPLED_COLOR_ATTACHMENT,
-                WindowTextureNode::new(
-                    WindowId::primary(),
-                    TextureDescriptor {
-                        size: Extent3d {
-                            depth: 1,
-                            width: 1,
-                            height: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: msaa.samples,
-                        dimension: TextureDimension::D2,
-                        format: TextureFormat::Bgra8UnormSrgb,
-                        usage: TextureUsage::OUTPUT_ATTACHMENT,
-                    },
-                ),
-            );
-
             self.add_slot_edge(
                 node::MAIN_SAMPLED_COLOR_ATTACHMENT,
-                WindowSwapChainNode::OUT_TEXTURE,
+                WindowTextureNode::OUT_TEXTURE,
                 node::MAIN_PASS,
                 "color_attachment",
             )
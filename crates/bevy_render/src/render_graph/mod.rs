@@ -7,6 +7,7 @@ mod node_slot;
 mod nodes;
 mod schedule;
 mod system;
+mod transient_texture_pool;
 
 pub use command::*;
 pub use edge::*;
@@ -16,6 +17,7 @@ pub use node_slot::*;
 pub use nodes::*;
 pub use schedule::*;
 pub use system::*;
+pub use transient_texture_pool::*;
 
 use thiserror::Error;
 
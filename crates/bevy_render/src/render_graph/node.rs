@@ -31,6 +31,13 @@ pub trait Node: Downcast + Send + Sync + 'static {
         input: &ResourceSlots,
         output: &mut ResourceSlots,
     );
+
+    /// Whether [Node::update] can run on another thread while other nodes of the same stage run, recording into its
+    /// own command encoder. Nodes that return true must only borrow resources immutably, because borrowing a resource
+    /// mutably while another thread borrows it panics.
+    fn encodes_in_parallel(&self) -> bool {
+        false
+    }
 }
 
 impl_downcast!(Node);
@@ -40,6 +40,10 @@ impl Node for CameraNode {
     ) {
         self.command_queue.execute(render_context);
     }
+
+    fn encodes_in_parallel(&self) -> bool {
+        true
+    }
 }
 
 impl SystemNode for CameraNode {
@@ -1,19 +1,21 @@
 use crate::{
-    camera::{ActiveCameras, VisibleEntities},
+    camera::{ActiveCameras, Camera, VisibleEntities},
     draw::{Draw, RenderCommand},
     pass::{ClearColor, LoadOp, PassDescriptor, TextureAttachment},
     pipeline::{
-        BindGroupDescriptor, BindType, BindingDescriptor, PipelineDescriptor, UniformProperty,
-        BindingShaderStage,
+        BindGroupDescriptor, BindType, BindingDescriptor, BindingShaderStage, PipelineDescriptor,
+        UniformProperty,
     },
-    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots},
+    render_stats::RenderStats,
     renderer::{
         BindGroup, BindGroupId, BufferId, RenderContext, RenderResourceBindings, RenderResourceType,
     },
 };
 use bevy_asset::{Assets, Handle};
-use bevy_ecs::{Resources, World, HecsQuery};
-use std::marker::PhantomData;
+use bevy_ecs::{HecsQuery, Resources, World};
+use bevy_window::Windows;
+use std::{cell::Cell, marker::PhantomData};
 
 struct CameraInfo {
     name: String,
@@ -28,6 +30,7 @@ pub struct PassNode<Q: HecsQuery> {
     color_resolve_target_indices: Vec<Option<usize>>,
     depth_stencil_attachment_input_index: Option<usize>,
     default_clear_color_inputs: Vec<usize>,
+    use_msaa: bool,
     camera_bind_group_descriptor: BindGroupDescriptor,
     _marker: PhantomData<Q>,
 }
@@ -91,6 +94,7 @@ impl<Q: HecsQuery> PassNode<Q> {
             color_resolve_target_indices,
             depth_stencil_attachment_input_index,
             default_clear_color_inputs: Vec::new(),
+            use_msaa: false,
             camera_bind_group_descriptor,
             _marker: PhantomData::default(),
         }
@@ -103,9 +107,24 @@ impl<Q: HecsQuery> PassNode<Q> {
         });
     }
 
+    /// Stops drawing the camera with the given name. Returns false if the pass didn't draw it.
+    pub fn remove_camera(&mut self, camera_name: &str) -> bool {
+        let count = self.cameras.len();
+        self.cameras
+            .retain(|camera_info| camera_info.name != camera_name);
+        self.cameras.len() != count
+    }
+
     pub fn use_default_clear_color(&mut self, color_attachment_index: usize) {
         self.default_clear_color_inputs.push(color_attachment_index);
     }
+
+    /// Takes the sample count from the [Msaa] resource every frame instead of the descriptor. When msaa is disabled,
+    /// color attachments whose attachment and resolve target are both inputs are drawn straight into the resolve
+    /// target.
+    pub fn use_msaa(&mut self) {
+        self.use_msaa = true;
+    }
 }
 
 impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
@@ -124,6 +143,15 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
         let render_resource_bindings = resources.get::<RenderResourceBindings>().unwrap();
         let pipelines = resources.get::<Assets<PipelineDescriptor>>().unwrap();
         let active_cameras = resources.get::<ActiveCameras>().unwrap();
+        let windows = resources.get::<Windows>();
+        let msaa_samples = if self.use_msaa {
+            resources.get::<Msaa>().map(|msaa| msaa.samples)
+        } else {
+            None
+        };
+        if let Some(samples) = msaa_samples {
+            self.descriptor.sample_count = samples;
+        }
 
         for (i, color_attachment) in self.descriptor.color_attachments.iter_mut().enumerate() {
             if self.default_clear_color_inputs.contains(&i) {
@@ -131,13 +159,24 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
                     color_attachment.ops.load = LoadOp::Clear(default_clear_color.0);
                 }
             }
-            if let Some(input_index) = self.color_attachment_input_indices[i] {
-                color_attachment.attachment =
-                    TextureAttachment::Id(input.get(input_index).unwrap().get_texture().unwrap());
+            // multisampled attachments are left empty while msaa is disabled
+            if let Some(texture) = self.color_attachment_input_indices[i]
+                .and_then(|input_index| input.get(input_index))
+            {
+                color_attachment.attachment = TextureAttachment::Id(texture.get_texture().unwrap());
             }
             if let Some(input_index) = self.color_resolve_target_indices[i] {
-                color_attachment.resolve_target =
-                    Some(TextureAttachment::Id(input.get(input_index).unwrap().get_texture().unwrap()));
+                color_attachment.resolve_target = Some(TextureAttachment::Id(
+                    input.get(input_index).unwrap().get_texture().unwrap(),
+                ));
+            }
+            // both are set from inputs again next frame, so they can be swapped back when msaa is re-enabled
+            if let (Some(1), Some(_), Some(_)) = (
+                msaa_samples,
+                self.color_attachment_input_indices[i],
+                self.color_resolve_target_indices[i],
+            ) {
+                color_attachment.attachment = color_attachment.resolve_target.take().unwrap();
             }
         }
 
@@ -168,6 +207,8 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
             }
         }
 
+        // the pass closure can't mutate its captures
+        let draw_calls = Cell::new(0);
         render_context.begin_pass(
             &self.descriptor,
             &render_resource_bindings,
@@ -180,11 +221,29 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
                     };
 
                     // get an ordered list of entities visible to the camera
-                    let visible_entities = if let Some(camera_entity) = active_cameras.get(&camera_info.name) {
-                        world.get::<VisibleEntities>(camera_entity).unwrap()
+                    let camera_entity = if let Some(camera_entity) = active_cameras.get(&camera_info.name) {
+                        camera_entity
                     } else {
                         continue;
                     };
+                    let visible_entities = world.get::<VisibleEntities>(camera_entity).unwrap();
+
+                    // cameras without a viewport draw to the whole window, which also resets the viewport of the previous camera
+                    if let (Some(windows), Ok(camera)) = (windows.as_ref(), world.get::<Camera>(camera_entity)) {
+                        if let Some(window) = windows.get(camera.window) {
+                            match camera.viewport {
+                                Some(viewport) => render_pass.set_viewport(
+                                    viewport.position.x(),
+                                    window.height as f32 - viewport.position.y() - viewport.size.y(),
+                                    viewport.size.x(),
+                                    viewport.size.y(),
+                                    0.0,
+                                    1.0,
+                                ),
+                                None => render_pass.set_viewport(0.0, 0.0, window.width as f32, window.height as f32, 0.0, 1.0),
+                            }
+                        }
+                    }
 
                     // attempt to draw each visible entity
                     let mut draw_state = DrawState::default();
@@ -240,10 +299,23 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
                                             *base_vertex,
                                             instances.clone(),
                                         );
+                                        draw_calls.set(draw_calls.get() + 1);
                                     } else {
                                         log::info!("Could not draw indexed because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
                                     }
                                 }
+                                RenderCommand::DrawIndexedIndirect {
+                                    buffer,
+                                    offset,
+                                    count,
+                                } => {
+                                    if draw_state.can_draw_indexed() {
+                                        render_pass.draw_indexed_indirect(*buffer, *offset, *count);
+                                        draw_calls.set(draw_calls.get() + *count as usize);
+                                    } else {
+                                        log::info!("Could not draw indexed indirect because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
+                                    }
+                                }
                                 RenderCommand::SetVertexBuffer {
                                     buffer,
                                     offset,
@@ -280,6 +352,14 @@ impl<Q: HecsQuery + Send + Sync + 'static> Node for PassNode<Q> {
                 }
             },
         );
+
+        if let Some(render_stats) = resources.get::<RenderStats>() {
+            render_stats.add_draw_calls(draw_calls.get());
+        }
+    }
+
+    fn encodes_in_parallel(&self) -> bool {
+        true
     }
 }
 
@@ -377,6 +377,10 @@ where
     ) {
         self.command_queue.execute(render_context);
     }
+
+    fn encodes_in_parallel(&self) -> bool {
+        true
+    }
 }
 
 impl<T> SystemNode for RenderResourcesNode<T>
@@ -423,10 +427,14 @@ fn render_resources_node_system<T: RenderResources>(
     let render_resource_context = &**render_resource_context;
     state.uniform_buffer_arrays.reset_changed_item_counts();
     // update uniforms info
-    for (uniforms, draw, _render_pipelines) in &mut query.iter() {
+    for (uniforms, draw, render_pipelines) in &mut query.iter() {
         if !draw.is_visible {
             return;
         }
+        // entities without pipelines (ex: batched sprites) never bind their uniforms
+        if render_pipelines.pipelines.is_empty() {
+            continue;
+        }
 
         state
             .uniform_buffer_arrays
@@ -443,6 +451,9 @@ fn render_resources_node_system<T: RenderResources>(
         if !draw.is_visible {
             return;
         }
+        if render_pipelines.pipelines.is_empty() {
+            continue;
+        }
 
         setup_uniform_texture_resources::<T>(
             &uniforms,
@@ -461,6 +472,9 @@ fn render_resources_node_system<T: RenderResources>(
                     if !draw.is_visible {
                         return;
                     }
+                    if render_pipelines.pipelines.is_empty() {
+                        continue;
+                    }
 
                     state.uniform_buffer_arrays.setup_uniform_buffer_resources(
                         &uniforms,
@@ -484,6 +498,9 @@ fn render_resources_node_system<T: RenderResources>(
             if !draw.is_visible {
                 return;
             }
+            if render_pipelines.pipelines.is_empty() {
+                continue;
+            }
 
             state.uniform_buffer_arrays.setup_uniform_buffer_resources(
                 &uniforms,
@@ -533,6 +550,10 @@ where
     ) {
         self.command_queue.execute(render_context);
     }
+
+    fn encodes_in_parallel(&self) -> bool {
+        true
+    }
 }
 
 const EXPECT_ASSET_MESSAGE: &str = "Only assets that exist should be in the modified assets list";
@@ -659,6 +680,9 @@ fn asset_render_resources_node_system<T: RenderResources>(
     }
 
     for (asset_handle, _draw, mut render_pipelines) in &mut query.iter() {
+        if render_pipelines.pipelines.is_empty() {
+            continue;
+        }
         if let Some(asset_bindings) = asset_render_resource_bindings.get(*asset_handle) {
             render_pipelines.bindings.extend(asset_bindings);
         }
@@ -0,0 +1,218 @@
+use crate::{
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots},
+    renderer::{RenderContext, RenderResourceId, RenderResourceType, TextureId},
+    texture::{
+        Extent3d, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage,
+        TEXTURE_ASSET_INDEX,
+    },
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Resources, World};
+use std::borrow::Cow;
+
+/// Creates the attachments of a pass that renders into a [Texture] asset. The texture that is rendered into replaces
+/// the asset's GPU texture, so materials and UI images using the asset's handle show the rendered image.
+///
+/// The asset decides the size of the attachments. Pipelines render in [TextureFormat::Bgra8UnormSrgb], so the asset
+/// should use that format (see [Texture::new_render_target]).
+pub struct TextureTargetNode {
+    texture: Handle<Texture>,
+    descriptor: Option<TextureDescriptor>,
+    sample_count: u32,
+    attachments: Option<[TextureId; 3]>,
+}
+
+impl TextureTargetNode {
+    pub const OUT_COLOR_ATTACHMENT: &'static str = "color_attachment";
+    pub const OUT_COLOR_RESOLVE_TARGET: &'static str = "color_resolve_target";
+    pub const OUT_DEPTH: &'static str = "depth";
+
+    pub fn new(texture: Handle<Texture>) -> Self {
+        TextureTargetNode {
+            texture,
+            descriptor: None,
+            sample_count: 1,
+            attachments: None,
+        }
+    }
+}
+
+impl Node for TextureTargetNode {
+    fn output(&self) -> &[ResourceSlotInfo] {
+        static OUTPUT: &[ResourceSlotInfo] = &[
+            ResourceSlotInfo {
+                name: Cow::Borrowed(TextureTargetNode::OUT_COLOR_ATTACHMENT),
+                resource_type: RenderResourceType::Texture,
+            },
+            ResourceSlotInfo {
+                name: Cow::Borrowed(TextureTargetNode::OUT_COLOR_RESOLVE_TARGET),
+                resource_type: RenderResourceType::Texture,
+            },
+            ResourceSlotInfo {
+                name: Cow::Borrowed(TextureTargetNode::OUT_DEPTH),
+                resource_type: RenderResourceType::Texture,
+            },
+        ];
+        OUTPUT
+    }
+
+    fn update(
+        &mut self,
+        _world: &World,
+        resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        const OUT_COLOR_ATTACHMENT: usize = 0;
+        const OUT_COLOR_RESOLVE_TARGET: usize = 1;
+        const OUT_DEPTH: usize = 2;
+        let textures = resources.get::<Assets<Texture>>().unwrap();
+        let sample_count = resources.get::<Msaa>().map_or(1, |msaa| msaa.samples);
+        let texture = textures.get(&self.texture);
+
+        // textures that aren't loaded yet are rendered at 1x1 and not shown
+        let (width, height) = texture.map_or((1, 1), |texture| {
+            (
+                (texture.size.x() as u32).max(1),
+                (texture.size.y() as u32).max(1),
+            )
+        });
+        let descriptor = TextureDescriptor {
+            size: Extent3d {
+                width,
+                height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            // texture copies still upload the asset's data when it is created or modified
+            usage: TextureUsage::SAMPLED | TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_DST,
+        };
+
+        let render_resource_context = render_context.resources_mut();
+        let asset_texture = render_resource_context
+            .get_asset_resource(self.texture, TEXTURE_ASSET_INDEX)
+            .and_then(|resource| resource.get_texture());
+        // the asset's texture is recreated when the asset is modified, which replaces ours
+        let replaced = texture.is_some()
+            && asset_texture != self.attachments.map(|attachments| attachments[1]);
+        if self.descriptor != Some(descriptor) || self.sample_count != sample_count || replaced {
+            if let Some(texture) = texture {
+                if texture.format != TextureFormat::Bgra8UnormSrgb {
+                    log::warn!(
+                        "Render target textures should use TextureFormat::Bgra8UnormSrgb, but one uses {:?}",
+                        texture.format
+                    );
+                }
+            }
+            if let Some(attachments) = self.attachments.take() {
+                for attachment in attachments.iter() {
+                    render_resource_context.remove_texture(*attachment);
+                }
+            }
+            if let (true, Some(asset_texture)) = (replaced, asset_texture) {
+                render_resource_context.remove_texture(asset_texture);
+            }
+
+            let color_resolve_target = render_resource_context.create_texture(descriptor);
+            let color_attachment = render_resource_context.create_texture(TextureDescriptor {
+                sample_count,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+                ..descriptor
+            });
+            let depth = render_resource_context.create_texture(TextureDescriptor {
+                sample_count,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+                ..descriptor
+            });
+            if texture.is_some() {
+                render_resource_context.set_asset_resource(
+                    self.texture,
+                    RenderResourceId::Texture(color_resolve_target),
+                    TEXTURE_ASSET_INDEX,
+                );
+            }
+
+            self.attachments = Some([color_attachment, color_resolve_target, depth]);
+            self.descriptor = Some(descriptor);
+            self.sample_count = sample_count;
+        }
+
+        let attachments = self.attachments.unwrap();
+        output.set(
+            OUT_COLOR_ATTACHMENT,
+            RenderResourceId::Texture(attachments[0]),
+        );
+        output.set(
+            OUT_COLOR_RESOLVE_TARGET,
+            RenderResourceId::Texture(attachments[1]),
+        );
+        output.set(OUT_DEPTH, RenderResourceId::Texture(attachments[2]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextureTargetNode;
+    use crate::{
+        prelude::Msaa,
+        render_graph::{Node, ResourceSlots},
+        renderer::{RenderContext, RenderResourceId, TestRenderContext},
+        texture::{Texture, TEXTURE_ASSET_INDEX},
+    };
+    use bevy_asset::{Assets, Handle};
+    use bevy_ecs::{Resources, World};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn render_into_texture_asset() {
+        let world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Assets::<Texture>::default());
+        resources.insert(Msaa { samples: 1 });
+        let texture = Handle::<Texture>::new();
+        let mut node = TextureTargetNode::new(texture);
+        let mut render_context = TestRenderContext::default();
+        let input = ResourceSlots::default();
+        let mut output = ResourceSlots::from(node.output());
+        let mut update = |resources: &Resources, node: &mut TextureTargetNode| {
+            node.update(&world, resources, &mut render_context, &input, &mut output);
+            let asset_texture = render_context
+                .resources()
+                .get_asset_resource(texture, TEXTURE_ASSET_INDEX);
+            (
+                output.get(TextureTargetNode::OUT_COLOR_RESOLVE_TARGET),
+                asset_texture,
+            )
+        };
+
+        // attachments exist before the asset is loaded, but aren't shown
+        let (unloaded_target, asset_texture) = update(&resources, &mut node);
+        assert!(unloaded_target.is_some());
+        assert_eq!(asset_texture, None);
+
+        // once the asset is loaded, it is replaced by the texture that is rendered into
+        resources
+            .get_mut::<Assets<Texture>>()
+            .unwrap()
+            .set(texture, Texture::new_render_target(Vec2::new(64.0, 32.0)));
+        let (target, asset_texture) = update(&resources, &mut node);
+        assert_ne!(target, unloaded_target);
+        assert_eq!(asset_texture, target);
+        assert_eq!(
+            update(&resources, &mut node),
+            (target.clone(), asset_texture)
+        );
+
+        // changing msaa recreates the attachments
+        resources.get_mut::<Msaa>().unwrap().samples = 4;
+        let (msaa_target, asset_texture) = update(&resources, &mut node);
+        assert_ne!(msaa_target, target);
+        assert_eq!(asset_texture, msaa_target);
+        assert!(matches!(msaa_target, Some(RenderResourceId::Texture(_))));
+    }
+}
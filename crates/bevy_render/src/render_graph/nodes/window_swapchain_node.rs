@@ -4,13 +4,14 @@ use crate::{
 };
 use bevy_app::prelude::{EventReader, Events};
 use bevy_ecs::{Resources, World};
-use bevy_window::{WindowCreated, WindowId, WindowResized, Windows};
+use bevy_window::{WindowCreated, WindowId, WindowResized, WindowVsyncChanged, Windows};
 use std::borrow::Cow;
 
 pub struct WindowSwapChainNode {
     window_id: WindowId,
     window_created_event_reader: EventReader<WindowCreated>,
     window_resized_event_reader: EventReader<WindowResized>,
+    window_vsync_changed_event_reader: EventReader<WindowVsyncChanged>,
 }
 
 impl WindowSwapChainNode {
@@ -21,6 +22,7 @@ impl WindowSwapChainNode {
             window_id,
             window_created_event_reader: Default::default(),
             window_resized_event_reader: Default::default(),
+            window_vsync_changed_event_reader: Default::default(),
         }
     }
 }
@@ -45,6 +47,7 @@ impl Node for WindowSwapChainNode {
         const WINDOW_TEXTURE: usize = 0;
         let window_created_events = resources.get::<Events<WindowCreated>>().unwrap();
         let window_resized_events = resources.get::<Events<WindowResized>>().unwrap();
+        let window_vsync_changed_events = resources.get::<Events<WindowVsyncChanged>>().unwrap();
         let windows = resources.get::<Windows>().unwrap();
 
         let window = windows
@@ -53,7 +56,7 @@ impl Node for WindowSwapChainNode {
 
         let render_resource_context = render_context.resources_mut();
 
-        // create window swapchain when window is resized or created
+        // create window swapchain when window is resized, created, or its vsync changes
         if self
             .window_created_event_reader
             .find_latest(&window_created_events, |e| e.id == window.id)
@@ -62,6 +65,10 @@ impl Node for WindowSwapChainNode {
                 .window_resized_event_reader
                 .find_latest(&window_resized_events, |e| e.id == window.id)
                 .is_some()
+            || self
+                .window_vsync_changed_event_reader
+                .find_latest(&window_vsync_changed_events, |e| e.id == window.id)
+                .is_some()
         {
             render_resource_context.create_swap_chain(window);
         }
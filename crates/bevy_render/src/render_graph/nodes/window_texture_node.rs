@@ -1,5 +1,5 @@
 use crate::{
-    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    render_graph::{base::Msaa, Node, ResourceSlotInfo, ResourceSlots},
     renderer::{RenderContext, RenderResourceId, RenderResourceType},
     texture::TextureDescriptor,
 };
@@ -11,6 +11,8 @@ use std::borrow::Cow;
 pub struct WindowTextureNode {
     window_id: WindowId,
     descriptor: TextureDescriptor,
+    use_msaa: bool,
+    only_with_msaa: bool,
     window_created_event_reader: EventReader<WindowCreated>,
     window_resized_event_reader: EventReader<WindowResized>,
 }
@@ -22,10 +24,24 @@ impl WindowTextureNode {
         WindowTextureNode {
             window_id,
             descriptor,
+            use_msaa: false,
+            only_with_msaa: false,
             window_created_event_reader: Default::default(),
             window_resized_event_reader: Default::default(),
         }
     }
+
+    /// Takes the sample count of the texture from the [Msaa] resource. The texture is recreated when it changes.
+    pub fn use_msaa(&mut self) {
+        self.use_msaa = true;
+    }
+
+    /// Like [WindowTextureNode::use_msaa], but the texture is only created while the sample count is above 1. Without
+    /// msaa the output is left empty, ex: for multisampled color attachments that passes resolve into another texture.
+    pub fn only_with_msaa(&mut self) {
+        self.use_msaa = true;
+        self.only_with_msaa = true;
+    }
 }
 
 impl Node for WindowTextureNode {
@@ -54,10 +70,30 @@ impl Node for WindowTextureNode {
             .get(self.window_id)
             .expect("Received window resized event for non-existent window");
 
-        if self
-            .window_created_event_reader
-            .find_latest(&window_created_events, |e| e.id == window.id)
-            .is_some()
+        let mut sample_count_changed = false;
+        if self.use_msaa {
+            if let Some(msaa) = resources.get::<Msaa>() {
+                sample_count_changed = self.descriptor.sample_count != msaa.samples;
+                self.descriptor.sample_count = msaa.samples;
+            }
+        }
+
+        if self.only_with_msaa && self.descriptor.sample_count == 1 {
+            if let Some(RenderResourceId::Texture(old_texture)) =
+                output.get_slot_mut(WINDOW_TEXTURE).unwrap().resource.take()
+            {
+                render_context.resources_mut().remove_texture(old_texture);
+            }
+            return;
+        }
+
+        // nodes added after the window was created have to create their texture without an event
+        if output.get(WINDOW_TEXTURE).is_none()
+            || sample_count_changed
+            || self
+                .window_created_event_reader
+                .find_latest(&window_created_events, |e| e.id == window.id)
+                .is_some()
             || self
                 .window_resized_event_reader
                 .find_latest(&window_resized_events, |e| e.id == window.id)
@@ -75,3 +111,51 @@ impl Node for WindowTextureNode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::WindowTextureNode;
+    use crate::{
+        prelude::Msaa,
+        render_graph::{Node, ResourceSlots},
+        renderer::TestRenderContext,
+        texture::TextureDescriptor,
+    };
+    use bevy_app::prelude::Events;
+    use bevy_ecs::{Resources, World};
+    use bevy_window::{Window, WindowCreated, WindowDescriptor, WindowId, WindowResized, Windows};
+
+    #[test]
+    fn only_with_msaa() {
+        let world = World::default();
+        let mut resources = Resources::default();
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+        ));
+        resources.insert(windows);
+        resources.insert(Events::<WindowCreated>::default());
+        resources.insert(Events::<WindowResized>::default());
+        resources.insert(Msaa { samples: 1 });
+
+        let mut node = WindowTextureNode::new(WindowId::primary(), TextureDescriptor::default());
+        node.only_with_msaa();
+        let mut render_context = TestRenderContext::default();
+        let input = ResourceSlots::default();
+        let mut output = ResourceSlots::from(node.output());
+        let mut update = |node: &mut WindowTextureNode, output: &mut ResourceSlots| {
+            node.update(&world, &resources, &mut render_context, &input, output);
+            output.get(WindowTextureNode::OUT_TEXTURE)
+        };
+
+        // without msaa the texture isn't allocated
+        assert!(update(&mut node, &mut output).is_none());
+        resources.get_mut::<Msaa>().unwrap().samples = 4;
+        let texture = update(&mut node, &mut output);
+        assert!(texture.is_some());
+        assert_eq!(update(&mut node, &mut output), texture);
+        resources.get_mut::<Msaa>().unwrap().samples = 1;
+        assert!(update(&mut node, &mut output).is_none());
+    }
+}
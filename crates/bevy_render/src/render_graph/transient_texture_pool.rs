@@ -0,0 +1,227 @@
+use super::{Edge, NodeId, NodeState, StageBorrow};
+use crate::{
+    renderer::{RenderResourceContext, RenderResourceId, TextureId},
+    texture::TextureDescriptor,
+};
+use std::collections::HashMap;
+
+/// Textures that render graph nodes only need for part of a frame, ex: the intermediate targets of a post processing
+/// stack or a shadow map that is sampled once. Nodes acquire a texture with [TransientTexturePool::acquire] instead of
+/// creating one. A texture stops being used when the node releases it, or after the stage of the last node that reads
+/// it through a slot edge ran, and is then handed to the next node that acquires a texture with the same descriptor. Targets
+/// whose lifetimes don't overlap share the same memory this way.
+///
+/// Textures that weren't acquired for `max_unused_frames` frames are freed.
+#[derive(Debug)]
+pub struct TransientTexturePool {
+    pub max_unused_frames: u32,
+    free: Vec<FreeTexture>,
+    in_use: HashMap<TextureId, TextureDescriptor>,
+}
+
+#[derive(Debug)]
+struct FreeTexture {
+    texture: TextureId,
+    descriptor: TextureDescriptor,
+    unused_frames: u32,
+}
+
+impl Default for TransientTexturePool {
+    fn default() -> Self {
+        TransientTexturePool {
+            max_unused_frames: 3,
+            free: Vec::new(),
+            in_use: HashMap::new(),
+        }
+    }
+}
+
+impl TransientTexturePool {
+    /// Returns a texture matching `descriptor` that no other node uses right now. Its contents are undefined, so it
+    /// should be cleared or completely overwritten.
+    pub fn acquire(
+        &mut self,
+        render_resource_context: &dyn RenderResourceContext,
+        descriptor: TextureDescriptor,
+    ) -> TextureId {
+        let texture = match self
+            .free
+            .iter()
+            .position(|free| free.descriptor == descriptor)
+        {
+            Some(index) => self.free.remove(index).texture,
+            None => render_resource_context.create_texture(descriptor),
+        };
+        self.in_use.insert(texture, descriptor);
+        texture
+    }
+
+    /// Hands `texture` to the next node that acquires a texture like it. Commands recorded before the release still
+    /// see its contents. Returns false if the texture isn't an acquired transient texture.
+    pub fn release(&mut self, texture: TextureId) -> bool {
+        match self.in_use.remove(&texture) {
+            Some(descriptor) => {
+                self.free.push(FreeTexture {
+                    texture,
+                    descriptor,
+                    unused_frames: 0,
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_in_use(&self, texture: TextureId) -> bool {
+        self.in_use.contains_key(&texture)
+    }
+
+    /// The number of textures the pool owns
+    pub fn texture_count(&self) -> usize {
+        self.free.len() + self.in_use.len()
+    }
+
+    /// Ends the lifetime of every texture that is still in use, and frees textures that went unused for too long.
+    /// The render graph executor calls this after each frame.
+    pub fn end_frame(&mut self, render_resource_context: &dyn RenderResourceContext) {
+        let in_use = self.in_use.keys().cloned().collect::<Vec<_>>();
+        for texture in in_use {
+            self.release(texture);
+        }
+
+        let max_unused_frames = self.max_unused_frames;
+        self.free.retain(|free| {
+            if free.unused_frames >= max_unused_frames {
+                render_resource_context.remove_texture(free.texture);
+                false
+            } else {
+                true
+            }
+        });
+        for free in self.free.iter_mut() {
+            free.unused_frames += 1;
+        }
+    }
+}
+
+/// Tracks how many nodes still have to read each node output during a frame, so the render graph executor can release
+/// transient textures after the stage of their last reader ran
+#[derive(Debug, Default)]
+pub struct TransientTextureReaders {
+    remaining_readers: HashMap<(NodeId, usize), usize>,
+}
+
+impl TransientTextureReaders {
+    pub fn new(stages: &[StageBorrow]) -> Self {
+        let mut remaining_readers = HashMap::new();
+        let node_states = stages
+            .iter()
+            .flat_map(|stage| stage.jobs.iter())
+            .flat_map(|job| job.node_states.iter());
+        for node_state in node_states {
+            for edge in node_state.edges.input_edges.iter() {
+                if let Edge::SlotEdge {
+                    output_node,
+                    output_index,
+                    ..
+                } = edge
+                {
+                    *remaining_readers
+                        .entry((*output_node, *output_index))
+                        .or_insert(0) += 1;
+                }
+            }
+        }
+        TransientTextureReaders { remaining_readers }
+    }
+
+    /// Releases the transient textures `node_state` was the last reader of, and the transient textures it output that
+    /// no node reads
+    pub fn node_finished(&mut self, node_state: &NodeState, pool: &mut TransientTexturePool) {
+        for edge in node_state.edges.input_edges.iter() {
+            let (output_node, output_index, input_index) = match *edge {
+                Edge::SlotEdge {
+                    output_node,
+                    output_index,
+                    input_index,
+                    ..
+                } => (output_node, output_index, input_index),
+                Edge::NodeEdge { .. } => continue,
+            };
+            let remaining = match self.remaining_readers.get_mut(&(output_node, output_index)) {
+                Some(remaining) => remaining,
+                None => continue,
+            };
+            *remaining = remaining.saturating_sub(1);
+            if *remaining > 0 {
+                continue;
+            }
+            if let Some(RenderResourceId::Texture(texture)) =
+                node_state.input_slots.get(input_index)
+            {
+                pool.release(texture);
+            }
+        }
+
+        for (index, slot) in node_state.output_slots.iter().enumerate() {
+            if self.remaining_readers.contains_key(&(node_state.id, index)) {
+                continue;
+            }
+            if let Some(RenderResourceId::Texture(texture)) = slot.resource {
+                pool.release(texture);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransientTexturePool;
+    use crate::{
+        renderer::HeadlessRenderResourceContext,
+        texture::{Extent3d, TextureDescriptor, TextureFormat},
+    };
+
+    #[test]
+    fn transient_textures() {
+        let render_resource_context = HeadlessRenderResourceContext::default();
+        let mut pool = TransientTexturePool::default();
+        let small = TextureDescriptor {
+            size: Extent3d {
+                width: 64,
+                height: 64,
+                depth: 1,
+            },
+            ..Default::default()
+        };
+        let hdr = TextureDescriptor {
+            format: TextureFormat::Rgba16Float,
+            ..small
+        };
+
+        // targets that don't overlap share a texture
+        let shadow = pool.acquire(&render_resource_context, small);
+        assert!(pool.release(shadow));
+        let bloom = pool.acquire(&render_resource_context, small);
+        assert_eq!(bloom, shadow);
+
+        // targets that overlap, or are different, don't
+        let blur = pool.acquire(&render_resource_context, small);
+        let tonemap = pool.acquire(&render_resource_context, hdr);
+        assert_ne!(blur, bloom);
+        assert_ne!(tonemap, bloom);
+        assert_eq!(pool.texture_count(), 3);
+
+        // everything is released at the end of the frame, and reused in the next one
+        pool.end_frame(&render_resource_context);
+        assert!(!pool.is_in_use(blur));
+        assert_eq!(pool.acquire(&render_resource_context, hdr), tonemap);
+        assert_eq!(pool.texture_count(), 3);
+
+        // unused textures are freed
+        for _ in 0..=pool.max_unused_frames {
+            pool.end_frame(&render_resource_context);
+        }
+        assert_eq!(pool.texture_count(), 0);
+    }
+}
@@ -0,0 +1,116 @@
+use bevy_ecs::ResMut;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Why a batch couldn't be drawn together with the batch before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatchBreak {
+    /// The batches use different textures or materials
+    Texture,
+    /// The batches use different meshes
+    Mesh,
+    /// The batches are drawn to different [RenderLayers](crate::camera::RenderLayers)
+    Layers,
+    /// The batches are at different depths, so they have to be drawn in depth order
+    ZOrder,
+    /// The batches are clipped to different rects
+    Clip,
+}
+
+impl BatchBreak {
+    pub const ALL: [BatchBreak; 5] = [
+        BatchBreak::Texture,
+        BatchBreak::Mesh,
+        BatchBreak::Layers,
+        BatchBreak::ZOrder,
+        BatchBreak::Clip,
+    ];
+}
+
+/// Statistics about the current frame's draws. They are cleared at the start of each frame's render stages, and are
+/// complete after the [RENDER](crate::stage::RENDER) stage.
+#[derive(Debug, Default)]
+pub struct RenderStats {
+    /// The number of draw calls issued by render passes. Passes can be recorded on several threads at once, so this is
+    /// counted through a shared reference with [RenderStats::add_draw_calls].
+    draw_calls: AtomicUsize,
+    /// The number of batches sprites and UI nodes were grouped into
+    pub batches: usize,
+    batch_breaks: HashMap<BatchBreak, usize>,
+}
+
+impl RenderStats {
+    pub fn add_draw_calls(&self, count: usize) {
+        self.draw_calls.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// The number of draw calls issued by render passes
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls.load(Ordering::Relaxed)
+    }
+
+    /// Records a batch. `batch_break` is why it couldn't join the batch before it, or `None` if it is the first batch.
+    pub fn add_batch(&mut self, batch_break: Option<BatchBreak>) {
+        self.batches += 1;
+        if let Some(batch_break) = batch_break {
+            *self.batch_breaks.entry(batch_break).or_insert(0) += 1;
+        }
+    }
+
+    /// The number of batches that were broken for the given reason
+    pub fn batch_breaks(&self, batch_break: BatchBreak) -> usize {
+        self.batch_breaks.get(&batch_break).cloned().unwrap_or(0)
+    }
+
+    pub fn clear(&mut self) {
+        self.draw_calls.store(0, Ordering::Relaxed);
+        self.batches = 0;
+        self.batch_breaks.clear();
+    }
+}
+
+pub fn clear_render_stats_system(mut render_stats: ResMut<RenderStats>) {
+    render_stats.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchBreak, RenderStats};
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn render_stats() {
+        let render_stats = Arc::new(RenderStats::default());
+        // passes recorded on several threads count their draw calls through a shared reference
+        let threads = (0..4)
+            .map(|_| {
+                let render_stats = render_stats.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        render_stats.add_draw_calls(2);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut render_stats = Arc::try_unwrap(render_stats).unwrap();
+        assert_eq!(render_stats.draw_calls(), 800);
+
+        render_stats.add_batch(None);
+        render_stats.add_batch(Some(BatchBreak::Texture));
+        render_stats.add_batch(Some(BatchBreak::Texture));
+        assert_eq!(render_stats.batches, 3);
+        assert_eq!(render_stats.batch_breaks(BatchBreak::Texture), 2);
+        assert_eq!(render_stats.batch_breaks(BatchBreak::Clip), 0);
+
+        render_stats.clear();
+        assert_eq!(render_stats.draw_calls(), 0);
+        assert_eq!(render_stats.batches, 0);
+        assert_eq!(render_stats.batch_breaks(BatchBreak::Texture), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use super::RenderResourceContext;
 use crate::{
-    pipeline::{BindGroupDescriptorId, PipelineDescriptor},
+    pipeline::{BindGroupDescriptorId, ComputePipelineDescriptor, PipelineDescriptor},
     renderer::{BindGroup, BufferId, BufferInfo, RenderResourceId, SamplerId, TextureId},
     shader::Shader,
     texture::{SamplerDescriptor, TextureDescriptor},
@@ -125,6 +125,18 @@ impl RenderResourceContext for HeadlessRenderResourceContext {
     ) {
     }
 
+    fn create_compute_pipeline(
+        &self,
+        _pipeline_handle: Handle<ComputePipelineDescriptor>,
+        _pipeline_descriptor: &ComputePipelineDescriptor,
+        _shaders: &Assets<Shader>,
+    ) {
+    }
+
+    fn supports_compute(&self) -> bool {
+        false
+    }
+
     fn create_bind_group(
         &self,
         _bind_group_descriptor_id: BindGroupDescriptorId,
@@ -1,6 +1,6 @@
 use super::RenderResourceContext;
 use crate::{
-    pass::{PassDescriptor, RenderPass},
+    pass::{ComputePass, PassDescriptor, RenderPass},
     renderer::{BufferId, RenderResourceBindings, TextureId},
     texture::Extent3d,
 };
@@ -32,4 +32,47 @@ pub trait RenderContext {
         render_resource_bindings: &RenderResourceBindings,
         run_pass: &mut dyn Fn(&mut dyn RenderPass),
     );
+    fn begin_compute_pass(&mut self, run_pass: &mut dyn Fn(&mut dyn ComputePass));
+}
+
+/// A [RenderContext] without a gpu, for testing render graph nodes. Copies and passes are ignored.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct TestRenderContext {
+    render_resource_context: super::HeadlessRenderResourceContext,
+}
+
+#[cfg(test)]
+impl RenderContext for TestRenderContext {
+    fn resources(&self) -> &dyn RenderResourceContext {
+        &self.render_resource_context
+    }
+
+    fn resources_mut(&mut self) -> &mut dyn RenderResourceContext {
+        &mut self.render_resource_context
+    }
+
+    fn copy_buffer_to_buffer(&mut self, _: BufferId, _: u64, _: BufferId, _: u64, _: u64) {}
+
+    fn copy_buffer_to_texture(
+        &mut self,
+        _: BufferId,
+        _: u64,
+        _: u32,
+        _: TextureId,
+        _: [u32; 3],
+        _: u32,
+        _: Extent3d,
+    ) {
+    }
+
+    fn begin_pass(
+        &mut self,
+        _: &PassDescriptor,
+        _: &RenderResourceBindings,
+        _: &mut dyn Fn(&mut dyn RenderPass),
+    ) {
+    }
+
+    fn begin_compute_pass(&mut self, _: &mut dyn Fn(&mut dyn ComputePass)) {}
 }
@@ -1,7 +1,9 @@
 mod shader;
 mod shader_defs;
+mod shader_loader;
 mod shader_reflect;
 
 pub use shader::*;
 pub use shader_defs::*;
+pub use shader_loader::*;
 pub use shader_reflect::*;
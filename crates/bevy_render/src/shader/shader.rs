@@ -2,6 +2,7 @@ use super::ShaderLayout;
 use bevy_asset::Handle;
 use bevy_glsl_to_spirv::compile;
 use std::{io::Read, marker::Copy};
+use thiserror::Error;
 
 /// The stage of a shader
 #[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
@@ -21,15 +22,23 @@ impl Into<bevy_glsl_to_spirv::ShaderType> for ShaderStage {
     }
 }
 
+/// An error that occurs while compiling a shader
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error("Shader compilation failed: {0}")]
+    Compilation(String),
+}
+
 fn glsl_to_spirv(
     glsl_source: &str,
     stage: ShaderStage,
     shader_defs: Option<&[String]>,
-) -> Vec<u32> {
-    let mut output = compile(glsl_source, stage.into(), shader_defs).unwrap();
+) -> Result<Vec<u32>, ShaderError> {
+    let mut output =
+        compile(glsl_source, stage.into(), shader_defs).map_err(ShaderError::Compilation)?;
     let mut spv_bytes = Vec::new();
     output.read_to_end(&mut spv_bytes).unwrap();
-    bytes_to_words(&spv_bytes)
+    Ok(bytes_to_words(&spv_bytes))
 }
 
 fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
@@ -76,8 +85,13 @@ impl Shader {
     }
 
     pub fn get_spirv(&self, macros: Option<&[String]>) -> Vec<u32> {
+        self.try_get_spirv(macros).unwrap()
+    }
+
+    /// Like [Shader::get_spirv], but returns glsl compilation errors instead of panicking
+    pub fn try_get_spirv(&self, macros: Option<&[String]>) -> Result<Vec<u32>, ShaderError> {
         match self.source {
-            ShaderSource::Spirv(ref bytes) => bytes.clone(),
+            ShaderSource::Spirv(ref bytes) => Ok(bytes.clone()),
             ShaderSource::Glsl(ref source) => glsl_to_spirv(&source, self.stage, macros),
         }
     }
@@ -110,3 +110,38 @@ pub fn asset_shader_defs_system<T>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ShaderDefs;
+
+    #[derive(ShaderDefs)]
+    #[as_crate(bevy_render)]
+    struct TestMaterial {
+        #[shader_def]
+        shaded: bool,
+        #[shader_def]
+        transparent: bool,
+        #[shader_defs]
+        extra_shader_defs: Vec<String>,
+    }
+
+    #[test]
+    fn shader_defs() {
+        let mut material = TestMaterial {
+            shaded: true,
+            transparent: false,
+            extra_shader_defs: vec!["NORMAL_MAP".to_string(), "DETAIL".to_string()],
+        };
+        assert_eq!(
+            material.iter_shader_defs().collect::<Vec<_>>(),
+            vec!["TESTMATERIAL_SHADED", "NORMAL_MAP", "DETAIL"]
+        );
+
+        material.extra_shader_defs.clear();
+        assert_eq!(
+            material.iter_shader_defs().collect::<Vec<_>>(),
+            vec!["TESTMATERIAL_SHADED"]
+        );
+    }
+}
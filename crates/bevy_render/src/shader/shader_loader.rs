@@ -0,0 +1,31 @@
+use super::{Shader, ShaderStage};
+use anyhow::{anyhow, Result};
+use bevy_asset::AssetLoader;
+use std::path::Path;
+
+/// Loads glsl shaders as [Shader] assets. The stage is taken from the file extension: `.vert`, `.frag`, or `.comp`.
+///
+/// Pipelines that use a loaded shader are recompiled when the shader changes (ex: when it is reloaded because the
+/// [AssetServer](bevy_asset::AssetServer) watches for changes).
+#[derive(Clone, Default)]
+pub struct ShaderLoader;
+
+impl AssetLoader<Shader> for ShaderLoader {
+    fn from_bytes(&self, asset_path: &Path, bytes: Vec<u8>) -> Result<Shader> {
+        let stage = match asset_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("vert") => ShaderStage::Vertex,
+            Some("frag") => ShaderStage::Fragment,
+            Some("comp") => ShaderStage::Compute,
+            _ => return Err(anyhow!("unknown shader stage of {}", asset_path.display())),
+        };
+        Ok(Shader::from_glsl(stage, &String::from_utf8(bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["vert", "frag", "comp"];
+        EXTENSIONS
+    }
+}
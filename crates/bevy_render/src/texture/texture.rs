@@ -38,6 +38,14 @@ impl Texture {
         Self { data, size, format }
     }
 
+    /// Creates a texture that cameras can render into (see [Camera::render_target](crate::camera::Camera)). Its
+    /// contents are cleared every frame the camera renders.
+    pub fn new_render_target(size: Vec2) -> Self {
+        let format = TextureFormat::Bgra8UnormSrgb;
+        let data = vec![0; size.x() as usize * size.y() as usize * format.pixel_size()];
+        Self::new(size, data, format)
+    }
+
     pub fn new_fill(size: Vec2, pixel: &[u8], format: TextureFormat) -> Self {
         let mut value = Self::default();
         value.format = format;
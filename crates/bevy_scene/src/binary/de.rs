@@ -0,0 +1,320 @@
+use super::{tag, BinaryError};
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use std::convert::TryInto;
+
+/// Reads values written by [Serializer](super::Serializer). Values are never borrowed from the input, so the input
+/// can be a temporary buffer, ex: decompressed data.
+pub struct Deserializer<'a> {
+    input: &'a [u8],
+    strings: Vec<String>,
+}
+
+impl<'a> Deserializer<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Deserializer {
+            input,
+            strings: Vec::new(),
+        }
+    }
+
+    /// Deserializes one value with `seed`, and fails if there is data left over
+    pub fn deserialize_seed<'de, T>(mut self, seed: T) -> Result<T::Value, BinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(&mut self)?;
+        if self.input.is_empty() {
+            Ok(value)
+        } else {
+            Err(BinaryError::TrailingBytes)
+        }
+    }
+
+    fn peek_tag(&self) -> Result<u8, BinaryError> {
+        self.input
+            .first()
+            .copied()
+            .ok_or(BinaryError::UnexpectedEof)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], BinaryError> {
+        if self.input.len() < len {
+            return Err(BinaryError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn read_tag(&mut self) -> Result<u8, BinaryError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_array<A>(&mut self) -> Result<A, BinaryError>
+    where
+        A: Default + AsMut<[u8]>,
+    {
+        let mut array = A::default();
+        let len = array.as_mut().len();
+        array.as_mut().copy_from_slice(self.read_bytes(len)?);
+        Ok(array)
+    }
+
+    fn read_len(&mut self) -> Result<usize, BinaryError> {
+        // LEB128
+        let mut value = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_tag()?;
+            value |= ((byte & 0x7f) as usize)
+                .checked_shl(shift)
+                .ok_or_else(|| BinaryError::Message("length overflow".to_string()))?;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads the string following a [tag::STR] or [tag::STR_REF] tag, and returns its index in the string table
+    fn read_str(&mut self, tag: u8) -> Result<usize, BinaryError> {
+        match tag {
+            tag::STR => {
+                let len = self.read_len()?;
+                let bytes = self.read_bytes(len)?;
+                let string = std::str::from_utf8(bytes).map_err(|_| BinaryError::InvalidUtf8)?;
+                self.strings.push(string.to_string());
+                Ok(self.strings.len() - 1)
+            }
+            tag::STR_REF => {
+                let index = self.read_len()?;
+                if index < self.strings.len() {
+                    Ok(index)
+                } else {
+                    Err(BinaryError::InvalidStringRef(index))
+                }
+            }
+            tag => Err(BinaryError::InvalidTag(tag)),
+        }
+    }
+
+    /// Consumes the [tag::END] that terminates a sequence or map, if it is next
+    fn read_end(&mut self) -> Result<bool, BinaryError> {
+        if self.peek_tag()? == tag::END {
+            self.input = &self.input[1..];
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl<'de, 'a, 'b> de::Deserializer<'de> for &'b mut Deserializer<'a> {
+    type Error = BinaryError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read_tag()? {
+            tag::UNIT => visitor.visit_unit(),
+            tag::NONE => visitor.visit_none(),
+            tag::SOME => visitor.visit_some(self),
+            tag::FALSE => visitor.visit_bool(false),
+            tag::TRUE => visitor.visit_bool(true),
+            tag::I8 => visitor.visit_i8(i8::from_le_bytes(self.read_array()?)),
+            tag::I16 => visitor.visit_i16(i16::from_le_bytes(self.read_array()?)),
+            tag::I32 => visitor.visit_i32(i32::from_le_bytes(self.read_array()?)),
+            tag::I64 => visitor.visit_i64(i64::from_le_bytes(self.read_array()?)),
+            tag::I128 => visitor.visit_i128(i128::from_le_bytes(self.read_array()?)),
+            tag::U8 => visitor.visit_u8(self.read_tag()?),
+            tag::U16 => visitor.visit_u16(u16::from_le_bytes(self.read_array()?)),
+            tag::U32 => visitor.visit_u32(u32::from_le_bytes(self.read_array()?)),
+            tag::U64 => visitor.visit_u64(u64::from_le_bytes(self.read_array()?)),
+            tag::U128 => visitor.visit_u128(u128::from_le_bytes(self.read_array()?)),
+            tag::F32 => visitor.visit_f32(f32::from_le_bytes(self.read_array()?)),
+            tag::F64 => visitor.visit_f64(f64::from_le_bytes(self.read_array()?)),
+            tag::CHAR => {
+                let value = u32::from_le_bytes(self.read_array()?);
+                let value = value
+                    .try_into()
+                    .map_err(|_| BinaryError::Message(format!("invalid char {}", value)))?;
+                visitor.visit_char(value)
+            }
+            tag @ tag::STR | tag @ tag::STR_REF => {
+                let index = self.read_str(tag)?;
+                visitor.visit_str(&self.strings[index])
+            }
+            tag::BYTES => {
+                let len = self.read_len()?;
+                visitor.visit_bytes(self.read_bytes(len)?)
+            }
+            tag::SEQ => {
+                let mut access = Access::new(self);
+                let value = visitor.visit_seq(&mut access)?;
+                access.end()?;
+                Ok(value)
+            }
+            tag::MAP => {
+                let mut access = Access::new(self);
+                let value = visitor.visit_map(&mut access)?;
+                access.end()?;
+                Ok(value)
+            }
+            tag::VARIANT => visitor.visit_enum(Access::new(self)),
+            tag => Err(BinaryError::InvalidTag(tag)),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read_tag()? {
+            tag::NONE => visitor.visit_none(),
+            tag::SOME => visitor.visit_some(self),
+            tag => Err(BinaryError::InvalidTag(tag)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read_tag()? {
+            tag::VARIANT => visitor.visit_enum(Access::new(self)),
+            tag => Err(BinaryError::InvalidTag(tag)),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Access<'b, 'a> {
+    de: &'b mut Deserializer<'a>,
+    ended: bool,
+}
+
+impl<'b, 'a> Access<'b, 'a> {
+    fn new(de: &'b mut Deserializer<'a>) -> Self {
+        Access { de, ended: false }
+    }
+
+    /// Consumes the end of the sequence or map if the visitor stopped before reaching it, ex: fixed size tuples
+    fn end(self) -> Result<(), BinaryError> {
+        if self.ended || self.de.read_end()? {
+            Ok(())
+        } else {
+            Err(BinaryError::Message(
+                "expected the end of a sequence or map".to_string(),
+            ))
+        }
+    }
+}
+
+impl<'de, 'a, 'b> SeqAccess<'de> for Access<'b, 'a> {
+    type Error = BinaryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, BinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.de.read_end()? {
+            self.ended = true;
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.de).map(Some)
+        }
+    }
+}
+
+impl<'de, 'a, 'b> MapAccess<'de> for Access<'b, 'a> {
+    type Error = BinaryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, BinaryError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.de.read_end()? {
+            self.ended = true;
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.de).map(Some)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, BinaryError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de, 'a, 'b> EnumAccess<'de> for Access<'b, 'a> {
+    type Error = BinaryError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), BinaryError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(&mut *self.de)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, 'b> VariantAccess<'de> for Access<'b, 'a> {
+    type Error = BinaryError;
+
+    fn unit_variant(self) -> Result<(), BinaryError> {
+        match self.de.read_tag()? {
+            tag::UNIT => Ok(()),
+            tag => Err(BinaryError::InvalidTag(tag)),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, BinaryError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(&mut *self.de, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, BinaryError>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(&mut *self.de, visitor)
+    }
+}
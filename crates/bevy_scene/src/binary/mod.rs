@@ -0,0 +1,211 @@
+//! A compact binary serialization format for scenes and snapshots. It is self describing like RON, so it shares the
+//! same property serializers, but skips text parsing and writes each distinct string once, which makes large scenes
+//! much faster to load.
+
+mod de;
+mod ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+use serde::{de::DeserializeSeed, Serialize};
+use std::fmt::Display;
+use thiserror::Error;
+
+/// The extension of binary scene files
+pub const BINARY_SCENE_EXTENSION: &str = "scnb";
+
+const MAGIC: &[u8; 4] = b"BSCN";
+const VERSION: u8 = 1;
+const FLAG_COMPRESSED: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// The level passed to the deflate compressor, from 0 (fastest) to 10 (smallest)
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Every value starts with one of these tags, which is what makes the format self describing
+mod tag {
+    pub const UNIT: u8 = 0;
+    pub const NONE: u8 = 1;
+    pub const SOME: u8 = 2;
+    pub const FALSE: u8 = 3;
+    pub const TRUE: u8 = 4;
+    pub const I8: u8 = 5;
+    pub const I16: u8 = 6;
+    pub const I32: u8 = 7;
+    pub const I64: u8 = 8;
+    pub const I128: u8 = 9;
+    pub const U8: u8 = 10;
+    pub const U16: u8 = 11;
+    pub const U32: u8 = 12;
+    pub const U64: u8 = 13;
+    pub const U128: u8 = 14;
+    pub const F32: u8 = 15;
+    pub const F64: u8 = 16;
+    pub const CHAR: u8 = 17;
+    /// A string that hasn't been written before, which is added to the string table
+    pub const STR: u8 = 18;
+    /// The index of a string in the string table
+    pub const STR_REF: u8 = 19;
+    pub const BYTES: u8 = 20;
+    /// Sequences and maps are terminated by [END]
+    pub const SEQ: u8 = 21;
+    pub const MAP: u8 = 22;
+    pub const END: u8 = 23;
+    /// An enum variant, followed by the variant name and its value
+    pub const VARIANT: u8 = 24;
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BinaryError {
+    #[error("{0}")]
+    Message(String),
+    #[error("The data is not a binary scene.")]
+    InvalidMagic,
+    #[error("Binary scene version {0} is not supported.")]
+    UnsupportedVersion(u8),
+    #[error("Failed to decompress the binary scene.")]
+    Decompress,
+    #[error("Unexpected end of data.")]
+    UnexpectedEof,
+    #[error("Invalid value tag {0}.")]
+    InvalidTag(u8),
+    #[error("Invalid string reference {0}.")]
+    InvalidStringRef(usize),
+    #[error("Invalid UTF-8 string.")]
+    InvalidUtf8,
+    #[error("Unexpected data after the end of the value.")]
+    TrailingBytes,
+}
+
+impl serde::ser::Error for BinaryError {
+    fn custom<T: Display>(msg: T) -> Self {
+        BinaryError::Message(msg.to_string())
+    }
+}
+
+impl serde::de::Error for BinaryError {
+    fn custom<T: Display>(msg: T) -> Self {
+        BinaryError::Message(msg.to_string())
+    }
+}
+
+/// Returns true if `bytes` start with the header written by [serialize_binary]
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Serializes `value` to the binary format, optionally compressing it with deflate. Compression makes files several
+/// times smaller, but adds a little time to loading.
+pub fn serialize_binary<S>(value: S, compress: bool) -> Result<Vec<u8>, BinaryError>
+where
+    S: Serialize,
+{
+    let mut serializer = Serializer::default();
+    value.serialize(&mut serializer)?;
+    let payload = serializer.into_bytes();
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    if compress {
+        bytes.push(FLAG_COMPRESSED);
+        bytes.extend(miniz_oxide::deflate::compress_to_vec(
+            &payload,
+            COMPRESSION_LEVEL,
+        ));
+    } else {
+        bytes.push(0);
+        bytes.extend(payload);
+    }
+    Ok(bytes)
+}
+
+/// Deserializes data written by [serialize_binary] with `seed`
+pub fn deserialize_binary<'de, T>(seed: T, bytes: &[u8]) -> Result<T::Value, BinaryError>
+where
+    T: DeserializeSeed<'de>,
+{
+    if bytes.len() < HEADER_LEN || !is_binary(bytes) {
+        return Err(BinaryError::InvalidMagic);
+    }
+    let version = bytes[MAGIC.len()];
+    if version != VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+    let flags = bytes[MAGIC.len() + 1];
+    let payload = &bytes[HEADER_LEN..];
+
+    if flags & FLAG_COMPRESSED != 0 {
+        let payload = miniz_oxide::inflate::decompress_to_vec(payload)
+            .map_err(|_| BinaryError::Decompress)?;
+        Deserializer::new(&payload).deserialize_seed(seed)
+    } else {
+        Deserializer::new(payload).deserialize_seed(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize_binary, serialize_binary, BinaryError};
+    use serde::{Deserialize, Serialize};
+    use std::{collections::BTreeMap, marker::PhantomData};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f32),
+        Rect { width: f32, height: f32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        name: String,
+        id: u32,
+        offset: i64,
+        visible: bool,
+        parent: Option<u32>,
+        tags: Vec<String>,
+        values: BTreeMap<String, (f64, char)>,
+        shapes: Vec<Shape>,
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut values = BTreeMap::new();
+        values.insert("a".to_string(), (1.5, 'x'));
+        let data = Data {
+            name: "tree".to_string(),
+            id: 7,
+            offset: -3,
+            visible: true,
+            parent: None,
+            tags: vec!["tree".to_string(), "tree".to_string(), "big".to_string()],
+            values,
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(2.0),
+                Shape::Rect {
+                    width: 1.0,
+                    height: 2.0,
+                },
+            ],
+        };
+
+        for compress in [false, true].iter() {
+            let bytes = serialize_binary(&data, *compress).unwrap();
+            let result: Data = deserialize_binary(PhantomData, &bytes).unwrap();
+            assert_eq!(result, data);
+        }
+
+        let bytes = serialize_binary(&data, false).unwrap();
+        assert_eq!(
+            deserialize_binary::<PhantomData<Data>>(PhantomData, &bytes[..bytes.len() - 1]),
+            Err(BinaryError::UnexpectedEof)
+        );
+        assert_eq!(
+            deserialize_binary::<PhantomData<Data>>(PhantomData, b"[]"),
+            Err(BinaryError::InvalidMagic)
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use super::{tag, BinaryError};
+use serde::{ser, Serialize};
+use std::collections::HashMap;
+
+/// Writes values in the binary format. Sequences and maps are terminated instead of length prefixed, so they can be
+/// written without knowing their length up front.
+#[derive(Default)]
+pub struct Serializer {
+    output: Vec<u8>,
+    strings: HashMap<String, usize>,
+}
+
+impl Serializer {
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.output
+    }
+
+    fn write_tag(&mut self, tag: u8) {
+        self.output.push(tag);
+    }
+
+    fn write_len(&mut self, mut value: usize) {
+        // LEB128
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.output.push(byte);
+                return;
+            }
+            self.output.push(byte | 0x80);
+        }
+    }
+
+    /// Writes each distinct string once, and refers back to it afterwards. Scenes repeat the same type and field names
+    /// for every component, so this keeps them small.
+    fn write_str(&mut self, value: &str) {
+        if let Some(index) = self.strings.get(value) {
+            let index = *index;
+            self.write_tag(tag::STR_REF);
+            self.write_len(index);
+        } else {
+            self.strings.insert(value.to_string(), self.strings.len());
+            self.write_tag(tag::STR);
+            self.write_len(value.len());
+            self.output.extend_from_slice(value.as_bytes());
+        }
+    }
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), BinaryError> {
+        self.write_tag(if v { tag::TRUE } else { tag::FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), BinaryError> {
+        self.write_tag(tag::I8);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), BinaryError> {
+        self.write_tag(tag::I16);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), BinaryError> {
+        self.write_tag(tag::I32);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), BinaryError> {
+        self.write_tag(tag::I64);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), BinaryError> {
+        self.write_tag(tag::I128);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), BinaryError> {
+        self.write_tag(tag::U8);
+        self.output.push(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), BinaryError> {
+        self.write_tag(tag::U16);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), BinaryError> {
+        self.write_tag(tag::U32);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), BinaryError> {
+        self.write_tag(tag::U64);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), BinaryError> {
+        self.write_tag(tag::U128);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), BinaryError> {
+        self.write_tag(tag::F32);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), BinaryError> {
+        self.write_tag(tag::F64);
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), BinaryError> {
+        self.write_tag(tag::CHAR);
+        self.output.extend_from_slice(&(v as u32).to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), BinaryError> {
+        self.write_str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), BinaryError> {
+        self.write_tag(tag::BYTES);
+        self.write_len(v.len());
+        self.output.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::NONE);
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.write_tag(tag::SOME);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::UNIT);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), BinaryError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), BinaryError> {
+        self.write_tag(tag::VARIANT);
+        self.write_str(variant);
+        self.serialize_unit()
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.write_tag(tag::VARIANT);
+        self.write_str(variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, BinaryError> {
+        self.write_tag(tag::SEQ);
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, BinaryError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, BinaryError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, BinaryError> {
+        self.write_tag(tag::VARIANT);
+        self.write_str(variant);
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, BinaryError> {
+        self.write_tag(tag::MAP);
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, BinaryError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, BinaryError> {
+        self.write_tag(tag::VARIANT);
+        self.write_str(variant);
+        self.serialize_map(Some(len))
+    }
+}
+
+impl ser::SerializeSeq for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.write_str(key);
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = BinaryError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), BinaryError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.write_str(key);
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryError> {
+        self.write_tag(tag::END);
+        Ok(())
+    }
+}
@@ -0,0 +1,474 @@
+use crate::{InstanceId, Scene, SceneSpawnEvent, SceneSpawner};
+use bevy_app::prelude::{EventReader, Events};
+use bevy_asset::{AssetServer, Handle};
+use bevy_ecs::{Commands, Local, Query, Res, ResMut};
+use bevy_math::Vec3;
+use bevy_transform::prelude::Transform;
+use std::{collections::HashMap, time::Duration};
+
+/// The coordinates of a chunk on a [ChunkGrid]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChunkCoord {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl ChunkCoord {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        ChunkCoord { x, y, z }
+    }
+
+    /// Returns the number of chunks between two coordinates along the axis where they are the furthest apart
+    pub fn distance(&self, other: ChunkCoord) -> u32 {
+        let x = (self.x - other.x).abs();
+        let y = (self.y - other.y).abs();
+        let z = (self.z - other.z).abs();
+        x.max(y).max(z) as u32
+    }
+}
+
+/// Divides the world into chunks of `chunk_size`. Axes with a size of 0.0 aren't divided, ex: a 2D tilemap uses
+/// `Vec3::new(size, size, 0.0)` and an open world map on the ground uses `Vec3::new(size, 0.0, size)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkGrid {
+    pub chunk_size: Vec3,
+}
+
+impl ChunkGrid {
+    pub fn new(chunk_size: Vec3) -> Self {
+        ChunkGrid { chunk_size }
+    }
+
+    /// Returns the coordinates of the chunk containing `position`
+    pub fn chunk_coord(&self, position: Vec3) -> ChunkCoord {
+        let axis = |position: f32, size: f32| {
+            if size > 0.0 {
+                (position / size).floor() as i32
+            } else {
+                0
+            }
+        };
+        ChunkCoord {
+            x: axis(position.x(), self.chunk_size.x()),
+            y: axis(position.y(), self.chunk_size.y()),
+            z: axis(position.z(), self.chunk_size.z()),
+        }
+    }
+
+    /// Returns the position of the chunk's minimum corner
+    pub fn chunk_origin(&self, coord: ChunkCoord) -> Vec3 {
+        Vec3::new(coord.x as f32, coord.y as f32, coord.z as f32) * self.chunk_size
+    }
+
+    /// Returns the coordinates of the chunks at most `radius` chunks away from `center`
+    pub fn chunks_in_radius(&self, center: ChunkCoord, radius: u32) -> Vec<ChunkCoord> {
+        let radius = radius as i32;
+        let range = |size: f32| {
+            if size > 0.0 {
+                -radius..=radius
+            } else {
+                0..=0
+            }
+        };
+        let mut chunks = Vec::new();
+        for x in range(self.chunk_size.x()) {
+            for y in range(self.chunk_size.y()) {
+                for z in range(self.chunk_size.z()) {
+                    chunks.push(ChunkCoord::new(center.x + x, center.y + y, center.z + z));
+                }
+            }
+        }
+        chunks
+    }
+}
+
+/// Keeps the chunks around an entity loaded. Chunks are loaded once they are within `load_radius` chunks of an
+/// anchor, and unloaded once they are further than `unload_radius` chunks from every anchor. The gap between the two
+/// keeps chunks on the border from loading and unloading repeatedly while an anchor moves back and forth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkAnchor {
+    pub load_radius: u32,
+    pub unload_radius: u32,
+}
+
+impl ChunkAnchor {
+    pub fn new(load_radius: u32) -> Self {
+        ChunkAnchor {
+            load_radius,
+            unload_radius: load_radius + 1,
+        }
+    }
+}
+
+impl Default for ChunkAnchor {
+    fn default() -> Self {
+        ChunkAnchor::new(1)
+    }
+}
+
+/// Added to the entities spawned for a chunk once the chunk's scene has finished spawning
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chunk {
+    pub coord: ChunkCoord,
+}
+
+/// Provides the scenes of chunks, ex: by loading a scene file named after the chunk's coordinates. Scenes should be
+/// loaded with the [AssetServer], which loads them in the background. Chunks are spawned once their scenes have loaded.
+pub trait ChunkProvider: Send + Sync + 'static {
+    /// Returns the scene of the chunk at `coord`, or `None` if the provider has nothing there
+    fn provide(&self, coord: ChunkCoord, asset_server: &AssetServer) -> Option<Handle<Scene>>;
+}
+
+impl<F> ChunkProvider for F
+where
+    F: Fn(ChunkCoord, &AssetServer) -> Option<Handle<Scene>> + Send + Sync + 'static,
+{
+    fn provide(&self, coord: ChunkCoord, asset_server: &AssetServer) -> Option<Handle<Scene>> {
+        self(coord, asset_server)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChunkEvent {
+    /// The scenes of every provider have been spawned for the chunk
+    Loaded(ChunkCoord),
+    /// The chunk finished loading, but some of its scenes failed to spawn or were cancelled. The other scenes have
+    /// been spawned.
+    LoadedWithErrors(ChunkCoord),
+    /// The chunk's entities have been queued to be despawned
+    Unloaded(ChunkCoord),
+}
+
+struct LoadedChunk {
+    instances: Vec<InstanceId>,
+    /// The number of instances that haven't finished spawning
+    pending_instances: usize,
+    /// The number of instances that failed to spawn or were cancelled
+    failed_instances: usize,
+}
+
+/// Loads and unloads chunk scenes around [ChunkAnchor]s. Each provider adds one scene instance per chunk, which is
+/// spawned over multiple frames with [SceneSpawner::instance_streamed].
+pub struct ChunkStreamer {
+    pub grid: ChunkGrid,
+    /// The time spent on spawning each chunk instance per frame
+    pub spawn_budget: Duration,
+    providers: Vec<Box<dyn ChunkProvider>>,
+    chunks: HashMap<ChunkCoord, LoadedChunk>,
+}
+
+impl Default for ChunkStreamer {
+    fn default() -> Self {
+        ChunkStreamer {
+            grid: ChunkGrid::new(Vec3::new(100.0, 0.0, 100.0)),
+            spawn_budget: Duration::from_millis(1),
+            providers: Vec::new(),
+            chunks: HashMap::default(),
+        }
+    }
+}
+
+impl ChunkStreamer {
+    pub fn new(grid: ChunkGrid) -> Self {
+        ChunkStreamer {
+            grid,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_provider(&mut self, provider: impl ChunkProvider) -> &mut Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Returns true if the chunk has been requested and its scenes have finished spawning, including chunks whose
+    /// scenes failed to spawn
+    pub fn is_loaded(&self, coord: ChunkCoord) -> bool {
+        self.chunks
+            .get(&coord)
+            .map_or(false, |chunk| chunk.pending_instances == 0)
+    }
+
+    /// Iterates the chunks that have been requested, including chunks that are still spawning
+    pub fn iter_chunks(&self) -> impl Iterator<Item = ChunkCoord> + '_ {
+        self.chunks.keys().cloned()
+    }
+}
+
+/// Returns the chunks that should be loaded and the loaded chunks that should be unloaded for the given anchors. Chunks
+/// to load are sorted by their distance to the closest anchor, so nearby chunks are requested first.
+fn update_chunks(
+    grid: &ChunkGrid,
+    anchors: &[(ChunkCoord, ChunkAnchor)],
+    loaded: impl Iterator<Item = ChunkCoord>,
+    is_loaded: impl Fn(ChunkCoord) -> bool,
+) -> (Vec<ChunkCoord>, Vec<ChunkCoord>) {
+    let closest_distance = |coord: ChunkCoord, radius: fn(&ChunkAnchor) -> u32| {
+        anchors
+            .iter()
+            .filter(|(center, anchor)| coord.distance(*center) <= radius(anchor))
+            .map(|(center, _)| coord.distance(*center))
+            .min()
+    };
+
+    let mut to_load = Vec::new();
+    for (center, anchor) in anchors.iter() {
+        for coord in grid.chunks_in_radius(*center, anchor.load_radius) {
+            if !is_loaded(coord) && !to_load.contains(&coord) {
+                to_load.push(coord);
+            }
+        }
+    }
+    to_load.sort_by_key(|coord| closest_distance(*coord, |anchor| anchor.load_radius));
+
+    let to_unload = loaded
+        .filter(|coord| {
+            closest_distance(*coord, |anchor| {
+                anchor.unload_radius.max(anchor.load_radius)
+            })
+            .is_none()
+        })
+        .collect();
+    (to_load, to_unload)
+}
+
+/// State used by the chunk streaming system
+#[derive(Default)]
+pub struct ChunkStreamingState {
+    scene_spawn_event_reader: EventReader<SceneSpawnEvent>,
+    instance_chunks: HashMap<InstanceId, ChunkCoord>,
+}
+
+pub fn chunk_streaming_system(
+    mut state: Local<ChunkStreamingState>,
+    mut commands: Commands,
+    mut streamer: ResMut<ChunkStreamer>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    asset_server: Res<AssetServer>,
+    scene_spawn_events: Res<Events<SceneSpawnEvent>>,
+    mut chunk_events: ResMut<Events<ChunkEvent>>,
+    mut anchor_query: Query<(&ChunkAnchor, &Transform)>,
+) {
+    let state = &mut *state;
+    for event in state.scene_spawn_event_reader.iter(&scene_spawn_events) {
+        let (instance_id, failed) = match event {
+            SceneSpawnEvent::Spawned { instance_id } => (instance_id, false),
+            SceneSpawnEvent::Failed { instance_id }
+            | SceneSpawnEvent::Cancelled { instance_id } => (instance_id, true),
+            SceneSpawnEvent::Progress { .. } => continue,
+        };
+        let coord = match state.instance_chunks.get(instance_id) {
+            Some(coord) => *coord,
+            None => continue,
+        };
+        if !failed {
+            if let Some(entities) = scene_spawner.iter_instance_entities(*instance_id) {
+                for entity in entities {
+                    commands.insert_one(entity, Chunk { coord });
+                }
+            }
+        }
+        if let Some(chunk) = streamer.chunks.get_mut(&coord) {
+            chunk.pending_instances -= 1;
+            if failed {
+                chunk.failed_instances += 1;
+            }
+            if chunk.pending_instances == 0 {
+                chunk_events.send(if chunk.failed_instances == 0 {
+                    ChunkEvent::Loaded(coord)
+                } else {
+                    ChunkEvent::LoadedWithErrors(coord)
+                });
+            }
+        }
+    }
+
+    let anchors = anchor_query
+        .iter()
+        .iter()
+        .map(|(anchor, transform)| {
+            let position = Vec3::from(transform.value.w_axis().truncate());
+            (streamer.grid.chunk_coord(position), *anchor)
+        })
+        .collect::<Vec<_>>();
+    let (to_load, to_unload) = update_chunks(
+        &streamer.grid,
+        &anchors,
+        streamer.chunks.keys().cloned(),
+        |coord| streamer.chunks.contains_key(&coord),
+    );
+
+    for coord in to_unload {
+        let chunk = streamer.chunks.remove(&coord).unwrap();
+        for instance_id in chunk.instances {
+            state.instance_chunks.remove(&instance_id);
+            if scene_spawner.instance_is_ready(instance_id) {
+                scene_spawner.despawn_instance(instance_id);
+            } else {
+                scene_spawner.cancel_instance(instance_id);
+            }
+        }
+        chunk_events.send(ChunkEvent::Unloaded(coord));
+    }
+
+    for coord in to_load {
+        let mut instances = Vec::new();
+        for provider in streamer.providers.iter() {
+            if let Some(scene_handle) = provider.provide(coord, &asset_server) {
+                let instance_id =
+                    scene_spawner.instance_streamed(scene_handle, streamer.spawn_budget);
+                state.instance_chunks.insert(instance_id, coord);
+                instances.push(instance_id);
+            }
+        }
+        if instances.is_empty() {
+            chunk_events.send(ChunkEvent::Loaded(coord));
+        }
+        streamer.chunks.insert(
+            coord,
+            LoadedChunk {
+                pending_instances: instances.len(),
+                failed_instances: 0,
+                instances,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chunk_streaming_system, update_chunks, Chunk, ChunkAnchor, ChunkCoord, ChunkEvent,
+        ChunkGrid, ChunkStreamer,
+    };
+    use crate::{scene_spawner_system, Entity, Scene, SceneSpawnEvent, SceneSpawner};
+    use bevy_app::Events;
+    use bevy_asset::{AssetEvent, AssetServer, Assets};
+    use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem, Resources, Schedule, World};
+    use bevy_math::Vec3;
+    use bevy_property::{DynamicProperties, Properties};
+    use bevy_transform::prelude::Transform;
+    use bevy_type_registry::TypeRegistry;
+    use std::time::Duration;
+
+    #[derive(Properties, Default)]
+    struct Health {
+        value: f32,
+    }
+
+    fn scene_entity(entity: u32, type_name: &str) -> Entity {
+        let mut properties = DynamicProperties::map();
+        properties.type_name = type_name.to_string();
+        properties.set("value", entity as f32);
+        Entity {
+            entity,
+            components: vec![properties],
+        }
+    }
+
+    #[test]
+    fn chunk_streaming() {
+        let grid = ChunkGrid::new(Vec3::new(10.0, 10.0, 0.0));
+        assert_eq!(
+            grid.chunk_coord(Vec3::new(-5.0, 25.0, 100.0)),
+            ChunkCoord::new(-1, 2, 0)
+        );
+        assert_eq!(grid.chunks_in_radius(ChunkCoord::default(), 1).len(), 9);
+
+        let anchor = ChunkAnchor {
+            load_radius: 1,
+            unload_radius: 2,
+        };
+        let (to_load, _) = update_chunks(
+            &grid,
+            &[(ChunkCoord::default(), anchor)],
+            Vec::new().into_iter(),
+            |_| false,
+        );
+        assert_eq!(to_load.len(), 9);
+        assert_eq!(to_load[0], ChunkCoord::default());
+
+        // after moving one chunk to the right, chunks on the left are kept until the anchor moves further
+        let loaded = to_load;
+        let moved = [(ChunkCoord::new(1, 0, 0), anchor)];
+        let (to_load, to_unload) = update_chunks(&grid, &moved, loaded.iter().cloned(), |coord| {
+            loaded.contains(&coord)
+        });
+        assert_eq!(to_load.len(), 3);
+        assert!(to_unload.is_empty());
+
+        let moved = [(ChunkCoord::new(2, 0, 0), anchor)];
+        let (_, to_unload) = update_chunks(&grid, &moved, loaded.iter().cloned(), |coord| {
+            loaded.contains(&coord)
+        });
+        assert_eq!(to_unload.len(), 3);
+        assert!(to_unload.iter().all(|coord| coord.x == -1));
+    }
+
+    #[test]
+    fn chunk_with_failing_scene() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let type_registry = TypeRegistry::default();
+        type_registry
+            .component
+            .write()
+            .unwrap()
+            .register::<Health>();
+        resources.insert(type_registry);
+
+        let health = std::any::type_name::<Health>();
+        let mut scenes = Assets::<Scene>::default();
+        let scene = scenes.add(Scene {
+            entities: vec![scene_entity(0, health)],
+            ..Default::default()
+        });
+        // the second entity can't be spawned because its component isn't registered
+        let broken_scene = scenes.add(Scene {
+            entities: vec![scene_entity(0, health), scene_entity(1, "Unregistered")],
+            ..Default::default()
+        });
+        resources.insert(scenes);
+
+        // without a budget, one entity of each instance is spawned per frame
+        let mut streamer = ChunkStreamer {
+            spawn_budget: Duration::from_secs(0),
+            ..Default::default()
+        };
+        streamer
+            .add_provider(move |_coord, _asset_server: &AssetServer| Some(scene))
+            .add_provider(move |_coord, _asset_server: &AssetServer| Some(broken_scene));
+        resources.insert(streamer);
+        resources.insert(SceneSpawner::default());
+        resources.insert(AssetServer::default());
+        resources.insert(Events::<AssetEvent<Scene>>::default());
+        resources.insert(Events::<SceneSpawnEvent>::default());
+        resources.insert(Events::<ChunkEvent>::default());
+        world.spawn((ChunkAnchor::new(0), Transform::identity()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_stage("scene");
+        schedule.add_system_to_stage("update", chunk_streaming_system.system());
+        schedule.add_system_to_stage("scene", scene_spawner_system.thread_local_system());
+        schedule.initialize(&mut resources);
+        let mut chunk_event_reader = resources.get::<Events<ChunkEvent>>().unwrap().get_reader();
+        for _ in 0..3 {
+            schedule.run(&mut world, &mut resources);
+        }
+
+        // the chunk finishes loading even though one of its scenes failed to spawn
+        let coord = ChunkCoord::default();
+        assert!(resources.get::<ChunkStreamer>().unwrap().is_loaded(coord));
+        let chunk_events = resources.get::<Events<ChunkEvent>>().unwrap();
+        assert_eq!(
+            chunk_event_reader
+                .iter(&chunk_events)
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![ChunkEvent::LoadedWithErrors(coord)]
+        );
+        assert_eq!(world.query::<&Chunk>().iter().count(), 1);
+    }
+}
@@ -1,19 +1,27 @@
+pub mod binary;
+mod chunk_streaming;
 mod loaded_scenes;
 mod scene;
 mod scene_spawner;
 pub mod serde;
+mod snapshot;
 
+pub use chunk_streaming::*;
 pub use loaded_scenes::*;
 pub use scene::*;
 pub use scene_spawner::*;
+pub use snapshot::*;
 
 pub mod prelude {
-    pub use crate::{Scene, SceneSpawner};
+    pub use crate::{
+        Chunk, ChunkAnchor, ChunkCoord, ChunkEvent, ChunkGrid, ChunkStreamer, InstanceId, Scene,
+        SceneInstance, SceneSpawnEvent, SceneSpawner, WorldSnapshot,
+    };
 }
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
-use bevy_ecs::IntoThreadLocalSystem;
+use bevy_asset::{AddAsset, AssetServer};
+use bevy_ecs::{IntoQuerySystem, IntoThreadLocalSystem};
 
 #[derive(Default)]
 pub struct ScenePlugin;
@@ -24,8 +32,16 @@ impl Plugin for ScenePlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<Scene>()
             .add_asset_loader::<Scene, SceneLoader>()
+            .add_event::<SceneSpawnEvent>()
+            .add_event::<ChunkEvent>()
             .init_resource::<SceneSpawner>()
+            .init_resource::<ChunkStreamer>()
             .add_stage_after(stage::EVENT_UPDATE, SCENE_STAGE)
-            .add_system_to_stage(SCENE_STAGE, scene_spawner_system.thread_local_system());
+            .add_system_to_stage(SCENE_STAGE, scene_spawner_system.thread_local_system())
+            .add_system(chunk_streaming_system.system());
+    }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<AssetServer>();
     }
 }
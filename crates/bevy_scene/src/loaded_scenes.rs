@@ -1,9 +1,13 @@
-use crate::{serde::SceneDeserializer, Scene};
+use crate::{
+    binary::{deserialize_binary, BINARY_SCENE_EXTENSION},
+    serde::SceneDeserializer,
+    Scene,
+};
 use anyhow::Result;
 use bevy_asset::AssetLoader;
 use bevy_ecs::{FromResources, Resources};
 use bevy_property::PropertyTypeRegistry;
-use bevy_type_registry::TypeRegistry;
+use bevy_type_registry::{ComponentRegistry, TypeRegistry};
 use serde::de::DeserializeSeed;
 use std::{
     path::Path,
@@ -12,6 +16,7 @@ use std::{
 
 pub struct SceneLoader {
     property_type_registry: Arc<RwLock<PropertyTypeRegistry>>,
+    component_registry: Arc<RwLock<ComponentRegistry>>,
 }
 
 impl FromResources for SceneLoader {
@@ -19,23 +24,29 @@ impl FromResources for SceneLoader {
         let type_registry = resources.get::<TypeRegistry>().unwrap();
         SceneLoader {
             property_type_registry: type_registry.property.clone(),
+            component_registry: type_registry.component.clone(),
         }
     }
 }
 
 impl AssetLoader<Scene> for SceneLoader {
-    fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> Result<Scene> {
+    fn from_bytes(&self, asset_path: &Path, bytes: Vec<u8>) -> Result<Scene> {
         let registry = self.property_type_registry.read().unwrap();
-        let mut deserializer = bevy_ron::de::Deserializer::from_bytes(&bytes)?;
         let scene_deserializer = SceneDeserializer {
             property_type_registry: &registry,
         };
-        let scene = scene_deserializer.deserialize(&mut deserializer)?;
+        let mut scene = if asset_path.extension() == Some(BINARY_SCENE_EXTENSION.as_ref()) {
+            deserialize_binary(scene_deserializer, &bytes)?
+        } else {
+            let mut deserializer = bevy_ron::de::Deserializer::from_bytes(&bytes)?;
+            scene_deserializer.deserialize(&mut deserializer)?
+        };
+        scene.migrate(&self.component_registry.read().unwrap())?;
         Ok(scene)
     }
 
     fn extensions(&self) -> &[&str] {
-        static EXTENSIONS: &[&str] = &["scn"];
+        static EXTENSIONS: &[&str] = &["scn", BINARY_SCENE_EXTENSION];
         EXTENSIONS
     }
 }
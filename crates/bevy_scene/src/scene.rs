@@ -1,12 +1,19 @@
-use crate::serde::SceneSerializer;
+use crate::{
+    binary::{serialize_binary, BinaryError},
+    serde::SceneSerializer,
+};
 use anyhow::Result;
 use bevy_ecs::World;
 use bevy_property::{DynamicProperties, PropertyTypeRegistry};
-use bevy_type_registry::ComponentRegistry;
+use bevy_type_registry::{ComponentMigrationError, ComponentRegistry};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Default)]
 pub struct Scene {
+    /// The versions of the scene's components, by type name. Components that aren't listed are at version 0, see
+    /// [ComponentRegistry::add_migration].
+    pub component_versions: BTreeMap<String, u32>,
     pub entities: Vec<Entity>,
 }
 
@@ -28,7 +35,11 @@ impl Scene {
                     })
                 }
                 for type_info in archetype.types() {
-                    if let Some(component_registration) = component_registry.get(&type_info.id()) {
+                    let type_id = match type_info.id().type_id() {
+                        Some(type_id) => type_id,
+                        None => continue,
+                    };
+                    if let Some(component_registration) = component_registry.get(&type_id) {
                         let properties =
                             component_registration.get_component_properties(&archetype, index);
 
@@ -40,13 +51,70 @@ impl Scene {
             scene.entities.extend(entities.drain(..));
         }
 
+        scene.set_component_versions(component_registry);
         scene
     }
 
+    /// Migrates components that were saved with an older version to their current version. Components that aren't
+    /// registered are left unchanged.
+    pub fn migrate(
+        &mut self,
+        component_registry: &ComponentRegistry,
+    ) -> Result<(), ComponentMigrationError> {
+        let versions = self
+            .component_versions
+            .iter()
+            .filter_map(|(type_name, version)| {
+                component_registry
+                    .get_with_name(type_name)
+                    .map(|registration| (registration.ty, *version))
+            })
+            .collect::<HashMap<_, _>>();
+        for entity in self.entities.iter_mut() {
+            for component in entity.components.iter_mut() {
+                if let Some(registration) = component_registry.get_with_name(&component.type_name) {
+                    let version = versions.get(&registration.ty).cloned().unwrap_or(0);
+                    registration.migrate(component, version)?;
+                }
+            }
+        }
+
+        self.set_component_versions(component_registry);
+        Ok(())
+    }
+
+    /// Sets the versions of the registered components in the scene to their current version
+    fn set_component_versions(&mut self, component_registry: &ComponentRegistry) {
+        self.component_versions
+            .retain(|type_name, _| component_registry.get_with_name(type_name).is_none());
+        for entity in self.entities.iter() {
+            for component in entity.components.iter() {
+                if let Some(registration) = component_registry.get_with_name(&component.type_name) {
+                    if registration.version() > 0 {
+                        self.component_versions
+                            .insert(registration.long_name.to_string(), registration.version());
+                    }
+                }
+            }
+        }
+    }
+
     // TODO: move to AssetSaver when it is implemented
-    pub fn serialize_ron(&self, registry: &PropertyTypeRegistry) -> Result<String, bevy_ron::Error> {
+    pub fn serialize_ron(
+        &self,
+        registry: &PropertyTypeRegistry,
+    ) -> Result<String, bevy_ron::Error> {
         serialize_ron(SceneSerializer::new(self, registry))
     }
+
+    /// Serializes the scene to the binary format used by `.scnb` files, which loads much faster than RON
+    pub fn serialize_binary(
+        &self,
+        registry: &PropertyTypeRegistry,
+        compress: bool,
+    ) -> Result<Vec<u8>, BinaryError> {
+        serialize_binary(SceneSerializer::new(self, registry), compress)
+    }
 }
 
 pub fn serialize_ron<S>(serialize: S) -> Result<String, bevy_ron::Error>
@@ -62,3 +130,103 @@ where
     serialize.serialize(&mut ron_serializer)?;
     Ok(String::from_utf8(buf).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Scene;
+    use crate::{binary::deserialize_binary, serde::SceneDeserializer};
+    use bevy_ecs::World;
+    use bevy_property::{Properties, PropertiesVal, PropertyTypeRegistry};
+    use bevy_type_registry::ComponentRegistry;
+    use serde::de::DeserializeSeed;
+
+    #[derive(Properties, Default)]
+    struct Health {
+        value: f32,
+    }
+
+    fn deserialize(ron: &str, registry: &PropertyTypeRegistry) -> Scene {
+        let mut deserializer = bevy_ron::de::Deserializer::from_str(ron).unwrap();
+        SceneDeserializer {
+            property_type_registry: registry,
+        }
+        .deserialize(&mut deserializer)
+        .unwrap()
+    }
+
+    #[test]
+    fn scene_versions() {
+        let mut property_registry = PropertyTypeRegistry::default();
+        property_registry.register::<Health>();
+        let mut component_registry = ComponentRegistry::default();
+        component_registry.register::<Health>();
+        // version 1 renamed "hp" to "value"
+        component_registry.add_migration::<Health>(0, |properties| {
+            if properties.rename("hp", "value") {
+                Ok(())
+            } else {
+                Err("missing hp".to_string())
+            }
+        });
+
+        // scenes saved before format versions are a list of entities, and their components are at version 0
+        let mut scene = deserialize(
+            r#"[(entity: 1, components: [{"type": "Health", "map": {"hp": 3.0}}])]"#,
+            &property_registry,
+        );
+        scene.migrate(&component_registry).unwrap();
+        assert_eq!(
+            scene.entities[0].components[0].prop_val::<f32>("value"),
+            Some(&3.0)
+        );
+        assert_eq!(scene.component_versions.values().next(), Some(&1));
+
+        // saved scenes record the current component versions, so they aren't migrated again
+        let mut world = World::default();
+        world.spawn((Health { value: 5.0 },));
+        let scene = Scene::from_world(&world, &component_registry);
+        let ron = scene.serialize_ron(&property_registry).unwrap();
+        let mut scene = deserialize(&ron, &property_registry);
+        scene.migrate(&component_registry).unwrap();
+        assert_eq!(
+            scene.entities[0].components[0].prop_val::<f32>("value"),
+            Some(&5.0)
+        );
+    }
+
+    #[test]
+    fn binary_scene() {
+        let mut property_registry = PropertyTypeRegistry::default();
+        property_registry.register::<Health>();
+        let mut component_registry = ComponentRegistry::default();
+        component_registry.register::<Health>();
+        let mut world = World::default();
+        for value in 0..100 {
+            world.spawn((Health {
+                value: value as f32,
+            },));
+        }
+        let scene = Scene::from_world(&world, &component_registry);
+
+        let ron = scene.serialize_ron(&property_registry).unwrap();
+        let binary = scene.serialize_binary(&property_registry, false).unwrap();
+        let compressed = scene.serialize_binary(&property_registry, true).unwrap();
+        assert!(binary.len() < ron.len() / 2);
+        assert!(compressed.len() < binary.len());
+
+        for bytes in [binary, compressed].iter() {
+            let scene = deserialize_binary(
+                SceneDeserializer {
+                    property_type_registry: &property_registry,
+                },
+                bytes,
+            )
+            .unwrap();
+            assert_eq!(scene.entities.len(), 100);
+            assert_eq!(
+                scene.entities[42].components[0].prop_val::<f32>("value"),
+                Some(&42.0)
+            );
+        }
+    }
+}
@@ -1,18 +1,76 @@
 use crate::Scene;
 use bevy_app::prelude::*;
 use bevy_asset::{AssetEvent, Assets, Handle};
-use bevy_ecs::{Resources, World};
-use bevy_type_registry::TypeRegistry;
-use std::collections::{HashMap, HashSet};
+use bevy_ecs::{Entity, Resources, World};
+use bevy_transform::prelude::{Children, Parent};
+use bevy_type_registry::{ComponentRegistry, ComponentValidationError, TypeRegistry};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use uuid::Uuid;
 
 struct InstanceInfo {
-    entity_map: HashMap<u32, bevy_ecs::Entity>,
+    scene_handle: Handle<Scene>,
+    /// The entity the instance was spawned under with [SceneSpawner::spawn_as_child]
+    parent: Option<Entity>,
+    entity_map: HashMap<u32, Entity>,
 }
 
+impl InstanceInfo {
+    fn new(scene_handle: Handle<Scene>) -> Self {
+        InstanceInfo {
+            scene_handle,
+            parent: None,
+            entity_map: HashMap::default(),
+        }
+    }
+}
+
+/// A component added to every entity spawned by a scene instance. It identifies the instance the entity came from,
+/// ex: to despawn or reload the instance from a system that only has the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneInstance {
+    pub instance_id: InstanceId,
+    /// The scene the instance was spawned from
+    pub scene: Handle<Scene>,
+}
+
+/// An instance that is spawned over multiple frames, see [SceneSpawner::instance_streamed]
+struct StreamedInstance {
+    scene_handle: Handle<Scene>,
+    instance_id: InstanceId,
+    budget: Duration,
+    /// The index of the next scene entity to spawn, which is also the number of spawned entities
+    next_entity: usize,
+    /// The number of entities in the scene, or 0 while the scene is loading
+    total_entities: usize,
+    instance_info: InstanceInfo,
+}
+
+/// Reports the state of instances spawned with [SceneSpawner::instance_streamed]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneSpawnEvent {
+    /// Some of the instance's entities were spawned this frame
+    Progress {
+        instance_id: InstanceId,
+        spawned: usize,
+        total: usize,
+    },
+    /// All of the instance's entities have been spawned
+    Spawned { instance_id: InstanceId },
+    /// The instance was cancelled with [SceneSpawner::cancel_instance] before it finished spawning. Its partially
+    /// spawned entities have been despawned.
+    Cancelled { instance_id: InstanceId },
+    /// One of the instance's entities could not be spawned. Its partially spawned entities have been despawned, and
+    /// the error is returned by [SceneSpawner::spawn_streamed_scenes].
+    Failed { instance_id: InstanceId },
+}
+
+/// Identifies a single spawned instance of a [Scene]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct InstanceId(Uuid);
+pub struct InstanceId(Uuid);
 
 impl InstanceId {
     pub fn new() -> Self {
@@ -26,8 +84,13 @@ pub struct SceneSpawner {
     spawned_scenes: HashMap<Handle<Scene>, Vec<InstanceId>>,
     spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: EventReader<AssetEvent<Scene>>,
-    scenes_to_spawn: Vec<Handle<Scene>>,
+    scenes_to_spawn: Vec<(Handle<Scene>, InstanceId)>,
     scenes_to_load: Vec<Handle<Scene>>,
+    scenes_to_despawn: Vec<InstanceId>,
+    scenes_to_reload: Vec<InstanceId>,
+    scenes_with_parent: Vec<(InstanceId, Entity)>,
+    streamed_instances: Vec<StreamedInstance>,
+    scenes_to_cancel: Vec<InstanceId>,
 }
 
 #[derive(Error, Debug)]
@@ -36,11 +99,177 @@ pub enum SceneSpawnError {
     UnregisteredComponent { type_name: String },
     #[error("Scene does not exist. Perhaps it is still loading?")]
     NonExistentScene { handle: Handle<Scene> },
+    #[error("Scene contains an invalid component.")]
+    InvalidComponent(#[from] ComponentValidationError),
 }
 
 impl SceneSpawner {
-    pub fn instance(&mut self, scene_handle: Handle<Scene>) {
-        self.scenes_to_spawn.push(scene_handle);
+    /// Queues a new instance of the given scene to be spawned. The returned [InstanceId] can be used to look up
+    /// the instance's entities once it has been spawned.
+    pub fn instance(&mut self, scene_handle: Handle<Scene>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.scenes_to_spawn.push((scene_handle, instance_id));
+        instance_id
+    }
+
+    /// Queues a new instance of the given scene to be spawned. Scene entities that don't have a parent are added
+    /// as children of `parent`.
+    pub fn spawn_as_child(&mut self, scene_handle: Handle<Scene>, parent: Entity) -> InstanceId {
+        let instance_id = self.instance(scene_handle);
+        self.scenes_with_parent.push((instance_id, parent));
+        instance_id
+    }
+
+    /// Queues a new instance of the given scene to be spawned over multiple frames, spending at most about `budget` on it
+    /// each frame. This keeps large scenes from stalling the app. [SceneSpawnEvent]s report the progress of the
+    /// instance, which is ready once [SceneSpawnEvent::Spawned] is sent.
+    pub fn instance_streamed(
+        &mut self,
+        scene_handle: Handle<Scene>,
+        budget: Duration,
+    ) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.streamed_instances.push(StreamedInstance {
+            scene_handle,
+            instance_id,
+            budget,
+            next_entity: 0,
+            total_entities: 0,
+            instance_info: InstanceInfo::new(scene_handle),
+        });
+        instance_id
+    }
+
+    /// Queues an instance that hasn't finished spawning to be cancelled. Entities it already spawned are despawned.
+    /// Instances that have finished spawning are left alone, use [SceneSpawner::despawn_instance] for those.
+    pub fn cancel_instance(&mut self, instance_id: InstanceId) {
+        self.scenes_to_cancel.push(instance_id);
+    }
+
+    /// Returns the number of spawned entities and the total number of entities of an instance that is being
+    /// streamed, or `None` if the instance isn't being streamed
+    pub fn streamed_instance_progress(&self, instance_id: InstanceId) -> Option<(usize, usize)> {
+        let streamed_instance = self
+            .streamed_instances
+            .iter()
+            .find(|streamed_instance| streamed_instance.instance_id == instance_id)?;
+        Some((
+            streamed_instance.next_entity,
+            streamed_instance.total_entities,
+        ))
+    }
+
+    /// Queues the entities of the given scene instance to be despawned.
+    pub fn despawn_instance(&mut self, instance_id: InstanceId) {
+        self.scenes_to_despawn.push(instance_id);
+    }
+
+    /// Despawns the entities of the given scene instance right away. Instances spawned with
+    /// [SceneSpawner::spawn_as_child] are also removed from their parent's [Children]. Does nothing if the instance
+    /// hasn't been spawned.
+    pub fn despawn_instance_sync(&mut self, world: &mut World, instance_id: InstanceId) {
+        if let Some(instance) = self.spawned_instances.remove(&instance_id) {
+            Self::despawn_instance_entities(world, &instance);
+            if let Some(instances) = self.spawned_scenes.get_mut(&instance.scene_handle) {
+                instances.retain(|id| *id != instance_id);
+            }
+        }
+    }
+
+    fn despawn_instance_entities(world: &mut World, instance: &InstanceInfo) {
+        if let Some(parent) = instance.parent {
+            if let Ok(mut children) = world.get_mut::<Children>(parent) {
+                children
+                    .0
+                    .retain(|child| !instance.entity_map.values().any(|entity| entity == child));
+            }
+        }
+        for entity in instance.entity_map.values() {
+            // the entity may have already been despawned by someone else
+            let _ = world.despawn(*entity);
+        }
+    }
+
+    /// Queues a spawned instance to be reloaded. Its entities are despawned and the scene is spawned again under the
+    /// same [InstanceId], which resets anything that changed since the instance was spawned.
+    pub fn reload_instance(&mut self, instance_id: InstanceId) {
+        self.scenes_to_reload.push(instance_id);
+    }
+
+    /// Despawns the entities of a spawned instance and spawns its scene again under the same [InstanceId]. Instances
+    /// spawned with [SceneSpawner::spawn_as_child] keep their parent. Does nothing if the instance hasn't been spawned.
+    /// If the scene can't be spawned, the instance keeps its old entities and the error is returned.
+    pub fn reload_instance_sync(
+        &mut self,
+        world: &mut World,
+        resources: &Resources,
+        instance_id: InstanceId,
+    ) -> Result<(), SceneSpawnError> {
+        let instance_info = match self.spawned_instances.get_mut(&instance_id) {
+            Some(instance_info) => instance_info,
+            None => return Ok(()),
+        };
+        // the scene is spawned before the old entities are despawned, so a failed reload leaves the instance as it was
+        let mut reloaded = InstanceInfo::new(instance_info.scene_handle);
+        reloaded.parent = instance_info.parent;
+        if let Err(err) = Self::load_internal(
+            world,
+            resources,
+            instance_info.scene_handle,
+            Some((instance_id, &mut reloaded)),
+        ) {
+            Self::despawn_instance_entities(world, &reloaded);
+            return Err(err);
+        }
+        Self::despawn_instance_entities(world, instance_info);
+        *instance_info = reloaded;
+        if let Some(parent) = instance_info.parent {
+            self.scenes_with_parent.push((instance_id, parent));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the given instance has been spawned
+    pub fn instance_is_ready(&self, instance_id: InstanceId) -> bool {
+        self.spawned_instances.contains_key(&instance_id)
+    }
+
+    /// Returns the scene the given instance was spawned from, or `None` if the instance hasn't been spawned yet
+    pub fn get_instance_scene(&self, instance_id: InstanceId) -> Option<Handle<Scene>> {
+        self.spawned_instances
+            .get(&instance_id)
+            .map(|instance| instance.scene_handle)
+    }
+
+    /// Iterates the spawned instances of the given scene
+    pub fn iter_scene_instances(
+        &self,
+        scene_handle: Handle<Scene>,
+    ) -> impl Iterator<Item = InstanceId> + '_ {
+        self.spawned_scenes
+            .get(&scene_handle)
+            .into_iter()
+            .flat_map(|instances| instances.iter().cloned())
+    }
+
+    /// Returns the instance that spawned `entity`, or `None` if it wasn't spawned by a scene instance. Systems can
+    /// also read the entity's [SceneInstance] component.
+    pub fn get_entity_instance(&self, world: &World, entity: Entity) -> Option<InstanceId> {
+        world
+            .get::<SceneInstance>(entity)
+            .ok()
+            .map(|scene_instance| scene_instance.instance_id)
+            .filter(|instance_id| self.spawned_instances.contains_key(instance_id))
+    }
+
+    /// Iterates the entities of the given scene instance. Returns `None` if the instance hasn't been spawned yet.
+    pub fn iter_instance_entities(
+        &self,
+        instance_id: InstanceId,
+    ) -> Option<impl Iterator<Item = Entity> + '_> {
+        self.spawned_instances
+            .get(&instance_id)
+            .map(|instance| instance.entity_map.values().cloned())
     }
 
     pub fn load(&mut self, scene_handle: Handle<Scene>) {
@@ -63,12 +292,26 @@ impl SceneSpawner {
         world: &mut World,
         resources: &Resources,
         scene_handle: Handle<Scene>,
-    ) -> Result<(), SceneSpawnError> {
+    ) -> Result<InstanceId, SceneSpawnError> {
         let instance_id = InstanceId::new();
-        let mut instance_info = InstanceInfo {
-            entity_map: HashMap::default(),
-        };
-        Self::load_internal(world, resources, scene_handle, Some(&mut instance_info))?;
+        self.spawn_sync_internal(world, resources, scene_handle, instance_id)?;
+        Ok(instance_id)
+    }
+
+    fn spawn_sync_internal(
+        &mut self,
+        world: &mut World,
+        resources: &Resources,
+        scene_handle: Handle<Scene>,
+        instance_id: InstanceId,
+    ) -> Result<(), SceneSpawnError> {
+        let mut instance_info = InstanceInfo::new(scene_handle);
+        Self::load_internal(
+            world,
+            resources,
+            scene_handle,
+            Some((instance_id, &mut instance_info)),
+        )?;
         self.spawned_instances.insert(instance_id, instance_info);
         let spawned = self
             .spawned_scenes
@@ -82,7 +325,7 @@ impl SceneSpawner {
         world: &mut World,
         resources: &Resources,
         scene_handle: Handle<Scene>,
-        mut instance_info: Option<&mut InstanceInfo>,
+        instance: Option<(InstanceId, &mut InstanceInfo)>,
     ) -> Result<(), SceneSpawnError> {
         let type_registry = resources.get::<TypeRegistry>().unwrap();
         let component_registry = type_registry.component.read().unwrap();
@@ -93,40 +336,110 @@ impl SceneSpawner {
                 handle: scene_handle,
             })?;
 
+        match instance {
+            Some((instance_id, instance_info)) => {
+                Self::write_scene(
+                    world,
+                    resources,
+                    &component_registry,
+                    scene,
+                    Some(&mut instance_info.entity_map),
+                )?;
+                let scene_instance = SceneInstance {
+                    instance_id,
+                    scene: scene_handle,
+                };
+                for entity in instance_info.entity_map.values() {
+                    world.insert_one(*entity, scene_instance).unwrap();
+                }
+                Ok(())
+            }
+            None => Self::write_scene(world, resources, &component_registry, scene, None),
+        }
+    }
+
+    /// Writes the entities in `scene` to `world`. If `entity_map` is provided, scene entities are mapped to new
+    /// world entities (allocating them as needed). Otherwise scene entity ids are used directly.
+    pub(crate) fn write_scene(
+        world: &mut World,
+        resources: &Resources,
+        component_registry: &ComponentRegistry,
+        scene: &Scene,
+        mut entity_map: Option<&mut HashMap<u32, Entity>>,
+    ) -> Result<(), SceneSpawnError> {
         for scene_entity in scene.entities.iter() {
-            let entity = if let Some(ref mut instance_info) = instance_info {
-                *instance_info
-                    .entity_map
-                    .entry(scene_entity.entity)
-                    .or_insert_with(|| bevy_ecs::Entity::new())
-            } else {
-                bevy_ecs::Entity::from_id(scene_entity.entity)
-            };
-            if world.contains(entity) {
-                for component in scene_entity.components.iter() {
-                    let component_registration = component_registry
-                        .get_with_name(&component.type_name)
-                        .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
-                            type_name: component.type_name.to_string(),
-                        })?;
-                    if component.type_name != "Camera" {
-                        component_registration.apply_component_to_entity(world, entity, component);
+            Self::write_scene_entity(
+                world,
+                resources,
+                component_registry,
+                scene_entity,
+                entity_map.as_deref_mut(),
+            )?;
+        }
+
+        if let Some(entity_map) = entity_map {
+            Self::map_hierarchy_entities(world, entity_map);
+        }
+        Ok(())
+    }
+
+    /// Parents and children in a scene refer to scene entities. Once all of them have been spawned, this points them
+    /// to the world entities the scene entities were mapped to.
+    fn map_hierarchy_entities(world: &mut World, entity_map: &HashMap<u32, Entity>) {
+        for entity in entity_map.values() {
+            if let Ok(mut parent) = world.get_mut::<Parent>(*entity) {
+                if let Some(mapped_parent) = entity_map.get(&parent.0.id()) {
+                    parent.0 = *mapped_parent;
+                }
+            }
+            if let Ok(mut children) = world.get_mut::<Children>(*entity) {
+                for child in children.iter_mut() {
+                    if let Some(mapped_child) = entity_map.get(&child.id()) {
+                        *child = *mapped_child;
                     }
                 }
+            }
+        }
+    }
 
-            } else {
-                world.spawn_as_entity(entity, (1,));
-                for component in scene_entity.components.iter() {
-                    let component_registration = component_registry
-                        .get_with_name(&component.type_name)
-                        .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
-                            type_name: component.type_name.to_string(),
-                        })?;
-                    component_registration.add_component_to_entity(world, resources, entity, component);
+    fn write_scene_entity(
+        world: &mut World,
+        resources: &Resources,
+        component_registry: &ComponentRegistry,
+        scene_entity: &crate::Entity,
+        entity_map: Option<&mut HashMap<u32, Entity>>,
+    ) -> Result<Entity, SceneSpawnError> {
+        let entity = if let Some(entity_map) = entity_map {
+            *entity_map
+                .entry(scene_entity.entity)
+                .or_insert_with(Entity::new)
+        } else {
+            Entity::from_id(scene_entity.entity)
+        };
+        if world.contains(entity) {
+            for component in scene_entity.components.iter() {
+                let component_registration = component_registry
+                    .get_with_name(&component.type_name)
+                    .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
+                        type_name: component.type_name.to_string(),
+                    })?;
+                if component.type_name != "Camera" {
+                    component_registration.apply_component_to_entity(world, entity, component)?;
                 }
             }
+        } else {
+            world.spawn_as_entity(entity, (1,));
+            for component in scene_entity.components.iter() {
+                let component_registration = component_registry
+                    .get_with_name(&component.type_name)
+                    .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
+                        type_name: component.type_name.to_string(),
+                    })?;
+                component_registration
+                    .add_component_to_entity(world, resources, entity, component)?;
+            }
         }
-        Ok(())
+        Ok(entity)
     }
 
     pub fn update_spawned_scenes(
@@ -139,7 +452,12 @@ impl SceneSpawner {
             if let Some(spawned_instances) = self.spawned_scenes.get(scene_handle) {
                 for instance_id in spawned_instances.iter() {
                     if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
-                        Self::load_internal(world, resources, *scene_handle, Some(instance_info))?;
+                        Self::load_internal(
+                            world,
+                            resources,
+                            *scene_handle,
+                            Some((*instance_id, instance_info)),
+                        )?;
                     }
                 }
             }
@@ -175,11 +493,11 @@ impl SceneSpawner {
     ) -> Result<(), SceneSpawnError> {
         let scenes_to_spawn = self.scenes_to_spawn.drain(..).collect::<Vec<_>>();
         let mut non_existent_scenes = Vec::new();
-        for scene_handle in scenes_to_spawn {
-            match self.spawn_sync(world, resources, scene_handle) {
+        for (scene_handle, instance_id) in scenes_to_spawn {
+            match self.spawn_sync_internal(world, resources, scene_handle, instance_id) {
                 Ok(_) => {}
                 Err(SceneSpawnError::NonExistentScene { .. }) => {
-                    non_existent_scenes.push(scene_handle)
+                    non_existent_scenes.push((scene_handle, instance_id))
                 }
                 Err(err) => return Err(err),
             }
@@ -188,11 +506,197 @@ impl SceneSpawner {
         self.scenes_to_spawn = non_existent_scenes;
         Ok(())
     }
+
+    /// Spawns the next entities of streamed instances, within each instance's budget. Instances whose scene hasn't
+    /// loaded yet are kept until it has. An instance that fails to spawn is despawned and reported with
+    /// [SceneSpawnEvent::Failed], the other instances keep streaming and the first error is returned afterwards.
+    pub fn spawn_streamed_scenes(
+        &mut self,
+        world: &mut World,
+        resources: &Resources,
+        spawn_events: &mut Events<SceneSpawnEvent>,
+    ) -> Result<(), SceneSpawnError> {
+        if self.streamed_instances.is_empty() {
+            return Ok(());
+        }
+
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+        let component_registry = type_registry.component.read().unwrap();
+        let scenes = resources.get::<Assets<Scene>>().unwrap();
+        let mut result = Ok(());
+        let streamed_instances = self.streamed_instances.drain(..).collect::<Vec<_>>();
+        for mut streamed_instance in streamed_instances {
+            let scene = match scenes.get(&streamed_instance.scene_handle) {
+                Some(scene) => scene,
+                None => {
+                    self.streamed_instances.push(streamed_instance);
+                    continue;
+                }
+            };
+
+            streamed_instance.total_entities = scene.entities.len();
+            let instance_id = streamed_instance.instance_id;
+            if let Err(err) = Self::spawn_streamed_entities(
+                world,
+                resources,
+                &component_registry,
+                scene,
+                &mut streamed_instance,
+            ) {
+                Self::despawn_instance_entities(world, &streamed_instance.instance_info);
+                self.scenes_with_parent.retain(|(id, _)| *id != instance_id);
+                spawn_events.send(SceneSpawnEvent::Failed { instance_id });
+                if result.is_ok() {
+                    result = Err(err);
+                }
+                continue;
+            }
+
+            spawn_events.send(SceneSpawnEvent::Progress {
+                instance_id,
+                spawned: streamed_instance.next_entity,
+                total: scene.entities.len(),
+            });
+            if streamed_instance.next_entity < scene.entities.len() {
+                self.streamed_instances.push(streamed_instance);
+            } else {
+                // a parent can be spawned in a later frame than its children, so the hierarchy is only mapped once
+                // the whole instance has been spawned
+                Self::map_hierarchy_entities(world, &streamed_instance.instance_info.entity_map);
+                self.spawned_instances
+                    .insert(instance_id, streamed_instance.instance_info);
+                self.spawned_scenes
+                    .entry(streamed_instance.scene_handle)
+                    .or_default()
+                    .push(instance_id);
+                spawn_events.send(SceneSpawnEvent::Spawned { instance_id });
+            }
+        }
+
+        result
+    }
+
+    /// Spawns the next entities of a streamed instance until its budget for this frame is used up
+    fn spawn_streamed_entities(
+        world: &mut World,
+        resources: &Resources,
+        component_registry: &ComponentRegistry,
+        scene: &Scene,
+        streamed_instance: &mut StreamedInstance,
+    ) -> Result<(), SceneSpawnError> {
+        // at least one entity is spawned every frame, so instances always make progress
+        let start = Instant::now();
+        let mut spawned_this_frame = 0;
+        while streamed_instance.next_entity < scene.entities.len()
+            && (spawned_this_frame == 0 || start.elapsed() < streamed_instance.budget)
+        {
+            let entity = Self::write_scene_entity(
+                world,
+                resources,
+                component_registry,
+                &scene.entities[streamed_instance.next_entity],
+                Some(&mut streamed_instance.instance_info.entity_map),
+            )?;
+            world
+                .insert_one(
+                    entity,
+                    SceneInstance {
+                        instance_id: streamed_instance.instance_id,
+                        scene: streamed_instance.scene_handle,
+                    },
+                )
+                .unwrap();
+            streamed_instance.next_entity += 1;
+            spawned_this_frame += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the instances queued with [SceneSpawner::cancel_instance] that haven't finished spawning
+    pub fn cancel_queued_scenes(
+        &mut self,
+        world: &mut World,
+        spawn_events: &mut Events<SceneSpawnEvent>,
+    ) {
+        let scenes_to_cancel = self.scenes_to_cancel.drain(..).collect::<Vec<_>>();
+        for instance_id in scenes_to_cancel {
+            let mut cancelled = false;
+            self.scenes_to_spawn.retain(|(_, id)| {
+                let pending = *id == instance_id;
+                cancelled |= pending;
+                !pending
+            });
+            if let Some(index) = self
+                .streamed_instances
+                .iter()
+                .position(|streamed_instance| streamed_instance.instance_id == instance_id)
+            {
+                let streamed_instance = self.streamed_instances.remove(index);
+                for entity in streamed_instance.instance_info.entity_map.values() {
+                    // the entity may have already been despawned by someone else
+                    let _ = world.despawn(*entity);
+                }
+                cancelled = true;
+            }
+
+            if cancelled {
+                self.scenes_with_parent.retain(|(id, _)| *id != instance_id);
+                spawn_events.send(SceneSpawnEvent::Cancelled { instance_id });
+            }
+        }
+    }
+
+    pub fn despawn_queued_scenes(&mut self, world: &mut World) {
+        let scenes_to_despawn = self.scenes_to_despawn.drain(..).collect::<Vec<_>>();
+        for instance_id in scenes_to_despawn {
+            self.despawn_instance_sync(world, instance_id);
+        }
+    }
+
+    /// Reloads the instances queued with [SceneSpawner::reload_instance]. Instances that fail to reload keep their old
+    /// entities, the other instances are still reloaded and the first error is returned afterwards.
+    pub fn reload_queued_scenes(
+        &mut self,
+        world: &mut World,
+        resources: &Resources,
+    ) -> Result<(), SceneSpawnError> {
+        let mut result = Ok(());
+        let scenes_to_reload = self.scenes_to_reload.drain(..).collect::<Vec<_>>();
+        for instance_id in scenes_to_reload {
+            if let Err(err) = self.reload_instance_sync(world, resources, instance_id) {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    /// Parents the root entities of newly spawned instances that were queued with [SceneSpawner::spawn_as_child]
+    pub fn set_scene_instance_parents(&mut self, world: &mut World) {
+        let scenes_with_parent = self.scenes_with_parent.drain(..).collect::<Vec<_>>();
+        for (instance_id, parent) in scenes_with_parent {
+            if let Some(instance) = self.spawned_instances.get_mut(&instance_id) {
+                instance.parent = Some(parent);
+                for entity in instance.entity_map.values() {
+                    if world.get::<Parent>(*entity).is_err() {
+                        // the entity may have already been despawned by someone else
+                        let _ = world.insert_one(*entity, Parent(parent));
+                    }
+                }
+            } else {
+                // the instance hasn't been spawned yet. try again next frame
+                self.scenes_with_parent.push((instance_id, parent));
+            }
+        }
+    }
 }
 
 pub fn scene_spawner_system(world: &mut World, resources: &mut Resources) {
     let mut scene_spawner = resources.get_mut::<SceneSpawner>().unwrap();
     let scene_asset_events = resources.get::<Events<AssetEvent<Scene>>>().unwrap();
+    let mut scene_spawn_events = resources.get_mut::<Events<SceneSpawnEvent>>().unwrap();
 
     let mut updated_spawned_scenes = Vec::new();
     for event in scene_spawner
@@ -209,10 +713,326 @@ pub fn scene_spawner_system(world: &mut World, resources: &mut Resources) {
         }
     }
 
-
+    scene_spawner.cancel_queued_scenes(world, &mut scene_spawn_events);
+    scene_spawner.despawn_queued_scenes(world);
     scene_spawner.load_queued_scenes(world, resources).unwrap();
     scene_spawner.spawn_queued_scenes(world, resources).unwrap();
+    // instances that fail to reload keep their old entities
+    if let Err(err) = scene_spawner.reload_queued_scenes(world, resources) {
+        log::error!("Failed to reload scene instance: {:?}", err);
+    }
+    // failed instances have been despawned and reported with SceneSpawnEvent::Failed, the others keep streaming
+    if let Err(err) = scene_spawner.spawn_streamed_scenes(world, resources, &mut scene_spawn_events)
+    {
+        log::error!("Failed to spawn streamed scene instance: {:?}", err);
+    }
+    scene_spawner.set_scene_instance_parents(world);
     scene_spawner
         .update_spawned_scenes(world, resources, &updated_spawned_scenes)
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SceneInstance, SceneSpawnError, SceneSpawnEvent, SceneSpawner};
+    use crate::{Entity, Scene};
+    use bevy_app::Events;
+    use bevy_asset::Assets;
+    use bevy_ecs::{Resources, World};
+    use bevy_property::{DynamicProperties, Properties};
+    use bevy_transform::prelude::{Children, Parent};
+    use bevy_type_registry::TypeRegistry;
+    use std::time::Duration;
+
+    #[derive(Properties, Default)]
+    struct Health {
+        value: f32,
+    }
+
+    fn scene_entity(entity: u32, type_name: &str) -> Entity {
+        let mut properties = DynamicProperties::map();
+        properties.type_name = type_name.to_string();
+        properties.set("value", entity as f32);
+        Entity {
+            entity,
+            components: vec![properties],
+        }
+    }
+
+    fn setup() -> Resources {
+        let mut resources = Resources::default();
+        let type_registry = TypeRegistry::default();
+        type_registry
+            .component
+            .write()
+            .unwrap()
+            .register::<Health>();
+        resources.insert(type_registry);
+        resources
+    }
+
+    #[test]
+    fn spawn_and_despawn_child_instance() {
+        let mut world = World::default();
+        let mut resources = setup();
+        let health = std::any::type_name::<Health>();
+        let mut scenes = Assets::<Scene>::default();
+        let scene = scenes.add(Scene {
+            entities: (0..2).map(|entity| scene_entity(entity, health)).collect(),
+            ..Default::default()
+        });
+        resources.insert(scenes);
+
+        let other_child = world.spawn((0,));
+        let parent = world.spawn((Children::with(&[other_child]),));
+        let mut spawner = SceneSpawner::default();
+        let instance = spawner.spawn_as_child(scene, parent);
+        spawner.spawn_queued_scenes(&mut world, &resources).unwrap();
+        spawner.set_scene_instance_parents(&mut world);
+        let entities = world
+            .query::<(bevy_ecs::Entity, &Parent)>()
+            .iter()
+            .map(|(entity, scene_parent)| {
+                assert_eq!(scene_parent.0, parent);
+                entity
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(entities.len(), 2);
+        // parent_update_system adds the instance's roots to the parent's children
+        world
+            .get_mut::<Children>(parent)
+            .unwrap()
+            .0
+            .extend(entities.iter().cloned());
+
+        spawner.despawn_instance_sync(&mut world, instance);
+        assert!(!spawner.instance_is_ready(instance));
+        assert!(entities.iter().all(|entity| !world.contains(*entity)));
+        assert_eq!(
+            world.get::<Children>(parent).unwrap().0.as_slice(),
+            &[other_child]
+        );
+    }
+
+    #[test]
+    fn spawn_streamed_scenes() {
+        let mut world = World::default();
+        let mut resources = setup();
+        let health = std::any::type_name::<Health>();
+        let mut scenes = Assets::<Scene>::default();
+        let scene = scenes.add(Scene {
+            entities: (0..3).map(|entity| scene_entity(entity, health)).collect(),
+            ..Default::default()
+        });
+        // the second entity can't be spawned because its component isn't registered
+        let broken_scene = scenes.add(Scene {
+            entities: vec![scene_entity(0, health), scene_entity(1, "Unregistered")],
+            ..Default::default()
+        });
+        resources.insert(scenes);
+
+        let mut spawner = SceneSpawner::default();
+        let mut events = Events::<SceneSpawnEvent>::default();
+        let mut reader = events.get_reader();
+        // without a budget, one entity is spawned per frame
+        let broken = spawner.instance_streamed(broken_scene, Duration::from_secs(0));
+        let instance = spawner.instance_streamed(scene, Duration::from_secs(0));
+
+        spawner
+            .spawn_streamed_scenes(&mut world, &resources, &mut events)
+            .unwrap();
+        assert_eq!(spawner.streamed_instance_progress(instance), Some((1, 3)));
+        assert_eq!(spawner.streamed_instance_progress(broken), Some((1, 2)));
+
+        let result = spawner.spawn_streamed_scenes(&mut world, &resources, &mut events);
+        assert!(matches!(
+            result,
+            Err(SceneSpawnError::UnregisteredComponent { .. })
+        ));
+        assert_eq!(spawner.streamed_instance_progress(broken), None);
+        assert_eq!(spawner.streamed_instance_progress(instance), Some((2, 3)));
+
+        spawner
+            .spawn_streamed_scenes(&mut world, &resources, &mut events)
+            .unwrap();
+        assert_eq!(spawner.streamed_instance_progress(instance), None);
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![
+                SceneSpawnEvent::Progress {
+                    instance_id: broken,
+                    spawned: 1,
+                    total: 2,
+                },
+                SceneSpawnEvent::Progress {
+                    instance_id: instance,
+                    spawned: 1,
+                    total: 3,
+                },
+                SceneSpawnEvent::Failed {
+                    instance_id: broken
+                },
+                SceneSpawnEvent::Progress {
+                    instance_id: instance,
+                    spawned: 2,
+                    total: 3,
+                },
+                SceneSpawnEvent::Progress {
+                    instance_id: instance,
+                    spawned: 3,
+                    total: 3,
+                },
+                SceneSpawnEvent::Spawned {
+                    instance_id: instance
+                },
+            ]
+        );
+
+        // the partially spawned entities of the failed instance were despawned
+        let instances = world
+            .query::<&SceneInstance>()
+            .iter()
+            .map(|scene_instance| scene_instance.instance_id)
+            .collect::<Vec<_>>();
+        assert_eq!(instances, vec![instance; 3]);
+        assert_eq!(world.query::<&Health>().iter().count(), 3);
+    }
+
+    #[test]
+    fn stream_hierarchy() {
+        let mut world = World::default();
+        let mut resources = setup();
+        {
+            let type_registry = resources.get::<TypeRegistry>().unwrap();
+            let mut component_registry = type_registry.component.write().unwrap();
+            component_registry.register::<Parent>();
+            component_registry.register::<Children>();
+        }
+        let scene_parent = |entity| Parent(bevy_ecs::Entity::from_id(entity)).to_dynamic();
+        let scene_children =
+            |entity| Children::with(&[bevy_ecs::Entity::from_id(entity)]).to_dynamic();
+        let mut scenes = Assets::<Scene>::default();
+        // the leaf comes first, so it is spawned two frames before the root
+        let scene = scenes.add(Scene {
+            entities: vec![
+                Entity {
+                    entity: 2,
+                    components: vec![scene_parent(1)],
+                },
+                Entity {
+                    entity: 1,
+                    components: vec![scene_parent(0), scene_children(2)],
+                },
+                Entity {
+                    entity: 0,
+                    components: vec![scene_children(1)],
+                },
+            ],
+            ..Default::default()
+        });
+        resources.insert(scenes);
+
+        let mut spawner = SceneSpawner::default();
+        let mut events = Events::<SceneSpawnEvent>::default();
+        let instance = spawner.instance_streamed(scene, Duration::from_secs(0));
+        for _ in 0..3 {
+            spawner
+                .spawn_streamed_scenes(&mut world, &resources, &mut events)
+                .unwrap();
+        }
+        assert!(spawner.instance_is_ready(instance));
+
+        let root = world
+            .query::<(bevy_ecs::Entity, &Children)>()
+            .iter()
+            .map(|(entity, _children)| entity)
+            .find(|entity| world.get::<Parent>(*entity).is_err())
+            .unwrap();
+        let middle = world.get::<Children>(root).unwrap()[0];
+        let leaf = world.get::<Children>(middle).unwrap()[0];
+        assert_eq!(world.get::<Parent>(middle).unwrap().0, root);
+        assert_eq!(world.get::<Parent>(leaf).unwrap().0, middle);
+        for entity in [root, middle, leaf].iter() {
+            assert_eq!(spawner.get_entity_instance(&world, *entity), Some(instance));
+        }
+    }
+
+    #[test]
+    fn instance_lookup_and_reload() {
+        let mut world = World::default();
+        let mut resources = setup();
+        let health = std::any::type_name::<Health>();
+        let mut scenes = Assets::<Scene>::default();
+        let scene = scenes.add(Scene {
+            entities: (0..2).map(|entity| scene_entity(entity, health)).collect(),
+            ..Default::default()
+        });
+        resources.insert(scenes);
+
+        let mut spawner = SceneSpawner::default();
+        let instance = spawner.instance(scene);
+        assert_eq!(spawner.get_instance_scene(instance), None);
+        spawner.spawn_queued_scenes(&mut world, &resources).unwrap();
+        assert_eq!(spawner.get_instance_scene(instance), Some(scene));
+        assert_eq!(
+            spawner.iter_scene_instances(scene).collect::<Vec<_>>(),
+            vec![instance]
+        );
+
+        let entities = spawner
+            .iter_instance_entities(instance)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(entities.len(), 2);
+        for entity in entities.iter() {
+            assert_eq!(
+                *world.get::<SceneInstance>(*entity).unwrap(),
+                SceneInstance {
+                    instance_id: instance,
+                    scene,
+                }
+            );
+            assert_eq!(spawner.get_entity_instance(&world, *entity), Some(instance));
+        }
+        let other_entity = world.spawn((Health::default(),));
+        assert_eq!(spawner.get_entity_instance(&world, other_entity), None);
+
+        // reloading replaces the instance's entities with fresh ones, and keeps its id
+        world.get_mut::<Health>(entities[0]).unwrap().value = 10.0;
+        spawner
+            .reload_instance_sync(&mut world, &resources, instance)
+            .unwrap();
+        assert!(entities.iter().all(|entity| !world.contains(*entity)));
+        let reloaded = spawner
+            .iter_instance_entities(instance)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(reloaded.len(), 2);
+        for entity in reloaded.iter() {
+            assert_eq!(spawner.get_entity_instance(&world, *entity), Some(instance));
+            assert!(world.get::<Health>(*entity).unwrap().value < 2.0);
+        }
+
+        // an instance that fails to reload keeps its entities
+        resources
+            .get_mut::<Assets<Scene>>()
+            .unwrap()
+            .get_mut(&scene)
+            .unwrap()
+            .entities
+            .push(scene_entity(2, "Unregistered"));
+        spawner.reload_instance(instance);
+        assert!(spawner
+            .reload_queued_scenes(&mut world, &resources)
+            .is_err());
+        assert_eq!(
+            spawner
+                .iter_instance_entities(instance)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            reloaded
+        );
+        assert!(reloaded.iter().all(|entity| world.contains(*entity)));
+        assert_eq!(world.query::<&SceneInstance>().iter().count(), 2);
+    }
+}
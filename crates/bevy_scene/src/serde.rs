@@ -26,8 +26,35 @@ impl<'a> Serialize for SceneSerializer<'a> {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_seq(Some(self.scene.entities.len()))?;
-        for entity in self.scene.entities.iter() {
+        let mut state = serializer.serialize_struct(SCENE_STRUCT, 3)?;
+        state.serialize_field(SCENE_FIELD_VERSION, &SCENE_FORMAT_VERSION)?;
+        state.serialize_field(
+            SCENE_FIELD_COMPONENT_VERSIONS,
+            &self.scene.component_versions,
+        )?;
+        state.serialize_field(
+            SCENE_FIELD_ENTITIES,
+            &EntitiesSerializer {
+                entities: &self.scene.entities,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+pub struct EntitiesSerializer<'a> {
+    pub entities: &'a [Entity],
+    pub registry: &'a PropertyTypeRegistry,
+}
+
+impl<'a> Serialize for EntitiesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities.iter() {
             state.serialize_element(&EntitySerializer {
                 entity,
                 registry: self.registry,
@@ -92,12 +119,115 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
     where
         D: serde::Deserializer<'de>,
     {
-        let mut scene = Scene::default();
-        scene.entities = deserializer.deserialize_seq(SceneEntitySeqVisiter {
+        deserializer.deserialize_any(SceneVisiter {
+            property_type_registry: self.property_type_registry,
+        })
+    }
+}
+
+/// The version of the scene format written by [SceneSerializer]. Version 0 scenes, which were saved before scenes had a
+/// version, are a list of entities.
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+pub const SCENE_STRUCT: &str = "Scene";
+pub const SCENE_FIELD_VERSION: &str = "version";
+pub const SCENE_FIELD_COMPONENT_VERSIONS: &str = "component_versions";
+pub const SCENE_FIELD_ENTITIES: &str = "entities";
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum SceneField {
+    Version,
+    ComponentVersions,
+    Entities,
+}
+
+struct SceneVisiter<'a> {
+    pub property_type_registry: &'a PropertyTypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for SceneVisiter<'a> {
+    type Value = Scene;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("scene")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let entities = SceneEntitySeqVisiter {
             property_type_registry: self.property_type_registry,
-        })?;
+        }
+        .visit_seq(seq)?;
+        Ok(Scene {
+            entities,
+            ..Default::default()
+        })
+    }
 
-        Ok(scene)
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut version = None;
+        let mut component_versions = None;
+        let mut entities = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                SceneField::Version => {
+                    if version.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_VERSION));
+                    }
+                    version = Some(map.next_value::<u32>()?);
+                }
+                SceneField::ComponentVersions => {
+                    if component_versions.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_COMPONENT_VERSIONS));
+                    }
+                    component_versions = Some(map.next_value()?);
+                }
+                SceneField::Entities => {
+                    if entities.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_ENTITIES));
+                    }
+                    entities = Some(map.next_value_seed(SceneEntitySeqDeserializer {
+                        property_type_registry: self.property_type_registry,
+                    })?);
+                }
+            }
+        }
+
+        let version = version.ok_or_else(|| Error::missing_field(SCENE_FIELD_VERSION))?;
+        if version > SCENE_FORMAT_VERSION {
+            return Err(Error::custom(format!(
+                "scene format version {} is newer than the supported version {}",
+                version, SCENE_FORMAT_VERSION
+            )));
+        }
+
+        Ok(Scene {
+            component_versions: component_versions.unwrap_or_default(),
+            entities: entities.ok_or_else(|| Error::missing_field(SCENE_FIELD_ENTITIES))?,
+        })
+    }
+}
+
+struct SceneEntitySeqDeserializer<'a> {
+    pub property_type_registry: &'a PropertyTypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SceneEntitySeqDeserializer<'a> {
+    type Value = Vec<Entity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SceneEntitySeqVisiter {
+            property_type_registry: self.property_type_registry,
+        })
     }
 }
 
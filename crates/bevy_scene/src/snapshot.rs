@@ -0,0 +1,170 @@
+use crate::{
+    binary::{self, BinaryError},
+    serde::SceneDeserializer,
+    Scene, SceneSpawnError, SceneSpawner,
+};
+use bevy_ecs::{Entity, Resources, World};
+use bevy_type_registry::{ComponentMigrationError, TypeRegistry};
+use serde::de::DeserializeSeed;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("The TypeRegistry resource is missing.")]
+    MissingTypeRegistry,
+    #[error("Failed to serialize or deserialize the snapshot.")]
+    Ron(#[from] bevy_ron::Error),
+    #[error("Failed to serialize or deserialize the binary snapshot.")]
+    Binary(#[from] BinaryError),
+    #[error("Failed to migrate the snapshot.")]
+    Migration(#[from] ComponentMigrationError),
+    #[error("Failed to spawn the snapshot.")]
+    Spawn(#[from] SceneSpawnError),
+}
+
+/// Saves and restores every registered component in a [World]. Useful for implementing save games.
+pub trait WorldSnapshot {
+    /// Serializes all entities and their registered components to a byte buffer.
+    /// Components that aren't registered in the [TypeRegistry] are not included.
+    fn snapshot(&self, type_registry: &TypeRegistry) -> Result<Vec<u8>, SnapshotError>;
+
+    /// Like [WorldSnapshot::snapshot], but uses the binary scene format, which is smaller and much faster to load.
+    /// `compress` shrinks the snapshot further with deflate.
+    fn snapshot_binary(
+        &self,
+        type_registry: &TypeRegistry,
+        compress: bool,
+    ) -> Result<Vec<u8>, SnapshotError>;
+
+    /// Spawns the entities in a snapshot created by [WorldSnapshot::snapshot]. Snapshot entities are given new ids
+    /// so they never collide with entities that already exist in the world. The returned map goes from
+    /// snapshot entity ids to the newly spawned entities, and [Parent](bevy_transform::prelude::Parent) and
+    /// [Children](bevy_transform::prelude::Children) are remapped to them. Components saved with an older version are
+    /// migrated first. Both RON and binary snapshots can be loaded.
+    fn load_snapshot(
+        &mut self,
+        resources: &Resources,
+        bytes: &[u8],
+    ) -> Result<HashMap<u32, Entity>, SnapshotError>;
+}
+
+impl WorldSnapshot for World {
+    fn snapshot(&self, type_registry: &TypeRegistry) -> Result<Vec<u8>, SnapshotError> {
+        let scene = Scene::from_world(self, &type_registry.component.read().unwrap());
+        let ron = scene.serialize_ron(&type_registry.property.read().unwrap())?;
+        Ok(ron.into_bytes())
+    }
+
+    fn snapshot_binary(
+        &self,
+        type_registry: &TypeRegistry,
+        compress: bool,
+    ) -> Result<Vec<u8>, SnapshotError> {
+        let scene = Scene::from_world(self, &type_registry.component.read().unwrap());
+        let bytes = scene.serialize_binary(&type_registry.property.read().unwrap(), compress)?;
+        Ok(bytes)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        resources: &Resources,
+        bytes: &[u8],
+    ) -> Result<HashMap<u32, Entity>, SnapshotError> {
+        let type_registry = resources
+            .get::<TypeRegistry>()
+            .ok_or(SnapshotError::MissingTypeRegistry)?;
+        let mut scene = {
+            let property_type_registry = type_registry.property.read().unwrap();
+            let scene_deserializer = SceneDeserializer {
+                property_type_registry: &property_type_registry,
+            };
+            if binary::is_binary(bytes) {
+                binary::deserialize_binary(scene_deserializer, bytes)?
+            } else {
+                let mut deserializer = bevy_ron::de::Deserializer::from_bytes(bytes)?;
+                scene_deserializer.deserialize(&mut deserializer)?
+            }
+        };
+        scene.migrate(&type_registry.component.read().unwrap())?;
+
+        let mut entity_map = HashMap::new();
+        SceneSpawner::write_scene(
+            self,
+            resources,
+            &type_registry.component.read().unwrap(),
+            &scene,
+            Some(&mut entity_map),
+        )?;
+        Ok(entity_map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SnapshotError, WorldSnapshot};
+    use bevy_ecs::{Entity, Resources, World};
+    use bevy_property::Properties;
+    use bevy_transform::prelude::{Children, Parent};
+    use bevy_type_registry::TypeRegistry;
+
+    #[derive(Properties, Default, Debug, PartialEq)]
+    struct Health {
+        value: f32,
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut resources = Resources::default();
+        let type_registry = TypeRegistry::default();
+        {
+            let mut component_registry = type_registry.component.write().unwrap();
+            component_registry.register::<Health>();
+            component_registry.register::<Parent>();
+            component_registry.register::<Children>();
+            let mut property_registry = type_registry.property.write().unwrap();
+            property_registry.register::<Health>();
+            property_registry.register::<Parent>();
+            property_registry.register::<Children>();
+            property_registry.register::<Entity>();
+        }
+
+        let mut world = World::default();
+        let parent = world.spawn((Health { value: 1.0 },));
+        let child = world.spawn((Health { value: 2.0 }, Parent(parent)));
+        world.insert_one(parent, Children::with(&[child])).unwrap();
+        let snapshots = vec![
+            world.snapshot(&type_registry).unwrap(),
+            world.snapshot_binary(&type_registry, true).unwrap(),
+        ];
+        resources.insert(type_registry);
+
+        for snapshot in snapshots {
+            // loading into the same world spawns new entities, whose hierarchy refers to each other
+            let entity_map = world.load_snapshot(&resources, &snapshot).unwrap();
+            let loaded_parent = entity_map[&parent.id()];
+            let loaded_child = entity_map[&child.id()];
+            assert_ne!(loaded_parent, parent);
+            assert_eq!(
+                *world.get::<Health>(loaded_parent).unwrap(),
+                Health { value: 1.0 }
+            );
+            assert_eq!(
+                *world.get::<Health>(loaded_child).unwrap(),
+                Health { value: 2.0 }
+            );
+            assert_eq!(world.get::<Parent>(loaded_child).unwrap().0, loaded_parent);
+            assert_eq!(
+                world.get::<Children>(loaded_parent).unwrap().as_slice(),
+                &[loaded_child]
+            );
+        }
+    }
+
+    #[test]
+    fn load_snapshot_without_type_registry() {
+        let mut world = World::default();
+        let result = world.load_snapshot(&Resources::default(), &[]);
+        assert!(matches!(result, Err(SnapshotError::MissingTypeRegistry)));
+    }
+}
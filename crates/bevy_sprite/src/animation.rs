@@ -0,0 +1,239 @@
+use crate::TextureAtlasSprite;
+use bevy_core::Time;
+use bevy_ecs::{Query, Res};
+use std::ops::Range;
+
+/// What a [SpriteSheetAnimation] does after the last frame of its clip
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationMode {
+    /// Stops on the last frame
+    Once,
+    /// Starts over from the first frame
+    Loop,
+    /// Plays the clip backwards to the first frame, then forwards again
+    PingPong,
+}
+
+/// A texture atlas index and how long it is shown, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+    pub index: u32,
+    pub duration: f32,
+}
+
+/// A sequence of texture atlas frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: AnimationMode,
+}
+
+impl AnimationClip {
+    pub fn new(frames: Vec<AnimationFrame>, mode: AnimationMode) -> Self {
+        AnimationClip { frames, mode }
+    }
+
+    /// Returns a looping clip that shows each atlas index in `range` for `frame_duration` seconds
+    pub fn from_range(range: Range<u32>, frame_duration: f32) -> Self {
+        AnimationClip {
+            frames: range
+                .map(|index| AnimationFrame {
+                    index,
+                    duration: frame_duration,
+                })
+                .collect(),
+            mode: AnimationMode::Loop,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Plays an [AnimationClip] on the [TextureAtlasSprite] of its entity. Frames with a duration of zero or less are
+/// shown until the animation is restarted.
+#[derive(Debug, Clone)]
+pub struct SpriteSheetAnimation {
+    pub clip: AnimationClip,
+    /// Scales the passage of time. Negative values are treated as zero.
+    pub speed: f32,
+    playing: bool,
+    finished: bool,
+    frame: usize,
+    elapsed: f32,
+    backwards: bool,
+}
+
+impl SpriteSheetAnimation {
+    /// Returns an animation that starts playing `clip` from its first frame
+    pub fn new(clip: AnimationClip) -> Self {
+        SpriteSheetAnimation {
+            clip,
+            speed: 1.0,
+            playing: true,
+            finished: false,
+            frame: 0,
+            elapsed: 0.0,
+            backwards: false,
+        }
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Returns true if the clip is played [AnimationMode::Once] and has reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Goes back to the first frame. Paused animations stay paused.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.finished = false;
+    }
+
+    /// Replaces the clip and starts it from its first frame, unless `clip` is already playing
+    pub fn set_clip(&mut self, clip: AnimationClip) {
+        if self.clip != clip {
+            self.clip = clip;
+            self.restart();
+        }
+    }
+
+    /// Returns the position of the current frame in the clip
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Returns the atlas index of the current frame, or `None` if the clip has no frames
+    pub fn current_index(&self) -> Option<u32> {
+        self.clip.frames.get(self.frame).map(|frame| frame.index)
+    }
+
+    /// Advances the animation by `delta_seconds`
+    pub fn tick(&mut self, delta_seconds: f32) {
+        if !self.playing || self.finished || self.clip.frames.is_empty() {
+            return;
+        }
+
+        // the clip might have been replaced by a shorter one
+        if self.frame >= self.clip.frames.len() {
+            self.restart();
+        }
+
+        self.elapsed += delta_seconds * self.speed.max(0.0);
+        loop {
+            let duration = self.clip.frames[self.frame].duration;
+            if duration <= 0.0 || self.elapsed < duration {
+                break;
+            }
+
+            self.elapsed -= duration;
+            if !self.next_frame() {
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+    }
+
+    /// Moves to the next frame. Returns false if the animation finished instead.
+    fn next_frame(&mut self) -> bool {
+        let last = self.clip.frames.len() - 1;
+        match self.clip.mode {
+            AnimationMode::Once => {
+                if self.frame < last {
+                    self.frame += 1;
+                } else {
+                    self.finished = true;
+                }
+            }
+            AnimationMode::Loop => {
+                self.frame = if self.frame < last { self.frame + 1 } else { 0 };
+            }
+            AnimationMode::PingPong => {
+                if last == 0 {
+                    return true;
+                }
+                if (self.backwards && self.frame == 0) || (!self.backwards && self.frame == last) {
+                    self.backwards = !self.backwards;
+                }
+                if self.backwards {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+        !self.finished
+    }
+}
+
+pub fn sprite_sheet_animation_system(
+    time: Res<Time>,
+    mut query: Query<(&mut SpriteSheetAnimation, &mut TextureAtlasSprite)>,
+) {
+    for (mut animation, mut sprite) in &mut query.iter() {
+        animation.tick(time.delta_seconds);
+        if let Some(index) = animation.current_index() {
+            // avoid mutating sprites whose frame didn't change
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnimationClip, AnimationMode, SpriteSheetAnimation};
+
+    fn frames(mode: AnimationMode, ticks: usize) -> Vec<u32> {
+        let mut animation =
+            SpriteSheetAnimation::new(AnimationClip::from_range(4..7, 0.1).with_mode(mode));
+        (0..ticks)
+            .map(|_| {
+                animation.tick(0.1);
+                animation.current_index().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn animation_modes() {
+        assert_eq!(frames(AnimationMode::Loop, 6), vec![5, 6, 4, 5, 6, 4]);
+        assert_eq!(frames(AnimationMode::Once, 4), vec![5, 6, 6, 6]);
+        assert_eq!(frames(AnimationMode::PingPong, 6), vec![5, 6, 5, 4, 5, 6]);
+    }
+
+    #[test]
+    fn pause_and_speed() {
+        let mut animation = SpriteSheetAnimation::new(AnimationClip::from_range(0..4, 1.0));
+        animation.pause();
+        animation.tick(5.0);
+        assert_eq!(animation.current_frame(), 0);
+
+        animation.play();
+        animation.speed = 2.0;
+        animation.tick(1.5);
+        assert_eq!(animation.current_frame(), 3);
+
+        animation.set_clip(AnimationClip::from_range(0..4, 1.0).with_mode(AnimationMode::Once));
+        assert_eq!(animation.current_frame(), 0);
+        animation.tick(10.0);
+        assert!(animation.is_finished());
+        assert_eq!(animation.current_frame(), 3);
+    }
+}
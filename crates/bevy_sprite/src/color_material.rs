@@ -6,6 +6,10 @@ pub struct ColorMaterial {
     pub color: Color,
     #[shader_def]
     pub texture: Option<Handle<Texture>>,
+    /// A tangent space normal map that shades the sprite under [PointLight2d](crate::PointLight2d)s. It should use a
+    /// linear (not sRGB) texture format.
+    #[shader_def]
+    pub normal_map: Option<Handle<Texture>>,
 }
 
 impl ColorMaterial {
@@ -13,6 +17,7 @@ impl ColorMaterial {
         ColorMaterial {
             color,
             texture: None,
+            normal_map: None,
         }
     }
 
@@ -20,6 +25,7 @@ impl ColorMaterial {
         ColorMaterial {
             color: Color::WHITE,
             texture: Some(texture),
+            normal_map: None,
         }
     }
 
@@ -27,6 +33,7 @@ impl ColorMaterial {
         ColorMaterial {
             color,
             texture: Some(texture),
+            normal_map: None,
         }
     }
 }
@@ -36,6 +43,7 @@ impl Default for ColorMaterial {
         ColorMaterial {
             color: Color::rgb(1.0, 1.0, 1.0),
             texture: None,
+            normal_map: None,
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
-    render::SPRITE_PIPELINE_HANDLE, sprite::Sprite, ColorMaterial, TextureAtlas,
-    TextureAtlasSprite, QUAD_HANDLE, SPRITE_SHEET_PIPELINE_HANDLE,
+    sprite::Sprite, ColorMaterial, TextureAtlas, TextureAtlasSprite, QUAD_HANDLE,
+    SPRITE_SHEET_PIPELINE_HANDLE,
 };
 use bevy_asset::Handle;
 use bevy_ecs::Bundle;
@@ -12,6 +12,9 @@ use bevy_render::{
 };
 use bevy_transform::prelude::{Rotation, Scale, Transform, Translation};
 
+/// The components of a sprite. By default `render_pipelines` is empty: sprites without pipelines of their own are
+/// grouped by depth, material and mesh, and each group is drawn with one instanced draw call by the
+/// [SpriteBatchNode](crate::SpriteBatchNode). Sprites that set `render_pipelines` are drawn on their own.
 #[derive(Bundle)]
 pub struct SpriteComponents {
     pub sprite: Sprite,
@@ -30,24 +33,7 @@ impl Default for SpriteComponents {
     fn default() -> Self {
         Self {
             mesh: QUAD_HANDLE,
-            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
-                SPRITE_PIPELINE_HANDLE,
-                PipelineSpecialization {
-                    dynamic_bindings: vec![
-                        // Transform
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 0,
-                        },
-                        // Sprite
-                        DynamicBinding {
-                            bind_group: 2,
-                            binding: 1,
-                        },
-                    ],
-                    ..Default::default()
-                },
-            )]),
+            render_pipelines: RenderPipelines::from_pipelines(Vec::new()),
             draw: Draw {
                 is_transparent: true,
                 ..Default::default()
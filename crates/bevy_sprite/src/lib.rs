@@ -1,16 +1,24 @@
 pub mod collide_aabb;
 pub mod entity;
 
+mod animation;
 mod color_material;
 mod dynamic_texture_atlas_builder;
+mod light;
+mod parallax;
+mod pixel_camera;
 mod rect;
 mod render;
 mod sprite;
 mod texture_atlas;
 mod texture_atlas_builder;
 
+pub use animation::*;
 pub use color_material::*;
 pub use dynamic_texture_atlas_builder::*;
+pub use light::*;
+pub use parallax::*;
+pub use pixel_camera::*;
 pub use rect::*;
 pub use render::*;
 pub use sprite::*;
@@ -20,7 +28,8 @@ pub use texture_atlas_builder::*;
 pub mod prelude {
     pub use crate::{
         entity::{SpriteComponents, SpriteSheetComponents},
-        ColorMaterial, Sprite, TextureAtlas, TextureAtlasSprite,
+        AmbientLight2d, AnimationClip, AnimationMode, ColorMaterial, Occluder2d, ParallaxLayer,
+        PixelCamera, PointLight2d, Sprite, SpriteSheetAnimation, TextureAtlas, TextureAtlasSprite,
     };
 }
 
@@ -44,7 +53,14 @@ impl Plugin for SpritePlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_asset::<ColorMaterial>()
             .add_asset::<TextureAtlas>()
+            .init_resource::<AmbientLight2d>()
+            .add_system_to_stage(stage::PRE_UPDATE, pixel_camera_setup_system.system())
             .add_system_to_stage(stage::POST_UPDATE, sprite_system.system())
+            // registered after sprite_system, so parallax layers keep their repeated size
+            .add_system_to_stage(stage::POST_UPDATE, parallax_system.system())
+            // registered after parallax_system, so parallax layers are snapped too
+            .add_system_to_stage(stage::POST_UPDATE, pixel_camera_system.system())
+            .add_system_to_stage(stage::POST_UPDATE, sprite_sheet_animation_system.system())
             .add_system_to_stage(
                 stage::POST_UPDATE,
                 asset_shader_defs_system::<ColorMaterial>.system(),
@@ -64,4 +80,8 @@ impl Plugin for SpritePlugin {
         let mut color_materials = resources.get_mut::<Assets<ColorMaterial>>().unwrap();
         color_materials.add_default(ColorMaterial::default());
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<RenderGraph>();
+    }
 }
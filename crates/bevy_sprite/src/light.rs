@@ -0,0 +1,156 @@
+use bevy_core::Byteable;
+use bevy_math::{Mat2, Mat4, Vec2};
+use bevy_render::color::Color;
+
+/// The maximum number of [PointLight2d]s that light sprites. Additional lights are ignored.
+pub const MAX_LIGHTS_2D: usize = 16;
+/// The maximum number of [Occluder2d]s that cast shadows. Additional occluders are ignored.
+pub const MAX_OCCLUDERS_2D: usize = 64;
+
+/// A light that shines on sprites in all directions from its translation.
+///
+/// Once a scene contains at least one [PointLight2d], sprites are only lit by the lights and [AmbientLight2d]. Scenes
+/// without lights are drawn unlit.
+#[derive(Debug, Clone)]
+pub struct PointLight2d {
+    pub color: Color,
+    pub intensity: f32,
+    /// The distance at which the light has faded out completely
+    pub radius: f32,
+    /// How far the light is in front of the sprites. Lower lights shade normal mapped sprites at flatter angles.
+    pub height: f32,
+    pub shadows_enabled: bool,
+    /// The radius of the light's source. Zero casts hard shadows, larger values widen the penumbra of soft shadows.
+    pub source_radius: f32,
+}
+
+impl Default for PointLight2d {
+    fn default() -> Self {
+        PointLight2d {
+            color: Color::WHITE,
+            intensity: 1.0,
+            radius: 300.0,
+            height: 50.0,
+            shadows_enabled: true,
+            source_radius: 0.0,
+        }
+    }
+}
+
+/// A rectangle that blocks the light of [PointLight2d]s, centered on the entity's translation and following its
+/// rotation and scale. Surfaces inside an occluder aren't shadowed by it, so occluders can cover the sprites of walls.
+#[derive(Debug, Clone, Copy)]
+pub struct Occluder2d {
+    pub size: Vec2,
+}
+
+impl Occluder2d {
+    pub fn new(size: Vec2) -> Self {
+        Occluder2d { size }
+    }
+}
+
+/// The light that reaches every sprite once the scene contains [PointLight2d]s
+#[derive(Debug, Clone)]
+pub struct AmbientLight2d {
+    pub color: Color,
+}
+
+impl Default for AmbientLight2d {
+    fn default() -> Self {
+        AmbientLight2d {
+            color: Color::rgb(0.1, 0.1, 0.1),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PointLight2dRaw {
+    /// x, y, height, radius
+    pub position: [f32; 4],
+    /// color * intensity, and the source radius (negative without shadows)
+    pub color: [f32; 4],
+}
+
+unsafe impl Byteable for PointLight2dRaw {}
+
+impl PointLight2dRaw {
+    pub fn from(light: &PointLight2d, transform: &Mat4) -> PointLight2dRaw {
+        let translation = transform.w_axis();
+        let color: [f32; 4] = (light.color * light.intensity).into();
+        PointLight2dRaw {
+            position: [translation.x(), translation.y(), light.height, light.radius],
+            color: [
+                color[0],
+                color[1],
+                color[2],
+                if light.shadows_enabled {
+                    light.source_radius.max(0.0)
+                } else {
+                    -1.0
+                },
+            ],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Occluder2dRaw {
+    /// The columns of the matrix that maps world space offsets from the center into the occluder's space
+    pub inverse_transform: [f32; 4],
+    /// center, and half of the size
+    pub center: [f32; 4],
+}
+
+unsafe impl Byteable for Occluder2dRaw {}
+
+impl Occluder2dRaw {
+    /// Returns `None` if the occluder is scaled to nothing
+    pub fn from(occluder: &Occluder2d, transform: &Mat4) -> Option<Occluder2dRaw> {
+        let (x_axis, y_axis) = (transform.x_axis(), transform.y_axis());
+        let rotation_scale = Mat2::from_cols(
+            Vec2::new(x_axis.x(), x_axis.y()),
+            Vec2::new(y_axis.x(), y_axis.y()),
+        );
+        if rotation_scale.determinant().abs() <= std::f32::EPSILON {
+            return None;
+        }
+
+        let translation = transform.w_axis();
+        Some(Occluder2dRaw {
+            inverse_transform: rotation_scale.inverse().to_cols_array(),
+            center: [
+                translation.x(),
+                translation.y(),
+                occluder.size.x() / 2.0,
+                occluder.size.y() / 2.0,
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Occluder2d, Occluder2dRaw};
+    use bevy_math::{Mat2, Mat4, Quat, Vec2, Vec3};
+
+    #[test]
+    fn occluder_space() {
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::new(2.0, 2.0, 1.0),
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            Vec3::new(10.0, 20.0, 0.0),
+        );
+        let raw = Occluder2dRaw::from(&Occluder2d::new(Vec2::new(4.0, 6.0)), &transform).unwrap();
+        assert_eq!(&raw.center, &[10.0, 20.0, 2.0, 3.0]);
+
+        // the world space point 2 units above the center is 1 unit along the occluder's rotated x axis
+        let local = Mat2::from_cols_array(&raw.inverse_transform) * Vec2::new(0.0, 2.0);
+        assert!((local - Vec2::new(1.0, 0.0)).length() < 1e-5);
+
+        let flattened = Mat4::from_scale(Vec3::new(0.0, 1.0, 1.0));
+        assert!(Occluder2dRaw::from(&Occluder2d::new(Vec2::new(4.0, 6.0)), &flattened).is_none());
+    }
+}
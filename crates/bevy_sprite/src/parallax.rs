@@ -0,0 +1,174 @@
+use crate::{ColorMaterial, Rect, Sprite};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Query, Res};
+use bevy_math::{Vec2, Vec4};
+use bevy_render::{
+    camera::{ActiveCameras, OrthographicProjection},
+    render_graph::base,
+    texture::Texture,
+};
+use bevy_transform::prelude::{Transform, Translation};
+
+/// Moves a sprite relative to the active 2D camera, so it appears further away than the rest of the scene. The sprite
+/// is moved every frame, so its [Translation] only decides its depth.
+#[derive(Debug, Clone)]
+pub struct ParallaxLayer {
+    /// How much of the camera's movement the layer follows on each axis. 0.0 moves like the rest of the scene, 1.0
+    /// stays in place on the screen.
+    pub factor: Vec2,
+    /// The position of the layer while the camera is at the origin
+    pub offset: Vec2,
+    /// Repeats the sprite's texture endlessly along the x axis, covering the camera's view
+    pub repeat_x: bool,
+    /// Repeats the sprite's texture endlessly along the y axis, covering the camera's view
+    pub repeat_y: bool,
+}
+
+impl ParallaxLayer {
+    pub fn new(factor: Vec2) -> Self {
+        ParallaxLayer {
+            factor,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the position of the layer for the given camera position
+    pub fn position(&self, camera_position: Vec2) -> Vec2 {
+        self.offset + camera_position * self.factor
+    }
+}
+
+impl Default for ParallaxLayer {
+    fn default() -> Self {
+        ParallaxLayer {
+            factor: Vec2::new(0.5, 0.5),
+            offset: Vec2::zero(),
+            repeat_x: false,
+            repeat_y: false,
+        }
+    }
+}
+
+/// Covers the visible range `view_min..view_max` of one axis with a texture of `tile_size` repeated from `position`,
+/// where one of the tiles is centered. Returns the center and size of the sprite and the range of texture
+/// coordinates it shows, from the low to the high end of the axis.
+fn repeat_axis(
+    view_min: f32,
+    view_max: f32,
+    position: f32,
+    tile_size: f32,
+) -> (f32, f32, f32, f32) {
+    let tile_start = position - tile_size / 2.0;
+    (
+        (view_min + view_max) / 2.0,
+        view_max - view_min,
+        (view_min - tile_start) / tile_size,
+        (view_max - tile_start) / tile_size,
+    )
+}
+
+/// Positions [ParallaxLayer]s relative to the active 2D camera. It runs after transforms are updated, so it updates
+/// the layers' [Transform]s as well, which keeps them from lagging behind the camera.
+pub fn parallax_system(
+    active_cameras: Res<ActiveCameras>,
+    materials: Res<Assets<ColorMaterial>>,
+    textures: Res<Assets<Texture>>,
+    camera_query: Query<(&OrthographicProjection, &Transform)>,
+    mut layer_query: Query<(
+        &ParallaxLayer,
+        &Handle<ColorMaterial>,
+        &mut Sprite,
+        &mut Translation,
+        &mut Transform,
+    )>,
+) {
+    let camera = match active_cameras.get(base::camera::CAMERA2D) {
+        Some(camera) => camera,
+        None => return,
+    };
+    let (camera_position, projection) = match (
+        camera_query.get::<Transform>(camera),
+        camera_query.get::<OrthographicProjection>(camera),
+    ) {
+        (Ok(transform), Ok(projection)) => {
+            let translation = transform.value.w_axis();
+            (
+                Vec2::new(translation.x(), translation.y()),
+                projection.clone(),
+            )
+        }
+        _ => return,
+    };
+
+    for (layer, material, mut sprite, mut translation, mut transform) in &mut layer_query.iter() {
+        let mut position = layer.position(camera_position);
+        let texture_size = materials
+            .get(material)
+            .and_then(|material| material.texture)
+            .and_then(|texture| textures.get(&texture))
+            .map(|texture| texture.size);
+        if let Some(texture_size) = texture_size {
+            let mut size = texture_size;
+            let mut uv = Rect {
+                min: Vec2::new(0.0, 0.0),
+                max: Vec2::new(1.0, 1.0),
+            };
+            if layer.repeat_x {
+                let (center, width, uv_left, uv_right) = repeat_axis(
+                    camera_position.x() + projection.left,
+                    camera_position.x() + projection.right,
+                    position.x(),
+                    texture_size.x(),
+                );
+                position.set_x(center);
+                size.set_x(width);
+                uv.min.set_x(uv_left);
+                uv.max.set_x(uv_right);
+            }
+            if layer.repeat_y {
+                let (center, height, uv_bottom, uv_top) = repeat_axis(
+                    camera_position.y() + projection.bottom,
+                    camera_position.y() + projection.top,
+                    position.y(),
+                    texture_size.y(),
+                );
+                position.set_y(center);
+                size.set_y(height);
+                // texture coordinates go down, so the top of the sprite is the start of the range
+                uv.min.set_y(1.0 - uv_top);
+                uv.max.set_y(1.0 - uv_bottom);
+            }
+            sprite.size = size;
+            sprite.uv = uv;
+        }
+
+        *translation.x_mut() = position.x();
+        *translation.y_mut() = position.y();
+        transform
+            .value
+            .set_w_axis(Vec4::new(position.x(), position.y(), translation.z(), 1.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{repeat_axis, ParallaxLayer};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn parallax_layers() {
+        let layer = ParallaxLayer {
+            offset: Vec2::new(0.0, 100.0),
+            ..ParallaxLayer::new(Vec2::new(0.5, 1.0))
+        };
+        assert_eq!(
+            layer.position(Vec2::new(200.0, 50.0)),
+            Vec2::new(100.0, 150.0)
+        );
+
+        // a view from -100 to 300 covered by 100 wide tiles, one of which is centered at 50
+        let (center, size, uv_min, uv_max) = repeat_axis(-100.0, 300.0, 50.0, 100.0);
+        assert_eq!((center, size), (100.0, 400.0));
+        assert_eq!((uv_min, uv_max), (-1.0, 3.0));
+    }
+}
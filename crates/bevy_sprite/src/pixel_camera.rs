@@ -0,0 +1,234 @@
+use crate::{entity::SpriteComponents, ColorMaterial, Sprite};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Commands, Entity, Query, ResMut, Without};
+use bevy_math::{Mat4, Quat, Vec2, Vec3, Vec4};
+use bevy_render::{
+    camera::{ActiveCameras, Camera, Letterbox, RenderLayers},
+    prelude::Camera2dComponents,
+    render_graph::base,
+    texture::Texture,
+};
+use bevy_transform::prelude::{Scale, Transform, Translation};
+
+/// Renders a 2D camera at a low resolution and scales it up to the window by whole numbers, so pixel art stays crisp
+/// and every sprite lands on the same grid of virtual pixels.
+///
+/// Add this to a 2D camera. [pixel_camera_setup_system] then renders the camera into a texture of `resolution`, and
+/// adds a display camera that shows the texture with nearest filtering, letterboxed in the window (see [Letterbox]).
+/// The display camera takes the name of the 2D camera, usually `Camera2d`, so systems that look up the active 2D
+/// camera by name find the display camera. The display camera and the texture are on `display_layer`, which the pixel
+/// camera must not see.
+#[derive(Debug, Clone)]
+pub struct PixelCamera {
+    /// The size of the view in virtual pixels. One world unit is one virtual pixel.
+    pub resolution: Vec2,
+    /// Moves the camera by fractions of a virtual pixel by offsetting the upscaled image, so scrolling is smooth while
+    /// sprites stay on the pixel grid. Without smoothing the camera moves a whole virtual pixel at a time.
+    pub smoothing: bool,
+    /// Moves every [Sprite] to the nearest position on the virtual pixel grid
+    pub snap_sprites: bool,
+    /// The render layer of the display camera and its sprite
+    pub display_layer: u8,
+    texture: Option<Handle<Texture>>,
+    display_camera: Option<Entity>,
+    display_sprite: Option<Entity>,
+}
+
+impl PixelCamera {
+    pub fn new(width: f32, height: f32) -> Self {
+        PixelCamera {
+            resolution: Vec2::new(width, height),
+            smoothing: false,
+            snap_sprites: true,
+            display_layer: RenderLayers::TOTAL_LAYERS - 1,
+            texture: None,
+            display_camera: None,
+            display_sprite: None,
+        }
+    }
+
+    pub fn with_smoothing(mut self) -> Self {
+        self.smoothing = true;
+        self
+    }
+
+    /// The texture the camera renders into, once it was set up
+    pub fn texture(&self) -> Option<Handle<Texture>> {
+        self.texture
+    }
+
+    /// The size of the texture. With smoothing it has a border of one virtual pixel, which is cropped by the display
+    /// camera and shown while the image is offset.
+    fn texture_size(&self) -> Vec2 {
+        if self.smoothing {
+            self.resolution + Vec2::new(2.0, 2.0)
+        } else {
+            self.resolution
+        }
+    }
+}
+
+/// Marks the display camera and sprite of a [PixelCamera]
+#[derive(Debug, Clone, Copy)]
+pub struct PixelCameraDisplay;
+
+/// Returns the position of the center of something `size` long, such that its edges are on the pixel grid
+pub fn snap_to_pixel_grid(center: f32, size: f32) -> f32 {
+    (center - size / 2.0).round() + size / 2.0
+}
+
+/// Sets up the render texture and the display of new [PixelCamera]s, and follows changes to their resolution
+pub fn pixel_camera_setup_system(
+    mut commands: Commands,
+    mut active_cameras: ResMut<ActiveCameras>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut query: Query<(Entity, &mut PixelCamera, &mut Camera)>,
+    letterbox_query: Query<&mut Letterbox>,
+) {
+    for (entity, mut pixel_camera, mut camera) in &mut query.iter() {
+        let texture_size = pixel_camera.texture_size();
+        let letterbox = if pixel_camera.smoothing {
+            Letterbox::new(pixel_camera.resolution.x() / pixel_camera.resolution.y())
+        } else {
+            Letterbox::pixel_perfect(pixel_camera.resolution.x(), pixel_camera.resolution.y())
+        };
+
+        if let Some(texture) = pixel_camera.texture {
+            if let Some(texture) = textures.get_mut(&texture) {
+                if texture.size != texture_size {
+                    texture.resize(texture_size);
+                }
+            }
+            if let Some(display_camera) = pixel_camera.display_camera {
+                if let Ok(mut display_letterbox) =
+                    letterbox_query.get_mut::<Letterbox>(display_camera)
+                {
+                    if *display_letterbox != letterbox {
+                        *display_letterbox = letterbox;
+                    }
+                }
+            }
+            continue;
+        }
+
+        let texture = textures.add(Texture::new_render_target(texture_size));
+        pixel_camera.texture = Some(texture);
+        camera.render_target = Some(texture);
+
+        // the display camera takes over the name, so it is drawn by the main pass
+        let display_name = camera
+            .name
+            .replace(format!("PixelCamera{}", entity.id()))
+            .unwrap_or_else(|| base::camera::CAMERA2D.to_string());
+        if active_cameras.cameras.contains_key(&display_name) {
+            active_cameras.add(&display_name);
+        }
+
+        let display_layers = RenderLayers::layer(pixel_camera.display_layer);
+        commands
+            .spawn(Camera2dComponents {
+                camera: Camera {
+                    name: Some(display_name),
+                    window: camera.window,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with_bundle((display_layers, letterbox, PixelCameraDisplay));
+        pixel_camera.display_camera = commands.current_entity();
+        commands
+            .spawn(SpriteComponents {
+                material: materials.add(texture.into()),
+                ..Default::default()
+            })
+            .with_bundle((display_layers, PixelCameraDisplay));
+        pixel_camera.display_sprite = commands.current_entity();
+    }
+}
+
+/// Snaps [PixelCamera]s and sprites to the virtual pixel grid, and scales the display sprites to the display
+/// viewports. It runs after transforms are updated, so it updates the [Transform]s as well.
+pub fn pixel_camera_system(
+    mut camera_query: Query<(&PixelCamera, &mut Transform)>,
+    display_camera_query: Query<&Camera>,
+    display_sprite_query: Query<(&mut Translation, &mut Scale, &mut Transform)>,
+    mut sprite_query: Query<Without<PixelCameraDisplay, (&Sprite, &mut Transform)>>,
+) {
+    let mut snap_sprites = false;
+    for (pixel_camera, mut transform) in &mut camera_query.iter() {
+        snap_sprites |= pixel_camera.snap_sprites;
+        let texture_size = pixel_camera.texture_size();
+        let position = transform.value.w_axis();
+        let snapped = Vec2::new(
+            snap_to_pixel_grid(position.x(), texture_size.x()),
+            snap_to_pixel_grid(position.y(), texture_size.y()),
+        );
+        transform
+            .value
+            .set_w_axis(Vec4::new(snapped.x(), snapped.y(), position.z(), 1.0));
+
+        let (display_camera, display_sprite) =
+            match (pixel_camera.display_camera, pixel_camera.display_sprite) {
+                (Some(display_camera), Some(display_sprite)) => (display_camera, display_sprite),
+                _ => continue,
+            };
+        let scale = match display_camera_query
+            .get::<Camera>(display_camera)
+            .ok()
+            .and_then(|camera| camera.viewport)
+        {
+            Some(viewport) => viewport.size.x() / pixel_camera.resolution.x(),
+            None => continue,
+        };
+        // the rest of the camera movement is made up by moving the image, in the opposite direction
+        let offset = if pixel_camera.smoothing {
+            (snapped - Vec2::new(position.x(), position.y())) * scale
+        } else {
+            Vec2::zero()
+        };
+        if let (Ok(mut translation), Ok(mut sprite_scale), Ok(mut sprite_transform)) = (
+            display_sprite_query.get_mut::<Translation>(display_sprite),
+            display_sprite_query.get_mut::<Scale>(display_sprite),
+            display_sprite_query.get_mut::<Transform>(display_sprite),
+        ) {
+            *translation = Translation::new(offset.x(), offset.y(), 0.0);
+            sprite_scale.0 = scale;
+            sprite_transform.value = Mat4::from_scale_rotation_translation(
+                Vec3::new(scale, scale, 1.0),
+                Quat::identity(),
+                offset.extend(0.0),
+            );
+        }
+    }
+
+    if !snap_sprites {
+        return;
+    }
+    for (sprite, mut transform) in &mut sprite_query.iter() {
+        let position = transform.value.w_axis();
+        let size = sprite.size * transform.value.x_axis().truncate().length();
+        let snapped = Vec2::new(
+            snap_to_pixel_grid(position.x(), size.x()),
+            snap_to_pixel_grid(position.y(), size.y()),
+        );
+        if snapped.x() != position.x() || snapped.y() != position.y() {
+            transform
+                .value
+                .set_w_axis(Vec4::new(snapped.x(), snapped.y(), position.z(), 1.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::snap_to_pixel_grid;
+
+    #[test]
+    fn pixel_grid() {
+        // even sizes are centered on whole pixels, odd sizes on half pixels
+        assert_eq!(snap_to_pixel_grid(10.3, 16.0), 10.0);
+        assert_eq!(snap_to_pixel_grid(10.3, 15.0), 10.5);
+        assert_eq!(snap_to_pixel_grid(-2.6, 4.0), -3.0);
+    }
+}
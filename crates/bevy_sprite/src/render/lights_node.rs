@@ -0,0 +1,152 @@
+use super::uniform;
+use crate::{
+    AmbientLight2d, Occluder2d, Occluder2dRaw, PointLight2d, PointLight2dRaw, MAX_LIGHTS_2D,
+    MAX_OCCLUDERS_2D,
+};
+use bevy_core::AsBytes;
+use bevy_ecs::{Commands, IntoQuerySystem, Local, Query, Res, ResMut, Resources, System, World};
+use bevy_render::{
+    render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
+    renderer::{
+        BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding,
+        RenderResourceBindings, RenderResourceContext,
+    },
+};
+use bevy_transform::prelude::*;
+
+/// A Render Graph [Node] that writes the [PointLight2d]s, [Occluder2d]s and [AmbientLight2d] to the "Lights2d"
+/// uniform buffer read by the sprite pipeline
+#[derive(Default)]
+pub struct Lights2dNode {
+    command_queue: CommandQueue,
+}
+
+impl Node for Lights2dNode {
+    fn update(
+        &mut self,
+        _world: &World,
+        _resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        self.command_queue.execute(render_context);
+    }
+}
+
+impl SystemNode for Lights2dNode {
+    fn get_system(&self, commands: &mut Commands) -> Box<dyn System> {
+        let system = lights_2d_node_system.system();
+        commands.insert_local_resource(
+            system.id(),
+            Lights2dNodeSystemState {
+                command_queue: self.command_queue.clone(),
+                lights_buffer: None,
+                staging_buffer: None,
+            },
+        );
+        system
+    }
+}
+
+/// Local "lights 2d node system" state
+#[derive(Default)]
+pub struct Lights2dNodeSystemState {
+    lights_buffer: Option<BufferId>,
+    staging_buffer: Option<BufferId>,
+    command_queue: CommandQueue,
+}
+
+pub fn lights_2d_node_system(
+    mut state: Local<Lights2dNodeSystemState>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    ambient_light: Res<AmbientLight2d>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    mut light_query: Query<(&PointLight2d, &Transform)>,
+    mut occluder_query: Query<(&Occluder2d, &Transform)>,
+) {
+    let state = &mut state;
+    let render_resource_context = &**render_resource_context;
+
+    // ambient light, light count and occluder count
+    let header_size = std::mem::size_of::<[f32; 4]>() * 2;
+    let light_size = std::mem::size_of::<PointLight2dRaw>();
+    let occluder_size = std::mem::size_of::<Occluder2dRaw>();
+    let occluders_offset = header_size + light_size * MAX_LIGHTS_2D;
+    let uniform_size = occluders_offset + occluder_size * MAX_OCCLUDERS_2D;
+
+    if let Some(staging_buffer) = state.staging_buffer {
+        render_resource_context.map_buffer(staging_buffer);
+    } else {
+        // the buffer is created even without lights, as the sprite pipeline always binds it
+        let buffer = render_resource_context.create_buffer(BufferInfo {
+            size: uniform_size,
+            buffer_usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            ..Default::default()
+        });
+        render_resource_bindings.set(
+            uniform::LIGHTS_2D,
+            RenderResourceBinding::Buffer {
+                buffer,
+                range: 0..uniform_size as u64,
+                dynamic_index: None,
+            },
+        );
+        state.lights_buffer = Some(buffer);
+
+        let staging_buffer = render_resource_context.create_buffer(BufferInfo {
+            size: uniform_size,
+            buffer_usage: BufferUsage::COPY_SRC | BufferUsage::MAP_WRITE,
+            mapped_at_creation: true,
+        });
+        state.staging_buffer = Some(staging_buffer);
+    }
+
+    let lights = light_query
+        .iter()
+        .iter()
+        .take(MAX_LIGHTS_2D)
+        .map(|(light, transform)| PointLight2dRaw::from(light, &transform.value))
+        .collect::<Vec<_>>();
+    let occluders = occluder_query
+        .iter()
+        .iter()
+        .filter_map(|(occluder, transform)| Occluder2dRaw::from(occluder, &transform.value))
+        .take(MAX_OCCLUDERS_2D)
+        .collect::<Vec<_>>();
+
+    let staging_buffer = state.staging_buffer.unwrap();
+    render_resource_context.write_mapped_buffer(
+        staging_buffer,
+        0..uniform_size as u64,
+        &mut |data, _renderer| {
+            let ambient: [f32; 4] = ambient_light.color.into();
+            data[0..header_size / 2].copy_from_slice(ambient.as_bytes());
+            data[header_size / 2..header_size]
+                .copy_from_slice([lights.len() as u32, occluders.len() as u32, 0, 0].as_bytes());
+
+            for (light, slot) in lights
+                .iter()
+                .zip(data[header_size..occluders_offset].chunks_exact_mut(light_size))
+            {
+                slot.copy_from_slice(light.as_bytes());
+            }
+
+            for (occluder, slot) in occluders
+                .iter()
+                .zip(data[occluders_offset..uniform_size].chunks_exact_mut(occluder_size))
+            {
+                slot.copy_from_slice(occluder.as_bytes());
+            }
+        },
+    );
+    render_resource_context.unmap_buffer(staging_buffer);
+    let lights_buffer = state.lights_buffer.unwrap();
+    state.command_queue.copy_buffer_to_buffer(
+        staging_buffer,
+        0,
+        lights_buffer,
+        0,
+        uniform_size as u64,
+    );
+}
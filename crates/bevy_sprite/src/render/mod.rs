@@ -1,11 +1,18 @@
+mod lights_node;
+mod sprite_batch_node;
+
+pub use lights_node::*;
+pub use sprite_batch_node::*;
+
 use crate::{ColorMaterial, Sprite, TextureAtlas, TextureAtlasSprite};
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::Resources;
 use bevy_render::{
     pipeline::{
-        BlendDescriptor, BlendFactor, BlendOperation, ColorStateDescriptor, ColorWrite,
-        CompareFunction, CullMode, DepthStencilStateDescriptor, FrontFace, PipelineDescriptor,
-        RasterizationStateDescriptor, StencilStateFaceDescriptor,
+        AsVertexBufferDescriptor, BlendDescriptor, BlendFactor, BlendOperation,
+        ColorStateDescriptor, ColorWrite, CompareFunction, CullMode, DepthStencilStateDescriptor,
+        FrontFace, PipelineDescriptor, RasterizationStateDescriptor, StencilStateFaceDescriptor,
+        VertexBufferDescriptors,
     },
     render_graph::{base, AssetRenderResourcesNode, RenderGraph, RenderResourcesNode},
     shader::{Shader, ShaderStage, ShaderStages},
@@ -111,8 +118,14 @@ pub fn build_sprite_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor
 pub mod node {
     pub const COLOR_MATERIAL: &'static str = "color_material";
     pub const SPRITE: &'static str = "sprite";
+    pub const SPRITE_BATCH: &'static str = "sprite_batch";
     pub const SPRITE_SHEET: &'static str = "sprite_sheet";
     pub const SPRITE_SHEET_SPRITE: &'static str = "sprite_sheet_sprite";
+    pub const LIGHTS_2D: &'static str = "lights_2d";
+}
+
+pub mod uniform {
+    pub const LIGHTS_2D: &'static str = "Lights2d";
 }
 
 pub trait SpriteRenderGraphBuilder {
@@ -128,10 +141,19 @@ impl SpriteRenderGraphBuilder for RenderGraph {
         self.add_node_edge(node::COLOR_MATERIAL, base::node::MAIN_PASS)
             .unwrap();
 
+        // registered after the color material node, so new materials are batched in the frame they are set up
+        self.add_system_node(node::SPRITE_BATCH, SpriteBatchNode::default());
+        self.add_node_edge(node::SPRITE_BATCH, base::node::MAIN_PASS)
+            .unwrap();
+
         self.add_system_node(node::SPRITE, RenderResourcesNode::<Sprite>::new(true));
         self.add_node_edge(node::SPRITE, base::node::MAIN_PASS)
             .unwrap();
 
+        self.add_system_node(node::LIGHTS_2D, Lights2dNode::default());
+        self.add_node_edge(node::LIGHTS_2D, base::node::MAIN_PASS)
+            .unwrap();
+
         self.add_system_node(
             node::SPRITE_SHEET,
             AssetRenderResourcesNode::<TextureAtlas>::new(false),
@@ -142,6 +164,9 @@ impl SpriteRenderGraphBuilder for RenderGraph {
             RenderResourcesNode::<TextureAtlasSprite>::new(true),
         );
 
+        let mut vertex_buffer_descriptors = resources.get_mut::<VertexBufferDescriptors>().unwrap();
+        vertex_buffer_descriptors.set(SpriteInstance::as_vertex_buffer_descriptor().clone());
+
         let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
         let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
         pipelines.set(SPRITE_PIPELINE_HANDLE, build_sprite_pipeline(&mut shaders));
@@ -152,3 +177,24 @@ impl SpriteRenderGraphBuilder for RenderGraph {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_render::shader::{Shader, ShaderStage};
+
+    #[test]
+    fn sprite_shaders_compile() {
+        // compilation panics on glsl errors
+        let vertex =
+            Shader::from_glsl(ShaderStage::Vertex, include_str!("sprite.vert")).get_spirv(None);
+        assert!(!vertex.is_empty());
+
+        let fragment = Shader::from_glsl(ShaderStage::Fragment, include_str!("sprite.frag"));
+        assert!(!fragment.get_spirv(None).is_empty());
+        let shader_defs = vec![
+            "COLORMATERIAL_TEXTURE".to_string(),
+            "COLORMATERIAL_NORMAL_MAP".to_string(),
+        ];
+        assert!(!fragment.get_spirv(Some(&shader_defs)).is_empty());
+    }
+}
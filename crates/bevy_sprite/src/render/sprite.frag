@@ -1,6 +1,28 @@
 #version 450
 
+const int MAX_LIGHTS_2D = 16;
+const int MAX_OCCLUDERS_2D = 64;
+// the number of points on the light's source that are tested for soft shadows
+const int SOFT_SHADOW_SAMPLES = 8;
+
+struct PointLight2d {
+    // x, y, height, radius
+    vec4 position;
+    // rgb: color * intensity, a: source radius (negative without shadows)
+    vec4 color;
+};
+
+struct Occluder2d {
+    // the columns of the matrix mapping world space offsets from the center into the occluder's space
+    vec4 inverse_transform;
+    // xy: center, zw: half size
+    vec4 center;
+};
+
 layout(location = 0) in vec2 v_Uv;
+layout(location = 1) in vec2 v_Position;
+layout(location = 2) in vec2 v_Tangent;
+layout(location = 3) in vec2 v_Bitangent;
 
 layout(location = 0) out vec4 o_Target;
 
@@ -8,17 +30,106 @@ layout(set = 1, binding = 0) uniform ColorMaterial_color {
     vec4 Color;
 };
 
-# ifdef COLORMATERIAL_TEXTURE 
+# ifdef COLORMATERIAL_TEXTURE
 layout(set = 1, binding = 1) uniform texture2D ColorMaterial_texture;
 layout(set = 1, binding = 2) uniform sampler ColorMaterial_texture_sampler;
 # endif
 
+# ifdef COLORMATERIAL_NORMAL_MAP
+layout(set = 1, binding = 3) uniform texture2D ColorMaterial_normal_map;
+layout(set = 1, binding = 4) uniform sampler ColorMaterial_normal_map_sampler;
+# endif
+
+layout(set = 2, binding = 0) uniform Lights2d {
+    vec4 AmbientLight;
+    // x: light count, y: occluder count
+    uvec4 NumLights2d;
+    PointLight2d Lights[MAX_LIGHTS_2D];
+    Occluder2d Occluders[MAX_OCCLUDERS_2D];
+};
+
+// whether the segment from the fragment to a point on the light crosses an occluder
+bool occluded(vec2 target) {
+    for (int i = 0; i < int(NumLights2d.y) && i < MAX_OCCLUDERS_2D; ++i) {
+        Occluder2d occluder = Occluders[i];
+        mat2 inverse_transform = mat2(occluder.inverse_transform.xy, occluder.inverse_transform.zw);
+        vec2 half_size = occluder.center.zw;
+        vec2 origin = inverse_transform * (v_Position - occluder.center.xy);
+        // occluders don't shadow the surfaces they cover
+        if (all(lessThan(abs(origin), half_size))) {
+            continue;
+        }
+
+        vec2 direction = inverse_transform * (target - occluder.center.xy) - origin;
+        vec2 t0 = (-half_size - origin) / direction;
+        vec2 t1 = (half_size - origin) / direction;
+        vec2 t_min = min(t0, t1);
+        vec2 t_max = max(t0, t1);
+        float enter = max(t_min.x, t_min.y);
+        float exit = min(t_max.x, t_max.y);
+        if (enter <= exit && exit > 0.0 && enter < 1.0) {
+            return true;
+        }
+    }
+    return false;
+}
+
+float visibility(PointLight2d light) {
+    float source_radius = light.color.a;
+    if (source_radius < 0.0) {
+        return 1.0;
+    }
+    if (source_radius == 0.0) {
+        return occluded(light.position.xy) ? 0.0 : 1.0;
+    }
+
+    // soft shadows test points spread across the light's source, perpendicular to the light's direction
+    vec2 to_light = light.position.xy - v_Position;
+    vec2 across = normalize(vec2(-to_light.y, to_light.x) + vec2(1e-6, 0.0)) * source_radius;
+    float visible = 0.0;
+    for (int i = 0; i < SOFT_SHADOW_SAMPLES; ++i) {
+        float offset = (float(i) + 0.5) / float(SOFT_SHADOW_SAMPLES) * 2.0 - 1.0;
+        visible += occluded(light.position.xy + across * offset) ? 0.0 : 1.0;
+    }
+    return visible / float(SOFT_SHADOW_SAMPLES);
+}
+
 void main() {
+    // texture coordinates outside of 0.0 to 1.0 repeat the texture
+    vec2 outside = vec2(lessThan(v_Uv, vec2(0.0))) + vec2(greaterThan(v_Uv, vec2(1.0)));
+    vec2 uv = mix(v_Uv, fract(v_Uv), outside);
+
     vec4 color = Color;
 # ifdef COLORMATERIAL_TEXTURE
     color *= texture(
         sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler),
-        v_Uv);
+        uv);
+# endif
+
+    // scenes without lights are drawn unlit
+    if (NumLights2d.x > 0u) {
+# ifdef COLORMATERIAL_NORMAL_MAP
+        vec3 normal_sample = texture(
+            sampler2D(ColorMaterial_normal_map, ColorMaterial_normal_map_sampler),
+            uv).xyz * 2.0 - 1.0;
+        vec3 normal = normalize(vec3(
+            normal_sample.x * v_Tangent + normal_sample.y * v_Bitangent,
+            normal_sample.z));
+# endif
+        vec3 light_color = AmbientLight.rgb;
+        for (int i = 0; i < int(NumLights2d.x) && i < MAX_LIGHTS_2D; ++i) {
+            PointLight2d light = Lights[i];
+            vec2 to_light = light.position.xy - v_Position;
+            float falloff = clamp(1.0 - dot(to_light, to_light) / (light.position.w * light.position.w), 0.0, 1.0);
+            float intensity = falloff * falloff;
+# ifdef COLORMATERIAL_NORMAL_MAP
+            intensity *= max(dot(normal, normalize(vec3(to_light, light.position.z))), 0.0);
 # endif
+            if (intensity > 0.0) {
+                light_color += light.color.rgb * intensity * visibility(light);
+            }
+        }
+        color.rgb *= light_color;
+    }
     o_Target = color;
 }
@@ -4,21 +4,33 @@ layout(location = 0) in vec3 Vertex_Position;
 layout(location = 1) in vec3 Vertex_Normal;
 layout(location = 2) in vec2 Vertex_Uv;
 
+// per sprite: the sprite's transform scaled by its size, and the region of the texture it shows (xy: min, zw: max)
+layout(location = 3) in vec4 I_SpriteInstance_Model_0;
+layout(location = 4) in vec4 I_SpriteInstance_Model_1;
+layout(location = 5) in vec4 I_SpriteInstance_Model_2;
+layout(location = 6) in vec4 I_SpriteInstance_Model_3;
+layout(location = 7) in vec4 I_SpriteInstance_Uv;
+
 layout(location = 0) out vec2 v_Uv;
+layout(location = 1) out vec2 v_Position;
+layout(location = 2) out vec2 v_Tangent;
+layout(location = 3) out vec2 v_Bitangent;
 
 layout(set = 0, binding = 0) uniform Camera {
     mat4 ViewProj;
 };
 
-layout(set = 2, binding = 0) uniform Transform {
-    mat4 Model;
-};
-layout(set = 2, binding = 1) uniform Sprite {
-    vec2 Sprite_size;
-};
-
 void main() {
-    v_Uv = Vertex_Uv;
-    vec3 position = Vertex_Position * vec3(Sprite_size, 1.0);
-    gl_Position = ViewProj * Model * vec4(position, 1.0);
-}
\ No newline at end of file
+    mat4 Model = mat4(
+        I_SpriteInstance_Model_0,
+        I_SpriteInstance_Model_1,
+        I_SpriteInstance_Model_2,
+        I_SpriteInstance_Model_3);
+    v_Uv = mix(I_SpriteInstance_Uv.xy, I_SpriteInstance_Uv.zw, Vertex_Uv);
+    vec4 world_position = Model * vec4(Vertex_Position, 1.0);
+    v_Position = world_position.xy;
+    // the directions of the sprite's x and y axes in the world, which normal maps are relative to
+    v_Tangent = normalize(Model[0].xy);
+    v_Bitangent = normalize(Model[1].xy);
+    gl_Position = ViewProj * world_position;
+}
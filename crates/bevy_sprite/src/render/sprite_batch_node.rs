@@ -0,0 +1,456 @@
+use super::SPRITE_PIPELINE_HANDLE;
+use crate::{ColorMaterial, Sprite};
+use bevy_asset::{Assets, Handle};
+use bevy_core::{AsBytes, Byteable, FloatOrd};
+use bevy_ecs::{
+    Commands, Entity, IntoQuerySystem, Local, Query, Res, ResMut, Resources, System, World,
+};
+use bevy_math::{Mat4, Vec3, Vec4};
+use bevy_render::{
+    camera::RenderLayers,
+    draw::{Draw, DrawContext},
+    mesh::{Mesh, Vertex, INDEX_BUFFER_ASSET_INDEX, VERTEX_BUFFER_ASSET_INDEX},
+    pipeline::{
+        AsVertexBufferDescriptor, PipelineSpecialization, RenderPipelines, ShaderSpecialization,
+    },
+    prelude::Msaa,
+    render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
+    render_stats::{BatchBreak, RenderStats},
+    renderer::{
+        AssetRenderResourceBindings, BufferId, BufferInfo, BufferUsage, RenderContext,
+        RenderResourceBindings, RenderResourceId,
+    },
+    shader::ShaderDefs,
+};
+use bevy_transform::prelude::Transform;
+use std::collections::HashMap;
+
+/// The data the sprite pipeline reads for each drawn sprite
+#[repr(C)]
+#[derive(Debug, Clone, Copy, AsVertexBufferDescriptor)]
+pub struct SpriteInstance {
+    /// The sprite's transform, scaled by its size
+    #[vertex(instance)]
+    pub model: Mat4,
+    /// The region of the texture the sprite shows. xy is the minimum and zw the maximum.
+    #[vertex(instance)]
+    pub uv: Vec4,
+}
+
+impl SpriteInstance {
+    pub fn new(sprite: &Sprite, transform: &Mat4) -> Self {
+        SpriteInstance {
+            model: *transform * Mat4::from_scale(Vec3::new(sprite.size.x(), sprite.size.y(), 1.0)),
+            uv: Vec4::new(
+                sprite.uv.min.x(),
+                sprite.uv.min.y(),
+                sprite.uv.max.x(),
+                sprite.uv.max.y(),
+            ),
+        }
+    }
+}
+
+// SAFE: SpriteInstance is repr(C) and only consists of floats
+unsafe impl Byteable for SpriteInstance {}
+
+/// Sprites are batched when they share these. Sprites at different depths are kept apart, so batches are still drawn
+/// back to front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SpriteBatchKey {
+    depth: FloatOrd,
+    material: Handle<ColorMaterial>,
+    mesh: Handle<Mesh>,
+    layers: u32,
+}
+
+impl SpriteBatchKey {
+    /// Why a batch with this key can't be drawn together with a batch with the `previous` key
+    fn batch_break(&self, previous: &SpriteBatchKey) -> BatchBreak {
+        if self.material != previous.material {
+            BatchBreak::Texture
+        } else if self.mesh != previous.mesh {
+            BatchBreak::Mesh
+        } else if self.layers != previous.layers {
+            BatchBreak::Layers
+        } else {
+            BatchBreak::ZOrder
+        }
+    }
+}
+
+struct SpriteBatch {
+    key: SpriteBatchKey,
+    /// The entity whose [Draw] draws the whole batch
+    leader: Entity,
+    instances: Vec<SpriteInstance>,
+}
+
+/// Adds `instance` to the batch with the given `key`, or starts a new batch led by `entity`
+fn add_to_batch(
+    batches: &mut Vec<SpriteBatch>,
+    batch_indices: &mut HashMap<SpriteBatchKey, usize>,
+    key: SpriteBatchKey,
+    entity: Entity,
+    instance: SpriteInstance,
+) {
+    let index = *batch_indices.entry(key).or_insert_with(|| {
+        batches.push(SpriteBatch {
+            key,
+            leader: entity,
+            instances: Vec::new(),
+        });
+        batches.len() - 1
+    });
+    batches[index].instances.push(instance);
+}
+
+/// Returns why each batch couldn't be drawn together with the batch before it. Batches are drawn back to front, so
+/// they are compared in depth order.
+fn batch_breaks(batches: &[SpriteBatch]) -> Vec<Option<BatchBreak>> {
+    let mut keys = batches.iter().map(|batch| batch.key).collect::<Vec<_>>();
+    keys.sort_by_key(|key| key.depth);
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| {
+            i.checked_sub(1)
+                .map(|previous| key.batch_break(&keys[previous]))
+        })
+        .collect()
+}
+
+/// A Render Graph [Node] that uploads the instance buffer of the sprite pipeline. Its system groups sprites without
+/// [RenderPipelines] of their own into batches, and draws each batch with one instanced draw call.
+#[derive(Default)]
+pub struct SpriteBatchNode {
+    command_queue: CommandQueue,
+}
+
+impl Node for SpriteBatchNode {
+    fn update(
+        &mut self,
+        _world: &World,
+        _resources: &Resources,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        self.command_queue.execute(render_context);
+    }
+}
+
+impl SystemNode for SpriteBatchNode {
+    fn get_system(&self, commands: &mut Commands) -> Box<dyn System> {
+        let system = sprite_batch_node_system.system();
+        commands.insert_local_resource(
+            system.id(),
+            SpriteBatchNodeSystemState {
+                command_queue: self.command_queue.clone(),
+                ..Default::default()
+            },
+        );
+        system
+    }
+}
+
+/// Local "sprite batch node system" state
+#[derive(Default)]
+pub struct SpriteBatchNodeSystemState {
+    command_queue: CommandQueue,
+    instance_buffer: Option<BufferId>,
+    staging_buffer: Option<BufferId>,
+    capacity: usize,
+    batches: Vec<SpriteBatch>,
+    batch_indices: HashMap<SpriteBatchKey, usize>,
+}
+
+pub fn sprite_batch_node_system(
+    mut state: Local<SpriteBatchNodeSystemState>,
+    mut draw_context: DrawContext,
+    msaa: Res<Msaa>,
+    materials: Res<Assets<ColorMaterial>>,
+    meshes: Res<Assets<Mesh>>,
+    mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    mut render_stats: ResMut<RenderStats>,
+    mut sprite_query: Query<(
+        Entity,
+        &Sprite,
+        &Handle<ColorMaterial>,
+        &Handle<Mesh>,
+        &mut Draw,
+        &RenderPipelines,
+        &Transform,
+    )>,
+    layers_query: Query<(&Sprite, &RenderLayers)>,
+) {
+    let state = &mut *state;
+    state.batches.clear();
+    state.batch_indices.clear();
+    if !draw_context.is_pipeline_loaded(SPRITE_PIPELINE_HANDLE) {
+        return;
+    }
+
+    for (entity, sprite, material, mesh, draw, render_pipelines, transform) in
+        &mut sprite_query.iter()
+    {
+        // sprites with pipelines of their own are drawn on their own
+        if !draw.is_visible || !render_pipelines.pipelines.is_empty() {
+            continue;
+        }
+
+        let key = SpriteBatchKey {
+            depth: FloatOrd(transform.value.w_axis().z()),
+            material: *material,
+            mesh: *mesh,
+            layers: layers_query
+                .get::<RenderLayers>(entity)
+                .map_or_else(|_| RenderLayers::default().mask, |layers| layers.mask),
+        };
+        add_to_batch(
+            &mut state.batches,
+            &mut state.batch_indices,
+            key,
+            entity,
+            SpriteInstance::new(&sprite, &transform.value),
+        );
+    }
+
+    for batch_break in batch_breaks(&state.batches) {
+        render_stats.add_batch(batch_break);
+    }
+
+    let instance_count = state
+        .batches
+        .iter()
+        .map(|batch| batch.instances.len())
+        .sum::<usize>();
+    if instance_count == 0 {
+        return;
+    }
+
+    let render_resource_context = &**draw_context.render_resource_context;
+    let instance_size = std::mem::size_of::<SpriteInstance>();
+    if instance_count > state.capacity {
+        if let Some(instance_buffer) = state.instance_buffer.take() {
+            render_resource_context.remove_buffer(instance_buffer);
+        }
+        if let Some(staging_buffer) = state.staging_buffer.take() {
+            render_resource_context.remove_buffer(staging_buffer);
+        }
+
+        state.capacity = instance_count.next_power_of_two();
+        state.instance_buffer = Some(render_resource_context.create_buffer(BufferInfo {
+            size: state.capacity * instance_size,
+            buffer_usage: BufferUsage::VERTEX | BufferUsage::COPY_DST,
+            ..Default::default()
+        }));
+        state.staging_buffer = Some(render_resource_context.create_buffer(BufferInfo {
+            size: state.capacity * instance_size,
+            buffer_usage: BufferUsage::COPY_SRC | BufferUsage::MAP_WRITE,
+            mapped_at_creation: true,
+        }));
+    } else {
+        render_resource_context.map_buffer(state.staging_buffer.unwrap());
+    }
+
+    let instance_buffer = state.instance_buffer.unwrap();
+    let staging_buffer = state.staging_buffer.unwrap();
+    let batches = &state.batches;
+    render_resource_context.write_mapped_buffer(
+        staging_buffer,
+        0..(instance_count * instance_size) as u64,
+        &mut |data, _renderer| {
+            let mut offset = 0;
+            for batch in batches.iter() {
+                let bytes = batch.instances.as_slice().as_bytes();
+                data[offset..offset + bytes.len()].copy_from_slice(bytes);
+                offset += bytes.len();
+            }
+        },
+    );
+    render_resource_context.unmap_buffer(staging_buffer);
+    state.command_queue.copy_buffer_to_buffer(
+        staging_buffer,
+        0,
+        instance_buffer,
+        0,
+        (instance_count * instance_size) as u64,
+    );
+
+    let mut first_instance = 0;
+    for batch in state.batches.iter() {
+        let instances = first_instance..first_instance + batch.instances.len() as u32;
+        first_instance = instances.end;
+
+        let render_resource_context = &**draw_context.render_resource_context;
+        let (vertex_buffer, index_buffer) = match (
+            render_resource_context.get_asset_resource(batch.key.mesh, VERTEX_BUFFER_ASSET_INDEX),
+            render_resource_context.get_asset_resource(batch.key.mesh, INDEX_BUFFER_ASSET_INDEX),
+        ) {
+            (Some(RenderResourceId::Buffer(vertex)), Some(RenderResourceId::Buffer(index))) => {
+                (vertex, index)
+            }
+            _ => continue,
+        };
+        let index_count = match render_resource_context.get_buffer_info(index_buffer) {
+            Some(buffer_info) => (buffer_info.size / 2) as u32,
+            None => continue,
+        };
+        let (material, material_bindings) = match (
+            materials.get(&batch.key.material),
+            asset_render_resource_bindings.get_mut(batch.key.material),
+        ) {
+            (Some(material), Some(material_bindings)) => (material, material_bindings),
+            _ => continue,
+        };
+
+        let specialization = PipelineSpecialization {
+            shader_specialization: ShaderSpecialization {
+                shader_defs: material
+                    .iter_shader_defs()
+                    .map(|shader_def| shader_def.to_string())
+                    .collect(),
+            },
+            primitive_topology: meshes
+                .get(&batch.key.mesh)
+                .map_or_else(Default::default, |mesh| mesh.primitive_topology),
+            sample_count: msaa.samples,
+            ..Default::default()
+        };
+
+        let mut draw = sprite_query.get_mut::<Draw>(batch.leader).unwrap();
+        draw_context
+            .set_pipeline(&mut draw, SPRITE_PIPELINE_HANDLE, &specialization)
+            .unwrap();
+        draw_context
+            .set_bind_groups_from_bindings(
+                &mut draw,
+                &mut [material_bindings, &mut render_resource_bindings],
+            )
+            .unwrap();
+        let layout = draw_context.get_pipeline_layout().unwrap();
+        for (slot, descriptor) in layout.vertex_buffer_descriptors.iter().enumerate() {
+            if descriptor.name == SpriteInstance::as_vertex_buffer_descriptor().name {
+                draw.set_vertex_buffer(slot as u32, instance_buffer, 0);
+            } else if descriptor.name == Vertex::as_vertex_buffer_descriptor().name {
+                draw.set_vertex_buffer(slot as u32, vertex_buffer, 0);
+            }
+        }
+        draw.set_index_buffer(index_buffer, 0);
+        draw.draw_indexed(0..index_count, 0, instances);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_to_batch, batch_breaks, SpriteBatchKey, SpriteInstance};
+    use crate::{Rect, Sprite};
+    use bevy_asset::Handle;
+    use bevy_core::FloatOrd;
+    use bevy_ecs::Entity;
+    use bevy_math::{Mat4, Vec2, Vec3, Vec4};
+    use bevy_render::{
+        pipeline::{AsVertexBufferDescriptor, InputStepMode},
+        render_stats::BatchBreak,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn sprite_batch_break() {
+        let key = SpriteBatchKey {
+            depth: FloatOrd(0.0),
+            material: Handle::from_u128(1),
+            mesh: Handle::from_u128(2),
+            layers: 1,
+        };
+        let deeper = SpriteBatchKey {
+            depth: FloatOrd(1.0),
+            ..key
+        };
+        assert_eq!(deeper.batch_break(&key), BatchBreak::ZOrder);
+        let other_layers = SpriteBatchKey {
+            layers: 2,
+            ..deeper
+        };
+        assert_eq!(other_layers.batch_break(&key), BatchBreak::Layers);
+        let other_material = SpriteBatchKey {
+            material: Handle::from_u128(3),
+            ..other_layers
+        };
+        assert_eq!(other_material.batch_break(&key), BatchBreak::Texture);
+    }
+
+    #[test]
+    fn sprite_batches() {
+        let key = SpriteBatchKey {
+            depth: FloatOrd(1.0),
+            material: Handle::from_u128(1),
+            mesh: Handle::from_u128(2),
+            layers: 1,
+        };
+        let other_material = SpriteBatchKey {
+            material: Handle::from_u128(3),
+            ..key
+        };
+        let front = SpriteBatchKey {
+            depth: FloatOrd(0.0),
+            ..key
+        };
+        let instance = SpriteInstance::new(&Sprite::default(), &Mat4::identity());
+
+        // sprites that share a key are batched even when other sprites are queried between them
+        let mut batches = Vec::new();
+        let mut batch_indices = HashMap::new();
+        for (id, key) in [key, other_material, key, front, key].iter().enumerate() {
+            add_to_batch(
+                &mut batches,
+                &mut batch_indices,
+                *key,
+                Entity::from_id(id as u32),
+                instance,
+            );
+        }
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].key, key);
+        assert_eq!(batches[0].leader, Entity::from_id(0));
+        assert_eq!(batches[0].instances.len(), 3);
+        assert_eq!(batches[1].leader, Entity::from_id(1));
+        assert_eq!(batches[1].instances.len(), 1);
+        assert_eq!(batches[2].leader, Entity::from_id(3));
+
+        // the sprites in front are compared with the first batch, which is compared with the second
+        assert_eq!(
+            batch_breaks(&batches),
+            vec![None, Some(BatchBreak::ZOrder), Some(BatchBreak::Texture)]
+        );
+    }
+
+    #[test]
+    fn sprite_instance() {
+        let descriptor = SpriteInstance::as_vertex_buffer_descriptor();
+        assert_eq!(descriptor.name, "SpriteInstance");
+        assert_eq!(
+            descriptor.stride,
+            std::mem::size_of::<SpriteInstance>() as u64
+        );
+        assert_eq!(descriptor.attributes[0].name, "I_SpriteInstance_Model_0");
+        assert_eq!(descriptor.attributes[4].name, "I_SpriteInstance_Uv");
+        assert_eq!(descriptor.step_mode, InputStepMode::Instance);
+
+        let sprite = Sprite {
+            size: Vec2::new(2.0, 4.0),
+            uv: Rect {
+                min: Vec2::new(0.25, 0.0),
+                max: Vec2::new(0.5, 1.0),
+            },
+        };
+        let instance =
+            SpriteInstance::new(&sprite, &Mat4::from_translation(Vec3::new(10.0, 20.0, 1.0)));
+        assert_eq!(
+            instance.model.transform_point3(Vec3::new(0.5, 0.5, 0.0)),
+            Vec3::new(11.0, 22.0, 1.0)
+        );
+        assert_eq!(instance.uv, Vec4::new(0.25, 0.0, 0.5, 1.0));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ColorMaterial;
+use crate::{ColorMaterial, Rect};
 use bevy_asset::{Assets, Handle};
 use bevy_core::Byteable;
 use bevy_ecs::{Query, Res};
@@ -9,10 +9,34 @@ use bevy_render::{
 };
 
 #[repr(C)]
-#[derive(Default, RenderResources, RenderResource)]
+#[derive(RenderResources, RenderResource)]
 #[render_resources(from_self)]
 pub struct Sprite {
     pub size: Vec2,
+    /// The region of the texture the sprite shows, in texture coordinates. Coordinates outside of 0.0 to 1.0 repeat
+    /// the texture.
+    pub uv: Rect,
+}
+
+impl Sprite {
+    pub fn new(size: Vec2) -> Self {
+        Sprite {
+            size,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Sprite {
+            size: Vec2::default(),
+            uv: Rect {
+                min: Vec2::new(0.0, 0.0),
+                max: Vec2::new(1.0, 1.0),
+            },
+        }
+    }
 }
 
 // SAFE: sprite is repr(C) and only consists of byteables
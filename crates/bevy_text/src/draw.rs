@@ -1,4 +1,4 @@
-use crate::{Font, FontAtlasSet};
+use crate::{Font, FontAtlasSet, TextLine};
 use ab_glyph::{Glyph, PxScale, ScaleFont};
 use bevy_asset::Assets;
 use bevy_math::{Mat4, Vec2, Vec3};
@@ -14,6 +14,7 @@ use bevy_render::{
 };
 use bevy_sprite::{TextureAtlas, TextureAtlasSprite};
 
+#[derive(Clone)]
 pub struct TextStyle {
     pub font_size: f32,
     pub color: Color,
@@ -38,6 +39,8 @@ pub struct DrawableText<'a> {
     pub container_size: Vec2,
     pub style: &'a TextStyle,
     pub text: &'a str,
+    /// The lines of `text` to draw, ex: from [wrap_text](crate::wrap_text)
+    pub lines: &'a [TextLine],
     pub msaa: &'a Msaa,
 }
 
@@ -77,74 +80,77 @@ impl<'a> Drawable for DrawableText<'a> {
         let font = &self.font.font;
         let scale = PxScale::from(self.style.font_size);
         let scaled_font = ab_glyph::Font::as_scaled(&font, scale);
-        let mut caret = self.position;
-        let mut last_glyph: Option<Glyph> = None;
 
         // set local per-character bindings
-        for character in self.text.chars() {
-            if character.is_control() {
-                continue;
-            }
+        for line in self.lines.iter() {
+            let mut caret = self.position + line.position.extend(0.0);
+            let mut last_glyph: Option<Glyph> = None;
+            for character in self.text[line.range.clone()].chars() {
+                if character.is_control() {
+                    continue;
+                }
 
-            let glyph = scaled_font.scaled_glyph(character);
-            if let Some(last_glyph) = last_glyph.take() {
-                caret.set_x(caret.x() + scaled_font.kern(last_glyph.id, glyph.id));
-            }
-            if let Some(glyph_atlas_info) = self
-                .font_atlas_set
-                .get_glyph_atlas_info(self.style.font_size, character)
-            {
-                if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
-                    let texture_atlas = self
-                        .texture_atlases
-                        .get(&glyph_atlas_info.texture_atlas)
-                        .unwrap();
-                    let glyph_rect = texture_atlas.textures[glyph_atlas_info.char_index as usize];
-                    let glyph_width = glyph_rect.width();
-                    let glyph_height = glyph_rect.height();
-                    let atlas_render_resource_bindings = self
-                        .asset_render_resource_bindings
-                        .get_mut(glyph_atlas_info.texture_atlas)
-                        .unwrap();
-                    context.set_bind_groups_from_bindings(
-                        draw,
-                        &mut [atlas_render_resource_bindings],
-                    )?;
+                let glyph = scaled_font.scaled_glyph(character);
+                if let Some(last_glyph) = last_glyph.take() {
+                    caret.set_x(caret.x() + scaled_font.kern(last_glyph.id, glyph.id));
+                }
+                if let Some(glyph_atlas_info) = self
+                    .font_atlas_set
+                    .get_glyph_atlas_info(self.style.font_size, character)
+                {
+                    if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
+                        let texture_atlas = self
+                            .texture_atlases
+                            .get(&glyph_atlas_info.texture_atlas)
+                            .unwrap();
+                        let glyph_rect =
+                            texture_atlas.textures[glyph_atlas_info.char_index as usize];
+                        let glyph_width = glyph_rect.width();
+                        let glyph_height = glyph_rect.height();
+                        let atlas_render_resource_bindings = self
+                            .asset_render_resource_bindings
+                            .get_mut(glyph_atlas_info.texture_atlas)
+                            .unwrap();
+                        context.set_bind_groups_from_bindings(
+                            draw,
+                            &mut [atlas_render_resource_bindings],
+                        )?;
 
-                    let bounds = outlined.px_bounds();
-                    let offset = scaled_font.descent() + glyph_height;
-                    let transform = Mat4::from_translation(
-                        caret
-                            + Vec3::new(
-                                0.0 + glyph_width / 2.0 + bounds.min.x,
-                                glyph_height / 2.0 - bounds.min.y - offset,
-                                0.0,
-                            ),
-                    );
-                    let sprite = TextureAtlasSprite {
-                        index: glyph_atlas_info.char_index,
-                        color: self.style.color,
-                    };
+                        let bounds = outlined.px_bounds();
+                        let offset = scaled_font.descent() + glyph_height;
+                        let transform = Mat4::from_translation(
+                            caret
+                                + Vec3::new(
+                                    0.0 + glyph_width / 2.0 + bounds.min.x,
+                                    glyph_height / 2.0 - bounds.min.y - offset,
+                                    0.0,
+                                ),
+                        );
+                        let sprite = TextureAtlasSprite {
+                            index: glyph_atlas_info.char_index,
+                            color: self.style.color,
+                        };
 
-                    let transform_buffer = context
-                        .shared_buffers
-                        .get_buffer(&transform, BufferUsage::UNIFORM)
-                        .unwrap();
-                    let sprite_buffer = context
-                        .shared_buffers
-                        .get_buffer(&sprite, BufferUsage::UNIFORM)
-                        .unwrap();
-                    let sprite_bind_group = BindGroup::build()
-                        .add_binding(0, transform_buffer)
-                        .add_binding(1, sprite_buffer)
-                        .finish();
-                    context.create_bind_group_resource(2, &sprite_bind_group)?;
-                    draw.set_bind_group(2, &sprite_bind_group);
-                    draw.draw_indexed(indices.clone(), 0, 0..1);
+                        let transform_buffer = context
+                            .shared_buffers
+                            .get_buffer(&transform, BufferUsage::UNIFORM)
+                            .unwrap();
+                        let sprite_buffer = context
+                            .shared_buffers
+                            .get_buffer(&sprite, BufferUsage::UNIFORM)
+                            .unwrap();
+                        let sprite_bind_group = BindGroup::build()
+                            .add_binding(0, transform_buffer)
+                            .add_binding(1, sprite_buffer)
+                            .finish();
+                        context.create_bind_group_resource(2, &sprite_bind_group)?;
+                        draw.set_bind_group(2, &sprite_bind_group);
+                        draw.draw_indexed(indices.clone(), 0, 0..1);
+                    }
                 }
+                caret.set_x(caret.x() + scaled_font.h_advance(glyph.id));
+                last_glyph = Some(glyph);
             }
-            caret.set_x(caret.x() + scaled_font.h_advance(glyph.id));
-            last_glyph = Some(glyph);
         }
         Ok(())
     }
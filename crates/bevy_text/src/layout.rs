@@ -0,0 +1,133 @@
+use crate::Font;
+use ab_glyph::{Glyph, ScaleFont};
+use bevy_math::Vec2;
+use std::ops::Range;
+
+/// A line of laid out text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// The byte range of the line in the text
+    pub range: Range<usize>,
+    pub width: f32,
+    /// The position of the line's bottom left corner, relative to the bottom left corner of the text
+    pub position: Vec2,
+}
+
+/// Breaks `text` into lines at newlines and, if `max_width` is set, between words so that lines are no wider than
+/// `max_width`. Words wider than `max_width` get a line of their own. Lines are `font_size` apart and left aligned.
+pub fn wrap_text(font: &Font, font_size: f32, text: &str, max_width: Option<f32>) -> Vec<TextLine> {
+    let measure = |text: &str| text_width(font, font_size, text);
+
+    let mut lines = Vec::new();
+    let mut paragraph_start = 0;
+    for paragraph in text.split('\n') {
+        let mut line_start = paragraph_start;
+        let mut line_end = paragraph_start;
+        let mut line_width = 0.0;
+        for word in words(paragraph) {
+            let word = paragraph_start + word.start..paragraph_start + word.end;
+            let width = measure(&text[line_start..word.end]);
+            if line_end > line_start && max_width.map_or(false, |max_width| width > max_width) {
+                lines.push(TextLine {
+                    range: line_start..line_end,
+                    width: line_width,
+                    position: Vec2::zero(),
+                });
+                line_start = word.start;
+                line_width = measure(&text[word.clone()]);
+            } else {
+                line_width = width;
+            }
+            line_end = word.end;
+        }
+        lines.push(TextLine {
+            range: line_start..line_end,
+            width: line_width,
+            position: Vec2::zero(),
+        });
+        paragraph_start += paragraph.len() + 1;
+    }
+
+    // the first line is at the top
+    let line_count = lines.len();
+    for (index, line) in lines.iter_mut().enumerate() {
+        line.position = Vec2::new(0.0, (line_count - 1 - index) as f32 * font_size);
+    }
+
+    lines
+}
+
+/// The advance width of `text` laid out on a single line, ignoring control characters like newlines
+pub fn text_width(font: &Font, font_size: f32, text: &str) -> f32 {
+    let scaled_font = ab_glyph::Font::as_scaled(&font.font, font_size);
+    let mut last_glyph: Option<Glyph> = None;
+    let mut width = 0.0;
+    for character in text.chars() {
+        if character.is_control() {
+            continue;
+        }
+        let glyph = scaled_font.scaled_glyph(character);
+        if let Some(last_glyph) = last_glyph.take() {
+            width += scaled_font.kern(last_glyph.id, glyph.id);
+        }
+        width += scaled_font.h_advance(glyph.id);
+        last_glyph = Some(glyph);
+    }
+    width
+}
+
+fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (index, character) in text.char_indices() {
+        if character.is_whitespace() {
+            if let Some(word_start) = word_start.take() {
+                words.push(word_start..index);
+            }
+        } else if word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(word_start) = word_start {
+        words.push(word_start..text.len());
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wrap_text;
+    use crate::Font;
+
+    #[test]
+    fn wrap_lines() {
+        let font = Font::try_from_bytes(
+            include_bytes!("../../../assets/fonts/FiraMono-Medium.ttf").to_vec(),
+        )
+        .unwrap();
+        let text = "aaa bbb ccc\n\nddddddddd";
+        let lines = wrap_text(&font, 20.0, text, None);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| &text[line.range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["aaa bbb ccc", "", "ddddddddd"]
+        );
+
+        // FiraMono is monospaced, so this fits "aaa bbb" but not "aaa bbb ccc"
+        let max_width = lines[0].width * 8.0 / 11.0;
+        let lines = wrap_text(&font, 20.0, text, Some(max_width));
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| &text[line.range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["aaa bbb", "ccc", "", "ddddddddd"]
+        );
+        assert!(lines[0].width <= max_width);
+        assert!(lines[3].width > max_width);
+        assert_eq!(lines[0].position.y(), 60.0);
+        assert_eq!(lines[3].position.y(), 0.0);
+    }
+}
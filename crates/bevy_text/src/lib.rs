@@ -3,19 +3,21 @@ mod font;
 mod font_atlas;
 mod font_atlas_set;
 mod font_loader;
+mod layout;
 
 pub use draw::*;
 pub use font::*;
 pub use font_atlas::*;
 pub use font_atlas_set::*;
 pub use font_loader::*;
+pub use layout::*;
 
 pub mod prelude {
     pub use crate::{Font, TextStyle};
 }
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, AssetServer};
 
 #[derive(Default)]
 pub struct TextPlugin;
@@ -26,4 +28,8 @@ impl Plugin for TextPlugin {
             .add_asset::<FontAtlasSet>()
             .add_asset_loader::<Font, FontLoader>();
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<AssetServer>();
+    }
 }
@@ -5,7 +5,9 @@ mod parent;
 mod rotation;
 mod scale;
 mod transform;
+mod transform_interpolation;
 mod translation;
+mod world_translation;
 
 pub use children::Children;
 pub use local_transform::*;
@@ -14,4 +16,6 @@ pub use parent::{Parent, PreviousParent};
 pub use rotation::*;
 pub use scale::*;
 pub use transform::*;
+pub use transform_interpolation::*;
 pub use translation::*;
+pub use world_translation::*;
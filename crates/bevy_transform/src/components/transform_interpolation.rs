@@ -0,0 +1,64 @@
+use bevy_math::{Mat4, Quat, Vec3};
+
+/// The translation, rotation, and scale of an entity at the end of a fixed update
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TransformState {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for TransformState {
+    fn default() -> Self {
+        TransformState {
+            translation: Vec3::zero(),
+            rotation: Quat::identity(),
+            scale: Vec3::one(),
+        }
+    }
+}
+
+impl TransformState {
+    /// Blends from this state to `other`, where `t` is between 0.0 (this state) and 1.0 (`other`)
+    pub fn interpolate(&self, other: &TransformState, t: f32) -> TransformState {
+        TransformState {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+/// Smooths the [Transform](super::Transform) of entities moved by fixed update systems. The state at the end of the
+/// last two fixed updates is recorded, and rendering blends between them, so movement doesn't stutter when the frame
+/// rate and the fixed timestep don't line up. Only root entities are interpolated; their children follow them.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TransformInterpolation {
+    pub previous: TransformState,
+    pub current: TransformState,
+    pub(crate) recorded: bool,
+}
+
+impl TransformInterpolation {
+    /// Records the state at the end of a fixed update. The first recorded state is also used as the previous state,
+    /// so entities don't blend in from the origin.
+    pub fn record(&mut self, state: TransformState) {
+        self.previous = if self.recorded { self.current } else { state };
+        self.current = state;
+        self.recorded = true;
+    }
+
+    /// Returns whether a fixed update has recorded a state yet
+    pub fn is_recorded(&self) -> bool {
+        self.recorded
+    }
+
+    /// Blends from the previous to the current state, see [TransformState::interpolate]
+    pub fn interpolate(&self, t: f32) -> TransformState {
+        self.previous.interpolate(&self.current, t)
+    }
+}
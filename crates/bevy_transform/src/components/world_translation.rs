@@ -0,0 +1,47 @@
+use bevy_math::Vec3;
+use bevy_property::Properties;
+
+/// The double-precision translation of a root entity, for worlds too large for [Translation](super::Translation).
+/// [Translation] is written from it every frame, relative to the [FloatingOrigin](crate::floating_origin_systems::FloatingOrigin),
+/// so positions stay precise on the GPU no matter how far they are from the world's origin.
+#[derive(Debug, PartialEq, Copy, Clone, Default, Properties)]
+pub struct WorldTranslation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl WorldTranslation {
+    #[inline(always)]
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Moves the translation by `offset`
+    #[inline(always)]
+    pub fn translate(&mut self, offset: Vec3) {
+        self.x += offset.x() as f64;
+        self.y += offset.y() as f64;
+        self.z += offset.z() as f64;
+    }
+
+    /// Returns the offset from `origin` to this translation. Precision is only lost when the offset itself is large.
+    #[inline(always)]
+    pub fn relative_to(&self, origin: &WorldTranslation) -> Vec3 {
+        Vec3::new(
+            (self.x - origin.x) as f32,
+            (self.y - origin.y) as f32,
+            (self.z - origin.z) as f32,
+        )
+    }
+}
+
+impl From<Vec3> for WorldTranslation {
+    fn from(translation: Vec3) -> Self {
+        Self::new(
+            translation.x() as f64,
+            translation.y() as f64,
+            translation.z() as f64,
+        )
+    }
+}
@@ -0,0 +1,136 @@
+use crate::components::*;
+use bevy_ecs::prelude::*;
+
+/// The point in [WorldTranslation] space that [Translation]s are relative to. Keeping the origin close to the camera
+/// keeps [Translation]s small, which avoids jitter from `f32` precision loss far from the world's origin.
+#[derive(Debug, Clone, Copy)]
+pub struct FloatingOrigin {
+    pub origin: WorldTranslation,
+    /// The origin is moved to the [FloatingOriginFocus] once the focus is further than this from it
+    pub rebase_distance: f32,
+}
+
+impl Default for FloatingOrigin {
+    fn default() -> Self {
+        FloatingOrigin {
+            origin: WorldTranslation::default(),
+            rebase_distance: 1000.0,
+        }
+    }
+}
+
+/// Marks the entity the [FloatingOrigin] follows, usually the camera. If there are several, one of them is used.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FloatingOriginFocus;
+
+/// Moves the [FloatingOrigin] to the [FloatingOriginFocus] once the focus is too far from it. Root entities without a
+/// [WorldTranslation] are moved by the same amount, so they keep their place in the world.
+pub fn floating_origin_rebase_system(
+    mut floating_origin: ResMut<FloatingOrigin>,
+    mut focus_query: Query<With<FloatingOriginFocus, (&Translation, Option<&WorldTranslation>)>>,
+    mut root_query: Query<Without<Parent, Without<WorldTranslation, &mut Translation>>>,
+) {
+    let focus = match focus_query.iter().iter().next() {
+        Some((_, Some(world_translation))) => *world_translation,
+        Some((translation, None)) => {
+            let mut focus = floating_origin.origin;
+            focus.translate(translation.0);
+            focus
+        }
+        None => return,
+    };
+
+    let offset = focus.relative_to(&floating_origin.origin);
+    if offset.length() <= floating_origin.rebase_distance {
+        return;
+    }
+
+    floating_origin.origin = focus;
+    for mut translation in &mut root_query.iter() {
+        translation.0 -= offset;
+    }
+}
+
+/// Writes the [Translation] of entities with a [WorldTranslation], relative to the [FloatingOrigin]. This is where
+/// double-precision translations are converted to the `f32` values used for rendering.
+pub fn world_translation_system(
+    floating_origin: Res<FloatingOrigin>,
+    mut query: Query<(&WorldTranslation, &mut Translation)>,
+) {
+    for (world_translation, mut translation) in &mut query.iter() {
+        let relative_translation = world_translation.relative_to(&floating_origin.origin);
+        if translation.0 != relative_translation {
+            translation.0 = relative_translation;
+        }
+    }
+}
+
+pub fn floating_origin_systems() -> Vec<Box<dyn System>> {
+    vec![
+        floating_origin_rebase_system.system(),
+        world_translation_system.system(),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_ecs::{Resources, Schedule, World};
+    use bevy_math::Vec3;
+
+    #[test]
+    fn rebase_origin() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(FloatingOrigin::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        for system in floating_origin_systems() {
+            schedule.add_system_to_stage("update", system);
+        }
+        schedule.initialize(&mut resources);
+
+        let ship = world.spawn((
+            FloatingOriginFocus,
+            WorldTranslation::new(1.0e9, 0.0, 0.0),
+            Translation::default(),
+        ));
+        let planet = world.spawn((
+            WorldTranslation::new(1.0e9 + 10.0, 0.5, 0.0),
+            Translation::default(),
+        ));
+        let debris = world.spawn((Translation::new(5.0, 0.0, 0.0),));
+
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            resources.get::<FloatingOrigin>().unwrap().origin,
+            WorldTranslation::new(1.0e9, 0.0, 0.0)
+        );
+        assert_eq!(world.get::<Translation>(ship).unwrap().0, Vec3::zero());
+        // precision is kept for entities near the origin, even though they are far from the world's origin
+        assert_eq!(
+            world.get::<Translation>(planet).unwrap().0,
+            Vec3::new(10.0, 0.5, 0.0)
+        );
+        assert_eq!(
+            world.get::<Translation>(debris).unwrap().0,
+            Vec3::new(5.0 - 1.0e9, 0.0, 0.0)
+        );
+
+        // small movements don't move the origin
+        world
+            .get_mut::<WorldTranslation>(ship)
+            .unwrap()
+            .translate(Vec3::new(100.0, 0.0, 0.0));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            resources.get::<FloatingOrigin>().unwrap().origin,
+            WorldTranslation::new(1.0e9, 0.0, 0.0)
+        );
+        assert_eq!(
+            world.get::<Translation>(ship).unwrap().0,
+            Vec3::new(100.0, 0.0, 0.0)
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use crate::prelude::{Children, LocalTransform, Parent, PreviousParent};
-use bevy_ecs::{Commands, CommandsInternal, Component, DynamicBundle, Entity, WorldWriter};
+use bevy_ecs::{
+    missing_entity_error, BevyResult, Commands, CommandsInternal, Component, DynamicBundle, Entity,
+    WorldWriter,
+};
 use smallvec::SmallVec;
 
 pub struct InsertChildren {
@@ -9,7 +12,7 @@ pub struct InsertChildren {
 }
 
 impl WorldWriter for InsertChildren {
-    fn write(self: Box<Self>, world: &mut bevy_ecs::World) {
+    fn write(self: Box<Self>, world: &mut bevy_ecs::World) -> BevyResult {
         for child in self.children.iter() {
             world
                 .insert(
@@ -20,7 +23,7 @@ impl WorldWriter for InsertChildren {
                         LocalTransform::default(),
                     ),
                 )
-                .unwrap();
+                .map_err(|_| missing_entity_error("add a child", *child))?;
         }
         {
             let mut added = false;
@@ -31,11 +34,13 @@ impl WorldWriter for InsertChildren {
 
             // NOTE: ideally this is just an else statement, but currently that _incorrectly_ fails borrow-checking
             if !added {
+                let parent = self.parent;
                 world
-                    .insert_one(self.parent, Children(SmallVec::from(self.children)))
-                    .unwrap();
+                    .insert_one(parent, Children(SmallVec::from(self.children)))
+                    .map_err(|_| missing_entity_error("add children to", parent))?;
             }
         }
+        Ok(())
     }
 }
 
@@ -50,7 +55,7 @@ pub struct ChildBuilder<'a> {
 }
 
 impl WorldWriter for PushChildren {
-    fn write(self: Box<Self>, world: &mut bevy_ecs::World) {
+    fn write(self: Box<Self>, world: &mut bevy_ecs::World) -> BevyResult {
         for child in self.children.iter() {
             world
                 .insert(
@@ -61,7 +66,7 @@ impl WorldWriter for PushChildren {
                         LocalTransform::default(),
                     ),
                 )
-                .unwrap();
+                .map_err(|_| missing_entity_error("add a child", *child))?;
         }
         {
             let mut added = false;
@@ -72,11 +77,13 @@ impl WorldWriter for PushChildren {
 
             // NOTE: ideally this is just an else statement, but currently that _incorrectly_ fails borrow-checking
             if !added {
+                let parent = self.parent;
                 world
-                    .insert_one(self.parent, Children(SmallVec::from(self.children)))
-                    .unwrap();
+                    .insert_one(parent, Children(SmallVec::from(self.children)))
+                    .map_err(|_| missing_entity_error("add children to", parent))?;
             }
         }
+        Ok(())
     }
 }
 
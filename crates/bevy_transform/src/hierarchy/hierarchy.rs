@@ -1,5 +1,5 @@
 use crate::components::Children;
-use bevy_ecs::{Commands, Entity, Query, World, WorldWriter};
+use bevy_ecs::{missing_entity_error, BevyResult, Commands, Entity, Query, World, WorldWriter};
 
 pub fn run_on_hierarchy<T, S>(
     children_query: &Query<&Children>,
@@ -46,7 +46,7 @@ pub struct DespawnRecursive {
     entity: Entity,
 }
 
-fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
+fn despawn_with_children_recursive(world: &mut World, entity: Entity) -> BevyResult {
     if let Some(children) = world.get::<Children>(entity).ok().map(|children| {
         children
             .0
@@ -55,16 +55,18 @@ fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
             .collect::<Vec<Entity>>()
     }) {
         for e in children {
-            despawn_with_children_recursive(world, e);
+            despawn_with_children_recursive(world, e)?;
         }
     }
 
-    world.despawn(entity).unwrap();
+    world
+        .despawn(entity)
+        .map_err(|_| missing_entity_error("despawn", entity))
 }
 
 impl WorldWriter for DespawnRecursive {
-    fn write(self: Box<Self>, world: &mut World) {
-        despawn_with_children_recursive(world, self.entity);
+    fn write(self: Box<Self>, world: &mut World) -> BevyResult {
+        despawn_with_children_recursive(world, self.entity)
     }
 }
 
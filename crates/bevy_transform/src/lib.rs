@@ -1,18 +1,28 @@
 pub mod components;
+pub mod floating_origin_systems;
 pub mod hierarchy;
 pub mod local_transform_systems;
+pub mod transform_interpolation_systems;
 pub mod transform_propagate_system;
 pub mod transform_systems;
+pub mod validation;
 
 pub mod prelude {
-    pub use crate::{components::*, hierarchy::*, TransformPlugin};
+    pub use crate::{
+        components::*,
+        floating_origin_systems::{FloatingOrigin, FloatingOriginFocus},
+        hierarchy::*,
+        TransformPlugin,
+    };
 }
 
 use bevy_app::prelude::*;
+use bevy_core::{fixed_stage, AddFixedSystem};
 use bevy_ecs::prelude::*;
 use bevy_type_registry::RegisterType;
 use prelude::{
-    Children, LocalTransform, NonUniformScale, Parent, Rotation, Scale, Transform, Translation,
+    Children, FloatingOrigin, LocalTransform, NonUniformScale, Parent, Rotation, Scale, Transform,
+    Translation, WorldTranslation,
 };
 
 pub(crate) fn transform_systems() -> Vec<Box<dyn System>> {
@@ -39,8 +49,32 @@ impl Plugin for TransformPlugin {
             .register_component::<Rotation>()
             .register_component::<Scale>()
             .register_component::<NonUniformScale>()
+            .register_component::<WorldTranslation>()
+            .register_component_validator(validation::validate_local_transform)
+            .register_component_validator(validation::validate_transform)
+            .register_component_validator(validation::validate_translation)
+            .register_component_validator(validation::validate_rotation)
+            .register_component_validator(validation::validate_scale)
+            .register_component_validator(validation::validate_non_uniform_scale)
+            .register_component_validator(validation::validate_world_translation)
+            .init_resource::<FloatingOrigin>()
             // add transform systems to startup so the first update is "correct"
+            .add_startup_systems(floating_origin_systems::floating_origin_systems())
             .add_startup_systems(transform_systems())
-            .add_systems_to_stage(stage::POST_UPDATE, transform_systems());
+            // world translations are converted before transforms are calculated from them
+            .add_systems_to_stage(
+                stage::POST_UPDATE,
+                floating_origin_systems::floating_origin_systems(),
+            )
+            .add_systems_to_stage(stage::POST_UPDATE, transform_systems())
+            // interpolated transforms replace the ones calculated from the last fixed update
+            .add_system_to_stage(
+                stage::POST_UPDATE,
+                transform_interpolation_systems::transform_interpolation_system.system(),
+            )
+            .add_fixed_system_to_stage(
+                fixed_stage::LAST,
+                transform_interpolation_systems::transform_interpolation_record_system.system(),
+            );
     }
 }
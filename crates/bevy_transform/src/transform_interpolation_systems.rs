@@ -0,0 +1,98 @@
+use crate::{components::*, transform_propagate_system::propagate_recursive};
+use bevy_core::FixedTimestep;
+use bevy_ecs::prelude::*;
+use bevy_math::{Quat, Vec3};
+
+/// Records the state of each [TransformInterpolation] entity. This runs at the end of every fixed update.
+pub fn transform_interpolation_record_system(
+    mut query: Query<(
+        &mut TransformInterpolation,
+        Option<&Translation>,
+        Option<&Rotation>,
+        Option<&Scale>,
+        Option<&NonUniformScale>,
+    )>,
+) {
+    for (mut interpolation, translation, rotation, scale, non_uniform_scale) in &mut query.iter() {
+        let scale = match (non_uniform_scale, scale) {
+            (Some(non_uniform_scale), _) => non_uniform_scale.0,
+            (None, Some(scale)) => Vec3::splat(scale.0),
+            (None, None) => Vec3::one(),
+        };
+        interpolation.record(TransformState {
+            translation: translation.map(|t| t.0).unwrap_or_else(Vec3::zero),
+            rotation: rotation.map(|r| r.0).unwrap_or_else(Quat::identity),
+            scale,
+        });
+    }
+}
+
+/// Writes the [Transform] of root [TransformInterpolation] entities, blended between the last two fixed updates by
+/// how far the frame is into the next one. Children are propagated again so they follow the interpolated entity.
+pub fn transform_interpolation_system(
+    fixed_timestep: Res<FixedTimestep>,
+    mut root_query: Query<
+        Without<Parent, (&TransformInterpolation, &mut Transform, Option<&Children>)>,
+    >,
+    mut local_transform_query: Query<(&mut Transform, &LocalTransform, Option<&Children>)>,
+) {
+    let t = fixed_timestep.overstep_fraction().min(1.0);
+    for (interpolation, mut transform, children) in &mut root_query.iter() {
+        if !transform.sync || !interpolation.is_recorded() {
+            continue;
+        }
+
+        transform.value = interpolation.interpolate(t).to_mat4();
+        if let Some(children) = children {
+            for child in children.0.iter() {
+                propagate_recursive(*transform, &mut local_transform_query, *child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_ecs::{Resources, Schedule, World};
+    use bevy_math::Mat4;
+    use std::time::Duration;
+
+    #[test]
+    fn interpolate_between_fixed_updates() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(FixedTimestep::from_seconds(0.1));
+
+        let mut fixed_schedule = Schedule::default();
+        fixed_schedule.add_stage("last");
+        fixed_schedule.add_system_to_stage("last", transform_interpolation_record_system.system());
+        fixed_schedule.initialize(&mut resources);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("post_update");
+        schedule.add_system_to_stage("post_update", transform_interpolation_system.system());
+        schedule.initialize(&mut resources);
+
+        let entity = world.spawn((
+            TransformInterpolation::default(),
+            Translation::new(0.0, 0.0, 0.0),
+            Transform::identity(),
+        ));
+        fixed_schedule.run(&mut world, &mut resources);
+
+        world.get_mut::<Translation>(entity).unwrap().0 = Vec3::new(4.0, 0.0, 0.0);
+        fixed_schedule.run(&mut world, &mut resources);
+
+        // a quarter of the way to the next fixed update
+        resources
+            .get_mut::<FixedTimestep>()
+            .unwrap()
+            .update(Duration::from_millis(25));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().value,
+            Mat4::from_translation(Vec3::new(1.0, 0.0, 0.0))
+        );
+    }
+}
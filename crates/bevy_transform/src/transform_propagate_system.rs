@@ -20,7 +20,7 @@ pub fn transform_propagate_system(
     }
 }
 
-fn propagate_recursive(
+pub(crate) fn propagate_recursive(
     parent_local_to_world: Transform,
     local_transform_query: &mut Query<(&mut Transform, &LocalTransform, Option<&Children>)>,
     entity: Entity,
@@ -0,0 +1,87 @@
+//! Component validators that catch malformed transforms, ex: NaN values in scene files, before they propagate
+//! through the hierarchy
+
+use crate::components::{
+    LocalTransform, NonUniformScale, Rotation, Scale, Transform, Translation, WorldTranslation,
+};
+use bevy_math::{Mat4, Vec3, Vec4};
+
+fn check_finite(values: &[f32]) -> Result<(), String> {
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(())
+    } else {
+        Err(format!("{:?} contains a NaN or infinite value", values))
+    }
+}
+
+fn check_mat4(value: &Mat4) -> Result<(), String> {
+    check_finite(&value.to_cols_array())
+}
+
+fn check_vec3(value: Vec3) -> Result<(), String> {
+    check_finite(&[value.x(), value.y(), value.z()])
+}
+
+pub fn validate_transform(transform: &mut Transform) -> Result<(), String> {
+    check_mat4(&transform.value)
+}
+
+pub fn validate_local_transform(local_transform: &mut LocalTransform) -> Result<(), String> {
+    check_mat4(&local_transform.0)
+}
+
+pub fn validate_translation(translation: &mut Translation) -> Result<(), String> {
+    check_vec3(translation.0)
+}
+
+/// Rejects rotations that aren't finite or have a length of zero, and normalizes the rest
+pub fn validate_rotation(rotation: &mut Rotation) -> Result<(), String> {
+    let value = Vec4::from(rotation.0);
+    check_finite(&[value.x(), value.y(), value.z(), value.w()])?;
+    if rotation.0.length_squared() == 0.0 {
+        return Err("the rotation has a length of zero".to_string());
+    }
+    if !rotation.0.is_normalized() {
+        rotation.0 = rotation.0.normalize();
+    }
+    Ok(())
+}
+
+pub fn validate_scale(scale: &mut Scale) -> Result<(), String> {
+    check_finite(&[scale.0])
+}
+
+pub fn validate_world_translation(world_translation: &mut WorldTranslation) -> Result<(), String> {
+    let values = [
+        world_translation.x,
+        world_translation.y,
+        world_translation.z,
+    ];
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(())
+    } else {
+        Err(format!("{:?} contains a NaN or infinite value", values))
+    }
+}
+
+pub fn validate_non_uniform_scale(non_uniform_scale: &mut NonUniformScale) -> Result<(), String> {
+    check_vec3(non_uniform_scale.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_rotation, validate_translation};
+    use crate::components::{Rotation, Translation};
+    use bevy_math::Quat;
+
+    #[test]
+    fn transform_validation() {
+        assert!(validate_translation(&mut Translation::new(1.0, 2.0, 3.0)).is_ok());
+        assert!(validate_translation(&mut Translation::new(std::f32::NAN, 2.0, 3.0)).is_err());
+
+        let mut rotation = Rotation(Quat::from_xyzw(0.0, 0.0, 2.0, 0.0));
+        assert!(validate_rotation(&mut rotation).is_ok());
+        assert_eq!(rotation.0, Quat::from_xyzw(0.0, 0.0, 1.0, 0.0));
+        assert!(validate_rotation(&mut Rotation(Quat::from_xyzw(0.0, 0.0, 0.0, 0.0))).is_err());
+    }
+}
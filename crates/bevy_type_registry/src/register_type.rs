@@ -1,4 +1,4 @@
-use crate::TypeRegistry;
+use crate::{ComponentDefault, ComponentMigration, ComponentValidator, TypeRegistry};
 use bevy_app::AppBuilder;
 use bevy_ecs::{Component, FromResources};
 use bevy_property::{DeserializeProperty, Properties, Property};
@@ -7,6 +7,22 @@ pub trait RegisterType {
     fn register_component<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + Component + FromResources;
+    /// Sets the validator of a registered component, see [ComponentRegistry::set_validator](crate::ComponentRegistry::set_validator)
+    fn register_component_validator<T>(&mut self, validator: ComponentValidator<T>) -> &mut Self
+    where
+        T: Component;
+    /// Sets the default value of a registered component, see [ComponentRegistry::set_default](crate::ComponentRegistry::set_default)
+    fn register_component_default<T>(&mut self, default: ComponentDefault<T>) -> &mut Self
+    where
+        T: Component;
+    /// Adds a migration to a registered component, see [ComponentRegistry::add_migration](crate::ComponentRegistry::add_migration)
+    fn register_component_migration<T>(
+        &mut self,
+        from_version: u32,
+        migration: ComponentMigration,
+    ) -> &mut Self
+    where
+        T: Component;
     fn register_properties<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + FromResources;
@@ -28,6 +44,55 @@ impl RegisterType for AppBuilder {
         self
     }
 
+    fn register_component_validator<T>(&mut self, validator: ComponentValidator<T>) -> &mut Self
+    where
+        T: Component,
+    {
+        {
+            let type_registry = self.app.resources.get::<TypeRegistry>().unwrap();
+            type_registry
+                .component
+                .write()
+                .unwrap()
+                .set_validator(validator);
+        }
+        self
+    }
+
+    fn register_component_default<T>(&mut self, default: ComponentDefault<T>) -> &mut Self
+    where
+        T: Component,
+    {
+        {
+            let type_registry = self.app.resources.get::<TypeRegistry>().unwrap();
+            type_registry
+                .component
+                .write()
+                .unwrap()
+                .set_default(default);
+        }
+        self
+    }
+
+    fn register_component_migration<T>(
+        &mut self,
+        from_version: u32,
+        migration: ComponentMigration,
+    ) -> &mut Self
+    where
+        T: Component,
+    {
+        {
+            let type_registry = self.app.resources.get::<TypeRegistry>().unwrap();
+            type_registry
+                .component
+                .write()
+                .unwrap()
+                .add_migration::<T>(from_version, migration);
+        }
+        self
+    }
+
     fn register_properties<T>(&mut self) -> &mut Self
     where
         T: Properties + DeserializeProperty + Component + FromResources,
@@ -1,10 +1,14 @@
 use bevy_ecs::{Archetype, Component, Entity, FromResources, Resources, World};
-use bevy_property::{Properties, Property, PropertyTypeRegistration, PropertyTypeRegistry};
+use bevy_property::{
+    DynamicProperties, Properties, Property, PropertyType, PropertyTypeRegistration,
+    PropertyTypeRegistry,
+};
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
 };
+use thiserror::Error;
 
 #[derive(Clone, Default)]
 pub struct TypeRegistry {
@@ -39,6 +43,42 @@ impl ComponentRegistry {
         self.registrations.insert(registration.ty, registration);
     }
 
+    /// Sets the hook that checks `T` components when they are spawned from a scene or edited through their
+    /// registration. Validators can fix invalid values in place, ex: by clamping them, or reject the component.
+    pub fn set_validator<T: Component>(&mut self, validator: ComponentValidator<T>) {
+        self.get_registration_mut::<T>().hooks.validator = Some(Arc::new(validator));
+    }
+
+    /// Sets the function that creates the default value of `T` components, which scene data is applied on top of.
+    /// Without one, components are created with [FromResources].
+    pub fn set_default<T: Component>(&mut self, default: ComponentDefault<T>) {
+        self.get_registration_mut::<T>().hooks.default = Some(Arc::new(default));
+    }
+
+    /// Adds the migration that converts serialized `T` components from `from_version` to `from_version + 1`, and bumps
+    /// the version of `T` to at least `from_version + 1`. Components start at version 0, and scenes record the version
+    /// their components were saved with, so older data is migrated one version at a time when it is loaded.
+    pub fn add_migration<T: Component>(
+        &mut self,
+        from_version: u32,
+        migration: ComponentMigration,
+    ) {
+        let registration = self.get_registration_mut::<T>();
+        registration.migrations.insert(from_version, migration);
+        registration.version = registration.version.max(from_version + 1);
+    }
+
+    fn get_registration_mut<T: Component>(&mut self) -> &mut ComponentRegistration {
+        self.registrations
+            .get_mut(&TypeId::of::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Component {} must be registered before its hooks are set",
+                    std::any::type_name::<T>()
+                )
+            })
+    }
+
     pub fn get(&self, type_id: &TypeId) -> Option<&ComponentRegistration> {
         self.registrations.get(type_id)
     }
@@ -69,12 +109,101 @@ impl ComponentRegistry {
     }
 }
 
+/// Checks a component's value, and either fixes it in place or returns why it is invalid
+pub type ComponentValidator<T> = fn(&mut T) -> Result<(), String>;
+
+/// Creates the default value of a component
+pub type ComponentDefault<T> = fn(&Resources) -> T;
+
+/// Converts the serialized properties of a component from one version to the next, ex: by renaming a field
+pub type ComponentMigration = fn(&mut DynamicProperties) -> Result<(), String>;
+
+#[derive(Error, Debug)]
+#[error("Invalid {type_name} component: {reason}")]
+pub struct ComponentValidationError {
+    pub type_name: &'static str,
+    pub reason: String,
+}
+
+#[derive(Error, Debug)]
+pub enum ComponentMigrationError {
+    #[error("{type_name} has no migration from version {version}")]
+    MissingMigration {
+        type_name: &'static str,
+        version: u32,
+    },
+    #[error("{type_name} version {version} is newer than the current version {current_version}")]
+    NewerVersion {
+        type_name: &'static str,
+        version: u32,
+        current_version: u32,
+    },
+    #[error("Failed to migrate {type_name} from version {version}: {reason}")]
+    Failed {
+        type_name: &'static str,
+        version: u32,
+        reason: String,
+    },
+}
+
+/// The optional hooks of a [ComponentRegistration]. They hold a [ComponentValidator] and a [ComponentDefault] of the
+/// registered type.
+#[derive(Clone, Default)]
+struct ComponentHooks {
+    validator: Option<Arc<dyn Any + Send + Sync>>,
+    default: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl ComponentHooks {
+    fn validate<T: Component>(&self, component: &mut T) -> Result<(), ComponentValidationError> {
+        match self
+            .validator
+            .as_ref()
+            .and_then(|validator| validator.downcast_ref::<ComponentValidator<T>>())
+        {
+            Some(validator) => validator(component).map_err(|reason| ComponentValidationError {
+                type_name: std::any::type_name::<T>(),
+                reason,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn create<T: Component + FromResources>(&self, resources: &Resources) -> T {
+        match self
+            .default
+            .as_ref()
+            .and_then(|default| default.downcast_ref::<ComponentDefault<T>>())
+        {
+            Some(default) => default(resources),
+            None => T::from_resources(resources),
+        }
+    }
+}
+
+type ComponentAddFn = fn(
+    &mut World,
+    &Resources,
+    Entity,
+    &dyn Property,
+    &ComponentHooks,
+) -> Result<(), ComponentValidationError>;
+type ComponentApplyFn =
+    fn(&mut World, Entity, &dyn Property, &ComponentHooks) -> Result<(), ComponentValidationError>;
+
 #[derive(Clone)]
 pub struct ComponentRegistration {
     pub ty: TypeId,
-    component_add_fn: fn(&mut World, resources: &Resources, Entity, &dyn Property),
-    component_apply_fn: fn(&mut World, Entity, &dyn Property),
+    hooks: ComponentHooks,
+    version: u32,
+    migrations: HashMap<u32, ComponentMigration>,
+    component_add_fn: ComponentAddFn,
+    component_apply_fn: ComponentApplyFn,
+    component_default_fn: fn(&Resources, &ComponentHooks) -> DynamicProperties,
     component_properties_fn: fn(&Archetype, usize) -> &dyn Properties,
+    component_dynamic_fn: fn(&World, Entity) -> Option<DynamicProperties>,
+    component_get_prop_fn: fn(&World, Entity, &str) -> Option<Box<dyn Property>>,
+    component_set_prop_fn: fn(&mut World, Entity, &str, &dyn Property, &ComponentHooks) -> bool,
     pub short_name: String,
     pub long_name: &'static str,
 }
@@ -84,17 +213,34 @@ impl ComponentRegistration {
         let ty = TypeId::of::<T>();
         Self {
             ty,
+            hooks: ComponentHooks::default(),
+            version: 0,
+            migrations: HashMap::new(),
             component_add_fn: |world: &mut World,
                                resources: &Resources,
                                entity: Entity,
-                               property: &dyn Property| {
-                let mut component = T::from_resources(resources);
+                               property: &dyn Property,
+                               hooks: &ComponentHooks| {
+                let mut component = hooks.create::<T>(resources);
                 component.apply(property);
+                hooks.validate(&mut component)?;
                 world.insert_one(entity, component).unwrap();
+                Ok(())
             },
-            component_apply_fn: |world: &mut World, entity: Entity, property: &dyn Property| {
+            component_apply_fn: |world: &mut World,
+                                 entity: Entity,
+                                 property: &dyn Property,
+                                 hooks: &ComponentHooks| {
                 let mut component = world.get_mut::<T>(entity).unwrap();
+                let previous = component.to_dynamic();
                 component.apply(property);
+                hooks.validate(&mut *component).map_err(|err| {
+                    component.apply(&previous);
+                    err
+                })
+            },
+            component_default_fn: |resources: &Resources, hooks: &ComponentHooks| {
+                hooks.create::<T>(resources).to_dynamic()
             },
             component_properties_fn: |archetype: &Archetype, index: usize| {
                 // the type has been looked up by the caller, so this is safe
@@ -107,28 +253,101 @@ impl ComponentRegistration {
                     ptr.as_ref().unwrap()
                 }
             },
+            component_dynamic_fn: |world: &World, entity: Entity| {
+                world
+                    .get::<T>(entity)
+                    .ok()
+                    .map(|component| component.to_dynamic())
+            },
+            component_get_prop_fn: |world: &World, entity: Entity, name: &str| {
+                let component = world.get::<T>(entity).ok()?;
+                component.prop(name).map(|prop| prop.clone_prop())
+            },
+            component_set_prop_fn: |world: &mut World,
+                                    entity: Entity,
+                                    name: &str,
+                                    value: &dyn Property,
+                                    hooks: &ComponentHooks| {
+                if let Ok(mut component) = world.get_mut::<T>(entity) {
+                    if let Some(prop) = component.prop_mut(name) {
+                        if !is_same_property_type(prop, value) {
+                            return false;
+                        }
+                        let previous = prop.clone_prop();
+                        prop.set(value);
+                        if hooks.validate(&mut *component).is_ok() {
+                            return true;
+                        }
+                        // the validator rejected the value, so the previous one is restored
+                        component.prop_mut(name).unwrap().set(&*previous);
+                    }
+                }
+                false
+            },
             short_name: PropertyTypeRegistration::get_short_name(std::any::type_name::<T>()),
             long_name: std::any::type_name::<T>(),
         }
     }
 
+    /// The current version of the component, see [ComponentRegistry::add_migration]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Migrates the serialized `properties` of this component from `version` to the current version
+    pub fn migrate(
+        &self,
+        properties: &mut DynamicProperties,
+        version: u32,
+    ) -> Result<(), ComponentMigrationError> {
+        if version > self.version {
+            return Err(ComponentMigrationError::NewerVersion {
+                type_name: self.long_name,
+                version,
+                current_version: self.version,
+            });
+        }
+
+        for version in version..self.version {
+            let migration =
+                self.migrations
+                    .get(&version)
+                    .ok_or(ComponentMigrationError::MissingMigration {
+                        type_name: self.long_name,
+                        version,
+                    })?;
+            migration(properties).map_err(|reason| ComponentMigrationError::Failed {
+                type_name: self.long_name,
+                version,
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
     pub fn add_component_to_entity(
         &self,
         world: &mut World,
         resources: &Resources,
         entity: Entity,
         property: &dyn Property,
-    ) {
-        (self.component_add_fn)(world, resources, entity, property);
+    ) -> Result<(), ComponentValidationError> {
+        (self.component_add_fn)(world, resources, entity, property, &self.hooks)
     }
 
+    /// Applies `property` to `entity`'s component. The component is left unchanged if the result is invalid.
     pub fn apply_component_to_entity(
         &self,
         world: &mut World,
         entity: Entity,
         property: &dyn Property,
-    ) {
-        (self.component_apply_fn)(world, entity, property);
+    ) -> Result<(), ComponentValidationError> {
+        (self.component_apply_fn)(world, entity, property, &self.hooks)
+    }
+
+    /// Returns the default value of the component, ex: to reset a component in an editor
+    pub fn get_component_default(&self, resources: &Resources) -> DynamicProperties {
+        (self.component_default_fn)(resources, &self.hooks)
     }
 
     pub fn get_component_properties<'a>(
@@ -138,4 +357,149 @@ impl ComponentRegistration {
     ) -> &'a dyn Properties {
         (self.component_properties_fn)(archetype, entity_index)
     }
+
+    /// Returns a copy of `entity`'s component, or `None` if `entity` doesn't have this component
+    pub fn get_component_dynamic(
+        &self,
+        world: &World,
+        entity: Entity,
+    ) -> Option<DynamicProperties> {
+        (self.component_dynamic_fn)(world, entity)
+    }
+
+    /// Returns a copy of the field called `name` on `entity`'s component
+    pub fn get_component_prop(
+        &self,
+        world: &World,
+        entity: Entity,
+        name: &str,
+    ) -> Option<Box<dyn Property>> {
+        (self.component_get_prop_fn)(world, entity, name)
+    }
+
+    /// Sets the field called `name` on `entity`'s component. Returns false if `entity` doesn't have this component,
+    /// the component doesn't have a field called `name`, the value doesn't have the field's type, or the component's
+    /// validator rejected the value.
+    pub fn set_component_prop(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        name: &str,
+        value: &dyn Property,
+    ) -> bool {
+        (self.component_set_prop_fn)(world, entity, name, value, &self.hooks)
+    }
+}
+
+/// Returns true if `value` can be set on `prop` without panicking
+fn is_same_property_type(prop: &dyn Property, value: &dyn Property) -> bool {
+    match prop.property_type() {
+        PropertyType::Value => Any::type_id(prop.any()) == Any::type_id(value.any()),
+        property_type => property_type == value.property_type(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentMigrationError, ComponentRegistry};
+    use bevy_ecs::{Resources, World};
+    use bevy_property::{DynamicProperties, Properties, PropertiesVal};
+
+    #[derive(Properties, Default)]
+    struct Size {
+        width: f32,
+        height: f32,
+    }
+
+    #[test]
+    fn component_hooks() {
+        let mut world = World::default();
+        let resources = Resources::default();
+        let mut registry = ComponentRegistry::default();
+        registry.register::<Size>();
+        registry.set_default::<Size>(|_| Size {
+            width: 1.0,
+            height: 1.0,
+        });
+        registry.set_validator::<Size>(|size| {
+            if size.width.is_nan() || size.height.is_nan() {
+                return Err("size is NaN".to_string());
+            }
+            size.width = size.width.max(0.0);
+            size.height = size.height.max(0.0);
+            Ok(())
+        });
+        let registration = registry.get_with_name("Size").unwrap();
+
+        // missing fields use the default value, and invalid values are clamped
+        let mut properties = DynamicProperties::map();
+        properties.set("width", -5.0f32);
+        let entity = world.spawn((0,));
+        registration
+            .add_component_to_entity(&mut world, &resources, entity, &properties)
+            .unwrap();
+        {
+            let size = world.get::<Size>(entity).unwrap();
+            assert_eq!((size.width, size.height), (0.0, 1.0));
+        }
+
+        // rejected values leave the component unchanged
+        properties.set("width", std::f32::NAN);
+        assert!(registration
+            .apply_component_to_entity(&mut world, entity, &properties)
+            .is_err());
+        assert!(!registration.set_component_prop(&mut world, entity, "height", &std::f32::NAN));
+
+        // values of the wrong type are rejected instead of panicking
+        assert!(!registration.set_component_prop(&mut world, entity, "height", &2.0f64));
+        assert!(!registration.set_component_prop(&mut world, entity, "width", &"wide".to_string()));
+        {
+            let size = world.get::<Size>(entity).unwrap();
+            assert_eq!((size.width, size.height), (0.0, 1.0));
+        }
+
+        assert!(registration.set_component_prop(&mut world, entity, "height", &2.0f32));
+        assert_eq!(world.get::<Size>(entity).unwrap().height, 2.0);
+
+        let default = registration.get_component_default(&resources);
+        assert_eq!(default.prop_val::<f32>("width"), Some(&1.0));
+    }
+
+    #[test]
+    fn component_migrations() {
+        let mut registry = ComponentRegistry::default();
+        registry.register::<Size>();
+        // version 1 renamed "w" to "width", and version 2 doubled the width
+        registry.add_migration::<Size>(0, |properties| {
+            if properties.rename("w", "width") {
+                Ok(())
+            } else {
+                Err("missing w".to_string())
+            }
+        });
+        registry.add_migration::<Size>(1, |properties| {
+            let width = *properties.prop_val::<f32>("width").unwrap();
+            properties.set("width", width * 2.0);
+            Ok(())
+        });
+        let registration = registry.get_with_name("Size").unwrap();
+        assert_eq!(registration.version(), 2);
+
+        let mut properties = DynamicProperties::map();
+        properties.set("w", 2.0f32);
+        properties.set("height", 3.0f32);
+        registration.migrate(&mut properties, 0).unwrap();
+        assert_eq!(properties.prop_val::<f32>("width"), Some(&4.0));
+        assert_eq!(properties.prop_val::<f32>("height"), Some(&3.0));
+        assert!(properties.prop("w").is_none());
+
+        assert!(matches!(
+            registration.migrate(&mut properties, 0),
+            Err(ComponentMigrationError::Failed { version: 0, .. })
+        ));
+        assert!(matches!(
+            registration.migrate(&mut properties, 3),
+            Err(ComponentMigrationError::NewerVersion { version: 3, .. })
+        ));
+    }
 }
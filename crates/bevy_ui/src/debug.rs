@@ -0,0 +1,205 @@
+use crate::{entity::NodeComponents, Node, PositionType, Style, UiScale, Val, ZIndex};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Commands, Entity, Local, Query, Res, ResMut, With, Without};
+use bevy_math::{Rect, Size, Vec2};
+use bevy_render::color::Color;
+use bevy_sprite::ColorMaterial;
+use bevy_transform::prelude::{Parent, Transform};
+use bevy_window::Windows;
+use std::collections::{HashMap, HashSet};
+
+/// Configures the ui debug overlay, which outlines the margin box, rect, and content box (inside the padding) of every
+/// ui [Node]. The overlay is disabled by default.
+#[derive(Debug, Clone)]
+pub struct UiDebugOptions {
+    pub enabled: bool,
+    pub margin_color: Color,
+    pub rect_color: Color,
+    pub padding_color: Color,
+    /// The width of the outlines in logical pixels
+    pub thickness: f32,
+}
+
+impl Default for UiDebugOptions {
+    fn default() -> Self {
+        UiDebugOptions {
+            enabled: false,
+            margin_color: Color::rgb(1.0, 0.6, 0.0),
+            rect_color: Color::rgb(1.0, 0.0, 0.0),
+            padding_color: Color::rgb(0.0, 0.8, 0.2),
+            thickness: 1.0,
+        }
+    }
+}
+
+/// Marks the nodes that draw the debug overlay. They are not outlined themselves.
+pub struct UiDebugOutline;
+
+#[derive(Default)]
+pub struct UiDebugState {
+    outlines: HashMap<Entity, Vec<Entity>>,
+    materials: Option<[Handle<ColorMaterial>; 3]>,
+}
+
+pub fn ui_debug_system(
+    mut commands: Commands,
+    mut state: Local<UiDebugState>,
+    options: Res<UiDebugOptions>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut node_query: Query<
+        Without<UiDebugOutline, (Entity, &Node, &Style, &Transform, Option<&Parent>)>,
+    >,
+    parent_query: Query<&Node>,
+    outline_query: Query<With<UiDebugOutline, &mut Style>>,
+) {
+    if !options.enabled {
+        for (_node, outlines) in state.outlines.drain() {
+            for outline in outlines {
+                commands.despawn(outline);
+            }
+        }
+        return;
+    }
+
+    let colors = [
+        options.margin_color,
+        options.rect_color,
+        options.padding_color,
+    ];
+    let handles = match state.materials {
+        Some(handles) => handles,
+        None => {
+            let handles = [
+                materials.add(colors[0].into()),
+                materials.add(colors[1].into()),
+                materials.add(colors[2].into()),
+            ];
+            state.materials = Some(handles);
+            handles
+        }
+    };
+    for (handle, color) in handles.iter().zip(colors.iter()) {
+        // avoid sending modified events for unchanged materials
+        if materials.get(handle).map(|material| material.color) != Some(*color) {
+            if let Some(material) = materials.get_mut(handle) {
+                material.color = *color;
+            }
+        }
+    }
+
+    // nodes are drawn in physical pixels, but the overlay's styles are in logical pixels
+    let scale_factor = ui_scale.primary_scale_factor(&windows) as f32;
+    let window_width = windows
+        .get_primary()
+        .map(|window| window.width as f32 / scale_factor)
+        .unwrap_or(0.0);
+
+    let mut nodes = HashSet::new();
+    for (entity, node, style, transform, parent) in &mut node_query.iter() {
+        nodes.insert(entity);
+
+        // like stretch, percentages of margins, borders and padding resolve against the parent's width
+        let parent_width = parent
+            .and_then(|parent| parent_query.get::<Node>(parent.0).ok())
+            .map(|parent_node| parent_node.size.x() / scale_factor)
+            .unwrap_or(window_width);
+        let margin = resolve_insets(&style.margin, parent_width);
+        let border = resolve_insets(&style.border, parent_width);
+        let padding = resolve_insets(&style.padding, parent_width);
+
+        let size = node.size / scale_factor;
+        let min = transform.value.w_axis().truncate().truncate() / scale_factor - size / 2.0;
+        let max = min + size;
+        let rects = [
+            (min - margin.0, max + margin.1),
+            (min, max),
+            (min + border.0 + padding.0, max - border.1 - padding.1),
+        ];
+
+        let mut styles = Vec::with_capacity(12);
+        for (min, max) in rects.iter() {
+            for (position, size) in outline_edges(*min, *max, options.thickness).iter() {
+                styles.push(edge_style(*position, *size));
+            }
+        }
+
+        if let Some(outlines) = state.outlines.get(&entity) {
+            for (outline, edge_style) in outlines.iter().zip(styles.into_iter()) {
+                if let Ok(mut style) = outline_query.get_mut::<Style>(*outline) {
+                    // avoid mutating unchanged styles, which would trigger a layout
+                    if style.position != edge_style.position || style.size != edge_style.size {
+                        *style = edge_style;
+                    }
+                }
+            }
+        } else {
+            let mut outlines = Vec::with_capacity(styles.len());
+            for (index, style) in styles.into_iter().enumerate() {
+                commands
+                    .spawn(NodeComponents {
+                        style,
+                        material: handles[index / 4],
+                        ..Default::default()
+                    })
+                    .with(UiDebugOutline)
+                    .with(ZIndex::Global(i32::MAX));
+                outlines.push(commands.current_entity().unwrap());
+            }
+            state.outlines.insert(entity, outlines);
+        }
+    }
+
+    // remove the outlines of despawned nodes
+    let removed = state
+        .outlines
+        .keys()
+        .filter(|entity| !nodes.contains(entity))
+        .cloned()
+        .collect::<Vec<Entity>>();
+    for entity in removed {
+        for outline in state.outlines.remove(&entity).unwrap() {
+            commands.despawn(outline);
+        }
+    }
+}
+
+/// Returns the (left, bottom) and (right, top) insets of `rect` in logical pixels
+fn resolve_insets(rect: &Rect<Val>, parent_width: f32) -> (Vec2, Vec2) {
+    let resolve = |val: Val| match val {
+        Val::Px(value) => value,
+        Val::Percent(percent) => parent_width * percent / 100.0,
+        Val::Undefined | Val::Auto => 0.0,
+    };
+    (
+        Vec2::new(resolve(rect.left), resolve(rect.bottom)),
+        Vec2::new(resolve(rect.right), resolve(rect.top)),
+    )
+}
+
+/// Returns the position and size of the left, right, bottom, and top edges of the given rect's outline
+fn outline_edges(min: Vec2, max: Vec2, thickness: f32) -> [(Vec2, Vec2); 4] {
+    let size = (max - min).max(Vec2::zero());
+    let vertical = Vec2::new(thickness, size.y());
+    let horizontal = Vec2::new(size.x(), thickness);
+    [
+        (min, vertical),
+        (Vec2::new(max.x() - thickness, min.y()), vertical),
+        (min, horizontal),
+        (Vec2::new(min.x(), max.y() - thickness), horizontal),
+    ]
+}
+
+fn edge_style(position: Vec2, size: Vec2) -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        position: Rect {
+            left: Val::Px(position.x()),
+            bottom: Val::Px(position.y()),
+            ..Default::default()
+        },
+        size: Size::new(Val::Px(size.x()), Val::Px(size.y())),
+        ..Default::default()
+    }
+}
@@ -1,8 +1,8 @@
-use super::Node;
+use super::{ComputedLayout, Node};
 use crate::{
     render::UI_PIPELINE_HANDLE,
-    widget::{Button, Image, Text},
-    CalculatedSize, FocusPolicy, Interaction, Style,
+    widget::{Button, FillMaterial, Image, ProgressBar, RadialFill, Text, TextEditor},
+    CalculatedSize, ContentAlign, FocusPolicy, Interaction, Style,
 };
 use bevy_asset::Handle;
 use bevy_ecs::Bundle;
@@ -21,6 +21,7 @@ use bevy_transform::{
 #[derive(Bundle)]
 pub struct NodeComponents {
     pub node: Node,
+    pub computed_layout: ComputedLayout,
     pub style: Style,
     pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
     pub material: Handle<ColorMaterial>,
@@ -48,11 +49,22 @@ impl Default for NodeComponents {
                             bind_group: 1,
                             binding: 1,
                         },
+                        // Node_opacity
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 2,
+                        },
+                        // Node_clip
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 3,
+                        },
                     ],
                     ..Default::default()
                 },
             )]),
             node: Default::default(),
+            computed_layout: Default::default(),
             style: Default::default(),
             material: Default::default(),
             draw: Default::default(),
@@ -65,8 +77,10 @@ impl Default for NodeComponents {
 #[derive(Bundle)]
 pub struct ImageComponents {
     pub node: Node,
+    pub computed_layout: ComputedLayout,
     pub style: Style,
     pub image: Image,
+    pub content_align: ContentAlign,
     pub calculated_size: CalculatedSize,
     pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
     pub material: Handle<ColorMaterial>,
@@ -94,12 +108,24 @@ impl Default for ImageComponents {
                             bind_group: 1,
                             binding: 1,
                         },
+                        // Node_opacity
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 2,
+                        },
+                        // Node_clip
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 3,
+                        },
                     ],
                     ..Default::default()
                 },
             )]),
             node: Default::default(),
+            computed_layout: Default::default(),
             image: Default::default(),
+            content_align: ContentAlign::center(),
             calculated_size: Default::default(),
             style: Default::default(),
             material: Default::default(),
@@ -113,6 +139,7 @@ impl Default for ImageComponents {
 #[derive(Bundle)]
 pub struct TextComponents {
     pub node: Node,
+    pub computed_layout: ComputedLayout,
     pub style: Style,
     pub draw: Draw,
     pub text: Text,
@@ -132,6 +159,7 @@ impl Default for TextComponents {
             },
             text: Default::default(),
             node: Default::default(),
+            computed_layout: Default::default(),
             calculated_size: Default::default(),
             style: Default::default(),
             transform: Default::default(),
@@ -140,9 +168,109 @@ impl Default for TextComponents {
     }
 }
 
+/// A [TextEditor] node. It has no background, so it is usually the child of a node with a material.
+#[derive(Bundle)]
+pub struct TextEditorComponents {
+    pub node: Node,
+    pub computed_layout: ComputedLayout,
+    pub style: Style,
+    pub draw: Draw,
+    pub text_editor: TextEditor,
+    pub interaction: Interaction,
+    pub focus_policy: FocusPolicy,
+    pub transform: Transform,
+    pub local_transform: LocalTransform,
+}
+
+impl Default for TextEditorComponents {
+    fn default() -> Self {
+        TextEditorComponents {
+            draw: Draw {
+                is_transparent: true,
+                ..Default::default()
+            },
+            text_editor: Default::default(),
+            interaction: Default::default(),
+            focus_policy: Default::default(),
+            node: Default::default(),
+            computed_layout: Default::default(),
+            style: Default::default(),
+            transform: Default::default(),
+            local_transform: Default::default(),
+        }
+    }
+}
+
+/// A [ProgressBar] node. Its [FillMaterial] sets the bar's colors, and needs to be added to the node.
+#[derive(Bundle)]
+pub struct ProgressBarComponents {
+    pub node: Node,
+    pub computed_layout: ComputedLayout,
+    pub style: Style,
+    pub progress_bar: ProgressBar,
+    pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
+    pub material: Handle<FillMaterial>,
+    pub draw: Draw,
+    /// Set to the [FillMaterial] pipeline when the node is added
+    pub render_pipelines: RenderPipelines,
+    pub transform: Transform,
+    pub local_transform: LocalTransform,
+}
+
+impl Default for ProgressBarComponents {
+    fn default() -> Self {
+        ProgressBarComponents {
+            mesh: QUAD_HANDLE,
+            progress_bar: Default::default(),
+            node: Default::default(),
+            computed_layout: Default::default(),
+            style: Default::default(),
+            material: Default::default(),
+            draw: Default::default(),
+            render_pipelines: Default::default(),
+            transform: Default::default(),
+            local_transform: Default::default(),
+        }
+    }
+}
+
+/// A [RadialFill] node. Its [FillMaterial] sets the fill's colors, and needs to be added to the node.
+#[derive(Bundle)]
+pub struct RadialFillComponents {
+    pub node: Node,
+    pub computed_layout: ComputedLayout,
+    pub style: Style,
+    pub radial_fill: RadialFill,
+    pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
+    pub material: Handle<FillMaterial>,
+    pub draw: Draw,
+    /// Set to the [FillMaterial] pipeline when the node is added
+    pub render_pipelines: RenderPipelines,
+    pub transform: Transform,
+    pub local_transform: LocalTransform,
+}
+
+impl Default for RadialFillComponents {
+    fn default() -> Self {
+        RadialFillComponents {
+            mesh: QUAD_HANDLE,
+            radial_fill: Default::default(),
+            node: Default::default(),
+            computed_layout: Default::default(),
+            style: Default::default(),
+            material: Default::default(),
+            draw: Default::default(),
+            render_pipelines: Default::default(),
+            transform: Default::default(),
+            local_transform: Default::default(),
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct ButtonComponents {
     pub node: Node,
+    pub computed_layout: ComputedLayout,
     pub button: Button,
     pub style: Style,
     pub interaction: Interaction,
@@ -174,6 +302,16 @@ impl Default for ButtonComponents {
                             bind_group: 1,
                             binding: 1,
                         },
+                        // Node_opacity
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 2,
+                        },
+                        // Node_clip
+                        DynamicBinding {
+                            bind_group: 1,
+                            binding: 3,
+                        },
                     ],
                     ..Default::default()
                 },
@@ -181,6 +319,7 @@ impl Default for ButtonComponents {
             interaction: Default::default(),
             focus_policy: Default::default(),
             node: Default::default(),
+            computed_layout: Default::default(),
             style: Default::default(),
             material: Default::default(),
             draw: Default::default(),
@@ -1,8 +1,8 @@
 use crate::{
-    AlignContent, AlignItems, AlignSelf, Direction, Display, FlexDirection, FlexWrap,
-    JustifyContent, PositionType, Style, Val,
+    AlignContent, AlignItems, AlignSelf, Direction, Display, FlexDirection, FlexWrap, GridCell,
+    JustifyContent, Overflow, PositionType, Style, Val,
 };
-use bevy_math::{Rect, Size};
+use bevy_math::{Rect, Size, Vec2};
 
 fn from_rect<T, U>(rect: Rect<U>) -> stretch::geometry::Rect<T>
 where
@@ -30,7 +30,7 @@ where
 impl From<&Style> for stretch::style::Style {
     fn from(value: &Style) -> Self {
         Self {
-            overflow: stretch::style::Overflow::Visible,
+            overflow: value.overflow.into(),
             display: value.display.into(),
             position_type: value.position_type.into(),
             direction: value.direction.into(),
@@ -58,6 +58,242 @@ impl From<&Style> for stretch::style::Style {
     }
 }
 
+/// The gaps a flex item's parent places around the item, in logical pixels. stretch doesn't support gaps, so they are
+/// added to the item's margins once the parent's layout shows where its lines wrap.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct FlexGaps {
+    /// The gap to the previous item on the same line
+    pub main_leading: f32,
+    /// The gap to the next item on the same line, when that item's leading margin can't hold it
+    pub main_trailing: f32,
+    /// The gap to the previous line
+    pub cross_leading: f32,
+    /// The gap to the next line, when none of that line's items can hold it
+    pub cross_trailing: f32,
+}
+
+/// Returns the leading and trailing main axis margins and the leading and trailing cross axis margins of a flex item
+fn gap_margins<'a>(
+    margin: &'a mut Rect<Val>,
+    parent_style: &Style,
+) -> (&'a mut Val, &'a mut Val, &'a mut Val, &'a mut Val) {
+    let Rect {
+        left,
+        right,
+        top,
+        bottom,
+    } = margin;
+    // NOTE: stretch's y-axis is flipped, so the leading edge of a column (or of the lines of a row) is our "bottom"
+    let reverse_wrap = parent_style.flex_wrap == FlexWrap::WrapReverse;
+    match (parent_style.flex_direction, reverse_wrap) {
+        (FlexDirection::Row, false) => (left, right, bottom, top),
+        (FlexDirection::Row, true) => (left, right, top, bottom),
+        (FlexDirection::RowReverse, false) => (right, left, bottom, top),
+        (FlexDirection::RowReverse, true) => (right, left, top, bottom),
+        (FlexDirection::Column, false) => (bottom, top, left, right),
+        (FlexDirection::Column, true) => (bottom, top, right, left),
+        (FlexDirection::ColumnReverse, false) => (top, bottom, left, right),
+        (FlexDirection::ColumnReverse, true) => (top, bottom, right, left),
+    }
+}
+
+/// Whether a gap can be added to the margin. Auto margins absorb the free space, and percentages can't be combined
+/// with pixels before layout.
+fn holds_gap(margin: Val) -> bool {
+    matches!(margin, Val::Undefined | Val::Px(_))
+}
+
+fn add_gap(margin: &mut Val, gap: f32) {
+    *margin = match *margin {
+        _ if gap == 0.0 => return,
+        Val::Undefined => Val::Px(gap),
+        Val::Px(margin) => Val::Px(margin + gap),
+        margin => margin,
+    };
+}
+
+/// Returns `style` with `gaps` added to its margins
+pub(crate) fn with_flex_gaps(style: &Style, parent_style: &Style, gaps: &FlexGaps) -> Style {
+    let mut style = style.clone();
+    let (main_leading, main_trailing, cross_leading, cross_trailing) =
+        gap_margins(&mut style.margin, parent_style);
+    add_gap(main_leading, gaps.main_leading);
+    add_gap(main_trailing, gaps.main_trailing);
+    add_gap(cross_leading, gaps.cross_leading);
+    add_gap(cross_trailing, gaps.cross_trailing);
+    style
+}
+
+/// Whether a flex item laid out at `location` starts a new line, given the location of the previous item. Locations
+/// are relative to the top left corner of the parent.
+pub(crate) fn starts_flex_line(parent_style: &Style, previous: Vec2, location: Vec2) -> bool {
+    match (parent_style.flex_wrap, parent_style.flex_direction) {
+        (FlexWrap::NoWrap, _) => false,
+        (_, FlexDirection::Row) => location.x() <= previous.x(),
+        (_, FlexDirection::RowReverse) => location.x() >= previous.x(),
+        (_, FlexDirection::Column) => location.y() <= previous.y(),
+        (_, FlexDirection::ColumnReverse) => location.y() >= previous.y(),
+    }
+}
+
+/// Places the gaps of a flex node between its items, given each item's style and whether it starts a new line.
+/// `content_size` is the size of the node's content area. Gaps go between the items of a line and between lines, but
+/// never before the first item of a line. A gap between two items whose facing margins are both auto is left out, as
+/// is a gap between two lines whose facing cross axis margins are all auto.
+pub(crate) fn place_flex_gaps(
+    parent_style: &Style,
+    content_size: Vec2,
+    items: &[(&Style, bool)],
+) -> Vec<FlexGaps> {
+    let (main_gap, cross_gap) = match parent_style.flex_direction {
+        FlexDirection::Row | FlexDirection::RowReverse => (
+            resolve_gap(parent_style.gap.width, content_size.x()),
+            resolve_gap(parent_style.gap.height, content_size.y()),
+        ),
+        FlexDirection::Column | FlexDirection::ColumnReverse => (
+            resolve_gap(parent_style.gap.height, content_size.y()),
+            resolve_gap(parent_style.gap.width, content_size.x()),
+        ),
+    };
+    let margins = items
+        .iter()
+        .map(|(style, _)| {
+            let mut margin = style.margin;
+            let (main_leading, main_trailing, cross_leading, cross_trailing) =
+                gap_margins(&mut margin, parent_style);
+            (
+                holds_gap(*main_leading),
+                holds_gap(*main_trailing),
+                holds_gap(*cross_leading),
+                holds_gap(*cross_trailing),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    for (index, (_, starts_line)) in items.iter().enumerate() {
+        if index > 0 && *starts_line {
+            lines.push(line_start..index);
+            line_start = index;
+        }
+    }
+    if !items.is_empty() {
+        lines.push(line_start..items.len());
+    }
+
+    let mut gaps = vec![FlexGaps::default(); items.len()];
+    for (line_index, line) in lines.iter().enumerate() {
+        for index in line.start + 1..line.end {
+            if margins[index].0 {
+                gaps[index].main_leading = main_gap;
+            } else if margins[index - 1].1 {
+                gaps[index - 1].main_trailing = main_gap;
+            }
+        }
+
+        if line_index == 0 {
+            continue;
+        }
+        if line.clone().any(|index| margins[index].2) {
+            for index in line.clone().filter(|index| margins[*index].2) {
+                gaps[index].cross_leading = cross_gap;
+            }
+        } else {
+            let previous_line = lines[line_index - 1].clone();
+            for index in previous_line.filter(|index| margins[*index].3) {
+                gaps[index].cross_trailing = cross_gap;
+            }
+        }
+    }
+
+    gaps
+}
+
+/// Returns `style` with its padding increased by `insets`
+pub(crate) fn with_safe_area_padding(style: &Style, insets: Rect<f32>) -> Style {
+    fn pad(padding: Val, inset: f32) -> Val {
+        match padding {
+            Val::Undefined => Val::Px(inset),
+            Val::Px(padding) => Val::Px(padding + inset),
+            // auto and percent padding can't be combined with pixel insets before layout
+            padding => padding,
+        }
+    }
+
+    let mut style = style.clone();
+    style.padding = Rect {
+        left: pad(style.padding.left, insets.left),
+        right: pad(style.padding.right, insets.right),
+        top: pad(style.padding.top, insets.top),
+        bottom: pad(style.padding.bottom, insets.bottom),
+    };
+    style
+}
+
+/// Resolves a grid gap against the length of the grid's content area along the same axis
+pub(crate) fn resolve_gap(gap: Val, length: f32) -> f32 {
+    match gap {
+        Val::Px(value) => value,
+        Val::Percent(percent) => length * percent / 100.0,
+        Val::Undefined | Val::Auto => 0.0,
+    }
+}
+
+/// Returns `style` absolutely positioned in `cell`, whose position is relative to the bottom left corner of the grid's
+/// padding box
+pub(crate) fn with_grid_cell(style: &Style, cell: &GridCell) -> Style {
+    let mut style = style.clone();
+    style.position_type = PositionType::Absolute;
+    style.position = Rect {
+        left: Val::Px(cell.position.x()),
+        right: Val::Undefined,
+        top: Val::Undefined,
+        bottom: Val::Px(cell.position.y()),
+    };
+    style.size = Size::new(Val::Px(cell.size.x()), Val::Px(cell.size.y()));
+    style
+}
+
+/// Returns the offset of a node's content area from the bottom left corner of its padding box, and the content area's
+/// size. `size` is the node's computed size.
+pub(crate) fn content_area(style: &Style, size: Vec2) -> (Vec2, Vec2) {
+    // NOTE: like stretch, percentages of padding and borders resolve against the width
+    let resolve = |val: Val| match val {
+        Val::Px(value) => value,
+        Val::Percent(percent) => size.x() * percent / 100.0,
+        Val::Undefined | Val::Auto => 0.0,
+    };
+    let offset = Vec2::new(resolve(style.padding.left), resolve(style.padding.bottom));
+    let insets = Vec2::new(
+        resolve(style.padding.left)
+            + resolve(style.padding.right)
+            + resolve(style.border.left)
+            + resolve(style.border.right),
+        resolve(style.padding.bottom)
+            + resolve(style.padding.top)
+            + resolve(style.border.bottom)
+            + resolve(style.border.top),
+    );
+    (offset, (size - insets).max(Vec2::zero()))
+}
+
+/// Returns the combined width of the padding and border on each side of a node of the given size
+pub(crate) fn content_insets(style: &Style, size: Vec2) -> Rect<f32> {
+    // NOTE: like stretch, percentages of padding and borders resolve against the width
+    let resolve = |val: Val| match val {
+        Val::Px(value) => value,
+        Val::Percent(percent) => size.x() * percent / 100.0,
+        Val::Undefined | Val::Auto => 0.0,
+    };
+    Rect {
+        left: resolve(style.padding.left) + resolve(style.border.left),
+        right: resolve(style.padding.right) + resolve(style.border.right),
+        top: resolve(style.padding.top) + resolve(style.border.top),
+        bottom: resolve(style.padding.bottom) + resolve(style.border.bottom),
+    }
+}
+
 impl From<Val> for stretch::style::Dimension {
     fn from(val: Val) -> Self {
         match val {
@@ -120,12 +356,23 @@ impl From<Direction> for stretch::style::Direction {
 impl From<Display> for stretch::style::Display {
     fn from(value: Display) -> Self {
         match value {
-            Display::Flex => stretch::style::Display::Flex,
+            // grid children are absolutely positioned, so the grid itself is laid out as a flex node
+            Display::Flex | Display::Grid => stretch::style::Display::Flex,
             Display::None => stretch::style::Display::None,
         }
     }
 }
 
+impl From<Overflow> for stretch::style::Overflow {
+    fn from(value: Overflow) -> Self {
+        match value {
+            Overflow::Visible => stretch::style::Overflow::Visible,
+            Overflow::Hidden => stretch::style::Overflow::Hidden,
+            Overflow::Scroll => stretch::style::Overflow::Scroll,
+        }
+    }
+}
+
 impl From<FlexDirection> for stretch::style::FlexDirection {
     fn from(value: FlexDirection) -> Self {
         match value {
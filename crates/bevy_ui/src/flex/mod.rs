@@ -1,16 +1,60 @@
 mod convert;
 
-use crate::{CalculatedSize, Node, Style};
-use bevy_ecs::{Changed, Entity, Query, Res, ResMut, With, Without};
+pub(crate) use convert::content_insets;
+use convert::FlexGaps;
+
+use crate::{
+    compute_grid_cells, CalculatedSize, Display, GridCell, Node, Overflow, PositionType,
+    SafeAreaPadding, ScrollPosition, Style, UiScale, UiTargetWindow,
+};
+use bevy_ecs::{Changed, Entity, EntityReferences, Local, Query, Res, ResMut, With, Without};
 use bevy_math::Vec2;
 use bevy_transform::prelude::{Children, LocalTransform, Parent};
-use bevy_window::{Window, WindowId, Windows};
-use std::collections::HashMap;
-use stretch::{number::Number, Stretch};
+use bevy_window::{SafeAreaInsets, Window, WindowId, Windows};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+use stretch::{node::MeasureFunc, number::Number, Stretch};
+use thiserror::Error;
+
+/// The size and location of a laid out ui node, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeLayout {
+    pub size: Vec2,
+    /// The location of the node's top left corner, relative to its parent's top left corner
+    pub location: Vec2,
+}
+
+/// The maximum number of times layouts are recomputed to place the children of grid nodes and the gaps of flex nodes,
+/// which depend on the node's computed layout. Each level of nesting needs another pass.
+const MAX_LAYOUT_PASSES: usize = 8;
+
+/// An error that occurs when updating a [FlexSurface]
+#[derive(Debug, Error)]
+pub enum FlexError {
+    #[error("Entity {0:?} has not been added to the flex surface.")]
+    MissingNode(Entity),
+    #[error("Window {0:?} has not been added to the flex surface.")]
+    MissingWindow(WindowId),
+    #[error("Failed to update the layout: {0}")]
+    Stretch(String),
+}
+
+fn stretch_error(error: stretch::Error) -> FlexError {
+    FlexError::Stretch(error.to_string())
+}
 
 pub struct FlexSurface {
     entity_to_stretch: HashMap<Entity, stretch::node::Node>,
     window_nodes: HashMap<WindowId, stretch::node::Node>,
+    window_scale_factors: HashMap<stretch::node::Node, f64>,
+    node_parents: HashMap<stretch::node::Node, stretch::node::Node>,
+    grid_cells: HashMap<Entity, GridCell>,
+    flex_gaps: HashMap<Entity, FlexGaps>,
+    /// Detached nodes of removed entities. They are reused instead of removed, as `Stretch::remove` loses track of the
+    /// node it moves into the removed node's slot.
+    free_nodes: Vec<stretch::node::Node>,
     stretch: Stretch,
 }
 
@@ -19,31 +63,87 @@ impl Default for FlexSurface {
         Self {
             entity_to_stretch: Default::default(),
             window_nodes: Default::default(),
+            window_scale_factors: Default::default(),
+            node_parents: Default::default(),
+            grid_cells: Default::default(),
+            flex_gaps: Default::default(),
+            free_nodes: Default::default(),
             stretch: Stretch::new(),
         }
     }
 }
 
+// nodes are normally removed by flex_node_system, but nodes despawned after it runs in a frame are only seen here
+impl EntityReferences for FlexSurface {
+    fn references_despawned(&self, is_alive: &dyn Fn(Entity) -> bool) -> bool {
+        self.entity_to_stretch
+            .keys()
+            .chain(self.grid_cells.keys())
+            .chain(self.flex_gaps.keys())
+            .any(|entity| !is_alive(*entity))
+    }
+
+    fn remove_despawned(&mut self, is_alive: &dyn Fn(Entity) -> bool) {
+        let despawned = self
+            .entity_to_stretch
+            .keys()
+            .chain(self.grid_cells.keys())
+            .chain(self.flex_gaps.keys())
+            .filter(|entity| !is_alive(**entity))
+            .cloned()
+            .collect::<HashSet<_>>();
+        if let Err(err) = self.remove_entities(despawned) {
+            log::warn!("Failed to remove despawned ui nodes: {}", err);
+        }
+    }
+}
+
 impl FlexSurface {
-    pub fn upsert_node(&mut self, entity: Entity, style: &Style) {
-        let mut added = false;
-        let stretch = &mut self.stretch;
-        let stretch_style = style.into();
-        let stretch_node = self.entity_to_stretch.entry(entity).or_insert_with(|| {
-            added = true;
-            let stretch_node = stretch.new_node(stretch_style, Vec::new()).unwrap();
-            stretch_node
-        });
+    /// Creates a stretch node without children, reusing a free node if there is one
+    fn new_stretch_node(
+        &mut self,
+        style: stretch::style::Style,
+        measure: Option<MeasureFunc>,
+    ) -> Result<stretch::node::Node, FlexError> {
+        if let Some(node) = self.free_nodes.pop() {
+            self.stretch.set_style(node, style).map_err(stretch_error)?;
+            self.stretch
+                .set_measure(node, measure)
+                .map_err(stretch_error)?;
+            return Ok(node);
+        }
 
-        if !added {
+        match measure {
+            Some(measure) => self.stretch.new_leaf(style, measure),
+            None => self.stretch.new_node(style, Vec::new()),
+        }
+        .map_err(stretch_error)
+    }
+
+    pub fn upsert_node(&mut self, entity: Entity, style: &Style) -> Result<(), FlexError> {
+        let stretch_style = style.into();
+        if let Some(stretch_node) = self.entity_to_stretch.get(&entity) {
             self.stretch
                 .set_style(*stretch_node, stretch_style)
-                .unwrap();
+                .map_err(stretch_error)?;
+            // the node may have previously been a leaf with a measure func
+            self.stretch
+                .set_measure(*stretch_node, None)
+                .map_err(stretch_error)?;
+        } else {
+            let stretch_node = self.new_stretch_node(stretch_style, None)?;
+            self.entity_to_stretch.insert(entity, stretch_node);
         }
+
+        Ok(())
     }
 
-    pub fn upsert_leaf(&mut self, entity: Entity, style: &Style, calculated_size: CalculatedSize) {
-        let stretch = &mut self.stretch;
+    pub fn upsert_leaf(
+        &mut self,
+        entity: Entity,
+        style: &Style,
+        calculated_size: CalculatedSize,
+    ) -> Result<(), FlexError> {
         let stretch_style = style.into();
         let measure = Box::new(move |constraints: stretch::geometry::Size<Number>| {
             let mut size = stretch::geometry::Size {
@@ -52,12 +152,17 @@ impl FlexSurface {
             };
             match (constraints.width, constraints.height) {
                 (Number::Undefined, Number::Undefined) => {}
+                // preserve the content's aspect ratio when only one axis is constrained, unless it wraps
                 (Number::Defined(width), Number::Undefined) => {
-                    size.height = width * size.height / size.width;
+                    if size.width > 0.0 && !calculated_size.wraps {
+                        size.height = width * size.height / size.width;
+                    }
                     size.width = width;
                 }
                 (Number::Undefined, Number::Defined(height)) => {
-                    size.width = height * size.width / size.height;
+                    if size.height > 0.0 && !calculated_size.wraps {
+                        size.width = height * size.width / size.height;
+                    }
                     size.height = height;
                 }
                 (Number::Defined(width), Number::Defined(height)) => {
@@ -71,76 +176,242 @@ impl FlexSurface {
         if let Some(stretch_node) = self.entity_to_stretch.get(&entity) {
             self.stretch
                 .set_style(*stretch_node, stretch_style)
-                .unwrap();
+                .map_err(stretch_error)?;
             self.stretch
                 .set_measure(*stretch_node, Some(measure))
-                .unwrap();
+                .map_err(stretch_error)?;
         } else {
-            let stretch_node = stretch.new_leaf(stretch_style, measure).unwrap();
+            let stretch_node = self.new_stretch_node(stretch_style, Some(measure))?;
             self.entity_to_stretch.insert(entity, stretch_node);
         }
+
+        Ok(())
     }
 
-    pub fn update_children(&mut self, entity: Entity, children: &Children) {
-        let mut stretch_children = Vec::with_capacity(children.len());
-        for child in children.iter() {
-            let stretch_node = self.entity_to_stretch.get(child).unwrap();
-            stretch_children.push(*stretch_node);
+    /// Sets the children of the entity's node. Fails if the entity or any of the children haven't been added yet, in
+    /// which case the node's children are left unchanged.
+    pub fn update_children(
+        &mut self,
+        entity: Entity,
+        children: impl Iterator<Item = Entity>,
+    ) -> Result<(), FlexError> {
+        let stretch_node = self.stretch_node(entity)?;
+        let stretch_children = children
+            .map(|child| self.stretch_node(child))
+            .collect::<Result<Vec<stretch::node::Node>, FlexError>>()?;
+        self.set_stretch_children(stretch_node, stretch_children)
+    }
+
+    fn stretch_node(&self, entity: Entity) -> Result<stretch::node::Node, FlexError> {
+        self.entity_to_stretch
+            .get(&entity)
+            .cloned()
+            .ok_or_else(|| FlexError::MissingNode(entity))
+    }
+
+    fn set_stretch_children(
+        &mut self,
+        stretch_node: stretch::node::Node,
+        stretch_children: Vec<stretch::node::Node>,
+    ) -> Result<(), FlexError> {
+        let old_children = self.stretch.children(stretch_node).map_err(stretch_error)?;
+        for old_child in old_children {
+            if self.node_parents.get(&old_child) == Some(&stretch_node) {
+                self.node_parents.remove(&old_child);
+            }
+        }
+
+        for child in stretch_children.iter() {
+            self.node_parents.insert(*child, stretch_node);
         }
 
-        let stretch_node = self.entity_to_stretch.get(&entity).unwrap();
         self.stretch
-            .set_children(*stretch_node, stretch_children)
-            .unwrap();
+            .set_children(stretch_node, stretch_children)
+            .map_err(stretch_error)
     }
 
-    pub fn update_window(&mut self, window: &Window) {
-        let stretch = &mut self.stretch;
-        let node = self.window_nodes.entry(window.id).or_insert_with(|| {
-            stretch
-                .new_node(stretch::style::Style::default(), Vec::new())
-                .unwrap()
-        });
+    /// Removes the stretch nodes of the given entities, detaching them from their parents and children
+    pub fn remove_entities(
+        &mut self,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> Result<(), FlexError> {
+        for entity in entities {
+            self.grid_cells.remove(&entity);
+            self.flex_gaps.remove(&entity);
+            if let Some(stretch_node) = self.entity_to_stretch.remove(&entity) {
+                // detach the node so it can be reused, which also marks its parent dirty
+                if let Some(parent) = self.node_parents.remove(&stretch_node) {
+                    self.stretch
+                        .remove_child(parent, stretch_node)
+                        .map_err(stretch_error)?;
+                }
+
+                for child in self.stretch.children(stretch_node).map_err(stretch_error)? {
+                    self.node_parents.remove(&child);
+                    self.stretch
+                        .remove_child(stretch_node, child)
+                        .map_err(stretch_error)?;
+                }
 
-        stretch
+                self.stretch
+                    .set_measure(stretch_node, None)
+                    .map_err(stretch_error)?;
+                self.free_nodes.push(stretch_node);
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the root node of the given window. Layouts are computed in logical pixels, which are converted to
+    /// physical pixels using `scale_factor`.
+    pub fn update_window(&mut self, window: &Window, scale_factor: f64) -> Result<(), FlexError> {
+        let node = match self.window_nodes.get(&window.id) {
+            Some(node) => *node,
+            None => {
+                let node = self.new_stretch_node(stretch::style::Style::default(), None)?;
+                self.window_nodes.insert(window.id, node);
+                node
+            }
+        };
+        self.window_scale_factors.insert(node, scale_factor);
+
+        self.stretch
             .set_style(
-                *node,
+                node,
                 stretch::style::Style {
                     size: stretch::geometry::Size {
-                        width: stretch::style::Dimension::Points(window.width as f32),
-                        height: stretch::style::Dimension::Points(window.height as f32),
+                        width: stretch::style::Dimension::Points(
+                            (window.width as f64 / scale_factor) as f32,
+                        ),
+                        height: stretch::style::Dimension::Points(
+                            (window.height as f64 / scale_factor) as f32,
+                        ),
                     },
                     ..Default::default()
                 },
             )
-            .unwrap();
+            .map_err(stretch_error)
     }
 
+    /// Sets the root nodes of the window. Fails if the window or any of the children haven't been added yet, in which
+    /// case the window's children are left unchanged.
     pub fn set_window_children(
         &mut self,
         window_id: WindowId,
         children: impl Iterator<Item = Entity>,
-    ) {
-        let stretch_node = self.window_nodes.get(&window_id).unwrap();
+    ) -> Result<(), FlexError> {
+        let stretch_node = *self
+            .window_nodes
+            .get(&window_id)
+            .ok_or_else(|| FlexError::MissingWindow(window_id))?;
         let child_nodes = children
-            .map(|e| *self.entity_to_stretch.get(&e).unwrap())
-            .collect::<Vec<stretch::node::Node>>();
-        self.stretch
-            .set_children(*stretch_node, child_nodes)
-            .unwrap();
+            .map(|child| self.stretch_node(child))
+            .collect::<Result<Vec<stretch::node::Node>, FlexError>>()?;
+        self.set_stretch_children(stretch_node, child_nodes)
     }
 
-    pub fn compute_window_layouts(&mut self) {
+    pub fn compute_window_layouts(&mut self) -> Result<(), FlexError> {
         for window_node in self.window_nodes.values() {
             self.stretch
                 .compute_layout(*window_node, stretch::geometry::Size::undefined())
-                .unwrap();
+                .map_err(stretch_error)?;
         }
+
+        Ok(())
+    }
+
+    pub(crate) fn get_layout(&self, entity: Entity) -> Result<&stretch::result::Layout, FlexError> {
+        let stretch_node = self.stretch_node(entity)?;
+        self.stretch.layout(stretch_node).map_err(stretch_error)
+    }
+
+    /// The cell the entity's node was placed in by its grid parent
+    pub fn grid_cell(&self, entity: Entity) -> Option<&GridCell> {
+        self.grid_cells.get(&entity)
     }
 
-    pub fn get_layout(&self, entity: Entity) -> Result<&stretch::result::Layout, stretch::Error> {
-        let stretch_node = self.entity_to_stretch.get(&entity).unwrap();
-        self.stretch.layout(*stretch_node)
+    /// The scale factor of the window the entity's node is laid out in, or 1.0 if it isn't part of a window's layout
+    pub fn scale_factor(&self, entity: Entity) -> f64 {
+        let mut stretch_node = match self.entity_to_stretch.get(&entity) {
+            Some(stretch_node) => *stretch_node,
+            None => return 1.0,
+        };
+        while let Some(parent) = self.node_parents.get(&stretch_node) {
+            stretch_node = *parent;
+        }
+
+        self.window_scale_factors
+            .get(&stretch_node)
+            .cloned()
+            .unwrap_or(1.0)
+    }
+
+    /// The computed layout of the entity's node, or `None` if it isn't a ui node
+    pub fn node_layout(&self, entity: Entity) -> Option<NodeLayout> {
+        self.get_layout(entity).ok().map(|layout| NodeLayout {
+            size: Vec2::new(layout.size.width, layout.size.height),
+            location: Vec2::new(layout.location.x, layout.location.y),
+        })
+    }
+
+    /// Returns the stretch node hierarchy of every window, along with each node's entity and computed layout, ex: to
+    /// log it while debugging a layout
+    pub fn debug_tree(&self) -> String {
+        let stretch_to_entity = self
+            .entity_to_stretch
+            .iter()
+            .map(|(entity, stretch_node)| (*stretch_node, *entity))
+            .collect::<HashMap<stretch::node::Node, Entity>>();
+        let mut tree = String::new();
+        for (window_id, window_node) in self.window_nodes.iter() {
+            tree.push_str(&format!(
+                "{:?} {}\n",
+                window_id,
+                self.debug_layout_string(*window_node)
+            ));
+            self.debug_write_children(&mut tree, *window_node, &stretch_to_entity, "");
+        }
+        tree
+    }
+
+    fn debug_write_children(
+        &self,
+        tree: &mut String,
+        stretch_node: stretch::node::Node,
+        stretch_to_entity: &HashMap<stretch::node::Node, Entity>,
+        indent: &str,
+    ) {
+        let children = self.stretch.children(stretch_node).unwrap_or_default();
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index == children.len() - 1;
+            let entity = match stretch_to_entity.get(child) {
+                Some(entity) => format!("{:?}", entity),
+                None => "(no entity)".to_string(),
+            };
+            tree.push_str(&format!(
+                "{}{} {} {}\n",
+                indent,
+                if is_last { "└──" } else { "├──" },
+                entity,
+                self.debug_layout_string(*child)
+            ));
+            let child_indent = format!("{}{}", indent, if is_last { "    " } else { "│   " });
+            self.debug_write_children(tree, *child, stretch_to_entity, &child_indent);
+        }
+    }
+
+    fn debug_layout_string(&self, stretch_node: stretch::node::Node) -> String {
+        let style = match self.stretch.style(stretch_node) {
+            Ok(style) => format!("{:?} {:?}", style.display, style.position_type),
+            Err(_) => String::new(),
+        };
+        match self.stretch.layout(stretch_node) {
+            Ok(layout) => format!(
+                "{} [x: {}, y: {}, width: {}, height: {}]",
+                style, layout.location.x, layout.location.y, layout.size.width, layout.size.height
+            ),
+            Err(_) => format!("{} [no layout]", style),
+        }
     }
 }
 
@@ -148,62 +419,716 @@ impl FlexSurface {
 unsafe impl Send for FlexSurface {}
 unsafe impl Sync for FlexSurface {}
 
+#[derive(Default)]
+pub struct FlexNodeSystemState {
+    safe_area_insets: Option<SafeAreaInsets>,
+    /// Nodes that failed to update last frame
+    pending_nodes: HashSet<Entity>,
+    /// Nodes whose children failed to update last frame
+    pending_children: HashSet<Entity>,
+    /// Nodes without a layout that have already been logged, so they aren't logged again every frame
+    nodes_without_layout: HashSet<Entity>,
+}
+
 pub fn flex_node_system(
+    mut state: Local<FlexNodeSystemState>,
     windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    safe_area_insets: Res<SafeAreaInsets>,
     mut flex_surface: ResMut<FlexSurface>,
-    mut root_node_query: Query<With<Node, Without<Parent, Entity>>>,
-    mut node_query: Query<With<Node, (Entity, Changed<Style>, Option<&CalculatedSize>)>>,
-    mut changed_size_query: Query<With<Node, (Entity, &Style, Changed<CalculatedSize>)>>,
+    mut root_node_query: Query<With<Node, Without<Parent, (Entity, Option<&UiTargetWindow>)>>>,
+    mut node_query: Query<With<Node, (Entity, Changed<Style>, Option<&Children>)>>,
+    mut changed_size_query: Query<With<Node, (Entity, Changed<CalculatedSize>)>>,
     mut children_query: Query<With<Node, (Entity, Changed<Children>)>>,
+    mut safe_area_query: Query<With<Node, With<SafeAreaPadding, Entity>>>,
+    mut scroll_query: Query<With<Node, (Entity, &Style, &mut ScrollPosition)>>,
+    mut grid_query: Query<With<Node, (Entity, &Style, &Children)>>,
+    style_query: Query<
+        With<
+            Node,
+            (
+                &Style,
+                Option<&CalculatedSize>,
+                Option<&Parent>,
+                Option<&SafeAreaPadding>,
+                Option<&UiTargetWindow>,
+            ),
+        >,
+    >,
     mut node_transform_query: Query<(Entity, &mut Node, &mut LocalTransform, Option<&Parent>)>,
 ) {
     // update window root nodes
     for window in windows.iter() {
-        flex_surface.update_window(window);
+        if let Err(err) = flex_surface.update_window(window, ui_scale.scale_factor(window)) {
+            log::warn!(
+                "Failed to update the root node of window {:?}: {}",
+                window.id,
+                err
+            );
+        }
+    }
+
+    // remove despawned nodes first, as their entity ids may already be reused by new nodes
+    if let Err(err) =
+        flex_surface.remove_entities(node_transform_query.removed::<Node>().iter().cloned())
+    {
+        log::warn!("Failed to remove despawned ui nodes: {}", err);
+    }
+
+    // collect changed nodes. a parent's gap is applied to its children, so their styles need to be updated too
+    let mut changed_nodes = state.pending_nodes.drain().collect::<HashSet<Entity>>();
+    for (entity, _style, children) in &mut node_query.iter() {
+        changed_nodes.insert(entity);
+        if let Some(children) = children {
+            changed_nodes.extend(children.iter().cloned());
+        }
+    }
+
+    for (entity, _calculated_size) in &mut changed_size_query.iter() {
+        changed_nodes.insert(entity);
+    }
+
+    for (_entity, children) in &mut children_query.iter() {
+        changed_nodes.extend(children.iter().cloned());
     }
 
+    if state.safe_area_insets.as_ref() != Some(&*safe_area_insets) {
+        changed_nodes.extend(safe_area_query.iter().iter());
+        state.safe_area_insets = Some(safe_area_insets.clone());
+    }
+
+    let primary_window_id = windows.get_primary().map(|window| window.id);
+
     // update changed nodes
-    for (entity, style, calculated_size) in &mut node_query.iter() {
+    for entity in changed_nodes {
+        let mut query = match style_query.entity(entity) {
+            Ok(query) => query,
+            Err(_) => continue,
+        };
+        let (style, calculated_size, parent, safe_area_padding, target_window) = match query.get() {
+            Some(item) => item,
+            None => continue,
+        };
+
+        let mut style = Cow::Borrowed(style);
+        if let Some(parent) = parent {
+            if let Ok(parent_style) = style_query.get::<Style>(parent.0) {
+                // children are placed in grid cells and gaps are placed between children after the next layout
+                if parent_style.display == Display::Grid {
+                    flex_surface.flex_gaps.remove(&entity);
+                    if let Some(cell) = flex_surface.grid_cell(entity) {
+                        style = Cow::Owned(convert::with_grid_cell(&style, cell));
+                    }
+                } else {
+                    flex_surface.grid_cells.remove(&entity);
+                    if let Some(gaps) = flex_surface.flex_gaps.get(&entity) {
+                        style = Cow::Owned(convert::with_flex_gaps(&style, &parent_style, gaps));
+                    }
+                }
+            }
+        } else if safe_area_padding.is_some() {
+            let window_id = target_window
+                .map(|target_window| target_window.0)
+                .or(primary_window_id);
+            if let Some(window_id) = window_id {
+                let insets = safe_area_insets.get(window_id);
+                style = Cow::Owned(convert::with_safe_area_padding(&style, insets));
+            }
+        }
+
         // TODO: remove node from old hierarchy if its root has changed
-        if let Some(calculated_size) = calculated_size {
-            flex_surface.upsert_leaf(entity, &style, *calculated_size);
+        let result = if let Some(calculated_size) = calculated_size {
+            flex_surface.upsert_leaf(entity, &style, *calculated_size)
         } else {
-            flex_surface.upsert_node(entity, &style);
+            flex_surface.upsert_node(entity, &style)
+        };
+        if let Err(err) = result {
+            log::warn!(
+                "Failed to update ui node {:?}, retrying next frame: {}",
+                entity,
+                err
+            );
+            state.pending_nodes.insert(entity);
         }
     }
 
-    for (entity, style, calculated_size) in &mut changed_size_query.iter() {
-        flex_surface.upsert_leaf(entity, &style, *calculated_size);
+    // update window children. root nodes without a UiTargetWindow live in the primary window
+    let mut window_roots = windows
+        .iter()
+        .map(|window| (window.id, Vec::new()))
+        .collect::<HashMap<WindowId, Vec<Entity>>>();
+    for (entity, target_window) in &mut root_node_query.iter() {
+        let window_id = target_window
+            .map(|target_window| target_window.0)
+            .or(primary_window_id);
+        if let Some(roots) = window_id.and_then(|window_id| window_roots.get_mut(&window_id)) {
+            roots.push(entity);
+        }
     }
 
-    // TODO: handle removed nodes
-
-    // update window children (for now assuming all Nodes live in the primary window)
-    if let Some(primary_window) = windows.get_primary() {
-        flex_surface.set_window_children(primary_window.id, root_node_query.iter().iter());
+    for (window_id, roots) in window_roots {
+        if let Err(err) = flex_surface.set_window_children(window_id, roots.into_iter()) {
+            log::warn!(
+                "Failed to update the root nodes of window {:?}: {}",
+                window_id,
+                err
+            );
+        }
     }
 
-    // update children
-    for (entity, children) in &mut children_query.iter() {
-        flex_surface.update_children(entity, &children);
+    // update children. children that are not ui nodes (or that have been despawned) are not part of the layout
+    let mut changed_children = state.pending_children.drain().collect::<HashSet<Entity>>();
+    changed_children.extend(
+        (&mut children_query.iter())
+            .iter()
+            .map(|(entity, _)| entity),
+    );
+    for entity in changed_children {
+        let children = match style_query.get::<Children>(entity) {
+            Ok(children) => children,
+            Err(_) => continue,
+        };
+        let result = flex_surface.update_children(
+            entity,
+            children
+                .iter()
+                .cloned()
+                .filter(|child| style_query.get::<Style>(*child).is_ok()),
+        );
+        if let Err(err) = result {
+            log::warn!(
+                "Failed to update the children of ui node {:?}, retrying next frame: {}",
+                entity,
+                err
+            );
+            state.pending_children.insert(entity);
+        }
     }
 
     // compute layouts
-    flex_surface.compute_window_layouts();
+    if let Err(err) = flex_surface.compute_window_layouts() {
+        log::warn!("Failed to compute ui layouts: {}", err);
+    }
+
+    // place the children of grid nodes and the gaps of flex nodes, which depend on the node's computed layout
+    for _ in 0..MAX_LAYOUT_PASSES {
+        let mut placed_children = false;
+        for (entity, style, children) in &mut grid_query.iter() {
+            let layout = match flex_surface.get_layout(entity) {
+                Ok(layout) => layout,
+                Err(_) => continue,
+            };
+            let (content_offset, content_size) =
+                convert::content_area(&style, Vec2::new(layout.size.width, layout.size.height));
+
+            let mut placed_styles = Vec::new();
+            if style.display == Display::Grid {
+                let mut items = Vec::new();
+                let mut item_entities = Vec::new();
+                for child in children.iter() {
+                    if let Ok(child_style) = style_query.get::<Style>(*child) {
+                        items.push((child_style.grid_column, child_style.grid_row));
+                        item_entities.push(*child);
+                    }
+                }
+
+                let gap = Vec2::new(
+                    convert::resolve_gap(style.gap.width, content_size.x()),
+                    convert::resolve_gap(style.gap.height, content_size.y()),
+                );
+                let cells = compute_grid_cells(
+                    content_size,
+                    gap,
+                    &style.grid_template_columns,
+                    &style.grid_template_rows,
+                    &items,
+                );
+                for (child, mut cell) in item_entities.into_iter().zip(cells.into_iter()) {
+                    cell.position += content_offset;
+                    if flex_surface.grid_cell(child) == Some(&cell) {
+                        continue;
+                    }
+
+                    flex_surface.grid_cells.insert(child, cell);
+                    let child_style = style_query.get::<Style>(child).unwrap();
+                    placed_styles.push((child, convert::with_grid_cell(&child_style, &cell)));
+                }
+            } else {
+                // absolutely positioned and hidden children aren't part of the node's lines
+                let mut items = Vec::new();
+                let mut previous_location = None;
+                for child in children.iter() {
+                    let child_style = match style_query.get::<Style>(*child) {
+                        Ok(child_style) => child_style,
+                        Err(_) => continue,
+                    };
+                    let child_layout = match flex_surface.get_layout(*child) {
+                        Ok(child_layout) => child_layout,
+                        Err(_) => continue,
+                    };
+                    if child_style.position_type == PositionType::Absolute
+                        || child_style.display == Display::None
+                    {
+                        if flex_surface.flex_gaps.remove(child).is_some() {
+                            placed_styles.push((*child, (*child_style).clone()));
+                        }
+                        continue;
+                    }
+
+                    let location = Vec2::new(child_layout.location.x, child_layout.location.y);
+                    let starts_line = previous_location.map_or(false, |previous| {
+                        convert::starts_flex_line(&style, previous, location)
+                    });
+                    previous_location = Some(location);
+                    items.push((*child, child_style, starts_line));
+                }
+
+                let gaps = convert::place_flex_gaps(
+                    &style,
+                    content_size,
+                    &items
+                        .iter()
+                        .map(|(_, child_style, starts_line)| (&**child_style, *starts_line))
+                        .collect::<Vec<_>>(),
+                );
+                for ((child, child_style, _), gaps) in items.iter().zip(gaps.into_iter()) {
+                    let previous_gaps = flex_surface
+                        .flex_gaps
+                        .get(child)
+                        .cloned()
+                        .unwrap_or_default();
+                    if previous_gaps == gaps {
+                        continue;
+                    }
+
+                    if gaps == FlexGaps::default() {
+                        flex_surface.flex_gaps.remove(child);
+                    } else {
+                        flex_surface.flex_gaps.insert(*child, gaps);
+                    }
+                    placed_styles
+                        .push((*child, convert::with_flex_gaps(child_style, &style, &gaps)));
+                }
+            }
+
+            for (child, placed_style) in placed_styles {
+                let result = if let Ok(calculated_size) = style_query.get::<CalculatedSize>(child) {
+                    flex_surface.upsert_leaf(child, &placed_style, *calculated_size)
+                } else {
+                    flex_surface.upsert_node(child, &placed_style)
+                };
+                if let Err(err) = result {
+                    log::warn!(
+                        "Failed to place the child {:?} of ui node {:?}, retrying next frame: {}",
+                        child,
+                        entity,
+                        err
+                    );
+                    state.pending_nodes.insert(child);
+                }
+                placed_children = true;
+            }
+        }
+
+        if !placed_children {
+            break;
+        }
+        if let Err(err) = flex_surface.compute_window_layouts() {
+            log::warn!("Failed to compute ui layouts: {}", err);
+        }
+    }
+
+    // clamp scroll offsets so the children's bounds stay within reach of the scrolled node's rect
+    let mut scroll_offsets = HashMap::new();
+    for (entity, style, mut scroll_position) in &mut scroll_query.iter() {
+        if style.overflow != Overflow::Scroll {
+            continue;
+        }
+
+        let layout = match flex_surface.get_layout(entity) {
+            Ok(layout) => layout,
+            Err(_) => continue,
+        };
+        let size = Vec2::new(layout.size.width, layout.size.height);
+        let mut children_min = Vec2::zero();
+        let mut children_max = Vec2::zero();
+        if let Ok(children) = style_query.get::<Children>(entity) {
+            for child in children.iter() {
+                if let Ok(child_layout) = flex_surface.get_layout(*child) {
+                    let location = Vec2::new(child_layout.location.x, child_layout.location.y);
+                    let child_size = Vec2::new(child_layout.size.width, child_layout.size.height);
+                    children_min = children_min.min(location);
+                    children_max = children_max.max(location + child_size);
+                }
+            }
+        }
+
+        let min_offset = children_min.min(Vec2::zero());
+        let max_offset = (children_max - size).max(Vec2::zero());
+        let offset = scroll_position.offset.max(min_offset).min(max_offset);
+        // avoid mutating unchanged scroll positions
+        if scroll_position.offset != offset {
+            scroll_position.offset = offset;
+        }
+
+        scroll_offsets.insert(entity, offset);
+    }
 
     for (entity, mut node, mut local, parent) in &mut node_transform_query.iter() {
-        let layout = flex_surface.get_layout(entity).unwrap();
-        node.size = Vec2::new(layout.size.width, layout.size.height);
-        let mut position = local.w_axis();
-        position.set_x(layout.location.x + layout.size.width / 2.0);
-        position.set_y(layout.location.y + layout.size.height / 2.0);
+        let layout = match flex_surface.get_layout(entity) {
+            Ok(layout) => {
+                if !state.nodes_without_layout.is_empty() {
+                    state.nodes_without_layout.remove(&entity);
+                }
+                layout
+            }
+            Err(err) => {
+                if state.nodes_without_layout.insert(entity) {
+                    log::warn!("Skipping ui node {:?} without a layout: {}", entity, err);
+                }
+                continue;
+            }
+        };
+        let size = Vec2::new(layout.size.width, layout.size.height);
+        let mut logical_position = Vec2::new(layout.location.x, layout.location.y) + size / 2.0;
         if let Some(parent) = parent {
             if let Ok(parent_layout) = flex_surface.get_layout(parent.0) {
-                *position.x_mut() -= parent_layout.size.width / 2.0;
-                *position.y_mut() -= parent_layout.size.height / 2.0;
+                logical_position -=
+                    Vec2::new(parent_layout.size.width, parent_layout.size.height) / 2.0;
+            }
+            if let Some(scroll_offset) = scroll_offsets.get(&parent.0) {
+                logical_position -= *scroll_offset;
             }
         }
 
+        // layouts are computed in logical pixels, but nodes are drawn in physical pixels
+        let scale_factor = flex_surface.scale_factor(entity) as f32;
+        node.size = size * scale_factor;
+        let mut position = local.w_axis();
+        position.set_x(logical_position.x() * scale_factor);
+        position.set_y(logical_position.y() * scale_factor);
         local.set_w_axis(position);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{flex_node_system, FlexError, FlexSurface, NodeLayout};
+    use crate::{AlignContent, CalculatedSize, FlexDirection, FlexWrap, Node, Style, UiScale, Val};
+    use bevy_ecs::{Entity, EntityReferences, IntoQuerySystem, Resources, Schedule, World};
+    use bevy_math::{Rect, Size, Vec2};
+    use bevy_transform::prelude::{Children, LocalTransform, Parent};
+    use bevy_window::{SafeAreaInsets, Window, WindowDescriptor, WindowId, Windows};
+
+    /// Lays out a root node with the given children, and returns the location of each child relative to the root
+    fn layout_children(parent_style: Style, child_styles: Vec<Style>) -> Vec<Vec2> {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: 800,
+                height: 600,
+                ..Default::default()
+            },
+        ));
+        resources.insert(windows);
+        resources.insert(UiScale::default());
+        resources.insert(SafeAreaInsets::default());
+        resources.insert(FlexSurface::default());
+
+        let children = child_styles
+            .into_iter()
+            .map(|style| world.spawn((Node::default(), style, LocalTransform::default())))
+            .collect::<Vec<_>>();
+        let parent = world.spawn((
+            Node::default(),
+            parent_style,
+            LocalTransform::default(),
+            Children::with(&children),
+        ));
+        for child in children.iter() {
+            world.insert_one(*child, Parent(parent)).unwrap();
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", flex_node_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        let flex_surface = resources.get::<FlexSurface>().unwrap();
+        children
+            .iter()
+            .map(|child| flex_surface.node_layout(*child).unwrap().location)
+            .collect()
+    }
+
+    fn item(width: f32, height: f32) -> Style {
+        Style {
+            size: Size::new(Val::Px(width), Val::Px(height)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn gap_between_items_and_lines() {
+        let parent_style = Style {
+            size: Size::new(Val::Px(230.0), Val::Px(200.0)),
+            flex_wrap: FlexWrap::Wrap,
+            align_content: AlignContent::FlexStart,
+            gap: Size::new(Val::Px(10.0), Val::Px(20.0)),
+            ..Default::default()
+        };
+        // four items and their gaps fit on the first line exactly. the first item of the second line has no gap
+        let locations = layout_children(parent_style, vec![item(50.0, 50.0); 5]);
+        assert_eq!(
+            locations,
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(60.0, 0.0),
+                Vec2::new(120.0, 0.0),
+                Vec2::new(180.0, 0.0),
+                Vec2::new(0.0, 70.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn gap_in_reversed_column() {
+        let parent_style = Style {
+            size: Size::new(Val::Px(100.0), Val::Px(200.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            gap: Size::new(Val::Undefined, Val::Percent(5.0)),
+            ..Default::default()
+        };
+        let locations = layout_children(parent_style, vec![item(50.0, 50.0); 3]);
+        assert_eq!(
+            locations,
+            vec![
+                Vec2::new(0.0, 150.0),
+                Vec2::new(0.0, 90.0),
+                Vec2::new(0.0, 30.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn gap_with_auto_margins() {
+        let parent_style = Style {
+            size: Size::new(Val::Px(300.0), Val::Px(100.0)),
+            gap: Size::new(Val::Px(10.0), Val::Undefined),
+            ..Default::default()
+        };
+        // the free space left after the gaps is split between the auto margins, which center the middle item
+        let centered = Style {
+            margin: Rect {
+                left: Val::Auto,
+                right: Val::Auto,
+                ..Default::default()
+            },
+            ..item(50.0, 50.0)
+        };
+        let locations = layout_children(
+            parent_style,
+            vec![item(50.0, 50.0), centered, item(50.0, 50.0)],
+        );
+        assert_eq!(
+            locations,
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(125.0, 0.0),
+                Vec2::new(250.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_children_before_child_is_added() {
+        let mut flex_surface = FlexSurface::default();
+        let parent = Entity::new();
+        let child = Entity::new();
+        flex_surface.upsert_node(parent, &Style::default()).unwrap();
+
+        match flex_surface.update_children(parent, vec![child].into_iter()) {
+            Err(FlexError::MissingNode(entity)) => assert_eq!(entity, child),
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(flex_surface.get_layout(child).is_err());
+
+        flex_surface.upsert_node(child, &Style::default()).unwrap();
+        flex_surface
+            .update_children(parent, vec![child].into_iter())
+            .unwrap();
+        assert_eq!(flex_surface.scale_factor(child), 1.0);
+    }
+
+    #[test]
+    fn measured_leaf_nodes() {
+        let mut flex_surface = FlexSurface::default();
+        let window = Window::new(WindowId::primary(), &WindowDescriptor::default());
+        let parent = Entity::new();
+        let image = Entity::new();
+        let empty = Entity::new();
+        let column = Style {
+            size: Size::new(Val::Px(100.0), Val::Px(200.0)),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        };
+        let measured = |width, height| CalculatedSize {
+            size: Size::new(width, height),
+            wraps: false,
+        };
+        flex_surface.upsert_node(parent, &column).unwrap();
+        flex_surface
+            .upsert_leaf(image, &Style::default(), measured(50.0, 25.0))
+            .unwrap();
+        flex_surface
+            .upsert_leaf(empty, &Style::default(), measured(0.0, 0.0))
+            .unwrap();
+        flex_surface
+            .update_children(parent, vec![image, empty].into_iter())
+            .unwrap();
+        flex_surface.update_window(&window, 1.0).unwrap();
+        flex_surface
+            .set_window_children(window.id, vec![parent].into_iter())
+            .unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+
+        // stretched leaves keep their aspect ratio, and empty content doesn't produce NaN sizes
+        let size =
+            |flex_surface: &FlexSurface, entity| flex_surface.node_layout(entity).unwrap().size;
+        assert_eq!(size(&flex_surface, image), Vec2::new(100.0, 50.0));
+        let empty_size = size(&flex_surface, empty);
+        assert!(empty_size.x().is_finite() && empty_size.y() == 0.0);
+
+        // a leaf that becomes a regular node is no longer measured, so it is laid out like any empty node
+        flex_surface.upsert_node(image, &Style::default()).unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+        assert_eq!(size(&flex_surface, image), Vec2::zero());
+    }
+
+    #[test]
+    fn node_layout() {
+        let mut flex_surface = FlexSurface::default();
+        let window = Window::new(WindowId::primary(), &WindowDescriptor::default());
+        let parent = Entity::new();
+        let child = Entity::new();
+        let padded = Style {
+            padding: Rect::all(Val::Px(10.0)),
+            ..item(100.0, 100.0)
+        };
+        flex_surface.upsert_node(parent, &padded).unwrap();
+        flex_surface.upsert_node(child, &item(20.0, 30.0)).unwrap();
+        flex_surface
+            .update_children(parent, vec![child].into_iter())
+            .unwrap();
+        flex_surface.update_window(&window, 2.0).unwrap();
+        flex_surface
+            .set_window_children(window.id, vec![parent].into_iter())
+            .unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+
+        // layouts are in logical pixels, relative to the parent
+        assert_eq!(
+            flex_surface.node_layout(child),
+            Some(NodeLayout {
+                size: Vec2::new(20.0, 30.0),
+                location: Vec2::new(10.0, 10.0),
+            })
+        );
+        assert_eq!(flex_surface.node_layout(Entity::new()), None);
+    }
+
+    #[test]
+    fn remove_entities() {
+        let mut flex_surface = FlexSurface::default();
+        let window = Window::new(WindowId::primary(), &WindowDescriptor::default());
+        let parent = Entity::new();
+        let children = [Entity::new(), Entity::new(), Entity::new()];
+        flex_surface.upsert_node(parent, &Style::default()).unwrap();
+        for child in children.iter() {
+            flex_surface.upsert_node(*child, &item(10.0, 10.0)).unwrap();
+        }
+        flex_surface
+            .update_children(parent, children.iter().cloned())
+            .unwrap();
+        flex_surface.update_window(&window, 1.0).unwrap();
+        flex_surface
+            .set_window_children(window.id, vec![parent].into_iter())
+            .unwrap();
+
+        // the remaining children keep their order and move into the removed child's place
+        flex_surface.remove_entities(vec![children[1]]).unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+        assert!(flex_surface.node_layout(children[1]).is_none());
+        assert_eq!(
+            flex_surface.node_layout(children[2]).unwrap().location,
+            Vec2::new(10.0, 0.0)
+        );
+
+        // despawned entities are removed through their EntityReferences
+        let despawned = [parent, children[0]];
+        let is_alive = |entity: Entity| !despawned.contains(&entity);
+        assert!(flex_surface.references_despawned(&is_alive));
+        flex_surface.remove_despawned(&is_alive);
+        assert!(!flex_surface.references_despawned(&is_alive));
+        assert!(flex_surface.node_layout(parent).is_none());
+        assert!(flex_surface.node_parents.is_empty());
+        flex_surface
+            .set_window_children(window.id, vec![children[2]].into_iter())
+            .unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+        assert_eq!(
+            flex_surface.node_layout(children[2]).unwrap().location,
+            Vec2::new(0.0, 0.0)
+        );
+
+        // new entities reuse the removed nodes
+        let node_count = flex_surface.free_nodes.len();
+        let entity = Entity::new();
+        flex_surface.upsert_node(entity, &item(20.0, 20.0)).unwrap();
+        assert_eq!(flex_surface.free_nodes.len(), node_count - 1);
+        flex_surface
+            .set_window_children(window.id, vec![entity, children[2]].into_iter())
+            .unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+        assert_eq!(
+            flex_surface.node_layout(entity).unwrap().size,
+            Vec2::new(20.0, 20.0)
+        );
+        assert_eq!(
+            flex_surface.node_layout(children[2]).unwrap().location,
+            Vec2::new(20.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn debug_tree() {
+        let mut flex_surface = FlexSurface::default();
+        let window = Window::new(WindowId::primary(), &WindowDescriptor::default());
+        let root = Entity::new();
+        let children = [Entity::new(), Entity::new()];
+        flex_surface.upsert_node(root, &Style::default()).unwrap();
+        for child in children.iter() {
+            flex_surface.upsert_node(*child, &Style::default()).unwrap();
+        }
+        flex_surface
+            .update_children(root, children.iter().cloned())
+            .unwrap();
+        flex_surface.update_window(&window, 1.0).unwrap();
+        flex_surface
+            .set_window_children(window.id, vec![root].into_iter())
+            .unwrap();
+        flex_surface.compute_window_layouts().unwrap();
+
+        let tree = flex_surface.debug_tree();
+        let lines = tree.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with(&format!("{:?}", window.id)));
+        assert!(lines[1].starts_with(&format!("└── {:?}", root)));
+        assert!(lines[2].starts_with(&format!("    ├── {:?}", children[0])));
+        assert!(lines[3].starts_with(&format!("    └── {:?}", children[1])));
+    }
+}
@@ -1,10 +1,8 @@
-use crate::Node;
+use crate::{HitTestMasks, UiStack};
 use bevy_app::{EventReader, Events};
-use bevy_core::FloatOrd;
 use bevy_ecs::prelude::*;
-use bevy_input::{mouse::MouseButton, Input};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
 use bevy_math::Vec2;
-use bevy_transform::components::Transform;
 use bevy_window::CursorMoved;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -32,89 +30,109 @@ impl Default for FocusPolicy {
     }
 }
 
+/// Whether the cursor is over interactive ui, which is any node with an [Interaction] that isn't covered by a blocking
+/// node. Gameplay systems that pick or raycast into the world should skip input while this is set, so clicks on ui
+/// don't reach the world behind it. This is set by [ui_focus_system] in the `PRE_UPDATE` stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointerOverUi {
+    /// The topmost interactive node under the cursor
+    pub entity: Option<Entity>,
+}
+
+impl PointerOverUi {
+    pub fn is_over_ui(&self) -> bool {
+        self.entity.is_some()
+    }
+}
+
 #[derive(Default)]
 pub struct State {
     cursor_moved_event_reader: EventReader<CursorMoved>,
-    cursor_position: Vec2,
+    /// The position of the mouse cursor or the primary touch. This is `None` after the primary touch ends, because
+    /// there is nothing left to hover.
+    cursor_position: Option<Vec2>,
     hovered_entity: Option<Entity>,
 }
 
 pub fn ui_focus_system(
     mut state: Local<State>,
     mouse_button_input: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
     cursor_moved_events: Res<Events<CursorMoved>>,
-    mut node_query: Query<(
-        Entity,
-        &Node,
-        &Transform,
-        Option<&mut Interaction>,
-        Option<&FocusPolicy>,
-    )>,
+    ui_stack: Res<UiStack>,
+    hit_test_masks: Res<HitTestMasks>,
+    mut pointer_over_ui: ResMut<PointerOverUi>,
+    mut interaction_query: Query<&mut Interaction>,
 ) {
     if let Some(cursor_moved) = state.cursor_moved_event_reader.latest(&cursor_moved_events) {
-        state.cursor_position = cursor_moved.position;
+        state.cursor_position = Some(cursor_moved.position);
     }
+    // the primary touch acts like the mouse cursor with the left button held down
+    let primary_touch = touches.primary_touch();
+    if let Some(touch) = primary_touch {
+        state.cursor_position = Some(touch.position);
+    }
+    let touch_pressed = primary_touch.map_or(false, |touch| touches.just_pressed(touch.id));
+    let touch_released = primary_touch.map_or(false, |touch| {
+        touches.just_released(touch.id) || touches.just_cancelled(touch.id)
+    });
 
-    if mouse_button_input.just_released(MouseButton::Left) {
-        for (_entity, _node, _transform, interaction, _focus_policy) in &mut node_query.iter() {
-            if let Some(mut interaction) = interaction {
-                if *interaction == Interaction::Clicked {
-                    *interaction = Interaction::None;
-                }
+    if mouse_button_input.just_released(MouseButton::Left) || touch_released {
+        for mut interaction in &mut interaction_query.iter() {
+            if *interaction == Interaction::Clicked {
+                *interaction = Interaction::None;
             }
         }
     }
 
-    let mouse_clicked = mouse_button_input.just_pressed(MouseButton::Left);
+    let mouse_clicked = mouse_button_input.just_pressed(MouseButton::Left) || touch_pressed;
     let mut hovered_entity = None;
+    pointer_over_ui.entity = None;
+    if touch_released {
+        state.cursor_position = None;
+    }
 
-    {
-        let mut query_iter = node_query.iter();
-        let mut moused_over_z_sorted_nodes = query_iter
-            .iter()
-            .filter_map(|(entity, node, transform, interaction, focus_policy)| {
-                let position = transform.value.w_axis();
-                let ui_position = position.truncate().truncate();
-                let extents = node.size / 2.0;
-                let min = ui_position - extents;
-                let max = ui_position + extents;
-                // if the current cursor position is within the bounds of the node, consider it for clicking
-                if (min.x()..max.x()).contains(&state.cursor_position.x())
-                    && (min.y()..max.y()).contains(&state.cursor_position.y())
-                {
-                    Some((entity, focus_policy, interaction, FloatOrd(position.z())))
-                } else {
-                    if let Some(mut interaction) = interaction {
-                        if *interaction == Interaction::Hovered {
-                            *interaction = Interaction::None;
-                        }
-                    }
-                    None
+    // the ui stack is sorted from the topmost node down
+    let cursor_position = state.cursor_position;
+    let mut blocked = false;
+    for node in ui_stack.iter() {
+        // if the current cursor position is within the hit shape of the node (and not clipped), consider it for
+        // clicking. nodes below a blocking node are occluded, so they can't stay hovered
+        let contains_cursor = !blocked
+            && cursor_position.map_or(false, |cursor_position| {
+                node.contains(cursor_position, &hit_test_masks)
+            });
+        let interaction = interaction_query.get_mut::<Interaction>(node.entity).ok();
+        if !contains_cursor {
+            if let Some(mut interaction) = interaction {
+                if *interaction == Interaction::Hovered {
+                    *interaction = Interaction::None;
                 }
-            })
-            .collect::<Vec<_>>();
+            }
+            continue;
+        }
 
-        moused_over_z_sorted_nodes.sort_by_key(|(_, _, _, z)| -*z);
-        for (entity, focus_policy, interaction, _) in moused_over_z_sorted_nodes {
-            if let Some(mut interaction) = interaction {
-                if mouse_clicked {
-                    // only consider nodes with ClickState "clickable"
-                    if *interaction != Interaction::Clicked {
-                        *interaction = Interaction::Clicked;
-                    }
-                } else if *interaction == Interaction::None {
-                    *interaction = Interaction::Hovered;
+        if let Some(mut interaction) = interaction {
+            if pointer_over_ui.entity.is_none() {
+                pointer_over_ui.entity = Some(node.entity);
+            }
+            if mouse_clicked {
+                // only consider nodes with ClickState "clickable"
+                if *interaction != Interaction::Clicked {
+                    *interaction = Interaction::Clicked;
                 }
+            } else if *interaction == Interaction::None {
+                *interaction = Interaction::Hovered;
             }
+        }
 
-            hovered_entity = Some(entity);
+        hovered_entity = Some(node.entity);
 
-            match focus_policy.cloned().unwrap_or(FocusPolicy::Block) {
-                FocusPolicy::Block => {
-                    break;
-                }
-                FocusPolicy::Pass => { /* allow the next node to be hovered/clicked */ }
+        match node.focus_policy {
+            FocusPolicy::Block => {
+                blocked = true;
             }
+            FocusPolicy::Pass => { /* allow the next node to be hovered/clicked */ }
         }
     }
 
@@ -122,7 +140,9 @@ pub fn ui_focus_system(
     if let Some(new_hovered_entity) = hovered_entity {
         if let Some(old_hovered_entity) = state.hovered_entity {
             if new_hovered_entity != old_hovered_entity {
-                if let Ok(mut interaction) = node_query.get_mut::<Interaction>(old_hovered_entity) {
+                if let Ok(mut interaction) =
+                    interaction_query.get_mut::<Interaction>(old_hovered_entity)
+                {
                     if *interaction == Interaction::Hovered {
                         *interaction = Interaction::None;
                     }
@@ -133,3 +153,116 @@ pub fn ui_focus_system(
         state.hovered_entity = hovered_entity;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ui_focus_system, FocusPolicy, Interaction, PointerOverUi};
+    use crate::{ui_stack_system, FlexSurface, HitTestMasks, Node, UiStack};
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+    use bevy_math::{Mat4, Vec2, Vec3};
+    use bevy_transform::components::Transform;
+    use bevy_window::{CursorMoved, WindowId};
+
+    fn focus_schedule(resources: &mut Resources) -> Schedule {
+        resources.insert(Input::<MouseButton>::default());
+        resources.insert(Touches::default());
+        resources.insert(Events::<CursorMoved>::default());
+        resources.insert(FlexSurface::default());
+        resources.insert(HitTestMasks::default());
+        resources.insert(PointerOverUi::default());
+        resources.insert(UiStack::default());
+        let mut schedule = Schedule::default();
+        schedule.add_stage("ui_stack");
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("ui_stack", ui_stack_system.system());
+        schedule.add_system_to_stage("update", ui_focus_system.system());
+        schedule.initialize(resources);
+        schedule
+    }
+
+    fn node(z: f32) -> (Node, Transform) {
+        (
+            Node {
+                size: Vec2::new(100.0, 100.0),
+                ..Default::default()
+            },
+            Transform::new(Mat4::from_translation(Vec3::new(50.0, 50.0, z))),
+        )
+    }
+
+    fn move_cursor(resources: &Resources, position: Vec2) {
+        resources
+            .get_mut::<Events<CursorMoved>>()
+            .unwrap()
+            .send(CursorMoved {
+                id: WindowId::primary(),
+                position,
+            });
+    }
+
+    #[test]
+    fn occluded_nodes_are_not_hovered() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut schedule = focus_schedule(&mut resources);
+        let bottom = world.spawn(node(1.0));
+        world.insert_one(bottom, Interaction::None).unwrap();
+        let top = world.spawn(node(2.0));
+        world
+            .insert(top, (Interaction::None, FocusPolicy::Pass))
+            .unwrap();
+        move_cursor(&resources, Vec2::new(60.0, 60.0));
+
+        // nodes below a node that lets focus pass are hovered too
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            *world.get::<Interaction>(top).unwrap(),
+            Interaction::Hovered
+        );
+        assert_eq!(
+            *world.get::<Interaction>(bottom).unwrap(),
+            Interaction::Hovered
+        );
+        assert_eq!(resources.get::<PointerOverUi>().unwrap().entity, Some(top));
+
+        // once the top node blocks focus, the node below it stops being hovered
+        *world.get_mut::<FocusPolicy>(top).unwrap() = FocusPolicy::Block;
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(
+            *world.get::<Interaction>(top).unwrap(),
+            Interaction::Hovered
+        );
+        assert_eq!(
+            *world.get::<Interaction>(bottom).unwrap(),
+            Interaction::None
+        );
+    }
+
+    #[test]
+    fn pointer_over_ui() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut schedule = focus_schedule(&mut resources);
+        let button = world.spawn(node(1.0));
+        world.insert_one(button, Interaction::None).unwrap();
+        // nodes without an Interaction (ex: panels) aren't interactive, but still block the nodes below them
+        let panel = world.spawn(node(2.0));
+        let pointer_over_ui =
+            |resources: &Resources| resources.get::<PointerOverUi>().unwrap().entity;
+
+        move_cursor(&resources, Vec2::new(60.0, 60.0));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(pointer_over_ui(&resources), None);
+
+        world.insert_one(panel, FocusPolicy::Pass).unwrap();
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(pointer_over_ui(&resources), Some(button));
+        assert!(resources.get::<PointerOverUi>().unwrap().is_over_ui());
+
+        move_cursor(&resources, Vec2::new(160.0, 60.0));
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(pointer_over_ui(&resources), None);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{GridPlacement, GridTrack};
+use bevy_math::Vec2;
+
+/// A child's rect in a grid, relative to the bottom left corner of the grid's content area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridCell {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+/// Places items in a grid with the given tracks. `items` contains the (column, row) placement of each item and the
+/// returned cells are in the same order.
+pub fn compute_grid_cells(
+    content_size: Vec2,
+    gap: Vec2,
+    columns: &[GridTrack],
+    rows: &[GridTrack],
+    items: &[(GridPlacement, GridPlacement)],
+) -> Vec<GridCell> {
+    let column_count = columns.len().max(1);
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let mut auto_cursor = (0, 0);
+    let mut placed = Vec::with_capacity(items.len());
+    for (column, row) in items.iter() {
+        let column_span = column.span.max(1).min(column_count);
+        let row_span = row.span.max(1);
+        let column_start = column
+            .start
+            .map(|start| start.min(column_count - column_span));
+        let (column_start, row_start) = match (column_start, row.start) {
+            (Some(column_start), Some(row_start)) => (column_start, row_start),
+            (None, Some(row_start)) => {
+                let column_start = (0..=column_count - column_span)
+                    .find(|column_start| {
+                        is_free(&occupied, *column_start, row_start, column_span, row_span)
+                    })
+                    .unwrap_or(0);
+                (column_start, row_start)
+            }
+            (Some(column_start), None) => {
+                let row_start = (0..)
+                    .find(|row_start| {
+                        is_free(&occupied, column_start, *row_start, column_span, row_span)
+                    })
+                    .unwrap();
+                (column_start, row_start)
+            }
+            (None, None) => {
+                // auto placed items fill the grid row by row, after the previous auto placed item
+                let (mut column_start, mut row_start) = auto_cursor;
+                loop {
+                    if column_start + column_span > column_count {
+                        column_start = 0;
+                        row_start += 1;
+                    }
+                    if is_free(&occupied, column_start, row_start, column_span, row_span) {
+                        break;
+                    }
+                    column_start += 1;
+                }
+                auto_cursor = (column_start + column_span, row_start);
+                (column_start, row_start)
+            }
+        };
+
+        for row_index in row_start..row_start + row_span {
+            if occupied.len() <= row_index {
+                occupied.resize(row_index + 1, vec![false; column_count]);
+            }
+            for column_index in column_start..column_start + column_span {
+                occupied[row_index][column_index] = true;
+            }
+        }
+
+        placed.push((column_start, column_span, row_start, row_span));
+    }
+
+    let (column_offsets, column_sizes) =
+        resolve_tracks(columns, column_count, content_size.x(), gap.x());
+    let row_count = rows.len().max(occupied.len());
+    let (row_offsets, row_sizes) = resolve_tracks(rows, row_count, content_size.y(), gap.y());
+
+    placed
+        .into_iter()
+        .map(|(column_start, column_span, row_start, row_span)| {
+            let column_end = column_start + column_span - 1;
+            let row_end = row_start + row_span - 1;
+            let x = column_offsets[column_start];
+            let width = column_offsets[column_end] + column_sizes[column_end] - x;
+            let top = row_offsets[row_start];
+            let height = row_offsets[row_end] + row_sizes[row_end] - top;
+            // rows are laid out from top to bottom, but ui positions start at the bottom
+            GridCell {
+                position: Vec2::new(x, content_size.y() - top - height),
+                size: Vec2::new(width, height),
+            }
+        })
+        .collect()
+}
+
+fn is_free(
+    occupied: &[Vec<bool>],
+    column_start: usize,
+    row_start: usize,
+    column_span: usize,
+    row_span: usize,
+) -> bool {
+    (row_start..row_start + row_span).all(|row_index| match occupied.get(row_index) {
+        Some(row) => row[column_start..column_start + column_span]
+            .iter()
+            .all(|occupied| !occupied),
+        None => true,
+    })
+}
+
+/// Returns the offset and size of each track. Tracks past the end of `tracks` use `GridTrack::Fr(1.0)`.
+fn resolve_tracks(
+    tracks: &[GridTrack],
+    count: usize,
+    available: f32,
+    gap: f32,
+) -> (Vec<f32>, Vec<f32>) {
+    let track = |index: usize| tracks.get(index).cloned().unwrap_or(GridTrack::Fr(1.0));
+    let mut fixed_size = 0.0;
+    let mut total_fr = 0.0;
+    for index in 0..count {
+        match track(index) {
+            GridTrack::Px(size) => fixed_size += size,
+            GridTrack::Percent(percent) => fixed_size += available * percent / 100.0,
+            GridTrack::Fr(fr) => total_fr += fr,
+        }
+    }
+
+    let total_gap = gap * count.saturating_sub(1) as f32;
+    let free_space = (available - fixed_size - total_gap).max(0.0);
+    let mut offsets = Vec::with_capacity(count);
+    let mut sizes = Vec::with_capacity(count);
+    let mut offset = 0.0;
+    for index in 0..count {
+        let size = match track(index) {
+            GridTrack::Px(size) => size,
+            GridTrack::Percent(percent) => available * percent / 100.0,
+            GridTrack::Fr(fr) if total_fr > 0.0 => free_space * fr / total_fr,
+            GridTrack::Fr(_) => 0.0,
+        };
+        offsets.push(offset);
+        sizes.push(size);
+        offset += size + gap;
+    }
+
+    (offsets, sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_grid_cells, GridCell};
+    use crate::{GridPlacement, GridTrack};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn auto_placement() {
+        let cells = compute_grid_cells(
+            Vec2::new(100.0, 100.0),
+            Vec2::zero(),
+            &[GridTrack::Px(20.0), GridTrack::Fr(1.0), GridTrack::Fr(3.0)],
+            &[],
+            &[
+                (GridPlacement::default(), GridPlacement::default()),
+                (GridPlacement::span(2), GridPlacement::default()),
+                (GridPlacement::default(), GridPlacement::default()),
+            ],
+        );
+
+        assert_eq!(
+            cells,
+            vec![
+                GridCell {
+                    position: Vec2::new(0.0, 50.0),
+                    size: Vec2::new(20.0, 50.0),
+                },
+                GridCell {
+                    position: Vec2::new(20.0, 50.0),
+                    size: Vec2::new(80.0, 50.0),
+                },
+                GridCell {
+                    position: Vec2::new(0.0, 0.0),
+                    size: Vec2::new(20.0, 50.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_placement_and_gaps() {
+        let cells = compute_grid_cells(
+            Vec2::new(110.0, 50.0),
+            Vec2::new(10.0, 0.0),
+            &[GridTrack::Fr(1.0), GridTrack::Fr(1.0)],
+            &[GridTrack::Percent(100.0)],
+            &[
+                (GridPlacement::start(1), GridPlacement::start(0)),
+                (GridPlacement::default(), GridPlacement::default()),
+            ],
+        );
+
+        assert_eq!(
+            cells,
+            vec![
+                GridCell {
+                    position: Vec2::new(60.0, 0.0),
+                    size: Vec2::new(50.0, 50.0),
+                },
+                GridCell {
+                    position: Vec2::new(0.0, 0.0),
+                    size: Vec2::new(50.0, 50.0),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use bevy_app::{EventReader, Events};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::{Local, Query, Res, ResMut};
+use bevy_math::Vec2;
+use bevy_render::texture::Texture;
+use std::collections::HashMap;
+
+/// The shape of a ui node that the pointer hovers and clicks. Nodes without a `HitTest` use `HitTest::Rect`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HitTest {
+    /// The node's rect
+    Rect,
+    /// The pointer passes through the node, as if it didn't exist. Unlike [FocusPolicy::Pass](crate::FocusPolicy),
+    /// the node is never hovered, so decorative overlays don't take the hover from nodes below them.
+    None,
+    /// The largest circle that fits in the node, centered in it
+    Circle,
+    /// The node's rect with corners rounded by `radius` logical pixels
+    RoundedRect { radius: f32 },
+    /// The pixels of `texture`, stretched over the node, whose alpha is at least `threshold` (0.0 to 1.0). Only
+    /// textures with 8 bits per channel and an alpha channel have a mask, and the node's rect is used until the
+    /// texture is loaded.
+    AlphaMask {
+        texture: Handle<Texture>,
+        threshold: f32,
+    },
+}
+
+impl Default for HitTest {
+    fn default() -> Self {
+        HitTest::Rect
+    }
+}
+
+impl HitTest {
+    /// Whether `position`, relative to the node's center, is on a node of the given size. Sizes and positions are in
+    /// physical pixels, with y pointing up.
+    pub fn contains(
+        &self,
+        position: Vec2,
+        size: Vec2,
+        scale_factor: f32,
+        masks: &HitTestMasks,
+    ) -> bool {
+        let extents = size / 2.0;
+        let in_rect = position.x().abs() <= extents.x() && position.y().abs() <= extents.y();
+        match self {
+            HitTest::Rect => in_rect,
+            HitTest::None => false,
+            HitTest::Circle => position.length() <= extents.x().min(extents.y()),
+            HitTest::RoundedRect { radius } => {
+                let radius = (radius * scale_factor)
+                    .min(extents.x())
+                    .min(extents.y())
+                    .max(0.0);
+                // the distance from the rect shrunk by the radius
+                let outside =
+                    (position.abs() - (extents - Vec2::new(radius, radius))).max(Vec2::zero());
+                in_rect && outside.length() <= radius
+            }
+            HitTest::AlphaMask { texture, threshold } => {
+                if !in_rect {
+                    return false;
+                }
+                match masks.get(*texture) {
+                    Some(mask) if size.x() > 0.0 && size.y() > 0.0 => {
+                        // textures start at the top left, but ui positions point up
+                        let uv =
+                            Vec2::new(position.x() / size.x() + 0.5, 0.5 - position.y() / size.y());
+                        mask.alpha(uv) >= *threshold
+                    }
+                    _ => true,
+                }
+            }
+        }
+    }
+}
+
+/// The alpha channel of a texture, kept on the cpu for [HitTest::AlphaMask]
+#[derive(Debug, Clone)]
+pub struct AlphaMask {
+    width: usize,
+    height: usize,
+    alpha: Vec<u8>,
+}
+
+impl AlphaMask {
+    /// Returns `None` if the texture's format has no 8 bit alpha channel
+    pub fn from_texture(texture: &Texture) -> Option<Self> {
+        let pixel_info = texture.format.pixel_info();
+        if pixel_info.type_size != 1 || pixel_info.num_components != 4 {
+            return None;
+        }
+        let width = texture.size.x() as usize;
+        let height = texture.size.y() as usize;
+        let alpha = texture
+            .data
+            .chunks_exact(4)
+            .map(|pixel| pixel[3])
+            .collect::<Vec<_>>();
+        if width == 0 || height == 0 || alpha.len() < width * height {
+            return None;
+        }
+        Some(AlphaMask {
+            width,
+            height,
+            alpha,
+        })
+    }
+
+    /// The alpha of the pixel at `uv`, from 0.0 to 1.0
+    pub fn alpha(&self, uv: Vec2) -> f32 {
+        let x = ((uv.x() * self.width as f32) as usize).min(self.width - 1);
+        let y = ((uv.y() * self.height as f32) as usize).min(self.height - 1);
+        self.alpha[y * self.width + x] as f32 / 255.0
+    }
+}
+
+/// The [AlphaMask]s of the textures used by [HitTest::AlphaMask]
+#[derive(Debug, Default)]
+pub struct HitTestMasks {
+    masks: HashMap<Handle<Texture>, Option<AlphaMask>>,
+}
+
+impl HitTestMasks {
+    pub fn get(&self, texture: Handle<Texture>) -> Option<&AlphaMask> {
+        self.masks.get(&texture).and_then(|mask| mask.as_ref())
+    }
+}
+
+/// Creates the [AlphaMask]s of loaded textures used by [HitTest::AlphaMask], and updates them when the textures
+/// change
+pub fn hit_test_mask_system(
+    mut texture_event_reader: Local<EventReader<AssetEvent<Texture>>>,
+    texture_events: Res<Events<AssetEvent<Texture>>>,
+    textures: Res<Assets<Texture>>,
+    mut masks: ResMut<HitTestMasks>,
+    mut query: Query<&HitTest>,
+) {
+    for event in texture_event_reader.iter(&texture_events) {
+        match event {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => {
+                masks.masks.remove(handle);
+            }
+        }
+    }
+
+    for hit_test in &mut query.iter() {
+        if let HitTest::AlphaMask { texture, .. } = hit_test {
+            if masks.masks.contains_key(texture) {
+                continue;
+            }
+            if let Some(texture_asset) = textures.get(texture) {
+                masks
+                    .masks
+                    .insert(*texture, AlphaMask::from_texture(texture_asset));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HitTest, HitTestMasks};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn hit_test_shapes() {
+        let masks = HitTestMasks::default();
+        let size = Vec2::new(100.0, 50.0);
+        let corner = Vec2::new(49.0, 24.0);
+        let center = Vec2::zero();
+
+        assert!(HitTest::Rect.contains(corner, size, 1.0, &masks));
+        assert!(!HitTest::Rect.contains(Vec2::new(51.0, 0.0), size, 1.0, &masks));
+        assert!(!HitTest::None.contains(center, size, 1.0, &masks));
+
+        assert!(HitTest::Circle.contains(Vec2::new(0.0, 24.0), size, 1.0, &masks));
+        assert!(!HitTest::Circle.contains(Vec2::new(30.0, 0.0), size, 1.0, &masks));
+
+        // corners are cut off, edges aren't
+        let rounded = HitTest::RoundedRect { radius: 10.0 };
+        assert!(!rounded.contains(corner, size, 1.0, &masks));
+        assert!(rounded.contains(Vec2::new(49.0, 0.0), size, 1.0, &masks));
+        assert!(rounded.contains(Vec2::new(45.0, 22.0), size, 1.0, &masks));
+        // the radius is in logical pixels
+        assert!(!rounded.contains(Vec2::new(45.0, 22.0), size, 2.0, &masks));
+    }
+}
@@ -1,33 +1,66 @@
 mod anchors;
+mod debug;
 pub mod entity;
 mod flex;
 mod focus;
+mod grid;
+mod hit_test;
 mod margins;
+mod navigation;
 mod node;
 mod render;
+mod replay;
+mod responsive;
+mod scroll;
+mod split_screen;
+mod toast;
+mod ui_builder;
+mod ui_stack;
 pub mod update;
+mod virtual_cursor;
 pub mod widget;
+mod world_anchor;
 
 pub use anchors::*;
+pub use debug::*;
 pub use flex::*;
 pub use focus::*;
+pub use grid::*;
+pub use hit_test::*;
 pub use margins::*;
+pub use navigation::*;
 pub use node::*;
 pub use render::*;
+pub use replay::*;
+pub use responsive::*;
+pub use scroll::*;
+pub use split_screen::*;
+pub use toast::*;
+pub use ui_stack::*;
+pub use update::ZIndex;
+pub use virtual_cursor::*;
+pub use world_anchor::*;
 
 pub mod prelude {
     pub use crate::{
         entity::*,
         node::*,
-        widget::{Button, Text},
-        Anchors, Interaction, Margins,
+        widget::{
+            Button, FillDirection, FillMaterial, ImageMode, ProgressBar, RadialFill, Text,
+            TextAlignment, TextEditor,
+        },
+        Anchors, Breakpoint, BreakpointScale, Focus, FocusActivated, FocusChanged, Focusable,
+        HitTest, Interaction, Margins, OffscreenMode, PointerOverUi, ResponsiveStyle, Toast,
+        ToastSeverity, Toasts, UiBreakpoints, UiStack, VirtualCursor, WorldAnchor,
+        WorldAnchorIndicator, ZIndex,
     };
 }
 
 use bevy_app::prelude::*;
-use bevy_ecs::IntoQuerySystem;
-use bevy_render::render_graph::RenderGraph;
-use update::ui_z_system;
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use bevy_render::{render_graph::RenderGraph, shader::asset_shader_defs_system};
+use bevy_window::{Window, WindowDescriptor, WindowId, Windows};
+use update::{ui_clip_system, ui_opacity_system, ui_target_window_system, ui_z_system};
 
 #[derive(Default)]
 pub struct UiPlugin;
@@ -38,18 +71,117 @@ pub mod stage {
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.init_resource::<FlexSurface>()
-            .add_stage_before(bevy_app::stage::POST_UPDATE, stage::UI)
-            .add_system_to_stage(bevy_app::stage::PRE_UPDATE, ui_focus_system.system())
-            // add these stages to front because these must run before transform update systems
-            .add_system_to_stage(stage::UI, widget::text_system.system())
-            .add_system_to_stage(stage::UI, widget::image_node_system.system())
-            .add_system_to_stage(stage::UI, ui_z_system.system())
-            .add_system_to_stage(stage::UI, flex_node_system.system())
-            .add_system_to_stage(bevy_render::stage::DRAW, widget::draw_text_system.system());
-
-        let resources = app.resources();
-        let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
-        render_graph.add_ui_graph(resources);
+        add_ui_systems(app, |app| {
+            app.add_system_to_stage(stage::UI, split_screen_ui_system.system())
+                .add_system_to_stage(stage::UI, world_anchor_system.system())
+                .add_system_to_stage(stage::UI, widget::text_system.system())
+                .add_system_to_stage(stage::UI, widget::image_node_system.system())
+                .add_system_to_stage(stage::UI, widget::text_editor_system.system())
+                .add_system_to_stage(stage::UI, widget::text_editor_highlight_system.system())
+                .add_system_to_stage(stage::UI, widget::fill_system.system())
+                .add_system_to_stage(stage::UI, toast_system.system());
+        });
+        app.init_resource::<UiDebugOptions>()
+            .init_resource::<VirtualCursor>()
+            .init_resource::<widget::UiClipboard>()
+            .init_resource::<Toasts>()
+            .add_system_to_stage(bevy_app::stage::FIRST, virtual_cursor_system.system())
+            .add_system_to_stage(bevy_app::stage::FIRST, hit_test_mask_system.system())
+            .add_system_to_stage(stage::UI, widget::image_slice_system.system())
+            .add_system_to_stage(stage::UI, widget::image_align_system.system())
+            .add_system_to_stage(bevy_app::stage::POST_UPDATE, ui_debug_system.system())
+            .add_system_to_stage(
+                bevy_app::stage::POST_UPDATE,
+                asset_shader_defs_system::<widget::FillMaterial>.system(),
+            )
+            .add_system_to_stage(
+                bevy_app::stage::POST_UPDATE,
+                world_anchor_indicator_system.system(),
+            )
+            .add_system_to_stage(bevy_render::stage::DRAW, widget::draw_text_system.system())
+            .add_system_to_stage(
+                bevy_render::stage::DRAW,
+                widget::draw_text_editor_system.system(),
+            )
+            .add_system_to_stage(
+                bevy_render::stage::DRAW,
+                render::ui_render_stats_system.system(),
+            );
+
+        {
+            let resources = app.resources();
+            let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
+            render_graph.add_ui_graph(resources);
+        }
+
+        // ui materials add their nodes to the ui graph
+        app.add_plugin(UiMaterialPlugin::<widget::FillMaterial>::default());
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<RenderGraph>().requires::<Windows>();
+    }
+}
+
+/// Runs ui layout and interaction without rendering, ex: to replay a [UiInputRecording] against a menu in a test. Text
+/// and image nodes aren't measured. This requires the input, window, and transform plugins. If the app has no primary
+/// window, one is added with the size of the [WindowDescriptor] resource.
+#[derive(Default)]
+pub struct HeadlessUiPlugin;
+
+impl Plugin for HeadlessUiPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        if app.resources().get::<WindowDescriptor>().is_none() {
+            app.init_resource::<WindowDescriptor>();
+        }
+        app.add_system_to_stage_front(bevy_app::stage::FIRST, headless_window_system.system());
+        add_ui_systems(app, |_| {});
+    }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<Windows>();
+    }
+}
+
+/// Adds a primary window with the size of the [WindowDescriptor] resource if there is none, as headless apps don't have
+/// a windowing backend to create it
+fn headless_window_system(mut windows: ResMut<Windows>, window_descriptor: Res<WindowDescriptor>) {
+    if windows.get_primary().is_none() {
+        windows.add(Window::new(WindowId::primary(), &window_descriptor));
+    }
+}
+
+/// Adds the resources and systems shared by [UiPlugin] and [HeadlessUiPlugin]. `add_widget_systems` adds the systems
+/// of the ui stage that must run before layout, ex: because they measure nodes.
+fn add_ui_systems(app: &mut AppBuilder, add_widget_systems: impl FnOnce(&mut AppBuilder)) {
+    app.init_resource::<FlexSurface>()
+        .add_entity_references_resource::<FlexSurface>()
+        .init_resource::<UiScale>()
+        .init_resource::<UiBreakpoints>()
+        .init_resource::<HitTestMasks>()
+        .init_resource::<UiStack>()
+        .init_resource::<PointerOverUi>()
+        .init_resource::<UiInputRecorder>()
+        .init_resource::<UiInputReplay>()
+        .init_resource::<Focus>()
+        .add_event::<FocusChanged>()
+        .add_event::<FocusActivated>()
+        .add_stage_before(bevy_app::stage::POST_UPDATE, stage::UI)
+        // both systems access the input events and UiInputReplay, so the recorder always runs after the replay and
+        // can tell which events were replayed
+        .add_system_to_stage(bevy_app::stage::FIRST, ui_input_replay_system.system())
+        .add_system_to_stage(bevy_app::stage::FIRST, ui_input_record_system.system())
+        .add_system_to_stage(bevy_app::stage::PRE_UPDATE, ui_focus_system.system())
+        .add_system_to_stage(bevy_app::stage::PRE_UPDATE, ui_navigation_system.system())
+        .add_system_to_stage(bevy_app::stage::PRE_UPDATE, ui_scroll_system.system())
+        // add these stages to front because these must run before transform update systems
+        .add_system_to_stage(stage::UI, ui_breakpoint_system.system());
+    add_widget_systems(app);
+    app.add_system_to_stage(stage::UI, ui_z_system.system())
+        .add_system_to_stage(stage::UI, ui_opacity_system.system())
+        .add_system_to_stage(stage::UI, ui_target_window_system.system())
+        .add_system_to_stage(stage::UI, flex_node_system.system())
+        // clip rects are computed from global transforms, so this must run after transform propagation
+        .add_system_to_stage(bevy_app::stage::POST_UPDATE, ui_clip_system.system())
+        .add_system_to_stage(bevy_app::stage::POST_UPDATE, ui_stack_system.system());
 }
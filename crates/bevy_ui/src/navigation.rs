@@ -0,0 +1,271 @@
+use crate::Node;
+use bevy_app::Events;
+use bevy_core::FloatOrd;
+use bevy_ecs::prelude::*;
+use bevy_input::{keyboard::KeyCode, Input};
+use bevy_math::Vec2;
+use bevy_transform::components::Transform;
+
+/// Marks a ui node that can receive keyboard focus
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Focusable {
+    /// Tab moves focus through nodes in ascending order. Nodes with the same order are visited top to bottom, then
+    /// left to right.
+    pub tab_order: i32,
+}
+
+/// A move of the [Focus] to another [Focusable] node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusMove {
+    /// The next node in tab order
+    Next,
+    /// The previous node in tab order
+    Previous,
+    /// The closest node above the focused node
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// The [Focusable] node that has keyboard focus. Tab and shift+tab move focus in tab order, the arrow keys move it to
+/// the closest node in their direction, and enter or space activate the focused node. Other input (ex: a gamepad) can
+/// drive focus with [Focus::request_move] and [Focus::request_activate].
+#[derive(Debug, Default)]
+pub struct Focus {
+    focused: Option<Entity>,
+    requested_focus: Option<Option<Entity>>,
+    requested_moves: Vec<FocusMove>,
+    activate_requested: bool,
+}
+
+impl Focus {
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    /// Focuses `entity` when focus is next updated
+    pub fn set(&mut self, entity: Entity) {
+        self.requested_focus = Some(Some(entity));
+    }
+
+    /// Removes focus from the focused node when focus is next updated
+    pub fn clear(&mut self) {
+        self.requested_focus = Some(None);
+    }
+
+    pub fn request_move(&mut self, focus_move: FocusMove) {
+        self.requested_moves.push(focus_move);
+    }
+
+    /// Activates the focused node when focus is next updated
+    pub fn request_activate(&mut self) {
+        self.activate_requested = true;
+    }
+}
+
+/// Sent when the [Focus] moves to another node, or is cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusChanged {
+    pub previous: Option<Entity>,
+    pub focused: Option<Entity>,
+}
+
+/// Sent when the focused node is activated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusActivated {
+    pub entity: Entity,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FocusNode {
+    entity: Entity,
+    tab_order: i32,
+    /// The center of the node in window space, where y points up
+    position: Vec2,
+}
+
+/// Returns the node `focus_move` moves focus to from `focused`. Tab moves wrap around, and directional moves pick the
+/// closest node whose center lies in their direction, preferring nodes that are aligned with the focused node.
+fn navigate(nodes: &[FocusNode], focused: Option<Entity>, focus_move: FocusMove) -> Option<Entity> {
+    let mut tab_ordered = nodes.to_vec();
+    tab_ordered.sort_by_key(|node| {
+        (
+            node.tab_order,
+            FloatOrd(-node.position.y()),
+            FloatOrd(node.position.x()),
+        )
+    });
+    let current = focused.and_then(|focused| nodes.iter().find(|node| node.entity == focused));
+    let current = match current {
+        Some(current) => current,
+        // without a focused node, any move focuses the first node
+        None => return tab_ordered.first().map(|node| node.entity),
+    };
+
+    let direction = match focus_move {
+        FocusMove::Next | FocusMove::Previous => {
+            let index = tab_ordered
+                .iter()
+                .position(|node| node.entity == current.entity)
+                .unwrap();
+            let index = if focus_move == FocusMove::Next {
+                (index + 1) % tab_ordered.len()
+            } else {
+                (index + tab_ordered.len() - 1) % tab_ordered.len()
+            };
+            return Some(tab_ordered[index].entity);
+        }
+        FocusMove::Up => Vec2::new(0.0, 1.0),
+        FocusMove::Down => Vec2::new(0.0, -1.0),
+        FocusMove::Left => Vec2::new(-1.0, 0.0),
+        FocusMove::Right => Vec2::new(1.0, 0.0),
+    };
+    nodes
+        .iter()
+        .filter_map(|node| {
+            let offset = node.position - current.position;
+            let along = offset.dot(direction);
+            if node.entity == current.entity || along <= 0.0 {
+                return None;
+            }
+            let across = (offset - direction * along).length();
+            Some((node.entity, FloatOrd(along + across * 2.0)))
+        })
+        .min_by_key(|(_entity, distance)| *distance)
+        .map(|(entity, _distance)| entity)
+        .or(Some(current.entity))
+}
+
+/// Moves the [Focus] with keyboard input and focus requests, and sends [FocusChanged] and [FocusActivated] events.
+/// Nodes are navigated between using the geometry computed by the flex layout.
+pub fn ui_navigation_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut focus: ResMut<Focus>,
+    mut focus_changed_events: ResMut<Events<FocusChanged>>,
+    mut focus_activated_events: ResMut<Events<FocusActivated>>,
+    mut node_query: Query<(Entity, &Node, &Transform, &Focusable)>,
+) {
+    let nodes = node_query
+        .iter()
+        .iter()
+        .filter_map(|(entity, node, transform, focusable)| {
+            let position = transform.value.w_axis().truncate().truncate();
+            // nodes that are empty or clipped away can't be seen, so they can't be focused
+            if node.size == Vec2::zero() || !node.clip_contains(position) {
+                return None;
+            }
+            Some(FocusNode {
+                entity,
+                tab_order: focusable.tab_order,
+                position,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let previous = focus.focused;
+    // focus is lost when the focused node is despawned or stops being focusable
+    if let Some(focused) = focus.focused {
+        if !nodes.iter().any(|node| node.entity == focused) {
+            focus.focused = None;
+        }
+    }
+    if let Some(requested_focus) = focus.requested_focus.take() {
+        focus.focused =
+            requested_focus.filter(|requested| nodes.iter().any(|node| node.entity == *requested));
+    }
+
+    let shift = keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
+    let mut moves = std::mem::take(&mut focus.requested_moves);
+    for (key_code, focus_move) in [
+        (KeyCode::Up, FocusMove::Up),
+        (KeyCode::Down, FocusMove::Down),
+        (KeyCode::Left, FocusMove::Left),
+        (KeyCode::Right, FocusMove::Right),
+    ]
+    .iter()
+    {
+        if keyboard_input.just_pressed(*key_code) {
+            moves.push(*focus_move);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        moves.push(if shift {
+            FocusMove::Previous
+        } else {
+            FocusMove::Next
+        });
+    }
+    for focus_move in moves {
+        focus.focused = navigate(&nodes, focus.focused, focus_move);
+    }
+
+    if focus.focused != previous {
+        focus_changed_events.send(FocusChanged {
+            previous,
+            focused: focus.focused,
+        });
+    }
+
+    let activate = keyboard_input.just_pressed(KeyCode::Return)
+        || keyboard_input.just_pressed(KeyCode::NumpadEnter)
+        || keyboard_input.just_pressed(KeyCode::Space);
+    if std::mem::take(&mut focus.activate_requested) || activate {
+        if let Some(entity) = focus.focused {
+            focus_activated_events.send(FocusActivated { entity });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{navigate, FocusMove, FocusNode};
+    use bevy_ecs::Entity;
+    use bevy_math::Vec2;
+
+    #[test]
+    fn navigation() {
+        // a row of two buttons above a wide button, where y points up
+        let left = Entity::new();
+        let right = Entity::new();
+        let bottom = Entity::new();
+        let nodes = [
+            FocusNode {
+                entity: bottom,
+                tab_order: 0,
+                position: Vec2::new(60.0, 0.0),
+            },
+            FocusNode {
+                entity: right,
+                tab_order: 0,
+                position: Vec2::new(100.0, 50.0),
+            },
+            FocusNode {
+                entity: left,
+                tab_order: 0,
+                position: Vec2::new(0.0, 50.0),
+            },
+        ];
+
+        // tab order is top to bottom, then left to right, and wraps around
+        assert_eq!(navigate(&nodes, None, FocusMove::Next), Some(left));
+        assert_eq!(navigate(&nodes, Some(left), FocusMove::Next), Some(right));
+        assert_eq!(navigate(&nodes, Some(right), FocusMove::Next), Some(bottom));
+        assert_eq!(navigate(&nodes, Some(bottom), FocusMove::Next), Some(left));
+        assert_eq!(
+            navigate(&nodes, Some(left), FocusMove::Previous),
+            Some(bottom)
+        );
+
+        assert_eq!(navigate(&nodes, Some(left), FocusMove::Right), Some(right));
+        assert_eq!(navigate(&nodes, Some(right), FocusMove::Down), Some(bottom));
+        assert_eq!(navigate(&nodes, Some(bottom), FocusMove::Up), Some(right));
+        // there is nothing to the left, so focus stays
+        assert_eq!(navigate(&nodes, Some(left), FocusMove::Left), Some(left));
+
+        // tab order takes precedence over position
+        let mut ordered = nodes;
+        ordered[0].tab_order = -1;
+        assert_eq!(navigate(&ordered, None, FocusMove::Next), Some(bottom));
+    }
+}
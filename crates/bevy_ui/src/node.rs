@@ -1,17 +1,165 @@
-use bevy_math::{Rect, Size, Vec2};
+use bevy_math::{Rect, Size, Vec2, Vec4};
 use bevy_render::renderer::RenderResources;
+use bevy_window::{Window, WindowId, Windows};
 use std::ops::{Add, AddAssign};
 
-#[derive(Debug, Clone, Default, RenderResources)]
+#[derive(Debug, Clone, RenderResources)]
 pub struct Node {
     pub size: Vec2,
+    /// The node's [Opacity] multiplied by the opacity of all of its ancestors
+    pub opacity: f32,
+    /// The window space rect (`min_x`, `min_y`, `max_x`, `max_y`) the node is clipped to by ancestors with
+    /// [Overflow::Hidden] or [Overflow::Scroll]
+    pub clip: Vec4,
 }
 
+impl Node {
+    /// A clip rect that doesn't clip anything
+    pub const UNCLIPPED: [f32; 4] = [std::f32::MIN, std::f32::MIN, std::f32::MAX, std::f32::MAX];
+
+    /// Returns true if the given window space point is inside the node's clip rect
+    pub fn clip_contains(&self, point: Vec2) -> bool {
+        point.x() >= self.clip.x()
+            && point.y() >= self.clip.y()
+            && point.x() <= self.clip.z()
+            && point.y() <= self.clip.w()
+    }
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node {
+            size: Default::default(),
+            opacity: 1.0,
+            clip: Node::UNCLIPPED.into(),
+        }
+    }
+}
+
+/// An axis aligned rect in window space, in physical pixels with the origin at the bottom left of the window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LayoutRect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl LayoutRect {
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        LayoutRect {
+            min: center - size / 2.0,
+            max: center + size / 2.0,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        (self.max - self.min).max(Vec2::zero())
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x() >= self.min.x()
+            && point.y() >= self.min.y()
+            && point.x() <= self.max.x()
+            && point.y() <= self.max.y()
+    }
+}
+
+/// The result of laying out a ui node, in the same physical window space as its [Transform](bevy_transform::prelude::Transform)
+/// and cursor positions. It is written every frame in the `POST_UPDATE` stage, after transforms are propagated, so
+/// systems in the `UPDATE` stage see the layout of the previous frame. Tooltips, popups, and hit tests should read this
+/// instead of combining the node's transform, size, and style themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComputedLayout {
+    pub size: Vec2,
+    /// The center of the node
+    pub position: Vec2,
+    /// The part of the node inside its padding and border, where its children are placed
+    pub content: LayoutRect,
+    /// The part of the window the node is visible in. Ancestors with [Overflow::Hidden] or [Overflow::Scroll] clip
+    /// their descendants to their rect.
+    pub clip: LayoutRect,
+}
+
+impl ComputedLayout {
+    /// The node's rect, including its padding and border
+    pub fn rect(&self) -> LayoutRect {
+        LayoutRect::from_center_size(self.position, self.size)
+    }
+
+    /// Whether the given window space point is on the visible part of the node
+    pub fn contains_visible(&self, point: Vec2) -> bool {
+        self.rect().contains(point) && self.clip.contains(point)
+    }
+}
+
+impl Default for ComputedLayout {
+    fn default() -> Self {
+        ComputedLayout {
+            size: Vec2::zero(),
+            position: Vec2::zero(),
+            content: LayoutRect::default(),
+            clip: LayoutRect {
+                min: Vec2::new(std::f32::MIN, std::f32::MIN),
+                max: Vec2::new(std::f32::MAX, std::f32::MAX),
+            },
+        }
+    }
+}
+
+/// The scroll offset (in logical pixels) of a node with [Overflow::Scroll]. Children are shifted by `-offset`, which is
+/// clamped so the children's bounds stay within reach of the node's rect.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollPosition {
+    pub offset: Vec2,
+}
+
+/// The opacity of a node and all of its descendants, ranging from 0.0 (transparent) to 1.0 (opaque)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Opacity(pub f32);
+
+impl Default for Opacity {
+    fn default() -> Self {
+        Opacity(1.0)
+    }
+}
+
+/// The window a root ui node (and its descendants) is laid out in. Root nodes without this component use the primary window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiTargetWindow(pub WindowId);
+
+/// Ui layouts are computed in logical pixels and multiplied by a scale factor, so point based styles look the same on
+/// displays with different pixel densities. By default each window's scale factor is used, which `scale_factor`
+/// overrides for every window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UiScale {
+    pub scale_factor: Option<f64>,
+}
+
+impl UiScale {
+    pub fn scale_factor(&self, window: &Window) -> f64 {
+        self.scale_factor.unwrap_or(window.scale_factor)
+    }
+
+    pub fn primary_scale_factor(&self, windows: &Windows) -> f64 {
+        match windows.get_primary() {
+            Some(window) => self.scale_factor(window),
+            None => self.scale_factor.unwrap_or(1.0),
+        }
+    }
+}
+
+/// Pads a root ui node by its window's [SafeAreaInsets](bevy_window::SafeAreaInsets), keeping its children clear of notches
+/// and rounded corners. This has no effect on nodes that have a parent.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SafeAreaPadding;
+
+/// A length used by [Style]
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Val {
     Undefined,
     Auto,
+    /// A length in logical pixels
     Px(f32),
+    /// A percentage of the parent node's length along the same axis
     Percent(f32),
 }
 
@@ -47,8 +195,13 @@ impl AddAssign<f32> for Val {
 #[derive(Default, Copy, Clone)]
 pub struct CalculatedSize {
     pub size: Size,
+    /// Wrapping content (ex: text) keeps its height when the layout changes its width, instead of scaling to preserve
+    /// its aspect ratio. Its widget re-measures the height against the node's new width.
+    pub wraps: bool,
 }
 
+/// Describes how a ui node and its children are laid out. This is converted to a stretch style during layout, which keeps
+/// stretch types out of bevy_ui's public api.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Style {
     pub display: Display,
@@ -71,6 +224,18 @@ pub struct Style {
     pub min_size: Size<Val>,
     pub max_size: Size<Val>,
     pub aspect_ratio: Option<f32>,
+    /// Spacing between adjacent children. `width` separates columns and `height` separates rows, so a row uses `width`
+    /// between the items of a line and `height` between wrapped lines.
+    pub gap: Size<Val>,
+    pub overflow: Overflow,
+    /// The column tracks of a [Display::Grid] node
+    pub grid_template_columns: Vec<GridTrack>,
+    /// The row tracks of a [Display::Grid] node. Rows are added as needed to fit every child, using `GridTrack::Fr(1.0)`.
+    pub grid_template_rows: Vec<GridTrack>,
+    /// The placement of the node within its parent's columns, if its parent uses [Display::Grid]
+    pub grid_column: GridPlacement,
+    /// The placement of the node within its parent's rows, if its parent uses [Display::Grid]
+    pub grid_row: GridPlacement,
 }
 
 impl Default for Style {
@@ -96,8 +261,74 @@ impl Default for Style {
             min_size: Default::default(),
             max_size: Default::default(),
             aspect_ratio: Default::default(),
+            gap: Default::default(),
+            overflow: Default::default(),
+            grid_template_columns: Vec::new(),
+            grid_template_rows: Vec::new(),
+            grid_column: Default::default(),
+            grid_row: Default::default(),
+        }
+    }
+}
+
+/// Alignment of a node's drawn content within the node's computed rect. This does not affect layout. Text is aligned
+/// with [Text::alignment](crate::widget::Text::alignment), and images that are smaller than their node (ex: because they
+/// keep their aspect ratio) with a `ContentAlign` component.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ContentAlign {
+    pub horizontal: HorizontalAlign,
+    pub vertical: VerticalAlign,
+}
+
+impl ContentAlign {
+    pub fn center() -> Self {
+        ContentAlign {
+            horizontal: HorizontalAlign::Center,
+            vertical: VerticalAlign::Center,
         }
     }
+
+    /// Offset of content with the given size from the bottom left corner of a node with the given size
+    pub fn offset(&self, node_size: Vec2, content_size: Vec2) -> Vec2 {
+        let free_space = node_size - content_size;
+        let x = match self.horizontal {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => free_space.x() / 2.0,
+            HorizontalAlign::Right => free_space.x(),
+        };
+        let y = match self.vertical {
+            VerticalAlign::Bottom => 0.0,
+            VerticalAlign::Center => free_space.y() / 2.0,
+            VerticalAlign::Top => free_space.y(),
+        };
+        Vec2::new(x, y)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl Default for HorizontalAlign {
+    fn default() -> HorizontalAlign {
+        HorizontalAlign::Left
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum VerticalAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+impl Default for VerticalAlign {
+    fn default() -> VerticalAlign {
+        VerticalAlign::Bottom
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -163,6 +394,9 @@ impl Default for Direction {
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Display {
     Flex,
+    /// Places children in the cells of the node's grid tracks. Rows are laid out from top to bottom and the gap between
+    /// tracks is taken from [Style::gap].
+    Grid,
     None,
 }
 
@@ -172,6 +406,48 @@ impl Default for Display {
     }
 }
 
+/// The size of a row or column in a [Display::Grid] node
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GridTrack {
+    /// A track size in logical pixels
+    Px(f32),
+    /// A percentage of the grid's content size along the track's axis
+    Percent(f32),
+    /// A share of the space left over by `Px` and `Percent` tracks, relative to the other `Fr` tracks
+    Fr(f32),
+}
+
+/// The cells a child of a [Display::Grid] node covers along one axis
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GridPlacement {
+    /// The zero based index of the first track. Children without a start are placed in the next free cells, row by row.
+    pub start: Option<usize>,
+    /// The number of tracks covered
+    pub span: usize,
+}
+
+impl GridPlacement {
+    pub fn start(start: usize) -> Self {
+        GridPlacement {
+            start: Some(start),
+            span: 1,
+        }
+    }
+
+    pub fn span(span: usize) -> Self {
+        GridPlacement { start: None, span }
+    }
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        GridPlacement {
+            start: None,
+            span: 1,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum FlexDirection {
     Row,
@@ -202,19 +478,21 @@ impl Default for JustifyContent {
     }
 }
 
-// TODO: add support for overflow settings
-// #[derive(Copy, Clone, PartialEq, Debug)]
-// pub enum Overflow {
-//     Visible,
-//     Hidden,
-//     Scroll,
-// }
+/// Controls how a node's children are drawn outside of the node's rect
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Overflow {
+    Visible,
+    /// Children are clipped to the node's rect
+    Hidden,
+    /// Children are clipped to the node's rect and offset by the node's [ScrollPosition], which the mouse wheel updates
+    Scroll,
+}
 
-// impl Default for Overflow {
-//     fn default() -> Overflow {
-//         Overflow::Visible
-//     }
-// }
+impl Default for Overflow {
+    fn default() -> Overflow {
+        Overflow::Visible
+    }
+}
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum PositionType {
@@ -1,26 +1,49 @@
+mod ui_material;
+
+pub use ui_material::*;
+
 use crate::Node;
 use bevy_asset::{Assets, Handle};
-use bevy_ecs::Resources;
+use bevy_core::FloatOrd;
+use bevy_ecs::{Query, Res, ResMut, Resources};
 use bevy_render::{
-    camera::ActiveCameras,
+    camera::{window_node, window_node_name, ActiveCameras, WindowGraphBuilder},
+    draw::Draw,
     pass::{
-        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachmentDescriptor,
-        TextureAttachment,
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachmentDescriptor,
+        RenderPassDepthStencilAttachmentDescriptor, TextureAttachment,
     },
     pipeline::*,
-    prelude::Msaa,
     render_graph::{
         base, CameraNode, PassNode, RenderGraph, RenderResourcesNode, WindowSwapChainNode,
         WindowTextureNode,
     },
+    render_stats::{BatchBreak, RenderStats},
     shader::{Shader, ShaderStage, ShaderStages},
-    texture::TextureFormat,
+    texture::{Texture, TextureFormat},
 };
+use bevy_sprite::ColorMaterial;
+use bevy_transform::prelude::Transform;
+use bevy_window::WindowId;
 
 pub const UI_PIPELINE_HANDLE: Handle<PipelineDescriptor> =
     Handle::from_u128(323432002226399387835192542539754486265);
 
 pub fn build_ui_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
+    ui_pipeline(ShaderStages {
+        vertex: shaders.add(Shader::from_glsl(
+            ShaderStage::Vertex,
+            include_str!("ui.vert"),
+        )),
+        fragment: Some(shaders.add(Shader::from_glsl(
+            ShaderStage::Fragment,
+            include_str!("ui.frag"),
+        ))),
+    })
+}
+
+/// The ui pipeline with the given shaders, which draws nodes with alpha blending
+fn ui_pipeline(shader_stages: ShaderStages) -> PipelineDescriptor {
     PipelineDescriptor {
         rasterization_state: Some(RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
@@ -52,16 +75,7 @@ pub fn build_ui_pipeline(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
             },
             write_mask: ColorWrite::ALL,
         }],
-        ..PipelineDescriptor::new(ShaderStages {
-            vertex: shaders.add(Shader::from_glsl(
-                ShaderStage::Vertex,
-                include_str!("ui.vert"),
-            )),
-            fragment: Some(shaders.add(Shader::from_glsl(
-                ShaderStage::Fragment,
-                include_str!("ui.frag"),
-            ))),
-        })
+        ..PipelineDescriptor::new(shader_stages)
     }
 }
 
@@ -77,35 +91,43 @@ pub mod camera {
 
 pub trait UiRenderGraphBuilder {
     fn add_ui_graph(&mut self, resources: &Resources) -> &mut Self;
+    /// Draws the ui camera named `camera_name` in a window other than the primary window, after the window's main pass
+    /// (see [WindowGraphBuilder]). The camera needs its window set and has to be added to [ActiveCameras]. Root nodes
+    /// with a [UiTargetWindow](crate::UiTargetWindow) of this window are drawn by it.
+    fn add_ui_window_graph(&mut self, window_id: WindowId, camera_name: &str) -> &mut Self;
+}
+
+fn ui_pass_descriptor() -> PassDescriptor {
+    PassDescriptor {
+        color_attachments: vec![RenderPassColorAttachmentDescriptor {
+            attachment: TextureAttachment::Input("color_attachment".to_string()),
+            resolve_target: Some(TextureAttachment::Input("color_resolve_target".to_string())),
+            ops: Operations {
+                load: LoadOp::Load,
+                store: true,
+            },
+        }],
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    }
 }
 
 impl UiRenderGraphBuilder for RenderGraph {
     fn add_ui_graph(&mut self, resources: &Resources) -> &mut Self {
         let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
         let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
-        let msaa = resources.get::<Msaa>().unwrap();
         pipelines.set(UI_PIPELINE_HANDLE, build_ui_pipeline(&mut shaders));
 
-        let mut ui_pass_node = PassNode::<&Node>::new(PassDescriptor {
-            color_attachments: vec![msaa.color_attachment_descriptor(
-                TextureAttachment::Input("color_attachment".to_string()),
-                TextureAttachment::Input("color_resolve_target".to_string()),
-                Operations {
-                    load: LoadOp::Load,
-                    store: true,
-                },
-            )],
-            depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
-                attachment: TextureAttachment::Input("depth".to_string()),
-                depth_ops: Some(Operations {
-                    load: LoadOp::Clear(1.0),
-                    store: true,
-                }),
-                stencil_ops: None,
-            }),
-            sample_count: msaa.samples,
-        });
+        let mut ui_pass_node = PassNode::<&Node>::new(ui_pass_descriptor());
 
+        ui_pass_node.use_msaa();
         ui_pass_node.add_camera(camera::UI_CAMERA);
         self.add_node(node::UI_PASS, ui_pass_node);
 
@@ -113,11 +135,7 @@ impl UiRenderGraphBuilder for RenderGraph {
             base::node::PRIMARY_SWAP_CHAIN,
             WindowSwapChainNode::OUT_TEXTURE,
             node::UI_PASS,
-            if msaa.samples > 1 {
-                "color_resolve_target"
-            } else {
-                "color_attachment"
-            },
+            "color_resolve_target",
         )
         .unwrap();
 
@@ -129,15 +147,13 @@ impl UiRenderGraphBuilder for RenderGraph {
         )
         .unwrap();
 
-        if msaa.samples > 1 {
-            self.add_slot_edge(
-                base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
-                WindowSwapChainNode::OUT_TEXTURE,
-                node::UI_PASS,
-                "color_attachment",
-            )
-            .unwrap();
-        }
+        self.add_slot_edge(
+            base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+            WindowTextureNode::OUT_TEXTURE,
+            node::UI_PASS,
+            "color_attachment",
+        )
+        .unwrap();
 
         // ensure ui pass runs after main pass
         self.add_node_edge(base::node::MAIN_PASS, node::UI_PASS)
@@ -152,4 +168,89 @@ impl UiRenderGraphBuilder for RenderGraph {
         active_cameras.add(camera::UI_CAMERA);
         self
     }
+
+    fn add_ui_window_graph(&mut self, window_id: WindowId, camera_name: &str) -> &mut Self {
+        self.add_window_graph(window_id);
+        let ui_pass = window_node_name(window_id, node::UI_PASS);
+        let ui_camera = format!("{}_camera", camera_name);
+        if self.get_node_id(ui_pass.clone()).is_err() {
+            let mut ui_pass_node = PassNode::<&Node>::new(ui_pass_descriptor());
+            ui_pass_node.use_msaa();
+            self.add_node(ui_pass.clone(), ui_pass_node);
+
+            self.add_slot_edge(
+                window_node_name(window_id, window_node::SWAP_CHAIN),
+                WindowSwapChainNode::OUT_TEXTURE,
+                ui_pass.clone(),
+                "color_resolve_target",
+            )
+            .unwrap();
+            self.add_slot_edge(
+                window_node_name(window_id, window_node::DEPTH_TEXTURE),
+                WindowTextureNode::OUT_TEXTURE,
+                ui_pass.clone(),
+                "depth",
+            )
+            .unwrap();
+            self.add_slot_edge(
+                window_node_name(window_id, window_node::SAMPLED_COLOR_ATTACHMENT),
+                WindowTextureNode::OUT_TEXTURE,
+                ui_pass.clone(),
+                "color_attachment",
+            )
+            .unwrap();
+
+            // ensure the ui pass runs after the window's main pass
+            self.add_node_edge(
+                window_node_name(window_id, window_node::MAIN_PASS),
+                ui_pass.clone(),
+            )
+            .unwrap();
+            self.add_node_edge(node::NODE, ui_pass.clone()).unwrap();
+        }
+
+        self.get_node_mut::<PassNode<&Node>>(ui_pass.clone())
+            .unwrap()
+            .add_camera(camera_name);
+        self.add_system_node(ui_camera.clone(), CameraNode::new(camera_name.to_string()));
+        self.add_node_edge(ui_camera, ui_pass).unwrap();
+        self
+    }
+}
+
+/// Records the batches visible UI nodes fall into, in draw order, in [RenderStats]. UI nodes are drawn one at a time,
+/// but nodes drawn in a row with the same texture and clip rect could share a draw call, so each change of texture or
+/// clip rect is counted as a [BatchBreak].
+pub fn ui_render_stats_system(
+    mut render_stats: ResMut<RenderStats>,
+    materials: Res<Assets<ColorMaterial>>,
+    mut node_query: Query<(&Node, &Handle<ColorMaterial>, &Draw, &Transform)>,
+) {
+    let mut nodes = Vec::new();
+    for (node, material, draw, transform) in &mut node_query.iter() {
+        if !draw.is_visible {
+            continue;
+        }
+        let texture: Option<Handle<Texture>> = materials
+            .get(material)
+            .and_then(|material| material.texture);
+        nodes.push((FloatOrd(transform.value.w_axis().z()), texture, node.clip));
+    }
+
+    // nodes are drawn back to front
+    nodes.sort_by_key(|(z, _, _)| *z);
+    let mut previous = None;
+    for (_, texture, clip) in nodes {
+        match previous {
+            None => render_stats.add_batch(None),
+            Some((previous_texture, _)) if previous_texture != texture => {
+                render_stats.add_batch(Some(BatchBreak::Texture))
+            }
+            Some((_, previous_clip)) if previous_clip != clip => {
+                render_stats.add_batch(Some(BatchBreak::Clip))
+            }
+            Some(_) => {}
+        }
+        previous = Some((texture, clip));
+    }
 }
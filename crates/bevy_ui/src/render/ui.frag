@@ -1,9 +1,18 @@
 #version 450
 
 layout(location = 0) in vec2 v_Uv;
+layout(location = 1) in vec2 v_Position;
 
 layout(location = 0) out vec4 o_Target;
 
+layout(set = 1, binding = 2) uniform Node_opacity {
+    float Opacity;
+};
+// (min_x, min_y, max_x, max_y)
+layout(set = 1, binding = 3) uniform Node_clip {
+    vec4 Clip;
+};
+
 layout(set = 2, binding = 0) uniform ColorMaterial_color {
     vec4 Color;
 };
@@ -14,11 +23,16 @@ layout(set = 2, binding = 2) uniform sampler ColorMaterial_texture_sampler;
 # endif
 
 void main() {
+    if (v_Position.x < Clip.x || v_Position.y < Clip.y || v_Position.x > Clip.z || v_Position.y > Clip.w) {
+        discard;
+    }
+
     vec4 color = Color;
 # ifdef COLORMATERIAL_TEXTURE
     color *= texture(
         sampler2D(ColorMaterial_texture, ColorMaterial_texture_sampler),
         v_Uv);
 # endif
+    color.a *= Opacity;
     o_Target = color;
 }
@@ -0,0 +1,201 @@
+use super::{node, ui_pipeline};
+use crate::{FlexSurface, Node};
+use bevy_app::prelude::*;
+use bevy_asset::{AddAsset, Assets, Handle};
+use bevy_ecs::{Added, IntoQuerySystem, Query, Res, With};
+use bevy_render::{
+    pipeline::{
+        DynamicBinding, PipelineDescriptor, PipelineSpecialization, RenderPipeline, RenderPipelines,
+    },
+    render_graph::{AssetRenderResourcesNode, RenderGraph},
+    renderer::RenderResources,
+    shader::{Shader, ShaderStage, ShaderStages},
+};
+use std::marker::PhantomData;
+
+/// A material that draws ui nodes with its own fragment shader, ex: progress bars, gradients, and minimaps. Nodes with a
+/// `Handle<M>` are drawn with the material instead of their [ColorMaterial](bevy_sprite::ColorMaterial), inside their
+/// rect and clip rect and with their opacity. Like other [RenderResources], each field is a uniform named
+/// `{type name}_{field name}`, which the shader declares in bind group 2.
+pub trait UiMaterial: RenderResources + Send + Sync + 'static {
+    /// GLSL that declares the material's uniforms and defines `vec4 ui_material(vec2 uv)`, which returns the color at
+    /// `uv`. `uv` goes from (0, 0) at the top left corner of the node to (1, 1) at the bottom right corner. The shader can
+    /// also read `NodeSize`, the size of the node in physical pixels.
+    ///
+    /// ```ignore
+    /// layout(set = 2, binding = 0) uniform ProgressBar_color {
+    ///     vec4 Color;
+    /// };
+    /// layout(set = 2, binding = 1) uniform ProgressBar_progress {
+    ///     float Progress;
+    /// };
+    ///
+    /// vec4 ui_material(vec2 uv) {
+    ///     return uv.x <= Progress ? Color : vec4(0.0);
+    /// }
+    /// ```
+    fn fragment_shader() -> &'static str;
+}
+
+const UI_MATERIAL_FRAGMENT_HEADER: &str = r#"#version 450
+
+layout(location = 0) in vec2 v_Uv;
+layout(location = 1) in vec2 v_Position;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 1) uniform Node_size {
+    vec2 NodeSize;
+};
+layout(set = 1, binding = 2) uniform Node_opacity {
+    float Opacity;
+};
+// (min_x, min_y, max_x, max_y)
+layout(set = 1, binding = 3) uniform Node_clip {
+    vec4 Clip;
+};
+"#;
+
+const UI_MATERIAL_FRAGMENT_MAIN: &str = r#"
+void main() {
+    if (v_Position.x < Clip.x || v_Position.y < Clip.y || v_Position.x > Clip.z || v_Position.y > Clip.w) {
+        discard;
+    }
+
+    vec4 color = ui_material(v_Uv);
+    color.a *= Opacity;
+    o_Target = color;
+}
+"#;
+
+fn ui_material_fragment_shader(material: &str) -> String {
+    format!(
+        "{}\n{}\n{}",
+        UI_MATERIAL_FRAGMENT_HEADER, material, UI_MATERIAL_FRAGMENT_MAIN
+    )
+}
+
+/// The pipeline that draws nodes with the [UiMaterial] `M`
+pub struct UiMaterialPipeline<M> {
+    pub handle: Handle<PipelineDescriptor>,
+    marker: PhantomData<M>,
+}
+
+/// Adds the [UiMaterial] `M` as an asset, along with its pipeline and render graph node. This has to be added after the
+/// [UiPlugin](crate::UiPlugin).
+pub struct UiMaterialPlugin<M> {
+    marker: PhantomData<M>,
+}
+
+impl<M> Default for UiMaterialPlugin<M> {
+    fn default() -> Self {
+        UiMaterialPlugin {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: UiMaterial> Plugin for UiMaterialPlugin<M> {
+    fn build(&self, app: &mut AppBuilder) {
+        let handle = {
+            let resources = app.resources();
+            let mut shaders = resources.get_mut::<Assets<Shader>>().unwrap();
+            let mut pipelines = resources.get_mut::<Assets<PipelineDescriptor>>().unwrap();
+            let fragment_shader = ui_material_fragment_shader(M::fragment_shader());
+            let handle = pipelines.add(ui_pipeline(ShaderStages {
+                vertex: shaders.add(Shader::from_glsl(
+                    ShaderStage::Vertex,
+                    include_str!("ui.vert"),
+                )),
+                fragment: Some(
+                    shaders.add(Shader::from_glsl(ShaderStage::Fragment, &fragment_shader)),
+                ),
+            }));
+
+            let mut render_graph = resources.get_mut::<RenderGraph>().unwrap();
+            let material_node = render_graph.add_system_node(
+                format!("ui_material_{}", std::any::type_name::<M>()),
+                AssetRenderResourcesNode::<M>::new(false),
+            );
+            render_graph
+                .add_node_edge(material_node, node::UI_PASS)
+                .unwrap();
+            handle
+        };
+
+        app.add_asset::<M>()
+            .add_resource(UiMaterialPipeline::<M> {
+                handle,
+                marker: PhantomData,
+            })
+            .add_system_to_stage(crate::stage::UI, ui_material_system::<M>.system());
+    }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements
+            .requires::<RenderGraph>()
+            .requires::<FlexSurface>();
+    }
+}
+
+/// Switches nodes that were given a `Handle<M>` to the pipeline of the [UiMaterial] `M`
+pub fn ui_material_system<M: UiMaterial>(
+    pipeline: Res<UiMaterialPipeline<M>>,
+    mut query: Query<With<Node, (Added<Handle<M>>, &mut RenderPipelines)>>,
+) {
+    for (_material, mut render_pipelines) in &mut query.iter() {
+        render_pipelines.pipelines = vec![RenderPipeline::specialized(
+            pipeline.handle,
+            PipelineSpecialization {
+                dynamic_bindings: vec![
+                    // Transform
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 0,
+                    },
+                    // Node_size
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 1,
+                    },
+                    // Node_opacity
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 2,
+                    },
+                    // Node_clip
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 3,
+                    },
+                ],
+                ..Default::default()
+            },
+        )];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ui_material_fragment_shader;
+    use bevy_render::shader::{Shader, ShaderStage};
+
+    #[test]
+    fn material_fragment_shader() {
+        let shader = Shader::from_glsl(
+            ShaderStage::Fragment,
+            &ui_material_fragment_shader(
+                r#"
+                layout(set = 2, binding = 0) uniform ProgressBar_color {
+                    vec4 Color;
+                };
+
+                vec4 ui_material(vec2 uv) {
+                    return uv.x <= NodeSize.x ? Color : vec4(0.0);
+                }
+                "#,
+            ),
+        );
+        assert!(shader.try_get_spirv(None).is_ok());
+    }
+}
@@ -0,0 +1,300 @@
+use bevy_app::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use bevy_input::{
+    keyboard::{ElementState, KeyCode, KeyboardInput},
+    mouse::{MouseButton, MouseButtonInput, MouseScrollUnit, MouseWheel},
+};
+use bevy_math::Vec2;
+use bevy_window::{CursorMoved, ReceivedCharacter, WindowId};
+
+/// An input event that drives ui interaction
+#[derive(Debug, Clone)]
+pub enum UiInputEvent {
+    CursorMoved(CursorMoved),
+    MouseButton(MouseButtonInput),
+    MouseWheel(MouseWheel),
+    Keyboard(KeyboardInput),
+    Character(ReceivedCharacter),
+}
+
+/// The ui input events of consecutive frames. Recordings are captured with [UiInputRecorder] and played back with
+/// [UiInputReplay], or written by hand:
+///
+/// ```ignore
+/// let mut recording = UiInputRecording::default();
+/// recording
+///     .move_cursor(Vec2::new(100.0, 50.0))
+///     .next_frame()
+///     .mouse_button(MouseButton::Left, ElementState::Pressed)
+///     .next_frame()
+///     .mouse_button(MouseButton::Left, ElementState::Released);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiInputRecording {
+    pub frames: Vec<Vec<UiInputEvent>>,
+}
+
+impl UiInputRecording {
+    /// Adds an event to the last frame
+    pub fn push(&mut self, event: UiInputEvent) -> &mut Self {
+        if self.frames.is_empty() {
+            self.frames.push(Vec::new());
+        }
+        self.frames.last_mut().unwrap().push(event);
+        self
+    }
+
+    /// Starts a new frame. Events added after this are sent one update after the events before it.
+    pub fn next_frame(&mut self) -> &mut Self {
+        if self.frames.is_empty() {
+            self.frames.push(Vec::new());
+        }
+        self.frames.push(Vec::new());
+        self
+    }
+
+    /// Moves the cursor of the primary window to `position`, in physical pixels from the bottom left corner
+    pub fn move_cursor(&mut self, position: Vec2) -> &mut Self {
+        self.push(UiInputEvent::CursorMoved(CursorMoved {
+            id: WindowId::primary(),
+            position,
+        }))
+    }
+
+    pub fn mouse_button(&mut self, button: MouseButton, state: ElementState) -> &mut Self {
+        self.push(UiInputEvent::MouseButton(MouseButtonInput {
+            button,
+            state,
+        }))
+    }
+
+    pub fn scroll(&mut self, unit: MouseScrollUnit, x: f32, y: f32) -> &mut Self {
+        self.push(UiInputEvent::MouseWheel(MouseWheel { unit, x, y }))
+    }
+
+    pub fn key(&mut self, key_code: KeyCode, state: ElementState) -> &mut Self {
+        self.push(UiInputEvent::Keyboard(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        }))
+    }
+
+    /// Types `text` into the primary window, one [ReceivedCharacter] per character
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for char in text.chars() {
+            self.push(UiInputEvent::Character(ReceivedCharacter {
+                id: WindowId::primary(),
+                char,
+            }));
+        }
+        self
+    }
+}
+
+/// Records ui input events while `enabled`. Each update adds one frame to the recording, even when it has no events,
+/// so replaying the recording preserves the timing between events. Updates that replay a [UiInputReplay] frame aren't
+/// recorded.
+#[derive(Debug, Default)]
+pub struct UiInputRecorder {
+    pub enabled: bool,
+    pub recording: UiInputRecording,
+}
+
+impl UiInputRecorder {
+    /// Stops recording and returns the recorded events
+    pub fn finish(&mut self) -> UiInputRecording {
+        self.enabled = false;
+        std::mem::take(&mut self.recording)
+    }
+}
+
+/// Sends the events of a [UiInputRecording], one frame per update. Replaying the same recording against the same ui
+/// produces the same interactions, which makes it useful for regression tests of menus.
+#[derive(Debug, Default)]
+pub struct UiInputReplay {
+    recording: UiInputRecording,
+    frame: usize,
+    /// Whether a frame of the recording was sent this update
+    replayed_frame: bool,
+}
+
+impl UiInputReplay {
+    /// Replaces the current replay with `recording`, starting on the next update
+    pub fn play(&mut self, recording: UiInputRecording) {
+        self.recording = recording;
+        self.frame = 0;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.frames.len()
+    }
+
+    /// Whether the events of this update include a replayed frame
+    pub fn is_replaying(&self) -> bool {
+        self.replayed_frame
+    }
+}
+
+#[derive(Default)]
+pub struct UiInputRecorderState {
+    cursor_moved_event_reader: EventReader<CursorMoved>,
+    mouse_button_event_reader: EventReader<MouseButtonInput>,
+    mouse_wheel_event_reader: EventReader<MouseWheel>,
+    keyboard_event_reader: EventReader<KeyboardInput>,
+    character_event_reader: EventReader<ReceivedCharacter>,
+}
+
+pub fn ui_input_record_system(
+    mut state: Local<UiInputRecorderState>,
+    mut recorder: ResMut<UiInputRecorder>,
+    replay: Res<UiInputReplay>,
+    cursor_moved_events: Res<Events<CursorMoved>>,
+    mouse_button_events: Res<Events<MouseButtonInput>>,
+    mouse_wheel_events: Res<Events<MouseWheel>>,
+    keyboard_events: Res<Events<KeyboardInput>>,
+    character_events: Res<Events<ReceivedCharacter>>,
+) {
+    // events are always read, so enabling the recorder doesn't record events from before it was enabled
+    let mut frame = Vec::new();
+    for event in state.cursor_moved_event_reader.iter(&cursor_moved_events) {
+        frame.push(UiInputEvent::CursorMoved(event.clone()));
+    }
+    for event in state.mouse_button_event_reader.iter(&mouse_button_events) {
+        frame.push(UiInputEvent::MouseButton(event.clone()));
+    }
+    for event in state.mouse_wheel_event_reader.iter(&mouse_wheel_events) {
+        frame.push(UiInputEvent::MouseWheel(event.clone()));
+    }
+    for event in state.keyboard_event_reader.iter(&keyboard_events) {
+        frame.push(UiInputEvent::Keyboard(event.clone()));
+    }
+    for event in state.character_event_reader.iter(&character_events) {
+        frame.push(UiInputEvent::Character(event.clone()));
+    }
+
+    if recorder.enabled && !replay.is_replaying() {
+        recorder.recording.frames.push(frame);
+    }
+}
+
+pub fn ui_input_replay_system(
+    mut replay: ResMut<UiInputReplay>,
+    mut cursor_moved_events: ResMut<Events<CursorMoved>>,
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+    mut mouse_wheel_events: ResMut<Events<MouseWheel>>,
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut character_events: ResMut<Events<ReceivedCharacter>>,
+) {
+    replay.replayed_frame = !replay.is_finished();
+    if !replay.replayed_frame {
+        return;
+    }
+
+    let frame = replay.frame;
+    for event in replay.recording.frames[frame].iter() {
+        match event.clone() {
+            UiInputEvent::CursorMoved(event) => cursor_moved_events.send(event),
+            UiInputEvent::MouseButton(event) => mouse_button_events.send(event),
+            UiInputEvent::MouseWheel(event) => mouse_wheel_events.send(event),
+            UiInputEvent::Keyboard(event) => keyboard_events.send(event),
+            UiInputEvent::Character(event) => character_events.send(event),
+        }
+    }
+    replay.frame += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UiInputRecorder, UiInputRecording, UiInputReplay};
+    use crate::{entity::ButtonComponents, HeadlessUiPlugin, Interaction, Style, Val};
+    use bevy_app::App;
+    use bevy_core::CorePlugin;
+    use bevy_input::{keyboard::ElementState, mouse::MouseButton, InputPlugin};
+    use bevy_math::Size;
+    use bevy_transform::{components::Transform, TransformPlugin};
+    use bevy_type_registry::TypeRegistryPlugin;
+    use bevy_window::WindowPlugin;
+
+    fn headless_app() -> App {
+        let mut app_builder = App::build();
+        app_builder
+            .add_plugin(TypeRegistryPlugin::default())
+            .add_plugin(CorePlugin::default())
+            .add_plugin(TransformPlugin::default())
+            .add_plugin(InputPlugin::default())
+            .add_plugin(WindowPlugin {
+                add_primary_window: false,
+                exit_on_close: false,
+            })
+            .add_plugin(HeadlessUiPlugin::default());
+        app_builder.app
+    }
+
+    #[test]
+    fn replay_button_click() {
+        let mut app = headless_app();
+        let button = app.world.spawn(ButtonComponents {
+            style: Style {
+                size: Size::new(Val::Px(100.0), Val::Px(50.0)),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        app.update();
+        let position = app.world.get::<Transform>(button).unwrap().value.w_axis();
+        assert_eq!(
+            *app.world.get::<Interaction>(button).unwrap(),
+            Interaction::None
+        );
+
+        let mut recording = UiInputRecording::default();
+        recording
+            .move_cursor(position.truncate().truncate())
+            .next_frame()
+            .mouse_button(MouseButton::Left, ElementState::Pressed)
+            .next_frame()
+            .mouse_button(MouseButton::Left, ElementState::Released);
+        app.resources
+            .get_mut::<UiInputReplay>()
+            .unwrap()
+            .play(recording);
+
+        let mut interactions = Vec::new();
+        while !app.resources.get::<UiInputReplay>().unwrap().is_finished() {
+            app.update();
+            interactions.push(*app.world.get::<Interaction>(button).unwrap());
+        }
+        assert_eq!(
+            interactions,
+            vec![
+                Interaction::Hovered,
+                Interaction::Clicked,
+                Interaction::Hovered
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_recording_replayed_frames() {
+        let mut app = headless_app();
+        app.resources.get_mut::<UiInputRecorder>().unwrap().enabled = true;
+        let mut recording = UiInputRecording::default();
+        recording
+            .mouse_button(MouseButton::Left, ElementState::Pressed)
+            .next_frame()
+            .mouse_button(MouseButton::Left, ElementState::Released);
+        app.resources
+            .get_mut::<UiInputReplay>()
+            .unwrap()
+            .play(recording);
+
+        // only the update after the replay finished is recorded
+        for _ in 0..3 {
+            app.update();
+        }
+        let recorded = app.resources.get_mut::<UiInputRecorder>().unwrap().finish();
+        assert_eq!(recorded.frames.len(), 1);
+        assert!(recorded.frames[0].is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{Style, UiScale};
+use bevy_app::{EventReader, Events};
+use bevy_ecs::{Added, Local, Query, Res, ResMut};
+use bevy_window::{WindowCreated, WindowResized, WindowScaleFactorChanged, Windows};
+use std::collections::HashMap;
+
+/// How the ui is scaled while a [Breakpoint] is active
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakpointScale {
+    /// The window's scale factor is used, like without breakpoints
+    Window,
+    /// Multiplies the window's scale factor, ex: 1.5 draws everything 50% larger
+    Fixed(f64),
+    /// Scales the ui with the window's width, so a layout designed for `reference_width` logical pixels fills the
+    /// window
+    Proportional { reference_width: f32 },
+}
+
+impl Default for BreakpointScale {
+    fn default() -> Self {
+        BreakpointScale::Window
+    }
+}
+
+/// A range of window widths, in logical pixels, that the ui adapts to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoint {
+    /// The name [ResponsiveStyle]s use to select a style for this breakpoint
+    pub name: String,
+    pub min_width: f32,
+    /// The width this breakpoint ends at, exclusive. It has no end if this is `None`.
+    pub max_width: Option<f32>,
+    pub scale: BreakpointScale,
+}
+
+impl Breakpoint {
+    pub fn new(name: impl Into<String>, min_width: f32, max_width: Option<f32>) -> Self {
+        Breakpoint {
+            name: name.into(),
+            min_width,
+            max_width,
+            scale: BreakpointScale::Window,
+        }
+    }
+
+    pub fn with_scale(mut self, scale: BreakpointScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn contains(&self, width: f32) -> bool {
+        width >= self.min_width && self.max_width.map_or(true, |max_width| width < max_width)
+    }
+
+    /// The [UiScale] scale factor for a window of the given logical width and scale factor
+    pub fn scale_factor(&self, width: f32, window_scale_factor: f64) -> Option<f64> {
+        match self.scale {
+            BreakpointScale::Window => None,
+            BreakpointScale::Fixed(scale) => Some(window_scale_factor * scale),
+            BreakpointScale::Proportional { reference_width } if reference_width > 0.0 => {
+                Some(window_scale_factor * (width / reference_width) as f64)
+            }
+            BreakpointScale::Proportional { .. } => None,
+        }
+    }
+}
+
+/// Breakpoints over the width of the primary window, ex: "compact" below 1280 logical pixels and "wide" from 2560.
+/// When the window is resized into another breakpoint, [ResponsiveStyle]s swap to that breakpoint's style. While a
+/// breakpoint is active, it sets the [UiScale], so it replaces scale factors set by other code. The first breakpoint
+/// that contains the window's width is active.
+#[derive(Debug, Default)]
+pub struct UiBreakpoints {
+    breakpoints: Vec<Breakpoint>,
+    active: Option<usize>,
+    changed: bool,
+}
+
+impl UiBreakpoints {
+    pub fn add(&mut self, breakpoint: Breakpoint) -> &mut Self {
+        self.breakpoints.push(breakpoint);
+        self.changed = true;
+        self
+    }
+
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.changed = true;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.iter()
+    }
+
+    pub fn find(&self, width: f32) -> Option<usize> {
+        self.breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.contains(width))
+    }
+
+    pub fn active(&self) -> Option<&Breakpoint> {
+        self.active.and_then(|index| self.breakpoints.get(index))
+    }
+}
+
+/// A node [Style] for each [Breakpoint], by breakpoint name. The node uses `base` while no breakpoint, or a breakpoint
+/// without a style here, is active.
+#[derive(Debug, Clone, Default)]
+pub struct ResponsiveStyle {
+    pub base: Style,
+    pub styles: HashMap<String, Style>,
+}
+
+impl ResponsiveStyle {
+    pub fn new(base: Style) -> Self {
+        ResponsiveStyle {
+            base,
+            styles: HashMap::new(),
+        }
+    }
+
+    pub fn with(mut self, breakpoint: impl Into<String>, style: Style) -> Self {
+        self.styles.insert(breakpoint.into(), style);
+        self
+    }
+
+    pub fn get(&self, breakpoint: Option<&Breakpoint>) -> &Style {
+        breakpoint
+            .and_then(|breakpoint| self.styles.get(&breakpoint.name))
+            .unwrap_or(&self.base)
+    }
+}
+
+#[derive(Default)]
+pub struct UiBreakpointSystemState {
+    window_resized_event_reader: EventReader<WindowResized>,
+    window_created_event_reader: EventReader<WindowCreated>,
+    window_scale_factor_changed_event_reader: EventReader<WindowScaleFactorChanged>,
+}
+
+/// Selects the active [Breakpoint] when the primary window is created or resized, or the breakpoints change, and
+/// applies its scale and styles
+pub fn ui_breakpoint_system(
+    mut state: Local<UiBreakpointSystemState>,
+    windows: Res<Windows>,
+    window_resized_events: Res<Events<WindowResized>>,
+    window_created_events: Res<Events<WindowCreated>>,
+    window_scale_factor_changed_events: Res<Events<WindowScaleFactorChanged>>,
+    mut breakpoints: ResMut<UiBreakpoints>,
+    mut ui_scale: ResMut<UiScale>,
+    mut style_query: Query<(&ResponsiveStyle, &mut Style)>,
+    mut added_style_query: Query<(Added<ResponsiveStyle>, &mut Style)>,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let resized = state
+        .window_resized_event_reader
+        .iter(&window_resized_events)
+        .any(|event| event.id == window.id);
+    let created = state
+        .window_created_event_reader
+        .iter(&window_created_events)
+        .any(|event| event.id == window.id);
+    let rescaled = state
+        .window_scale_factor_changed_event_reader
+        .iter(&window_scale_factor_changed_events)
+        .any(|event| event.id == window.id);
+
+    let breakpoints = &mut *breakpoints;
+    let breakpoints_changed = breakpoints.changed;
+    if resized || created || rescaled || breakpoints_changed {
+        breakpoints.changed = false;
+        // breakpoints are measured in the window's own logical pixels, so the ui scale doesn't feed back into them
+        let width = (window.width as f64 / window.scale_factor) as f32;
+        let active = breakpoints.find(width);
+        let scale_factor = active.and_then(|index| {
+            breakpoints.breakpoints[index].scale_factor(width, window.scale_factor)
+        });
+        if active.is_some() || breakpoints.active.is_some() {
+            // avoid mutating an unchanged scale, which would trigger a layout
+            if ui_scale.scale_factor != scale_factor {
+                ui_scale.scale_factor = scale_factor;
+            }
+        }
+
+        // the styles of a changed breakpoint may have a different name
+        if active != breakpoints.active || breakpoints_changed {
+            breakpoints.active = active;
+            for (responsive_style, mut style) in &mut style_query.iter() {
+                let responsive_style = responsive_style.get(breakpoints.active());
+                if *style != *responsive_style {
+                    *style = responsive_style.clone();
+                }
+            }
+            return;
+        }
+    }
+
+    for (responsive_style, mut style) in &mut added_style_query.iter() {
+        let responsive_style = responsive_style.get(breakpoints.active());
+        if *style != *responsive_style {
+            *style = responsive_style.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Breakpoint, BreakpointScale, UiBreakpoints};
+
+    #[test]
+    fn breakpoints() {
+        let mut breakpoints = UiBreakpoints::default();
+        breakpoints
+            .add(Breakpoint::new("compact", 0.0, Some(1280.0)))
+            .add(Breakpoint::new("regular", 1280.0, Some(2560.0)))
+            .add(
+                Breakpoint::new("wide", 2560.0, None).with_scale(BreakpointScale::Proportional {
+                    reference_width: 1920.0,
+                }),
+            );
+        assert_eq!(breakpoints.find(720.0), Some(0));
+        assert_eq!(breakpoints.find(1280.0), Some(1));
+        assert_eq!(breakpoints.find(3440.0), Some(2));
+
+        let wide = breakpoints.iter().nth(2).unwrap();
+        assert_eq!(wide.scale_factor(3840.0, 1.0), Some(2.0));
+        assert_eq!(wide.scale_factor(3840.0, 1.5), Some(3.0));
+        assert_eq!(
+            breakpoints.iter().next().unwrap().scale_factor(720.0, 2.0),
+            None
+        );
+    }
+}
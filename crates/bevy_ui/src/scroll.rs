@@ -0,0 +1,137 @@
+use crate::{Node, Overflow, ScrollPosition, Style};
+use bevy_app::{EventReader, Events};
+use bevy_core::FloatOrd;
+use bevy_ecs::prelude::*;
+use bevy_input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy_math::Vec2;
+use bevy_transform::components::Transform;
+use bevy_window::CursorMoved;
+
+/// The number of pixels scrolled per [MouseScrollUnit::Line]
+pub const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
+#[derive(Default)]
+pub struct ScrollState {
+    cursor_moved_event_reader: EventReader<CursorMoved>,
+    mouse_wheel_event_reader: EventReader<MouseWheel>,
+    cursor_position: Vec2,
+}
+
+/// Scrolls the topmost [Overflow::Scroll] node under the cursor by the latest [MouseWheel] events
+pub fn ui_scroll_system(
+    mut state: Local<ScrollState>,
+    cursor_moved_events: Res<Events<CursorMoved>>,
+    mouse_wheel_events: Res<Events<MouseWheel>>,
+    mut node_query: Query<(&Node, &Style, &Transform, &mut ScrollPosition)>,
+) {
+    if let Some(cursor_moved) = state.cursor_moved_event_reader.latest(&cursor_moved_events) {
+        state.cursor_position = cursor_moved.position;
+    }
+
+    let mut delta = Vec2::zero();
+    for event in state.mouse_wheel_event_reader.iter(&mouse_wheel_events) {
+        let scale = match event.unit {
+            MouseScrollUnit::Line => SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => 1.0,
+        };
+        delta += Vec2::new(event.x, event.y) * scale;
+    }
+
+    if delta == Vec2::zero() {
+        return;
+    }
+
+    let cursor_position = state.cursor_position;
+    let mut query_iter = node_query.iter();
+    let topmost_scroll_node = query_iter
+        .iter()
+        .filter(|(node, style, transform, _scroll_position)| {
+            if style.overflow != Overflow::Scroll || !node.clip_contains(cursor_position) {
+                return false;
+            }
+
+            let position = transform.value.w_axis().truncate().truncate();
+            let extents = node.size / 2.0;
+            let min = position - extents;
+            let max = position + extents;
+            (min.x()..max.x()).contains(&cursor_position.x())
+                && (min.y()..max.y()).contains(&cursor_position.y())
+        })
+        .max_by_key(|(_node, _style, transform, _scroll_position)| {
+            FloatOrd(transform.value.w_axis().z())
+        });
+
+    // the offset is clamped to the children's bounds during layout
+    if let Some((_node, _style, _transform, mut scroll_position)) = topmost_scroll_node {
+        scroll_position.offset += delta;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ui_scroll_system, SCROLL_LINE_HEIGHT};
+    use crate::{Node, Overflow, ScrollPosition, Style};
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use bevy_input::mouse::{MouseScrollUnit, MouseWheel};
+    use bevy_math::{Mat4, Vec2, Vec3};
+    use bevy_transform::components::Transform;
+    use bevy_window::{CursorMoved, WindowId};
+
+    #[test]
+    fn scroll_topmost_node() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Events::<CursorMoved>::default());
+        resources.insert(Events::<MouseWheel>::default());
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", ui_scroll_system.system());
+        schedule.initialize(&mut resources);
+
+        let node = |overflow, z| {
+            (
+                Node {
+                    size: Vec2::new(100.0, 100.0),
+                    ..Default::default()
+                },
+                Style {
+                    overflow,
+                    ..Default::default()
+                },
+                Transform::new(Mat4::from_translation(Vec3::new(50.0, 50.0, z))),
+                ScrollPosition::default(),
+            )
+        };
+        let bottom = world.spawn(node(Overflow::Scroll, 1.0));
+        let middle = world.spawn(node(Overflow::Scroll, 2.0));
+        // nodes that don't scroll are skipped, even when they are on top
+        let top = world.spawn(node(Overflow::Hidden, 3.0));
+
+        resources
+            .get_mut::<Events<CursorMoved>>()
+            .unwrap()
+            .send(CursorMoved {
+                id: WindowId::primary(),
+                position: Vec2::new(60.0, 60.0),
+            });
+        let mut mouse_wheel_events = resources.get_mut::<Events<MouseWheel>>().unwrap();
+        mouse_wheel_events.send(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: 1.0,
+        });
+        mouse_wheel_events.send(MouseWheel {
+            unit: MouseScrollUnit::Pixel,
+            x: 5.0,
+            y: 0.0,
+        });
+        drop(mouse_wheel_events);
+        schedule.run(&mut world, &mut resources);
+
+        let offset = |entity| world.get::<ScrollPosition>(entity).unwrap().offset;
+        assert_eq!(offset(middle), Vec2::new(5.0, SCROLL_LINE_HEIGHT));
+        assert_eq!(offset(bottom), Vec2::zero());
+        assert_eq!(offset(top), Vec2::zero());
+    }
+}
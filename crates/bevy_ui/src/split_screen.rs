@@ -0,0 +1,60 @@
+use crate::{PositionType, Style, UiScale, Val};
+use bevy_ecs::{Query, Res};
+use bevy_math::{Rect, Size};
+use bevy_render::camera::{Camera, SplitScreen, SplitScreenCamera};
+use bevy_window::{WindowId, Windows};
+
+/// Positions a root ui node over the viewport of a split screen player, so each player can have their own ui tree.
+/// The node's position type, position, and size are overwritten while the player's camera exists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitScreenUiRoot {
+    pub player: usize,
+}
+
+impl SplitScreenUiRoot {
+    pub fn new(player: usize) -> Self {
+        SplitScreenUiRoot { player }
+    }
+}
+
+pub fn split_screen_ui_system(
+    split_screen: Res<SplitScreen>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    mut camera_query: Query<(&SplitScreenCamera, &Camera)>,
+    mut root_query: Query<(&SplitScreenUiRoot, &mut Style)>,
+) {
+    // viewports are computed like split_screen_system does, so roots don't lag a frame behind cameras
+    let cameras = (&mut camera_query.iter())
+        .into_iter()
+        .map(|(split_screen_camera, camera)| (*split_screen_camera, camera.window))
+        .collect::<Vec<_>>();
+    let viewports = split_screen.viewports(&windows, cameras.into_iter());
+
+    // viewports are in physical pixels, but styles are in logical pixels
+    let scale_factor = ui_scale.primary_scale_factor(&windows) as f32;
+    for (root, mut style) in &mut root_query.iter() {
+        let viewport = match viewports.get(&(WindowId::primary(), root.player)) {
+            Some(viewport) => viewport,
+            None => continue,
+        };
+        let position = Rect {
+            left: Val::Px(viewport.position.x() / scale_factor),
+            bottom: Val::Px(viewport.position.y() / scale_factor),
+            ..Default::default()
+        };
+        let size = Size::new(
+            Val::Px(viewport.size.x() / scale_factor),
+            Val::Px(viewport.size.y() / scale_factor),
+        );
+        // avoid mutating unchanged styles, which would trigger a layout
+        if style.position_type != PositionType::Absolute
+            || style.position != position
+            || style.size != size
+        {
+            style.position_type = PositionType::Absolute;
+            style.position = position;
+            style.size = size;
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use crate::{
+    entity::{ImageComponents, NodeComponents, TextComponents},
+    widget::Text,
+    AlignItems, FlexDirection, Opacity, PositionType, Style, Val, ZIndex,
+};
+use bevy_app::{EventReader, Events};
+use bevy_asset::{AssetLoadFailed, Assets, Handle};
+use bevy_core::Time;
+use bevy_ecs::{Commands, Entity, Local, Query, Res, ResMut};
+use bevy_math::{Rect, Size};
+use bevy_render::{color::Color, texture::Texture};
+use bevy_sprite::ColorMaterial;
+use bevy_text::{Font, TextStyle};
+use bevy_transform::prelude::{BuildChildren, DespawnRecursiveExt};
+use std::collections::{HashMap, VecDeque};
+
+/// How important a [Toast] is, which sets its background color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToastSeverity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// A notification shown by [Toasts]
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub text: String,
+    /// An image shown left of the text
+    pub icon: Option<Handle<Texture>>,
+    pub severity: ToastSeverity,
+    /// The time in seconds the toast is shown for, not counting its fades
+    pub duration: f32,
+}
+
+impl Toast {
+    pub fn new(severity: ToastSeverity, text: impl Into<String>) -> Self {
+        Toast {
+            text: text.into(),
+            icon: None,
+            severity,
+            duration: 4.0,
+        }
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Toast::new(ToastSeverity::Info, text)
+    }
+
+    pub fn success(text: impl Into<String>) -> Self {
+        Toast::new(ToastSeverity::Success, text)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Toast::new(ToastSeverity::Warning, text)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Toast::new(ToastSeverity::Error, text)
+    }
+
+    pub fn with_icon(mut self, icon: Handle<Texture>) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// Shows [Toast]s stacked up from the bottom right corner of the window, sliding and fading them in and out. Each toast
+/// is dismissed after its duration. Toasts pushed while `max_visible` toasts are shown wait for older toasts to be
+/// dismissed.
+#[derive(Debug)]
+pub struct Toasts {
+    /// The font of the toasts' text. Toasts are logged instead of shown until this is set.
+    pub font: Option<Handle<Font>>,
+    pub font_size: f32,
+    pub text_color: Color,
+    pub info_color: Color,
+    pub success_color: Color,
+    pub warning_color: Color,
+    pub error_color: Color,
+    /// The width of the toasts in logical pixels
+    pub width: f32,
+    pub max_visible: usize,
+    /// The time in seconds the toasts take to fade in and out
+    pub fade: f32,
+    /// Whether assets that fail to load are shown as error toasts. This is enabled in debug builds.
+    pub show_asset_load_failures: bool,
+    queue: VecDeque<Toast>,
+    cleared: bool,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Toasts {
+            font: None,
+            font_size: 18.0,
+            text_color: Color::WHITE,
+            info_color: Color::rgba(0.15, 0.15, 0.15, 0.9),
+            success_color: Color::rgba(0.1, 0.4, 0.15, 0.9),
+            warning_color: Color::rgba(0.55, 0.4, 0.05, 0.9),
+            error_color: Color::rgba(0.6, 0.1, 0.1, 0.9),
+            width: 320.0,
+            max_visible: 5,
+            fade: 0.25,
+            show_asset_load_failures: cfg!(debug_assertions),
+            queue: VecDeque::new(),
+            cleared: false,
+        }
+    }
+}
+
+impl Toasts {
+    pub fn push(&mut self, toast: Toast) {
+        self.queue.push_back(toast);
+    }
+
+    /// Dismisses the shown toasts and drops the waiting ones
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.cleared = true;
+    }
+
+    /// The toasts waiting to be shown
+    pub fn queued(&self) -> impl Iterator<Item = &Toast> {
+        self.queue.iter()
+    }
+
+    pub fn color(&self, severity: ToastSeverity) -> Color {
+        match severity {
+            ToastSeverity::Info => self.info_color,
+            ToastSeverity::Success => self.success_color,
+            ToastSeverity::Warning => self.warning_color,
+            ToastSeverity::Error => self.error_color,
+        }
+    }
+}
+
+/// Returns how far a toast has faded in (0.0 to 1.0) at `age` seconds after it was shown, or `None` once it has faded
+/// out
+fn toast_visibility(age: f32, duration: f32, fade: f32) -> Option<f32> {
+    if fade <= 0.0 {
+        return if age < duration { Some(1.0) } else { None };
+    }
+    if age < fade {
+        Some(age / fade)
+    } else if age < fade + duration {
+        Some(1.0)
+    } else if age < fade * 2.0 + duration {
+        Some(1.0 - (age - fade - duration) / fade)
+    } else {
+        None
+    }
+}
+
+struct ShownToast {
+    entity: Entity,
+    age: f32,
+    duration: f32,
+}
+
+#[derive(Default)]
+pub struct ToastState {
+    root: Option<Entity>,
+    shown: Vec<ShownToast>,
+    materials: HashMap<ToastSeverity, Handle<ColorMaterial>>,
+    icon_materials: HashMap<Handle<Texture>, Handle<ColorMaterial>>,
+    load_failed_event_reader: EventReader<AssetLoadFailed>,
+}
+
+/// Shows the toasts pushed to [Toasts] and animates and dismisses shown toasts
+pub fn toast_system(
+    mut commands: Commands,
+    mut state: Local<ToastState>,
+    time: Res<Time>,
+    load_failed_events: Res<Events<AssetLoadFailed>>,
+    mut toasts: ResMut<Toasts>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    toast_query: Query<(&mut Opacity, &mut Style)>,
+) {
+    let state = &mut *state;
+    for event in state.load_failed_event_reader.iter(&load_failed_events) {
+        if toasts.show_asset_load_failures {
+            toasts.push(Toast::error(format!(
+                "Failed to load {}",
+                event.path.display()
+            )));
+        }
+    }
+
+    if toasts.cleared {
+        toasts.cleared = false;
+        for toast in state.shown.drain(..) {
+            commands.despawn_recursive(toast.entity);
+        }
+    }
+
+    // animate the shown toasts, and remove those that have faded out
+    let fade = toasts.fade;
+    let slide = toasts.width / 4.0;
+    for toast in state.shown.iter_mut() {
+        toast.age += time.delta_seconds;
+    }
+    state.shown.retain(
+        |toast| match toast_visibility(toast.age, toast.duration, fade) {
+            Some(visibility) => {
+                if let Ok(mut opacity) = toast_query.get_mut::<Opacity>(toast.entity) {
+                    if opacity.0 != visibility {
+                        opacity.0 = visibility;
+                    }
+                }
+                let left = Val::Px((1.0 - visibility) * slide);
+                if let Ok(mut style) = toast_query.get_mut::<Style>(toast.entity) {
+                    // avoid mutating an unchanged style, which would trigger a layout
+                    if style.position.left != left {
+                        style.position.left = left;
+                    }
+                }
+                true
+            }
+            None => {
+                commands.despawn_recursive(toast.entity);
+                false
+            }
+        },
+    );
+
+    for severity in [
+        ToastSeverity::Info,
+        ToastSeverity::Success,
+        ToastSeverity::Warning,
+        ToastSeverity::Error,
+    ]
+    .iter()
+    {
+        let color = toasts.color(*severity);
+        match state.materials.get(severity) {
+            // avoid sending modified events for unchanged materials
+            Some(handle) => {
+                if materials.get(handle).map(|material| material.color) != Some(color) {
+                    if let Some(material) = materials.get_mut(handle) {
+                        material.color = color;
+                    }
+                }
+            }
+            None => {
+                state
+                    .materials
+                    .insert(*severity, materials.add(color.into()));
+            }
+        }
+    }
+
+    while state.shown.len() < toasts.max_visible {
+        let toast = match toasts.queue.pop_front() {
+            Some(toast) => toast,
+            None => break,
+        };
+        let font = match toasts.font {
+            Some(font) => font,
+            None => {
+                match toast.severity {
+                    ToastSeverity::Info | ToastSeverity::Success => log::info!("{}", toast.text),
+                    ToastSeverity::Warning => log::warn!("{}", toast.text),
+                    ToastSeverity::Error => log::error!("{}", toast.text),
+                }
+                continue;
+            }
+        };
+
+        let root = match state.root {
+            Some(root) => root,
+            None => {
+                commands
+                    .spawn(NodeComponents {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            position: Rect {
+                                right: Val::Px(16.0),
+                                bottom: Val::Px(16.0),
+                                ..Default::default()
+                            },
+                            flex_direction: FlexDirection::Column,
+                            ..Default::default()
+                        },
+                        material: materials.add(Color::NONE.into()),
+                        ..Default::default()
+                    })
+                    .with(ZIndex::Global(i32::MAX - 1));
+                let root = commands.current_entity().unwrap();
+                state.root = Some(root);
+                root
+            }
+        };
+
+        let icon_material = toast.icon.map(|icon| {
+            *state
+                .icon_materials
+                .entry(icon)
+                .or_insert_with(|| materials.add(icon.into()))
+        });
+        let font_size = toasts.font_size;
+        commands
+            .spawn(NodeComponents {
+                style: Style {
+                    size: Size::new(Val::Px(toasts.width), Val::Auto),
+                    margin: Rect {
+                        top: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                    padding: Rect::all(Val::Px(8.0)),
+                    position: Rect {
+                        left: Val::Px(slide),
+                        ..Default::default()
+                    },
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: state.materials[&toast.severity],
+                ..Default::default()
+            })
+            .with(Opacity(0.0))
+            .with_children(|parent| {
+                if let Some(icon_material) = icon_material {
+                    parent.spawn(ImageComponents {
+                        style: Style {
+                            size: Size::new(Val::Px(font_size * 1.5), Val::Px(font_size * 1.5)),
+                            margin: Rect {
+                                right: Val::Px(8.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        material: icon_material,
+                        ..Default::default()
+                    });
+                }
+                parent.spawn(TextComponents {
+                    text: Text {
+                        value: toast.text.clone(),
+                        font,
+                        style: TextStyle {
+                            font_size,
+                            color: toasts.text_color,
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                });
+            });
+        let entity = commands.current_entity().unwrap();
+        commands.push_children(root, &[entity]);
+        state.shown.push(ShownToast {
+            entity,
+            age: 0.0,
+            duration: toast.duration,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{toast_visibility, Toast, Toasts};
+
+    #[test]
+    fn toast_fades() {
+        assert_eq!(toast_visibility(0.0, 2.0, 0.5), Some(0.0));
+        assert_eq!(toast_visibility(0.25, 2.0, 0.5), Some(0.5));
+        assert_eq!(toast_visibility(1.0, 2.0, 0.5), Some(1.0));
+        assert_eq!(toast_visibility(2.75, 2.0, 0.5), Some(0.5));
+        assert_eq!(toast_visibility(3.0, 2.0, 0.5), None);
+        assert_eq!(toast_visibility(1.0, 2.0, 0.0), Some(1.0));
+        assert_eq!(toast_visibility(2.0, 2.0, 0.0), None);
+
+        let mut toasts = Toasts::default();
+        toasts.push(Toast::warning("Low battery").with_duration(1.0));
+        toasts.push(Toast::info("Saved"));
+        assert_eq!(
+            toasts
+                .queued()
+                .map(|toast| toast.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Low battery", "Saved"]
+        );
+        toasts.clear();
+        assert_eq!(toasts.queued().count(), 0);
+    }
+}
@@ -0,0 +1,93 @@
+/// Spawns a hierarchy of UI nodes using [Commands](bevy_ecs::Commands) (or a [ChildBuilder](bevy_transform::prelude::ChildBuilder)).
+/// Each entry is a component bundle, optionally followed by `=> { ... }` containing the bundles of its children.
+/// [BuildChildren](bevy_transform::prelude::BuildChildren) must be in scope.
+///
+/// ```
+/// # use bevy_asset::Handle;
+/// # use bevy_ecs::Commands;
+/// # use bevy_text::Font;
+/// # use bevy_transform::prelude::BuildChildren;
+/// # use bevy_ui::{prelude::*, ui};
+/// fn setup(mut commands: Commands, font: Handle<Font>) {
+///     ui!(commands, {
+///         NodeComponents {
+///             style: Style {
+///                 flex_direction: FlexDirection::Column,
+///                 ..Default::default()
+///             },
+///             ..Default::default()
+///         } => {
+///             ButtonComponents::default() => {
+///                 TextComponents {
+///                     text: Text {
+///                         value: "Play".to_string(),
+///                         font,
+///                         ..Default::default()
+///                     },
+///                     ..Default::default()
+///                 },
+///             },
+///             ButtonComponents::default(),
+///         },
+///     });
+/// }
+/// ```
+#[macro_export]
+macro_rules! ui {
+    ($builder:ident, { $($bundle:expr $(=> { $($children:tt)* })?),* $(,)? }) => {
+        $(
+            $builder
+                .spawn($bundle)
+                $(.with_children(|parent| {
+                    $crate::ui!(parent, { $($children)* });
+                }))?;
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::{ButtonComponents, NodeComponents, TextComponents},
+        widget::{Button, Text},
+    };
+    use bevy_ecs::{Commands, Entity, Resources, With, Without, World};
+    use bevy_transform::prelude::{BuildChildren, Children, Parent};
+
+    #[test]
+    fn build_ui_tree() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut commands = Commands::default();
+        ui!(commands, {
+            NodeComponents::default() => {
+                ButtonComponents::default() => {
+                    TextComponents::default(),
+                },
+                ButtonComponents::default(),
+            },
+        });
+        commands.apply(&mut world, &mut resources);
+
+        let roots = world
+            .query::<Without<Parent, (Entity, &Children)>>()
+            .iter()
+            .map(|(entity, children)| (entity, children.0.to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(roots.len(), 1);
+        let (root, buttons) = &roots[0];
+        assert_eq!(buttons.len(), 2);
+        for button in buttons.iter() {
+            assert!(world.get::<Button>(*button).is_ok());
+            assert_eq!(*world.get::<Parent>(*button).unwrap(), Parent(*root));
+        }
+
+        let text_parents = world
+            .query::<With<Text, &Parent>>()
+            .iter()
+            .map(|parent| parent.0)
+            .collect::<Vec<_>>();
+        assert_eq!(text_parents, vec![buttons[0]]);
+        assert!(world.get::<Children>(buttons[1]).is_err());
+    }
+}
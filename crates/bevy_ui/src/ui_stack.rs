@@ -0,0 +1,170 @@
+use crate::{FlexSurface, FocusPolicy, HitTest, HitTestMasks, Node};
+use bevy_core::FloatOrd;
+use bevy_ecs::{Entity, Query, Res, ResMut};
+use bevy_math::{Vec2, Vec4};
+use bevy_transform::components::Transform;
+
+/// A ui node in the [UiStack]
+#[derive(Debug, Clone)]
+pub struct UiStackNode {
+    pub entity: Entity,
+    /// The window space position of the node's center, in physical pixels
+    pub position: Vec2,
+    pub size: Vec2,
+    pub z: f32,
+    /// See [Node::clip]
+    pub clip: Vec4,
+    pub hit_test: HitTest,
+    pub focus_policy: FocusPolicy,
+    /// The scale factor of the window the node is laid out for
+    pub scale_factor: f32,
+}
+
+impl UiStackNode {
+    /// Whether the window space `position` is on the node's [HitTest] shape and inside its clip rect
+    pub fn contains(&self, position: Vec2, masks: &HitTestMasks) -> bool {
+        position.x() >= self.clip.x()
+            && position.y() >= self.clip.y()
+            && position.x() <= self.clip.z()
+            && position.y() <= self.clip.w()
+            && self.hit_test.contains(
+                position - self.position,
+                self.size,
+                self.scale_factor,
+                masks,
+            )
+    }
+}
+
+/// Every ui node, ordered from the topmost node down. Context menus, drag and drop, and editor tools can use this to
+/// find the nodes under a point without going through [Interaction](crate::Interaction). It is rebuilt in the
+/// `POST_UPDATE` stage, so during `UPDATE` it matches what was drawn in the last frame.
+/// [ui_focus_system](crate::ui_focus_system) updates [Interaction](crate::Interaction)s from it in `PRE_UPDATE`.
+#[derive(Debug, Default)]
+pub struct UiStack {
+    nodes: Vec<UiStackNode>,
+}
+
+impl UiStack {
+    /// The nodes from the topmost down
+    pub fn iter(&self) -> impl Iterator<Item = &UiStackNode> {
+        self.nodes.iter()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&UiStackNode> {
+        self.nodes.iter().find(|node| node.entity == entity)
+    }
+
+    /// The nodes whose hit shape contains the window space `position`, from the topmost down. This includes nodes
+    /// covered by a node with [FocusPolicy::Block]; use [UiStack::pick_unblocked] for the nodes the pointer reaches.
+    pub fn pick(&self, position: Vec2, masks: &HitTestMasks) -> Vec<Entity> {
+        self.nodes
+            .iter()
+            .filter(|node| node.contains(position, masks))
+            .map(|node| node.entity)
+            .collect()
+    }
+
+    /// Like [UiStack::pick], but stops at the first node with [FocusPolicy::Block], like
+    /// [ui_focus_system](crate::ui_focus_system)
+    pub fn pick_unblocked(&self, position: Vec2, masks: &HitTestMasks) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for node in self
+            .nodes
+            .iter()
+            .filter(|node| node.contains(position, masks))
+        {
+            entities.push(node.entity);
+            if node.focus_policy == FocusPolicy::Block {
+                break;
+            }
+        }
+        entities
+    }
+
+    /// The topmost node whose hit shape contains the window space `position`
+    pub fn pick_top(&self, position: Vec2, masks: &HitTestMasks) -> Option<Entity> {
+        self.nodes
+            .iter()
+            .find(|node| node.contains(position, masks))
+            .map(|node| node.entity)
+    }
+}
+
+/// Rebuilds the [UiStack] from the final transforms and clip rects of the frame
+pub fn ui_stack_system(
+    flex_surface: Res<FlexSurface>,
+    mut ui_stack: ResMut<UiStack>,
+    mut node_query: Query<(
+        Entity,
+        &Node,
+        &Transform,
+        Option<&HitTest>,
+        Option<&FocusPolicy>,
+    )>,
+) {
+    ui_stack.nodes.clear();
+    for (entity, node, transform, hit_test, focus_policy) in &mut node_query.iter() {
+        let position = transform.value.w_axis();
+        ui_stack.nodes.push(UiStackNode {
+            entity,
+            position: position.truncate().truncate(),
+            size: node.size,
+            z: position.z(),
+            clip: node.clip,
+            hit_test: hit_test.cloned().unwrap_or_default(),
+            focus_policy: focus_policy.cloned().unwrap_or_default(),
+            scale_factor: flex_surface.scale_factor(entity) as f32,
+        });
+    }
+    ui_stack.nodes.sort_by_key(|node| -FloatOrd(node.z));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UiStack, UiStackNode};
+    use crate::{FocusPolicy, HitTest, HitTestMasks, Node};
+    use bevy_ecs::Entity;
+    use bevy_math::{Vec2, Vec4};
+
+    fn stack_node(entity: Entity, z: f32, focus_policy: FocusPolicy) -> UiStackNode {
+        UiStackNode {
+            entity,
+            position: Vec2::new(50.0, 50.0),
+            size: Vec2::new(100.0, 100.0),
+            z,
+            clip: Vec4::from(Node::UNCLIPPED),
+            hit_test: HitTest::Rect,
+            focus_policy,
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn pick() {
+        let masks = HitTestMasks::default();
+        let (panel, button, tooltip) = (Entity::new(), Entity::new(), Entity::new());
+        let mut hidden = stack_node(Entity::new(), 0.3, FocusPolicy::Block);
+        hidden.clip = Vec4::new(0.0, 0.0, 10.0, 10.0);
+        let ui_stack = UiStack {
+            nodes: vec![
+                hidden,
+                stack_node(tooltip, 0.2, FocusPolicy::Pass),
+                stack_node(button, 0.1, FocusPolicy::Block),
+                stack_node(panel, 0.0, FocusPolicy::Block),
+            ],
+        };
+
+        let position = Vec2::new(50.0, 50.0);
+        assert_eq!(
+            ui_stack.pick(position, &masks),
+            vec![tooltip, button, panel]
+        );
+        assert_eq!(
+            ui_stack.pick_unblocked(position, &masks),
+            vec![tooltip, button]
+        );
+        assert_eq!(ui_stack.pick_top(position, &masks), Some(tooltip));
+        assert_eq!(ui_stack.pick_top(Vec2::new(150.0, 50.0), &masks), None);
+    }
+}
@@ -1,55 +1,627 @@
-use super::Node;
-use bevy_ecs::{Entity, Query, With, Without};
+use super::{
+    flex::content_insets, ComputedLayout, FlexSurface, LayoutRect, Node, Opacity, Overflow, Style,
+    UiTargetWindow,
+};
+use bevy_ecs::{Commands, Entity, Query, Res, With, Without};
+use bevy_math::{Vec2, Vec4};
+use bevy_render::camera::TargetWindow;
 use bevy_transform::{
     hierarchy,
-    prelude::{Children, LocalTransform, Parent},
+    prelude::{Children, LocalTransform, Parent, Transform},
 };
+use bevy_window::{WindowId, Windows};
+use std::collections::HashMap;
 
 pub const UI_Z_STEP: f32 = 0.001;
 
+/// Controls the order in which ui nodes are drawn and receive interactions. Nodes without a `ZIndex` use `ZIndex::Local(0)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZIndex {
+    /// Orders the node (and its descendants) relative to its siblings. Higher indices are drawn on top.
+    Local(i32),
+    /// Orders the node (and its descendants) relative to every root node, as if it were a root itself. Root nodes have
+    /// an implicit global index of 0, so `ZIndex::Global(1)` draws a node above all regular nodes (ex: tooltips and modals).
+    Global(i32),
+}
+
+impl Default for ZIndex {
+    fn default() -> Self {
+        ZIndex::Local(0)
+    }
+}
+
 pub fn ui_z_system(
     mut root_node_query: Query<With<Node, Without<Parent, Entity>>>,
-    mut node_query: Query<(Entity, &Node, &mut LocalTransform)>,
+    mut node_query: Query<(Entity, &Node, &mut LocalTransform, Option<&ZIndex>)>,
     children_query: Query<&Children>,
+    parent_query: Query<With<Node, &Parent>>,
 ) {
+    // root nodes and nodes with a global z index each start a new stacking context
+    let mut stacking_roots = Vec::new();
+    for entity in &mut root_node_query.iter().iter() {
+        let z_index = match node_query
+            .get::<ZIndex>(entity)
+            .ok()
+            .map(|z_index| *z_index)
+        {
+            Some(ZIndex::Global(z_index)) => z_index,
+            _ => 0,
+        };
+        stacking_roots.push((z_index, entity));
+    }
+
+    for (entity, _node, _transform, z_index) in &mut node_query.iter() {
+        if let Some(ZIndex::Global(z_index)) = z_index {
+            if parent_query.get::<Parent>(entity).is_ok() {
+                stacking_roots.push((*z_index, entity));
+            }
+        }
+    }
+
+    // NOTE: sort_by_key is stable, so nodes with equal indices keep their hierarchy order
+    stacking_roots.sort_by_key(|(z_index, _)| *z_index);
+
+    let mut global_z = HashMap::new();
     let mut current_global_z = 0.0;
+    for (_, entity) in stacking_roots {
+        assign_global_z(
+            &node_query,
+            &children_query,
+            entity,
+            &mut current_global_z,
+            &mut global_z,
+        );
+    }
+
+    // transforms are relative to the parent, so convert global z into local z
+    for (entity, z) in global_z.iter() {
+        let parent_z = parent_query
+            .get::<Parent>(*entity)
+            .ok()
+            .and_then(|parent| global_z.get(&parent.0).cloned())
+            .unwrap_or(0.0);
+        if let Ok(mut transform) = node_query.get_mut::<LocalTransform>(*entity) {
+            let mut position = transform.w_axis();
+            position.set_z(z - parent_z);
+            transform.set_w_axis(position);
+        }
+    }
+}
 
-    // PERF: we can probably avoid an allocation here by making root_node_query and node_query non-overlapping
+fn assign_global_z(
+    node_query: &Query<(Entity, &Node, &mut LocalTransform, Option<&ZIndex>)>,
+    children_query: &Query<&Children>,
+    entity: Entity,
+    current_global_z: &mut f32,
+    global_z: &mut HashMap<Entity, f32>,
+) {
+    *current_global_z += UI_Z_STEP;
+    global_z.insert(entity, *current_global_z);
+
+    let mut children = match children_query.get::<Children>(entity) {
+        Ok(children) => children
+            .iter()
+            .filter(|child| node_query.get::<Node>(**child).is_ok())
+            .filter_map(|child| {
+                match node_query
+                    .get::<ZIndex>(*child)
+                    .ok()
+                    .map(|z_index| *z_index)
+                {
+                    // globally indexed children are handled as stacking roots
+                    Some(ZIndex::Global(_)) => None,
+                    Some(ZIndex::Local(z_index)) => Some((z_index, *child)),
+                    None => Some((0, *child)),
+                }
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+
+    children.sort_by_key(|(z_index, _)| *z_index);
+    for (_, child) in children {
+        assign_global_z(
+            node_query,
+            children_query,
+            child,
+            current_global_z,
+            global_z,
+        );
+    }
+}
+
+pub fn ui_opacity_system(
+    mut root_node_query: Query<With<Node, Without<Parent, Entity>>>,
+    mut node_query: Query<(Entity, &mut Node, Option<&Opacity>)>,
+    children_query: Query<&Children>,
+) {
     let root_nodes = (&mut root_node_query.iter())
         .iter()
         .collect::<Vec<Entity>>();
 
     for entity in root_nodes {
-        if let Some(result) = hierarchy::run_on_hierarchy(
+        hierarchy::run_on_hierarchy(
             &children_query,
             &mut node_query,
             entity,
-            Some(current_global_z),
-            Some(current_global_z),
-            &mut update_node_entity,
-        ) {
-            current_global_z = result;
-        }
+            Some(1.0),
+            None,
+            &mut update_node_opacity,
+        );
     }
 }
 
-fn update_node_entity(
-    node_query: &mut Query<(Entity, &Node, &mut LocalTransform)>,
+fn update_node_opacity(
+    node_query: &mut Query<(Entity, &mut Node, Option<&Opacity>)>,
     entity: Entity,
     parent_result: Option<f32>,
-    previous_result: Option<f32>,
+    _previous_result: Option<f32>,
 ) -> Option<f32> {
-    let mut transform = node_query.get_mut::<LocalTransform>(entity).ok()?;
-    let mut z = UI_Z_STEP;
-    let parent_global_z = parent_result.unwrap();
-    if let Some(previous_global_z) = previous_result {
-        z += previous_global_z - parent_global_z;
+    let opacity = node_query
+        .get::<Opacity>(entity)
+        .map_or(1.0, |opacity| opacity.0.max(0.0).min(1.0))
+        * parent_result.unwrap();
+    let mut node = node_query.get_mut::<Node>(entity).ok()?;
+    // avoid mutating unchanged nodes
+    if node.opacity != opacity {
+        node.opacity = opacity;
+    }
+
+    Some(opacity)
+}
+
+/// Restricts drawing each ui node to the cameras of the window its root node is laid out in, see [UiTargetWindow]
+pub fn ui_target_window_system(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    mut root_node_query: Query<With<Node, Without<Parent, (Entity, Option<&UiTargetWindow>)>>>,
+    mut node_query: Query<(&Node, Option<&TargetWindow>)>,
+    children_query: Query<&Children>,
+) {
+    let primary_window = match windows.get_primary() {
+        Some(window) => window.id,
+        None => return,
+    };
+
+    let root_nodes = (&mut root_node_query.iter())
+        .iter()
+        .map(|(entity, target_window)| {
+            (
+                entity,
+                target_window.map_or(primary_window, |target_window| target_window.0),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = (&mut commands, &mut node_query);
+    for (entity, window_id) in root_nodes {
+        hierarchy::run_on_hierarchy(
+            &children_query,
+            &mut state,
+            entity,
+            Some(window_id),
+            None,
+            &mut update_node_target_window,
+        );
+    }
+}
+
+fn update_node_target_window(
+    (commands, node_query): &mut (&mut Commands, &mut Query<(&Node, Option<&TargetWindow>)>),
+    entity: Entity,
+    parent_result: Option<WindowId>,
+    _previous_result: Option<WindowId>,
+) -> Option<WindowId> {
+    let window_id = parent_result.unwrap();
+    let target_window = node_query
+        .get::<TargetWindow>(entity)
+        .ok()
+        .map(|target_window| *target_window);
+    // non-node children (ex: world anchored entities) keep drawing in every window
+    if node_query.get::<Node>(entity).is_err() {
+        return Some(window_id);
+    }
+    // avoid re-inserting unchanged targets
+    if target_window != Some(TargetWindow(window_id)) {
+        commands.insert_one(entity, TargetWindow(window_id));
+    }
+
+    Some(window_id)
+}
+
+/// Computes the rects nodes are clipped to, and writes each node's [ComputedLayout]
+pub fn ui_clip_system(
+    flex_surface: Res<FlexSurface>,
+    mut root_node_query: Query<With<Node, Without<Parent, Entity>>>,
+    mut node_query: Query<(
+        Entity,
+        &mut Node,
+        &Transform,
+        &Style,
+        Option<&mut ComputedLayout>,
+    )>,
+    children_query: Query<&Children>,
+) {
+    let root_nodes = (&mut root_node_query.iter())
+        .iter()
+        .collect::<Vec<Entity>>();
+
+    for entity in root_nodes {
+        hierarchy::run_on_hierarchy(
+            &children_query,
+            &mut (&*flex_surface, &mut node_query),
+            entity,
+            Some(Node::UNCLIPPED.into()),
+            None,
+            &mut update_node_clip,
+        );
+    }
+}
+
+fn update_node_clip(
+    (flex_surface, node_query): &mut (
+        &FlexSurface,
+        &mut Query<(
+            Entity,
+            &mut Node,
+            &Transform,
+            &Style,
+            Option<&mut ComputedLayout>,
+        )>,
+    ),
+    entity: Entity,
+    parent_result: Option<Vec4>,
+    _previous_result: Option<Vec4>,
+) -> Option<Vec4> {
+    let clip = parent_result.unwrap();
+    let style = node_query.get::<Style>(entity).ok()?;
+    let overflow = style.overflow;
+    let position = node_query.get::<Transform>(entity).ok()?.value.w_axis();
+    let mut node = node_query.get_mut::<Node>(entity).ok()?;
+    // avoid mutating unchanged nodes
+    if node.clip != clip {
+        node.clip = clip;
+    }
+
+    if let Ok(mut computed_layout) = node_query.get_mut::<ComputedLayout>(entity) {
+        // padding and borders are resolved in logical pixels, like the layout
+        let scale_factor = flex_surface.scale_factor(entity) as f32;
+        let insets = content_insets(&style, node.size / scale_factor);
+        let center = position.truncate().truncate();
+        let rect = LayoutRect::from_center_size(center, node.size);
+        let content_min = rect.min + Vec2::new(insets.left, insets.bottom) * scale_factor;
+        let content_max = rect.max - Vec2::new(insets.right, insets.top) * scale_factor;
+        let layout = ComputedLayout {
+            size: node.size,
+            position: center,
+            content: LayoutRect {
+                min: content_min,
+                max: content_max.max(content_min),
+            },
+            clip: LayoutRect {
+                min: Vec2::new(clip.x(), clip.y()),
+                max: Vec2::new(clip.z(), clip.w()),
+            },
+        };
+        // avoid mutating unchanged layouts
+        if *computed_layout != layout {
+            *computed_layout = layout;
+        }
+    }
+
+    match overflow {
+        Overflow::Visible => Some(clip),
+        Overflow::Hidden | Overflow::Scroll => {
+            // children are clipped to the intersection of this node's rect and its own clip rect
+            let extents = node.size / 2.0;
+            Some(Vec4::new(
+                clip.x().max(position.x() - extents.x()),
+                clip.y().max(position.y() - extents.y()),
+                clip.z().min(position.x() + extents.x()),
+                clip.w().min(position.y() + extents.y()),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::NodeComponents, ComputedLayout, HeadlessUiPlugin, LayoutRect, Node, Opacity,
+        Overflow, ScrollPosition, Style, UiScale, UiTargetWindow, Val, ZIndex,
     };
-    let global_z = z + parent_global_z;
+    use bevy_app::App;
+    use bevy_core::CorePlugin;
+    use bevy_ecs::{Entity, WorldBuilderSource};
+    use bevy_input::InputPlugin;
+    use bevy_math::{Rect, Size, Vec2};
+    use bevy_render::camera::TargetWindow;
+    use bevy_transform::{hierarchy::BuildWorldChildren, prelude::LocalTransform, TransformPlugin};
+    use bevy_type_registry::TypeRegistryPlugin;
+    use bevy_window::{Window, WindowDescriptor, WindowId, WindowPlugin, Windows};
+
+    fn ui_app() -> App {
+        let mut app_builder = App::build();
+        app_builder
+            .add_plugin(TypeRegistryPlugin::default())
+            .add_plugin(CorePlugin::default())
+            .add_plugin(TransformPlugin::default())
+            .add_plugin(InputPlugin::default())
+            .add_plugin(WindowPlugin {
+                add_primary_window: false,
+                exit_on_close: false,
+            })
+            .add_plugin(HeadlessUiPlugin::default());
+        app_builder.app
+    }
+
+    #[test]
+    fn computed_layout() {
+        let mut app = ui_app();
+        let parent = Entity::new();
+        let child = Entity::new();
+        app.world
+            .build()
+            .spawn_as_entity(
+                parent,
+                NodeComponents {
+                    style: Style {
+                        size: Size::new(Val::Px(100.0), Val::Px(50.0)),
+                        padding: Rect::all(Val::Px(10.0)),
+                        overflow: Overflow::Hidden,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .with_children(|parent| {
+                parent.spawn_as_entity(
+                    child,
+                    NodeComponents {
+                        style: Style {
+                            size: Size::new(Val::Px(200.0), Val::Px(20.0)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                );
+            });
+        app.update();
+
+        let parent_layout = *app.world.get::<ComputedLayout>(parent).unwrap();
+        assert_eq!(parent_layout.size, Vec2::new(100.0, 50.0));
+        assert_eq!(
+            parent_layout.rect(),
+            LayoutRect {
+                min: Vec2::zero(),
+                max: Vec2::new(100.0, 50.0)
+            }
+        );
+        assert_eq!(
+            parent_layout.content,
+            LayoutRect {
+                min: Vec2::new(10.0, 10.0),
+                max: Vec2::new(90.0, 40.0)
+            }
+        );
+
+        // the child overflows its parent, which clips it
+        let child_layout = *app.world.get::<ComputedLayout>(child).unwrap();
+        assert_eq!(child_layout.rect().min, Vec2::new(10.0, 10.0));
+        assert_eq!(child_layout.clip, parent_layout.rect());
+        assert!(child_layout.contains_visible(Vec2::new(50.0, 20.0)));
+        assert!(!child_layout.contains_visible(Vec2::new(150.0, 20.0)));
+    }
+
+    #[test]
+    fn hierarchical_opacity() {
+        let mut app = ui_app();
+        let parent = Entity::new();
+        let child = Entity::new();
+        let grandchild = Entity::new();
+        let opaque_child = Entity::new();
+        app.world
+            .build()
+            .spawn_as_entity(parent, NodeComponents::default())
+            .with(Opacity(0.5))
+            .with_children(|parent| {
+                parent
+                    .spawn_as_entity(child, NodeComponents::default())
+                    .with(Opacity(0.5))
+                    .with_children(|parent| {
+                        parent.spawn_as_entity(grandchild, NodeComponents::default());
+                    })
+                    // opacity is clamped, so children can't be more opaque than their parent
+                    .spawn_as_entity(opaque_child, NodeComponents::default())
+                    .with(Opacity(2.0));
+            });
+        app.update();
+
+        let opacity = |entity| app.world.get::<Node>(entity).unwrap().opacity;
+        assert_eq!(opacity(parent), 0.5);
+        assert_eq!(opacity(child), 0.25);
+        assert_eq!(opacity(grandchild), 0.25);
+        assert_eq!(opacity(opaque_child), 0.5);
+    }
+
+    #[test]
+    fn root_nodes_in_target_window() {
+        let mut app = ui_app();
+        let window = Window::new(
+            WindowId::new(),
+            &WindowDescriptor {
+                width: 200,
+                height: 100,
+                ..Default::default()
+            },
+        );
+        let window_id = window.id;
+        app.resources.get_mut::<Windows>().unwrap().add(window);
 
-    let mut position = transform.w_axis();
-    position.set_z(z);
-    transform.set_w_axis(position);
+        let full_size = || NodeComponents {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let primary_root = Entity::new();
+        let root = Entity::new();
+        let child = Entity::new();
+        app.world
+            .build()
+            .spawn_as_entity(primary_root, full_size())
+            .spawn_as_entity(root, full_size())
+            .with(UiTargetWindow(window_id))
+            .with_children(|parent| {
+                parent.spawn_as_entity(child, full_size());
+            });
+        app.update();
 
-    return Some(global_z);
+        let descriptor = WindowDescriptor::default();
+        let size = |entity| app.world.get::<ComputedLayout>(entity).unwrap().size;
+        assert_eq!(
+            size(primary_root),
+            Vec2::new(descriptor.width as f32, descriptor.height as f32)
+        );
+        assert_eq!(size(root), Vec2::new(200.0, 100.0));
+        assert_eq!(size(child), Vec2::new(200.0, 100.0));
+
+        // descendants are only drawn in their root's window
+        let target_window = |entity| *app.world.get::<TargetWindow>(entity).unwrap();
+        assert_eq!(
+            target_window(primary_root),
+            TargetWindow(WindowId::primary())
+        );
+        assert_eq!(target_window(child), TargetWindow(window_id));
+    }
+
+    #[test]
+    fn z_index() {
+        let mut app = ui_app();
+        let root = Entity::new();
+        let above = Entity::new();
+        let below = Entity::new();
+        let global = Entity::new();
+        let next_root = Entity::new();
+        app.world
+            .build()
+            .spawn_as_entity(root, NodeComponents::default())
+            .with_children(|parent| {
+                parent
+                    .spawn_as_entity(above, NodeComponents::default())
+                    .with(ZIndex::Local(1))
+                    .spawn_as_entity(below, NodeComponents::default())
+                    .spawn_as_entity(global, NodeComponents::default())
+                    .with(ZIndex::Global(1));
+            })
+            .spawn_as_entity(next_root, NodeComponents::default());
+        app.update();
+
+        // local z values are relative to the parent
+        let z = |entity| {
+            app.world
+                .get::<LocalTransform>(entity)
+                .unwrap()
+                .w_axis()
+                .z()
+        };
+        let global_z = |entity| {
+            if entity == root || entity == next_root {
+                z(entity)
+            } else {
+                z(root) + z(entity)
+            }
+        };
+        // globally indexed nodes are drawn above every root, the others are ordered among their siblings
+        let mut order = vec![global, above, below, root];
+        order.sort_by(|a, b| global_z(*a).partial_cmp(&global_z(*b)).unwrap());
+        assert_eq!(order, vec![root, below, above, global]);
+        assert!(global_z(global) > global_z(next_root));
+    }
+
+    #[test]
+    fn clamp_scroll_offset() {
+        let mut app = ui_app();
+        let parent = Entity::new();
+        let child = Entity::new();
+        app.world
+            .build()
+            .spawn_as_entity(
+                parent,
+                NodeComponents {
+                    style: Style {
+                        size: Size::new(Val::Px(100.0), Val::Px(50.0)),
+                        overflow: Overflow::Scroll,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .with(ScrollPosition {
+                offset: Vec2::new(-10.0, 500.0),
+            })
+            .with_children(|parent| {
+                parent.spawn_as_entity(
+                    child,
+                    NodeComponents {
+                        style: Style {
+                            size: Size::new(Val::Px(100.0), Val::Px(200.0)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                );
+            });
+        app.update();
+
+        // the offset stops once the bottom of the child reaches the bottom of the node
+        let offset = app.world.get::<ScrollPosition>(parent).unwrap().offset;
+        assert_eq!(offset, Vec2::new(0.0, 150.0));
+        let parent_layout = *app.world.get::<ComputedLayout>(parent).unwrap();
+        let child_layout = *app.world.get::<ComputedLayout>(child).unwrap();
+        assert_eq!(child_layout.rect().min, Vec2::new(0.0, -150.0));
+        assert_eq!(child_layout.clip, parent_layout.rect());
+    }
+
+    #[test]
+    fn logical_pixels() {
+        let mut app = ui_app();
+        let mut window = Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: 800,
+                height: 600,
+                ..Default::default()
+            },
+        );
+        window.scale_factor = 2.0;
+        app.resources.get_mut::<Windows>().unwrap().add(window);
+        let root = Entity::new();
+        app.world.build().spawn_as_entity(
+            root,
+            NodeComponents {
+                style: Style {
+                    size: Size::new(Val::Px(100.0), Val::Percent(100.0)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        // points are scaled by the window's scale factor, while percentages fill the same part of the window
+        app.update();
+        assert_eq!(
+            app.world.get::<Node>(root).unwrap().size,
+            Vec2::new(200.0, 600.0)
+        );
+
+        // UiScale overrides the scale factor of every window
+        app.resources.get_mut::<UiScale>().unwrap().scale_factor = Some(1.0);
+        app.update();
+        assert_eq!(
+            app.world.get::<Node>(root).unwrap().size,
+            Vec2::new(100.0, 600.0)
+        );
+    }
 }
@@ -0,0 +1,250 @@
+use bevy_app::Events;
+use bevy_core::Time;
+use bevy_ecs::{Res, ResMut};
+use bevy_input::{
+    gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads},
+    keyboard::ElementState,
+    mouse::{MouseButton, MouseButtonInput},
+    Axis, Input,
+};
+use bevy_math::Vec2;
+use bevy_window::{CursorMoved, WindowId, Windows};
+
+/// A cursor moved by a gamepad stick, so ui made for the mouse can be used with a controller. The cursor sends
+/// [CursorMoved] and [MouseButtonInput] events like a real mouse, so ui interaction and any other system reading
+/// those events work as usual. It is off until [VirtualCursor::enabled] is set.
+///
+/// The cursor isn't drawn. Use [VirtualCursor::position] to place a sprite or ui node at it.
+#[derive(Debug, Clone)]
+pub struct VirtualCursor {
+    pub enabled: bool,
+    /// The gamepad that moves the cursor. All gamepads move it if this is `None`.
+    pub gamepad: Option<Gamepad>,
+    pub stick_x: GamepadAxisType,
+    pub stick_y: GamepadAxisType,
+    /// The speed in logical pixels per second when the stick starts being pushed all the way
+    pub speed: f32,
+    /// The speed the cursor accelerates to while the stick is held
+    pub max_speed: f32,
+    /// How fast the speed goes from [VirtualCursor::speed] to [VirtualCursor::max_speed], in logical pixels per
+    /// second squared
+    pub acceleration: f32,
+    /// The gamepad buttons that press mouse buttons
+    pub buttons: Vec<(GamepadButtonType, MouseButton)>,
+    pub window: WindowId,
+    position: Option<Vec2>,
+    current_speed: f32,
+}
+
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        VirtualCursor {
+            enabled: false,
+            gamepad: None,
+            stick_x: GamepadAxisType::RightStickX,
+            stick_y: GamepadAxisType::RightStickY,
+            speed: 400.0,
+            max_speed: 1200.0,
+            acceleration: 1600.0,
+            buttons: vec![
+                (GamepadButtonType::South, MouseButton::Left),
+                (GamepadButtonType::East, MouseButton::Right),
+            ],
+            window: WindowId::primary(),
+            position: None,
+            current_speed: 0.0,
+        }
+    }
+}
+
+impl VirtualCursor {
+    /// The position of the cursor, in physical pixels from the bottom left corner of its window. This is `None` until
+    /// the cursor is first enabled.
+    pub fn position(&self) -> Option<Vec2> {
+        self.position
+    }
+
+    /// Moves the cursor to `position`, in physical pixels from the bottom left corner of its window
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = Some(position);
+    }
+
+    fn gamepads(&self, gamepads: &Gamepads) -> Vec<Gamepad> {
+        match self.gamepad {
+            Some(gamepad) => vec![gamepad],
+            None => gamepads.iter().cloned().collect(),
+        }
+    }
+
+    /// The stick deflection of the gamepads that move the cursor. The most deflected stick wins.
+    fn stick(&self, gamepads: &[Gamepad], axes: &Axis<GamepadAxis>) -> Vec2 {
+        let mut stick = Vec2::zero();
+        for gamepad in gamepads.iter() {
+            let value = Vec2::new(
+                axes.get(GamepadAxis(*gamepad, self.stick_x)).unwrap_or(0.0),
+                axes.get(GamepadAxis(*gamepad, self.stick_y)).unwrap_or(0.0),
+            );
+            if value.length_squared() > stick.length_squared() {
+                stick = value;
+            }
+        }
+        if stick.length_squared() > 1.0 {
+            stick = stick.normalize();
+        }
+        stick
+    }
+}
+
+/// Moves the [VirtualCursor] with the gamepad and sends the mouse events of the cursor. This runs before the mouse
+/// input and ui focus systems, so they handle the virtual cursor in the same frame.
+pub fn virtual_cursor_system(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut cursor: ResMut<VirtualCursor>,
+    mut cursor_moved_events: ResMut<Events<CursorMoved>>,
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+) {
+    if !cursor.enabled {
+        return;
+    }
+    let window = match windows.get(cursor.window) {
+        Some(window) => window,
+        None => return,
+    };
+    let window_size = Vec2::new(window.width as f32, window.height as f32);
+
+    let cursor_gamepads = cursor.gamepads(&gamepads);
+    let stick = cursor.stick(&cursor_gamepads, &gamepad_axes);
+    let position = match cursor.position {
+        Some(position) => position,
+        None => {
+            // start in the middle of the window
+            let position = window_size / 2.0;
+            cursor_moved_events.send(CursorMoved {
+                id: cursor.window,
+                position,
+            });
+            position
+        }
+    };
+
+    if stick == Vec2::zero() {
+        cursor.current_speed = cursor.speed;
+        cursor.position = Some(position);
+    } else {
+        let delta_seconds = time.delta_seconds;
+        cursor.current_speed = (cursor.current_speed.max(cursor.speed)
+            + cursor.acceleration * delta_seconds)
+            .min(cursor.max_speed.max(cursor.speed));
+        let velocity = stick * cursor.current_speed * window.scale_factor as f32;
+        let new_position = (position + velocity * delta_seconds)
+            .max(Vec2::zero())
+            .min(window_size);
+        cursor.position = Some(new_position);
+        if new_position != position {
+            cursor_moved_events.send(CursorMoved {
+                id: cursor.window,
+                position: new_position,
+            });
+        }
+    }
+
+    for (gamepad_button, mouse_button) in cursor.buttons.iter() {
+        for gamepad in cursor_gamepads.iter() {
+            let button = GamepadButton(*gamepad, *gamepad_button);
+            let state = if gamepad_buttons.just_pressed(button) {
+                ElementState::Pressed
+            } else if gamepad_buttons.just_released(button) {
+                ElementState::Released
+            } else {
+                continue;
+            };
+            mouse_button_events.send(MouseButtonInput {
+                button: *mouse_button,
+                state,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{virtual_cursor_system, VirtualCursor};
+    use bevy_app::{EventReader, Events};
+    use bevy_core::Time;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use bevy_input::{
+        gamepad::{
+            Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads,
+        },
+        keyboard::ElementState,
+        mouse::{MouseButton, MouseButtonInput},
+        Axis, Input,
+    };
+    use bevy_math::Vec2;
+    use bevy_window::{CursorMoved, Window, WindowDescriptor, WindowId, Windows};
+    use std::time::Duration;
+
+    #[test]
+    fn move_and_click() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: 800,
+                height: 600,
+                ..Default::default()
+            },
+        ));
+        resources.insert(windows);
+        let mut time = Time::default();
+        time.set_manual_delta(Some(Duration::from_millis(500)));
+        time.update();
+        time.update();
+        resources.insert(time);
+        resources.insert(Gamepads::default());
+        let mut axes = Axis::<GamepadAxis>::default();
+        axes.set(GamepadAxis(Gamepad(0), GamepadAxisType::RightStickX), 1.0);
+        resources.insert(axes);
+        let mut buttons = Input::<GamepadButton>::default();
+        buttons.press(GamepadButton(Gamepad(0), GamepadButtonType::South));
+        resources.insert(buttons);
+        resources.insert(VirtualCursor {
+            enabled: true,
+            gamepad: Some(Gamepad(0)),
+            acceleration: 0.0,
+            ..Default::default()
+        });
+        resources.insert(Events::<CursorMoved>::default());
+        resources.insert(Events::<MouseButtonInput>::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("first");
+        schedule.add_system_to_stage("first", virtual_cursor_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        // starts in the middle of the window and moves right at 400 pixels per second
+        let cursor_moved_events = resources.get::<Events<CursorMoved>>().unwrap();
+        let positions = EventReader::<CursorMoved>::default()
+            .iter(&cursor_moved_events)
+            .map(|event| event.position)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![Vec2::new(400.0, 300.0), Vec2::new(600.0, 300.0)]
+        );
+
+        let mouse_button_events = resources.get::<Events<MouseButtonInput>>().unwrap();
+        let clicks = EventReader::<MouseButtonInput>::default()
+            .iter(&mouse_button_events)
+            .map(|event| (event.button, event.state.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(clicks, vec![(MouseButton::Left, ElementState::Pressed)]);
+    }
+}
@@ -0,0 +1,62 @@
+layout(set = 2, binding = 0) uniform FillMaterial_color {
+    vec4 Color;
+};
+layout(set = 2, binding = 1) uniform FillMaterial_background {
+    vec4 Background;
+};
+
+# ifdef FILLMATERIAL_TEXTURE
+layout(set = 2, binding = 2) uniform texture2D FillMaterial_texture;
+layout(set = 2, binding = 3) uniform sampler FillMaterial_texture_sampler;
+# endif
+
+layout(set = 2, binding = 4) uniform FillMaterial_value {
+    float Value;
+};
+layout(set = 2, binding = 5) uniform FillMaterial_fill {
+    uint Fill;
+};
+layout(set = 2, binding = 6) uniform FillMaterial_start_angle {
+    float StartAngle;
+};
+layout(set = 2, binding = 7) uniform FillMaterial_inner_radius {
+    float InnerRadius;
+};
+
+const float TAU = 6.28318530718;
+
+vec4 ui_material(vec2 uv) {
+    // how far along the fill this pixel is, from 0.0 to 1.0
+    float t;
+    if (Fill == 0) {
+        t = uv.x;
+    } else if (Fill == 1) {
+        t = 1.0 - uv.x;
+    } else if (Fill == 2) {
+        t = 1.0 - uv.y;
+    } else if (Fill == 3) {
+        t = uv.y;
+    } else {
+        // the largest circle that fits in the node, with y up
+        vec2 position = (uv - vec2(0.5)) * NodeSize * vec2(1.0, -1.0);
+        float radius = min(NodeSize.x, NodeSize.y) / 2.0;
+        float distance = length(position);
+        if (distance > radius || distance < InnerRadius * radius) {
+            discard;
+        }
+        // the angle clockwise from the top
+        float angle = atan(position.x, position.y) - StartAngle;
+        t = fract((Fill == 4 ? angle : -angle) / TAU);
+    }
+
+    if (t >= Value && Value < 1.0) {
+        return Background;
+    }
+    vec4 color = Color;
+# ifdef FILLMATERIAL_TEXTURE
+    color *= texture(
+        sampler2D(FillMaterial_texture, FillMaterial_texture_sampler),
+        uv);
+# endif
+    return color;
+}
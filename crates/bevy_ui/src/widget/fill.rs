@@ -0,0 +1,302 @@
+use crate::UiMaterial;
+use bevy_asset::{self, Assets, Handle};
+use bevy_core::Time;
+use bevy_ecs::{Query, Res, ResMut};
+use bevy_render::{color::Color, renderer::RenderResources, shader::ShaderDefs, texture::Texture};
+use std::f32::consts::PI;
+
+/// The direction a [ProgressBar] fills in as its value goes from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillDirection {
+    LeftToRight,
+    RightToLeft,
+    BottomToTop,
+    TopToBottom,
+}
+
+impl Default for FillDirection {
+    fn default() -> Self {
+        FillDirection::LeftToRight
+    }
+}
+
+/// Animates the drawn value of a fill widget to its value, easing out over `duration` seconds
+#[derive(Debug, Clone, Default)]
+struct FillTransition {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    drawn: Option<f32>,
+}
+
+impl FillTransition {
+    fn update(&mut self, value: f32, duration: f32, delta_seconds: f32) -> f32 {
+        let value = value.max(0.0).min(1.0);
+        let drawn = match self.drawn {
+            Some(drawn) => drawn,
+            // new widgets start at their value instead of animating from 0
+            None => value,
+        };
+        if value != self.to || self.drawn.is_none() {
+            self.from = drawn;
+            self.to = value;
+            self.elapsed = 0.0;
+        }
+
+        self.elapsed += delta_seconds;
+        let drawn = if duration <= 0.0 || self.elapsed >= duration {
+            self.to
+        } else {
+            let t = 1.0 - self.elapsed / duration;
+            self.from + (self.to - self.from) * (1.0 - t * t * t)
+        };
+        self.drawn = Some(drawn);
+        drawn
+    }
+}
+
+/// A bar that fills with its value, ex: a health bar or a loading bar. It is drawn with the node's [FillMaterial], which
+/// sets its colors or texture. Each progress bar needs its own material, as the value is written to it.
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    /// From 0.0 (empty) to 1.0 (full)
+    pub value: f32,
+    pub direction: FillDirection,
+    /// The time in seconds the bar takes to animate to a new value. It jumps to the value if this is 0.
+    pub transition: f32,
+    animation: FillTransition,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        ProgressBar {
+            value: 0.0,
+            direction: FillDirection::LeftToRight,
+            transition: 0.25,
+            animation: Default::default(),
+        }
+    }
+}
+
+impl ProgressBar {
+    pub fn new(value: f32) -> Self {
+        ProgressBar {
+            value,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_direction(mut self, direction: FillDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_transition(mut self, transition: f32) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// The value that is drawn, which trails `value` while the bar animates
+    pub fn drawn_value(&self) -> f32 {
+        self.animation.drawn.unwrap_or(self.value)
+    }
+}
+
+/// A circle or ring that fills by sweeping around its center with its value, ex: a cooldown indicator. It is drawn with
+/// the node's [FillMaterial], in the largest circle that fits in the node. Each radial fill needs its own material, as
+/// the value is written to it.
+#[derive(Debug, Clone)]
+pub struct RadialFill {
+    /// From 0.0 (empty) to 1.0 (full)
+    pub value: f32,
+    /// Where the fill starts, in radians clockwise from the top of the circle
+    pub start_angle: f32,
+    pub clockwise: bool,
+    /// The radius of the ring's hole, as a fraction of its radius. A radial fill with 0.0 is a full circle.
+    pub inner_radius: f32,
+    /// The time in seconds the fill takes to animate to a new value. It jumps to the value if this is 0.
+    pub transition: f32,
+    animation: FillTransition,
+}
+
+impl Default for RadialFill {
+    fn default() -> Self {
+        RadialFill {
+            value: 0.0,
+            start_angle: 0.0,
+            clockwise: true,
+            inner_radius: 0.0,
+            transition: 0.25,
+            animation: Default::default(),
+        }
+    }
+}
+
+impl RadialFill {
+    pub fn new(value: f32) -> Self {
+        RadialFill {
+            value,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_inner_radius(mut self, inner_radius: f32) -> Self {
+        self.inner_radius = inner_radius;
+        self
+    }
+
+    pub fn with_transition(mut self, transition: f32) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// The value that is drawn, which trails `value` while the fill animates
+    pub fn drawn_value(&self) -> f32 {
+        self.animation.drawn.unwrap_or(self.value)
+    }
+}
+
+/// The colors or texture of a [ProgressBar] or [RadialFill]. The filled part is drawn with `color`, multiplied by
+/// `texture` if it is set, and the rest of the widget with `background`.
+#[derive(RenderResources, ShaderDefs)]
+pub struct FillMaterial {
+    pub color: Color,
+    pub background: Color,
+    #[shader_def]
+    pub texture: Option<Handle<Texture>>,
+    value: f32,
+    /// 0 to 3 for [FillDirection]s, 4 for clockwise radial fills, and 5 for counterclockwise radial fills
+    fill: u32,
+    start_angle: f32,
+    inner_radius: f32,
+}
+
+impl FillMaterial {
+    pub fn color(color: Color, background: Color) -> Self {
+        FillMaterial {
+            color,
+            background,
+            ..Default::default()
+        }
+    }
+
+    pub fn texture(texture: Handle<Texture>, background: Color) -> Self {
+        FillMaterial {
+            texture: Some(texture),
+            background,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for FillMaterial {
+    fn default() -> Self {
+        FillMaterial {
+            color: Color::WHITE,
+            background: Color::NONE,
+            texture: None,
+            value: 0.0,
+            fill: 0,
+            start_angle: 0.0,
+            inner_radius: 0.0,
+        }
+    }
+}
+
+impl UiMaterial for FillMaterial {
+    fn fragment_shader() -> &'static str {
+        include_str!("fill.frag")
+    }
+}
+
+fn write_fill(
+    materials: &mut Assets<FillMaterial>,
+    handle: &Handle<FillMaterial>,
+    value: f32,
+    fill: u32,
+    start_angle: f32,
+    inner_radius: f32,
+) {
+    let start_angle = start_angle.rem_euclid(2.0 * PI);
+    let changed = materials.get(handle).map_or(false, |material| {
+        material.value != value
+            || material.fill != fill
+            || material.start_angle != start_angle
+            || material.inner_radius != inner_radius
+    });
+    // only write on change, as a modified material is uploaded again
+    if changed {
+        let material = materials.get_mut(handle).unwrap();
+        material.value = value;
+        material.fill = fill;
+        material.start_angle = start_angle;
+        material.inner_radius = inner_radius;
+    }
+}
+
+/// Animates [ProgressBar]s and [RadialFill]s to their values and writes them to their [FillMaterial]s
+pub fn fill_system(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<FillMaterial>>,
+    mut progress_bar_query: Query<(&mut ProgressBar, &Handle<FillMaterial>)>,
+    mut radial_fill_query: Query<(&mut RadialFill, &Handle<FillMaterial>)>,
+) {
+    for (mut progress_bar, material) in &mut progress_bar_query.iter() {
+        let progress_bar = &mut *progress_bar;
+        let value = progress_bar.animation.update(
+            progress_bar.value,
+            progress_bar.transition,
+            time.delta_seconds,
+        );
+        let fill = match progress_bar.direction {
+            FillDirection::LeftToRight => 0,
+            FillDirection::RightToLeft => 1,
+            FillDirection::BottomToTop => 2,
+            FillDirection::TopToBottom => 3,
+        };
+        write_fill(&mut materials, material, value, fill, 0.0, 0.0);
+    }
+
+    for (mut radial_fill, material) in &mut radial_fill_query.iter() {
+        let radial_fill = &mut *radial_fill;
+        let value = radial_fill.animation.update(
+            radial_fill.value,
+            radial_fill.transition,
+            time.delta_seconds,
+        );
+        let fill = if radial_fill.clockwise { 4 } else { 5 };
+        write_fill(
+            &mut materials,
+            material,
+            value,
+            fill,
+            radial_fill.start_angle,
+            radial_fill.inner_radius,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FillTransition;
+
+    #[test]
+    fn fill_transition() {
+        let mut transition = FillTransition::default();
+        // the first value isn't animated
+        assert_eq!(transition.update(0.5, 1.0, 0.1), 0.5);
+
+        // eases out from the drawn value
+        let drawn = transition.update(1.0, 1.0, 0.5);
+        assert!(drawn > 0.75 && drawn < 1.0);
+        assert_eq!(transition.update(1.0, 1.0, 0.5), 1.0);
+
+        // a new value restarts the animation from where the fill is drawn
+        transition.update(0.0, 1.0, 0.5);
+        let drawn = transition.update(1.0, 1.0, 0.0);
+        assert!(drawn > 0.0 && drawn < 0.25);
+
+        // values are clamped, and jump without a duration
+        assert_eq!(transition.update(2.0, 0.0, 0.0), 1.0);
+    }
+}
@@ -1,10 +1,17 @@
-use crate::CalculatedSize;
+use crate::{CalculatedSize, ContentAlign, FlexSurface, Node};
 use bevy_asset::{Assets, Handle};
-use bevy_ecs::{Query, Res};
-use bevy_math::Size;
-use bevy_render::texture::Texture;
-use bevy_sprite::ColorMaterial;
+use bevy_ecs::{Entity, Local, Query, Res, ResMut};
+use bevy_math::{Rect, Size, Vec2};
+use bevy_render::{
+    mesh::{Mesh, VertexAttribute},
+    pipeline::PrimitiveTopology,
+    texture::Texture,
+};
+use bevy_sprite::{ColorMaterial, QUAD_HANDLE};
+use std::collections::HashMap;
 
+/// The node measures its texture. With a [ContentAlign] component, the texture is drawn at the largest size with the
+/// texture's aspect ratio that fits the node, and aligned within the node.
 pub enum Image {
     KeepAspect,
 }
@@ -26,10 +33,536 @@ pub fn image_node_system(
             .and_then(|material| material.texture)
             .and_then(|texture_handle| textures.get(&texture_handle))
             .map(|texture| {
-                calculated_size.size = Size {
+                let size = Size {
                     width: texture.size.x(),
                     height: texture.size.y(),
                 };
+                // only write on change, as a changed CalculatedSize triggers a relayout
+                if calculated_size.size != size {
+                    calculated_size.size = size;
+                }
             });
     }
 }
+
+struct AlignedMesh {
+    mesh: Handle<Mesh>,
+    node_size: Vec2,
+    texture_size: Vec2,
+    content_align: ContentAlign,
+}
+
+#[derive(Default)]
+pub struct ImageAlignSystemState {
+    aligned_meshes: HashMap<Entity, AlignedMesh>,
+}
+
+/// Replaces the quad of image nodes with a [ContentAlign] by a quad that keeps the texture's aspect ratio, which is
+/// rebuilt when the node is resized. Images that already fill their node keep the default quad. [ImageMode::Sliced]
+/// images fill their node and aren't aligned.
+pub fn image_align_system(
+    mut state: Local<ImageAlignSystemState>,
+    materials: Res<Assets<ColorMaterial>>,
+    textures: Res<Assets<Texture>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query: Query<(
+        Entity,
+        &Image,
+        &Node,
+        &ContentAlign,
+        &Handle<ColorMaterial>,
+        &mut Handle<Mesh>,
+    )>,
+    mode_query: Query<&ImageMode>,
+    mesh_query: Query<&mut Handle<Mesh>>,
+) {
+    // nodes that are no longer aligned go back to the default quad
+    for entity in query.removed::<ContentAlign>().iter() {
+        if let Some(aligned_mesh) = state.aligned_meshes.remove(entity) {
+            meshes.remove(&aligned_mesh.mesh);
+            if let Ok(mut mesh) = mesh_query.get_mut::<Handle<Mesh>>(*entity) {
+                *mesh = QUAD_HANDLE;
+            }
+        }
+    }
+
+    for (entity, _image, node, content_align, material, mut mesh) in &mut query.iter() {
+        let texture_size = materials
+            .get(&material)
+            .and_then(|material| material.texture)
+            .and_then(|texture_handle| textures.get(&texture_handle))
+            .map(|texture| texture.size);
+        let sliced = matches!(
+            mode_query.get::<ImageMode>(entity).as_deref(),
+            Ok(ImageMode::Sliced(_))
+        );
+        let (offset, size) = match texture_size {
+            Some(texture_size) if !sliced => fit_image(node.size, texture_size, content_align),
+            _ => (Vec2::zero(), node.size),
+        };
+
+        // images that fill their node use the default quad, or the mesh of image_slice_system if they are sliced
+        if size == node.size {
+            if let Some(aligned_mesh) = state.aligned_meshes.remove(&entity) {
+                meshes.remove(&aligned_mesh.mesh);
+                if *mesh == aligned_mesh.mesh {
+                    *mesh = QUAD_HANDLE;
+                }
+            }
+            continue;
+        }
+
+        let texture_size = texture_size.unwrap();
+        if let Some(aligned_mesh) = state.aligned_meshes.get(&entity) {
+            if aligned_mesh.node_size == node.size
+                && aligned_mesh.texture_size == texture_size
+                && aligned_mesh.content_align == *content_align
+                && *mesh == aligned_mesh.mesh
+            {
+                continue;
+            }
+        }
+
+        let aligned = aligned_quad(node.size, offset, size);
+        let handle = match state.aligned_meshes.get(&entity) {
+            Some(aligned_mesh) => {
+                meshes.set(aligned_mesh.mesh, aligned);
+                aligned_mesh.mesh
+            }
+            None => meshes.add(aligned),
+        };
+        if *mesh != handle {
+            *mesh = handle;
+        }
+        state.aligned_meshes.insert(
+            entity,
+            AlignedMesh {
+                mesh: handle,
+                node_size: node.size,
+                texture_size,
+                content_align: *content_align,
+            },
+        );
+    }
+}
+
+/// Returns the offset from the node's bottom left corner and the size of the largest rect with the texture's aspect
+/// ratio that fits the node
+fn fit_image(node_size: Vec2, texture_size: Vec2, content_align: &ContentAlign) -> (Vec2, Vec2) {
+    if texture_size.x() <= 0.0 || texture_size.y() <= 0.0 {
+        return (Vec2::zero(), node_size);
+    }
+
+    let scale = (node_size.x() / texture_size.x()).min(node_size.y() / texture_size.y());
+    // keep the node's size along the axis that limits the image, so rounding doesn't make it differ
+    let size = if node_size.x() / texture_size.x() <= node_size.y() / texture_size.y() {
+        Vec2::new(node_size.x(), texture_size.y() * scale)
+    } else {
+        Vec2::new(texture_size.x() * scale, node_size.y())
+    };
+    (content_align.offset(node_size, size), size)
+}
+
+/// Builds a quad that covers `size` at `offset` from the node's bottom left corner. Like the default quad, the mesh is
+/// 1x1 and the ui shader scales it by the node's size.
+fn aligned_quad(node_size: Vec2, offset: Vec2, size: Vec2) -> Mesh {
+    let node_size = node_size.max(Vec2::one());
+    let min = offset / node_size - Vec2::new(0.5, 0.5);
+    let max = (offset + size) / node_size - Vec2::new(0.5, 0.5);
+    // texture coordinates start at the top
+    let vertices = [
+        ([min.x(), min.y(), 0.0], [0.0, 1.0]),
+        ([min.x(), max.y(), 0.0], [0.0, 0.0]),
+        ([max.x(), max.y(), 0.0], [1.0, 0.0]),
+        ([max.x(), min.y(), 0.0], [1.0, 1.0]),
+    ];
+
+    Mesh {
+        primitive_topology: PrimitiveTopology::TriangleList,
+        attributes: vec![
+            VertexAttribute::position(vertices.iter().map(|(position, _)| *position).collect()),
+            VertexAttribute::normal(vec![[0.0, 0.0, 1.0]; 4]),
+            VertexAttribute::uv(vertices.iter().map(|(_, uv)| *uv).collect()),
+        ],
+        indices: Some(vec![0, 2, 1, 0, 3, 2]),
+    }
+}
+
+/// How a node's texture is fit to the node's size
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageMode {
+    /// The texture is stretched to the node's size
+    Stretch,
+    /// The texture is sliced into a 3x3 grid, so that its corners keep their size when the node is resized
+    Sliced(TextureSlicer),
+}
+
+impl Default for ImageMode {
+    fn default() -> Self {
+        ImageMode::Stretch
+    }
+}
+
+/// Slices a texture along its borders. Corners are drawn at their texture size, the top and bottom edges are resized
+/// horizontally, the left and right edges vertically, and the center in both directions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureSlicer {
+    /// The size of the texture's borders in texture pixels
+    pub border: Rect<f32>,
+    pub sides_scale_mode: SliceScaleMode,
+    pub center_scale_mode: SliceScaleMode,
+}
+
+impl TextureSlicer {
+    pub fn new(border: Rect<f32>) -> Self {
+        TextureSlicer {
+            border,
+            sides_scale_mode: SliceScaleMode::Stretch,
+            center_scale_mode: SliceScaleMode::Stretch,
+        }
+    }
+}
+
+/// How the edges and center of a [TextureSlicer] fill their part of the node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SliceScaleMode {
+    Stretch,
+    /// Repeats the slice at its texture size. The last repetition is cut off.
+    Tile,
+}
+
+struct SlicedMesh {
+    mesh: Handle<Mesh>,
+    node_size: Vec2,
+    texture_size: Vec2,
+    scale_factor: f32,
+    slicer: TextureSlicer,
+}
+
+#[derive(Default)]
+pub struct ImageSliceSystemState {
+    sliced_meshes: HashMap<Entity, SlicedMesh>,
+}
+
+/// Replaces the quad of [ImageMode::Sliced] nodes with a mesh of their slices, which is rebuilt when the node is resized
+pub fn image_slice_system(
+    mut state: Local<ImageSliceSystemState>,
+    flex_surface: Res<FlexSurface>,
+    materials: Res<Assets<ColorMaterial>>,
+    textures: Res<Assets<Texture>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query: Query<(
+        Entity,
+        &Node,
+        &ImageMode,
+        &Handle<ColorMaterial>,
+        &mut Handle<Mesh>,
+    )>,
+    mesh_query: Query<&mut Handle<Mesh>>,
+) {
+    // nodes that are no longer sliced go back to the default quad
+    for entity in query.removed::<ImageMode>().iter() {
+        if let Some(sliced_mesh) = state.sliced_meshes.remove(entity) {
+            meshes.remove(&sliced_mesh.mesh);
+            if let Ok(mut mesh) = mesh_query.get_mut::<Handle<Mesh>>(*entity) {
+                *mesh = QUAD_HANDLE;
+            }
+        }
+    }
+
+    for (entity, node, image_mode, material, mut mesh) in &mut query.iter() {
+        let slicer = match image_mode {
+            ImageMode::Sliced(slicer) => slicer,
+            ImageMode::Stretch => {
+                if let Some(sliced_mesh) = state.sliced_meshes.remove(&entity) {
+                    meshes.remove(&sliced_mesh.mesh);
+                    *mesh = QUAD_HANDLE;
+                }
+                continue;
+            }
+        };
+        let texture_size = match materials
+            .get(&material)
+            .and_then(|material| material.texture)
+            .and_then(|texture_handle| textures.get(&texture_handle))
+        {
+            Some(texture) => texture.size,
+            None => continue,
+        };
+        let scale_factor = flex_surface.scale_factor(entity) as f32;
+
+        if let Some(sliced_mesh) = state.sliced_meshes.get(&entity) {
+            if sliced_mesh.node_size == node.size
+                && sliced_mesh.texture_size == texture_size
+                && sliced_mesh.scale_factor == scale_factor
+                && sliced_mesh.slicer == *slicer
+                && *mesh == sliced_mesh.mesh
+            {
+                continue;
+            }
+        }
+
+        let sliced = slice_mesh(node.size, texture_size, slicer, scale_factor);
+        let handle = match state.sliced_meshes.get(&entity) {
+            Some(sliced_mesh) => {
+                meshes.set(sliced_mesh.mesh, sliced);
+                sliced_mesh.mesh
+            }
+            None => meshes.add(sliced),
+        };
+        if *mesh != handle {
+            *mesh = handle;
+        }
+        state.sliced_meshes.insert(
+            entity,
+            SlicedMesh {
+                mesh: handle,
+                node_size: node.size,
+                texture_size,
+                scale_factor,
+                slicer: slicer.clone(),
+            },
+        );
+    }
+}
+
+/// A span of a slice along one axis: (start, end) in node pixels and (start, end) in texture coordinates
+type SliceSpan = (f32, f32, f32, f32);
+
+/// Builds a mesh of the texture's slices. Like the default quad, the mesh is 1x1 and the ui shader scales it by the
+/// node's size, so the mesh depends on the node's size. Borders shrink to fit nodes smaller than the borders.
+fn slice_mesh(
+    node_size: Vec2,
+    texture_size: Vec2,
+    slicer: &TextureSlicer,
+    scale_factor: f32,
+) -> Mesh {
+    let border = &slicer.border;
+    // spans along each axis: the start border, the middle, and the end border
+    let axis_spans = |node_length: f32, texture_length: f32, start: f32, end: f32| {
+        let start = start.max(0.0).min(texture_length);
+        let end = end.max(0.0).min(texture_length - start);
+        let mut node_start = start * scale_factor;
+        let mut node_end = end * scale_factor;
+        if node_start + node_end > node_length {
+            let shrink = node_length / (node_start + node_end);
+            node_start *= shrink;
+            node_end *= shrink;
+        }
+        let middle_start = start / texture_length;
+        let middle_end = 1.0 - end / texture_length;
+        let tile_length = (texture_length - start - end) * scale_factor;
+        (
+            [
+                (0.0, node_start, 0.0, middle_start),
+                (node_start, node_length - node_end, middle_start, middle_end),
+                (node_length - node_end, node_length, middle_end, 1.0),
+            ],
+            tile_length,
+        )
+    };
+    let (columns, column_tile_length) =
+        axis_spans(node_size.x(), texture_size.x(), border.left, border.right);
+    // texture coordinates start at the top
+    let (rows, row_tile_length) =
+        axis_spans(node_size.y(), texture_size.y(), border.top, border.bottom);
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        for (column_index, column) in columns.iter().enumerate() {
+            let scale_mode = match (row_index, column_index) {
+                (1, 1) => slicer.center_scale_mode,
+                (1, _) | (_, 1) => slicer.sides_scale_mode,
+                _ => SliceScaleMode::Stretch,
+            };
+            let tile = scale_mode == SliceScaleMode::Tile;
+            let column_spans = tile_span(
+                *column,
+                if tile && column_index == 1 {
+                    column_tile_length
+                } else {
+                    0.0
+                },
+            );
+            let row_spans = tile_span(
+                flip_span(*row, node_size.y()),
+                if tile && row_index == 1 {
+                    row_tile_length
+                } else {
+                    0.0
+                },
+            );
+            for (x_start, x_end, u_start, u_end) in column_spans.iter() {
+                for (y_start, y_end, v_start, v_end) in row_spans.iter() {
+                    let first_index = positions.len() as u32;
+                    for (x, y, u, v) in [
+                        (x_start, y_start, u_start, v_start),
+                        (x_start, y_end, u_start, v_end),
+                        (x_end, y_end, u_end, v_end),
+                        (x_end, y_start, u_end, v_start),
+                    ]
+                    .iter()
+                    {
+                        positions.push([
+                            *x / node_size.x().max(1.0) - 0.5,
+                            *y / node_size.y().max(1.0) - 0.5,
+                            0.0,
+                        ]);
+                        normals.push([0.0, 0.0, 1.0]);
+                        uvs.push([**u, **v]);
+                    }
+                    indices.extend([0, 2, 1, 0, 3, 2].iter().map(|index| first_index + index));
+                }
+            }
+        }
+    }
+
+    Mesh {
+        primitive_topology: PrimitiveTopology::TriangleList,
+        attributes: vec![
+            VertexAttribute::position(positions),
+            VertexAttribute::normal(normals),
+            VertexAttribute::uv(uvs),
+        ],
+        indices: Some(indices),
+    }
+}
+
+/// Converts a span that starts at the top of the node to one that starts at the bottom, keeping its texture coordinates
+fn flip_span(span: SliceSpan, node_length: f32) -> SliceSpan {
+    let (start, end, uv_start, uv_end) = span;
+    (node_length - end, node_length - start, uv_end, uv_start)
+}
+
+/// Splits a span into tiles of `tile_length` node pixels, cutting off the last tile. Spans are not split if
+/// `tile_length` is 0. Empty spans have no tiles.
+fn tile_span(span: SliceSpan, tile_length: f32) -> Vec<SliceSpan> {
+    let (start, end, uv_start, uv_end) = span;
+    if end - start <= 0.0 {
+        return Vec::new();
+    }
+    if tile_length <= 0.0 {
+        return vec![span];
+    }
+
+    let mut tiles = Vec::new();
+    let mut tile_start = start;
+    while tile_start < end {
+        let tile_end = (tile_start + tile_length).min(end);
+        let uv_tile_end = uv_start + (uv_end - uv_start) * (tile_end - tile_start) / tile_length;
+        tiles.push((tile_start, tile_end, uv_start, uv_tile_end));
+        tile_start = tile_end;
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aligned_quad, fit_image, slice_mesh, SliceScaleMode, TextureSlicer};
+    use crate::{ContentAlign, HorizontalAlign, VerticalAlign};
+    use bevy_math::{Rect, Vec2};
+    use bevy_render::mesh::VertexAttributeValues;
+
+    fn positions_and_uvs(mesh: &bevy_render::mesh::Mesh) -> (Vec<[f32; 3]>, Vec<[f32; 2]>) {
+        let positions = match &mesh.attributes[0].values {
+            VertexAttributeValues::Float3(positions) => positions.clone(),
+            _ => panic!("expected positions"),
+        };
+        let uvs = match &mesh.attributes[2].values {
+            VertexAttributeValues::Float2(uvs) => uvs.clone(),
+            _ => panic!("expected uvs"),
+        };
+        (positions, uvs)
+    }
+
+    #[test]
+    fn stretched_slices() {
+        let mesh = slice_mesh(
+            Vec2::new(100.0, 50.0),
+            Vec2::new(40.0, 40.0),
+            &TextureSlicer::new(Rect::all(10.0)),
+            1.0,
+        );
+        let (positions, uvs) = positions_and_uvs(&mesh);
+        assert_eq!(positions.len(), 9 * 4);
+        assert_eq!(mesh.indices.as_ref().unwrap().len(), 9 * 6);
+
+        // the first slice is the top left corner, which keeps its texture size
+        assert_eq!(
+            &positions[0..4],
+            &[
+                [-0.5, 0.3, 0.0],
+                [-0.5, 0.5, 0.0],
+                [-0.4, 0.5, 0.0],
+                [-0.4, 0.3, 0.0]
+            ]
+        );
+        assert_eq!(
+            &uvs[0..4],
+            &[[0.0, 0.25], [0.0, 0.0], [0.25, 0.0], [0.25, 0.25]]
+        );
+    }
+
+    #[test]
+    fn tiled_slices() {
+        let mut slicer = TextureSlicer::new(Rect::all(10.0));
+        slicer.sides_scale_mode = SliceScaleMode::Tile;
+        // at a scale factor of 2, the 20 pixel middle of the texture is 40 pixels wide, so the 160 pixel wide top and
+        // bottom edges have 4 tiles and the 40 pixel tall side edges have 1
+        let mesh = slice_mesh(Vec2::new(200.0, 80.0), Vec2::new(40.0, 40.0), &slicer, 2.0);
+        let (positions, uvs) = positions_and_uvs(&mesh);
+        assert_eq!(positions.len(), (4 + 4 + 1 + 1 + 1 + 4) * 4);
+
+        // the last tile of the top edge is a full tile
+        assert_eq!(&uvs[4 * 4..5 * 4], &uvs[4..8]);
+    }
+
+    #[test]
+    fn aligned_images() {
+        // a square texture in a wide node is as tall as the node
+        let top_right = ContentAlign {
+            horizontal: HorizontalAlign::Right,
+            vertical: VerticalAlign::Top,
+        };
+        let (offset, size) = fit_image(Vec2::new(200.0, 100.0), Vec2::new(20.0, 20.0), &top_right);
+        assert_eq!(size, Vec2::new(100.0, 100.0));
+        assert_eq!(offset, Vec2::new(100.0, 0.0));
+
+        // a wide texture in a square node is centered vertically
+        let (offset, size) = fit_image(
+            Vec2::new(100.0, 100.0),
+            Vec2::new(40.0, 10.0),
+            &ContentAlign::center(),
+        );
+        assert_eq!(size, Vec2::new(100.0, 25.0));
+        assert_eq!(offset, Vec2::new(0.0, 37.5));
+
+        // textures with the node's aspect ratio fill the node
+        let (_, size) = fit_image(
+            Vec2::new(90.0, 30.0),
+            Vec2::new(30.0, 10.0),
+            &ContentAlign::default(),
+        );
+        assert_eq!(size, Vec2::new(90.0, 30.0));
+
+        let mesh = aligned_quad(
+            Vec2::new(200.0, 100.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+        );
+        let (positions, uvs) = positions_and_uvs(&mesh);
+        assert_eq!(
+            positions,
+            vec![
+                [0.0, -0.5, 0.0],
+                [0.0, 0.5, 0.0],
+                [0.5, 0.5, 0.0],
+                [0.5, -0.5, 0.0]
+            ]
+        );
+        assert_eq!(uvs, vec![[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]);
+    }
+}
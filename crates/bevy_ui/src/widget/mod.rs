@@ -1,7 +1,11 @@
 mod button;
+mod fill;
 mod image;
 mod text;
+mod text_editor;
 
 pub use button::*;
+pub use fill::*;
 pub use image::*;
 pub use text::*;
+pub use text_editor::*;
@@ -1,51 +1,139 @@
-use crate::{CalculatedSize, Node};
+use crate::{CalculatedSize, ContentAlign, FlexSurface, Node, UiScale};
 use bevy_asset::{Assets, Handle};
-use bevy_ecs::{Changed, Query, Res, ResMut};
-use bevy_math::{Size, Vec3};
+use bevy_ecs::{Changed, Entity, Local, Query, Res, ResMut};
+use bevy_math::{Size, Vec2, Vec3};
 use bevy_render::{
     draw::{Draw, DrawContext, Drawable},
+    prelude::Msaa,
     renderer::{AssetRenderResourceBindings, RenderResourceBindings},
-    texture::Texture, prelude::Msaa,
+    texture::Texture,
 };
 use bevy_sprite::TextureAtlas;
-use bevy_text::{DrawableText, Font, FontAtlasSet, TextStyle};
+use bevy_text::{wrap_text, DrawableText, Font, FontAtlasSet, TextStyle};
 use bevy_transform::prelude::Transform;
+use bevy_window::Windows;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct Text {
     pub value: String,
     pub font: Handle<Font>,
     pub style: TextStyle,
+    pub alignment: TextAlignment,
 }
 
+/// Alignment of text within its node. `horizontal` aligns each line and `vertical` aligns the lines as a whole.
+pub type TextAlignment = ContentAlign;
+
+/// Stretch rounds layouts to whole pixels, so text is only wrapped once its node is narrower than the text by more than
+/// this many logical pixels
+const WRAP_TOLERANCE: f32 = 1.0;
+
+#[derive(Default)]
+pub struct TextSystemState {
+    scale_factor: Option<f64>,
+    /// The layout width each text node was last wrapped at
+    wrap_widths: HashMap<Entity, Option<f32>>,
+}
+
+/// Measures text in logical pixels. Glyphs are rasterized at the ui scale factor, so text is re-measured when it changes.
+/// Text is wrapped at the width of its node's layout, and re-measured when that width changes.
+// TODO: use the scale factor of the window each text node is laid out in instead of the primary window's
 pub fn text_system(
+    mut state: Local<TextSystemState>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    flex_surface: Res<FlexSurface>,
     mut textures: ResMut<Assets<Texture>>,
     fonts: Res<Assets<Font>>,
     mut font_atlas_sets: ResMut<Assets<FontAtlasSet>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut query: Query<(Changed<Text>, &mut CalculatedSize)>,
+    mut changed_query: Query<(Entity, Changed<Text>)>,
+    mut query: Query<(Entity, &Text, &mut CalculatedSize)>,
 ) {
-    for (text, mut calculated_size) in &mut query.iter() {
-        let font_atlases = font_atlas_sets
-            .get_or_insert_with(Handle::from_id(text.font.id), || {
-                FontAtlasSet::new(text.font)
-            });
-        // TODO: this call results in one or more TextureAtlases, whose render resources are created in the RENDER_GRAPH_SYSTEMS
-        // stage. That logic runs _before_ the DRAW stage, which means we cant call add_glyphs_to_atlas in the draw stage
-        // without our render resources being a frame behind. Therefore glyph atlasing either needs its own system or the TextureAtlas
-        // resource generation needs to happen AFTER the render graph systems. maybe draw systems should execute within the
-        // render graph so ordering like this can be taken into account? Maybe the RENDER_GRAPH_SYSTEMS stage should be removed entirely
-        // in favor of node.update()? Regardless, in the immediate short term the current approach is fine.
-        let width = font_atlases.add_glyphs_to_atlas(
+    let scale_factor = ui_scale.primary_scale_factor(&windows);
+    let scale_factor_changed = state.scale_factor != Some(scale_factor);
+    state.scale_factor = Some(scale_factor);
+
+    let changed_text = (&mut changed_query.iter())
+        .iter()
+        .map(|(entity, _text)| entity)
+        .collect::<HashSet<Entity>>();
+    let mut wrap_widths = HashMap::new();
+    for (entity, text, mut calculated_size) in &mut query.iter() {
+        let wrap_width = flex_surface
+            .get_layout(entity)
+            .ok()
+            .map(|layout| layout.size.width);
+        wrap_widths.insert(entity, wrap_width);
+        if !scale_factor_changed
+            && !changed_text.contains(&entity)
+            && state.wrap_widths.get(&entity) == Some(&wrap_width)
+        {
+            continue;
+        }
+
+        let size = measure_text(
+            &text,
+            wrap_width,
+            scale_factor as f32,
             &fonts,
+            &mut font_atlas_sets,
             &mut texture_atlases,
             &mut textures,
-            text.style.font_size,
-            &text.value,
         );
-
-        calculated_size.size = Size::new(width, text.style.font_size);
+        // only write on change, as a changed CalculatedSize triggers a relayout
+        if calculated_size.size != size || !calculated_size.wraps {
+            calculated_size.size = size;
+            calculated_size.wraps = true;
+        }
     }
+    state.wrap_widths = wrap_widths;
+}
+
+/// Returns the unwrapped width of the text and its height when wrapped at `wrap_width`
+fn measure_text(
+    text: &Text,
+    wrap_width: Option<f32>,
+    scale_factor: f32,
+    fonts: &Assets<Font>,
+    font_atlas_sets: &mut Assets<FontAtlasSet>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    textures: &mut Assets<Texture>,
+) -> Size {
+    let font_atlases = font_atlas_sets.get_or_insert_with(Handle::from_id(text.font.id), || {
+        FontAtlasSet::new(text.font)
+    });
+    // TODO: this call results in one or more TextureAtlases, whose render resources are created in the RENDER_GRAPH_SYSTEMS
+    // stage. That logic runs _before_ the DRAW stage, which means we cant call add_glyphs_to_atlas in the draw stage
+    // without our render resources being a frame behind. Therefore glyph atlasing either needs its own system or the TextureAtlas
+    // resource generation needs to happen AFTER the render graph systems. maybe draw systems should execute within the
+    // render graph so ordering like this can be taken into account? Maybe the RENDER_GRAPH_SYSTEMS stage should be removed entirely
+    // in favor of node.update()? Regardless, in the immediate short term the current approach is fine.
+    font_atlases.add_glyphs_to_atlas(
+        fonts,
+        texture_atlases,
+        textures,
+        text.style.font_size * scale_factor,
+        &text.value,
+    );
+
+    let font = fonts.get(&text.font).unwrap();
+    let font_size = text.style.font_size * scale_factor;
+    let width = wrap_text(font, font_size, &text.value, None)
+        .iter()
+        .fold(0.0, |width: f32, line| width.max(line.width));
+    let lines = wrap_text(
+        font,
+        font_size,
+        &text.value,
+        wrap_width.map(|wrap_width| (wrap_width + WRAP_TOLERANCE) * scale_factor),
+    );
+
+    Size::new(
+        width / scale_factor,
+        lines.len() as f32 * text.style.font_size,
+    )
 }
 
 pub fn draw_text_system(
@@ -56,13 +144,55 @@ pub fn draw_text_system(
     texture_atlases: Res<Assets<TextureAtlas>>,
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
     mut query: Query<(&mut Draw, &Text, &Node, &Transform)>,
 ) {
+    let scale_factor = ui_scale.primary_scale_factor(&windows) as f32;
     for (mut draw, text, node, transform) in &mut query.iter() {
-        let position = Vec3::from(transform.value.w_axis().truncate()) - (node.size / 2.0).extend(0.0);
+        let font = fonts.get(&text.font).unwrap();
+        // nodes are sized in physical pixels, so text is wrapped and drawn in physical pixels
+        let font_size = text.style.font_size * scale_factor;
+        let mut lines = wrap_text(
+            font,
+            font_size,
+            &text.value,
+            Some(node.size.x() + WRAP_TOLERANCE * scale_factor),
+        );
+        let text_size = Vec2::new(
+            lines
+                .iter()
+                .fold(0.0, |width: f32, line| width.max(line.width)),
+            lines.len() as f32 * font_size,
+        );
+        for line in lines.iter_mut() {
+            let line_offset = text
+                .alignment
+                .offset(node.size, Vec2::new(line.width, text_size.y()));
+            line.position.set_x(line_offset.x());
+        }
+        let text_offset = text.alignment.offset(node.size, text_size);
+        let position = Vec3::from(transform.value.w_axis().truncate())
+            - (node.size / 2.0).extend(0.0)
+            + Vec2::new(0.0, text_offset.y()).extend(0.0);
+
+        // text is drawn with the sprite pipeline, which doesn't support clipping, so text is only hidden once it is
+        // entirely outside of its clip rect
+        let min_x = position.x() + text_offset.x();
+        if min_x + text_size.x() < node.clip.x()
+            || position.y() + text_size.y() < node.clip.y()
+            || min_x > node.clip.z()
+            || position.y() > node.clip.w()
+        {
+            continue;
+        }
+
+        let mut style = text.style.clone();
+        style.color.a *= node.opacity;
+        style.font_size = font_size;
 
         let mut drawable_text = DrawableText {
-            font: fonts.get(&text.font).unwrap(),
+            font,
             font_atlas_set: font_atlas_sets
                 .get(&text.font.as_handle::<FontAtlasSet>())
                 .unwrap(),
@@ -71,8 +201,9 @@ pub fn draw_text_system(
             asset_render_resource_bindings: &mut asset_render_resource_bindings,
             position,
             msaa: &msaa,
-            style: &text.style,
+            style: &style,
             text: &text.value,
+            lines: &lines,
             container_size: node.size,
         };
         drawable_text.draw(&mut draw, &mut draw_context).unwrap();
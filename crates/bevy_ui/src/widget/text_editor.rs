@@ -0,0 +1,808 @@
+use crate::{
+    entity::NodeComponents, Display, HitTest, Interaction, Node, PositionType, Style, UiScale, Val,
+};
+use bevy_app::{EventReader, Events};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{Commands, Entity, Local, Query, Res, ResMut};
+use bevy_input::{
+    keyboard::{ElementState, KeyCode, KeyboardInput},
+    mouse::{MouseButton, MouseScrollUnit, MouseWheel},
+    Input,
+};
+use bevy_math::{Rect, Size, Vec2, Vec3};
+use bevy_render::{
+    draw::{Draw, DrawContext, Drawable},
+    prelude::Msaa,
+    renderer::{AssetRenderResourceBindings, RenderResourceBindings},
+    texture::Texture,
+};
+use bevy_sprite::{ColorMaterial, TextureAtlas};
+use bevy_text::{text_width, wrap_text, DrawableText, Font, FontAtlasSet, TextLine, TextStyle};
+use bevy_transform::{hierarchy::BuildChildren, prelude::Transform};
+use bevy_window::{CursorMoved, ReceivedCharacter, Windows};
+use std::ops::Range;
+
+/// Text cut or copied from a [TextEditor]. The clipboard is shared by the editors of the app, but not with other
+/// applications.
+#[derive(Debug, Clone, Default)]
+pub struct UiClipboard {
+    pub contents: String,
+}
+
+/// A multi-line text editor, ex: for consoles, chat boxes, and description fields. Clicking the editor focuses it and
+/// places the caret, dragging selects text, and clicking anything else removes the focus. While it is focused, typed
+/// characters are inserted at the caret and these keys edit the text:
+///
+/// * arrow keys, Home, End, PageUp and PageDown move the caret, and select text while Shift is held. Ctrl+Home and
+///   Ctrl+End move it to the start and end of the text.
+/// * Backspace and Delete remove the selection or a character, and Return starts a new line
+/// * Ctrl+A selects everything, and Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste with the [UiClipboard]
+///
+/// Lines wrap between words at the width of the node. The editor shows as many whole lines as fit in the node, and
+/// scrolls to keep the caret visible or when the mouse wheel is turned over it.
+#[derive(Default)]
+pub struct TextEditor {
+    pub value: String,
+    pub font: Handle<Font>,
+    pub style: TextStyle,
+    pub focused: bool,
+    /// The material of the caret, which is shown while the editor is focused
+    pub caret_material: Handle<ColorMaterial>,
+    /// The material of the rects behind selected text. They are drawn over the text, so it should be translucent.
+    pub selection_material: Handle<ColorMaterial>,
+    /// The byte index of the caret in `value`
+    cursor: usize,
+    /// The byte index the selection extends from to the caret
+    selection_anchor: Option<usize>,
+    /// The first visible line
+    scroll_line: usize,
+    dragging: bool,
+    layout: TextEditorLayout,
+    caret: Option<Entity>,
+    selection_highlights: Vec<Entity>,
+}
+
+/// The lines of a [TextEditor], in physical pixels
+#[derive(Default)]
+struct TextEditorLayout {
+    value: String,
+    font_size: f32,
+    size: Vec2,
+    lines: Vec<TextLine>,
+}
+
+impl TextEditor {
+    pub fn new(value: impl Into<String>, font: Handle<Font>, style: TextStyle) -> Self {
+        TextEditor {
+            value: value.into(),
+            font,
+            style,
+            ..Default::default()
+        }
+    }
+
+    /// The byte index of the caret in `value`
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the caret to the byte index `position`. If `select` is true, the selection is extended to it.
+    pub fn set_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.cursor);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = floor_char_boundary(&self.value, position);
+    }
+
+    /// The byte range of the selected text, if any text is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = floor_char_boundary(&self.value, self.selection_anchor?);
+        let cursor = floor_char_boundary(&self.value, self.cursor);
+        if anchor == cursor {
+            None
+        } else {
+            Some(anchor.min(cursor)..anchor.max(cursor))
+        }
+    }
+
+    pub fn selected_text(&self) -> &str {
+        self.selection()
+            .map_or("", |selection| &self.value[selection])
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection_anchor = Some(0);
+        self.cursor = self.value.len();
+    }
+
+    /// Replaces the selection with `text`, or inserts it at the caret, and moves the caret after it
+    pub fn insert(&mut self, text: &str) {
+        let text = text.replace('\r', "");
+        let range = self.selection_or_cursor();
+        self.value.replace_range(range.clone(), &text);
+        self.cursor = range.start + text.len();
+        self.selection_anchor = None;
+    }
+
+    /// Removes the selection or the character before the caret
+    pub fn delete_backward(&mut self) {
+        if self.selection().is_none() {
+            self.cursor = floor_char_boundary(&self.value, self.cursor);
+            self.selection_anchor = Some(previous_char_boundary(&self.value, self.cursor));
+        }
+        self.insert("");
+    }
+
+    /// Removes the selection or the character after the caret
+    pub fn delete_forward(&mut self) {
+        if self.selection().is_none() {
+            self.cursor = floor_char_boundary(&self.value, self.cursor);
+            self.selection_anchor = Some(next_char_boundary(&self.value, self.cursor));
+        }
+        self.insert("");
+    }
+
+    /// Moves the caret one character to the left. Without `select`, the caret moves to the start of the selection
+    /// instead if there is one.
+    pub fn move_left(&mut self, select: bool) {
+        match self.selection() {
+            Some(selection) if !select => self.set_cursor(selection.start, false),
+            _ => self.set_cursor(previous_char_boundary(&self.value, self.cursor), select),
+        }
+    }
+
+    /// Moves the caret one character to the right. Without `select`, the caret moves to the end of the selection
+    /// instead if there is one.
+    pub fn move_right(&mut self, select: bool) {
+        match self.selection() {
+            Some(selection) if !select => self.set_cursor(selection.end, false),
+            _ => self.set_cursor(next_char_boundary(&self.value, self.cursor), select),
+        }
+    }
+
+    /// Copies the selection to the clipboard
+    pub fn copy(&self, clipboard: &mut UiClipboard) {
+        if self.selection().is_some() {
+            clipboard.contents = self.selected_text().to_string();
+        }
+    }
+
+    /// Copies the selection to the clipboard and removes it
+    pub fn cut(&mut self, clipboard: &mut UiClipboard) {
+        if self.selection().is_some() {
+            self.copy(clipboard);
+            self.insert("");
+        }
+    }
+
+    pub fn paste(&mut self, clipboard: &UiClipboard) {
+        self.insert(&clipboard.contents);
+    }
+
+    /// Wraps the text at `size.x()` and fits lines of `font_size` into `size.y()`, both in physical pixels. The
+    /// editor lays itself out every frame, so this only has to be called to use line based methods right away.
+    pub fn layout(&mut self, font: &Font, font_size: f32, size: Vec2) {
+        if self.layout.font_size != font_size || self.layout.size != size {
+            self.layout.font_size = font_size;
+            self.layout.size = size;
+            self.layout.lines.clear();
+        }
+        self.update_layout(font);
+    }
+
+    /// The lines of the text, wrapped at the width of the last layout
+    pub fn lines(&self) -> &[TextLine] {
+        &self.layout.lines
+    }
+
+    /// The index of the first visible line
+    pub fn scroll_line(&self) -> usize {
+        self.scroll_line
+    }
+
+    /// The lines that fit in the editor, starting at the scroll position
+    pub fn visible_lines(&self) -> Range<usize> {
+        let line_count = self.layout.lines.len();
+        let start = self.scroll_line.min(line_count);
+        start..(start + self.visible_line_count()).min(line_count)
+    }
+
+    /// The index of the line the caret is on
+    pub fn cursor_line(&self) -> usize {
+        self.line_at(self.cursor)
+    }
+
+    /// Moves the caret `delta` lines down, or up if `delta` is negative, keeping it close to its horizontal position.
+    /// Moving up from the first line moves the caret to the start of the text, and moving down from the last line
+    /// moves it to the end.
+    pub fn move_lines(&mut self, font: &Font, delta: isize, select: bool) {
+        self.update_layout(font);
+        if self.layout.lines.is_empty() {
+            return;
+        }
+        let line = self.cursor_line();
+        let target = line as isize + delta;
+        let position = if target < 0 {
+            0
+        } else if target as usize >= self.layout.lines.len() {
+            self.value.len()
+        } else {
+            let x = self.caret_x(font, line, self.cursor);
+            self.position_in_line(font, target as usize, x)
+        };
+        self.set_cursor(position, select);
+        self.scroll_to_cursor();
+    }
+
+    /// Moves the caret to the start of its line
+    pub fn move_line_start(&mut self, font: &Font, select: bool) {
+        self.update_layout(font);
+        if let Some(line) = self.layout.lines.get(self.cursor_line()) {
+            let start = line.range.start;
+            self.set_cursor(start, select);
+        }
+    }
+
+    /// Moves the caret to the end of its line
+    pub fn move_line_end(&mut self, font: &Font, select: bool) {
+        self.update_layout(font);
+        if let Some(line) = self.layout.lines.get(self.cursor_line()) {
+            let end = line.range.end;
+            self.set_cursor(end, select);
+        }
+    }
+
+    /// The byte index closest to `point`, which is in physical pixels from the top left corner of the editor with y
+    /// pointing down
+    pub fn position_at(&mut self, font: &Font, point: Vec2) -> usize {
+        self.update_layout(font);
+        if self.layout.lines.is_empty() || self.layout.font_size <= 0.0 {
+            return 0;
+        }
+        let line = self.scroll_line + (point.y().max(0.0) / self.layout.font_size) as usize;
+        let line = line.min(self.layout.lines.len() - 1);
+        self.position_in_line(font, line, point.x())
+    }
+
+    /// Scrolls by `delta` lines, without scrolling past the last line
+    pub fn scroll(&mut self, delta: isize) {
+        let scroll_line = (self.scroll_line as isize + delta).max(0) as usize;
+        self.scroll_line = scroll_line.min(self.max_scroll_line());
+    }
+
+    /// Scrolls just far enough to show the line of the caret
+    pub fn scroll_to_cursor(&mut self) {
+        let line = self.cursor_line();
+        let visible_line_count = self.visible_line_count();
+        if line < self.scroll_line {
+            self.scroll_line = line;
+        } else if line >= self.scroll_line + visible_line_count {
+            self.scroll_line = line + 1 - visible_line_count;
+        }
+        self.scroll_line = self.scroll_line.min(self.max_scroll_line());
+    }
+
+    fn update_layout(&mut self, font: &Font) {
+        self.cursor = floor_char_boundary(&self.value, self.cursor);
+        if !self.layout.lines.is_empty() && self.layout.value == self.value {
+            return;
+        }
+        self.layout.value = self.value.clone();
+        self.layout.lines = wrap_text(
+            font,
+            self.layout.font_size,
+            &self.value,
+            Some(self.layout.size.x()),
+        );
+        self.scroll_line = self.scroll_line.min(self.max_scroll_line());
+    }
+
+    fn visible_line_count(&self) -> usize {
+        if self.layout.font_size <= 0.0 {
+            return 1;
+        }
+        ((self.layout.size.y() / self.layout.font_size) as usize).max(1)
+    }
+
+    fn max_scroll_line(&self) -> usize {
+        self.layout
+            .lines
+            .len()
+            .saturating_sub(self.visible_line_count())
+    }
+
+    fn selection_or_cursor(&self) -> Range<usize> {
+        self.selection().unwrap_or_else(|| {
+            let cursor = floor_char_boundary(&self.value, self.cursor);
+            cursor..cursor
+        })
+    }
+
+    /// The index of the line `position` is on. Positions in the whitespace a line was wrapped at belong to the line
+    /// before the break.
+    fn line_at(&self, position: usize) -> usize {
+        self.layout
+            .lines
+            .iter()
+            .rposition(|line| line.range.start <= position)
+            .unwrap_or(0)
+    }
+
+    /// The distance of `position` from the start of `line`, in physical pixels
+    fn caret_x(&self, font: &Font, line: usize, position: usize) -> f32 {
+        let start = self.layout.lines[line].range.start;
+        if position <= start {
+            return 0.0;
+        }
+        text_width(font, self.layout.font_size, &self.value[start..position])
+    }
+
+    /// The byte index in `line` closest to `x`
+    fn position_in_line(&self, font: &Font, line: usize, x: f32) -> usize {
+        let range = self.layout.lines[line].range.clone();
+        let positions = self.value[range.clone()]
+            .char_indices()
+            .map(|(index, _)| range.start + index)
+            .chain(std::iter::once(range.end));
+        let mut closest = (range.start, std::f32::MAX);
+        for position in positions {
+            let distance = (self.caret_x(font, line, position) - x).abs();
+            if distance < closest.1 {
+                closest = (position, distance);
+            }
+        }
+        closest.0
+    }
+
+    /// The rects of the caret and the selection on visible lines, as (top left corner, size) in physical pixels from
+    /// the top left corner of the editor
+    fn highlight_rects(
+        &self,
+        font: &Font,
+        caret_width: f32,
+    ) -> (Option<(Vec2, Vec2)>, Vec<(Vec2, Vec2)>) {
+        let font_size = self.layout.font_size;
+        let visible_lines = self.visible_lines();
+        let line_top = |line: usize| (line - visible_lines.start) as f32 * font_size;
+
+        let cursor_line = self.cursor_line();
+        let caret = if self.focused && visible_lines.contains(&cursor_line) {
+            let x = self.caret_x(font, cursor_line, self.cursor);
+            Some((
+                Vec2::new(x, line_top(cursor_line)),
+                Vec2::new(caret_width, font_size),
+            ))
+        } else {
+            None
+        };
+
+        let mut selection_rects = Vec::new();
+        if let Some(selection) = self.selection() {
+            for line in visible_lines.clone() {
+                let range = self.layout.lines[line].range.clone();
+                // the whitespace or newline after a line belongs to it
+                let end = self
+                    .layout
+                    .lines
+                    .get(line + 1)
+                    .map_or(self.value.len(), |next_line| next_line.range.start);
+                if selection.start >= end.max(range.start + 1) || selection.end <= range.start {
+                    continue;
+                }
+                let min_x = self.caret_x(font, line, selection.start.max(range.start));
+                let mut max_x = self.caret_x(font, line, selection.end.min(range.end));
+                if selection.end > range.end && end > range.end {
+                    // show that the line break is selected
+                    max_x += font_size / 4.0;
+                }
+                selection_rects.push((
+                    Vec2::new(min_x, line_top(line)),
+                    Vec2::new(max_x - min_x, font_size),
+                ));
+            }
+        }
+        (caret, selection_rects)
+    }
+}
+
+fn floor_char_boundary(text: &str, position: usize) -> usize {
+    let mut position = position.min(text.len());
+    while !text.is_char_boundary(position) {
+        position -= 1;
+    }
+    position
+}
+
+fn previous_char_boundary(text: &str, position: usize) -> usize {
+    text[..position]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+fn next_char_boundary(text: &str, position: usize) -> usize {
+    text[position..]
+        .chars()
+        .next()
+        .map_or(position, |character| position + character.len_utf8())
+}
+
+#[derive(Default)]
+pub struct TextEditorState {
+    cursor_moved_event_reader: EventReader<CursorMoved>,
+    mouse_wheel_event_reader: EventReader<MouseWheel>,
+    keyboard_event_reader: EventReader<KeyboardInput>,
+    character_event_reader: EventReader<ReceivedCharacter>,
+    cursor_position: Vec2,
+}
+
+/// Focuses [TextEditor]s, applies typed characters, keys, clicks and scrolling to them, and lays out their lines
+pub fn text_editor_system(
+    mut state: Local<TextEditorState>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    fonts: Res<Assets<Font>>,
+    mut font_atlas_sets: ResMut<Assets<FontAtlasSet>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut clipboard: ResMut<UiClipboard>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor_moved_events: Res<Events<CursorMoved>>,
+    mouse_wheel_events: Res<Events<MouseWheel>>,
+    keyboard_events: Res<Events<KeyboardInput>>,
+    character_events: Res<Events<ReceivedCharacter>>,
+    mut editor_query: Query<(&mut TextEditor, &Node, &Transform, &Interaction)>,
+) {
+    if let Some(cursor_moved) = state.cursor_moved_event_reader.latest(&cursor_moved_events) {
+        state.cursor_position = cursor_moved.position;
+    }
+    let mut scroll = 0.0;
+    for event in state.mouse_wheel_event_reader.iter(&mouse_wheel_events) {
+        scroll += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / crate::SCROLL_LINE_HEIGHT,
+        };
+    }
+    // characters are applied before keys, as a frame's keys usually end the typing that came before them
+    let characters = state
+        .character_event_reader
+        .iter(&character_events)
+        .map(|event| event.char)
+        .filter(|character| !character.is_control())
+        .collect::<String>();
+    let keys = state
+        .keyboard_event_reader
+        .iter(&keyboard_events)
+        .filter(|event| event.state == ElementState::Pressed)
+        .filter_map(|event| event.key_code)
+        .collect::<Vec<_>>();
+    let shift = keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
+    let control = keyboard_input.pressed(KeyCode::LControl)
+        || keyboard_input.pressed(KeyCode::RControl)
+        || keyboard_input.pressed(KeyCode::LWin)
+        || keyboard_input.pressed(KeyCode::RWin);
+
+    let scale_factor = ui_scale.primary_scale_factor(&windows) as f32;
+    let cursor_position = state.cursor_position;
+    for (mut editor, node, transform, interaction) in &mut editor_query.iter() {
+        let font = match fonts.get(&editor.font) {
+            Some(font) => font,
+            None => continue,
+        };
+        let font_size = editor.style.font_size * scale_factor;
+        let glyphs_changed = editor.layout.value != editor.value
+            || editor.layout.font_size != font_size
+            || editor.layout.lines.is_empty();
+        editor.layout(font, font_size, node.size);
+        if glyphs_changed {
+            add_glyphs(
+                &editor,
+                &fonts,
+                &mut font_atlas_sets,
+                &mut texture_atlases,
+                &mut textures,
+            );
+        }
+
+        let top_left = transform.value.w_axis().truncate().truncate()
+            + Vec2::new(-node.size.x(), node.size.y()) / 2.0;
+        let point = Vec2::new(
+            cursor_position.x() - top_left.x(),
+            top_left.y() - cursor_position.y(),
+        );
+        if mouse_button_input.just_pressed(MouseButton::Left) {
+            editor.focused = *interaction == Interaction::Clicked;
+            editor.dragging = editor.focused;
+            if editor.focused {
+                let position = editor.position_at(font, point);
+                editor.set_cursor(position, shift);
+            }
+        } else if editor.dragging && mouse_button_input.pressed(MouseButton::Left) {
+            let position = editor.position_at(font, point);
+            editor.set_cursor(position, true);
+            editor.scroll_to_cursor();
+        } else {
+            editor.dragging = false;
+        }
+        if scroll != 0.0 && *interaction != Interaction::None {
+            editor.scroll(-scroll.round() as isize);
+        }
+
+        if !editor.focused || (characters.is_empty() && keys.is_empty()) {
+            continue;
+        }
+        if !control && !characters.is_empty() {
+            editor.insert(&characters);
+        }
+        let visible_line_count = editor.visible_lines().len().max(1) as isize;
+        for key in keys.iter() {
+            match key {
+                KeyCode::Left => editor.move_left(shift),
+                KeyCode::Right => editor.move_right(shift),
+                KeyCode::Up => editor.move_lines(font, -1, shift),
+                KeyCode::Down => editor.move_lines(font, 1, shift),
+                KeyCode::PageUp => editor.move_lines(font, -visible_line_count, shift),
+                KeyCode::PageDown => editor.move_lines(font, visible_line_count, shift),
+                KeyCode::Home if control => editor.set_cursor(0, shift),
+                KeyCode::End if control => {
+                    let end = editor.value.len();
+                    editor.set_cursor(end, shift);
+                }
+                KeyCode::Home => editor.move_line_start(font, shift),
+                KeyCode::End => editor.move_line_end(font, shift),
+                KeyCode::Back => editor.delete_backward(),
+                KeyCode::Delete => editor.delete_forward(),
+                KeyCode::Return | KeyCode::NumpadEnter => editor.insert("\n"),
+                KeyCode::A if control => editor.select_all(),
+                KeyCode::C if control => editor.copy(&mut clipboard),
+                KeyCode::X if control => editor.cut(&mut clipboard),
+                KeyCode::V if control => editor.paste(&clipboard),
+                _ => {}
+            }
+        }
+        if editor.layout.value != editor.value {
+            editor.update_layout(font);
+            add_glyphs(
+                &editor,
+                &fonts,
+                &mut font_atlas_sets,
+                &mut texture_atlases,
+                &mut textures,
+            );
+        }
+        editor.scroll_to_cursor();
+    }
+}
+
+fn add_glyphs(
+    editor: &TextEditor,
+    fonts: &Assets<Font>,
+    font_atlas_sets: &mut Assets<FontAtlasSet>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    textures: &mut Assets<Texture>,
+) {
+    let font_atlases = font_atlas_sets.get_or_insert_with(Handle::from_id(editor.font.id), || {
+        FontAtlasSet::new(editor.font)
+    });
+    font_atlases.add_glyphs_to_atlas(
+        fonts,
+        texture_atlases,
+        textures,
+        editor.layout.font_size,
+        &editor.value,
+    );
+}
+
+/// Positions the caret and selection nodes of [TextEditor]s, which are children of the editor
+pub fn text_editor_highlight_system(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    fonts: Res<Assets<Font>>,
+    mut editor_query: Query<(Entity, &mut TextEditor)>,
+    highlight_query: Query<&mut Style>,
+) {
+    let scale_factor = ui_scale.primary_scale_factor(&windows) as f32;
+    for (entity, mut editor) in &mut editor_query.iter() {
+        let font = match fonts.get(&editor.font) {
+            Some(font) => font,
+            None => continue,
+        };
+        let (caret, selection_rects) = editor.highlight_rects(font, scale_factor.max(1.0));
+
+        let caret_node = match editor.caret {
+            Some(caret_node) => caret_node,
+            None => {
+                let caret_node = spawn_highlight(&mut commands, entity, editor.caret_material);
+                editor.caret = Some(caret_node);
+                caret_node
+            }
+        };
+        set_highlight_style(&highlight_query, caret_node, caret, scale_factor);
+
+        while editor.selection_highlights.len() < selection_rects.len() {
+            let highlight = spawn_highlight(&mut commands, entity, editor.selection_material);
+            editor.selection_highlights.push(highlight);
+        }
+        for (index, highlight) in editor.selection_highlights.iter().enumerate() {
+            set_highlight_style(
+                &highlight_query,
+                *highlight,
+                selection_rects.get(index).cloned(),
+                scale_factor,
+            );
+        }
+    }
+}
+
+fn spawn_highlight(
+    commands: &mut Commands,
+    editor: Entity,
+    material: Handle<ColorMaterial>,
+) -> Entity {
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            material,
+            ..Default::default()
+        })
+        .with(HitTest::None);
+    let highlight = commands.current_entity().unwrap();
+    commands.push_children(editor, &[highlight]);
+    highlight
+}
+
+/// Shows the highlight node at `rect`, which is in physical pixels, or hides it
+fn set_highlight_style(
+    highlight_query: &Query<&mut Style>,
+    highlight: Entity,
+    rect: Option<(Vec2, Vec2)>,
+    scale_factor: f32,
+) {
+    let mut style = match highlight_query.get_mut::<Style>(highlight) {
+        Ok(style) => style,
+        // the node is spawned at the end of the stage
+        Err(_) => return,
+    };
+    let (display, position, size) = match rect {
+        Some((position, size)) => (
+            Display::Flex,
+            Rect {
+                left: Val::Px(position.x() / scale_factor),
+                top: Val::Px(position.y() / scale_factor),
+                ..Default::default()
+            },
+            Size::new(
+                Val::Px(size.x() / scale_factor),
+                Val::Px(size.y() / scale_factor),
+            ),
+        ),
+        None => (Display::None, style.position, style.size),
+    };
+    // avoid mutating unchanged styles, which would trigger a layout
+    if style.display != display || style.position != position || style.size != size {
+        style.display = display;
+        style.position = position;
+        style.size = size;
+    }
+}
+
+pub fn draw_text_editor_system(
+    mut draw_context: DrawContext,
+    fonts: Res<Assets<Font>>,
+    msaa: Res<Msaa>,
+    font_atlas_sets: Res<Assets<FontAtlasSet>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
+    mut query: Query<(&mut Draw, &TextEditor, &Node, &Transform)>,
+) {
+    for (mut draw, editor, node, transform) in &mut query.iter() {
+        let font = match fonts.get(&editor.font) {
+            Some(font) => font,
+            None => continue,
+        };
+        let font_atlas_set = match font_atlas_sets.get(&editor.font.as_handle::<FontAtlasSet>()) {
+            Some(font_atlas_set) => font_atlas_set,
+            None => continue,
+        };
+        let position =
+            Vec3::from(transform.value.w_axis().truncate()) - (node.size / 2.0).extend(0.0);
+        if position.x() + node.size.x() < node.clip.x()
+            || position.y() + node.size.y() < node.clip.y()
+            || position.x() > node.clip.z()
+            || position.y() > node.clip.w()
+        {
+            continue;
+        }
+
+        // only whole lines are drawn, from the top of the node
+        let font_size = editor.layout.font_size;
+        let lines = editor.layout.lines[editor.visible_lines()]
+            .iter()
+            .enumerate()
+            .map(|(index, line)| TextLine {
+                position: Vec2::new(0.0, node.size.y() - (index + 1) as f32 * font_size),
+                ..line.clone()
+            })
+            .collect::<Vec<_>>();
+
+        let mut style = editor.style.clone();
+        style.color.a *= node.opacity;
+        style.font_size = font_size;
+
+        let mut drawable_text = DrawableText {
+            font,
+            font_atlas_set,
+            texture_atlases: &texture_atlases,
+            render_resource_bindings: &mut render_resource_bindings,
+            asset_render_resource_bindings: &mut asset_render_resource_bindings,
+            position,
+            msaa: &msaa,
+            style: &style,
+            text: &editor.layout.value,
+            lines: &lines,
+            container_size: node.size,
+        };
+        drawable_text.draw(&mut draw, &mut draw_context).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextEditor, UiClipboard};
+    use bevy_math::Vec2;
+    use bevy_text::Font;
+
+    #[test]
+    fn edit_text() {
+        let font = Font::try_from_bytes(
+            include_bytes!("../../../../assets/fonts/FiraMono-Medium.ttf").to_vec(),
+        )
+        .unwrap();
+        let mut editor = TextEditor::default();
+        editor.insert("hello world\nsecond");
+        // FiraMono is monospaced, so this fits "hello" but not "hello world"
+        let char_width = bevy_text::text_width(&font, 10.0, "a");
+        editor.layout(&font, 10.0, Vec2::new(char_width * 8.0, 20.0));
+        editor.scroll_to_cursor();
+        assert_eq!(editor.lines().len(), 3);
+        assert_eq!(editor.visible_lines(), 1..3);
+        assert_eq!(editor.cursor_line(), 2);
+
+        // selections span lines
+        editor.move_lines(&font, -1, true);
+        assert_eq!(editor.selected_text(), "\nsecond");
+        editor.move_lines(&font, -1, true);
+        assert_eq!(editor.selected_text(), " world\nsecond");
+        assert_eq!(editor.visible_lines(), 0..2);
+        editor.move_line_start(&font, true);
+        assert_eq!(editor.selected_text(), "hello world\nsecond");
+
+        let mut clipboard = UiClipboard::default();
+        editor.move_right(false);
+        editor.move_lines(&font, -1, false);
+        editor.move_line_start(&font, true);
+        editor.cut(&mut clipboard);
+        assert_eq!(clipboard.contents, "world");
+        assert_eq!(editor.value, "hello \nsecond");
+        editor.move_left(false);
+        editor.paste(&clipboard);
+        assert_eq!(editor.value, "helloworld \nsecond");
+
+        editor.delete_backward();
+        editor.delete_forward();
+        assert_eq!(editor.value, "helloworl\nsecond");
+        let position = editor.position_at(&font, Vec2::new(char_width * 1.4, 15.0));
+        assert_eq!(&editor.value[position..], "econd");
+    }
+}
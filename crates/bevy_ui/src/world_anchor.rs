@@ -0,0 +1,295 @@
+use crate::{Display, Node, PositionType, Style, UiScale, Val};
+use bevy_ecs::{Entity, Query, Res};
+use bevy_math::{Mat4, Rect, Vec2, Vec3};
+use bevy_render::{
+    camera::{ActiveCameras, Camera},
+    render_graph::base,
+};
+use bevy_transform::prelude::{Parent, Transform};
+use bevy_window::Windows;
+
+/// What a [WorldAnchor] node does while its position is outside of the camera's view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffscreenMode {
+    /// Hides the node. Hidden nodes are shown again with [Display::Flex].
+    Hide,
+    /// Keeps the node on the edge of the screen, in the direction of the position
+    Clamp,
+    /// Like [OffscreenMode::Clamp], and shows the node's [WorldAnchorIndicator] children
+    Indicator,
+}
+
+/// Positions a root ui node over a point in the world, ex: a name tag above a character. The node is centered on the
+/// point, and its position type and position are overwritten every frame.
+#[derive(Debug, Clone)]
+pub struct WorldAnchor {
+    /// The world position the node follows. With `entity`, this is an offset from the entity's position.
+    pub position: Vec3,
+    /// An entity whose position the node follows
+    pub entity: Option<Entity>,
+    /// The name of the camera the position is projected with
+    pub camera: String,
+    pub offscreen: OffscreenMode,
+    /// The distance in logical pixels that nodes on the edge of the screen keep from it
+    pub margin: f32,
+    /// Whether the position is inside the camera's view. This is set by [world_anchor_system].
+    pub on_screen: bool,
+    /// A unit vector on the screen pointing from the center of the screen towards the position. This is set by
+    /// [world_anchor_system].
+    pub direction: Vec2,
+}
+
+impl WorldAnchor {
+    pub fn new(position: Vec3) -> Self {
+        WorldAnchor {
+            position,
+            ..Default::default()
+        }
+    }
+
+    /// Follows `entity`, offset by `offset`
+    pub fn follow(entity: Entity, offset: Vec3) -> Self {
+        WorldAnchor {
+            position: offset,
+            entity: Some(entity),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for WorldAnchor {
+    fn default() -> Self {
+        WorldAnchor {
+            position: Vec3::zero(),
+            entity: None,
+            camera: base::camera::CAMERA3D.to_string(),
+            offscreen: OffscreenMode::Clamp,
+            margin: 0.0,
+            on_screen: false,
+            direction: Vec2::zero(),
+        }
+    }
+}
+
+/// Marks a child of a [WorldAnchor] node that is only shown while the anchor's position is off screen in
+/// [OffscreenMode::Indicator]. It is rotated so its x axis points towards the position, ex: an arrow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorldAnchorIndicator;
+
+/// Where a [WorldAnchor] node is placed on a screen of `screen_size`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AnchorPlacement {
+    center: Vec2,
+    on_screen: bool,
+    direction: Vec2,
+}
+
+/// Places a node of `node_size` on a screen of `screen_size`. `target` is the projected position, or `None` when it is
+/// behind the camera, where `behind_direction` points towards it instead. All values are in the same units.
+fn place_anchor(
+    target: Option<Vec2>,
+    behind_direction: Vec2,
+    screen_size: Vec2,
+    node_size: Vec2,
+    margin: f32,
+) -> AnchorPlacement {
+    let screen_center = screen_size / 2.0;
+    let on_screen = target.map_or(false, |target| {
+        target.cmpge(Vec2::zero()).all() && target.cmple(screen_size).all()
+    });
+    let direction = match target {
+        Some(target) => target - screen_center,
+        None => behind_direction,
+    };
+    let direction = if direction.length_squared() > 0.0 {
+        direction.normalize()
+    } else {
+        Vec2::new(0.0, 1.0)
+    };
+
+    // the area the node's center is kept in, so the whole node stays on the screen
+    let half_extents = (screen_center - node_size / 2.0 - Vec2::splat(margin)).max(Vec2::zero());
+    let center = match target {
+        Some(target) if on_screen => target
+            .max(screen_center - half_extents)
+            .min(screen_center + half_extents),
+        _ => {
+            // move from the center of the screen towards the position until the node reaches the edge
+            let distance_x = if direction.x() != 0.0 {
+                half_extents.x() / direction.x().abs()
+            } else {
+                std::f32::MAX
+            };
+            let distance_y = if direction.y() != 0.0 {
+                half_extents.y() / direction.y().abs()
+            } else {
+                std::f32::MAX
+            };
+            screen_center + direction * distance_x.min(distance_y)
+        }
+    };
+
+    AnchorPlacement {
+        center,
+        on_screen,
+        direction,
+    }
+}
+
+pub fn world_anchor_system(
+    active_cameras: Res<ActiveCameras>,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    camera_query: Query<(&Camera, &Transform)>,
+    target_query: Query<&Transform>,
+    mut anchor_query: Query<(&mut WorldAnchor, &Node, &mut Style)>,
+    mut indicator_query: Query<(&WorldAnchorIndicator, &Parent, &mut Style)>,
+) {
+    for (mut anchor, node, mut style) in &mut anchor_query.iter() {
+        let camera_entity = match active_cameras.get(&anchor.camera) {
+            Some(camera_entity) => camera_entity,
+            None => continue,
+        };
+        let (camera, camera_transform) = match (
+            camera_query.get::<Camera>(camera_entity),
+            camera_query.get::<Transform>(camera_entity),
+        ) {
+            (Ok(camera), Ok(camera_transform)) => (camera, camera_transform),
+            _ => continue,
+        };
+        let (viewport, window) =
+            match (camera.window_viewport(&windows), windows.get(camera.window)) {
+                (Some(viewport), Some(window)) => (viewport, window),
+                _ => continue,
+            };
+
+        let world_position = match anchor.entity {
+            Some(entity) => match target_query.get::<Transform>(entity) {
+                Ok(transform) => transform.value.transform_point3(anchor.position),
+                Err(_) => continue,
+            },
+            None => anchor.position,
+        };
+        let target = camera
+            .world_to_screen(&windows, &camera_transform, world_position)
+            .map(|target| target - viewport.position);
+        // the camera looks along its negative z axis, so x and y in its space point right and up on the screen
+        let camera_space_position = camera_transform
+            .value
+            .inverse()
+            .transform_point3(world_position);
+        let behind_direction = Vec2::new(camera_space_position.x(), camera_space_position.y());
+
+        // viewports and node sizes are in physical pixels, but styles are in logical pixels
+        let scale_factor = ui_scale.scale_factor(window) as f32;
+        let placement = place_anchor(
+            target,
+            behind_direction,
+            viewport.size,
+            node.size,
+            anchor.margin * scale_factor,
+        );
+        anchor.on_screen = placement.on_screen;
+        anchor.direction = placement.direction;
+
+        let position = Rect {
+            left: Val::Px(
+                (viewport.position.x() + placement.center.x() - node.size.x() / 2.0) / scale_factor,
+            ),
+            bottom: Val::Px(
+                (viewport.position.y() + placement.center.y() - node.size.y() / 2.0) / scale_factor,
+            ),
+            ..Default::default()
+        };
+        let hidden = !placement.on_screen && anchor.offscreen == OffscreenMode::Hide;
+        // avoid mutating unchanged styles, which would trigger a layout
+        if style.position_type != PositionType::Absolute || style.position != position {
+            style.position_type = PositionType::Absolute;
+            style.position = position;
+        }
+        if hidden && style.display != Display::None {
+            style.display = Display::None;
+        } else if !hidden && style.display == Display::None {
+            style.display = Display::Flex;
+        }
+    }
+
+    for (_indicator, parent, mut style) in &mut indicator_query.iter() {
+        let shown = anchor_query
+            .get::<WorldAnchor>(parent.0)
+            .map_or(false, |anchor| {
+                !anchor.on_screen && anchor.offscreen == OffscreenMode::Indicator
+            });
+        let display = if shown { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+/// Rotates [WorldAnchorIndicator]s towards their anchor's position. Layouts can't rotate nodes, so this rotates their
+/// [Transform] after it is updated.
+pub fn world_anchor_indicator_system(
+    anchor_query: Query<&WorldAnchor>,
+    mut indicator_query: Query<(&WorldAnchorIndicator, &Parent, &mut Transform)>,
+) {
+    for (_indicator, parent, mut transform) in &mut indicator_query.iter() {
+        if let Ok(anchor) = anchor_query.get::<WorldAnchor>(parent.0) {
+            let angle = anchor.direction.y().atan2(anchor.direction.x());
+            transform.value = transform.value * Mat4::from_rotation_z(angle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{place_anchor, AnchorPlacement};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn anchor_placement() {
+        let screen_size = Vec2::new(800.0, 600.0);
+        let node_size = Vec2::new(100.0, 50.0);
+
+        // on screen nodes are centered on the position, but kept inside the screen
+        let on_screen = place_anchor(
+            Some(Vec2::new(300.0, 200.0)),
+            Vec2::zero(),
+            screen_size,
+            node_size,
+            0.0,
+        );
+        assert_eq!(on_screen.center, Vec2::new(300.0, 200.0));
+        assert!(on_screen.on_screen);
+        let near_edge = place_anchor(
+            Some(Vec2::new(790.0, 590.0)),
+            Vec2::zero(),
+            screen_size,
+            node_size,
+            10.0,
+        );
+        assert_eq!(near_edge.center, Vec2::new(740.0, 565.0));
+
+        // off screen nodes move to the edge in the direction of the position
+        assert_eq!(
+            place_anchor(
+                Some(Vec2::new(1400.0, 300.0)),
+                Vec2::zero(),
+                screen_size,
+                node_size,
+                0.0
+            ),
+            AnchorPlacement {
+                center: Vec2::new(750.0, 300.0),
+                on_screen: false,
+                direction: Vec2::new(1.0, 0.0),
+            }
+        );
+
+        // positions behind the camera use the given direction
+        let behind = place_anchor(None, Vec2::new(0.0, -2.0), screen_size, node_size, 0.0);
+        assert_eq!(behind.center, Vec2::new(400.0, 25.0));
+        assert_eq!(behind.direction, Vec2::new(0.0, -1.0));
+        assert!(!behind.on_screen);
+    }
+}
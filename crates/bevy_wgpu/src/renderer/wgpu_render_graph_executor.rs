@@ -1,9 +1,13 @@
 use super::{WgpuRenderContext, WgpuRenderResourceContext};
 use bevy_ecs::{Resources, World};
 use bevy_render::{
-    render_graph::{Edge, NodeId, ResourceSlots, StageBorrow},
-    renderer::RenderResourceContext,
+    render_graph::{
+        Edge, NodeId, OrderedJobBorrow, ResourceSlots, StageBorrow, TransientTexturePool,
+        TransientTextureReaders,
+    },
+    renderer::{RenderContext, RenderResourceContext},
 };
+use rayon::prelude::*;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -14,6 +18,7 @@ pub struct WgpuRenderGraphExecutor {
 }
 
 impl WgpuRenderGraphExecutor {
+    /// Runs the nodes of each stage and submits their command buffers in order, see [execute_stages]
     pub fn execute(
         &self,
         world: &World,
@@ -25,80 +30,346 @@ impl WgpuRenderGraphExecutor {
         let mut render_resource_context = resources
             .get_mut::<Box<dyn RenderResourceContext>>()
             .unwrap();
-        let render_resource_context = render_resource_context
+        let render_resource_context = &*render_resource_context
             .downcast_mut::<WgpuRenderResourceContext>()
             .unwrap();
-        let node_outputs: Arc<RwLock<HashMap<NodeId, ResourceSlots>>> = Default::default();
-        for stage in stages.iter_mut() {
-            // TODO: sort jobs and slice by "amount of work" / weights
-            // stage.jobs.sort_by_key(|j| j.node_states.len());
-
-            let (sender, receiver) = crossbeam_channel::bounded(self.max_thread_count);
-            let chunk_size = (stage.jobs.len() + self.max_thread_count - 1) / self.max_thread_count; // divide ints rounding remainder up
-            let mut actual_thread_count = 0;
-            // crossbeam_utils::thread::scope(|s| {
-            for jobs_chunk in stage.jobs.chunks_mut(chunk_size) {
-                let sender = sender.clone();
-                let world = &*world;
-                actual_thread_count += 1;
-                let device = device.clone();
-                let render_resource_context = render_resource_context.clone();
-                let node_outputs = node_outputs.clone();
-                // s.spawn(move |_| {
-                let mut render_context = WgpuRenderContext::new(device, render_resource_context);
-                for job in jobs_chunk.iter_mut() {
-                    for node_state in job.node_states.iter_mut() {
-                        // bind inputs from connected node outputs
-                        for (i, mut input_slot) in node_state.input_slots.iter_mut().enumerate() {
-                            if let Edge::SlotEdge {
-                                output_node,
-                                output_index,
-                                ..
-                            } = node_state.edges.get_input_slot_edge(i).unwrap()
-                            {
-                                let node_outputs = node_outputs.read().unwrap();
-                                let outputs = if let Some(outputs) = node_outputs.get(output_node) {
-                                    outputs
-                                } else {
-                                    panic!("node inputs not set")
-                                };
-
-                                let output_resource =
-                                    outputs.get(*output_index).expect("output should be set");
-                                input_slot.resource = Some(output_resource);
-                            } else {
-                                panic!("no edge connected to input")
-                            }
-                        }
-                        node_state.node.update(
-                            world,
-                            resources,
-                            &mut render_context,
-                            &node_state.input_slots,
-                            &mut node_state.output_slots,
-                        );
-
-                        node_outputs
-                            .write()
-                            .unwrap()
-                            .insert(node_state.id, node_state.output_slots.clone());
-                    }
+        let node_outputs: RwLock<HashMap<NodeId, ResourceSlots>> = Default::default();
+        execute_stages(
+            resources,
+            stages,
+            self.max_thread_count.max(1),
+            |jobs| {
+                let mut render_context =
+                    WgpuRenderContext::new(device.clone(), render_resource_context.clone());
+                for job in jobs.iter_mut() {
+                    run_job(world, resources, &mut render_context, job, &node_outputs);
                 }
-                sender.send(render_context.finish()).unwrap();
-                // });
+                render_context.finish()
+            },
+            |command_buffers| queue.submit(command_buffers),
+        );
+
+        if let Some(mut transient_texture_pool) = resources.get_mut::<TransientTexturePool>() {
+            transient_texture_pool.end_frame(render_resource_context);
+        }
+    }
+}
+
+/// Records and submits the jobs of each stage. `record` records a batch of jobs into one command encoder and returns its
+/// command buffer. Jobs whose nodes all [encode in parallel](bevy_render::render_graph::Node::encodes_in_parallel) are
+/// split into up to `thread_count` batches that are recorded on the rayon thread pool, and the other jobs are recorded
+/// in one batch on the current thread. The serial batch is submitted first, then the parallel batches in job order.
+fn execute_stages<'a, C: Send>(
+    resources: &Resources,
+    stages: &mut [StageBorrow<'a>],
+    thread_count: usize,
+    record: impl Fn(&mut [&mut OrderedJobBorrow<'a>]) -> Option<C> + Sync,
+    mut submit: impl FnMut(Vec<C>),
+) {
+    let mut transient_texture_readers = TransientTextureReaders::new(stages);
+    for stage in stages.iter_mut() {
+        let (mut parallel_jobs, mut serial_jobs): (Vec<_>, Vec<_>) =
+            stage.jobs.iter_mut().partition(|job| {
+                job.node_states
+                    .iter()
+                    .all(|node_state| node_state.node.encodes_in_parallel())
+            });
+        // a single job gains nothing from another thread
+        if parallel_jobs.len() < 2 {
+            serial_jobs.append(&mut parallel_jobs);
+        }
+
+        let mut command_buffers = Vec::new();
+        if !serial_jobs.is_empty() {
+            command_buffers.extend(record(&mut serial_jobs));
+        }
+
+        if !parallel_jobs.is_empty() {
+            // divide ints rounding remainder up
+            let chunk_size = (parallel_jobs.len() + thread_count - 1) / thread_count;
+            let parallel_command_buffers = parallel_jobs
+                .par_chunks_mut(chunk_size)
+                .map(&record)
+                .collect::<Vec<_>>();
+            command_buffers.extend(parallel_command_buffers.into_iter().flatten());
+        }
+
+        submit(command_buffers);
+
+        // transient textures are released once the whole stage was recorded, so a texture released in one job
+        // isn't handed to a job whose commands are submitted before it
+        if let Some(mut transient_texture_pool) = resources.get_mut::<TransientTexturePool>() {
+            let node_states = stage.jobs.iter().flat_map(|job| job.node_states.iter());
+            for node_state in node_states {
+                transient_texture_readers.node_finished(node_state, &mut transient_texture_pool);
             }
-            // })
-            // .unwrap();
-
-            let mut command_buffers = Vec::new();
-            for _i in 0..actual_thread_count {
-                let command_buffer = receiver.recv().unwrap();
-                if let Some(command_buffer) = command_buffer {
-                    command_buffers.push(command_buffer);
-                }
+        }
+    }
+}
+
+/// Binds the inputs of each node in `job` from the outputs of the nodes they are connected to, and updates the node
+fn run_job(
+    world: &World,
+    resources: &Resources,
+    render_context: &mut dyn RenderContext,
+    job: &mut OrderedJobBorrow<'_>,
+    node_outputs: &RwLock<HashMap<NodeId, ResourceSlots>>,
+) {
+    for node_state in job.node_states.iter_mut() {
+        // bind inputs from connected node outputs
+        for (i, mut input_slot) in node_state.input_slots.iter_mut().enumerate() {
+            if let Edge::SlotEdge {
+                output_node,
+                output_index,
+                ..
+            } = node_state.edges.get_input_slot_edge(i).unwrap()
+            {
+                let node_outputs = node_outputs.read().unwrap();
+                let outputs = if let Some(outputs) = node_outputs.get(output_node) {
+                    outputs
+                } else {
+                    panic!("node inputs not set")
+                };
+
+                // outputs can be empty, ex: multisampled attachments while msaa is disabled
+                input_slot.resource = outputs.get(*output_index);
+            } else {
+                panic!("no edge connected to input")
             }
+        }
+        node_state.node.update(
+            world,
+            resources,
+            render_context,
+            &node_state.input_slots,
+            &mut node_state.output_slots,
+        );
+
+        node_outputs
+            .write()
+            .unwrap()
+            .insert(node_state.id, node_state.output_slots.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{execute_stages, run_job};
+    use bevy_ecs::{Resources, World};
+    use bevy_render::{
+        pass::{ComputePass, PassDescriptor, RenderPass},
+        render_graph::{
+            Node, NodeId, OrderedJob, RenderGraph, ResourceSlotInfo, ResourceSlots, Stage, Stages,
+            TransientTexturePool,
+        },
+        renderer::{
+            BufferId, HeadlessRenderResourceContext, RenderContext, RenderResourceBindings,
+            RenderResourceContext, RenderResourceId, RenderResourceType, TextureId,
+        },
+        texture::{Extent3d, TextureDescriptor},
+    };
+    use std::{borrow::Cow, collections::HashMap, sync::RwLock};
+
+    #[derive(Default)]
+    struct TestRenderContext {
+        render_resource_context: HeadlessRenderResourceContext,
+    }
+
+    impl RenderContext for TestRenderContext {
+        fn resources(&self) -> &dyn RenderResourceContext {
+            &self.render_resource_context
+        }
+
+        fn resources_mut(&mut self) -> &mut dyn RenderResourceContext {
+            &mut self.render_resource_context
+        }
+
+        fn copy_buffer_to_buffer(&mut self, _: BufferId, _: u64, _: BufferId, _: u64, _: u64) {}
+
+        fn copy_buffer_to_texture(
+            &mut self,
+            _: BufferId,
+            _: u64,
+            _: u32,
+            _: TextureId,
+            _: [u32; 3],
+            _: u32,
+            _: Extent3d,
+        ) {
+        }
 
-            queue.submit(command_buffers.drain(..));
+        fn begin_pass(
+            &mut self,
+            _: &PassDescriptor,
+            _: &RenderResourceBindings,
+            _: &mut dyn Fn(&mut dyn RenderPass),
+        ) {
         }
+
+        fn begin_compute_pass(&mut self, _: &mut dyn Fn(&mut dyn ComputePass)) {}
+    }
+
+    /// Outputs `texture` from its only output slot, if it has one
+    struct TestNode {
+        parallel: bool,
+        inputs: Vec<ResourceSlotInfo>,
+        outputs: Vec<ResourceSlotInfo>,
+        texture: Option<TextureId>,
+    }
+
+    impl TestNode {
+        fn new(parallel: bool) -> Self {
+            TestNode {
+                parallel,
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                texture: None,
+            }
+        }
+
+        fn with_input(mut self) -> Self {
+            self.inputs.push(texture_slot("in"));
+            self
+        }
+
+        fn with_output(mut self, texture: TextureId) -> Self {
+            self.outputs.push(texture_slot("out"));
+            self.texture = Some(texture);
+            self
+        }
+    }
+
+    fn texture_slot(name: &'static str) -> ResourceSlotInfo {
+        ResourceSlotInfo {
+            name: name.into(),
+            resource_type: RenderResourceType::Texture,
+        }
+    }
+
+    impl Node for TestNode {
+        fn input(&self) -> &[ResourceSlotInfo] {
+            &self.inputs
+        }
+
+        fn output(&self) -> &[ResourceSlotInfo] {
+            &self.outputs
+        }
+
+        fn update(
+            &mut self,
+            _: &World,
+            _: &Resources,
+            _: &mut dyn RenderContext,
+            _: &ResourceSlots,
+            output: &mut ResourceSlots,
+        ) {
+            if let Some(texture) = self.texture {
+                output.set(0, RenderResourceId::Texture(texture));
+            }
+        }
+
+        fn encodes_in_parallel(&self) -> bool {
+            self.parallel
+        }
+    }
+
+    #[test]
+    fn execute_stages_in_order() {
+        let world = World::default();
+        let mut resources = Resources::default();
+        let render_resource_context = HeadlessRenderResourceContext::default();
+        let mut transient_texture_pool = TransientTexturePool::default();
+        let descriptor = TextureDescriptor {
+            size: Extent3d {
+                width: 64,
+                height: 64,
+                depth: 1,
+            },
+            ..Default::default()
+        };
+        let read_texture = transient_texture_pool.acquire(&render_resource_context, descriptor);
+        let unread_texture = transient_texture_pool.acquire(&render_resource_context, descriptor);
+        resources.insert(transient_texture_pool);
+
+        // the output of "serial_a" is read in the second stage, the output of "parallel_c" isn't read
+        let mut graph = RenderGraph::default();
+        let serial_a = graph.add_node("serial_a", TestNode::new(false).with_output(read_texture));
+        let parallel_a = graph.add_node("parallel_a", TestNode::new(true));
+        let serial_b = graph.add_node("serial_b", TestNode::new(false));
+        let parallel_b = graph.add_node("parallel_b", TestNode::new(true));
+        let parallel_c = graph.add_node(
+            "parallel_c",
+            TestNode::new(true).with_output(unread_texture),
+        );
+        let reader = graph.add_node("reader", TestNode::new(false).with_input());
+        graph
+            .add_slot_edge("serial_a", "out", "reader", "in")
+            .unwrap();
+        let job = |node: NodeId| OrderedJob { nodes: vec![node] };
+        let stages = Stages::new(vec![
+            Stage {
+                jobs: vec![
+                    job(serial_a),
+                    job(parallel_a),
+                    job(serial_b),
+                    job(parallel_b),
+                    job(parallel_c),
+                ],
+            },
+            Stage {
+                jobs: vec![job(reader)],
+            },
+        ]);
+        let mut stage_borrows = stages.borrow(&mut graph);
+
+        let node_outputs: RwLock<HashMap<NodeId, ResourceSlots>> = Default::default();
+        let mut submitted = Vec::new();
+        execute_stages(
+            &resources,
+            &mut stage_borrows,
+            2,
+            |jobs| {
+                let mut render_context = TestRenderContext::default();
+                for job in jobs.iter_mut() {
+                    run_job(&world, &resources, &mut render_context, job, &node_outputs);
+                }
+                let names = jobs
+                    .iter()
+                    .flat_map(|job| job.node_states.iter())
+                    .map(|node_state| node_state.name.clone().unwrap())
+                    .collect::<Vec<Cow<'static, str>>>();
+                Some(names)
+            },
+            |command_buffers| {
+                let transient_texture_pool = resources.get::<TransientTexturePool>().unwrap();
+                submitted.push((
+                    command_buffers,
+                    transient_texture_pool.is_in_use(read_texture),
+                    transient_texture_pool.is_in_use(unread_texture),
+                ));
+            },
+        );
+
+        // the serial jobs are recorded into one command buffer that is submitted before the parallel ones, and
+        // textures are only released after the stage of their last reader was submitted
+        assert_eq!(
+            submitted,
+            vec![
+                (
+                    vec![
+                        vec!["serial_a".into(), "serial_b".into()],
+                        vec!["parallel_a".into(), "parallel_b".into()],
+                        vec!["parallel_c".into()],
+                    ],
+                    true,
+                    true
+                ),
+                (vec![vec!["reader".into()]], true, false),
+            ]
+        );
+        assert!(!resources
+            .get::<TransientTexturePool>()
+            .unwrap()
+            .is_in_use(read_texture));
     }
 }
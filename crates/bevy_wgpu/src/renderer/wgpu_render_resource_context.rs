@@ -5,7 +5,10 @@ use crate::{
 
 use bevy_asset::{Assets, Handle, HandleUntyped};
 use bevy_render::{
-    pipeline::{BindGroupDescriptor, BindGroupDescriptorId, BindingShaderStage, PipelineDescriptor},
+    pipeline::{
+        BindGroupDescriptor, BindGroupDescriptorId, BindingShaderStage, ComputePipelineDescriptor,
+        PipelineDescriptor,
+    },
     renderer::{
         BindGroup, BufferId, BufferInfo, RenderResourceBinding, RenderResourceContext,
         RenderResourceId, SamplerId, TextureId,
@@ -113,12 +116,16 @@ impl WgpuRenderResourceContext {
             .bindings
             .iter()
             .map(|binding| {
-                let shader_stage = if binding.shader_stage == BindingShaderStage::VERTEX | BindingShaderStage::FRAGMENT {
+                let shader_stage = if binding.shader_stage
+                    == BindingShaderStage::VERTEX | BindingShaderStage::FRAGMENT
+                {
                     wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT
                 } else if binding.shader_stage == BindingShaderStage::VERTEX {
                     wgpu::ShaderStage::VERTEX
                 } else if binding.shader_stage == BindingShaderStage::FRAGMENT {
                     wgpu::ShaderStage::FRAGMENT
+                } else if binding.shader_stage == BindingShaderStage::COMPUTE {
+                    wgpu::ShaderStage::COMPUTE
                 } else {
                     panic!("Invalid binding shader stage.")
                 };
@@ -427,6 +434,62 @@ impl RenderResourceContext for WgpuRenderResourceContext {
         render_pipelines.insert(pipeline_handle, render_pipeline);
     }
 
+    fn create_compute_pipeline(
+        &self,
+        pipeline_handle: Handle<ComputePipelineDescriptor>,
+        pipeline_descriptor: &ComputePipelineDescriptor,
+        shaders: &Assets<Shader>,
+    ) {
+        if self
+            .resources
+            .compute_pipelines
+            .read()
+            .unwrap()
+            .get(&pipeline_handle)
+            .is_some()
+        {
+            return;
+        }
+
+        let layout = &pipeline_descriptor.layout;
+        for bind_group_descriptor in layout.bind_groups.iter() {
+            self.create_bind_group_layout(bind_group_descriptor);
+        }
+
+        let bind_group_layouts = self.resources.bind_group_layouts.read().unwrap();
+        let bind_group_layouts = layout
+            .bind_groups
+            .iter()
+            .map(|bind_group| bind_group_layouts.get(&bind_group.id).unwrap())
+            .collect::<Vec<&wgpu::BindGroupLayout>>();
+
+        let pipeline_layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                bind_group_layouts: bind_group_layouts.as_slice(),
+            });
+
+        self.create_shader_module(pipeline_descriptor.shader, shaders);
+        let shader_modules = self.resources.shader_modules.read().unwrap();
+        let shader_module = shader_modules.get(&pipeline_descriptor.shader).unwrap();
+
+        let compute_pipeline =
+            self.device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    layout: &pipeline_layout,
+                    compute_stage: wgpu::ProgrammableStageDescriptor {
+                        module: shader_module,
+                        entry_point: "main",
+                    },
+                });
+        let mut compute_pipelines = self.resources.compute_pipelines.write().unwrap();
+        compute_pipelines.insert(pipeline_handle, compute_pipeline);
+    }
+
+    fn supports_compute(&self) -> bool {
+        true
+    }
+
     fn bind_group_descriptor_exists(
         &self,
         bind_group_descriptor_id: BindGroupDescriptorId,
@@ -1,7 +1,7 @@
 use crate::{renderer::WgpuRenderContext, WgpuResourceRefs};
 use bevy_asset::Handle;
 use bevy_render::{
-    pass::RenderPass,
+    pass::{DrawIndexedIndirectArgs, RenderPass},
     pipeline::{BindGroupDescriptorId, PipelineDescriptor},
     renderer::{BindGroupId, BufferId, RenderContext},
 };
@@ -44,6 +44,31 @@ impl<'a> RenderPass for WgpuRenderPass<'a> {
             .draw_indexed(indices, base_vertex, instances);
     }
 
+    fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: BufferId,
+        indirect_offset: u64,
+        count: u32,
+    ) {
+        let buffer = self.wgpu_resources.buffers.get(&indirect_buffer).unwrap();
+        if self
+            .render_context
+            .device
+            .features()
+            .contains(wgpu::Features::MULTI_DRAW_INDIRECT)
+        {
+            self.render_pass
+                .multi_draw_indexed_indirect(buffer, indirect_offset, count);
+        } else {
+            for i in 0..count as u64 {
+                self.render_pass.draw_indexed_indirect(
+                    buffer,
+                    indirect_offset + i * DrawIndexedIndirectArgs::SIZE,
+                );
+            }
+        }
+    }
+
     fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
         self.render_pass.draw(vertices, instances);
     }
@@ -30,7 +30,8 @@ impl WgpuRenderer {
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
+                    // multi-draw-indirect is optional. indirect draws fall back to one draw per argument set without it
+                    features: adapter.features() & wgpu::Features::MULTI_DRAW_INDIRECT,
                     limits: wgpu::Limits::default(),
                     shader_validation: true,
                 },
@@ -84,7 +85,7 @@ impl WgpuRenderer {
 
         // execute stages
         let graph_executor = WgpuRenderGraphExecutor {
-            max_thread_count: 2,
+            max_thread_count: rayon::current_num_threads(),
         };
         graph_executor.execute(
             world,
@@ -0,0 +1,175 @@
+use bevy_app::{EventReader, Events};
+use bevy_ecs::{Local, Res, ResMut};
+use std::collections::VecDeque;
+
+/// How an [Announce] is read relative to what the screen reader is already saying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnouncePriority {
+    /// Read after the current speech, ex: "item acquired"
+    Polite,
+    /// Interrupts the current speech, ex: "your turn"
+    Assertive,
+}
+
+/// An event that asks the screen reader to read `text`, so players who can't see the screen hear about dynamic
+/// changes, ex: "turn changed" or "item acquired"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announce {
+    pub text: String,
+    pub priority: AnnouncePriority,
+}
+
+impl Announce {
+    pub fn polite(text: impl Into<String>) -> Self {
+        Announce {
+            text: text.into(),
+            priority: AnnouncePriority::Polite,
+        }
+    }
+
+    pub fn assertive(text: impl Into<String>) -> Self {
+        Announce {
+            text: text.into(),
+            priority: AnnouncePriority::Assertive,
+        }
+    }
+}
+
+/// Speaks text with the screen reader or text-to-speech engine of the platform
+pub trait ScreenReaderBackend: Send + Sync + 'static {
+    /// Speaks `text`. If `interrupt` is set, the current speech is cut off.
+    fn speak(&mut self, text: &str, interrupt: bool);
+}
+
+/// Sends [Announce] events to the [ScreenReaderBackend] of the platform. Platform integrations set the backend with
+/// [ScreenReader::set_backend]. Without a backend, announcements are logged so they can be checked during development.
+///
+/// The most recent announcements are kept, ex: to show them as captions or to repeat the last one on request.
+pub struct ScreenReader {
+    backend: Option<Box<dyn ScreenReaderBackend>>,
+    history: VecDeque<Announce>,
+    /// The number of announcements kept in the history
+    pub history_len: usize,
+}
+
+impl Default for ScreenReader {
+    fn default() -> Self {
+        ScreenReader {
+            backend: None,
+            history: VecDeque::new(),
+            history_len: 16,
+        }
+    }
+}
+
+impl ScreenReader {
+    pub fn set_backend(&mut self, backend: impl ScreenReaderBackend) {
+        self.backend = Some(Box::new(backend));
+    }
+
+    pub fn has_backend(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// Reads `announce` right away
+    pub fn announce(&mut self, announce: Announce) {
+        let interrupt = announce.priority == AnnouncePriority::Assertive;
+        match self.backend.as_mut() {
+            Some(backend) => backend.speak(&announce.text, interrupt),
+            None => log::info!("screen reader: {}", announce.text),
+        }
+
+        self.history.push_back(announce);
+        while self.history.len() > self.history_len {
+            self.history.pop_front();
+        }
+    }
+
+    /// The most recent announcements, oldest first
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Announce> {
+        self.history.iter()
+    }
+
+    /// Reads the most recent announcement again
+    pub fn repeat_last(&mut self) {
+        if let Some(last) = self.history.back().cloned() {
+            self.announce(last);
+        }
+    }
+}
+
+/// Reads the [Announce] events of this update with the [ScreenReader]. Assertive announcements are read first, as
+/// they interrupt anything read before them anyway.
+pub fn announce_system(
+    mut announce_event_reader: Local<EventReader<Announce>>,
+    announce_events: Res<Events<Announce>>,
+    mut screen_reader: ResMut<ScreenReader>,
+) {
+    let (assertive, polite): (Vec<&Announce>, Vec<&Announce>) = announce_event_reader
+        .iter(&announce_events)
+        .partition(|announce| announce.priority == AnnouncePriority::Assertive);
+    for announce in assertive.into_iter().chain(polite) {
+        screen_reader.announce(announce.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{announce_system, Announce, ScreenReader, ScreenReaderBackend};
+    use bevy_app::Events;
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use std::sync::{Arc, Mutex};
+
+    struct TestBackend(Arc<Mutex<Vec<(String, bool)>>>);
+
+    impl ScreenReaderBackend for TestBackend {
+        fn speak(&mut self, text: &str, interrupt: bool) {
+            self.0.lock().unwrap().push((text.to_string(), interrupt));
+        }
+    }
+
+    #[test]
+    fn announce() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let spoken = Arc::new(Mutex::new(Vec::new()));
+        let mut screen_reader = ScreenReader::default();
+        screen_reader.set_backend(TestBackend(spoken.clone()));
+        screen_reader.history_len = 2;
+        resources.insert(screen_reader);
+        let mut announce_events = Events::<Announce>::default();
+        announce_events.send(Announce::polite("sword acquired"));
+        announce_events.send(Announce::assertive("your turn"));
+        announce_events.send(Announce::polite("3 moves left"));
+        resources.insert(announce_events);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", announce_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(
+            *spoken.lock().unwrap(),
+            vec![
+                ("your turn".to_string(), true),
+                ("sword acquired".to_string(), false),
+                ("3 moves left".to_string(), false),
+            ]
+        );
+
+        let mut screen_reader = resources.get_mut::<ScreenReader>().unwrap();
+        assert_eq!(
+            screen_reader
+                .history()
+                .map(|announce| announce.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sword acquired", "3 moves left"]
+        );
+        screen_reader.repeat_last();
+        assert_eq!(
+            spoken.lock().unwrap().last(),
+            Some(&("3 moves left".to_string(), false))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::{WindowDescriptor, WindowId};
+use super::{WindowDescriptor, WindowId, WindowMode};
 use bevy_math::Vec2;
 
 /// A window event that is sent whenever a window has been resized.
@@ -9,6 +9,30 @@ pub struct WindowResized {
     pub height: usize,
 }
 
+/// A window event that is sent whenever a window's scale factor changes, ex: when it is moved to a monitor with a
+/// different pixel density.
+#[derive(Debug, Clone)]
+pub struct WindowScaleFactorChanged {
+    pub id: WindowId,
+    pub scale_factor: f64,
+}
+
+/// An event that is sent whenever a window's mode has been changed with
+/// [Window::set_mode](crate::Window::set_mode)
+#[derive(Debug, Clone)]
+pub struct WindowModeChanged {
+    pub id: WindowId,
+    pub mode: WindowMode,
+}
+
+/// An event that is sent whenever a window's vsync has been changed with [Window::set_vsync](crate::Window::set_vsync).
+/// Renderers recreate the window's swap chain when they receive it.
+#[derive(Debug, Clone)]
+pub struct WindowVsyncChanged {
+    pub id: WindowId,
+    pub vsync: bool,
+}
+
 /// An event that indicates that a new window should be created.
 #[derive(Debug, Clone)]
 pub struct CreateWindow {
@@ -35,8 +59,28 @@ pub struct WindowCloseRequested {
     pub id: WindowId,
 }
 
+/// An event that is sent whenever the cursor enters a window
+#[derive(Debug, Clone)]
+pub struct CursorEntered {
+    pub id: WindowId,
+}
+
+/// An event that is sent whenever the cursor leaves a window
+#[derive(Debug, Clone)]
+pub struct CursorLeft {
+    pub id: WindowId,
+}
+
 #[derive(Debug, Clone)]
 pub struct CursorMoved {
     pub id: WindowId,
     pub position: Vec2,
 }
+
+/// An event that is sent whenever a window receives a character from the keyboard or an input method, with keyboard
+/// layouts, dead keys and key repeat applied. Text input should use this instead of keyboard events.
+#[derive(Debug, Clone)]
+pub struct ReceivedCharacter {
+    pub id: WindowId,
+    pub char: char,
+}
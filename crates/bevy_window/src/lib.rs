@@ -1,15 +1,22 @@
+mod announce;
 mod event;
+mod safe_area;
 mod system;
 mod window;
 mod windows;
 
+pub use announce::*;
 pub use event::*;
+pub use safe_area::*;
 pub use system::*;
 pub use window::*;
 pub use windows::*;
 
 pub mod prelude {
-    pub use crate::{CursorMoved, Window, WindowDescriptor, Windows};
+    pub use crate::{
+        Announce, CursorEntered, CursorLeft, CursorMoved, ReceivedCharacter, SafeAreaInsets,
+        ScreenReader, Window, WindowDescriptor, Windows,
+    };
 }
 
 use bevy_app::prelude::*;
@@ -32,12 +39,22 @@ impl Default for WindowPlugin {
 impl Plugin for WindowPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_event::<WindowResized>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_event::<WindowModeChanged>()
+            .add_event::<WindowVsyncChanged>()
             .add_event::<CreateWindow>()
             .add_event::<WindowCreated>()
             .add_event::<WindowCloseRequested>()
             .add_event::<CloseWindow>()
             .add_event::<CursorMoved>()
-            .init_resource::<Windows>();
+            .add_event::<CursorEntered>()
+            .add_event::<CursorLeft>()
+            .add_event::<ReceivedCharacter>()
+            .add_event::<Announce>()
+            .init_resource::<Windows>()
+            .init_resource::<SafeAreaInsets>()
+            .init_resource::<ScreenReader>()
+            .add_system_to_stage(bevy_app::stage::POST_UPDATE, announce_system.system());
 
         if self.add_primary_window {
             let resources = app.resources();
@@ -0,0 +1,26 @@
+use crate::WindowId;
+use bevy_math::Rect;
+use std::collections::HashMap;
+
+/// The distance (in logical pixels) from each edge of a window that may be obscured by platform features like notches,
+/// rounded corners, or system bars. Windowing backends update these when the platform provides them: bevy_winit does
+/// on iOS, and the insets are zero elsewhere unless they are set manually.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SafeAreaInsets {
+    insets: HashMap<WindowId, Rect<f32>>,
+}
+
+impl SafeAreaInsets {
+    /// Returns the insets of the given window, which are zero if the platform has not reported any
+    pub fn get(&self, id: WindowId) -> Rect<f32> {
+        self.insets.get(&id).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, id: WindowId, insets: Rect<f32>) {
+        self.insets.insert(id, insets);
+    }
+
+    pub fn remove(&mut self, id: WindowId) {
+        self.insets.remove(&id);
+    }
+}
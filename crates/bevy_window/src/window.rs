@@ -1,3 +1,4 @@
+use bevy_math::Vec2;
 use uuid::Uuid;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -30,12 +31,34 @@ impl Default for WindowId {
 #[derive(Debug)]
 pub struct Window {
     pub id: WindowId,
+    /// The width of the window in physical pixels
     pub width: u32,
+    /// The height of the window in physical pixels
     pub height: u32,
+    /// The ratio of physical pixels to logical pixels, ex: 2.0 on most "retina" displays
+    pub scale_factor: f64,
+    /// The title, vsync, resizable, and mode fields describe the window, but changing them doesn't change the
+    /// window. Use the setters instead, ex: [Window::set_title].
     pub title: String,
     pub vsync: bool,
     pub resizable: bool,
     pub mode: WindowMode,
+    cursor_locked: bool,
+    cursor_visible: bool,
+    command_queue: Vec<WindowCommand>,
+}
+
+/// A change to a window that the window backend applies at the end of the frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowCommand {
+    SetTitle { title: String },
+    SetWindowMode { mode: WindowMode },
+    SetResolution { width: u32, height: u32 },
+    SetResizable { resizable: bool },
+    SetVsync { vsync: bool },
+    SetCursorLockMode { locked: bool },
+    SetCursorVisibility { visible: bool },
+    SetCursorPosition { position: Vec2 },
 }
 
 /// Defines the way a window is displayed
@@ -43,7 +66,7 @@ pub struct Window {
 /// defines whether a videomode is chosen that best fits the width and height
 /// in the Window structure, or if these are ignored.
 /// E.g. when use_size is set to false the best video mode possible is chosen.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WindowMode {
     Windowed,
     BorderlessFullscreen,
@@ -56,12 +79,83 @@ impl Window {
             id,
             height: window_descriptor.height,
             width: window_descriptor.width,
+            scale_factor: 1.0,
             title: window_descriptor.title.clone(),
             vsync: window_descriptor.vsync,
             resizable: window_descriptor.resizable,
             mode: window_descriptor.mode,
+            cursor_locked: window_descriptor.cursor_locked,
+            cursor_visible: window_descriptor.cursor_visible,
+            command_queue: Vec::new(),
         }
     }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = title.clone();
+        self.command_queue.push(WindowCommand::SetTitle { title });
+    }
+
+    /// Switches between windowed and fullscreen modes. [WindowModeChanged](crate::WindowModeChanged) is sent once
+    /// the mode has changed.
+    pub fn set_mode(&mut self, mode: WindowMode) {
+        self.mode = mode;
+        self.command_queue
+            .push(WindowCommand::SetWindowMode { mode });
+    }
+
+    /// Requests a new size in physical pixels. The width and height of the window are updated, and
+    /// [WindowResized](crate::WindowResized) is sent, once the window has been resized.
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.command_queue
+            .push(WindowCommand::SetResolution { width, height });
+    }
+
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.resizable = resizable;
+        self.command_queue
+            .push(WindowCommand::SetResizable { resizable });
+    }
+
+    /// Turns vsync on or off. The window's swap chain is recreated once
+    /// [WindowVsyncChanged](crate::WindowVsyncChanged) is sent.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.vsync = vsync;
+        self.command_queue.push(WindowCommand::SetVsync { vsync });
+    }
+
+    /// Whether the cursor is confined to the window
+    pub fn cursor_locked(&self) -> bool {
+        self.cursor_locked
+    }
+
+    /// Confines the cursor to the window, ex: for first person camera controls
+    pub fn set_cursor_lock_mode(&mut self, locked: bool) {
+        self.cursor_locked = locked;
+        self.command_queue
+            .push(WindowCommand::SetCursorLockMode { locked });
+    }
+
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Shows or hides the cursor while it is over the window
+    pub fn set_cursor_visibility(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.command_queue
+            .push(WindowCommand::SetCursorVisibility { visible });
+    }
+
+    /// Moves the cursor to `position`, in physical pixels from the bottom left of the window
+    pub fn set_cursor_position(&mut self, position: Vec2) {
+        self.command_queue
+            .push(WindowCommand::SetCursorPosition { position });
+    }
+
+    /// Removes the commands queued by changes to the window, so the window backend can apply them
+    pub fn drain_commands(&mut self) -> impl Iterator<Item = WindowCommand> + '_ {
+        self.command_queue.drain(..)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +166,8 @@ pub struct WindowDescriptor {
     pub vsync: bool,
     pub resizable: bool,
     pub mode: WindowMode,
+    pub cursor_locked: bool,
+    pub cursor_visible: bool,
 }
 
 impl Default for WindowDescriptor {
@@ -83,6 +179,72 @@ impl Default for WindowDescriptor {
             vsync: true,
             resizable: true,
             mode: WindowMode::Windowed,
+            cursor_locked: false,
+            cursor_visible: true,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Window, WindowCommand, WindowDescriptor, WindowId, WindowMode};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn cursor_commands() {
+        let mut window = Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                cursor_visible: false,
+                ..Default::default()
+            },
+        );
+        assert!(!window.cursor_visible());
+        assert_eq!(window.drain_commands().count(), 0);
+
+        window.set_cursor_lock_mode(true);
+        window.set_cursor_visibility(true);
+        window.set_cursor_position(Vec2::new(10.0, 20.0));
+        assert!(window.cursor_locked() && window.cursor_visible());
+        assert_eq!(
+            window.drain_commands().collect::<Vec<_>>(),
+            vec![
+                WindowCommand::SetCursorLockMode { locked: true },
+                WindowCommand::SetCursorVisibility { visible: true },
+                WindowCommand::SetCursorPosition {
+                    position: Vec2::new(10.0, 20.0)
+                },
+            ]
+        );
+        assert_eq!(window.drain_commands().count(), 0);
+    }
+
+    #[test]
+    fn window_setters() {
+        let mut window = Window::new(WindowId::primary(), &WindowDescriptor::default());
+        let mode = WindowMode::Fullscreen { use_size: true };
+        window.set_mode(mode);
+        window.set_vsync(false);
+        window.set_title("title".to_string());
+        window.set_resolution(800, 600);
+        assert_eq!(window.mode, mode);
+        assert!(!window.vsync);
+        assert_eq!(window.title, "title");
+        // the size only changes once the window has been resized
+        assert_eq!((window.width, window.height), (1280, 720));
+        assert_eq!(
+            window.drain_commands().collect::<Vec<_>>(),
+            vec![
+                WindowCommand::SetWindowMode { mode },
+                WindowCommand::SetVsync { vsync: false },
+                WindowCommand::SetTitle {
+                    title: "title".to_string()
+                },
+                WindowCommand::SetResolution {
+                    width: 800,
+                    height: 600
+                },
+            ]
+        );
+    }
+}
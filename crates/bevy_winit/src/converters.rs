@@ -1,6 +1,7 @@
 use bevy_input::{
     keyboard::{ElementState, KeyCode, KeyboardInput},
     mouse::MouseButton,
+    touch::TouchPhase,
 };
 
 pub fn convert_keyboard_input(keyboard_input: &winit::event::KeyboardInput) -> KeyboardInput {
@@ -194,3 +195,12 @@ pub fn convert_virtual_key_code(virtual_key_code: winit::event::VirtualKeyCode)
         winit::event::VirtualKeyCode::Cut => KeyCode::Cut,
     }
 }
+
+pub fn convert_touch_phase(touch_phase: winit::event::TouchPhase) -> TouchPhase {
+    match touch_phase {
+        winit::event::TouchPhase::Started => TouchPhase::Started,
+        winit::event::TouchPhase::Moved => TouchPhase::Moved,
+        winit::event::TouchPhase::Ended => TouchPhase::Ended,
+        winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+    }
+}
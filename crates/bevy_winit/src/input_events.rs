@@ -0,0 +1,218 @@
+use bevy_app::Events;
+use bevy_ecs::Resources;
+use bevy_input::{
+    keyboard::KeyboardInput,
+    mouse::{MouseButtonInput, MouseMotion, MouseWheel},
+    touch::TouchInput,
+};
+use bevy_window::{CursorEntered, CursorLeft, CursorMoved, ReceivedCharacter};
+
+/// Cursor, mouse, and keyboard input in the order it was reported by the platform.
+///
+/// Each of these events is also sent to its own `Events<T>` collection, but the ordering between different event types
+/// within a frame is lost there. Read `InputEvent`s when ordering matters, ex: to know where the cursor was when a
+/// button was pressed. Coalesced events (see [InputCoalescing]) are sent when the next event of a different kind
+/// arrives, so they never move past an event that was reported after them.
+#[derive(Debug, Clone)]
+pub enum InputEvent {
+    CursorMoved(CursorMoved),
+    CursorEntered(CursorEntered),
+    CursorLeft(CursorLeft),
+    MouseMotion(MouseMotion),
+    MouseButton(MouseButtonInput),
+    MouseWheel(MouseWheel),
+    Keyboard(KeyboardInput),
+    Touch(TouchInput),
+    ReceivedCharacter(ReceivedCharacter),
+}
+
+/// Controls whether consecutive high frequency input events are merged into a single event
+#[derive(Debug, Clone)]
+pub struct InputCoalescing {
+    /// Only keep the latest of each run of consecutive `CursorMoved` events for a window. Runs end at any other event,
+    /// so the cursor never moves past an event reported between two cursor positions. Disable this to receive every
+    /// reported cursor position, ex: for drawing applications.
+    pub cursor_moved: bool,
+    /// Sum the deltas of consecutive `MouseMotion` events
+    pub mouse_motion: bool,
+}
+
+impl Default for InputCoalescing {
+    fn default() -> Self {
+        InputCoalescing {
+            cursor_moved: true,
+            mouse_motion: true,
+        }
+    }
+}
+
+/// Buffers coalesced input events until they can be sent without reordering them
+#[derive(Default)]
+pub(crate) struct InputEventBuffer {
+    pending: Vec<InputEvent>,
+}
+
+impl InputEventBuffer {
+    pub fn push(&mut self, resources: &Resources, event: InputEvent) {
+        let coalescing = resources
+            .get::<InputCoalescing>()
+            .map(|coalescing| (*coalescing).clone())
+            .unwrap_or_default();
+        match event {
+            InputEvent::CursorMoved(event) if coalescing.cursor_moved => {
+                match self.pending.last_mut() {
+                    Some(InputEvent::CursorMoved(pending)) if pending.id == event.id => {
+                        *pending = event
+                    }
+                    _ => self.pending.push(InputEvent::CursorMoved(event)),
+                }
+            }
+            InputEvent::MouseMotion(event) if coalescing.mouse_motion => {
+                let pending_mouse_motion =
+                    self.pending.iter_mut().find_map(|pending| match pending {
+                        InputEvent::MouseMotion(pending) => Some(pending),
+                        _ => None,
+                    });
+                if let Some(pending_mouse_motion) = pending_mouse_motion {
+                    pending_mouse_motion.delta += event.delta;
+                } else {
+                    self.pending.push(InputEvent::MouseMotion(event));
+                }
+            }
+            event => {
+                self.flush(resources);
+                send_input_event(resources, event);
+            }
+        }
+    }
+
+    pub fn flush(&mut self, resources: &Resources) {
+        for event in self.pending.drain(..) {
+            send_input_event(resources, event);
+        }
+    }
+}
+
+fn send_input_event(resources: &Resources, event: InputEvent) {
+    match event {
+        InputEvent::CursorMoved(ref event) => {
+            let mut events = resources.get_mut::<Events<CursorMoved>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::CursorEntered(ref event) => {
+            let mut events = resources.get_mut::<Events<CursorEntered>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::CursorLeft(ref event) => {
+            let mut events = resources.get_mut::<Events<CursorLeft>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::MouseMotion(ref event) => {
+            let mut events = resources.get_mut::<Events<MouseMotion>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::MouseButton(ref event) => {
+            let mut events = resources.get_mut::<Events<MouseButtonInput>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::MouseWheel(ref event) => {
+            let mut events = resources.get_mut::<Events<MouseWheel>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::Keyboard(ref event) => {
+            let mut events = resources.get_mut::<Events<KeyboardInput>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::Touch(ref event) => {
+            let mut events = resources.get_mut::<Events<TouchInput>>().unwrap();
+            events.send(event.clone());
+        }
+        InputEvent::ReceivedCharacter(ref event) => {
+            let mut events = resources.get_mut::<Events<ReceivedCharacter>>().unwrap();
+            events.send(event.clone());
+        }
+    }
+
+    if let Some(mut input_events) = resources.get_mut::<Events<InputEvent>>() {
+        input_events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InputEvent, InputEventBuffer};
+    use bevy_app::Events;
+    use bevy_ecs::Resources;
+    use bevy_input::{
+        keyboard::{ElementState, KeyboardInput},
+        mouse::{MouseButton, MouseButtonInput, MouseMotion, MouseWheel},
+        touch::TouchInput,
+    };
+    use bevy_math::Vec2;
+    use bevy_window::{CursorEntered, CursorLeft, CursorMoved, ReceivedCharacter, WindowId};
+
+    fn input_resources() -> Resources {
+        let mut resources = Resources::default();
+        resources.insert(Events::<CursorMoved>::default());
+        resources.insert(Events::<CursorEntered>::default());
+        resources.insert(Events::<CursorLeft>::default());
+        resources.insert(Events::<MouseMotion>::default());
+        resources.insert(Events::<MouseButtonInput>::default());
+        resources.insert(Events::<MouseWheel>::default());
+        resources.insert(Events::<KeyboardInput>::default());
+        resources.insert(Events::<TouchInput>::default());
+        resources.insert(Events::<ReceivedCharacter>::default());
+        resources.insert(Events::<InputEvent>::default());
+        resources
+    }
+
+    fn cursor_moved(id: WindowId, x: f32) -> InputEvent {
+        InputEvent::CursorMoved(CursorMoved {
+            id,
+            position: Vec2::new(x, 0.0),
+        })
+    }
+
+    #[test]
+    fn coalesce_consecutive_cursor_moves() {
+        let resources = input_resources();
+        let primary = WindowId::primary();
+        let secondary = WindowId::new();
+        let mut buffer = InputEventBuffer::default();
+        for event in vec![
+            cursor_moved(primary, 1.0),
+            cursor_moved(primary, 2.0),
+            InputEvent::MouseButton(MouseButtonInput {
+                button: MouseButton::Left,
+                state: ElementState::Pressed,
+            }),
+            cursor_moved(primary, 3.0),
+            cursor_moved(secondary, 4.0),
+            cursor_moved(primary, 5.0),
+            cursor_moved(primary, 6.0),
+        ] {
+            buffer.push(&resources, event);
+        }
+        buffer.flush(&resources);
+
+        let input_events = resources.get::<Events<InputEvent>>().unwrap();
+        let events = input_events
+            .get_reader()
+            .iter(&input_events)
+            .map(|event| match event {
+                InputEvent::CursorMoved(event) => Some((event.id, event.position.x())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                Some((primary, 2.0)),
+                None,
+                Some((primary, 3.0)),
+                Some((secondary, 4.0)),
+                Some((primary, 6.0)),
+            ]
+        );
+    }
+}
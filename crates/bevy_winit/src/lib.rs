@@ -1,17 +1,21 @@
 mod converters;
+mod input_events;
 mod winit_windows;
+pub use input_events::*;
 pub use winit_windows::*;
 
 use bevy_input::{
-    keyboard::KeyboardInput,
-    mouse::{MouseButtonInput, MouseMotion},
+    mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    touch::TouchInput,
 };
 
 use bevy_app::{prelude::*, AppExit};
 use bevy_ecs::Resources;
 use bevy_math::Vec2;
 use bevy_window::{
-    CreateWindow, CursorMoved, Window, WindowCloseRequested, WindowCreated, WindowResized, Windows,
+    CreateWindow, CursorEntered, CursorLeft, CursorMoved, ReceivedCharacter, SafeAreaInsets,
+    Window, WindowCloseRequested, WindowCommand, WindowCreated, WindowMode, WindowModeChanged,
+    WindowResized, WindowScaleFactorChanged, WindowVsyncChanged, Windows,
 };
 use winit::{
     event,
@@ -28,15 +32,22 @@ impl Plugin for WinitPlugin {
             // TODO: It would be great to provide a raw winit WindowEvent here, but the lifetime on it is
             // stopping us. there are plans to remove the lifetime: https://github.com/rust-windowing/winit/pull/1456
             // .add_event::<winit::event::WindowEvent>()
+            .add_event::<InputEvent>()
+            .init_resource::<InputCoalescing>()
             .init_resource::<WinitWindows>()
             .set_runner(winit_runner);
     }
+
+    fn requirements(&self, requirements: &mut PluginRequirements) {
+        requirements.requires::<Windows>();
+    }
 }
 
 pub fn winit_runner(mut app: App) {
     let event_loop = EventLoop::new();
     let mut create_window_event_reader = EventReader::<CreateWindow>::default();
     let mut app_exit_event_reader = EventReader::<AppExit>::default();
+    let mut input_event_buffer = InputEventBuffer::default();
 
     handle_create_window_events(
         &mut app.resources,
@@ -71,6 +82,11 @@ pub fn winit_runner(mut app: App) {
                 window.width = size.width;
                 window.height = size.height;
 
+                // the safe area changes with the window's size, ex: when a phone is rotated
+                let mut safe_area_insets = app.resources.get_mut::<SafeAreaInsets>().unwrap();
+                let winit_window = winit_windows.get_window(window_id).unwrap();
+                safe_area_insets.set(window_id, get_safe_area_insets(winit_window));
+
                 let mut resize_events = app.resources.get_mut::<Events<WindowResized>>().unwrap();
                 resize_events.send(WindowResized {
                     id: window_id,
@@ -92,52 +108,148 @@ pub fn winit_runner(mut app: App) {
                     let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
                     window_close_requested_events.send(WindowCloseRequested { id: window_id });
                 }
-                WindowEvent::KeyboardInput { ref input, .. } => {
-                    let mut keyboard_input_events =
-                        app.resources.get_mut::<Events<KeyboardInput>>().unwrap();
-                    keyboard_input_events.send(converters::convert_keyboard_input(input));
-                }
-                WindowEvent::CursorMoved { position, .. } => {
-                    let mut cursor_moved_events =
-                        app.resources.get_mut::<Events<CursorMoved>>().unwrap();
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                } => {
                     let winit_windows = app.resources.get_mut::<WinitWindows>().unwrap();
+                    let mut windows = app.resources.get_mut::<Windows>().unwrap();
                     let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
-                    let window = winit_windows.get_window(window_id).unwrap();
-                    let inner_size = window.inner_size();
-                    // move origin to bottom left
-                    let y_position = inner_size.height as f32 - position.y as f32;
-                    cursor_moved_events.send(CursorMoved {
+                    let mut window = windows.get_mut(window_id).unwrap();
+                    window.scale_factor = scale_factor;
+                    window.width = new_inner_size.width;
+                    window.height = new_inner_size.height;
+
+                    let mut safe_area_insets = app.resources.get_mut::<SafeAreaInsets>().unwrap();
+                    let winit_window = winit_windows.get_window(window_id).unwrap();
+                    safe_area_insets.set(window_id, get_safe_area_insets(winit_window));
+
+                    let mut scale_factor_changed_events = app
+                        .resources
+                        .get_mut::<Events<WindowScaleFactorChanged>>()
+                        .unwrap();
+                    scale_factor_changed_events.send(WindowScaleFactorChanged {
+                        id: window_id,
+                        scale_factor,
+                    });
+                    let mut resize_events =
+                        app.resources.get_mut::<Events<WindowResized>>().unwrap();
+                    resize_events.send(WindowResized {
                         id: window_id,
-                        position: Vec2::new(position.x as f32, y_position as f32),
+                        height: window.height as usize,
+                        width: window.width as usize,
                     });
                 }
+                WindowEvent::KeyboardInput { ref input, .. } => {
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::Keyboard(converters::convert_keyboard_input(input)),
+                    );
+                }
+                WindowEvent::ReceivedCharacter(char) => {
+                    let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                    let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::ReceivedCharacter(ReceivedCharacter {
+                            id: window_id,
+                            char,
+                        }),
+                    );
+                }
+                WindowEvent::CursorMoved { position, .. } => {
+                    let cursor_moved = {
+                        let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                        let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
+                        let window = winit_windows.get_window(window_id).unwrap();
+                        let inner_size = window.inner_size();
+                        // move origin to bottom left
+                        let y_position = inner_size.height as f32 - position.y as f32;
+                        CursorMoved {
+                            id: window_id,
+                            position: Vec2::new(position.x as f32, y_position as f32),
+                        }
+                    };
+                    input_event_buffer.push(&app.resources, InputEvent::CursorMoved(cursor_moved));
+                }
+                WindowEvent::CursorEntered { .. } => {
+                    let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                    let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::CursorEntered(CursorEntered { id: window_id }),
+                    );
+                }
+                WindowEvent::CursorLeft { .. } => {
+                    let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                    let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::CursorLeft(CursorLeft { id: window_id }),
+                    );
+                }
+                WindowEvent::Touch(touch) => {
+                    let touch_input = {
+                        let winit_windows = app.resources.get::<WinitWindows>().unwrap();
+                        let window_id = winit_windows.get_window_id(winit_window_id).unwrap();
+                        let window = winit_windows.get_window(window_id).unwrap();
+                        let inner_size = window.inner_size();
+                        // move origin to bottom left
+                        let y_position = inner_size.height as f32 - touch.location.y as f32;
+                        TouchInput {
+                            phase: converters::convert_touch_phase(touch.phase),
+                            position: Vec2::new(touch.location.x as f32, y_position),
+                            id: touch.id,
+                        }
+                    };
+                    input_event_buffer.push(&app.resources, InputEvent::Touch(touch_input));
+                }
                 WindowEvent::MouseInput { state, button, .. } => {
-                    let mut mouse_button_input_events =
-                        app.resources.get_mut::<Events<MouseButtonInput>>().unwrap();
-                    mouse_button_input_events.send(MouseButtonInput {
-                        button: converters::convert_mouse_button(button.into()),
-                        state: converters::convert_element_state(state),
-                    });
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::MouseButton(MouseButtonInput {
+                            button: converters::convert_mouse_button(button.into()),
+                            state: converters::convert_element_state(state),
+                        }),
+                    );
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let mouse_wheel = match delta {
+                        event::MouseScrollDelta::LineDelta(x, y) => MouseWheel {
+                            unit: MouseScrollUnit::Line,
+                            x,
+                            y,
+                        },
+                        event::MouseScrollDelta::PixelDelta(position) => MouseWheel {
+                            unit: MouseScrollUnit::Pixel,
+                            x: position.x as f32,
+                            y: position.y as f32,
+                        },
+                    };
+                    input_event_buffer.push(&app.resources, InputEvent::MouseWheel(mouse_wheel));
                 }
                 _ => {}
             },
             event::Event::DeviceEvent { ref event, .. } => match event {
                 DeviceEvent::MouseMotion { delta } => {
-                    let mut mouse_motion_events =
-                        app.resources.get_mut::<Events<MouseMotion>>().unwrap();
-                    mouse_motion_events.send(MouseMotion {
-                        delta: Vec2::new(delta.0 as f32, delta.1 as f32),
-                    });
+                    input_event_buffer.push(
+                        &app.resources,
+                        InputEvent::MouseMotion(MouseMotion {
+                            delta: Vec2::new(delta.0 as f32, delta.1 as f32),
+                        }),
+                    );
                 }
                 _ => {}
             },
             event::Event::MainEventsCleared => {
+                input_event_buffer.flush(&app.resources);
                 handle_create_window_events(
                     &mut app.resources,
                     event_loop,
                     &mut create_window_event_reader,
                 );
                 app.update();
+                handle_window_commands(&app.resources);
             }
             _ => (),
         }
@@ -153,11 +265,83 @@ fn handle_create_window_events(
     let mut windows = resources.get_mut::<Windows>().unwrap();
     let create_window_events = resources.get::<Events<CreateWindow>>().unwrap();
     let mut window_created_events = resources.get_mut::<Events<WindowCreated>>().unwrap();
+    let mut safe_area_insets = resources.get_mut::<SafeAreaInsets>().unwrap();
     for create_window_event in create_window_event_reader.iter(&create_window_events) {
-        let window = Window::new(create_window_event.id, &create_window_event.descriptor);
+        let mut window = Window::new(create_window_event.id, &create_window_event.descriptor);
         winit_windows.create_window(event_loop, &window);
+        let winit_window = winit_windows.get_window(window.id).unwrap();
+        window.scale_factor = winit_window.scale_factor();
+        safe_area_insets.set(window.id, get_safe_area_insets(winit_window));
         let window_id = window.id;
         windows.add(window);
         window_created_events.send(WindowCreated { id: window_id });
     }
 }
+
+/// Applies the changes queued on [Window]s to their winit windows
+fn handle_window_commands(resources: &Resources) {
+    let winit_windows = resources.get::<WinitWindows>().unwrap();
+    let mut windows = resources.get_mut::<Windows>().unwrap();
+    let mut window_mode_changed_events = resources.get_mut::<Events<WindowModeChanged>>().unwrap();
+    let mut window_vsync_changed_events =
+        resources.get_mut::<Events<WindowVsyncChanged>>().unwrap();
+    for window in windows.iter_mut() {
+        let winit_window = match winit_windows.get_window(window.id) {
+            Some(winit_window) => winit_window,
+            None => continue,
+        };
+        let commands = window.drain_commands().collect::<Vec<_>>();
+        for command in commands {
+            match command {
+                WindowCommand::SetTitle { title } => {
+                    winit_window.set_title(&title);
+                }
+                WindowCommand::SetWindowMode { mode } => {
+                    winit_window.set_fullscreen(get_fullscreen(
+                        mode,
+                        winit_window.current_monitor(),
+                        window,
+                    ));
+                    if mode == WindowMode::Windowed {
+                        winit_window.set_resizable(window.resizable);
+                    }
+                    window_mode_changed_events.send(WindowModeChanged {
+                        id: window.id,
+                        mode,
+                    });
+                }
+                WindowCommand::SetResolution { width, height } => {
+                    winit_window.set_inner_size(winit::dpi::PhysicalSize::new(width, height));
+                }
+                WindowCommand::SetResizable { resizable } => {
+                    winit_window.set_resizable(resizable);
+                }
+                WindowCommand::SetVsync { vsync } => {
+                    // vsync is a property of the swap chain, which renderers recreate when they receive this event
+                    window_vsync_changed_events.send(WindowVsyncChanged {
+                        id: window.id,
+                        vsync,
+                    });
+                }
+                WindowCommand::SetCursorLockMode { locked } => {
+                    if let Err(err) = winit_window.set_cursor_grab(locked) {
+                        log::warn!("failed to set the cursor lock mode: {}", err);
+                    }
+                }
+                WindowCommand::SetCursorVisibility { visible } => {
+                    winit_window.set_cursor_visible(visible);
+                }
+                WindowCommand::SetCursorPosition { position } => {
+                    // move origin to top left
+                    let position = winit::dpi::PhysicalPosition::new(
+                        position.x() as f64,
+                        window.height as f64 - position.y() as f64,
+                    );
+                    if let Err(err) = winit_window.set_cursor_position(position) {
+                        log::warn!("failed to set the cursor position: {}", err);
+                    }
+                }
+            }
+        }
+    }
+}
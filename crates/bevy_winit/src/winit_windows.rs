@@ -1,3 +1,4 @@
+use bevy_math::Rect;
 use bevy_window::{Window, WindowId, WindowMode};
 use std::collections::HashMap;
 
@@ -24,18 +25,14 @@ impl WinitWindows {
         let mut winit_window_builder = winit::window::WindowBuilder::new();
 
         winit_window_builder = match window.mode {
-            WindowMode::BorderlessFullscreen => winit_window_builder.with_fullscreen(Some(
-                winit::window::Fullscreen::Borderless(event_loop.primary_monitor()),
-            )),
-            WindowMode::Fullscreen { use_size } => winit_window_builder.with_fullscreen(Some(
-                winit::window::Fullscreen::Exclusive(match use_size {
-                    true => get_fitting_videomode(&event_loop.primary_monitor(), &window),
-                    false => get_best_videomode(&event_loop.primary_monitor()),
-                }),
-            )),
-            _ => winit_window_builder
+            WindowMode::Windowed => winit_window_builder
                 .with_inner_size(winit::dpi::PhysicalSize::new(window.width, window.height))
                 .with_resizable(window.resizable),
+            mode => winit_window_builder.with_fullscreen(get_fullscreen(
+                mode,
+                event_loop.primary_monitor(),
+                window,
+            )),
         };
 
         let winit_window = winit_window_builder
@@ -43,6 +40,13 @@ impl WinitWindows {
             .build(&event_loop)
             .unwrap();
 
+        if window.cursor_locked() {
+            if let Err(err) = winit_window.set_cursor_grab(true) {
+                log::warn!("failed to lock the cursor: {}", err);
+            }
+        }
+        winit_window.set_cursor_visible(window.cursor_visible());
+
         self.window_id_to_winit.insert(window.id, winit_window.id());
         self.winit_to_window_id.insert(winit_window.id(), window.id);
 
@@ -59,6 +63,55 @@ impl WinitWindows {
         self.winit_to_window_id.get(&id).cloned()
     }
 }
+
+/// Returns the distance (in logical pixels) from each edge of the window to its safe area. winit only reports a safe area
+/// on iOS, as the window's inner rect. Elsewhere the inner rect only excludes decorations, so the insets are zero.
+pub fn get_safe_area_insets(winit_window: &winit::window::Window) -> Rect<f32> {
+    #[cfg(target_os = "ios")]
+    {
+        let (inner_position, outer_position) =
+            match (winit_window.inner_position(), winit_window.outer_position()) {
+                (Ok(inner_position), Ok(outer_position)) => (inner_position, outer_position),
+                _ => return Rect::default(),
+            };
+        let inner_size = winit_window.inner_size();
+        let outer_size = winit_window.outer_size();
+        let left = (inner_position.x - outer_position.x) as f32;
+        let top = (inner_position.y - outer_position.y) as f32;
+        let scale_factor = winit_window.scale_factor() as f32;
+        Rect {
+            left: left / scale_factor,
+            right: (outer_size.width as f32 - inner_size.width as f32 - left) / scale_factor,
+            top: top / scale_factor,
+            bottom: (outer_size.height as f32 - inner_size.height as f32 - top) / scale_factor,
+        }
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    {
+        let _ = winit_window;
+        Rect::default()
+    }
+}
+
+/// Returns the winit fullscreen mode of `mode` on `monitor`, or `None` for [WindowMode::Windowed]
+pub fn get_fullscreen(
+    mode: WindowMode,
+    monitor: winit::monitor::MonitorHandle,
+    window: &Window,
+) -> Option<winit::window::Fullscreen> {
+    match mode {
+        WindowMode::Windowed => None,
+        WindowMode::BorderlessFullscreen => Some(winit::window::Fullscreen::Borderless(monitor)),
+        WindowMode::Fullscreen { use_size } => {
+            Some(winit::window::Fullscreen::Exclusive(match use_size {
+                true => get_fitting_videomode(&monitor, window),
+                false => get_best_videomode(&monitor),
+            }))
+        }
+    }
+}
+
 fn get_fitting_videomode(
     monitor: &winit::monitor::MonitorHandle,
     window: &Window,
@@ -0,0 +1,115 @@
+use bevy::{prelude::*, render::texture::TextureFormat};
+
+/// This example lights a normal mapped floor with two 2D point lights, and casts shadows from a few walls
+fn main() {
+    App::build()
+        .add_resource(AmbientLight2d {
+            color: Color::rgb(0.05, 0.05, 0.08),
+        })
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(orbit_system.system())
+        .run();
+}
+
+struct Orbit {
+    radius: f32,
+    speed: f32,
+}
+
+fn orbit_system(time: Res<Time>, mut query: Query<(&Orbit, &mut Translation)>) {
+    for (orbit, mut translation) in &mut query.iter() {
+        let angle = time.seconds_since_startup as f32 * orbit.speed;
+        *translation.x_mut() = angle.cos() * orbit.radius;
+        *translation.y_mut() = angle.sin() * orbit.radius;
+    }
+}
+
+/// Builds a tangent space normal map of rounded tiles
+fn tile_normal_map(size: usize, tile_size: usize) -> Texture {
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            // the offset from the center of the tile, from -1.0 to 1.0
+            let offset = |value: usize| (value % tile_size) as f32 / tile_size as f32 * 2.0 - 1.0;
+            let slope = |offset: f32| offset.powi(5) * 2.0;
+            // texture rows go down, while normal maps point their green channel up
+            let normal = Vec3::new(slope(offset(x)), -slope(offset(y)), 1.0).normalize();
+            data.extend_from_slice(&[
+                ((normal.x() * 0.5 + 0.5) * 255.0) as u8,
+                ((normal.y() * 0.5 + 0.5) * 255.0) as u8,
+                ((normal.z() * 0.5 + 0.5) * 255.0) as u8,
+                255,
+            ]);
+        }
+    }
+    Texture::new(
+        Vec2::new(size as f32, size as f32),
+        data,
+        TextureFormat::Rgba8Unorm,
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut textures: ResMut<Assets<Texture>>,
+) {
+    let floor_material = materials.add(ColorMaterial {
+        normal_map: Some(textures.add(tile_normal_map(512, 64))),
+        ..ColorMaterial::color(Color::rgb(0.8, 0.75, 0.7))
+    });
+    let wall_material = materials.add(Color::rgb(0.3, 0.3, 0.35).into());
+
+    commands
+        .spawn(Camera2dComponents::default())
+        .spawn(SpriteComponents {
+            material: floor_material,
+            sprite: Sprite::new(Vec2::new(1024.0, 1024.0)),
+            ..Default::default()
+        });
+
+    // walls are sprites with an occluder of the same size
+    let walls = [
+        (Vec3::new(-150.0, 60.0, 1.0), Vec2::new(40.0, 160.0)),
+        (Vec3::new(120.0, -100.0, 1.0), Vec2::new(200.0, 40.0)),
+        (Vec3::new(220.0, 180.0, 1.0), Vec2::new(60.0, 60.0)),
+    ];
+    for (position, size) in walls.iter() {
+        commands
+            .spawn(SpriteComponents {
+                material: wall_material,
+                sprite: Sprite::new(*size),
+                translation: Translation(*position),
+                ..Default::default()
+            })
+            .with(Occluder2d::new(*size));
+    }
+
+    // a warm light with soft shadows circles the scene, while a red light casts hard shadows
+    commands
+        .spawn((
+            PointLight2d {
+                color: Color::rgb(1.0, 0.9, 0.7),
+                intensity: 1.5,
+                radius: 450.0,
+                source_radius: 20.0,
+                ..Default::default()
+            },
+            Transform::default(),
+            Translation::default(),
+            Orbit {
+                radius: 250.0,
+                speed: 0.5,
+            },
+        ))
+        .spawn((
+            PointLight2d {
+                color: Color::rgb(1.0, 0.2, 0.1),
+                radius: 300.0,
+                ..Default::default()
+            },
+            Transform::default(),
+            Translation::new(-300.0, -250.0, 0.0),
+        ));
+}
@@ -0,0 +1,56 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, PrintDiagnosticsPlugin},
+    prelude::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// This example spawns 10,000 moving sprites that share two materials. Sprites with the same material and depth are
+/// drawn together, so this only takes a few draw calls.
+/// For the best results, run it in release mode: ```cargo run --example many_sprites --release
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(PrintDiagnosticsPlugin::default())
+        .add_startup_system(setup.system())
+        .add_system(move_sprites.system())
+        .run();
+}
+
+struct Velocity(Vec2);
+
+fn move_sprites(time: Res<Time>, mut query: Query<(&Velocity, &mut Translation)>) {
+    for (velocity, mut translation) in &mut query.iter() {
+        let mut position = translation.0.truncate() + velocity.0 * time.delta_seconds;
+        // wrap around the edges of the window
+        position.set_x((position.x() + 640.0).rem_euclid(1280.0) - 640.0);
+        position.set_y((position.y() + 360.0).rem_euclid(720.0) - 360.0);
+        *translation.x_mut() = position.x();
+        *translation.y_mut() = position.y();
+    }
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let red = materials.add(Color::rgb(0.8, 0.3, 0.3).into());
+    let blue = materials.add(Color::rgb(0.3, 0.3, 0.8).into());
+    let mut rng = StdRng::from_entropy();
+
+    commands.spawn(Camera2dComponents::default());
+    for i in 0..10_000 {
+        commands
+            .spawn(SpriteComponents {
+                material: if i % 2 == 0 { red } else { blue },
+                sprite: Sprite::new(Vec2::new(16.0, 16.0)),
+                translation: Translation::new(
+                    rng.gen_range(-640.0, 640.0),
+                    rng.gen_range(-360.0, 360.0),
+                    0.0,
+                ),
+                ..Default::default()
+            })
+            .with(Velocity(Vec2::new(
+                rng.gen_range(-50.0, 50.0),
+                rng.gen_range(-50.0, 50.0),
+            )));
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::{prelude::*, render::texture::TextureFormat};
+
+/// This example scrolls the camera over three parallax layers of hills, which repeat endlessly along the x axis
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(scroll_system.system())
+        .run();
+}
+
+struct Scroll {
+    speed: f32,
+}
+
+fn scroll_system(time: Res<Time>, mut query: Query<(&Scroll, &mut Translation)>) {
+    for (scroll, mut translation) in &mut query.iter() {
+        *translation.x_mut() += scroll.speed * time.delta_seconds;
+    }
+}
+
+/// Builds a texture of hills with the given color, which wraps around horizontally
+fn hills_texture(width: usize, height: usize, frequency: f32, color: [u8; 3]) -> Texture {
+    let mut data = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let angle = x as f32 / width as f32 * std::f32::consts::PI * 2.0 * frequency;
+            let hill_height =
+                (0.5 + 0.25 * angle.sin() + 0.1 * (angle * 3.0).cos()) * height as f32;
+            // texture rows go down, so the ground is at the end of the data
+            let alpha = if (height - y) as f32 <= hill_height {
+                255
+            } else {
+                0
+            };
+            data.extend_from_slice(&[color[0], color[1], color[2], alpha]);
+        }
+    }
+    Texture::new(
+        Vec2::new(width as f32, height as f32),
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn(Camera2dComponents::default())
+        .with(Scroll { speed: 150.0 });
+
+    // further layers follow more of the camera's movement, so they scroll slower
+    let layers = [
+        (0.8, 3.0, [70, 80, 120], 150.0),
+        (0.5, 2.0, [50, 90, 70], 0.0),
+        (0.2, 4.0, [30, 60, 30], -150.0),
+    ];
+    for (depth, (factor, frequency, color, y)) in layers.iter().enumerate() {
+        let texture = textures.add(hills_texture(512, 256, *frequency, *color));
+        commands
+            .spawn(SpriteComponents {
+                material: materials.add(texture.into()),
+                translation: Translation::new(0.0, 0.0, depth as f32),
+                draw: Draw {
+                    is_transparent: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with(ParallaxLayer {
+                offset: Vec2::new(0.0, *y),
+                repeat_x: true,
+                ..ParallaxLayer::new(Vec2::new(*factor, 1.0))
+            });
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+/// This example renders the scene at 320x180 and scales it up to the window by whole numbers. The camera moves by
+/// fractions of a pixel, while the sprites stay on the pixel grid.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(camera_movement_system.system())
+        .run();
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands
+        .spawn(Camera2dComponents::default())
+        .with(PixelCamera::new(320.0, 180.0).with_smoothing());
+
+    for i in 0..10 {
+        commands.spawn(SpriteComponents {
+            material: materials.add(Color::rgb(0.2 + 0.08 * i as f32, 0.5, 0.8).into()),
+            sprite: Sprite::new(Vec2::new(15.0, 15.0)),
+            translation: Translation::new(-140.0 + 30.0 * i as f32, 0.3 * i as f32, 0.0),
+            ..Default::default()
+        });
+    }
+}
+
+fn camera_movement_system(time: Res<Time>, mut query: Query<(&PixelCamera, &mut Translation)>) {
+    for (_pixel_camera, mut translation) in &mut query.iter() {
+        *translation.x_mut() = (time.seconds_since_startup as f32 * 0.5).sin() * 40.0;
+    }
+}
@@ -4,23 +4,9 @@ fn main() {
     App::build()
         .add_default_plugins()
         .add_startup_system(setup.system())
-        .add_system(animate_sprite_system.system())
         .run();
 }
 
-fn animate_sprite_system(
-    texture_atlases: Res<Assets<TextureAtlas>>,
-    mut query: Query<(&mut Timer, &mut TextureAtlasSprite, &Handle<TextureAtlas>)>,
-) {
-    for (mut timer, mut sprite, texture_atlas_handle) in &mut query.iter() {
-        if timer.finished {
-            let texture_atlas = texture_atlases.get(&texture_atlas_handle).unwrap();
-            sprite.index = ((sprite.index as usize + 1) % texture_atlas.textures.len()) as u32;
-            timer.reset();
-        }
-    }
-}
-
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -43,5 +29,8 @@ fn setup(
             scale: Scale(6.0),
             ..Default::default()
         })
-        .with(Timer::from_seconds(0.1));
+        .with(SpriteSheetAnimation::new(AnimationClip::from_range(
+            0..7,
+            0.1,
+        )));
 }
@@ -21,9 +21,7 @@ pub struct RpgSpriteHandles {
 }
 
 fn setup(mut rpg_sprite_handles: ResMut<RpgSpriteHandles>, asset_server: Res<AssetServer>) {
-    rpg_sprite_handles.handles = asset_server
-        .load_asset_folder("assets/textures/rpg")
-        .unwrap();
+    rpg_sprite_handles.handles = asset_server.load_folder("assets/textures/rpg").unwrap();
 }
 
 fn load_atlas(
@@ -0,0 +1,139 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ActiveCameras, Camera},
+    window::CursorMoved,
+};
+
+/// This example moves a cube to the point on the ground under the cursor when the ground is clicked. Clicks on the
+/// button are handled by the ui, and PointerOverUi keeps them from reaching the ground behind it.
+fn main() {
+    App::build()
+        .add_resource(Msaa { samples: 4 })
+        .add_default_plugins()
+        .init_resource::<CursorState>()
+        .add_startup_system(setup.system())
+        .add_system(picking_system.system())
+        .add_system(reset_button_system.system())
+        .run();
+}
+
+#[derive(Default)]
+struct CursorState {
+    cursor_moved_event_reader: EventReader<CursorMoved>,
+    position: Vec2,
+}
+
+struct Marker;
+
+fn picking_system(
+    mut state: ResMut<CursorState>,
+    cursor_moved_events: Res<Events<CursorMoved>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    pointer_over_ui: Res<PointerOverUi>,
+    active_cameras: Res<ActiveCameras>,
+    windows: Res<Windows>,
+    camera_query: Query<(&Camera, &Transform)>,
+    mut marker_query: Query<(&Marker, &mut Translation)>,
+) {
+    if let Some(cursor_moved) = state.cursor_moved_event_reader.latest(&cursor_moved_events) {
+        state.position = cursor_moved.position;
+    }
+    // clicks on the ui shouldn't move the marker
+    if !mouse_button_input.just_pressed(MouseButton::Left) || pointer_over_ui.is_over_ui() {
+        return;
+    }
+
+    let camera_entity = active_cameras.get("Camera3d").unwrap();
+    let camera = camera_query.get::<Camera>(camera_entity).unwrap();
+    let camera_transform = camera_query.get::<Transform>(camera_entity).unwrap();
+    let ray = match camera.screen_to_world_ray(&windows, &camera_transform, state.position) {
+        Some(ray) => ray,
+        None => return,
+    };
+    if let Some(distance) = ray.intersect_plane(Vec3::zero(), Vec3::unit_y()) {
+        for (_marker, mut translation) in &mut marker_query.iter() {
+            translation.0 = ray.point_at(distance) + Vec3::new(0.0, 0.5, 0.0);
+        }
+    }
+}
+
+fn reset_button_system(
+    mut interaction_query: Query<(&Button, Mutated<Interaction>)>,
+    mut marker_query: Query<(&Marker, &mut Translation)>,
+) {
+    for (_button, interaction) in &mut interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            for (_marker, mut translation) in &mut marker_query.iter() {
+                translation.0 = Vec3::new(0.0, 0.5, 0.0);
+            }
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        // ground
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 20.0 })),
+            material: materials.add(Color::rgb(0.1, 0.2, 0.1).into()),
+            ..Default::default()
+        })
+        // marker
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 0.5 })),
+            material: materials.add(Color::rgb(0.8, 0.3, 0.3).into()),
+            translation: Translation::new(0.0, 0.5, 0.0),
+            ..Default::default()
+        })
+        .with(Marker)
+        .spawn(LightComponents {
+            translation: Translation::new(4.0, 8.0, 4.0),
+            ..Default::default()
+        })
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(-6.0, 10.0, 10.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        })
+        // ui
+        .spawn(UiCameraComponents::default())
+        .spawn(ButtonComponents {
+            style: Style {
+                size: Size::new(Val::Px(150.0), Val::Px(65.0)),
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..Default::default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: color_materials.add(Color::rgb(0.05, 0.05, 0.05).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextComponents {
+                text: Text {
+                    value: "Reset".to_string(),
+                    font: asset_server.load("assets/fonts/FiraSans-Bold.ttf").unwrap(),
+                    style: TextStyle {
+                        font_size: 40.0,
+                        color: Color::rgb(0.8, 0.8, 0.8),
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        });
+}
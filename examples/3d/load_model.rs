@@ -11,18 +11,17 @@ fn main() {
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut scene_spawner: ResMut<SceneSpawner>,
 ) {
+    // load the model's scene, which has its meshes, materials, and node hierarchy
+    let scene_handle: Handle<Scene> = asset_server
+        .load("assets/models/monkey/Monkey.gltf")
+        .unwrap();
+    // the scene is spawned once it has loaded
+    scene_spawner.instance(scene_handle);
+
     // add entities to the world
     commands
-        // mesh
-        .spawn(PbrComponents {
-            // load the mesh
-            mesh: asset_server.load("assets/models/monkey/Monkey.gltf").unwrap(),
-            // create a material for the mesh
-            material: materials.add(Color::rgb(0.5, 0.4, 0.3).into()),
-            ..Default::default()
-        })
         // light
         .spawn(LightComponents {
             translation: Translation::new(4.0, 5.0, 4.0),
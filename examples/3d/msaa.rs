@@ -2,15 +2,24 @@ use bevy::prelude::*;
 
 /// This example shows how to configure Multi-Sample Anti-Aliasing. Setting the sample count higher will result in smoother edges,
 /// but it will also increase the cost to render those edges. The range should generally be somewhere between 1 (no multi sampling,
-/// but cheap) to 8 (crisp but expensive)
+/// but cheap) to 8 (crisp but expensive). The sample count can also be changed while the app is running: press "M" to
+/// toggle msaa.
 fn main() {
     App::build()
         .add_resource(Msaa { samples: 4 })
         .add_default_plugins()
         .add_startup_system(setup.system())
+        .add_system(toggle_msaa_system.system())
         .run();
 }
 
+fn toggle_msaa_system(keyboard_input: Res<Input<KeyCode>>, mut msaa: ResMut<Msaa>) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        msaa.samples = if msaa.samples > 1 { 1 } else { 4 };
+        println!("msaa samples: {}", msaa.samples);
+    }
+}
+
 /// set up a simple 3D scene
 fn setup(
     mut commands: Commands,
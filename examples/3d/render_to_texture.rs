@@ -0,0 +1,98 @@
+use bevy::{prelude::*, render::camera::Camera};
+
+/// This example renders the scene from above into a texture, and shows the texture as a minimap in the UI
+fn main() {
+    App::build()
+        .add_resource(Msaa { samples: 4 })
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(rotator_system.system())
+        .run();
+}
+
+struct Rotator;
+
+fn rotator_system(time: Res<Time>, mut query: Query<(&Rotator, &mut Rotation)>) {
+    for (_rotator, mut rotation) in &mut query.iter() {
+        rotation.0 = rotation.0 * Quat::from_rotation_y(time.delta_seconds);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let minimap_texture = textures.add(Texture::new_render_target(Vec2::new(256.0, 256.0)));
+
+    commands
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 10.0 })),
+            material: materials.add(Color::rgb(0.1, 0.2, 0.1).into()),
+            ..Default::default()
+        })
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: materials.add(Color::rgb(0.5, 0.4, 0.3).into()),
+            translation: Translation::new(0.0, 1.0, 0.0),
+            ..Default::default()
+        })
+        .with(Rotator)
+        // the marker is on layer 1, which only the minimap camera renders
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Icosphere {
+                radius: 0.5,
+                subdivisions: 2,
+            })),
+            material: materials.add(Color::rgb(1.0, 0.1, 0.1).into()),
+            translation: Translation::new(0.0, 3.0, 0.0),
+            ..Default::default()
+        })
+        .with(RenderLayers::layer(1))
+        .spawn(LightComponents {
+            translation: Translation::new(4.0, 8.0, 4.0),
+            ..Default::default()
+        })
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(-3.0, 5.0, 8.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        })
+        // the minimap camera looks straight down and renders into the minimap texture. it needs its own name, as the
+        // main camera is already called "Camera3d"
+        .spawn(Camera3dComponents {
+            camera: Camera {
+                name: Some("Minimap".to_string()),
+                render_target: Some(minimap_texture),
+                ..Default::default()
+            },
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(0.0, 12.0, 0.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, -1.0),
+            )),
+            ..Default::default()
+        })
+        .with(RenderLayers::layer(0).with(1))
+        // ui is drawn by its own pass, so the minimap camera doesn't see the image of its own texture
+        .spawn(UiCameraComponents::default())
+        .spawn(ImageComponents {
+            style: Style {
+                size: Size::new(Val::Px(256.0), Val::Px(256.0)),
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            material: color_materials.add(minimap_texture.into()),
+            ..Default::default()
+        });
+}
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+/// This example shows how to make lights cast shadows
+fn main() {
+    App::build()
+        .add_resource(Msaa { samples: 4 })
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(orbit_light_system.system())
+        .run();
+}
+
+/// orbits the point light around the scene
+struct Orbit;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let cube_mesh = meshes.add(Mesh::from(shape::Cube { size: 0.5 }));
+    let cube_material = materials.add(Color::rgb(0.8, 0.7, 0.6).into());
+    commands
+        // plane
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 20.0 })),
+            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+            ..Default::default()
+        });
+    for x in -2i32..=2 {
+        for z in -2..=2 {
+            commands.spawn(PbrComponents {
+                mesh: cube_mesh,
+                material: cube_material,
+                translation: Translation::new(
+                    x as f32 * 2.0,
+                    0.5 + (x + z).abs() as f32 * 0.25,
+                    z as f32 * 2.0,
+                ),
+                ..Default::default()
+            });
+        }
+    }
+
+    commands
+        // directional light, facing the center of the scene like the sun
+        .spawn(LightComponents {
+            light: Light {
+                color: Color::rgb(0.6, 0.6, 0.5),
+                kind: LightKind::Directional { shadow_extent: 8.0 },
+                shadows_enabled: true,
+                ..Default::default()
+            },
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(-10.0, 15.0, 5.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        })
+        // point light
+        .spawn(LightComponents {
+            light: Light {
+                color: Color::rgb(0.8, 0.3, 0.2),
+                shadows_enabled: true,
+                ..Default::default()
+            },
+            translation: Translation::new(3.0, 2.0, 0.0),
+            ..Default::default()
+        })
+        .with(Orbit)
+        // camera
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(-8.0, 10.0, 12.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        });
+}
+
+fn orbit_light_system(time: Res<Time>, mut query: Query<(&Orbit, &mut Translation)>) {
+    let angle = time.seconds_since_startup as f32 * 0.5;
+    for (_orbit, mut translation) in &mut query.iter() {
+        translation.0 = Vec3::new(angle.cos() * 3.0, 2.0, angle.sin() * 3.0);
+    }
+}
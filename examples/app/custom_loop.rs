@@ -0,0 +1,62 @@
+use bevy::{
+    app::{AppExit, AppStatus},
+    core::CorePlugin,
+    prelude::*,
+    type_registry::TypeRegistryPlugin,
+};
+use std::time::Duration;
+
+/// This example drives an App from its own loop instead of a runner. This is useful when the loop is owned by
+/// something else, ex: an editor, a simulation, or a test harness.
+fn main() {
+    let mut app = App::build();
+    app.add_plugin(TypeRegistryPlugin::default())
+        .add_plugin(CorePlugin)
+        .add_event::<Command>()
+        .init_resource::<Position>()
+        .add_system(command_system.system());
+    let mut stepper = app.stepper();
+
+    let commands = [Command::Move(1.0), Command::Move(2.5), Command::Quit];
+    for command in commands.iter() {
+        stepper.send_event(*command);
+        // each step advances time by exactly 1/60th of a second
+        if stepper.step_with_delta(Duration::from_secs_f64(1.0 / 60.0)) == AppStatus::Exit {
+            break;
+        }
+    }
+
+    let position = stepper.app.resources.get::<Position>().unwrap();
+    println!("final position: {}", position.0);
+}
+
+/// An event sent from outside the App
+#[derive(Copy, Clone)]
+enum Command {
+    Move(f32),
+    Quit,
+}
+
+#[derive(Default)]
+struct Position(f32);
+
+fn command_system(
+    time: Res<Time>,
+    mut reader: Local<EventReader<Command>>,
+    commands: Res<Events<Command>>,
+    mut position: ResMut<Position>,
+    mut app_exit_events: ResMut<Events<AppExit>>,
+) {
+    for command in reader.iter(&commands) {
+        match command {
+            Command::Move(distance) => {
+                position.0 += distance;
+                println!(
+                    "moved to {} at {:.3} seconds",
+                    position.0, time.seconds_since_startup
+                );
+            }
+            Command::Quit => app_exit_events.send(AppExit),
+        }
+    }
+}
@@ -16,9 +16,7 @@ fn setup(
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     // You can load all assets in a folder like this. They will be loaded in parallel without blocking
-    asset_server
-        .load_asset_folder("assets/models/monkey")
-        .unwrap();
+    asset_server.load_folder("assets/models/monkey").unwrap();
 
     // Then any asset in the folder can be accessed like this:
     let monkey_handle = asset_server
@@ -0,0 +1,33 @@
+use bevy::{asset::MemoryAssetIo, prelude::*};
+
+/// This example illustrates loading assets that are embedded in the executable, using a custom AssetIo
+fn main() {
+    // MemoryAssetIo serves assets from memory. Embedded assets are added without copying them.
+    let asset_io = MemoryAssetIo::default();
+    asset_io.insert(
+        "assets/branding/icon.png",
+        &include_bytes!("../../assets/branding/icon.png")[..],
+    );
+
+    App::build()
+        .add_default_plugins()
+        // Tell the asset server to read assets from memory instead of the filesystem:
+        .set_asset_io(asset_io)
+        .add_startup_system(setup.system())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Assets are loaded the same way, no matter where they are read from
+    let texture_handle = asset_server.load("assets/branding/icon.png").unwrap();
+    commands
+        .spawn(Camera2dComponents::default())
+        .spawn(SpriteComponents {
+            material: materials.add(texture_handle.into()),
+            ..Default::default()
+        });
+}
@@ -5,6 +5,11 @@ use bevy::prelude::*;
 /// This example illustrates hot reloading mesh changes.
 fn main() {
     App::build()
+        // Tell the asset server to watch for asset changes on disk:
+        .add_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..Default::default()
+        })
         .add_default_plugins()
         .add_startup_system(setup.system())
         .run();
@@ -20,9 +25,6 @@ fn setup(
         .load("assets/models/monkey/Monkey.gltf")
         .unwrap();
 
-    // Tell the asset server to watch for asset changes on disk:
-    asset_server.watch_for_changes().unwrap();
-
     // Any changes to the mesh will be reloaded automatically! Try making a change to Monkey.gltf.
     // You should see the changes immediately show up in your app.
 
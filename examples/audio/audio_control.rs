@@ -0,0 +1,66 @@
+use bevy::{
+    input::{keyboard::KeyCode, Input},
+    prelude::*,
+};
+
+/// This example illustrates how to control playing audio: Space pauses and resumes the music, Up and Down change the
+/// music volume, Left and Right change its speed, and S stops it
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(music_control_system.system())
+        .run();
+}
+
+struct Music(AudioSink);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, audio_output: Res<AudioOutput>) {
+    let music = asset_server
+        .load("assets/sounds/Windless Slopes.mp3")
+        .unwrap();
+    let sink = audio_output.play_with_settings(
+        music,
+        PlaybackSettings {
+            looping: true,
+            channel: AudioChannel::Music,
+            ..Default::default()
+        },
+    );
+    commands.insert_resource(Music(sink));
+}
+
+fn music_control_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    music: Res<Music>,
+    mut audio_volume: ResMut<AudioVolume>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        if music.0.is_paused() {
+            music.0.play();
+        } else {
+            music.0.pause();
+        }
+    }
+
+    // the channel volume applies to all music, the sink volume only to this sound
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        let volume = audio_volume.get(AudioChannel::Music) + 0.1;
+        audio_volume.set(AudioChannel::Music, volume);
+    }
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        let volume = (audio_volume.get(AudioChannel::Music) - 0.1).max(0.0);
+        audio_volume.set(AudioChannel::Music, volume);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        music.0.set_speed(music.0.speed() * 1.25);
+    }
+    if keyboard_input.just_pressed(KeyCode::Left) {
+        music.0.set_speed(music.0.speed() / 1.25);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::S) {
+        music.0.stop();
+    }
+}
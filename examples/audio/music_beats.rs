@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+/// This example shows how to follow the beats of a music track, ex: for a rhythm game
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(beat_system.system())
+        .run();
+}
+
+fn setup(
+    asset_server: Res<AssetServer>,
+    audio_output: Res<AudioOutput>,
+    mut music_clock: ResMut<MusicClock>,
+) {
+    let music = asset_server
+        .load("assets/sounds/Windless Slopes.mp3")
+        .unwrap();
+    let sink = audio_output.play(music);
+    music_clock.set_track(sink, Tempo::new(100.0));
+}
+
+fn beat_system(
+    mut reader: Local<EventReader<Beat>>,
+    beats: Res<Events<Beat>>,
+    music_clock: Res<MusicClock>,
+) {
+    for beat in reader.iter(&beats) {
+        println!(
+            "bar {} beat {} (beat at {:.3}s, heard at {:.3}s)",
+            beat.bar,
+            beat.beat_in_bar + 1,
+            beat.time.as_secs_f64(),
+            music_clock.position().as_secs_f64()
+        );
+    }
+}
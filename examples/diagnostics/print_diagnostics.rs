@@ -1,5 +1,7 @@
 use bevy::{
-    diagnostic::{FrameTimeDiagnosticsPlugin, PrintDiagnosticsPlugin},
+    diagnostic::{
+        EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin, PrintDiagnosticsPlugin,
+    },
     prelude::*,
 };
 
@@ -8,10 +10,15 @@ fn main() {
         .add_default_plugins()
         // Adds frame time diagnostics
         .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        // Adds entity count diagnostics
+        .add_plugin(EntityCountDiagnosticsPlugin::default())
         // Adds a system that prints diagnostics to the console
         .add_plugin(PrintDiagnosticsPlugin::default())
         // Any plugin can register diagnostics
         // Uncomment this to add some render resource diagnostics:
         // .add_plugin(bevy::wgpu::diagnostic::WgpuResourceDiagnosticsPlugin::default())
+        // Uncomment this to add draw call and batching diagnostics:
+        // .add_plugin(bevy::render::diagnostic::RenderStatsDiagnosticsPlugin::default())
+        // Run with the "profiler" feature to add a diagnostic for each stage and system
         .run();
 }
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+/// This example turns systems on and off at runtime with the [SystemToggles] resource, which is handy to isolate
+/// subsystems while debugging.
+/// Press "1" to toggle the systems in the `movement` module, "2" to toggle the systems labeled "spin", and "0" to turn
+/// everything back on.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(movement::bounce_system.system())
+        .add_system(movement::drift_system.system())
+        .add_system(spin_system.system())
+        .add_system(toggle_system.system())
+        .run();
+}
+
+struct Bouncing;
+struct Spinning;
+
+fn setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut system_toggles: ResMut<SystemToggles>,
+) {
+    // systems are named after the path of their function
+    system_toggles.add_label(concat!(module_path!(), "::spin_system"), "spin");
+
+    commands
+        .spawn(Camera2dComponents::default())
+        .spawn(SpriteComponents {
+            material: materials.add(Color::rgb(0.8, 0.2, 0.2).into()),
+            sprite: Sprite::new(Vec2::new(50.0, 50.0)),
+            translation: Translation::new(-100.0, 0.0, 0.0),
+            ..Default::default()
+        })
+        .with(Bouncing)
+        .spawn(SpriteComponents {
+            material: materials.add(Color::rgb(0.2, 0.2, 0.8).into()),
+            sprite: Sprite::new(Vec2::new(50.0, 50.0)),
+            translation: Translation::new(100.0, 0.0, 0.0),
+            ..Default::default()
+        })
+        .with(Spinning);
+}
+
+mod movement {
+    use super::Bouncing;
+    use bevy::prelude::*;
+
+    pub fn bounce_system(time: Res<Time>, mut query: Query<With<Bouncing, &mut Translation>>) {
+        for mut translation in &mut query.iter() {
+            *translation.y_mut() = (time.seconds_since_startup as f32 * 3.0).sin() * 100.0;
+        }
+    }
+
+    pub fn drift_system(time: Res<Time>, mut query: Query<With<Bouncing, &mut Translation>>) {
+        for mut translation in &mut query.iter() {
+            *translation.x_mut() = -100.0 + (time.seconds_since_startup as f32).sin() * 50.0;
+        }
+    }
+}
+
+fn spin_system(time: Res<Time>, mut query: Query<With<Spinning, &mut Rotation>>) {
+    for mut rotation in &mut query.iter() {
+        rotation.0 = rotation.0 * Quat::from_rotation_z(time.delta_seconds * 2.0);
+    }
+}
+
+fn toggle_system(keyboard_input: Res<Input<KeyCode>>, mut system_toggles: ResMut<SystemToggles>) {
+    let command = if keyboard_input.just_pressed(KeyCode::Key1) {
+        concat!("toggle ", module_path!(), "::movement")
+    } else if keyboard_input.just_pressed(KeyCode::Key2) {
+        "toggle spin"
+    } else if keyboard_input.just_pressed(KeyCode::Key0) {
+        "enable_all"
+    } else {
+        return;
+    };
+
+    // the same commands could come from a debug console
+    match system_toggles.run_command(command) {
+        Ok(message) => println!("{}", message),
+        Err(err) => println!("{}", err),
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+/// This example prints gamepad connections and input. Gamepads are only read with the `bevy_gilrs` feature:
+/// `cargo run --example gamepad_input --features bevy_gilrs`
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_system(connection_system.system())
+        .add_system(gamepad_system.system())
+        .run();
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    gamepad_event_reader: EventReader<GamepadEvent>,
+}
+
+/// This system prints gamepads as they connect and disconnect
+fn connection_system(mut state: Local<ConnectionState>, gamepad_events: Res<Events<GamepadEvent>>) {
+    for event in state.gamepad_event_reader.iter(&gamepad_events) {
+        match event.event_type {
+            GamepadEventType::Connected => println!("{:?} connected", event.gamepad),
+            GamepadEventType::Disconnected => println!("{:?} disconnected", event.gamepad),
+            _ => {}
+        }
+    }
+}
+
+/// This system prints the South button and left stick of every connected gamepad
+fn gamepad_system(
+    gamepads: Res<Gamepads>,
+    button_input: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+) {
+    for gamepad in gamepads.iter() {
+        if button_input.just_pressed(GamepadButton(*gamepad, GamepadButtonType::South)) {
+            println!("{:?} just pressed South", gamepad);
+        } else if button_input.just_released(GamepadButton(*gamepad, GamepadButtonType::South)) {
+            println!("{:?} just released South", gamepad);
+        }
+
+        let x = axes
+            .get(GamepadAxis(*gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or(0.0);
+        let y = axes
+            .get(GamepadAxis(*gamepad, GamepadAxisType::LeftStickY))
+            .unwrap_or(0.0);
+        if x != 0.0 || y != 0.0 {
+            println!("{:?} left stick: ({}, {})", gamepad, x, y);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+/// This example binds keys, mouse buttons, and gamepad inputs to logical actions, and rebinds jump at runtime
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_input_map::<PlayerAction>()
+        .add_startup_system(setup_bindings.system())
+        .add_system(rebind_system.system())
+        .add_system(player_action_system.system())
+        .run();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PlayerAction {
+    Jump,
+    Fire,
+    Left,
+    Right,
+}
+
+fn setup_bindings(mut input_map: ResMut<InputMap<PlayerAction>>) {
+    input_map
+        .bind(PlayerAction::Jump, KeyCode::Space)
+        .bind(PlayerAction::Jump, GamepadButtonType::South)
+        .bind(PlayerAction::Fire, MouseButton::Left)
+        .bind(PlayerAction::Fire, GamepadButtonType::RightTrigger2)
+        .bind(PlayerAction::Left, KeyCode::A)
+        .bind(
+            PlayerAction::Left,
+            InputBinding::GamepadAxisNegative(GamepadAxisType::LeftStickX),
+        )
+        .bind(PlayerAction::Right, KeyCode::D)
+        .bind(
+            PlayerAction::Right,
+            InputBinding::GamepadAxisPositive(GamepadAxisType::LeftStickX),
+        );
+}
+
+/// Pressing 'R' moves jump from the space bar to 'W'
+fn rebind_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut input_map: ResMut<InputMap<PlayerAction>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        let jump_key = if input_map
+            .get_bindings(PlayerAction::Jump)
+            .contains(&InputBinding::Key(KeyCode::Space))
+        {
+            KeyCode::W
+        } else {
+            KeyCode::Space
+        };
+        input_map
+            .rebind(PlayerAction::Jump, jump_key)
+            .bind(PlayerAction::Jump, GamepadButtonType::South);
+        println!("jump is now bound to {:?}", jump_key);
+    }
+}
+
+fn player_action_system(action_state: Res<ActionState<PlayerAction>>) {
+    if action_state.just_pressed(PlayerAction::Jump) {
+        println!("jump");
+    }
+
+    if action_state.pressed(PlayerAction::Fire) {
+        println!(
+            "firing with strength {}",
+            action_state.value(PlayerAction::Fire)
+        );
+    }
+
+    let movement = action_state.axis(PlayerAction::Left, PlayerAction::Right);
+    if movement != 0.0 {
+        println!("moving {}", movement);
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::{input::touch::TouchGesture, prelude::*};
+
+/// This example illustrates how to move a camera with touch gestures: rotating two fingers orbits around the focus,
+/// pinching zooms, and panning moves the focus
+fn main() {
+    App::build()
+        .add_resource(Msaa { samples: 4 })
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(touch_camera_controller_system.system())
+        .run();
+}
+
+/// Moves a camera around `focus` using touch gestures. The camera's `Transform` is set directly, so it should be created
+/// with `Transform::new_sync_disabled`.
+struct TouchCameraController {
+    focus: Vec3,
+    /// World units moved per pixel of panning, at a distance of one unit from the focus
+    pan_sensitivity: f32,
+    /// Multiplier applied to rotation gestures
+    orbit_sensitivity: f32,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl Default for TouchCameraController {
+    fn default() -> Self {
+        TouchCameraController {
+            focus: Vec3::zero(),
+            pan_sensitivity: 0.002,
+            orbit_sensitivity: 1.0,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TouchCameraControllerState {
+    touch_gesture_event_reader: EventReader<TouchGesture>,
+}
+
+fn touch_camera_controller_system(
+    mut state: Local<TouchCameraControllerState>,
+    touch_gesture_events: Res<Events<TouchGesture>>,
+    mut query: Query<(&mut TouchCameraController, &mut Transform)>,
+) {
+    let gestures = state
+        .touch_gesture_event_reader
+        .iter(&touch_gesture_events)
+        .cloned()
+        .collect::<Vec<_>>();
+    if gestures.is_empty() {
+        return;
+    }
+
+    for (mut controller, mut transform) in &mut query.iter() {
+        let mut eye = Vec3::from(transform.value.w_axis().truncate());
+        for gesture in gestures.iter() {
+            let offset = eye - controller.focus;
+            match *gesture {
+                TouchGesture::Pan { delta } => {
+                    let right = Vec3::from(transform.value.x_axis().truncate()).normalize();
+                    let up = Vec3::from(transform.value.y_axis().truncate()).normalize();
+                    let scale = controller.pan_sensitivity * offset.length();
+                    let translation = -(right * delta.x() + up * delta.y()) * scale;
+                    controller.focus += translation;
+                    eye += translation;
+                }
+                TouchGesture::Pinch { scale } => {
+                    let distance = (offset.length() / scale)
+                        .max(controller.min_distance)
+                        .min(controller.max_distance);
+                    eye = controller.focus + offset.normalize() * distance;
+                }
+                TouchGesture::Rotate { angle } => {
+                    let rotation = Quat::from_rotation_y(-angle * controller.orbit_sensitivity);
+                    eye = controller.focus + rotation * offset;
+                }
+            }
+        }
+
+        transform.value = Mat4::face_toward(eye, controller.focus, Vec3::unit_y());
+    }
+}
+
+/// set up a simple 3D scene with a touch controlled camera
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        // plane
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 10.0 })),
+            material: materials.add(Color::rgb(0.1, 0.2, 0.1).into()),
+            ..Default::default()
+        })
+        // cube
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: materials.add(Color::rgb(0.5, 0.4, 0.3).into()),
+            translation: Translation::new(0.0, 1.0, 0.0),
+            ..Default::default()
+        })
+        // light
+        .spawn(LightComponents {
+            translation: Translation::new(4.0, 8.0, 4.0),
+            ..Default::default()
+        })
+        // camera
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(-3.0, 5.0, 8.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        })
+        .with(TouchCameraController::default());
+}
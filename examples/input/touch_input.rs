@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_system(touch_system.system())
+        .run();
+}
+
+/// This system prints the state of every finger on the touch screen
+fn touch_system(touches: Res<Touches>) {
+    for touch in touches.iter_just_pressed() {
+        println!("touch {} just started at {:?}", touch.id, touch.position);
+    }
+
+    for touch in touches.iter() {
+        if touch.delta() != Vec2::zero() {
+            println!(
+                "touch {} moved by {:?}, {:?} since it started",
+                touch.id,
+                touch.delta(),
+                touch.distance()
+            );
+        }
+    }
+
+    for touch in touches.iter_just_released() {
+        println!("touch {} just ended at {:?}", touch.id, touch.position);
+    }
+
+    for touch in touches.iter_just_cancelled() {
+        println!("touch {} was cancelled", touch.id);
+    }
+}
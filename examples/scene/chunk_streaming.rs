@@ -0,0 +1,124 @@
+use bevy::{
+    prelude::*,
+    scene::{ChunkCoord, ChunkEvent},
+};
+
+/// This example streams a 2D map in chunks around the camera. Move the camera with the arrow keys: chunks ahead of it are
+/// loaded from scene files, and chunks it leaves behind are unloaded.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .register_component::<ChunkTile>()
+        .init_resource::<TileMaterials>()
+        .add_startup_system(setup.system())
+        .add_system(camera_movement_system.system())
+        .add_system(chunk_tile_system.system())
+        .add_system(chunk_event_system.system())
+        .run();
+}
+
+const CHUNK_SIZE: f32 = 256.0;
+
+/// A square tile in a chunk scene, positioned relative to the chunk's corner
+#[derive(Properties, Default)]
+struct ChunkTile {
+    x: f32,
+    y: f32,
+    size: f32,
+    kind: u32,
+}
+
+struct TileMaterials {
+    grass: Handle<ColorMaterial>,
+    rock: Handle<ColorMaterial>,
+}
+
+impl FromResources for TileMaterials {
+    fn from_resources(resources: &Resources) -> Self {
+        let mut materials = resources.get_mut::<Assets<ColorMaterial>>().unwrap();
+        TileMaterials {
+            grass: materials.add(Color::rgb(0.2, 0.6, 0.2).into()),
+            rock: materials.add(Color::rgb(0.5, 0.5, 0.5).into()),
+        }
+    }
+}
+
+fn setup(mut commands: Commands, mut streamer: ResMut<ChunkStreamer>) {
+    streamer.grid = ChunkGrid::new(Vec3::new(CHUNK_SIZE, CHUNK_SIZE, 0.0));
+    // every other chunk has rocks in it
+    streamer.add_provider(|coord: ChunkCoord, asset_server: &AssetServer| {
+        let path = if (coord.x + coord.y) % 2 == 0 {
+            "assets/scenes/chunks/grass.scn"
+        } else {
+            "assets/scenes/chunks/rocks.scn"
+        };
+        asset_server.load(path).ok()
+    });
+
+    commands
+        .spawn(Camera2dComponents::default())
+        .with(ChunkAnchor {
+            load_radius: 2,
+            unload_radius: 3,
+        });
+}
+
+fn camera_movement_system(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(&ChunkAnchor, &mut Translation)>,
+) {
+    let mut direction = Vec3::zero();
+    if keyboard_input.pressed(KeyCode::Left) {
+        direction -= Vec3::unit_x();
+    }
+    if keyboard_input.pressed(KeyCode::Right) {
+        direction += Vec3::unit_x();
+    }
+    if keyboard_input.pressed(KeyCode::Down) {
+        direction -= Vec3::unit_y();
+    }
+    if keyboard_input.pressed(KeyCode::Up) {
+        direction += Vec3::unit_y();
+    }
+
+    for (_anchor, mut translation) in &mut query.iter() {
+        translation.0 += direction * 500.0 * time.delta_seconds;
+    }
+}
+
+/// Adds sprites to the tiles of chunks that finished spawning
+fn chunk_tile_system(
+    mut commands: Commands,
+    materials: Res<TileMaterials>,
+    streamer: Res<ChunkStreamer>,
+    mut query: Query<(Entity, &ChunkTile, Added<Chunk>)>,
+) {
+    for (entity, tile, chunk) in &mut query.iter() {
+        let origin = streamer.grid.chunk_origin(chunk.coord);
+        commands.insert(
+            entity,
+            SpriteComponents {
+                material: if tile.kind == 0 {
+                    materials.grass
+                } else {
+                    materials.rock
+                },
+                sprite: Sprite::new(Vec2::new(tile.size, tile.size)),
+                translation: Translation(origin + Vec3::new(tile.x, tile.y, tile.kind as f32)),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+#[derive(Default)]
+struct ChunkEventState {
+    chunk_event_reader: EventReader<ChunkEvent>,
+}
+
+fn chunk_event_system(mut state: Local<ChunkEventState>, chunk_events: Res<Events<ChunkEvent>>) {
+    for event in state.chunk_event_reader.iter(&chunk_events) {
+        println!("{:?}", event);
+    }
+}
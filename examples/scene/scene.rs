@@ -126,5 +126,11 @@ fn save_scene_system(_world: &mut World, resources: &mut Resources) {
             .unwrap()
     );
 
+    // Large scenes load much faster from the compact binary format, which is used by files with the `.scnb` extension.
+    let binary = scene
+        .serialize_binary(&type_registry.property.read().unwrap(), true)
+        .unwrap();
+    println!("binary scene: {} bytes", binary.len());
+
     // TODO: save scene
 }
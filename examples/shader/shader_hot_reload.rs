@@ -0,0 +1,117 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::shape,
+        pipeline::{DynamicBinding, PipelineDescriptor, PipelineSpecialization, RenderPipeline},
+        render_graph::{base, AssetRenderResourcesNode, RenderGraph},
+        renderer::RenderResources,
+        shader::{asset_shader_defs_system, ShaderDefs, ShaderStages},
+    },
+};
+
+/// This example loads its shaders from files and recompiles them whenever they change. Try editing
+/// assets/shaders/hot_reload.frag while the example is running. It also shows how a material can define shader defs
+/// by name.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_asset::<MyMaterial>()
+        .add_startup_system(setup.system())
+        .add_system_to_stage(
+            stage::POST_UPDATE,
+            asset_shader_defs_system::<MyMaterial>.system(),
+        )
+        .run();
+}
+
+#[derive(RenderResources, ShaderDefs, Default)]
+struct MyMaterial {
+    pub color: Color,
+    /// each name in this list is defined when the material's shaders are compiled
+    #[render_resources(ignore)]
+    #[shader_defs]
+    pub shader_defs: Vec<String>,
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<MyMaterial>>,
+    mut render_graph: ResMut<RenderGraph>,
+) {
+    // shaders loaded by the asset server are reloaded when their files change, which recompiles the pipelines that
+    // use them
+    asset_server.watch_for_changes().unwrap();
+    let pipeline_handle = pipelines.add(PipelineDescriptor::default_config(ShaderStages {
+        vertex: asset_server.load("assets/shaders/hot_reload.vert").unwrap(),
+        fragment: Some(asset_server.load("assets/shaders/hot_reload.frag").unwrap()),
+    }));
+
+    // Add an AssetRenderResourcesNode to our Render Graph. This will bind MyMaterial resources to our shader
+    render_graph.add_system_node(
+        "my_material",
+        AssetRenderResourcesNode::<MyMaterial>::new(true),
+    );
+    render_graph
+        .add_node_edge("my_material", base::node::MAIN_PASS)
+        .unwrap();
+
+    let colored_material = materials.add(MyMaterial {
+        color: Color::rgb(0.8, 0.4, 0.0),
+        shader_defs: Vec::new(),
+    });
+    let normals_material = materials.add(MyMaterial {
+        color: Color::rgb(0.8, 0.4, 0.0),
+        shader_defs: vec!["SHOW_NORMALS".to_string()],
+    });
+
+    let cube_handle = meshes.add(Mesh::from(shape::Cube { size: 1.0 }));
+    let render_pipelines = || {
+        RenderPipelines::from_pipelines(vec![RenderPipeline::specialized(
+            pipeline_handle,
+            // NOTE: in the future you wont need to manually declare dynamic bindings
+            PipelineSpecialization {
+                dynamic_bindings: vec![
+                    // Transform
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 0,
+                    },
+                    // MyMaterial_color
+                    DynamicBinding {
+                        bind_group: 1,
+                        binding: 1,
+                    },
+                ],
+                ..Default::default()
+            },
+        )])
+    };
+
+    commands
+        .spawn(MeshComponents {
+            mesh: cube_handle,
+            render_pipelines: render_pipelines(),
+            translation: Translation::new(-1.5, 0.0, 0.0),
+            ..Default::default()
+        })
+        .with(colored_material)
+        .spawn(MeshComponents {
+            mesh: cube_handle,
+            render_pipelines: render_pipelines(),
+            translation: Translation::new(1.5, 0.0, 0.0),
+            ..Default::default()
+        })
+        .with(normals_material)
+        // camera
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(3.0, 5.0, -8.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        });
+}
@@ -87,6 +87,7 @@ fn setup(
                         font_size: 40.0,
                         color: Color::rgb(0.8, 0.8, 0.8),
                     },
+                    ..Default::default()
                 },
                 ..Default::default()
             });
@@ -86,6 +86,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut state: ResM
                     font_size: 60.0,
                     color: Color::WHITE,
                 },
+                ..Default::default()
             },
             ..Default::default()
         });
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+/// This example illustrates a health bar and a cooldown indicator. Press space to take damage and use the ability.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(damage_system.system())
+        .add_system(cooldown_system.system())
+        .run();
+}
+
+fn damage_system(keyboard_input: Res<Input<KeyCode>>, mut query: Query<&mut ProgressBar>) {
+    for mut health_bar in &mut query.iter() {
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            health_bar.value -= 0.1;
+        }
+        if health_bar.value <= 0.0 {
+            health_bar.value = 1.0;
+        }
+    }
+}
+
+fn cooldown_system(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<&mut RadialFill>,
+) {
+    for mut cooldown in &mut query.iter() {
+        if keyboard_input.just_pressed(KeyCode::Space) && cooldown.value >= 1.0 {
+            cooldown.value = 0.0;
+        } else {
+            // refills in 3 seconds
+            cooldown.value = (cooldown.value + time.delta_seconds / 3.0).min(1.0);
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut fill_materials: ResMut<Assets<FillMaterial>>,
+) {
+    commands
+        // ui camera
+        .spawn(UiCameraComponents::default())
+        // root node
+        .spawn(NodeComponents {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::NONE.into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                // health bar
+                .spawn(ProgressBarComponents {
+                    style: Style {
+                        size: Size::new(Val::Px(400.0), Val::Px(30.0)),
+                        margin: Rect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                    progress_bar: ProgressBar::new(1.0).with_transition(0.5),
+                    material: fill_materials.add(FillMaterial::color(
+                        Color::rgb(0.8, 0.1, 0.1),
+                        Color::rgb(0.15, 0.15, 0.15),
+                    )),
+                    ..Default::default()
+                })
+                // cooldown indicator
+                .spawn(RadialFillComponents {
+                    style: Style {
+                        size: Size::new(Val::Px(100.0), Val::Px(100.0)),
+                        margin: Rect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                    radial_fill: RadialFill::new(1.0)
+                        .with_inner_radius(0.6)
+                        .with_transition(0.0),
+                    material: fill_materials.add(FillMaterial::color(
+                        Color::rgb(0.2, 0.5, 0.9),
+                        Color::rgb(0.15, 0.15, 0.15),
+                    )),
+                    ..Default::default()
+                });
+        });
+}
@@ -41,6 +41,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font_size: 60.0,
                     color: Color::WHITE,
                 },
+                ..Default::default()
             },
             ..Default::default()
         });
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+/// This example illustrates how to show notifications. Press 1 to 4 to push a toast of each severity, and L to load a
+/// missing texture, which is shown as an error.
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(toast_system.system())
+        .run();
+}
+
+fn toast_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Key1) {
+        toasts.push(Toast::info("Press 1 to 4 to show toasts"));
+    }
+    if keyboard_input.just_pressed(KeyCode::Key2) {
+        toasts.push(Toast::success("Game saved"));
+    }
+    if keyboard_input.just_pressed(KeyCode::Key3) {
+        toasts.push(Toast::warning("Connection is unstable").with_duration(6.0));
+    }
+    if keyboard_input.just_pressed(KeyCode::Key4) {
+        toasts.push(Toast::error("Disconnected from the server"));
+    }
+    if keyboard_input.just_pressed(KeyCode::L) {
+        // the texture fails to load in the background, which shows an error toast
+        asset_server
+            .load::<Texture, _>("assets/textures/missing.png")
+            .ok();
+    }
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut toasts: ResMut<Toasts>) {
+    toasts.font = Some(asset_server.load("assets/fonts/FiraSans-Bold.ttf").unwrap());
+    toasts.show_asset_load_failures = true;
+    toasts.push(Toast::info("Press 1 to 4 to show toasts"));
+
+    commands.spawn(UiCameraComponents::default());
+}
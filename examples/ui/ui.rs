@@ -66,6 +66,7 @@ fn setup(
                                         font_size: 30.0,
                                         color: Color::WHITE,
                                     },
+                                    ..Default::default()
                                 },
                                 ..Default::default()
                             });
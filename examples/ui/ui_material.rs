@@ -0,0 +1,74 @@
+use bevy::{
+    prelude::*,
+    render::renderer::RenderResources,
+    ui::{UiMaterial, UiMaterialPlugin},
+};
+
+/// This example illustrates how to draw a ui node with a custom shader, without rendering it to a texture first
+fn main() {
+    App::build()
+        .add_default_plugins()
+        .add_plugin(UiMaterialPlugin::<LoadingBar>::default())
+        .add_startup_system(setup.system())
+        .add_system(progress_system.system())
+        .run();
+}
+
+#[derive(RenderResources, Default)]
+struct LoadingBar {
+    pub color: Color,
+    pub progress: f32,
+}
+
+impl UiMaterial for LoadingBar {
+    fn fragment_shader() -> &'static str {
+        r#"
+        layout(set = 2, binding = 0) uniform LoadingBar_color {
+            vec4 Color;
+        };
+        layout(set = 2, binding = 1) uniform LoadingBar_progress {
+            float Progress;
+        };
+
+        vec4 ui_material(vec2 uv) {
+            // fade the unfilled part of the bar and round its ends
+            vec2 position = uv * NodeSize;
+            float radius = NodeSize.y / 2.0;
+            float x = clamp(position.x, radius, NodeSize.x - radius);
+            if (distance(position, vec2(x, radius)) > radius) {
+                discard;
+            }
+            return uv.x <= Progress ? Color : vec4(Color.rgb, 0.2);
+        }
+        "#
+    }
+}
+
+fn progress_system(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<LoadingBar>>,
+    mut query: Query<&Handle<LoadingBar>>,
+) {
+    for handle in &mut query.iter() {
+        let loading_bar = materials.get_mut(handle).unwrap();
+        loading_bar.progress = (time.seconds_since_startup as f32 * 0.25).fract();
+    }
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<LoadingBar>>) {
+    commands
+        // ui camera
+        .spawn(UiCameraComponents::default())
+        .spawn(NodeComponents {
+            style: Style {
+                size: Size::new(Val::Px(400.0), Val::Px(40.0)),
+                margin: Rect::all(Val::Auto),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with(materials.add(LoadingBar {
+            color: Color::rgb(0.2, 0.7, 0.3),
+            progress: 0.0,
+        }));
+}
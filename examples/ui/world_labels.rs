@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+/// This example shows labels that follow objects in the 3d scene. When an object leaves the screen, its label stays on
+/// the edge of the screen and shows an arrow pointing towards it.
+fn main() {
+    App::build()
+        .add_resource(Msaa { samples: 4 })
+        .add_default_plugins()
+        .add_startup_system(setup.system())
+        .add_system(orbit_system.system())
+        .run();
+}
+
+struct Orbit {
+    radius: f32,
+    speed: f32,
+}
+
+fn orbit_system(time: Res<Time>, mut query: Query<(&Orbit, &mut Translation)>) {
+    let seconds = time.seconds_since_startup as f32;
+    for (orbit, mut translation) in &mut query.iter() {
+        let angle = seconds * orbit.speed;
+        translation.0 =
+            Vec3::new(angle.cos(), 0.5, angle.sin()) * Vec3::new(orbit.radius, 1.0, orbit.radius);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let font_handle = asset_server.load("assets/fonts/FiraSans-Bold.ttf").unwrap();
+    let cube_mesh = meshes.add(Mesh::from(shape::Cube { size: 0.5 }));
+
+    commands
+        .spawn(PbrComponents {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 20.0 })),
+            material: materials.add(Color::rgb(0.1, 0.2, 0.1).into()),
+            ..Default::default()
+        })
+        .spawn(LightComponents {
+            translation: Translation::new(4.0, 8.0, 4.0),
+            ..Default::default()
+        })
+        .spawn(Camera3dComponents {
+            transform: Transform::new_sync_disabled(Mat4::face_toward(
+                Vec3::new(0.0, 6.0, 8.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            )),
+            ..Default::default()
+        })
+        .spawn(UiCameraComponents::default());
+
+    for (name, color, radius, speed) in [
+        ("Near", Color::rgb(0.8, 0.3, 0.3), 3.0, 0.7),
+        ("Far", Color::rgb(0.3, 0.3, 0.8), 12.0, 0.3),
+    ]
+    .iter()
+    {
+        commands
+            .spawn(PbrComponents {
+                mesh: cube_mesh,
+                material: materials.add((*color).into()),
+                ..Default::default()
+            })
+            .with(Orbit {
+                radius: *radius,
+                speed: *speed,
+            });
+        let cube = commands.current_entity().unwrap();
+
+        commands
+            .spawn(NodeComponents {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: color_materials.add(Color::NONE.into()),
+                ..Default::default()
+            })
+            .with(WorldAnchor {
+                offscreen: OffscreenMode::Indicator,
+                margin: 10.0,
+                ..WorldAnchor::follow(cube, Vec3::new(0.0, 0.6, 0.0))
+            })
+            .with_children(|parent| {
+                parent
+                    .spawn(TextComponents {
+                        text: Text {
+                            value: name.to_string(),
+                            font: font_handle,
+                            style: TextStyle {
+                                font_size: 30.0,
+                                color: Color::WHITE,
+                            },
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    // a bar pointing towards the cube while it is off screen
+                    .spawn(NodeComponents {
+                        style: Style {
+                            size: Size::new(Val::Px(30.0), Val::Px(6.0)),
+                            ..Default::default()
+                        },
+                        material: color_materials.add((*color).into()),
+                        ..Default::default()
+                    })
+                    .with(WorldAnchorIndicator);
+            });
+    }
+}
@@ -1,18 +1,14 @@
 use bevy::{
     prelude::*,
     render::{
-        camera::{ActiveCameras, Camera},
-        pass::*,
-        render_graph::{
-            base::MainPass, CameraNode, PassNode, RenderGraph, WindowSwapChainNode,
-            WindowTextureNode,
-        },
-        texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
+        camera::{ActiveCameras, Camera, WindowGraphBuilder},
+        render_graph::RenderGraph,
     },
+    ui::UiRenderGraphBuilder,
     window::{CreateWindow, WindowDescriptor, WindowId},
 };
 
-/// This example creates a second window and draws a mesh from two different cameras.
+/// This example creates a second window, draws a mesh from two different cameras, and adds a ui to the second window.
 fn main() {
     App::build()
         .add_default_plugins()
@@ -26,8 +22,8 @@ fn setup(
     mut active_cameras: ResMut<ActiveCameras>,
     mut render_graph: ResMut<RenderGraph>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
-    msaa: Res<Msaa>,
 ) {
     let window_id = WindowId::new();
 
@@ -43,112 +39,12 @@ fn setup(
         },
     });
 
-    // here we setup our render graph to draw our second camera to the new window's swap chain
-
-    // add a swapchain node for our new window
-    render_graph.add_node(
-        "second_window_swap_chain",
-        WindowSwapChainNode::new(window_id),
-    );
-
-    // add a new depth texture node for our new window
-    render_graph.add_node(
-        "second_window_depth_texture",
-        WindowTextureNode::new(
-            window_id,
-            TextureDescriptor {
-                format: TextureFormat::Depth32Float,
-                usage: TextureUsage::OUTPUT_ATTACHMENT,
-                sample_count: msaa.samples,
-                ..Default::default()
-            },
-        ),
-    );
-
-    // add a new camera node for our new window
-    render_graph.add_system_node("secondary_camera", CameraNode::new("Secondary"));
-
-    // add a new render pass for our new window / camera
-    let mut second_window_pass = PassNode::<&MainPass>::new(PassDescriptor {
-        color_attachments: vec![msaa.color_attachment_descriptor(
-            TextureAttachment::Input("color_attachment".to_string()),
-            TextureAttachment::Input("color_resolve_target".to_string()),
-            Operations {
-                load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.3)),
-                store: true,
-            },
-        )],
-        depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
-            attachment: TextureAttachment::Input("depth".to_string()),
-            depth_ops: Some(Operations {
-                load: LoadOp::Clear(1.0),
-                store: true,
-            }),
-            stencil_ops: None,
-        }),
-        sample_count: msaa.samples,
-    });
-
-    second_window_pass.add_camera("Secondary");
-    active_cameras.add("Secondary");
-
-    render_graph.add_node("second_window_pass", second_window_pass);
-
+    // here we setup our render graph to draw our second camera and its ui to the new window's swap chain
     render_graph
-        .add_slot_edge(
-            "second_window_swap_chain",
-            WindowSwapChainNode::OUT_TEXTURE,
-            "second_window_pass",
-            if msaa.samples > 1 {
-                "color_resolve_target"
-            } else {
-                "color_attachment"
-            },
-        )
-        .unwrap();
-
-    render_graph
-        .add_slot_edge(
-            "second_window_depth_texture",
-            WindowTextureNode::OUT_TEXTURE,
-            "second_window_pass",
-            "depth",
-        )
-        .unwrap();
-
-    render_graph
-        .add_node_edge("secondary_camera", "second_window_pass")
-        .unwrap();
-
-    if msaa.samples > 1 {
-        render_graph.add_node(
-            "second_multi_sampled_color_attachment",
-            WindowTextureNode::new(
-                window_id,
-                TextureDescriptor {
-                    size: Extent3d {
-                        depth: 1,
-                        width: 1,
-                        height: 1,
-                    },
-                    mip_level_count: 1,
-                    sample_count: msaa.samples,
-                    dimension: TextureDimension::D2,
-                    format: TextureFormat::Bgra8UnormSrgb,
-                    usage: TextureUsage::OUTPUT_ATTACHMENT,
-                },
-            ),
-        );
-
-        render_graph
-            .add_slot_edge(
-                "second_multi_sampled_color_attachment",
-                WindowSwapChainNode::OUT_TEXTURE,
-                "second_window_pass",
-                "color_attachment",
-            )
-            .unwrap();
-    }
+        .add_window_camera_graph(window_id, "Secondary")
+        .add_ui_window_graph(window_id, "SecondaryUi");
+    active_cameras.add("Secondary");
+    active_cameras.add("SecondaryUi");
 
     // SETUP SCENE
 
@@ -198,5 +94,30 @@ fn setup(
                 Vec3::new(0.0, 1.0, 0.0),
             )),
             ..Default::default()
-        });
+        })
+        // second window ui camera
+        .spawn(UiCameraComponents {
+            camera: Camera {
+                name: Some("SecondaryUi".to_string()),
+                window: window_id,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        // this root node (and its children) is laid out and drawn in the second window
+        .spawn(NodeComponents {
+            style: Style {
+                size: Size::new(Val::Px(200.0), Val::Px(100.0)),
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            material: color_materials.add(Color::rgb(0.2, 0.2, 0.8).into()),
+            ..Default::default()
+        })
+        .with(UiTargetWindow(window_id));
 }
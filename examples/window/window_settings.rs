@@ -14,5 +14,56 @@ fn main() {
             ..Default::default()
         })
         .add_default_plugins()
+        .add_system(change_window_settings.system())
+        .add_system(toggle_cursor.system())
+        .add_system(cursor_enter_leave.system())
         .run();
 }
+
+/// This system switches between windowed and fullscreen modes when 'F' is pressed, toggles vsync when 'V' is
+/// pressed, and shows the vsync setting in the title
+fn change_window_settings(input: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
+    let window = windows.get_primary_mut().unwrap();
+    if input.just_pressed(KeyCode::F) {
+        let mode = match window.mode {
+            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+            _ => WindowMode::Windowed,
+        };
+        window.set_mode(mode);
+    }
+
+    if input.just_pressed(KeyCode::V) {
+        let vsync = !window.vsync;
+        window.set_vsync(vsync);
+        window.set_title(format!("I am a window! (vsync: {})", vsync));
+    }
+}
+
+/// This system locks and hides the cursor when space is pressed, and unlocks it when space is pressed again
+fn toggle_cursor(input: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
+    if input.just_pressed(KeyCode::Space) {
+        let window = windows.get_primary_mut().unwrap();
+        let locked = !window.cursor_locked();
+        window.set_cursor_lock_mode(locked);
+        window.set_cursor_visibility(!locked);
+        if locked {
+            let center = Vec2::new(window.width as f32, window.height as f32) / 2.0;
+            window.set_cursor_position(center);
+        }
+    }
+}
+
+/// This system prints when the cursor enters or leaves the window
+fn cursor_enter_leave(
+    mut entered_reader: Local<EventReader<CursorEntered>>,
+    mut left_reader: Local<EventReader<CursorLeft>>,
+    entered_events: Res<Events<CursorEntered>>,
+    left_events: Res<Events<CursorLeft>>,
+) {
+    for _event in entered_reader.iter(&entered_events) {
+        println!("cursor entered the window");
+    }
+    for _event in left_reader.iter(&left_events) {
+        println!("cursor left the window");
+    }
+}
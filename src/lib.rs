@@ -62,6 +62,9 @@ pub use bevy_window as window;
 #[cfg(feature = "bevy_audio")]
 pub use bevy_audio as audio;
 
+#[cfg(feature = "bevy_gilrs")]
+pub use bevy_gilrs as gilrs;
+
 #[cfg(feature = "bevy_gltf")]
 pub use bevy_gltf as gltf;
 